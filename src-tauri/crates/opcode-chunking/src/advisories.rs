@@ -0,0 +1,494 @@
+use super::dependency_audit::{
+    ecosystem_of_file, is_ancestor_dir, latest_metadata_by_file, normalize_name,
+};
+use super::resolver::parent_dir;
+use super::storage::{delete_security_findings, insert_security_finding};
+use super::types::{
+    CallgraphMetadata, ChunkType, DependencyKind, ManifestMetadata, SecurityFinding,
+};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Advisory en formato OSV (https://ossf.github.io/osv-schema/).
+/// RustSec, GitHub (npm) y PyPA publican sus bases en este formato.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Advisory {
+    pub id: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub details: String,
+    #[serde(default)]
+    pub affected: Vec<AffectedPackage>,
+    #[serde(default)]
+    pub database_specific: Option<Value>,
+}
+
+/// Paquete afectado por un advisory
+#[derive(Debug, Clone, Deserialize)]
+pub struct AffectedPackage {
+    pub package: OsvPackage,
+    #[serde(default)]
+    pub ranges: Vec<OsvRange>,
+    #[serde(default)]
+    pub versions: Vec<String>,
+    #[serde(default)]
+    pub ecosystem_specific: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OsvPackage {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+/// Rango de versiones afectadas, expresado como eventos introduced/fixed/last_affected
+#[derive(Debug, Clone, Deserialize)]
+pub struct OsvRange {
+    #[serde(rename = "type")]
+    pub range_type: String,
+    #[serde(default)]
+    pub events: Vec<HashMap<String, String>>,
+}
+
+/// Carga una base de advisories OSV local: un archivo `.json` (objeto o array)
+/// o un directorio con un advisory por archivo (ej: export de osv.dev descomprimido)
+pub fn load_advisory_db(path: &Path) -> Result<Vec<Advisory>> {
+    let files: Vec<_> = if path.is_dir() {
+        WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("json"))
+            .map(|e| e.path().to_path_buf())
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut advisories = Vec::new();
+    for file in files {
+        let content = std::fs::read_to_string(&file)?;
+        if let Ok(list) = serde_json::from_str::<Vec<Advisory>>(&content) {
+            advisories.extend(list);
+        } else {
+            match serde_json::from_str::<Advisory>(&content) {
+                Ok(advisory) => advisories.push(advisory),
+                Err(e) => log::debug!("Skipped advisory {}: {}", file.display(), e),
+            }
+        }
+    }
+
+    Ok(advisories)
+}
+
+/// Cruza las versiones declaradas en los manifiestos/lockfiles (chunks de metadata) con
+/// la base de advisories y registra un `SecurityFinding` por cada vulnerabilidad.
+/// Reemplaza los hallazgos del escaneo anterior del proyecto.
+pub fn scan_vulnerabilities(
+    conn: &Connection,
+    project_path: &str,
+    advisories: &[Advisory],
+) -> Result<Vec<SecurityFinding>> {
+    let manifests = latest_manifest_chunks(conn, project_path)?;
+
+    // Paquetes importados por cada archivo, para enlazar los archivos afectados
+    let file_packages: Vec<(String, &'static str, HashSet<String>)> =
+        latest_metadata_by_file(conn, project_path, &ChunkType::Callgraph)?
+            .into_iter()
+            .filter_map(|(path, metadata)| {
+                let ecosystem = ecosystem_of_file(&path)?;
+                let metadata: CallgraphMetadata = serde_json::from_str(&metadata).ok()?;
+                let packages = metadata
+                    .dependencies
+                    .iter()
+                    .filter(|d| d.kind != DependencyKind::Internal)
+                    .filter_map(|d| d.package.as_deref())
+                    .map(|p| normalize_name(ecosystem, p))
+                    .collect();
+                Some((path, ecosystem, packages))
+            })
+            .collect();
+
+    // Si hay lockfile, sus versiones exactas sustituyen a los requisitos del manifiesto
+    let lock_dirs: Vec<(&'static str, String)> = manifests
+        .iter()
+        .filter(|(_, path, _)| is_lockfile(path))
+        .filter_map(|(_, path, _)| Some((ecosystem_of_manifest(path)?, parent_dir(path))))
+        .collect();
+
+    delete_security_findings(conn, project_path)?;
+
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for (chunk_id, manifest_path, metadata) in &manifests {
+        let Some(ecosystem) = ecosystem_of_manifest(manifest_path) else {
+            continue;
+        };
+        let manifest_dir = parent_dir(manifest_path);
+        if !is_lockfile(manifest_path)
+            && lock_dirs
+                .iter()
+                .any(|(eco, dir)| *eco == ecosystem && is_ancestor_dir(dir, &manifest_dir))
+        {
+            continue;
+        }
+
+        for dep in &metadata.dependencies {
+            let Some(version) = dep.version.as_deref().and_then(concrete_version) else {
+                continue;
+            };
+
+            for advisory in advisories {
+                let Some(fixed_version) = affects(advisory, ecosystem, &dep.name, &version) else {
+                    continue;
+                };
+                if !seen.insert((
+                    manifest_path.clone(),
+                    dep.name.clone(),
+                    version.clone(),
+                    advisory.id.clone(),
+                )) {
+                    continue;
+                }
+
+                let package = normalize_name(ecosystem, &dep.name);
+                let affected_files = file_packages
+                    .iter()
+                    .filter(|(file, file_eco, packages)| {
+                        *file_eco == ecosystem
+                            && is_ancestor_dir(&manifest_dir, &parent_dir(file))
+                            && packages.contains(&package)
+                    })
+                    .map(|(file, _, _)| file.clone())
+                    .collect();
+
+                let mut finding = SecurityFinding {
+                    id: None,
+                    project_path: project_path.to_string(),
+                    metadata_chunk_id: Some(*chunk_id),
+                    manifest_path: manifest_path.clone(),
+                    ecosystem: osv_ecosystem(ecosystem).to_string(),
+                    package_name: dep.name.clone(),
+                    installed_version: version.clone(),
+                    advisory_id: advisory.id.clone(),
+                    aliases: advisory.aliases.clone(),
+                    severity: severity_of(advisory),
+                    summary: if advisory.summary.is_empty() {
+                        advisory.details.lines().next().unwrap_or("").to_string()
+                    } else {
+                        advisory.summary.clone()
+                    },
+                    fixed_version,
+                    affected_files,
+                    created_at: Utc::now(),
+                };
+                finding.id = Some(insert_security_finding(conn, &finding)?);
+                findings.push(finding);
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Chunks de metadata más recientes por archivo, con su id y dependencias parseadas
+fn latest_manifest_chunks(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Vec<(i64, String, ManifestMetadata)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, file_path, metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2
+           AND file_path IS NOT NULL AND metadata IS NOT NULL AND deleted_at IS NULL
+         ORDER BY updated_at DESC, id DESC",
+    )?;
+
+    let rows = stmt
+        .query_map(
+            params![project_path, ChunkType::ProjectMetadata.as_str()],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut seen = HashSet::new();
+    Ok(rows
+        .into_iter()
+        .filter(|(_, path, _)| seen.insert(path.clone()))
+        .filter_map(|(id, path, metadata)| {
+            serde_json::from_str::<ManifestMetadata>(&metadata)
+                .ok()
+                .map(|m| (id, path, m))
+        })
+        .collect())
+}
+
+/// Retorna Some(fixed_version) si el advisory afecta a `name@version`
+fn affects(
+    advisory: &Advisory,
+    ecosystem: &str,
+    name: &str,
+    version: &str,
+) -> Option<Option<String>> {
+    let target = normalize_name(ecosystem, name);
+
+    for affected in &advisory.affected {
+        if affected.package.ecosystem != osv_ecosystem(ecosystem)
+            || normalize_name(ecosystem, &affected.package.name) != target
+        {
+            continue;
+        }
+
+        if affected.versions.iter().any(|v| v == version) {
+            return Some(first_fixed(affected));
+        }
+
+        for range in &affected.ranges {
+            if range.range_type != "SEMVER" && range.range_type != "ECOSYSTEM" {
+                continue;
+            }
+            if let Some(fixed) = version_in_range(version, &range.events) {
+                return Some(fixed);
+            }
+        }
+    }
+
+    None
+}
+
+/// Evalúa los eventos de un rango OSV. Retorna Some(fixed) si la versión está afectada
+fn version_in_range(version: &str, events: &[HashMap<String, String>]) -> Option<Option<String>> {
+    let mut introduced: Option<&str> = None;
+
+    for event in events {
+        if let Some(v) = event.get("introduced") {
+            introduced = Some(v);
+        } else if let Some(fixed) = event.get("fixed") {
+            if let Some(start) = introduced.take() {
+                if is_at_least(version, start) && compare_versions(version, fixed) == Ordering::Less
+                {
+                    return Some(Some(fixed.clone()));
+                }
+            }
+        } else if let Some(last) = event.get("last_affected") {
+            if let Some(start) = introduced.take() {
+                if is_at_least(version, start)
+                    && compare_versions(version, last) != Ordering::Greater
+                {
+                    return Some(None);
+                }
+            }
+        }
+    }
+
+    // Rango abierto: afectado desde `introduced` sin versión corregida
+    match introduced {
+        Some(start) if is_at_least(version, start) => Some(None),
+        _ => None,
+    }
+}
+
+fn is_at_least(version: &str, start: &str) -> bool {
+    start == "0" || compare_versions(version, start) != Ordering::Less
+}
+
+fn first_fixed(affected: &AffectedPackage) -> Option<String> {
+    affected
+        .ranges
+        .iter()
+        .flat_map(|r| r.events.iter())
+        .find_map(|e| e.get("fixed").cloned())
+}
+
+/// Compara versiones numéricas (`1.2.10` > `1.2.9`); un pre-release es menor que su release
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> (Vec<u64>, bool) {
+        let v = v.trim_start_matches('v');
+        let (core, pre) = match v.split_once(['-', '+']) {
+            Some((core, rest)) => (core, v[core.len()..].starts_with('-') && !rest.is_empty()),
+            None => (v, false),
+        };
+        (
+            core.split('.').map(|p| p.parse().unwrap_or(0)).collect(),
+            pre,
+        )
+    };
+
+    let (a_parts, a_pre) = split(a);
+    let (b_parts, b_pre) = split(b);
+    let len = a_parts.len().max(b_parts.len());
+    for i in 0..len {
+        let x = a_parts.get(i).copied().unwrap_or(0);
+        let y = b_parts.get(i).copied().unwrap_or(0);
+        match x.cmp(&y) {
+            Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+
+    match (a_pre, b_pre) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+/// Versión concreta de un requisito: exacta en lockfiles, mínima en rangos (`^1.2` -> `1.2`)
+fn concrete_version(spec: &str) -> Option<String> {
+    let spec = spec.split("||").next()?.trim();
+    let spec = spec.split(',').next()?.trim();
+    let version: String = spec
+        .trim_start_matches(['^', '~', '=', '>', '<', '!', 'v', ' '])
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+        .collect();
+
+    if version.starts_with(|c: char| c.is_ascii_digit()) {
+        Some(version)
+    } else {
+        None
+    }
+}
+
+fn severity_of(advisory: &Advisory) -> String {
+    let from_value = |v: &Option<Value>| {
+        v.as_ref()
+            .and_then(|v| v.get("severity"))
+            .and_then(|s| s.as_str())
+            .map(|s| s.to_lowercase())
+    };
+
+    from_value(&advisory.database_specific)
+        .or_else(|| {
+            advisory
+                .affected
+                .iter()
+                .find_map(|a| from_value(&a.ecosystem_specific))
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn is_lockfile(path: &str) -> bool {
+    path.ends_with("Cargo.lock") || path.ends_with("package-lock.json")
+}
+
+fn ecosystem_of_manifest(path: &str) -> Option<&'static str> {
+    match path.rsplit('/').next().unwrap_or(path) {
+        "Cargo.toml" | "Cargo.lock" => Some("rust"),
+        "package.json" | "package-lock.json" => Some("js"),
+        "pyproject.toml" | "requirements.txt" => Some("python"),
+        _ => None,
+    }
+}
+
+/// Nombre del ecosistema en OSV
+fn osv_ecosystem(ecosystem: &str) -> &'static str {
+    match ecosystem {
+        "rust" => "crates.io",
+        "js" => "npm",
+        "python" => "PyPI",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::generate_metadata_chunks;
+    use crate::storage::{get_security_findings, init_chunk_database};
+
+    fn advisory(id: &str, ecosystem: &str, name: &str, events: &str) -> Advisory {
+        serde_json::from_str(&format!(
+            r#"{{"id": "{}", "summary": "bad", "database_specific": {{"severity": "HIGH"}},
+               "affected": [{{"package": {{"ecosystem": "{}", "name": "{}"}},
+                             "ranges": [{{"type": "SEMVER", "events": {}}}]}}]}}"#,
+            id, ecosystem, name, events
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_version_ranges() {
+        assert_eq!(compare_versions("1.2.10", "1.2.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0-beta", "1.0.0"), Ordering::Less);
+        assert_eq!(concrete_version("^1.2.3").as_deref(), Some("1.2.3"));
+        assert_eq!(concrete_version(">=2.0, <3").as_deref(), Some("2.0"));
+        assert_eq!(concrete_version("*"), None);
+
+        let events: Vec<HashMap<String, String>> =
+            serde_json::from_str(r#"[{"introduced": "0"}, {"fixed": "1.5.0"}]"#).unwrap();
+        assert_eq!(
+            version_in_range("1.4.9", &events),
+            Some(Some("1.5.0".to_string()))
+        );
+        assert_eq!(version_in_range("1.5.0", &events), None);
+    }
+
+    #[test]
+    fn test_scan_uses_lockfile_versions() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let project = "/tmp/project";
+
+        generate_metadata_chunks(
+            &conn,
+            project,
+            "Cargo.toml",
+            "[dependencies]\ntime = \"0.1\"\nserde = \"1\"\n",
+        )
+        .unwrap();
+        generate_metadata_chunks(
+            &conn,
+            project,
+            "Cargo.lock",
+            "[[package]]\nname = \"time\"\nversion = \"0.1.45\"\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.200\"\n",
+        )
+        .unwrap();
+
+        let advisories = vec![
+            advisory(
+                "RUSTSEC-2020-0071",
+                "crates.io",
+                "time",
+                r#"[{"introduced": "0"}, {"fixed": "0.2.23"}]"#,
+            ),
+            advisory(
+                "RUSTSEC-0000-0001",
+                "crates.io",
+                "serde",
+                r#"[{"introduced": "0"}, {"fixed": "1.0.100"}]"#,
+            ),
+            advisory("GHSA-xxxx", "npm", "time", r#"[{"introduced": "0"}]"#),
+        ];
+
+        let findings = scan_vulnerabilities(&conn, project, &advisories).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].manifest_path, "Cargo.lock");
+        assert_eq!(findings[0].installed_version, "0.1.45");
+        assert_eq!(findings[0].fixed_version.as_deref(), Some("0.2.23"));
+        assert_eq!(findings[0].severity, "high");
+
+        // Un nuevo escaneo reemplaza los hallazgos anteriores
+        scan_vulnerabilities(&conn, project, &advisories).unwrap();
+        assert_eq!(
+            get_security_findings(&conn, project, Some("time"))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use super::storage::row_timestamp;
+use super::types::{
+    ChunkTypeCount, ChunksPerDay, ErrorRatePerDay, FileTypeFreshness, SnapshotCadence,
+    SnapshotsPerDay,
+};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+
+/// Ventana por defecto de los agregados por día
+pub const DEFAULT_ANALYTICS_DAYS: u32 = 30;
+
+/// Modificador de julianday() para el inicio de la ventana ("-30 days")
+fn window_start(days: u32) -> String {
+    format!("-{} days", days)
+}
+
+/// Chunks creados por día y tipo en la ventana, con el total acumulado del proyecto
+pub fn chunks_over_time(
+    conn: &Connection,
+    project_path: &str,
+    days: u32,
+) -> Result<Vec<ChunksPerDay>> {
+    let mut stmt = conn.prepare(
+        "WITH daily AS (
+            SELECT date(created_at) AS day, chunk_type, COUNT(*) AS n
+            FROM chunks WHERE project_path = ?1
+            GROUP BY day, chunk_type
+         ),
+         totals AS (
+            SELECT day, SUM(n) AS created,
+                   SUM(SUM(n)) OVER (ORDER BY day) AS cumulative
+            FROM daily GROUP BY day
+         )
+         SELECT d.day, d.chunk_type, d.n, t.created, t.cumulative
+         FROM daily d JOIN totals t ON t.day = d.day
+         WHERE julianday(d.day) >= julianday('now', 'start of day', ?2)
+         ORDER BY d.day, d.chunk_type",
+    )?;
+    let rows = stmt
+        .query_map(params![project_path, window_start(days)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // Agrupar las filas por día (ya vienen ordenadas)
+    let mut by_day: BTreeMap<String, ChunksPerDay> = BTreeMap::new();
+    for (day, chunk_type, count, created, cumulative) in rows {
+        by_day
+            .entry(day.clone())
+            .or_insert_with(|| ChunksPerDay {
+                day,
+                created: created as usize,
+                cumulative: cumulative as usize,
+                by_type: Vec::new(),
+            })
+            .by_type
+            .push(ChunkTypeCount {
+                chunk_type,
+                count: count as usize,
+            });
+    }
+    Ok(by_day.into_values().collect())
+}
+
+/// Snapshots master/agent por día y horas promedio entre snapshots master
+pub fn snapshot_cadence(
+    conn: &Connection,
+    project_path: &str,
+    days: u32,
+) -> Result<SnapshotCadence> {
+    let mut stmt = conn.prepare(
+        "SELECT date(created_at) AS day,
+                SUM(snapshot_type = 'master'), SUM(snapshot_type = 'agent')
+         FROM snapshots
+         WHERE project_path = ?1 AND julianday(created_at) >= julianday('now', 'start of day', ?2)
+         GROUP BY day ORDER BY day",
+    )?;
+    let per_day = stmt
+        .query_map(params![project_path, window_start(days)], |row| {
+            Ok(SnapshotsPerDay {
+                day: row.get(0)?,
+                master: row.get::<_, i64>(1)? as usize,
+                agent: row.get::<_, i64>(2)? as usize,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let (avg_hours_between_masters, avg_agents_per_master): (Option<f64>, Option<f64>) = conn
+        .query_row(
+            "WITH masters AS (
+                SELECT id,
+                       julianday(created_at) - LAG(julianday(created_at)) OVER (ORDER BY created_at, id) AS gap
+                FROM snapshots
+                WHERE project_path = ?1 AND snapshot_type = 'master'
+                  AND julianday(created_at) >= julianday('now', 'start of day', ?2)
+             )
+             SELECT AVG(gap) * 24,
+                    (SELECT AVG(n) FROM (
+                        SELECT COUNT(a.id) AS n FROM masters m
+                        LEFT JOIN snapshots a ON a.parent_snapshot_id = m.id AND a.snapshot_type = 'agent'
+                        GROUP BY m.id))
+             FROM masters",
+            params![project_path, window_start(days)],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+    Ok(SnapshotCadence {
+        per_day,
+        avg_hours_between_masters,
+        avg_agents_per_master,
+    })
+}
+
+/// Errores abiertos (first_seen) y resueltos (resolved_at) por día, con los que
+/// quedaban abiertos al final de cada día
+pub fn error_rates(
+    conn: &Connection,
+    project_path: &str,
+    days: u32,
+) -> Result<Vec<ErrorRatePerDay>> {
+    let mut stmt = conn.prepare(
+        "WITH events AS (
+            SELECT date(first_seen) AS day, 1 AS opened, 0 AS resolved
+            FROM error_logs WHERE project_path = ?1
+            UNION ALL
+            SELECT date(resolved_at), 0, 1
+            FROM error_logs WHERE project_path = ?1 AND is_resolved = 1 AND resolved_at IS NOT NULL
+         ),
+         daily AS (
+            SELECT day, SUM(opened) AS opened, SUM(resolved) AS resolved,
+                   SUM(SUM(opened) - SUM(resolved)) OVER (ORDER BY day) AS open_at_end
+            FROM events GROUP BY day
+         )
+         SELECT day, opened, resolved, open_at_end FROM daily
+         WHERE julianday(day) >= julianday('now', 'start of day', ?2)
+         ORDER BY day",
+    )?;
+    let rates = stmt
+        .query_map(params![project_path, window_start(days)], |row| {
+            Ok(ErrorRatePerDay {
+                day: row.get(0)?,
+                opened: row.get::<_, i64>(1)? as usize,
+                resolved: row.get::<_, i64>(2)? as usize,
+                open_at_end: row.get::<_, i64>(3)?.max(0) as usize,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rates)
+}
+
+/// Frescura del índice por extensión: archivos indexados (raw source) y la
+/// antigüedad de su última indexación
+pub fn index_freshness(conn: &Connection, project_path: &str) -> Result<Vec<FileTypeFreshness>> {
+    // Extensión = texto tras el último "." del nombre de archivo (tras el último "/")
+    let mut stmt = conn.prepare(
+        "WITH files AS (
+            SELECT file_path, MAX(updated_at) AS indexed_at,
+                   replace(file_path, rtrim(file_path, replace(file_path, '/', '')), '') AS name
+            FROM chunks
+            WHERE project_path = ?1 AND chunk_type = 'raw_source' AND file_path IS NOT NULL
+              AND deleted_at IS NULL
+            GROUP BY file_path
+         ),
+         typed AS (
+            SELECT indexed_at,
+                   CASE WHEN instr(name, '.') = 0 THEN ''
+                        ELSE lower(replace(name, rtrim(name, replace(name, '.', '')), '')) END AS extension
+            FROM files
+         )
+         SELECT extension, COUNT(*), MIN(indexed_at), MAX(indexed_at),
+                AVG(julianday('now') - julianday(indexed_at)) * 24
+         FROM typed GROUP BY extension ORDER BY COUNT(*) DESC, extension",
+    )?;
+    let freshness = stmt
+        .query_map(params![project_path], |row| {
+            Ok(FileTypeFreshness {
+                extension: row.get(0)?,
+                files: row.get::<_, i64>(1)? as usize,
+                oldest_indexed_at: row_timestamp(row, 2)?,
+                newest_indexed_at: row_timestamp(row, 3)?,
+                avg_age_hours: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(freshness)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::resolve_error;
+    use crate::storage::{create_snapshot, init_chunk_database, upsert_error_log};
+    use crate::test_support::chunk;
+    use crate::types::{ErrorLog, Snapshot, SnapshotType};
+    use chrono::Utc;
+
+    fn raw(conn: &Connection, file_path: &str) {
+        chunk().file(file_path).content(file_path).insert(conn);
+    }
+
+    #[test]
+    fn test_project_analytics() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        for file in ["src/main.rs", "src/lib.rs", "web/app.TSX", "v1.2/Makefile"] {
+            raw(&conn, file);
+        }
+
+        let chunks = chunks_over_time(&conn, "/p", DEFAULT_ANALYTICS_DAYS).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].created, 4);
+        assert_eq!(chunks[0].cumulative, 4);
+        assert_eq!(chunks[0].by_type[0].chunk_type, "raw_source");
+
+        let freshness = index_freshness(&conn, "/p").unwrap();
+        let extensions: Vec<_> = freshness
+            .iter()
+            .map(|f| (f.extension.as_str(), f.files))
+            .collect();
+        assert_eq!(extensions, vec![("rs", 2), ("", 1), ("tsx", 1)]);
+        assert!(freshness[0].avg_age_hours < 1.0);
+
+        let snapshot = |snapshot_type, parent| Snapshot {
+            id: None,
+            project_path: "/p".to_string(),
+            snapshot_type,
+            parent_snapshot_id: parent,
+            message: "s".to_string(),
+            user_message: None,
+            changed_files: "[]".to_string(),
+            diff_summary: None,
+            metadata: None,
+            git_commit_hash: None,
+            git_tag: None,
+            git_branch: None,
+            version_major: 1,
+            version_minor: None,
+            created_at: Utc::now(),
+        };
+        let master = create_snapshot(&conn, &snapshot(SnapshotType::Master, None)).unwrap();
+        create_snapshot(&conn, &snapshot(SnapshotType::Agent, Some(master))).unwrap();
+        create_snapshot(&conn, &snapshot(SnapshotType::Agent, Some(master))).unwrap();
+        let cadence = snapshot_cadence(&conn, "/p", DEFAULT_ANALYTICS_DAYS).unwrap();
+        assert_eq!(cadence.per_day.len(), 1);
+        assert_eq!(
+            (cadence.per_day[0].master, cadence.per_day[0].agent),
+            (1, 2)
+        );
+        assert_eq!(cadence.avg_hours_between_masters, None);
+        assert_eq!(cadence.avg_agents_per_master, Some(2.0));
+
+        let error = |message: &str| ErrorLog {
+            id: None,
+            project_path: "/p".to_string(),
+            snapshot_id: None,
+            file_path: None,
+            entity_name: None,
+            error_type: "TypeError".to_string(),
+            message: message.to_string(),
+            stacktrace: None,
+            severity: None,
+            occurrence_count: 1,
+            first_seen: Utc::now(),
+            last_seen: Utc::now(),
+            is_resolved: false,
+        };
+        let first = upsert_error_log(&conn, &error("a")).unwrap();
+        upsert_error_log(&conn, &error("b")).unwrap();
+        resolve_error(&conn, first).unwrap();
+        let rates = error_rates(&conn, "/p", DEFAULT_ANALYTICS_DAYS).unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!(
+            (rates[0].opened, rates[0].resolved, rates[0].open_at_end),
+            (2, 1, 1)
+        );
+    }
+}
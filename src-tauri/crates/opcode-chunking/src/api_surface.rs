@@ -0,0 +1,561 @@
+use super::ast::detect_language;
+use super::types::ApiChange;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use tree_sitter::{Node, Parser};
+
+/// Símbolo de la API pública de un archivo
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicSymbol {
+    pub name: String,
+    pub kind: String,
+    /// Firma normalizada (sin cuerpos ni espacios redundantes)
+    pub signature: String,
+}
+
+/// Diferencias de API pública entre dos versiones de un archivo
+#[derive(Debug, Clone, Default)]
+pub struct ApiDiff {
+    pub removed: Vec<ApiChange>,
+    pub changed: Vec<ApiChange>,
+    pub added: Vec<ApiChange>,
+}
+
+impl ApiDiff {
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.changed.is_empty()
+    }
+}
+
+/// Extrae los símbolos públicos (exportados) de un archivo Rust, TypeScript/JavaScript o Python
+pub fn extract_public_api(file_path: &str, content: &str) -> Result<Vec<PublicSymbol>> {
+    let language = detect_language(file_path)?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .context("Failed to set language")?;
+    let tree = parser
+        .parse(content, None)
+        .context("Failed to parse file")?;
+
+    let ext = file_path.rsplit('.').next().unwrap_or("");
+    let mut symbols = Vec::new();
+    let root = tree.root_node();
+    match ext {
+        "rs" => collect_rust(root, content, "", &mut symbols),
+        "py" => collect_python(root, content, &mut symbols),
+        _ => collect_js(root, content, &mut symbols),
+    }
+    Ok(symbols)
+}
+
+/// Compara la API pública de dos versiones de un archivo (None = archivo inexistente)
+pub fn diff_public_api(
+    file_path: &str,
+    old_content: Option<&str>,
+    new_content: Option<&str>,
+) -> Result<ApiDiff> {
+    let index = |content: Option<&str>| -> Result<BTreeMap<(String, String), String>> {
+        Ok(match content {
+            Some(content) => extract_public_api(file_path, content)?
+                .into_iter()
+                .map(|s| ((s.kind, s.name), s.signature))
+                .collect(),
+            None => BTreeMap::new(),
+        })
+    };
+    let old = index(old_content)?;
+    let new = index(new_content)?;
+
+    let change =
+        |(kind, name): &(String, String), old_sig: Option<&String>, new_sig: Option<&String>| {
+            ApiChange {
+                file_path: file_path.to_string(),
+                name: name.clone(),
+                kind: kind.clone(),
+                old_signature: old_sig.cloned(),
+                new_signature: new_sig.cloned(),
+            }
+        };
+
+    let mut diff = ApiDiff::default();
+    for (key, old_sig) in &old {
+        match new.get(key) {
+            None => diff.removed.push(change(key, Some(old_sig), None)),
+            Some(new_sig) if new_sig != old_sig => {
+                diff.changed.push(change(key, Some(old_sig), Some(new_sig)))
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, new_sig) in &new {
+        if !old.contains_key(key) {
+            diff.added.push(change(key, None, Some(new_sig)));
+        }
+    }
+    Ok(diff)
+}
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+fn normalize(signature: &str) -> String {
+    signature.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Texto del nodo hasta el inicio de su cuerpo (firma sin implementación)
+fn header(node: Node, source: &str) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map(|body| body.start_byte())
+        .unwrap_or(node.end_byte());
+    normalize(&source[node.start_byte()..end])
+}
+
+fn field_text(node: Node, field: &str, source: &str) -> Option<String> {
+    node.child_by_field_name(field)
+        .map(|n| text(n, source).to_string())
+}
+
+fn push(symbols: &mut Vec<PublicSymbol>, name: String, kind: &str, signature: String) {
+    symbols.push(PublicSymbol {
+        name,
+        kind: kind.to_string(),
+        signature,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Rust
+// ---------------------------------------------------------------------------
+
+/// `pub` sin restricciones (`pub(crate)` y similares no forman parte de la API)
+fn is_rust_pub(node: Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    let is_pub = node
+        .children(&mut cursor)
+        .any(|c| c.kind() == "visibility_modifier" && text(c, source).trim() == "pub");
+    is_pub
+}
+
+fn collect_rust(node: Node, source: &str, prefix: &str, symbols: &mut Vec<PublicSymbol>) {
+    let mut cursor = node.walk();
+    for item in node.named_children(&mut cursor) {
+        if item.kind() == "impl_item" {
+            collect_rust_impl(item, source, prefix, symbols);
+            continue;
+        }
+        if !is_rust_pub(item, source) {
+            continue;
+        }
+        let Some(name) = field_text(item, "name", source) else {
+            continue;
+        };
+        let qualified = format!("{}{}", prefix, name);
+
+        match item.kind() {
+            "function_item" => push(symbols, qualified, "function", header(item, source)),
+            "struct_item" => {
+                let signature = rust_struct_signature(item, source);
+                push(symbols, qualified, "struct", signature)
+            }
+            "enum_item" => push(symbols, qualified, "enum", normalize(text(item, source))),
+            "trait_item" => {
+                let signature = rust_trait_signature(item, source);
+                push(symbols, qualified, "trait", signature)
+            }
+            "type_item" => push(symbols, qualified, "type", normalize(text(item, source))),
+            "const_item" | "static_item" => {
+                let signature = format!(
+                    "{}: {}",
+                    name,
+                    field_text(item, "type", source).unwrap_or_default()
+                );
+                push(symbols, qualified, "const", signature)
+            }
+            "mod_item" => {
+                push(
+                    symbols,
+                    qualified.clone(),
+                    "module",
+                    format!("mod {}", name),
+                );
+                if let Some(body) = item.child_by_field_name("body") {
+                    collect_rust(body, source, &format!("{}::", qualified), symbols);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Campos públicos de un struct (los privados no afectan la API)
+fn rust_struct_signature(item: Node, source: &str) -> String {
+    match item.child_by_field_name("body") {
+        Some(body) if body.kind() == "field_declaration_list" => {
+            let mut cursor = body.walk();
+            let fields: Vec<String> = body
+                .named_children(&mut cursor)
+                .filter(|f| f.kind() == "field_declaration" && is_rust_pub(*f, source))
+                .map(|f| normalize(text(f, source)))
+                .collect();
+            format!("{} {{ {} }}", header(item, source), fields.join(", "))
+        }
+        _ => normalize(text(item, source)),
+    }
+}
+
+/// Firma de un trait: encabezado y firmas de sus métodos sin cuerpos
+fn rust_trait_signature(item: Node, source: &str) -> String {
+    let mut parts = vec![header(item, source)];
+    if let Some(body) = item.child_by_field_name("body") {
+        let mut cursor = body.walk();
+        for member in body.named_children(&mut cursor) {
+            match member.kind() {
+                "function_item" | "function_signature_item" | "associated_type" => {
+                    parts.push(header(member, source))
+                }
+                _ => {}
+            }
+        }
+    }
+    parts.join(" ")
+}
+
+/// Métodos públicos de bloques `impl Tipo` (los `impl Trait for Tipo` se cubren con el trait)
+fn collect_rust_impl(item: Node, source: &str, prefix: &str, symbols: &mut Vec<PublicSymbol>) {
+    if item.child_by_field_name("trait").is_some() {
+        return;
+    }
+    let (Some(type_name), Some(body)) = (
+        field_text(item, "type", source),
+        item.child_by_field_name("body"),
+    ) else {
+        return;
+    };
+    let type_name = type_name
+        .split('<')
+        .next()
+        .unwrap_or(&type_name)
+        .trim()
+        .to_string();
+
+    let mut cursor = body.walk();
+    for member in body.named_children(&mut cursor) {
+        if member.kind() != "function_item" || !is_rust_pub(member, source) {
+            continue;
+        }
+        if let Some(name) = field_text(member, "name", source) {
+            push(
+                symbols,
+                format!("{}{}::{}", prefix, type_name, name),
+                "method",
+                header(member, source),
+            );
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TypeScript / JavaScript
+// ---------------------------------------------------------------------------
+
+fn collect_js(root: Node, source: &str, symbols: &mut Vec<PublicSymbol>) {
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        if statement.kind() != "export_statement" {
+            continue;
+        }
+        let is_default = {
+            let mut c = statement.walk();
+            let found = statement.children(&mut c).any(|n| n.kind() == "default");
+            found
+        };
+
+        if let Some(declaration) = statement.child_by_field_name("declaration") {
+            collect_js_declaration(declaration, source, is_default, symbols);
+        } else if let Some(value) = statement.child_by_field_name("value") {
+            let signature = match value.kind() {
+                "function" | "function_expression" | "arrow_function" | "class" => {
+                    header(value, source)
+                }
+                _ => "default".to_string(),
+            };
+            push(symbols, "default".to_string(), "default", signature);
+        } else {
+            // export { a, b as c } / export { x } from "./y"
+            let mut c = statement.walk();
+            for clause in statement.named_children(&mut c) {
+                if clause.kind() != "export_clause" {
+                    continue;
+                }
+                let mut cc = clause.walk();
+                for specifier in clause.named_children(&mut cc) {
+                    let exported = field_text(specifier, "alias", source)
+                        .or_else(|| field_text(specifier, "name", source));
+                    if let Some(name) = exported {
+                        push(symbols, name.clone(), "export", name);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn collect_js_declaration(
+    declaration: Node,
+    source: &str,
+    is_default: bool,
+    symbols: &mut Vec<PublicSymbol>,
+) {
+    let name = field_text(declaration, "name", source);
+    let exported = |name: Option<String>| {
+        if is_default {
+            "default".to_string()
+        } else {
+            name.unwrap_or_default()
+        }
+    };
+
+    match declaration.kind() {
+        "function_declaration" | "generator_function_declaration" | "function_signature" => push(
+            symbols,
+            exported(name),
+            "function",
+            header(declaration, source),
+        ),
+        "class_declaration" | "abstract_class_declaration" => {
+            let class_name = exported(name);
+            push(
+                symbols,
+                class_name.clone(),
+                "class",
+                header(declaration, source),
+            );
+            if let Some(body) = declaration.child_by_field_name("body") {
+                collect_js_class_members(body, source, &class_name, symbols);
+            }
+        }
+        "interface_declaration" | "type_alias_declaration" | "enum_declaration" => push(
+            symbols,
+            exported(name),
+            "type",
+            normalize(text(declaration, source)),
+        ),
+        "lexical_declaration" | "variable_declaration" => {
+            let mut cursor = declaration.walk();
+            for declarator in declaration.named_children(&mut cursor) {
+                if declarator.kind() != "variable_declarator" {
+                    continue;
+                }
+                let Some(var_name) = field_text(declarator, "name", source) else {
+                    continue;
+                };
+                let type_annotation = field_text(declarator, "type", source).unwrap_or_default();
+                let signature = match declarator.child_by_field_name("value") {
+                    Some(value)
+                        if matches!(
+                            value.kind(),
+                            "arrow_function" | "function" | "function_expression"
+                        ) =>
+                    {
+                        format!(
+                            "{}{} = {}",
+                            var_name,
+                            type_annotation,
+                            header(value, source)
+                        )
+                    }
+                    _ => format!("{}{}", var_name, type_annotation),
+                };
+                push(symbols, var_name, "variable", normalize(&signature));
+            }
+        }
+        _ => {
+            if let Some(name) = name {
+                push(
+                    symbols,
+                    name,
+                    "export",
+                    normalize(text(declaration, source)),
+                );
+            }
+        }
+    }
+}
+
+fn collect_js_class_members(
+    body: Node,
+    source: &str,
+    class_name: &str,
+    symbols: &mut Vec<PublicSymbol>,
+) {
+    let mut cursor = body.walk();
+    for member in body.named_children(&mut cursor) {
+        if member.kind() != "method_definition" && member.kind() != "method_signature" {
+            continue;
+        }
+        let Some(name_node) = member.child_by_field_name("name") else {
+            continue;
+        };
+        if name_node.kind() == "private_property_identifier" {
+            continue;
+        }
+        let is_private = {
+            let mut c = member.walk();
+            let found = member.children(&mut c).any(|n| {
+                n.kind() == "accessibility_modifier"
+                    && matches!(text(n, source).trim(), "private" | "protected")
+            });
+            found
+        };
+        if is_private {
+            continue;
+        }
+        push(
+            symbols,
+            format!("{}.{}", class_name, text(name_node, source)),
+            "method",
+            header(member, source),
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Python
+// ---------------------------------------------------------------------------
+
+fn collect_python(root: Node, source: &str, symbols: &mut Vec<PublicSymbol>) {
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        let definition = if statement.kind() == "decorated_definition" {
+            match statement.child_by_field_name("definition") {
+                Some(d) => d,
+                None => continue,
+            }
+        } else {
+            statement
+        };
+        let Some(name) = field_text(definition, "name", source) else {
+            continue;
+        };
+        if name.starts_with('_') {
+            continue;
+        }
+
+        match definition.kind() {
+            "function_definition" => push(symbols, name, "function", header(definition, source)),
+            "class_definition" => {
+                push(symbols, name.clone(), "class", header(definition, source));
+                let Some(body) = definition.child_by_field_name("body") else {
+                    continue;
+                };
+                let mut c = body.walk();
+                for member in body.named_children(&mut c) {
+                    let method = if member.kind() == "decorated_definition" {
+                        match member.child_by_field_name("definition") {
+                            Some(d) => d,
+                            None => continue,
+                        }
+                    } else {
+                        member
+                    };
+                    if method.kind() != "function_definition" {
+                        continue;
+                    }
+                    let Some(method_name) = field_text(method, "name", source) else {
+                        continue;
+                    };
+                    if method_name.starts_with('_') && method_name != "__init__" {
+                        continue;
+                    }
+                    push(
+                        symbols,
+                        format!("{}.{}", name, method_name),
+                        "method",
+                        header(method, source),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(symbols: &[PublicSymbol]) -> Vec<String> {
+        symbols.iter().map(|s| s.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_extract_rust_public_api() {
+        let source = r#"
+pub fn parse(input: &str) -> Result<Ast> { todo!() }
+fn helper() {}
+pub(crate) fn internal() {}
+pub struct Config { pub name: String, secret: String }
+impl Config {
+    pub fn new() -> Self { todo!() }
+    fn private(&self) {}
+}
+pub mod nested { pub fn inner() {} }
+"#;
+        let symbols = extract_public_api("lib.rs", source).unwrap();
+        assert_eq!(
+            names(&symbols),
+            vec!["parse", "Config", "Config::new", "nested", "nested::inner"]
+        );
+        assert_eq!(
+            symbols[0].signature,
+            "pub fn parse(input: &str) -> Result<Ast>"
+        );
+        assert!(!symbols[1].signature.contains("secret"));
+    }
+
+    #[test]
+    fn test_extract_ts_and_python_public_api() {
+        let ts = r#"
+export function load(path: string): Config { return {} as Config; }
+export const VERSION = "1.0";
+export class Store { get(key: string) { return 1; } private hidden() {} }
+function local() {}
+export { local as exposed };
+"#;
+        let symbols = extract_public_api("index.ts", ts).unwrap();
+        assert_eq!(
+            names(&symbols),
+            vec!["load", "VERSION", "Store", "Store.get", "exposed"]
+        );
+
+        let py = "def run(x, y=1):\n    pass\n\ndef _private():\n    pass\n\nclass Client:\n    def __init__(self, url):\n        pass\n    def fetch(self):\n        pass\n    def _retry(self):\n        pass\n";
+        let symbols = extract_public_api("client.py", py).unwrap();
+        assert_eq!(
+            names(&symbols),
+            vec!["run", "Client", "Client.__init__", "Client.fetch"]
+        );
+    }
+
+    #[test]
+    fn test_diff_public_api() {
+        let old = "pub fn a(x: i32) {}\npub fn b() {}\nfn c() {}\n";
+        let new = "pub fn a(x: i64) { body() }\npub fn d() {}\nfn c() { changed() }\n";
+        let diff = diff_public_api("lib.rs", Some(old), Some(new)).unwrap();
+        assert!(diff.is_breaking());
+        assert_eq!(diff.changed[0].name, "a");
+        assert_eq!(diff.removed[0].name, "b");
+        assert_eq!(diff.added[0].name, "d");
+
+        let internal = diff_public_api(
+            "lib.rs",
+            Some("pub fn a() { one() }\nfn b() {}"),
+            Some("pub fn a() { two() }\nfn b() { x() }"),
+        )
+        .unwrap();
+        assert!(!internal.is_breaking());
+        assert!(internal.added.is_empty());
+    }
+}
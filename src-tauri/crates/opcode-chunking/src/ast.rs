@@ -0,0 +1,1648 @@
+use super::storage::{calculate_content_hash, normalize_file_path, upsert_chunk};
+use super::syntax_errors::record_syntax_errors;
+use super::types::{
+    AstDetail, AstMetadata, Chunk, ChunkType, ChunkingOptions, ClassSymbol, SymbolInfo,
+    SymbolsMetadata, SyntaxErrorLocation,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use lru::LruCache;
+use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use tree_sitter::{InputEdit, Language, Node, Parser, Point, Query, QueryCursor, Tree};
+
+/// Tamaño máximo de la representación del AST de un archivo. Bundles minificados y
+/// archivos generados pueden producir cientos de MB; lo que excede se omite
+pub const MAX_AST_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
+/// Tiempo máximo para parsear un archivo (minificados enormes, gramáticas patológicas)
+pub const PARSE_TIMEOUT_MICROS: u64 = 5_000_000;
+
+/// Árboles de parseo que se conservan, por hash del contenido (los menos usados
+/// recientemente se descartan)
+pub const MAX_CACHED_TREES: usize = 128;
+
+/// Archivos cuyo último contenido se conserva para reparsearlos incrementalmente
+/// (los editados hace poco: la reindexación tras una edición del agente)
+pub const MAX_INCREMENTAL_FILES: usize = 16;
+
+/// Largo máximo (en caracteres) de la firma guardada de un símbolo
+const MAX_SIGNATURE_CHARS: usize = 200;
+
+/// Errores de sintaxis que se guardan como máximo por archivo
+const MAX_SYNTAX_ERRORS: usize = 50;
+
+// Queries de símbolos por lenguaje. `@function`, `@class` y `@field` capturan la
+// declaración y `@name` su nombre. `@value` es el valor de una variable (solo cuenta
+// como función si es una arrow function o function expression) y `@receiver` el tipo
+// al que pertenece un método declarado fuera de él (Go)
+const RUST_SYMBOLS_QUERY: &str = r#"
+(function_item name: (identifier) @name) @function
+(function_signature_item name: (identifier) @name) @function
+(struct_item name: (type_identifier) @name) @class
+(enum_item name: (type_identifier) @name) @class
+(union_item name: (type_identifier) @name) @class
+(trait_item name: (type_identifier) @name) @class
+(impl_item type: (_) @name) @class
+(field_declaration name: (field_identifier) @name) @field
+(enum_variant name: (identifier) @name) @field
+"#;
+
+const JAVASCRIPT_SYMBOLS_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(generator_function_declaration name: (identifier) @name) @function
+(variable_declarator name: (identifier) @name value: (_) @value) @function
+(class_declaration name: (identifier) @name) @class
+(method_definition name: (_) @name) @function
+(field_definition property: (_) @name) @field
+"#;
+
+const TYPESCRIPT_SYMBOLS_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(generator_function_declaration name: (identifier) @name) @function
+(function_signature name: (identifier) @name) @function
+(variable_declarator name: (identifier) @name value: (_) @value) @function
+(class_declaration name: (type_identifier) @name) @class
+(abstract_class_declaration name: (type_identifier) @name) @class
+(interface_declaration name: (type_identifier) @name) @class
+(enum_declaration name: (identifier) @name) @class
+(type_alias_declaration name: (type_identifier) @name) @class
+(method_definition name: (_) @name) @function
+(method_signature name: (_) @name) @function
+(abstract_method_signature name: (_) @name) @function
+(public_field_definition name: (_) @name) @field
+(property_signature name: (_) @name) @field
+"#;
+
+const PYTHON_SYMBOLS_QUERY: &str = r#"
+(function_definition name: (identifier) @name) @function
+(class_definition name: (identifier) @name) @class
+(class_definition
+  body: (block (expression_statement (assignment left: (identifier) @name) @field)))
+"#;
+
+const GO_SYMBOLS_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(method_declaration
+  receiver: (parameter_list (parameter_declaration type: (_) @receiver))
+  name: (field_identifier) @name) @function
+(type_spec name: (type_identifier) @name) @class
+(field_declaration name: (field_identifier) @name) @field
+"#;
+
+const JAVA_SYMBOLS_QUERY: &str = r#"
+(class_declaration name: (identifier) @name) @class
+(interface_declaration name: (identifier) @name) @class
+(enum_declaration name: (identifier) @name) @class
+(record_declaration name: (identifier) @name) @class
+(method_declaration name: (identifier) @name) @function
+(constructor_declaration name: (identifier) @name) @function
+(field_declaration declarator: (variable_declarator name: (identifier) @name)) @field
+"#;
+
+const CSHARP_SYMBOLS_QUERY: &str = r#"
+(class_declaration name: (identifier) @name) @class
+(interface_declaration name: (identifier) @name) @class
+(struct_declaration name: (identifier) @name) @class
+(enum_declaration name: (identifier) @name) @class
+(record_declaration name: (identifier) @name) @class
+(method_declaration name: (identifier) @name) @function
+(constructor_declaration name: (identifier) @name) @function
+(property_declaration name: (identifier) @name) @field
+(field_declaration (variable_declaration (variable_declarator (identifier) @name))) @field
+"#;
+
+const RUBY_SYMBOLS_QUERY: &str = r#"
+(class name: (_) @name) @class
+(module name: (_) @name) @class
+(method name: (_) @name) @function
+(singleton_method name: (_) @name) @function
+"#;
+
+const PHP_SYMBOLS_QUERY: &str = r#"
+(class_declaration name: (name) @name) @class
+(interface_declaration name: (name) @name) @class
+(trait_declaration name: (name) @name) @class
+(enum_declaration name: (name) @name) @class
+(function_definition name: (name) @name) @function
+(method_declaration name: (name) @name) @function
+(property_declaration (property_element (variable_name) @name)) @field
+"#;
+
+/// Valores de variable que cuentan como declaración de función (JS/TS)
+const FUNCTION_VALUE_KINDS: [&str; 3] = ["arrow_function", "function_expression", "function"];
+
+/// Nodos (con nombre) que agregan un camino a la complejidad ciclomática: condicionales,
+/// bucles, casos de switch/match, ternarios y capturas de excepciones de cada gramática
+const DECISION_KINDS: [&str; 30] = [
+    "if_expression",
+    "if_statement",
+    "elif_clause",
+    "else_if_clause",
+    "while_expression",
+    "while_statement",
+    "do_statement",
+    "for_expression",
+    "for_statement",
+    "for_in_statement",
+    "for_each_statement",
+    "foreach_statement",
+    "enhanced_for_statement",
+    "match_arm",
+    "switch_case",
+    "switch_section",
+    "switch_block_statement_group",
+    "case_clause",
+    "case_statement",
+    "expression_case",
+    "type_case",
+    "ternary_expression",
+    "conditional_expression",
+    "catch_clause",
+    "except_clause",
+    // Ruby
+    "if",
+    "unless",
+    "while",
+    "until",
+    "when",
+];
+
+/// Operadores lógicos (tokens sin nombre) que también agregan un camino
+const LOGICAL_OPERATORS: [&str; 4] = ["&&", "||", "and", "or"];
+
+/// Nodos (con nombre) que declaran una función, método o lambda
+const FUNCTION_KINDS: [&str; 17] = [
+    "function_item",
+    "function_declaration",
+    "function_definition",
+    "function_expression",
+    "function",
+    "arrow_function",
+    "generator_function_declaration",
+    "method_definition",
+    "method_declaration",
+    "constructor_declaration",
+    "local_function_statement",
+    "closure_expression",
+    "func_literal",
+    "lambda",
+    "lambda_expression",
+    "method",
+    "singleton_method",
+];
+
+/// Nodos (con nombre) que declaran tipos, módulos o espacios de nombres: junto con las
+/// funciones son lo único que conserva el nivel `AstDetail::Skeleton`
+const DECLARATION_KINDS: [&str; 22] = [
+    "struct_item",
+    "enum_item",
+    "trait_item",
+    "union_item",
+    "type_item",
+    "impl_item",
+    "mod_item",
+    "class_declaration",
+    "abstract_class_declaration",
+    "interface_declaration",
+    "type_alias_declaration",
+    "enum_declaration",
+    "record_declaration",
+    "struct_declaration",
+    "trait_declaration",
+    "namespace_declaration",
+    "namespace_definition",
+    "class_definition",
+    "type_declaration",
+    "internal_module",
+    "module",
+    "class",
+];
+
+/// Indica si un símbolo de primer nivel (nodo y nombre) es exportado/público
+type ExportRule = fn(Node, &str, &str) -> bool;
+
+/// tree-sitter no pudo producir un AST utilizable para el archivo
+#[derive(Debug)]
+pub struct AstParseError(pub String);
+
+impl std::fmt::Display for AstParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to parse file: {}", self.0)
+    }
+}
+
+impl std::error::Error for AstParseError {}
+
+/// Parsea el contenido con un límite de tiempo. Falla con `AstParseError` si el parseo
+/// se corta o si el archivo completo es un nodo ERROR
+pub(crate) fn parse_source(language: &Language, content: &str) -> Result<Tree> {
+    parse_with_previous(language, content, None)
+}
+
+/// Como [`parse_source`], pero reutiliza las partes sin cambios de `previous` (ya
+/// ajustado con `Tree::edit`) para que reparsear tras una edición sea casi inmediato
+fn parse_with_previous(
+    language: &Language,
+    content: &str,
+    previous: Option<&Tree>,
+) -> Result<Tree> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language)
+        .context("Failed to set language")?;
+    parser.set_timeout_micros(PARSE_TIMEOUT_MICROS);
+
+    let tree = parser
+        .parse(content, previous)
+        .ok_or_else(|| AstParseError("timed out or cancelled".to_string()))?;
+    if tree.root_node().is_error() {
+        return Err(AstParseError("the whole file is a syntax error".to_string()).into());
+    }
+    Ok(tree)
+}
+
+/// Árboles compartidos por la indexación completa, la reindexación incremental y los
+/// demás extractores. Los árboles van por hash de lenguaje y contenido (sin copiar el
+/// contenido); solo los archivos parseados más recientemente guardan su último
+/// contenido, que hace falta para calcular la edición del reparseo incremental
+struct TreeCache {
+    trees: LruCache<blake3::Hash, Tree>,
+    /// (proyecto, archivo) -> lenguaje y último contenido parseado
+    files: LruCache<(String, String), (&'static str, String)>,
+}
+
+fn tree_cache() -> &'static Mutex<TreeCache> {
+    static CACHE: OnceLock<Mutex<TreeCache>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        let capacity = |n: usize| NonZeroUsize::new(n).unwrap();
+        Mutex::new(TreeCache {
+            trees: LruCache::new(capacity(MAX_CACHED_TREES)),
+            files: LruCache::new(capacity(MAX_INCREMENTAL_FILES)),
+        })
+    })
+}
+
+fn tree_key(language_name: &str, content: &str) -> blake3::Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(language_name.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(content.as_bytes());
+    hasher.finalize()
+}
+
+/// Parsea el archivo reutilizando su árbol anterior: la diferencia con el contenido
+/// previo se aplica como `InputEdit` y tree-sitter solo reparsea la zona editada.
+/// Sin árbol previo (o con otro lenguaje) hace un parseo completo
+fn parse_file(
+    project_path: &str,
+    file_path: &str,
+    language: &Language,
+    language_name: &'static str,
+    content: &str,
+) -> Result<Tree> {
+    let file_key = (project_path.to_string(), normalize_file_path(file_path));
+    let content_key = tree_key(language_name, content);
+    // Mismo contenido ya parseado, o el árbol del contenido anterior del archivo
+    let (cached, previous) = match tree_cache().lock() {
+        Ok(mut cache) => {
+            let cached = cache.trees.get(&content_key).cloned();
+            let previous = cache
+                .files
+                .get(&file_key)
+                .filter(|(previous_language, _)| *previous_language == language_name)
+                .map(|(_, previous_content)| previous_content.clone())
+                .and_then(|previous_content| {
+                    let tree = cache
+                        .trees
+                        .get(&tree_key(language_name, &previous_content))?
+                        .clone();
+                    Some((previous_content, tree))
+                });
+            (cached, previous)
+        }
+        Err(_) => (None, None),
+    };
+
+    let tree = match (cached, previous) {
+        (Some(tree), _) => tree,
+        (None, Some((previous_content, mut previous_tree))) => {
+            previous_tree.edit(&input_edit(&previous_content, content));
+            log::debug!("Incremental reparse of {}", file_path);
+            parse_with_previous(language, content, Some(&previous_tree))?
+        }
+        (None, None) => parse_source(language, content)?,
+    };
+
+    if let Ok(mut cache) = tree_cache().lock() {
+        cache.trees.put(content_key, tree.clone());
+        cache
+            .files
+            .put(file_key, (language_name, content.to_string()));
+    }
+    Ok(tree)
+}
+
+/// Árbol de un archivo para otros extractores (callgraph): si la generación de chunks
+/// AST ya lo parseó con el mismo contenido se reutiliza sin volver a parsear
+pub(crate) fn parse_cached(project_path: &str, file_path: &str, content: &str) -> Result<Tree> {
+    let language = detect_language_with_overrides(file_path, content, &HashMap::new())?;
+    let language_name = get_language_name(&language);
+    parse_file(project_path, file_path, &language, language_name, content)
+}
+
+/// Descarta el último contenido guardado de un archivo (borrado o fuera del índice);
+/// su árbol sale del caché cuando deja de usarse
+pub fn forget_parse_tree(project_path: &str, file_path: &str) {
+    if let Ok(mut cache) = tree_cache().lock() {
+        cache
+            .files
+            .pop(&(project_path.to_string(), normalize_file_path(file_path)));
+    }
+}
+
+/// Edición única que transforma `old` en `new`: el rango entre el prefijo y el sufijo
+/// comunes (en bytes, como los cuenta tree-sitter)
+fn input_edit(old: &str, new: &str) -> InputEdit {
+    let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+    let prefix = old_bytes
+        .iter()
+        .zip(new_bytes)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_bytes
+        .iter()
+        .rev()
+        .zip(new_bytes.iter().rev())
+        .take(old_bytes.len().min(new_bytes.len()) - prefix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    InputEdit {
+        start_byte: prefix,
+        old_end_byte: old.len() - suffix,
+        new_end_byte: new.len() - suffix,
+        start_position: point_at(old_bytes, prefix),
+        old_end_position: point_at(old_bytes, old.len() - suffix),
+        new_end_position: point_at(new_bytes, new.len() - suffix),
+    }
+}
+
+/// Fila y columna (en bytes) de un offset
+fn point_at(text: &[u8], byte: usize) -> Point {
+    let before = &text[..byte];
+    let row = before.iter().filter(|&&b| b == b'\n').count();
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    Point::new(row, byte - line_start)
+}
+
+/// Acumula la representación del AST hasta un límite de bytes y cuenta lo omitido
+struct AstWriter {
+    output: String,
+    limit: usize,
+    omitted_bytes: usize,
+    /// Fila que se resta a las posiciones: los chunks de entidad usan filas relativas
+    /// a su inicio para que mover la entidad dentro del archivo no cambie su hash
+    base_row: usize,
+}
+
+impl AstWriter {
+    fn new(limit: usize) -> Self {
+        Self {
+            output: String::new(),
+            limit,
+            omitted_bytes: 0,
+            base_row: 0,
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.omitted_bytes > 0 || self.output.len() + line.len() + 1 > self.limit {
+            self.omitted_bytes += line.len() + 1;
+            return;
+        }
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    /// Tokens estimados de la representación completa, incluida la parte omitida
+    /// (misma estimación que `context_pack::estimate_tokens`)
+    fn estimated_tokens(&self) -> usize {
+        (self.output.len() + self.omitted_bytes).div_ceil(4)
+    }
+
+    /// Representación final (con marcador si se cortó) y bytes omitidos
+    fn finish(mut self) -> (String, usize) {
+        if self.omitted_bytes > 0 {
+            self.output.push_str(&format!(
+                "... [AST truncated: {} bytes omitted]\n",
+                self.omitted_bytes
+            ));
+        }
+        (self.output, self.omitted_bytes)
+    }
+}
+
+/// Entidad de primer nivel del archivo (función, struct, clase...) con su nodo
+struct AstEntity<'tree> {
+    name: String,
+    node: tree_sitter::Node<'tree>,
+}
+
+/// Genera chunks de AST comprimido por archivo: el del archivo completo y uno por
+/// entidad de primer nivel (`entity_name` = nombre). Los chunks de entidad que ya no
+/// existen en el archivo se eliminan y sus errores de sintaxis se registran en
+/// error_logs. Retorna los chunks generados
+pub fn generate_ast_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+    options: &ChunkingOptions,
+) -> Result<usize> {
+    let chunks = create_ast_chunks(project_path, file_path, content, options)?;
+    for chunk in &chunks {
+        upsert_chunk(conn, chunk, None)?;
+    }
+    remove_stale_entity_chunks(conn, project_path, file_path, &chunks)?;
+    record_syntax_errors(conn, project_path, file_path, &chunks)?;
+    Ok(chunks.len())
+}
+
+/// Crea los chunks AST de un archivo sin guardarlos (usado en reindexación incremental).
+/// De las opciones se usan `language_overrides`, `ast_detail` y `ast_max_tokens`
+pub fn create_ast_chunks(
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+    options: &ChunkingOptions,
+) -> Result<Vec<Chunk>> {
+    let language = detect_language_with_overrides(file_path, content, &options.language_overrides)?;
+    let language_name = get_language_name(&language);
+    let tree = parse_file(project_path, file_path, &language, language_name, content)?;
+    let root = tree.root_node();
+    let serializer = AstSerializer {
+        source: content,
+        language: language_name,
+        detail: options.ast_detail,
+        max_tokens: options.ast_max_tokens,
+    };
+
+    let (ast_repr, mut metadata) = serializer.serialize(&root, 0);
+    metadata.symbols = extract_symbols(&language, language_name, root, content);
+    metadata.syntax_errors = collect_syntax_errors(&root, content);
+    if metadata.omitted_bytes > 0 {
+        log::warn!(
+            "AST of {} truncated ({} bytes omitted)",
+            file_path,
+            metadata.omitted_bytes
+        );
+    }
+
+    let mut chunks = vec![Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::Ast,
+        file_path: Some(file_path.to_string()),
+        entity_name: None,
+        content_hash: calculate_content_hash(&ast_repr),
+        content: ast_repr,
+        metadata: Some(serde_json::to_string(&metadata)?),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }];
+
+    for entity in top_level_entities(&root, content) {
+        let node = entity.node;
+        let (ast_repr, metadata) = serializer.serialize(&node, node.start_position().row);
+        let metadata = AstMetadata {
+            entity_kind: Some(node.kind().to_string()),
+            start_line: Some(node.start_position().row + 1),
+            end_line: Some(node.end_position().row + 1),
+            ..metadata
+        };
+        chunks.push(Chunk {
+            id: None,
+            project_path: project_path.to_string(),
+            chunk_type: ChunkType::Ast,
+            file_path: Some(file_path.to_string()),
+            entity_name: Some(entity.name.clone()),
+            // El hash incluye archivo y nombre: dos entidades con el mismo AST no se mezclan
+            content_hash: calculate_content_hash(&format!(
+                "{}:{}\n{}",
+                file_path, entity.name, ast_repr
+            )),
+            content: ast_repr,
+            metadata: Some(serde_json::to_string(&metadata)?),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        });
+    }
+
+    Ok(chunks)
+}
+
+/// Elimina (con sus relaciones) los chunks AST de entidad del archivo que no están
+/// entre los recién generados: funciones borradas o renombradas. Retorna los eliminados
+pub fn remove_stale_entity_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    current: &[Chunk],
+) -> Result<usize> {
+    let hashes: HashSet<&str> = current.iter().map(|c| c.content_hash.as_str()).collect();
+    let mut stmt = conn.prepare(
+        "SELECT id, content_hash FROM chunks
+         WHERE project_path = ?1 AND file_path = ?2 AND chunk_type = ?3
+           AND entity_name IS NOT NULL",
+    )?;
+    let stale: Vec<i64> = stmt
+        .query_map(
+            params![
+                project_path,
+                normalize_file_path(file_path),
+                ChunkType::Ast.as_str()
+            ],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, hash)| !hashes.contains(hash.as_str()))
+        .map(|(id, _)| id)
+        .collect();
+    for id in &stale {
+        conn.execute(
+            "DELETE FROM chunk_relationships WHERE from_chunk_id = ?1 OR to_chunk_id = ?1",
+            params![id],
+        )?;
+        conn.execute("DELETE FROM chunks WHERE id = ?1", params![id])?;
+    }
+    Ok(stale.len())
+}
+
+/// Serializa subárboles de un archivo con el nivel de detalle de las opciones
+struct AstSerializer<'a> {
+    source: &'a str,
+    language: &'static str,
+    detail: AstDetail,
+    max_tokens: Option<usize>,
+}
+
+impl AstSerializer<'_> {
+    /// Representación comprimida (acotada) de un subárbol y su metadata. Si supera
+    /// `max_tokens` se repite con el siguiente nivel más compacto; en el mínimo
+    /// (`Skeleton`) lo que exceda se corta como cualquier AST demasiado grande
+    fn serialize(&self, node: &tree_sitter::Node, base_row: usize) -> (String, AstMetadata) {
+        let mut stats = AstStats::default();
+        collect_ast_stats(node, 0, &mut stats);
+
+        let mut detail = self.detail;
+        let (ast_repr, omitted_bytes) = loop {
+            let coarser = detail.coarser();
+            let limit = match (self.max_tokens, coarser) {
+                (Some(max_tokens), None) => MAX_AST_OUTPUT_BYTES.min(max_tokens * 4),
+                _ => MAX_AST_OUTPUT_BYTES,
+            };
+            let mut writer = AstWriter::new(limit);
+            writer.base_row = base_row;
+            serialize_ast_node(node, self.source, &mut writer, 0, 0, detail);
+            match (self.max_tokens, coarser) {
+                (Some(max_tokens), Some(coarser)) if writer.estimated_tokens() > max_tokens => {
+                    detail = coarser;
+                }
+                _ => break writer.finish(),
+            }
+        };
+
+        let metadata = AstMetadata {
+            language: self.language.to_string(),
+            node_count: stats.node_count,
+            max_depth: stats.max_depth,
+            cyclomatic_complexity: stats.decision_points + 1,
+            function_count: stats.function_count,
+            longest_function_lines: stats.longest_function_lines,
+            has_syntax_errors: node.has_error(),
+            detail,
+            truncated: omitted_bytes > 0,
+            omitted_bytes,
+            entity_kind: None,
+            start_line: None,
+            end_line: None,
+            symbols: None,
+            syntax_errors: Vec::new(),
+        };
+        (ast_repr, metadata)
+    }
+}
+
+/// Queries de símbolos y regla de exportación del lenguaje (ver [`get_language_name`])
+fn symbol_queries(language_name: &str) -> Option<(&'static str, ExportRule)> {
+    let query = match language_name {
+        "rust" => RUST_SYMBOLS_QUERY,
+        "javascript" => JAVASCRIPT_SYMBOLS_QUERY,
+        "typescript" | "tsx" => TYPESCRIPT_SYMBOLS_QUERY,
+        "python" => PYTHON_SYMBOLS_QUERY,
+        "go" => GO_SYMBOLS_QUERY,
+        "java" => JAVA_SYMBOLS_QUERY,
+        "csharp" => CSHARP_SYMBOLS_QUERY,
+        "ruby" => RUBY_SYMBOLS_QUERY,
+        "php" => PHP_SYMBOLS_QUERY,
+        _ => return None,
+    };
+    let is_exported: ExportRule = match language_name {
+        "rust" => has_visibility_modifier,
+        "javascript" | "typescript" | "tsx" => in_export_statement,
+        "python" => |_, name, _| !name.starts_with('_'),
+        "go" => |_, name, _| name.chars().next().is_some_and(char::is_uppercase),
+        "java" | "csharp" => has_public_modifier,
+        _ => |_, _, _| true,
+    };
+    Some((query, is_exported))
+}
+
+/// Rust: `pub`, `pub(crate)`...
+fn has_visibility_modifier(node: Node, _name: &str, _content: &str) -> bool {
+    let mut cursor = node.walk();
+    let visible = node
+        .children(&mut cursor)
+        .any(|child| child.kind() == "visibility_modifier");
+    visible
+}
+
+/// JS/TS: la declaración (o la variable) está dentro de un `export`
+fn in_export_statement(node: Node, _name: &str, _content: &str) -> bool {
+    std::iter::successors(node.parent(), |parent| parent.parent())
+        .take(2)
+        .any(|ancestor| ancestor.kind() == "export_statement")
+}
+
+/// Java y C#: modificador `public`
+fn has_public_modifier(node: Node, _name: &str, content: &str) -> bool {
+    let mut cursor = node.walk();
+    let public = node.children(&mut cursor).any(|child| {
+        matches!(child.kind(), "modifiers" | "modifier")
+            && content[child.byte_range()]
+                .split_whitespace()
+                .any(|word| word == "public")
+    });
+    public
+}
+
+/// Categoría de una captura de la query de símbolos
+#[derive(Clone, Copy, PartialEq)]
+enum SymbolCapture {
+    Function,
+    Class,
+    Field,
+}
+
+/// Declaración encontrada por la query, antes de armar el outline
+struct FoundSymbol<'tree> {
+    node: Node<'tree>,
+    name: String,
+    capture: SymbolCapture,
+    receiver: Option<String>,
+}
+
+/// Extrae el outline del archivo (símbolos exportados, firmas de funciones y miembros
+/// de clases) con las queries tree-sitter del lenguaje. None si el lenguaje no tiene
+/// queries o si la query no compila con la gramática
+fn extract_symbols(
+    language: &Language,
+    language_name: &str,
+    root: Node,
+    content: &str,
+) -> Option<SymbolsMetadata> {
+    let (source, is_exported) = symbol_queries(language_name)?;
+    let query = match Query::new(language, source) {
+        Ok(query) => query,
+        Err(e) => {
+            log::debug!("Invalid symbols query: {}", e);
+            return None;
+        }
+    };
+    let index = |name: &str| query.capture_index_for_name(name);
+    let (function, class, field) = (index("function"), index("class"), index("field"));
+    let (name_index, value_index, receiver_index) =
+        (index("name"), index("value"), index("receiver"));
+    let text = |node: Node| content[node.byte_range()].to_string();
+
+    let mut found: Vec<FoundSymbol> = Vec::new();
+    let mut cursor = QueryCursor::new();
+    for m in cursor.matches(&query, root, content.as_bytes()) {
+        let (mut declaration, mut name, mut value, mut receiver) = (None, None, None, None);
+        for capture in m.captures {
+            let captured = Some(capture.index);
+            if captured == name_index {
+                name = Some(capture.node);
+            } else if captured == value_index {
+                value = Some(capture.node);
+            } else if captured == receiver_index {
+                receiver = Some(capture.node);
+            } else if captured == function {
+                declaration = Some((capture.node, SymbolCapture::Function));
+            } else if captured == class {
+                declaration = Some((capture.node, SymbolCapture::Class));
+            } else if captured == field {
+                declaration = Some((capture.node, SymbolCapture::Field));
+            }
+        }
+        let (Some((node, capture)), Some(name)) = (declaration, name) else {
+            continue;
+        };
+        if value.is_some_and(|v| !FUNCTION_VALUE_KINDS.contains(&v.kind())) {
+            continue;
+        }
+        found.push(FoundSymbol {
+            node,
+            name: type_base_name(&text(name)),
+            capture,
+            receiver: receiver.map(|r| type_base_name(&text(r))),
+        });
+    }
+    found.sort_by_key(|f| (f.node.start_byte(), std::cmp::Reverse(f.node.end_byte())));
+    found.dedup_by_key(|f| f.node.id());
+
+    let mut symbols = SymbolsMetadata::default();
+    for symbol in &found {
+        // Declaración más interna (función o clase) que contiene al símbolo
+        let enclosing = found.iter().rfind(|other| {
+            other.capture != SymbolCapture::Field
+                && other.node.id() != symbol.node.id()
+                && other.node.start_byte() <= symbol.node.start_byte()
+                && symbol.node.end_byte() <= other.node.end_byte()
+        });
+        let line = symbol.node.start_position().row + 1;
+        let owner = match (enclosing, &symbol.receiver) {
+            // Las funciones anidadas no forman parte del outline
+            (Some(parent), _) if parent.capture == SymbolCapture::Function => continue,
+            (Some(parent), _) => Some(parent.name.clone()),
+            (None, Some(receiver)) => Some(receiver.clone()),
+            (None, None) => None,
+        };
+
+        match (owner, symbol.capture) {
+            (None, SymbolCapture::Class) => {
+                if symbols.classes.iter().any(|c| c.name == symbol.name) {
+                    continue; // `impl` de un tipo ya declarado
+                }
+                if is_exported(symbol.node, &symbol.name, content) {
+                    symbols.exports.push(symbol.name.clone());
+                }
+                symbols.classes.push(ClassSymbol {
+                    name: symbol.name.clone(),
+                    kind: symbol.node.kind().to_string(),
+                    line,
+                    members: Vec::new(),
+                });
+            }
+            (None, SymbolCapture::Function) => {
+                if is_exported(symbol.node, &symbol.name, content) {
+                    symbols.exports.push(symbol.name.clone());
+                }
+                symbols.functions.push(SymbolInfo {
+                    name: symbol.name.clone(),
+                    kind: "function".to_string(),
+                    signature: symbol_signature(symbol.node, content),
+                    line,
+                });
+            }
+            (None, SymbolCapture::Field) => {}
+            (Some(owner), capture) => {
+                let member = SymbolInfo {
+                    name: symbol.name.clone(),
+                    kind: if capture == SymbolCapture::Field {
+                        "field".to_string()
+                    } else {
+                        "method".to_string()
+                    },
+                    signature: symbol_signature(symbol.node, content),
+                    line,
+                };
+                match symbols.classes.iter_mut().find(|c| c.name == owner) {
+                    Some(class) => class.members.push(member),
+                    // Método de Go declarado antes que su tipo (o en otro archivo)
+                    None => symbols.classes.push(ClassSymbol {
+                        name: owner,
+                        kind: "receiver".to_string(),
+                        line,
+                        members: vec![member],
+                    }),
+                }
+            }
+        }
+    }
+    Some(symbols)
+}
+
+/// Nombre de un tipo sin punteros ni parámetros genéricos (`*List[T]` → `List`)
+fn type_base_name(name: &str) -> String {
+    let name = name.trim_start_matches(['*', '&']);
+    name.split(['<', '['])
+        .next()
+        .unwrap_or(name)
+        .trim()
+        .to_string()
+}
+
+/// Firma de una función o miembro: la declaración hasta el cuerpo (o su primera
+/// línea si no tiene), en una sola línea y acotada a `MAX_SIGNATURE_CHARS`
+fn symbol_signature(node: Node, content: &str) -> String {
+    let body = node.child_by_field_name("body").or_else(|| {
+        node.child_by_field_name("value")
+            .and_then(|value| value.child_by_field_name("body"))
+    });
+    let text = match body {
+        Some(body) => &content[node.start_byte()..body.start_byte()],
+        None => {
+            let text = &content[node.byte_range()];
+            text.lines().next().unwrap_or_default()
+        }
+    };
+    let signature = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let signature = signature.trim_end_matches(['{', ':', ',', ';']).trim_end();
+    signature.chars().take(MAX_SIGNATURE_CHARS).collect()
+}
+
+/// Funciones, structs, enums, traits, clases, interfaces y tipos de primer nivel del
+/// archivo. Se desenvuelven `export` (JS/TS) y los decoradores (Python) y se entra en
+/// namespaces (C#, PHP) y módulos (Ruby); los métodos de un `impl` de Rust se nombran
+/// `Tipo::método`, los de Go `Tipo.método`, y las arrow functions asignadas a una
+/// constante toman el nombre de la constante
+fn top_level_entities<'tree>(
+    root: &tree_sitter::Node<'tree>,
+    content: &str,
+) -> Vec<AstEntity<'tree>> {
+    let mut entities = Vec::new();
+    collect_entities(*root, content, &mut entities);
+    entities
+}
+
+fn collect_entities<'tree>(
+    parent: tree_sitter::Node<'tree>,
+    content: &str,
+    entities: &mut Vec<AstEntity<'tree>>,
+) {
+    let text = |node: tree_sitter::Node| content[node.byte_range()].to_string();
+    let mut cursor = parent.walk();
+    for child in parent.named_children(&mut cursor) {
+        // `export function f` / `@decorador def f`: la entidad es la declaración interna
+        let node = match child.kind() {
+            "export_statement" => match child.child_by_field_name("declaration") {
+                Some(declaration) => declaration,
+                None => continue,
+            },
+            "decorated_definition" => match child.child_by_field_name("definition") {
+                Some(definition) => definition,
+                None => continue,
+            },
+            _ => child,
+        };
+        match node.kind() {
+            "function_item"
+            | "struct_item"
+            | "enum_item"
+            | "trait_item"
+            | "union_item"
+            | "type_item"
+            | "function_declaration"
+            | "generator_function_declaration"
+            | "class_declaration"
+            | "abstract_class_declaration"
+            | "interface_declaration"
+            | "type_alias_declaration"
+            | "enum_declaration"
+            | "function_definition"
+            | "class_definition"
+            | "record_declaration"
+            | "struct_declaration"
+            | "trait_declaration"
+            | "method"
+            | "singleton_method"
+            | "class" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    // Con export/decoradores la entidad abarca desde la línea que los abre
+                    entities.push(AstEntity {
+                        name: text(name),
+                        node: if child.kind() == "decorated_definition" {
+                            child
+                        } else {
+                            node
+                        },
+                    });
+                }
+            }
+            "module" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    entities.push(AstEntity {
+                        name: text(name),
+                        node,
+                    });
+                }
+                collect_entities(node, content, entities);
+            }
+            "namespace_declaration" | "namespace_definition" => {
+                if let Some(body) = node.child_by_field_name("body") {
+                    collect_entities(body, content, entities);
+                }
+            }
+            "file_scoped_namespace_declaration" | "body_statement" => {
+                collect_entities(node, content, entities);
+            }
+            "impl_item" => {
+                let (Some(type_node), Some(body)) = (
+                    node.child_by_field_name("type"),
+                    node.child_by_field_name("body"),
+                ) else {
+                    continue;
+                };
+                let type_name = text(type_node);
+                let mut body_cursor = body.walk();
+                for item in body.named_children(&mut body_cursor) {
+                    if item.kind() != "function_item" {
+                        continue;
+                    }
+                    if let Some(name) = item.child_by_field_name("name") {
+                        entities.push(AstEntity {
+                            name: format!("{}::{}", type_name, text(name)),
+                            node: item,
+                        });
+                    }
+                }
+            }
+            "method_declaration" => {
+                let Some(name) = node.child_by_field_name("name") else {
+                    continue;
+                };
+                // Go: `func (u *User) Name()` → `User.Name`
+                let receiver = node
+                    .child_by_field_name("receiver")
+                    .and_then(|r| r.named_child(0))
+                    .and_then(|p| p.child_by_field_name("type"))
+                    .map(|t| text(t).trim_start_matches('*').to_string());
+                entities.push(AstEntity {
+                    name: match receiver {
+                        Some(receiver) => format!("{}.{}", receiver, text(name)),
+                        None => text(name),
+                    },
+                    node,
+                });
+            }
+            "type_declaration" => {
+                let mut specs = node.walk();
+                for spec in node.named_children(&mut specs) {
+                    if let Some(name) = spec.child_by_field_name("name") {
+                        entities.push(AstEntity {
+                            name: text(name),
+                            node: spec,
+                        });
+                    }
+                }
+            }
+            "lexical_declaration" | "variable_declaration" => {
+                let mut declarators = node.walk();
+                for declarator in node.named_children(&mut declarators) {
+                    let (Some(name), Some(value)) = (
+                        declarator.child_by_field_name("name"),
+                        declarator.child_by_field_name("value"),
+                    ) else {
+                        continue;
+                    };
+                    if matches!(
+                        value.kind(),
+                        "arrow_function" | "function_expression" | "function"
+                    ) {
+                        entities.push(AstEntity {
+                            name: text(name),
+                            node: declarator,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Contadores que se acumulan al recorrer el AST
+#[derive(Default)]
+struct AstStats {
+    node_count: usize,
+    max_depth: usize,
+    decision_points: usize,
+    function_count: usize,
+    longest_function_lines: usize,
+}
+
+/// Recorre el AST (hasta la profundidad que se serializa) acumulando las métricas;
+/// no depende del nivel de detalle
+fn collect_ast_stats(node: &tree_sitter::Node, depth: usize, stats: &mut AstStats) {
+    stats.node_count += 1;
+    if depth > stats.max_depth {
+        stats.max_depth = depth;
+    }
+
+    let kind = node.kind();
+    if node.is_named() {
+        if DECISION_KINDS.contains(&kind) {
+            stats.decision_points += 1;
+        }
+        if FUNCTION_KINDS.contains(&kind) {
+            let lines = node.end_position().row - node.start_position().row + 1;
+            stats.function_count += 1;
+            stats.longest_function_lines = stats.longest_function_lines.max(lines);
+        }
+    } else if LOGICAL_OPERATORS.contains(&kind) {
+        stats.decision_points += 1;
+    }
+
+    if depth < 50 {
+        for i in 0..node.child_count() {
+            if let Some(child) = node.child(i) {
+                collect_ast_stats(&child, depth + 1, stats);
+            }
+        }
+    }
+}
+
+/// Serializa un nodo del AST de forma comprimida. `depth` es la profundidad en el
+/// árbol e `indent` la de la línea escrita (en `Skeleton` solo anidan las declaraciones)
+fn serialize_ast_node(
+    node: &tree_sitter::Node,
+    source: &str,
+    writer: &mut AstWriter,
+    depth: usize,
+    indent: usize,
+    detail: AstDetail,
+) {
+    let kind = node.kind();
+    let is_function = node.is_named() && FUNCTION_KINDS.contains(&kind);
+    let emit = match detail {
+        AstDetail::Full => true,
+        AstDetail::SignaturesOnly => node.is_named(),
+        AstDetail::Skeleton => {
+            depth == 0 || is_function || (node.is_named() && DECLARATION_KINDS.contains(&kind))
+        }
+    };
+
+    let mut child_indent = indent;
+    if emit {
+        // Formato comprimido: tipo:inicio-fin
+        let mut line = format!(
+            "{}{}:{}-{}",
+            "  ".repeat(indent),
+            kind,
+            node.start_position().row - writer.base_row,
+            node.end_position().row - writer.base_row
+        );
+
+        let small_leaf = node.child_count() == 0 && node.byte_range().len() < 100;
+        match detail {
+            // Si el nodo tiene un identificador o literal, incluirlo
+            AstDetail::Full if small_leaf => line.push_str(&format!(" [{}]", kind)),
+            // Los niveles compactos muestran el texto en lugar del tipo: el de las hojas
+            // en `SignaturesOnly` y el nombre de cada declaración en `Skeleton`
+            AstDetail::SignaturesOnly if small_leaf => {
+                line.push_str(&format!(" [{}]", leaf_text(source, node)));
+            }
+            AstDetail::Skeleton => {
+                if let Some(name) = node
+                    .child_by_field_name("name")
+                    .filter(|name| name.byte_range().len() < 100)
+                {
+                    line.push_str(&format!(" [{}]", leaf_text(source, &name)));
+                }
+            }
+            _ => {}
+        }
+
+        writer.push_line(&line);
+        child_indent += 1;
+    }
+
+    // Los niveles compactos omiten los cuerpos de las funciones
+    let skipped_body = match detail {
+        AstDetail::Full => None,
+        _ if is_function => node.child_by_field_name("body").map(|body| body.id()),
+        _ => None,
+    };
+
+    // Recursivamente serializar hijos (limitado a profundidad razonable)
+    if depth < 50 {
+        for i in 0..node.child_count() {
+            if let Some(child) = node.child(i) {
+                if Some(child.id()) != skipped_body {
+                    serialize_ast_node(&child, source, writer, depth + 1, child_indent, detail);
+                }
+            }
+        }
+    }
+}
+
+/// Texto de un nodo en una sola línea (comentarios y strings pueden tener saltos)
+fn leaf_text(source: &str, node: &tree_sitter::Node) -> String {
+    source[node.byte_range()].replace('\n', "\\n")
+}
+
+/// Posiciones de los nodos ERROR (el más externo de cada región) y MISSING del árbol.
+/// Solo se desciende por los subárboles que contienen errores
+fn collect_syntax_errors(root: &tree_sitter::Node, source: &str) -> Vec<SyntaxErrorLocation> {
+    let mut errors = Vec::new();
+    let mut pending = vec![*root];
+    while let Some(node) = pending.pop() {
+        if errors.len() >= MAX_SYNTAX_ERRORS {
+            break;
+        }
+        if node.is_error() || node.is_missing() {
+            let message = if node.is_missing() {
+                format!("missing `{}`", node.kind())
+            } else {
+                let first_line = source[node.byte_range()].trim().lines().next();
+                let text: String = first_line.unwrap_or("").chars().take(40).collect();
+                format!("unexpected `{}`", text)
+            };
+            let (start, end) = (node.start_position(), node.end_position());
+            errors.push(SyntaxErrorLocation {
+                line: start.row + 1,
+                column: start.column + 1,
+                end_line: end.row + 1,
+                end_column: end.column + 1,
+                message,
+            });
+            continue;
+        }
+        if node.has_error() {
+            // En orden inverso para sacar los hijos de la pila en orden de aparición
+            for i in (0..node.child_count()).rev() {
+                if let Some(child) = node.child(i) {
+                    pending.push(child);
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Nombres de lenguaje soportados, en el orden en que se reportan en `AstMetadata.language`
+const LANGUAGE_NAMES: [&str; 10] = [
+    "rust",
+    "javascript",
+    "typescript",
+    "tsx",
+    "python",
+    "go",
+    "java",
+    "csharp",
+    "ruby",
+    "php",
+];
+
+/// Archivos sin extensión con lenguaje conocido por su nombre (DSLs de Ruby)
+const RUBY_FILE_NAMES: [&str; 9] = [
+    "Rakefile",
+    "Gemfile",
+    "Podfile",
+    "Vagrantfile",
+    "Guardfile",
+    "Brewfile",
+    "Fastfile",
+    "Capfile",
+    "Berksfile",
+];
+
+/// Detecta el lenguaje basado en la extensión (o el nombre) del archivo
+pub(crate) fn detect_language(file_path: &str) -> Result<Language> {
+    let path = Path::new(file_path);
+    let file_name = path.file_name().and_then(|s| s.to_str());
+    if file_name.is_some_and(|name| RUBY_FILE_NAMES.contains(&name)) {
+        return Ok(tree_sitter_ruby::language());
+    }
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .context("No file extension")?;
+
+    match ext {
+        "rs" => Ok(tree_sitter_rust::language()),
+        "js" | "jsx" | "mjs" | "cjs" => Ok(tree_sitter_javascript::language()),
+        "ts" | "tsx" | "mts" | "cts" => {
+            Ok(tree_sitter_typescript::language_typescript())
+        }
+        "py" | "pyw" | "pyi" => Ok(tree_sitter_python::language()),
+        "go" => Ok(tree_sitter_go::language()),
+        "java" => Ok(tree_sitter_java::language()),
+        "cs" => Ok(tree_sitter_c_sharp::language()),
+        "rb" | "rake" | "gemspec" | "ru" => Ok(tree_sitter_ruby::language()),
+        "php" => Ok(tree_sitter_php::language_php()),
+        _ => Err(anyhow::anyhow!("Unsupported language: {}", ext)),
+    }
+}
+
+/// Detecta el lenguaje por el contenido: shebang (`#!/usr/bin/env python3`) o la
+/// apertura `<?php`. Para scripts sin extensión o con una extensión desconocida
+pub(crate) fn detect_language_from_content(content: &str) -> Option<Language> {
+    let first_line = content.lines().next()?.trim();
+    if first_line.starts_with("<?php") {
+        return Some(tree_sitter_php::language_php());
+    }
+    let command = first_line.strip_prefix("#!")?;
+    let mut words = command.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    // `#!/usr/bin/env -S node --flags`: el intérprete es el primer argumento de env
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-'))?;
+    }
+    let name = match interpreter {
+        i if i.starts_with("python") => "python",
+        "node" | "nodejs" | "deno" | "bun" => "javascript",
+        "ts-node" | "tsx" => "typescript",
+        "ruby" | "jruby" => "ruby",
+        i if i.starts_with("php") => "php",
+        _ => return None,
+    };
+    language_by_name(name).ok()
+}
+
+/// Detecta el lenguaje aplicando primero los overrides por extensión del proyecto,
+/// después la extensión o el nombre del archivo y por último el contenido
+pub(crate) fn detect_language_with_overrides(
+    file_path: &str,
+    content: &str,
+    overrides: &HashMap<String, String>,
+) -> Result<Language> {
+    let ext = Path::new(file_path)
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase());
+
+    match ext.as_deref().and_then(|ext| overrides.get(ext)) {
+        Some(name) => language_by_name(name),
+        None => {
+            detect_language(file_path).or_else(|e| detect_language_from_content(content).ok_or(e))
+        }
+    }
+}
+
+/// Gramática para un nombre de lenguaje (`opcode.toml` → `[languages]`)
+pub(crate) fn language_by_name(name: &str) -> Result<Language> {
+    match name {
+        "rust" => Ok(tree_sitter_rust::language()),
+        "javascript" | "jsx" => Ok(tree_sitter_javascript::language()),
+        "typescript" => Ok(tree_sitter_typescript::language_typescript()),
+        "tsx" => Ok(tree_sitter_typescript::language_tsx()),
+        "python" => Ok(tree_sitter_python::language()),
+        "go" => Ok(tree_sitter_go::language()),
+        "java" => Ok(tree_sitter_java::language()),
+        "csharp" | "c#" => Ok(tree_sitter_c_sharp::language()),
+        "ruby" => Ok(tree_sitter_ruby::language()),
+        "php" => Ok(tree_sitter_php::language_php()),
+        _ => Err(anyhow::anyhow!("Unsupported language override: {}", name)),
+    }
+}
+
+/// Nombre del lenguaje de una gramática (el mismo que acepta [`language_by_name`]);
+/// "unknown" si no es una de las soportadas
+fn get_language_name(language: &Language) -> &'static str {
+    LANGUAGE_NAMES
+        .into_iter()
+        .find(|name| language_by_name(name).is_ok_and(|candidate| candidate == *language))
+        .unwrap_or("unknown")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert!(detect_language("test.rs").is_ok());
+        assert!(detect_language("test.js").is_ok());
+        assert!(detect_language("test.ts").is_ok());
+        assert!(detect_language("test.py").is_ok());
+        assert!(detect_language("main.go").is_ok());
+        assert!(detect_language("App.java").is_ok());
+        assert!(detect_language("Program.cs").is_ok());
+        assert!(detect_language("user.rb").is_ok());
+        assert!(detect_language("index.php").is_ok());
+        assert!(detect_language("test.unknown").is_err());
+        assert!(detect_language("Gemfile").is_ok());
+        assert!(detect_language("Dockerfile").is_err());
+    }
+
+    #[test]
+    fn test_language_names_and_content_detection() {
+        for name in LANGUAGE_NAMES {
+            assert_eq!(get_language_name(&language_by_name(name).unwrap()), name);
+        }
+        let detect = |content: &str| {
+            detect_language_from_content(content).map(|language| get_language_name(&language))
+        };
+        assert_eq!(detect("#!/usr/bin/env python3\nprint(1)\n"), Some("python"));
+        assert_eq!(detect("#!/usr/bin/env -S node -v\n"), Some("javascript"));
+        assert_eq!(detect("#!/usr/local/bin/ruby -w\n"), Some("ruby"));
+        assert_eq!(detect("<?php\necho 1;\n"), Some("php"));
+        assert_eq!(detect("#!/bin/bash\n"), None);
+        assert_eq!(detect("print(1)\n"), None);
+
+        let options = ChunkingOptions::default();
+        let script = "#!/usr/bin/env python3\ndef main():\n    pass\n";
+        let chunks = create_ast_chunks("/p", "bin/deploy", script, &options).unwrap();
+        let metadata: AstMetadata =
+            serde_json::from_str(chunks[0].metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata.language, "python");
+        assert_eq!(chunks[1].entity_name.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_ast_output_is_capped() {
+        let content = "const a = [1, 2, 3];\n".repeat(200);
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(&content, None).unwrap();
+
+        let mut writer = AstWriter::new(1024);
+        serialize_ast_node(
+            &tree.root_node(),
+            &content,
+            &mut writer,
+            0,
+            0,
+            AstDetail::Full,
+        );
+        let (repr, omitted) = writer.finish();
+
+        assert!(omitted > 0);
+        assert!(repr.len() < 1024 + 64);
+        assert!(repr.ends_with(&format!("... [AST truncated: {} bytes omitted]\n", omitted)));
+
+        let mut writer = AstWriter::new(MAX_AST_OUTPUT_BYTES);
+        serialize_ast_node(
+            &tree.root_node(),
+            &content,
+            &mut writer,
+            0,
+            0,
+            AstDetail::Full,
+        );
+        assert_eq!(writer.finish().1, 0);
+    }
+
+    #[test]
+    fn test_ast_detail_levels() {
+        let rust = "pub struct User {\n    id: u32,\n}\n\nimpl User {\n    pub fn new(id: u32) -> Self {\n        if id > 0 { Self { id } } else { Self { id: 1 } }\n    }\n}\n";
+        let file_chunk = |options: &ChunkingOptions| {
+            let chunk = create_ast_chunks("/p", "src/user.rs", rust, options)
+                .unwrap()
+                .remove(0);
+            let metadata: AstMetadata =
+                serde_json::from_str(chunk.metadata.as_deref().unwrap()).unwrap();
+            (chunk.content, metadata)
+        };
+
+        let (full, full_metadata) = file_chunk(&ChunkingOptions::default());
+        let (signatures, _) = file_chunk(&ChunkingOptions {
+            ast_detail: AstDetail::SignaturesOnly,
+            ..Default::default()
+        });
+        let (skeleton, skeleton_metadata) = file_chunk(&ChunkingOptions {
+            ast_detail: AstDetail::Skeleton,
+            ..Default::default()
+        });
+        assert_eq!(full_metadata.detail, AstDetail::Full);
+        assert!(full.contains("{:"));
+        // Sin puntuación ni cuerpos de función, pero con la firma
+        assert!(!signatures.contains("{:"));
+        assert!(!signatures.contains("if_expression"));
+        assert!(signatures.contains("parameters:5-5"));
+        assert!(signatures.contains("[u32]"));
+        assert!(signatures.len() < full.len());
+        assert_eq!(
+            skeleton,
+            "source_file:0-9\n  struct_item:0-2 [User]\n  impl_item:4-8\n    function_item:5-7 [new]\n"
+        );
+        // Las métricas no dependen del nivel de detalle
+        assert_eq!(skeleton_metadata.node_count, full_metadata.node_count);
+        assert_eq!(skeleton_metadata.cyclomatic_complexity, 2);
+
+        // Con presupuesto de tokens se baja de nivel hasta entrar
+        let budget = skeleton.len().div_ceil(4);
+        let (content, metadata) = file_chunk(&ChunkingOptions {
+            ast_max_tokens: Some(budget),
+            ..Default::default()
+        });
+        assert_eq!(metadata.detail, AstDetail::Skeleton);
+        assert_eq!(content, skeleton);
+        let (_, metadata) = file_chunk(&ChunkingOptions {
+            ast_max_tokens: Some(1),
+            ..Default::default()
+        });
+        assert!(metadata.truncated);
+    }
+
+    #[test]
+    fn test_complexity_metrics() {
+        let python = "def check(x):\n    if x and x > 1:\n        return 1\n    elif x:\n        return 2\n    return 0\n\ndouble = lambda y: y * 2\n";
+        let chunks =
+            create_ast_chunks("/p", "app/check.py", python, &ChunkingOptions::default()).unwrap();
+        let metadata: AstMetadata =
+            serde_json::from_str(chunks[0].metadata.as_deref().unwrap()).unwrap();
+        // if + and + elif
+        assert_eq!(metadata.cyclomatic_complexity, 4);
+        assert_eq!(metadata.function_count, 2);
+        assert_eq!(metadata.longest_function_lines, 6);
+    }
+
+    #[test]
+    fn test_entity_chunks_per_top_level_item() {
+        let rust = "use std::fmt;\n\npub struct User { id: u32 }\n\nimpl User {\n    pub fn new(id: u32) -> Self {\n        Self { id }\n    }\n}\n\nfn main() {}\n";
+        let chunks =
+            create_ast_chunks("/p", "src/user.rs", rust, &ChunkingOptions::default()).unwrap();
+        let names: Vec<Option<&str>> = chunks.iter().map(|c| c.entity_name.as_deref()).collect();
+        assert_eq!(
+            names,
+            vec![None, Some("User"), Some("User::new"), Some("main")]
+        );
+        let metadata: AstMetadata =
+            serde_json::from_str(chunks[2].metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata.entity_kind.as_deref(), Some("function_item"));
+        assert_eq!((metadata.start_line, metadata.end_line), (Some(6), Some(8)));
+        // Filas relativas al inicio de la entidad
+        assert!(chunks[2].content.starts_with("function_item:0-2\n"));
+
+        let ts = "export function login() {}\nexport const logout = async () => {};\nclass Session {}\ninterface Token { value: string }\n";
+        let chunks =
+            create_ast_chunks("/p", "src/auth.ts", ts, &ChunkingOptions::default()).unwrap();
+        let names: Vec<&str> = chunks
+            .iter()
+            .filter_map(|c| c.entity_name.as_deref())
+            .collect();
+        assert_eq!(names, vec!["login", "logout", "Session", "Token"]);
+
+        let py = "import os\n\n@cache\ndef load():\n    pass\n\nclass Repo:\n    def save(self):\n        pass\n";
+        let chunks =
+            create_ast_chunks("/p", "app/repo.py", py, &ChunkingOptions::default()).unwrap();
+        let names: Vec<&str> = chunks
+            .iter()
+            .filter_map(|c| c.entity_name.as_deref())
+            .collect();
+        assert_eq!(names, vec!["load", "Repo"]);
+        let metadata: AstMetadata =
+            serde_json::from_str(chunks[1].metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata.start_line, Some(3)); // Incluye el decorador
+    }
+
+    #[test]
+    fn test_entity_chunks_for_other_languages() {
+        let entities = |file: &str, content: &str| -> Vec<String> {
+            create_ast_chunks("/p", file, content, &ChunkingOptions::default())
+                .unwrap()
+                .into_iter()
+                .filter_map(|c| c.entity_name)
+                .collect()
+        };
+        let go = "package main\n\ntype User struct{ name string }\n\nfunc (u *User) Name() string { return u.name }\n\nfunc main() {}\n";
+        assert_eq!(entities("main.go", go), vec!["User", "User.Name", "main"]);
+
+        let csharp = "using System;\nnamespace App {\n    public class Greeter { }\n    interface IGreeter { }\n}\n";
+        assert_eq!(entities("Greeter.cs", csharp), vec!["Greeter", "IGreeter"]);
+
+        let java = "package app;\npublic class Main {\n    void run() {}\n}\n";
+        assert_eq!(entities("Main.java", java), vec!["Main"]);
+
+        let ruby = "module Billing\n  class Invoice\n  end\nend\n\ndef helper\nend\n";
+        assert_eq!(
+            entities("billing.rb", ruby),
+            vec!["Billing", "Invoice", "helper"]
+        );
+
+        let php = "<?php\nfunction render() {}\nclass Page {}\n";
+        assert_eq!(entities("page.php", php), vec!["render", "Page"]);
+    }
+
+    fn file_symbols(file_path: &str, content: &str) -> SymbolsMetadata {
+        let chunks =
+            create_ast_chunks("/p", file_path, content, &ChunkingOptions::default()).unwrap();
+        let metadata: AstMetadata =
+            serde_json::from_str(chunks[0].metadata.as_deref().unwrap()).unwrap();
+        metadata.symbols.unwrap()
+    }
+
+    #[test]
+    fn test_symbols_outline() {
+        let rust = "pub struct User {\n    pub id: u32,\n}\n\nimpl User {\n    pub fn new(\n        id: u32,\n    ) -> Self {\n        Self { id }\n    }\n}\n\nfn helper() {\n    fn inner() {}\n}\n";
+        let symbols = file_symbols("src/user.rs", rust);
+        assert_eq!(symbols.exports, vec!["User"]);
+        assert_eq!(symbols.functions.len(), 1);
+        assert_eq!(symbols.functions[0].signature, "fn helper()");
+        assert_eq!(symbols.classes.len(), 1);
+        let members: Vec<(&str, &str)> = symbols.classes[0]
+            .members
+            .iter()
+            .map(|m| (m.name.as_str(), m.kind.as_str()))
+            .collect();
+        assert_eq!(members, vec![("id", "field"), ("new", "method")]);
+        assert_eq!(
+            symbols.classes[0].members[1].signature,
+            "pub fn new( id: u32, ) -> Self"
+        );
+
+        let ts = "export class Api {\n  base: string;\n  get(path: string): Promise<void> {}\n}\n\nexport const load = async (id: number) => {};\nfunction local() {}\nconst value = 1;\n";
+        let symbols = file_symbols("src/api.ts", ts);
+        assert_eq!(symbols.exports, vec!["Api", "load"]);
+        let functions: Vec<&str> = symbols.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(functions, vec!["load", "local"]);
+        let get = &symbols.classes[0].members[1];
+        assert_eq!(get.signature, "get(path: string): Promise<void>");
+
+        let python = "class Service:\n    retries = 3\n\n    def run(self, job):\n        pass\n\ndef _private():\n    pass\n";
+        let symbols = file_symbols("service.py", python);
+        assert_eq!(symbols.exports, vec!["Service"]);
+        let run = &symbols.classes[0].members[1];
+        assert_eq!(run.kind, "method");
+        assert_eq!(run.signature, "def run(self, job)");
+    }
+
+    #[test]
+    fn test_entity_chunks_are_queryable_and_pruned() {
+        use crate::storage::{init_chunk_database, query_chunks};
+        use crate::types::ChunkQuery;
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let options = ChunkingOptions::default();
+        let before = "fn login() {}\n\nfn logout() {}\n";
+        assert_eq!(
+            generate_ast_chunks(&conn, "/p", "src/auth.rs", before, &options).unwrap(),
+            3
+        );
+        let find = |name: &str| {
+            query_chunks(
+                &conn,
+                &ChunkQuery {
+                    project_path: Some("/p".to_string()),
+                    entity_name: Some(name.to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+        assert_eq!(find("logout").len(), 1);
+        assert_eq!(find("logout")[0].chunk_type, ChunkType::Ast);
+
+        // logout desaparece; login se mueve de línea pero conserva su chunk
+        let login_id = find("login")[0].id;
+        let after = "// auth\nfn login() {}\n";
+        generate_ast_chunks(&conn, "/p", "src/auth.rs", after, &options).unwrap();
+        assert!(find("logout").is_empty());
+        let login = find("login");
+        assert_eq!(login[0].id, login_id);
+        let metadata: AstMetadata =
+            serde_json::from_str(login[0].metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata.start_line, Some(2));
+    }
+
+    #[test]
+    fn test_language_overrides() {
+        let overrides = HashMap::from([
+            ("es6".to_string(), "javascript".to_string()),
+            ("rs".to_string(), "cobol".to_string()),
+        ]);
+        assert!(detect_language_with_overrides("lib/a.ES6", "", &overrides).is_ok());
+        assert!(detect_language_with_overrides("src/main.rs", "", &overrides).is_err());
+        assert!(detect_language_with_overrides("app.py", "", &overrides).is_ok());
+    }
+
+    #[test]
+    fn test_incremental_reparse() {
+        let edit = input_edit("fn a() {}\nfn b() {}\n", "fn a() {}\nfn bc() {}\n");
+        assert_eq!(
+            (edit.start_byte, edit.old_end_byte, edit.new_end_byte),
+            (14, 14, 15)
+        );
+        assert_eq!(edit.start_position, Point::new(1, 4));
+        assert_eq!(edit.new_end_position, Point::new(1, 5));
+
+        let language = language_by_name("rust").unwrap();
+        let before = "fn login() {}\n\nfn logout() {}\n";
+        let after = "fn login() {}\n\nfn refresh(token: &str) {}\n\nfn logout() {}\n";
+        let project = format!("/incremental-{}", uuid::Uuid::new_v4());
+        parse_file(&project, "src/auth.rs", &language, "rust", before).unwrap();
+        let tree = parse_file(&project, "src/auth.rs", &language, "rust", after).unwrap();
+        let fresh = parse_source(&language, after).unwrap();
+        assert_eq!(tree.root_node().to_sexp(), fresh.root_node().to_sexp());
+
+        // El archivo guarda su último contenido, y el árbol va por el hash del contenido
+        let file_key = (project.clone(), "src/auth.rs".to_string());
+        let mut cache = tree_cache().lock().unwrap();
+        assert_eq!(cache.files.get(&file_key).unwrap().1, after);
+        assert!(cache.trees.contains(&tree_key("rust", after)));
+        drop(cache);
+        forget_parse_tree(&project, "src/auth.rs");
+        assert!(!tree_cache().lock().unwrap().files.contains(&file_key));
+    }
+}
@@ -0,0 +1,301 @@
+use super::index_runs::get_index_state;
+use super::storage::{
+    fetch_chunks_page, get_benchmark_runs, get_chunk_with_context, insert_benchmark_run,
+    query_chunks,
+};
+use super::types::{
+    BenchmarkRegression, BenchmarkRun, ChunkQuery, ChunkType, ChunkingResult, IndexingBenchmark,
+    QueryBenchmark,
+};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::time::{Duration, Instant};
+
+/// Repeticiones de cada consulta si no se indican
+pub const DEFAULT_BENCH_ITERATIONS: usize = 20;
+
+/// Una métrica es una regresión si es al menos este factor más lenta que en la
+/// ejecución anterior
+pub const REGRESSION_RATIO: f64 = 1.5;
+
+/// Latencia p50 mínima para considerar regresión una consulta (por debajo es ruido)
+const MIN_REGRESSION_US: u64 = 100;
+
+/// Tamaño de página de las consultas paginadas
+const BENCH_PAGE_SIZE: usize = 50;
+
+/// Throughput de una indexación completa (en dry run) que tardó `elapsed`
+pub fn indexing_benchmark(result: &ChunkingResult, elapsed: Duration) -> IndexingBenchmark {
+    let chunks = result.chunks_created + result.chunks_updated;
+    let seconds = elapsed.as_secs_f64();
+    IndexingBenchmark {
+        duration_ms: elapsed.as_millis() as u64,
+        chunks,
+        chunks_per_second: if seconds > 0.0 {
+            chunks as f64 / seconds
+        } else {
+            0.0
+        },
+    }
+}
+
+/// Mide la latencia de los patrones de consulta representativos sobre el proyecto,
+/// guarda la ejecución y marca las regresiones respecto a la anterior
+pub fn record_benchmark(
+    conn: &Connection,
+    project_path: &str,
+    iterations: usize,
+    indexing: Option<IndexingBenchmark>,
+) -> Result<BenchmarkRun> {
+    let iterations = iterations.max(1);
+    let queries = bench_queries(conn, project_path, iterations)?;
+    let total_chunks: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM chunks WHERE project_path = ?1 AND deleted_at IS NULL",
+        params![project_path],
+        |row| row.get(0),
+    )?;
+
+    let mut run = BenchmarkRun {
+        id: None,
+        project_path: project_path.to_string(),
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        iterations,
+        total_chunks: total_chunks as usize,
+        indexing,
+        queries,
+        regressions: Vec::new(),
+        created_at: Utc::now(),
+    };
+    if let Some(previous) = get_benchmark_runs(conn, project_path, 1)?.first() {
+        run.regressions = find_regressions(previous, &run);
+    }
+    for regression in &run.regressions {
+        log::warn!(
+            "Benchmark regression in {} for {}: {:.1}x slower than {}",
+            project_path,
+            regression.metric,
+            regression.ratio,
+            regression.previous_version
+        );
+    }
+    run.id = Some(insert_benchmark_run(conn, &run)?);
+    Ok(run)
+}
+
+/// Patrones de consulta: páginas del proyecto y por tipo, chunks de un archivo y de
+/// una entidad, paginación por cursor, chunk con sus relaciones y el conteo por tipo.
+/// Los que necesitan un chunk de muestra se omiten si el proyecto no tiene chunks
+pub fn bench_queries(
+    conn: &Connection,
+    project_path: &str,
+    iterations: usize,
+) -> Result<Vec<QueryBenchmark>> {
+    let project_query = ChunkQuery {
+        project_path: Some(project_path.to_string()),
+        limit: Some(BENCH_PAGE_SIZE),
+        ..Default::default()
+    };
+    let mut results = vec![
+        measure("project_page", iterations, || {
+            Ok(query_chunks(conn, &project_query)?.len())
+        })?,
+        measure("by_type", iterations, || {
+            let query = ChunkQuery {
+                chunk_types: Some(vec![ChunkType::RawSource]),
+                ..project_query.clone()
+            };
+            Ok(query_chunks(conn, &query)?.len())
+        })?,
+        measure("keyset_page", iterations, || {
+            let page = fetch_chunks_page(conn, &project_query, None, BENCH_PAGE_SIZE)?;
+            Ok(page.chunks.len())
+        })?,
+        measure("index_state", iterations, || {
+            Ok(get_index_state(conn, project_path)?.chunk_counts.len())
+        })?,
+    ];
+
+    // Muestra estable: el primer chunk con entidad (o con archivo) del proyecto
+    let sample: Option<(i64, String, Option<String>)> = conn
+        .query_row(
+            "SELECT id, file_path, entity_name FROM chunks
+             WHERE project_path = ?1 AND deleted_at IS NULL AND file_path IS NOT NULL
+             ORDER BY entity_name IS NULL, id LIMIT 1",
+            params![project_path],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    if let Some((chunk_id, file_path, entity_name)) = sample {
+        results.push(measure("by_file", iterations, || {
+            let query = ChunkQuery {
+                file_path: Some(file_path.clone()),
+                limit: None,
+                ..project_query.clone()
+            };
+            Ok(query_chunks(conn, &query)?.len())
+        })?);
+        if let Some(entity_name) = entity_name {
+            results.push(measure("by_entity", iterations, || {
+                let query = ChunkQuery {
+                    entity_name: Some(entity_name.clone()),
+                    limit: None,
+                    ..project_query.clone()
+                };
+                Ok(query_chunks(conn, &query)?.len())
+            })?);
+        }
+        results.push(measure("chunk_with_context", iterations, || {
+            Ok(get_chunk_with_context(conn, chunk_id)?
+                .map(|context| 1 + context.outgoing.len() + context.incoming.len())
+                .unwrap_or(0))
+        })?);
+    }
+    Ok(results)
+}
+
+/// Ejecuta la consulta `iterations` veces y resume sus latencias
+fn measure(
+    pattern: &str,
+    iterations: usize,
+    mut query: impl FnMut() -> Result<usize>,
+) -> Result<QueryBenchmark> {
+    let mut latencies = Vec::with_capacity(iterations);
+    let mut rows = 0;
+    for _ in 0..iterations {
+        let started = Instant::now();
+        rows = query()?;
+        latencies.push(started.elapsed().as_micros() as u64);
+    }
+    latencies.sort_unstable();
+    let percentile = |p: usize| latencies[(latencies.len() - 1) * p / 100];
+    Ok(QueryBenchmark {
+        pattern: pattern.to_string(),
+        iterations,
+        rows,
+        mean_us: latencies.iter().sum::<u64>() / latencies.len() as u64,
+        p50_us: percentile(50),
+        p95_us: percentile(95),
+        max_us: latencies[latencies.len() - 1],
+    })
+}
+
+/// Latencias p50 de consulta y throughput de indexación que empeoraron al menos
+/// [`REGRESSION_RATIO`] veces respecto a `previous`
+fn find_regressions(previous: &BenchmarkRun, current: &BenchmarkRun) -> Vec<BenchmarkRegression> {
+    let regression =
+        |metric: &str, previous_value: f64, current_value: f64, ratio: f64| BenchmarkRegression {
+            metric: metric.to_string(),
+            previous_version: previous.crate_version.clone(),
+            previous: previous_value,
+            current: current_value,
+            ratio,
+        };
+
+    let mut regressions = Vec::new();
+    for query in &current.queries {
+        let Some(before) = previous.queries.iter().find(|q| q.pattern == query.pattern) else {
+            continue;
+        };
+        let ratio = query.p50_us as f64 / before.p50_us.max(1) as f64;
+        if query.p50_us >= MIN_REGRESSION_US && ratio >= REGRESSION_RATIO {
+            regressions.push(regression(
+                &query.pattern,
+                before.p50_us as f64,
+                query.p50_us as f64,
+                ratio,
+            ));
+        }
+    }
+    if let (Some(before), Some(now)) = (&previous.indexing, &current.indexing) {
+        if now.chunks_per_second > 0.0 {
+            let ratio = before.chunks_per_second / now.chunks_per_second;
+            if ratio >= REGRESSION_RATIO {
+                regressions.push(regression(
+                    "indexing",
+                    before.chunks_per_second,
+                    now.chunks_per_second,
+                    ratio,
+                ));
+            }
+        }
+    }
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+
+    fn insert_chunk(conn: &Connection, file_path: &str, entity_name: Option<&str>) {
+        let content = format!("fn {}() {{}}", entity_name.unwrap_or("main"));
+        let builder = chunk().file(file_path).content(&content);
+        match entity_name {
+            Some(name) => builder.entity(name),
+            None => builder,
+        }
+        .insert(conn);
+    }
+
+    #[test]
+    fn test_benchmark_runs_are_stored_and_compared() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_chunk(&conn, "src/main.rs", None);
+        insert_chunk(&conn, "src/lib.rs", Some("parse"));
+
+        let first = record_benchmark(&conn, "/p", 3, None).unwrap();
+        assert_eq!(first.total_chunks, 2);
+        let patterns: Vec<_> = first.queries.iter().map(|q| q.pattern.as_str()).collect();
+        assert_eq!(
+            patterns,
+            vec![
+                "project_page",
+                "by_type",
+                "keyset_page",
+                "index_state",
+                "by_file",
+                "by_entity",
+                "chunk_with_context"
+            ]
+        );
+        let by_entity = first
+            .queries
+            .iter()
+            .find(|q| q.pattern == "by_entity")
+            .unwrap();
+        assert_eq!((by_entity.iterations, by_entity.rows), (3, 1));
+        assert!(first
+            .queries
+            .iter()
+            .all(|q| q.p50_us <= q.p95_us && q.p95_us <= q.max_us));
+
+        record_benchmark(&conn, "/p", 3, None).unwrap();
+        let runs = get_benchmark_runs(&conn, "/p", 10).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].queries, first.queries);
+
+        // Una consulta 3x más lenta y una indexación a la mitad de velocidad
+        let mut slower = first.clone();
+        slower.queries[0].p50_us = first.queries[0].p50_us.max(MIN_REGRESSION_US) * 3;
+        slower.indexing = Some(IndexingBenchmark {
+            duration_ms: 2000,
+            chunks: 100,
+            chunks_per_second: 50.0,
+        });
+        let baseline = BenchmarkRun {
+            indexing: Some(IndexingBenchmark {
+                duration_ms: 1000,
+                chunks: 100,
+                chunks_per_second: 100.0,
+            }),
+            ..first.clone()
+        };
+        let regressions = find_regressions(&baseline, &slower);
+        let metrics: Vec<_> = regressions.iter().map(|r| r.metric.as_str()).collect();
+        assert_eq!(metrics, vec!["project_page", "indexing"]);
+        assert!(regressions.iter().all(|r| r.ratio >= REGRESSION_RATIO));
+    }
+}
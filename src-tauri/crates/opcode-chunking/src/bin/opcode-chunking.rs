@@ -0,0 +1,98 @@
+use clap::{Parser, Subcommand};
+use opcode_chunking::graph_export::export_cypher;
+use opcode_chunking::storage::init_chunk_database;
+use opcode_chunking::watch::{self, WatchEvent};
+use rusqlite::Connection;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Identificador de la app Tauri: la base por defecto es la misma que usa la app
+const APP_IDENTIFIER: &str = "opcode.asterisk.so";
+
+#[derive(Parser)]
+#[command(name = "opcode-chunking")]
+#[command(about = "Opcode chunking CLI - keep project indexes warm")]
+struct Args {
+    /// Path to chunks.db (defaults to the app data dir)
+    #[arg(long, global = true)]
+    db: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Watch a project and reindex changed files, printing one JSON event per line
+    Watch {
+        /// Project root to watch
+        project_path: String,
+
+        /// Quiet period after the last change before reindexing
+        #[arg(long, default_value_t = watch::DEFAULT_DEBOUNCE_MS)]
+        debounce_ms: u64,
+    },
+    /// Print a Cypher script that loads the project graph into Neo4j
+    ExportCypher {
+        /// Indexed project root
+        project_path: String,
+
+        /// Include the content of each chunk as a node property
+        #[arg(long)]
+        include_content: bool,
+    },
+}
+
+fn default_db_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join("chunks.db"))
+}
+
+fn print_event(event: &WatchEvent) {
+    match serde_json::to_string(event) {
+        Ok(line) => println!("{}", line),
+        Err(e) => eprintln!("Failed to serialize event: {}", e),
+    }
+}
+
+fn open_database(db: Option<PathBuf>) -> anyhow::Result<Connection> {
+    let db_path = db
+        .or_else(default_db_path)
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the app data dir, pass --db"))?;
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(db_path)?;
+    init_chunk_database(&conn)?;
+    Ok(conn)
+}
+
+fn main() {
+    env_logger::init();
+
+    let args = Args::parse();
+
+    let result = open_database(args.db).and_then(|conn| match args.command {
+        Command::Watch {
+            project_path,
+            debounce_ms,
+        } => watch::watch_project(
+            &conn,
+            &project_path,
+            Duration::from_millis(debounce_ms),
+            print_event,
+        ),
+        Command::ExportCypher {
+            project_path,
+            include_content,
+        } => {
+            export_cypher(&conn, &project_path, include_content).map(|script| print!("{}", script))
+        }
+    });
+
+    if let Err(e) = result {
+        print_event(&WatchEvent::Error {
+            message: e.to_string(),
+        });
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,305 @@
+use super::dependency_graph::is_supported_source;
+use super::relationships::relationship;
+use super::storage::{delete_relationships_by_source, insert_relationship};
+use super::types::RelationshipType;
+use anyhow::Result;
+use regex::Regex;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Valor de `source` en la metadata de las relaciones entre lenguajes
+pub const BOUNDARY_SOURCE: &str = "ffi_boundary";
+
+/// Comando Tauri: el frontend lo llama por nombre (`invoke("nombre")`)
+pub const BOUNDARY_TAURI: &str = "tauri_command";
+/// Función exportada a Node con napi-rs (`#[napi]`, camelCase en JS)
+pub const BOUNDARY_NAPI: &str = "napi";
+/// Función exportada a JS con wasm-bindgen (`#[wasm_bindgen]`)
+pub const BOUNDARY_WASM: &str = "wasm";
+/// Símbolo C exportado (`#[no_mangle] extern "C"`), ej: exports de un módulo wasm
+pub const BOUNDARY_FFI: &str = "ffi";
+
+/// Función Rust expuesta a otro lenguaje
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundaryExport {
+    pub kind: &'static str,
+    pub js_name: String, // Nombre con el que se llama desde JS/TS
+    pub rust_fn: String,
+    pub line: usize,
+}
+
+/// Funciones de un archivo Rust expuestas a JS/TS como comando Tauri, binding N-API,
+/// export de wasm-bindgen o símbolo C
+pub fn boundary_exports(content: &str) -> Vec<BoundaryExport> {
+    let export_re = Regex::new(
+        r#"#\[(tauri::command|napi|wasm_bindgen|no_mangle)\b(\([^\]]*\))?\]\s*(?:#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?(?:unsafe\s+)?(?:extern\s+"C"\s+)?fn\s+(\w+)"#,
+    )
+    .unwrap();
+    let js_name_re = Regex::new(r#"js_name\s*=\s*"?(\w+)"?"#).unwrap();
+
+    export_re
+        .captures_iter(content)
+        .map(|cap| {
+            let rust_fn = cap[3].to_string();
+            let custom_name = cap
+                .get(2)
+                .and_then(|args| js_name_re.captures(args.as_str()))
+                .map(|c| c[1].to_string());
+            let (kind, js_name) = match &cap[1] {
+                "tauri::command" => (BOUNDARY_TAURI, rust_fn.clone()),
+                "napi" => (
+                    BOUNDARY_NAPI,
+                    custom_name.unwrap_or_else(|| snake_to_camel(&rust_fn)),
+                ),
+                "wasm_bindgen" => (
+                    BOUNDARY_WASM,
+                    custom_name.unwrap_or_else(|| rust_fn.clone()),
+                ),
+                _ => (BOUNDARY_FFI, rust_fn.clone()),
+            };
+            let start = cap.get(0).map_or(0, |m| m.start());
+            BoundaryExport {
+                kind,
+                js_name,
+                rust_fn,
+                line: content[..start].lines().count() + 1,
+            }
+        })
+        .collect()
+}
+
+/// Reconstruye las relaciones `calls` de los archivos JS/TS a los archivos Rust que
+/// implementan lo que llaman a través de la frontera entre lenguajes: comandos Tauri
+/// por nombre, y bindings N-API, wasm o FFI en archivos que cargan un módulo nativo.
+/// Los nombres exportados por más de un archivo Rust son ambiguos y no se enlazan
+pub fn rebuild_boundary_relationships(
+    conn: &Connection,
+    project_path: &str,
+    sources: &BTreeMap<String, (i64, String)>,
+) -> Result<usize> {
+    delete_relationships_by_source(conn, project_path, BOUNDARY_SOURCE)?;
+
+    // (tipo, nombre en JS) -> archivos Rust que lo exportan
+    let mut exports: BTreeMap<(&str, String), Vec<(&String, BoundaryExport)>> = BTreeMap::new();
+    for (file_path, (_, content)) in sources {
+        if !file_path.ends_with(".rs") {
+            continue;
+        }
+        for export in boundary_exports(content) {
+            exports
+                .entry((export.kind, export.js_name.clone()))
+                .or_default()
+                .push((file_path, export));
+        }
+    }
+    exports.retain(|_, files| files.iter().map(|(f, _)| f).collect::<BTreeSet<_>>().len() == 1);
+    if exports.is_empty() {
+        return Ok(0);
+    }
+
+    let name_arg_re = Regex::new(r#"\(\s*['"`]([A-Za-z_]\w*)['"`]"#).unwrap();
+    let call_re = Regex::new(r"\b([A-Za-z_]\w*)\s*\(").unwrap();
+
+    let mut created = 0;
+    for (file_path, (chunk_id, content)) in sources {
+        if file_path.ends_with(".rs") || !is_supported_source(file_path) {
+            continue;
+        }
+        let mut used: BTreeSet<(&str, String)> = name_arg_re
+            .captures_iter(content)
+            .map(|cap| (BOUNDARY_TAURI, cap[1].to_string()))
+            .collect();
+        if loads_native_module(content) {
+            for cap in call_re.captures_iter(content) {
+                for kind in [BOUNDARY_NAPI, BOUNDARY_WASM, BOUNDARY_FFI] {
+                    used.insert((kind, cap[1].to_string()));
+                }
+            }
+        }
+
+        // Archivo Rust destino -> (tipo, funciones)
+        let mut targets: BTreeMap<(&String, &str), Vec<&BoundaryExport>> = BTreeMap::new();
+        for key in &used {
+            if let Some([(rust_file, export)]) = exports.get(key).map(Vec::as_slice) {
+                targets
+                    .entry((*rust_file, export.kind))
+                    .or_default()
+                    .push(export);
+            }
+        }
+
+        for ((rust_file, kind), functions) in targets {
+            let Some((target_id, _)) = sources.get(rust_file) else {
+                continue;
+            };
+            let rel = relationship(
+                *chunk_id,
+                *target_id,
+                RelationshipType::Calls,
+                serde_json::json!({
+                    "source": BOUNDARY_SOURCE,
+                    "boundary": kind,
+                    "from_file": file_path,
+                    "to_file": rust_file,
+                    "functions": functions.iter().map(|e| &e.rust_fn).collect::<Vec<_>>(),
+                    "names": functions.iter().map(|e| &e.js_name).collect::<Vec<_>>(),
+                    "lines": functions.iter().map(|e| e.line).collect::<Vec<_>>(),
+                }),
+            );
+            insert_relationship(conn, &rel)?;
+            created += 1;
+        }
+    }
+    Ok(created)
+}
+
+/// Indica si un archivo JS/TS carga un addon nativo o un módulo wasm
+fn loads_native_module(content: &str) -> bool {
+    let specifier_re = Regex::new(r#"(?:require\(|import\(|from)\s*['"]([^'"]+)['"]"#).unwrap();
+    content.contains("WebAssembly.instantiate")
+        || specifier_re.captures_iter(content).any(|cap| {
+            let spec = &cap[1];
+            spec.ends_with(".node")
+                || spec.ends_with(".wasm")
+                || ["wasm", "napi", "native", "binding", "/pkg"]
+                    .iter()
+                    .any(|hint| spec.contains(hint))
+        })
+}
+
+fn snake_to_camel(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' && !camel.is_empty() {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+
+    #[test]
+    fn test_boundary_exports() {
+        let rust = r#"
+#[tauri::command]
+pub async fn get_user(id: u64) {}
+
+#[napi]
+pub fn parse_config(input: String) {}
+
+#[wasm_bindgen(js_name = renderFrame)]
+pub fn render(frame: u32) {}
+
+#[no_mangle]
+pub extern "C" fn add(a: i32, b: i32) -> i32 { a + b }
+
+pub fn internal() {}
+"#;
+        let exports: Vec<(&str, String, String, usize)> = boundary_exports(rust)
+            .into_iter()
+            .map(|e| (e.kind, e.js_name, e.rust_fn, e.line))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![
+                (BOUNDARY_TAURI, "get_user".into(), "get_user".into(), 2),
+                (
+                    BOUNDARY_NAPI,
+                    "parseConfig".into(),
+                    "parse_config".into(),
+                    5
+                ),
+                (BOUNDARY_WASM, "renderFrame".into(), "render".into(), 8),
+                (BOUNDARY_FFI, "add".into(), "add".into(), 11),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_links_frontend_calls_to_rust_handlers() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let sources: BTreeMap<String, (i64, String)> = [
+            (
+                "src-tauri/src/commands.rs",
+                1,
+                "#[tauri::command]\npub fn get_user() {}\n#[tauri::command]\nfn delete_user() {}\n",
+            ),
+            ("native/src/lib.rs", 2, "#[napi]\npub fn parse_config() {}\n"),
+            (
+                "src/api.ts",
+                3,
+                "const user = await invoke<User>(\"get_user\", { id });\napiCall(\"delete_user\");\nlog(\"missing_command\");\n",
+            ),
+            (
+                "src/config.js",
+                4,
+                "const native = require('./index.node');\nnative.parseConfig(text);\n",
+            ),
+            ("src/plain.js", 5, "parseConfig(text);\n"),
+        ]
+        .into_iter()
+        .map(|(file, id, content)| (file.to_string(), (id, content.to_string())))
+        .collect();
+        for (file, (id, content)) in &sources {
+            conn.execute(
+                "INSERT INTO chunks (id, project_path, chunk_type, file_path, content, content_hash, created_at, updated_at)
+                 VALUES (?1, '/p', 'raw_source', ?2, ?3, ?2, '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00')",
+                rusqlite::params![id, file, content],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            rebuild_boundary_relationships(&conn, "/p", &sources).unwrap(),
+            2
+        );
+        let mut stmt = conn
+            .prepare(
+                "SELECT from_chunk_id, to_chunk_id, json_extract(metadata, '$.boundary'),
+                        json_extract(metadata, '$.functions')
+                 FROM chunk_relationships WHERE relationship_type = 'calls' ORDER BY from_chunk_id",
+            )
+            .unwrap();
+        let edges: Vec<(i64, i64, String, String)> = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            edges,
+            vec![
+                (
+                    3,
+                    1,
+                    BOUNDARY_TAURI.to_string(),
+                    r#"["delete_user","get_user"]"#.to_string()
+                ),
+                (
+                    4,
+                    2,
+                    BOUNDARY_NAPI.to_string(),
+                    r#"["parse_config"]"#.to_string()
+                ),
+            ]
+        );
+
+        // Reconstruir no duplica
+        assert_eq!(
+            rebuild_boundary_relationships(&conn, "/p", &sources).unwrap(),
+            2
+        );
+    }
+}
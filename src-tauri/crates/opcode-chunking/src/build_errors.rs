@@ -0,0 +1,244 @@
+use super::errors::{log_diagnostic, resolve_errors_by_type_prefix};
+use super::lint::{
+    enclosing_entity, file_content, indexed_files, parse_clippy_json, resolve_project_file,
+    LintDiagnostic,
+};
+use super::storage::{
+    calculate_content_hash, get_chunk_id_by_hash, get_latest_file_chunk_id, insert_relationship,
+    upsert_chunk,
+};
+use super::types::{BuildImportResult, Chunk, ChunkRelationship, ChunkType, RelationshipType};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::Connection;
+use std::collections::{BTreeSet, HashMap};
+
+/// Origen de las relaciones `associated_with_error` creadas por este módulo
+pub const RELATIONSHIP_SOURCE: &str = "build_errors";
+
+/// Parsea la salida de `tsc --pretty false`:
+/// `src/app.ts(12,5): error TS2322: Type 'string' is not assignable...`
+/// Las líneas indentadas que siguen continúan el mensaje anterior
+pub fn parse_tsc_output(output: &str) -> Vec<LintDiagnostic> {
+    let line_re =
+        Regex::new(r"^(.+?)\((\d+),(\d+)\):\s+(error|warning)\s+(TS\d+):\s*(.*)$").unwrap();
+    let mut diagnostics: Vec<LintDiagnostic> = Vec::new();
+    let mut continuing = false;
+
+    for line in output.lines() {
+        if let Some(cap) = line_re.captures(line) {
+            diagnostics.push(LintDiagnostic {
+                file_path: cap[1].trim().to_string(),
+                line: cap[2].parse().unwrap_or(0),
+                column: cap[3].parse().unwrap_or(0),
+                rule: cap[5].to_string(),
+                severity: cap[4].to_string(),
+                message: cap[6].to_string(),
+            });
+            continuing = true;
+        } else if continuing && line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+            if let Some(last) = diagnostics.last_mut() {
+                last.message.push('\n');
+                last.message.push_str(line.trim());
+            }
+        } else {
+            continuing = false;
+        }
+    }
+
+    diagnostics
+}
+
+/// Importa los errores de compilación de `cargo build --message-format=json` ("cargo")
+/// o `tsc --pretty false` ("tsc"). Cada error se registra en error_logs, genera un chunk
+/// `error_log` y se enlaza con `associated_with_error` desde los chunks del archivo.
+/// Los errores abiertos de la importación anterior de la misma herramienta se marcan resueltos
+pub fn import_build_output(
+    conn: &Connection,
+    project_path: &str,
+    tool: &str,
+    output: &str,
+) -> Result<BuildImportResult> {
+    let diagnostics: Vec<LintDiagnostic> = match tool {
+        "cargo" => parse_clippy_json(output),
+        "tsc" => parse_tsc_output(output),
+        other => return Err(anyhow!("Unsupported build tool: {}", other)),
+    }
+    .into_iter()
+    .filter(|d| d.severity == "error")
+    .collect();
+
+    resolve_errors_by_type_prefix(conn, project_path, &format!("build:{}:", tool))?;
+    delete_build_relationships(conn, project_path, tool)?;
+
+    let known_files = indexed_files(conn, project_path)?;
+    let mut contents: HashMap<String, Option<String>> = HashMap::new();
+    let mut unmatched = BTreeSet::new();
+    let mut result = BuildImportResult {
+        tool: tool.to_string(),
+        errors_imported: 0,
+        relationships_created: 0,
+        unmatched_files: Vec::new(),
+    };
+
+    for diagnostic in &diagnostics {
+        let Some(file_path) =
+            resolve_project_file(project_path, &known_files, &diagnostic.file_path)
+        else {
+            unmatched.insert(diagnostic.file_path.clone());
+            continue;
+        };
+
+        let content = contents
+            .entry(file_path.clone())
+            .or_insert_with(|| file_content(conn, project_path, &file_path));
+        let entity = content
+            .as_deref()
+            .and_then(|c| enclosing_entity(c, diagnostic.line));
+
+        let error_type = format!("build:{}:{}", tool, diagnostic.rule);
+        let location = format!("{}:{}:{}", file_path, diagnostic.line, diagnostic.column);
+        let message = format!("{}: {}", location, diagnostic.message);
+        let error_id = log_diagnostic(
+            conn,
+            project_path,
+            &error_type,
+            "error",
+            &message,
+            Some(&file_path),
+            entity.as_deref(),
+        )?;
+        result.errors_imported += 1;
+
+        // Chunk del error para poder navegarlo desde el grafo
+        let chunk_content = format!("[{}] {}", error_type, message);
+        let content_hash = calculate_content_hash(&format!("{}\n{}", project_path, chunk_content));
+        let metadata = serde_json::json!({
+            "source": RELATIONSHIP_SOURCE,
+            "tool": tool,
+            "error_log_id": error_id,
+            "error_type": error_type,
+            "line": diagnostic.line,
+            "column": diagnostic.column,
+        });
+        upsert_chunk(
+            conn,
+            &Chunk {
+                id: None,
+                project_path: project_path.to_string(),
+                chunk_type: ChunkType::ErrorLog,
+                file_path: Some(file_path.clone()),
+                entity_name: entity.clone(),
+                content: chunk_content,
+                content_hash: content_hash.clone(),
+                metadata: Some(metadata.to_string()),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            None,
+        )?;
+        let Some(error_chunk_id) = get_chunk_id_by_hash(conn, project_path, &content_hash)? else {
+            continue;
+        };
+
+        // Chunks del archivo que contiene el error
+        for chunk_type in [ChunkType::RawSource, ChunkType::Ast] {
+            let Some(from_id) =
+                get_latest_file_chunk_id(conn, project_path, &file_path, &chunk_type)?
+            else {
+                continue;
+            };
+            insert_relationship(
+                conn,
+                &ChunkRelationship {
+                    id: None,
+                    from_chunk_id: from_id,
+                    to_chunk_id: error_chunk_id,
+                    relationship_type: RelationshipType::AssociatedWithError,
+                    metadata: Some(
+                        serde_json::json!({
+                            "source": RELATIONSHIP_SOURCE,
+                            "tool": tool,
+                            "line": diagnostic.line,
+                        })
+                        .to_string(),
+                    ),
+                    created_at: Utc::now(),
+                },
+            )?;
+            result.relationships_created += 1;
+        }
+    }
+
+    result.unmatched_files = unmatched.into_iter().collect();
+    Ok(result)
+}
+
+/// Elimina las relaciones de la importación anterior de una herramienta
+fn delete_build_relationships(conn: &Connection, project_path: &str, tool: &str) -> Result<usize> {
+    let count = conn.execute(
+        "DELETE FROM chunk_relationships
+         WHERE json_extract(metadata, '$.source') = ?1 AND json_extract(metadata, '$.tool') = ?2
+           AND to_chunk_id IN (SELECT id FROM chunks WHERE project_path = ?3)",
+        rusqlite::params![RELATIONSHIP_SOURCE, tool, project_path],
+    )?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{get_error_logs, init_chunk_database};
+
+    #[test]
+    fn test_parse_tsc_output() {
+        let output = "src/app.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.\n  Types of property 'id' are incompatible.\nsrc/util.ts(3,1): warning TS6133: 'x' is declared but never read.\nFound 2 errors.\n";
+        let diagnostics = parse_tsc_output(output);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].file_path, "src/app.ts");
+        assert_eq!(diagnostics[0].line, 12);
+        assert_eq!(diagnostics[0].rule, "TS2322");
+        assert!(diagnostics[0]
+            .message
+            .ends_with("Types of property 'id' are incompatible."));
+        assert_eq!(diagnostics[1].severity, "warning");
+    }
+
+    #[test]
+    fn test_import_cargo_errors_links_chunks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let project = "/work/app";
+        conn.execute(
+            "INSERT INTO chunks (project_path, chunk_type, file_path, content, content_hash, created_at, updated_at)
+             VALUES (?1, 'raw_source', 'src-tauri/src/main.rs', 'fn main() {\n    let x: u32 = \"a\";\n}\n', 'h1', '', '')",
+            rusqlite::params![project],
+        )
+        .unwrap();
+
+        let output = r#"{"reason":"compiler-message","message":{"level":"error","message":"mismatched types","code":{"code":"E0308"},"spans":[{"file_name":"src/main.rs","line_start":2,"column_start":18,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"level":"warning","message":"unused variable","code":{"code":"unused_variables"},"spans":[{"file_name":"src/main.rs","line_start":2,"column_start":9,"is_primary":true}]}}
+{"reason":"build-finished","success":false}"#;
+
+        let result = import_build_output(&conn, project, "cargo", output).unwrap();
+        assert_eq!(result.errors_imported, 1);
+        assert_eq!(result.relationships_created, 1);
+
+        let logs = get_error_logs(&conn, project, false).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].file_path.as_deref(), Some("src-tauri/src/main.rs"));
+        assert_eq!(logs[0].entity_name.as_deref(), Some("main"));
+
+        // Reimportar no duplica relaciones
+        import_build_output(&conn, project, "cargo", output).unwrap();
+        let links: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM chunk_relationships WHERE relationship_type = 'associated_with_error'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(links, 1);
+    }
+}
@@ -0,0 +1,103 @@
+use super::storage::{
+    get_business_rules, parse_business_rule_row, set_business_rule_tags, upsert_business_rule,
+    BUSINESS_RULE_COLUMNS,
+};
+use super::types::{BusinessRule, RulePinning};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::Connection;
+
+/// Etiqueta de las reglas no negociables que el modo `critical` fija en el contexto
+pub const CRITICAL_RULE_TAG: &str = "critical";
+
+/// Crea una regla de negocio propuesta (pendiente de validación)
+pub fn propose_business_rule(
+    conn: &Connection,
+    project_path: &str,
+    entity_name: &str,
+    file_path: &str,
+    ai_interpretation: &str,
+) -> Result<i64> {
+    let rule = BusinessRule {
+        id: None,
+        project_path: project_path.to_string(),
+        entity_name: entity_name.to_string(),
+        file_path: file_path.to_string(),
+        rule_description: String::new(), // Se llenará con la validación del usuario
+        ai_interpretation: ai_interpretation.to_string(),
+        user_correction: None,
+        is_validated: false,
+        validation_date: None,
+        tags: Vec::new(),
+        check: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    upsert_business_rule(conn, &rule)
+}
+
+/// Valida una regla de negocio con corrección del usuario
+pub fn validate_business_rule(
+    conn: &Connection,
+    rule_id: i64,
+    rule_description: &str,
+    user_correction: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE business_rules SET rule_description = ?1, user_correction = ?2, is_validated = 1, validation_date = ?3, updated_at = ?4 WHERE id = ?5",
+        rusqlite::params![
+            rule_description,
+            user_correction,
+            Utc::now().to_rfc3339(),
+            Utc::now().to_rfc3339(),
+            rule_id
+        ],
+    )?;
+    Ok(())
+}
+
+/// Obtiene reglas de negocio pendientes de validación
+pub fn get_pending_rules(conn: &Connection, project_path: &str) -> Result<Vec<BusinessRule>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM business_rules WHERE project_path = ?1 AND is_validated = 0 ORDER BY created_at",
+        BUSINESS_RULE_COLUMNS
+    ))?;
+
+    let rules = stmt
+        .query_map(rusqlite::params![project_path], parse_business_rule_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(rules)
+}
+
+/// Normaliza y guarda las etiquetas de una regla (minúsculas, sin vacías ni repetidas)
+pub fn tag_business_rule(conn: &Connection, rule_id: i64, tags: &[String]) -> Result<()> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    set_business_rule_tags(conn, rule_id, &normalized)
+}
+
+/// Reglas que el modo de fijado inyecta al inicio de cada paquete de contexto.
+/// Solo entran reglas validadas: las propuestas aún no tienen descripción confirmada
+pub fn pinned_rules(
+    conn: &Connection,
+    project_path: &str,
+    mode: RulePinning,
+) -> Result<Vec<BusinessRule>> {
+    if mode == RulePinning::None {
+        return Ok(Vec::new());
+    }
+    Ok(get_business_rules(conn, project_path)?
+        .into_iter()
+        .filter(|rule| rule.is_validated)
+        .filter(|rule| {
+            mode == RulePinning::Validated || rule.tags.iter().any(|t| t == CRITICAL_RULE_TAG)
+        })
+        .collect())
+}
@@ -0,0 +1,627 @@
+use super::ast::parse_cached;
+use super::entry_points::detect_entry_points;
+use super::resolver::ImportResolver;
+use super::storage::{calculate_content_hash, upsert_chunk};
+use super::types::{
+    CallgraphMetadata, Chunk, ChunkType, ClassifiedDependency, DependencyKind, DependencyUsage,
+};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::Connection;
+use std::collections::{BTreeSet, HashSet};
+use tree_sitter::Node;
+
+/// Receptores más largos (en caracteres) no se guardan en la llamada
+const MAX_RECEIVER_CHARS: usize = 40;
+
+/// Llamada a una función o método: `receiver` es el objeto, valor o ruta sobre el que
+/// se llama (`invoice` en `invoice.sum()`, `Invoice` en `Invoice::new()`)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FunctionCall {
+    pub name: String,
+    pub receiver: Option<String>,
+    /// Separador entre receptor y nombre en el código: `.`, `::` o `->`
+    pub separator: &'static str,
+}
+
+impl std::fmt::Display for FunctionCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.receiver {
+            Some(receiver) => write!(f, "{}{}{}", receiver, self.separator, self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Genera chunks de callgraph estático: imports/requires y llamadas extraídas del AST
+pub fn generate_callgraph_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+    resolver: &ImportResolver,
+) -> Result<usize> {
+    let language = detect_language_by_extension(file_path);
+
+    // Extraer imports/requires según el lenguaje
+    let mut dependencies = extract_dependencies(content, &language);
+    dependencies.sort();
+    let function_calls = extract_calls(project_path, file_path, content);
+    let entry_points = detect_entry_points(
+        file_path,
+        content,
+        &language,
+        resolver.bin_target(file_path),
+    );
+
+    // Clasificar cada import: interno, workspace o externo
+    let classified: Vec<ClassifiedDependency> = dependencies
+        .iter()
+        .map(|dep| resolver.classify(file_path, dep))
+        .collect();
+
+    // Crear metadata
+    let metadata = CallgraphMetadata {
+        is_static: true,
+        entry_points: entry_points.clone(),
+        external_calls: classified
+            .iter()
+            .filter(|d| d.kind == DependencyKind::External)
+            .map(|d| d.specifier.clone())
+            .collect(),
+        call_count: function_calls.len(),
+        dependencies: classified.clone(),
+    };
+
+    // Serializar el callgraph
+    let mut callgraph_repr = String::new();
+    callgraph_repr.push_str(&format!("# Dependencies ({})\n", dependencies.len()));
+    for dep in &classified {
+        match &dep.resolved_file {
+            Some(file) => callgraph_repr.push_str(&format!(
+                "import: {} [{}: {}]\n",
+                dep.specifier,
+                dep.kind.as_str(),
+                file
+            )),
+            None => callgraph_repr.push_str(&format!(
+                "import: {} [{}]\n",
+                dep.specifier,
+                dep.kind.as_str()
+            )),
+        }
+    }
+
+    if !entry_points.is_empty() {
+        callgraph_repr.push_str(&format!("\n# Entry Points ({})\n", entry_points.len()));
+        for entry in &entry_points {
+            callgraph_repr.push_str(&format!("entry: {}\n", entry));
+        }
+    }
+
+    callgraph_repr.push_str(&format!("\n# Function Calls ({})\n", function_calls.len()));
+    for call in &function_calls {
+        callgraph_repr.push_str(&format!("call: {}\n", call));
+    }
+
+    let content_hash = calculate_content_hash(&callgraph_repr);
+
+    let chunk = Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::Callgraph,
+        file_path: Some(file_path.to_string()),
+        entity_name: None,
+        content: callgraph_repr,
+        content_hash,
+        metadata: Some(serde_json::to_string(&metadata)?),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    upsert_chunk(conn, &chunk, None)?;
+    Ok(1)
+}
+
+/// Extrae los imports de un archivo detectando el lenguaje por su extensión
+pub fn extract_file_dependencies(file_path: &str, content: &str) -> Vec<String> {
+    let language = detect_language_by_extension(file_path);
+    let mut deps = extract_dependencies(content, &language);
+    deps.sort();
+    deps
+}
+
+/// Busca los archivos que usan un paquete y/o tipo de dependencia
+/// (ej: todos los usos externos de `reqwest`)
+pub fn find_dependency_usages(
+    conn: &Connection,
+    project_path: &str,
+    package: Option<&str>,
+    kind: Option<&DependencyKind>,
+) -> Result<Vec<DependencyUsage>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.file_path, json_extract(d.value, '$.specifier'), json_extract(d.value, '$.kind'),
+                json_extract(d.value, '$.package')
+         FROM chunks c, json_each(c.metadata, '$.dependencies') d
+         WHERE c.project_path = ?1 AND c.chunk_type = 'callgraph' AND c.file_path IS NOT NULL
+           AND c.deleted_at IS NULL
+           AND (?2 IS NULL OR json_extract(d.value, '$.package') = ?2)
+           AND (?3 IS NULL OR json_extract(d.value, '$.kind') = ?3)
+         GROUP BY c.file_path, json_extract(d.value, '$.specifier')
+         ORDER BY c.file_path",
+    )?;
+
+    let usages = stmt
+        .query_map(
+            rusqlite::params![project_path, package, kind.map(|k| k.as_str())],
+            |row| {
+                let kind_str: String = row.get(2)?;
+                Ok(DependencyUsage {
+                    file_path: row.get(0)?,
+                    specifier: row.get(1)?,
+                    kind: match kind_str.as_str() {
+                        "internal" => DependencyKind::Internal,
+                        "workspace" => DependencyKind::Workspace,
+                        _ => DependencyKind::External,
+                    },
+                    package: row.get(3)?,
+                })
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(usages)
+}
+
+/// Detecta el lenguaje por extensión de archivo
+pub(crate) fn detect_language_by_extension(file_path: &str) -> String {
+    if file_path.ends_with(".rs") {
+        "rust".to_string()
+    } else if file_path.ends_with(".js") || file_path.ends_with(".jsx") {
+        "javascript".to_string()
+    } else if file_path.ends_with(".ts") || file_path.ends_with(".tsx") {
+        "typescript".to_string()
+    } else if file_path.ends_with(".py") {
+        "python".to_string()
+    } else if file_path.ends_with(".go") {
+        "go".to_string()
+    } else if file_path.ends_with(".java") {
+        "java".to_string()
+    } else if file_path.ends_with(".cs") {
+        "csharp".to_string()
+    } else if file_path.ends_with(".rb") {
+        "ruby".to_string()
+    } else if file_path.ends_with(".php") {
+        "php".to_string()
+    } else {
+        "unknown".to_string()
+    }
+}
+
+/// Extrae dependencias (imports) del código
+fn extract_dependencies(content: &str, language: &str) -> Vec<String> {
+    let mut deps = HashSet::new();
+
+    match language {
+        "rust" => {
+            // use statements
+            let re = Regex::new(r"use\s+([a-zA-Z0-9_:]+)").unwrap();
+            for cap in re.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+        }
+        "javascript" | "typescript" => {
+            // import statements
+            let re_import = Regex::new(r#"import\s+.*?from\s+['"]([^'"]+)['"]"#).unwrap();
+            for cap in re_import.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+
+            // require statements
+            let re_require = Regex::new(r#"require\(['"]([^'"]+)['"]\)"#).unwrap();
+            for cap in re_require.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+        }
+        "python" => {
+            // import statements
+            let re_import = Regex::new(r"import\s+([a-zA-Z0-9_.]+)").unwrap();
+            for cap in re_import.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+
+            // from import
+            let re_from = Regex::new(r"from\s+([a-zA-Z0-9_.]+)\s+import").unwrap();
+            for cap in re_from.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+        }
+        "go" => {
+            // import "fmt" / import alias "github.com/x/y"
+            let re_single = Regex::new(r#"import\s+(?:[\w.]+\s+)?"([^"]+)""#).unwrap();
+            for cap in re_single.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+
+            // import ( ... ) en bloque
+            let re_block = Regex::new(r"import\s*\(([^)]*)\)").unwrap();
+            let re_path = Regex::new(r#""([^"]+)""#).unwrap();
+            for block in re_block.captures_iter(content) {
+                for cap in re_path.captures_iter(&block[1]) {
+                    deps.insert(cap[1].to_string());
+                }
+            }
+        }
+        "java" => {
+            // import [static] com.example.Foo; / import com.example.*;
+            let re = Regex::new(r"(?m)^\s*import\s+(?:static\s+)?([\w.]+(?:\.\*)?)\s*;").unwrap();
+            for cap in re.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+        }
+        "csharp" => {
+            // using System.Text; / using static X; / using Alias = X.Y; (no `using (var ...)`)
+            let re = Regex::new(
+                r"(?m)^\s*(?:global\s+)?using\s+(?:static\s+)?(?:\w+\s*=\s*)?([\w.]+)\s*;",
+            )
+            .unwrap();
+            for cap in re.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+        }
+        "ruby" => {
+            // require 'json' / require_relative '../lib/x'
+            let re = Regex::new(r#"require(?:_relative)?\s*\(?\s*['"]([^'"]+)['"]"#).unwrap();
+            for cap in re.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+        }
+        "php" => {
+            // use App\Models\User; / use function Foo\bar;
+            let re_use = Regex::new(r"(?m)^\s*use\s+(?:function\s+|const\s+)?([\w\\]+)").unwrap();
+            for cap in re_use.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+
+            // require/include(_once) 'file.php'
+            let re_require =
+                Regex::new(r#"(?:require|include)(?:_once)?\s*\(?\s*['"]([^'"]+)['"]"#).unwrap();
+            for cap in re_require.captures_iter(content) {
+                if let Some(dep) = cap.get(1) {
+                    deps.insert(dep.as_str().to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+
+    deps.into_iter().collect()
+}
+
+/// Llamadas reales del árbol (call expressions, invocaciones de métodos y `new`, y las
+/// que aparecen dentro de macros de Rust), sin repetir y ordenadas por nombre.
+/// Keywords, macros y strings no son llamadas
+pub(crate) fn extract_function_calls(root: Node, content: &str) -> Vec<FunctionCall> {
+    let calls: BTreeSet<FunctionCall> = calls_with_lines(root, content)
+        .into_iter()
+        .map(|(_, call)| call)
+        .collect();
+    calls.into_iter().collect()
+}
+
+/// Cada llamada del árbol con su línea (1-based), en orden de aparición
+fn calls_with_lines(root: Node, content: &str) -> Vec<(usize, FunctionCall)> {
+    let mut calls = Vec::new();
+    let mut cursor = root.walk();
+    loop {
+        collect_calls(cursor.node(), content, &mut calls);
+        if cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return calls;
+            }
+        }
+    }
+}
+
+/// Llamadas de un archivo sobre su árbol tree-sitter (el de la generación de chunks
+/// AST si el contenido no cambió). Sin gramática para el archivo, o si no se puede
+/// parsear, no hay llamadas
+pub fn extract_calls(project_path: &str, file_path: &str, content: &str) -> Vec<FunctionCall> {
+    match parse_cached(project_path, file_path, content) {
+        Ok(tree) => extract_function_calls(tree.root_node(), content),
+        Err(e) => {
+            log::debug!("No calls extracted from {}: {}", file_path, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Como [`extract_calls`], pero con la línea (1-based) de cada llamada, para
+/// atribuirla a la entidad que la contiene. Las repetidas aparecen una vez por línea
+pub fn extract_calls_by_line(
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+) -> Vec<(usize, FunctionCall)> {
+    match parse_cached(project_path, file_path, content) {
+        Ok(tree) => {
+            let calls: BTreeSet<(usize, FunctionCall)> =
+                calls_with_lines(tree.root_node(), content)
+                    .into_iter()
+                    .collect();
+            calls.into_iter().collect()
+        }
+        Err(e) => {
+            log::debug!("No calls extracted from {}: {}", file_path, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Llamada (o llamadas, en un token tree de macro) que representa el nodo
+fn collect_calls(node: Node, content: &str, calls: &mut Vec<(usize, FunctionCall)>) {
+    let field = |name: &str| node.child_by_field_name(name);
+    let call = match node.kind() {
+        // Rust, JS/TS y Go: `f()`, `valor.f()`, `Tipo::f()`, `pkg.F()`
+        "call_expression" => field("function").and_then(|f| callee(f, content)),
+        "call" => match field("function") {
+            // Python: `f()`, `obj.f()`
+            Some(function) => callee(function, content),
+            // Ruby: `f(x)`, `obj.f`, `Clase.new`
+            None => field("method").and_then(|m| method_call(m, field("receiver"), ".", content)),
+        },
+        // Java: `f()`, `obj.f()`
+        "method_invocation" => {
+            field("name").and_then(|n| method_call(n, field("object"), ".", content))
+        }
+        // C#: `F()`, `obj.F()`
+        "invocation_expression" => field("function").and_then(|f| callee(f, content)),
+        // PHP: `f()`, `$obj->f()`, `Clase::f()`
+        "function_call_expression" => field("function").and_then(|f| callee(f, content)),
+        "member_call_expression" | "nullsafe_member_call_expression" => {
+            field("name").and_then(|n| method_call(n, field("object"), "->", content))
+        }
+        "scoped_call_expression" => {
+            field("name").and_then(|n| method_call(n, field("scope"), "::", content))
+        }
+        // Constructores: `new Cart()` (JS/TS), `new User()` (Java, C#, PHP)
+        "new_expression" => field("constructor").and_then(|c| callee(c, content)),
+        "object_creation_expression" => field("type")
+            .or_else(|| node.named_child(0))
+            .and_then(|t| callee(t, content)),
+        // Rust: los argumentos de una macro (`assert!(f(x))`) no se parsean como
+        // expresiones, solo como tokens: `nombre (...)` es una llamada
+        "token_tree" => {
+            macro_calls(node, content, calls);
+            None
+        }
+        _ => None,
+    };
+    if let Some(call) = call {
+        calls.push((node.start_position().row + 1, call));
+    }
+}
+
+/// Función llamada según la forma del callee: nombre suelto, `receptor.método`,
+/// `Ruta::función` o con genéricos (`f::<T>()`, `F<T>()`, `new List<T>()`)
+fn callee(node: Node, content: &str) -> Option<FunctionCall> {
+    let field = |name: &str| node.child_by_field_name(name);
+    match node.kind() {
+        "identifier" | "type_identifier" | "name" => method_call(node, None, ".", content),
+        // Rust `Tipo::f`
+        "scoped_identifier" => method_call(field("name")?, field("path"), "::", content),
+        // PHP `\App\render`
+        "qualified_name" => method_call(
+            node.named_child(node.named_child_count().checked_sub(1)?)?,
+            None,
+            ".",
+            content,
+        ),
+        // Rust `valor.f`
+        "field_expression" => method_call(field("field")?, field("value"), ".", content),
+        // JS/TS `obj.f`
+        "member_expression" => method_call(field("property")?, field("object"), ".", content),
+        // Python `obj.f`
+        "attribute" => method_call(field("attribute")?, field("object"), ".", content),
+        // Go `pkg.F`
+        "selector_expression" => method_call(field("field")?, field("operand"), ".", content),
+        // C# `obj.F`
+        "member_access_expression" => {
+            method_call(field("name")?, field("expression"), ".", content)
+        }
+        "generic_function" => callee(field("function")?, content),
+        "generic_name" | "generic_type" => callee(node.named_child(0)?, content),
+        _ => None,
+    }
+}
+
+/// Llamada a `name` con su receptor, si es un identificador válido
+fn method_call(
+    name: Node,
+    receiver: Option<Node>,
+    separator: &'static str,
+    content: &str,
+) -> Option<FunctionCall> {
+    let name = match name.kind() {
+        "generic_name" => name.named_child(0)?,
+        _ => name,
+    };
+    let name = &content[name.byte_range()];
+    if !is_identifier(name) {
+        return None;
+    }
+    Some(FunctionCall {
+        name: name.to_string(),
+        receiver: receiver.and_then(|r| receiver_text(&content[r.byte_range()])),
+        separator,
+    })
+}
+
+/// Llamadas directas de un token tree de macro: un identificador seguido de un
+/// token tree entre paréntesis, con su receptor si va precedido de `x.` o `Tipo::`
+fn macro_calls(node: Node, content: &str, calls: &mut Vec<(usize, FunctionCall)>) {
+    let mut cursor = node.walk();
+    let tokens: Vec<Node> = node.children(&mut cursor).collect();
+    for (idx, token) in tokens.iter().enumerate() {
+        let Some(next) = tokens.get(idx + 1) else {
+            continue;
+        };
+        if token.kind() != "identifier"
+            || next.kind() != "token_tree"
+            || !content[next.byte_range()].starts_with('(')
+        {
+            continue;
+        }
+        let (receiver, separator) = match idx.checked_sub(2).map(|i| (tokens[i], tokens[i + 1])) {
+            Some((receiver, separator))
+                if matches!(receiver.kind(), "identifier" | "self")
+                    && matches!(separator.kind(), "." | "::") =>
+            {
+                (
+                    Some(receiver),
+                    if separator.kind() == "." { "." } else { "::" },
+                )
+            }
+            _ => (None, "."),
+        };
+        if let Some(call) = method_call(*token, receiver, separator, content) {
+            calls.push((token.start_position().row + 1, call));
+        }
+    }
+}
+
+/// Receptor tal como está en el código, en una línea; los muy largos (cadenas de
+/// llamadas, closures) no aportan y se omiten
+fn receiver_text(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty() && text.chars().count() <= MAX_RECEIVER_CHARS).then_some(text)
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_dependencies_rust() {
+        let code = "use std::collections::HashMap;\nuse serde::Serialize;";
+        let deps = extract_dependencies(code, "rust");
+        assert!(deps.contains(&"std::collections::HashMap".to_string()));
+        assert!(deps.contains(&"serde::Serialize".to_string()));
+    }
+
+    #[test]
+    fn test_extract_dependencies_js() {
+        let code = r#"import React from 'react';\nconst fs = require('fs');"#;
+        let deps = extract_dependencies(code, "javascript");
+        assert!(deps.contains(&"react".to_string()));
+        assert!(deps.contains(&"fs".to_string()));
+    }
+
+    #[test]
+    fn test_extract_dependencies_other_languages() {
+        let go = "package main\n\nimport \"fmt\"\nimport (\n\t\"net/http\"\n\tlog \"github.com/sirupsen/logrus\"\n)\n";
+        let mut deps = extract_dependencies(go, "go");
+        deps.sort();
+        assert_eq!(deps, vec!["fmt", "github.com/sirupsen/logrus", "net/http"]);
+
+        let java = "package app;\nimport java.util.List;\nimport static org.junit.Assert.*;\n";
+        let mut deps = extract_dependencies(java, "java");
+        deps.sort();
+        assert_eq!(deps, vec!["java.util.List", "org.junit.Assert.*"]);
+
+        let csharp = "using System.Text;\nusing Json = Newtonsoft.Json;\nclass A { void F() { using (var s = Open()) {} } }\n";
+        let mut deps = extract_dependencies(csharp, "csharp");
+        deps.sort();
+        assert_eq!(deps, vec!["Newtonsoft.Json", "System.Text"]);
+
+        let ruby = "require 'json'\nrequire_relative '../lib/user'\n";
+        let mut deps = extract_dependencies(ruby, "ruby");
+        deps.sort();
+        assert_eq!(deps, vec!["../lib/user", "json"]);
+
+        let php = "<?php\nuse App\\Models\\User;\nrequire_once 'config.php';\n";
+        let mut deps = extract_dependencies(php, "php");
+        deps.sort();
+        assert_eq!(deps, vec!["App\\Models\\User", "config.php"]);
+        assert_eq!(detect_language_by_extension("src/Models/User.php"), "php");
+    }
+
+    fn calls(file_path: &str, content: &str) -> Vec<String> {
+        extract_calls("/p", file_path, content)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_function_calls() {
+        let js = "// if (x) render()\nconsole.log('call(me)');\nconst result = calculate(10);\nif (ok) { new Cart(); }\n";
+        assert_eq!(
+            calls("src/app.js", js),
+            vec!["Cart", "calculate", "console.log"]
+        );
+
+        // Las macros no son llamadas, pero las llamadas dentro de sus argumentos sí
+        let rust = "fn total(items: &[u32]) -> u32 {\n    let invoice = Invoice::new(items);\n    println!(\"{}\", format_total(invoice.sum()));\n    if items.is_empty() { return 0; }\n    self.cache.get(1)\n}\n";
+        assert_eq!(
+            calls("src/lib.rs", rust),
+            vec![
+                "format_total",
+                "self.cache.get",
+                "items.is_empty",
+                "Invoice::new",
+                "invoice.sum"
+            ]
+        );
+
+        let python = "def load(repo):\n    while repo.ready():\n        print(f\"{fetch()}\")\n    return User(name='x')\n";
+        assert_eq!(
+            calls("app/load.py", python),
+            vec!["User", "fetch", "print", "repo.ready"]
+        );
+
+        let go = "package main\n\nfunc main() {\n\tfmt.Println(run())\n}\n";
+        assert_eq!(calls("main.go", go), vec!["fmt.Println", "run"]);
+
+        let php = "<?php\n$user = new User();\n$user->save();\nLog::info(render());\n";
+        assert_eq!(
+            calls("page.php", php),
+            vec!["User", "Log::info", "render", "$user->save"]
+        );
+
+        assert!(calls("notes.txt", "call(me)").is_empty());
+    }
+}
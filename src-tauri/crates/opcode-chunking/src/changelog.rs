@@ -0,0 +1,340 @@
+use super::commits::classify_commit_message;
+use super::storage::{get_snapshot, get_snapshots, CHUNK_CONTENT};
+use super::types::{CommitMetadata, Snapshot};
+use anyhow::{Context, Result};
+use git2::{Oid, Repository};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Secciones del changelog en orden de aparición: (tipo de commit, título)
+const SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Fixes"),
+    ("refactor", "Refactors"),
+    ("perf", "Performance"),
+    ("docs", "Documentation"),
+];
+
+/// Commit incluido en un changelog
+#[derive(Debug, Clone)]
+pub struct ChangelogEntry {
+    pub commit_hash: String,
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking_change: bool,
+    pub description: String,
+}
+
+/// Genera un changelog Markdown con los commits y snapshots entre dos snapshots
+/// (excluye `from_snapshot_id`, incluye `to_snapshot_id`)
+pub fn generate_changelog(
+    conn: &Connection,
+    from_snapshot_id: i64,
+    to_snapshot_id: i64,
+) -> Result<String> {
+    let from = get_snapshot(conn, from_snapshot_id)?
+        .with_context(|| format!("Snapshot {} not found", from_snapshot_id))?;
+    let to = get_snapshot(conn, to_snapshot_id)?
+        .with_context(|| format!("Snapshot {} not found", to_snapshot_id))?;
+    if from.project_path != to.project_path {
+        anyhow::bail!("Snapshots belong to different projects");
+    }
+
+    let entries = match commits_between_git(&from, &to) {
+        Some(hashes) => hashes
+            .iter()
+            .map(|hash| entry_for_commit(conn, &from.project_path, hash))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect(),
+        None => entries_between_dates(conn, &from, &to)?,
+    };
+
+    let snapshots = snapshots_between(conn, &from, &to)?;
+    Ok(render_changelog(&from, &to, &entries, &snapshots))
+}
+
+/// Hashes de los commits alcanzables desde `to` pero no desde `from` (más recientes primero).
+/// None si algún snapshot no tiene commit o el repositorio no es accesible
+fn commits_between_git(from: &Snapshot, to: &Snapshot) -> Option<Vec<String>> {
+    let from_oid = Oid::from_str(from.git_commit_hash.as_deref()?).ok()?;
+    let to_oid = Oid::from_str(to.git_commit_hash.as_deref()?).ok()?;
+    let repo = Repository::open(&to.project_path).ok()?;
+
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk.push(to_oid).ok()?;
+    revwalk.hide(from_oid).ok()?;
+    revwalk.set_sorting(git2::Sort::TIME).ok()?;
+
+    Some(
+        revwalk
+            .filter_map(|oid| oid.ok())
+            .map(|oid| oid.to_string())
+            .collect(),
+    )
+}
+
+/// Entrada de changelog de un commit, usando el chunk de commit si está indexado
+/// y el mensaje de Git en caso contrario
+fn entry_for_commit(
+    conn: &Connection,
+    project_path: &str,
+    commit_hash: &str,
+) -> Result<Option<ChangelogEntry>> {
+    let chunk: Option<(Option<String>, String)> = conn
+        .query_row(
+            &format!(
+                "SELECT metadata, {} FROM chunks
+                 WHERE project_path = ?1 AND chunk_type = 'commit_history' AND entity_name = ?2
+                 ORDER BY updated_at DESC LIMIT 1",
+                CHUNK_CONTENT
+            ),
+            params![project_path, commit_hash],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    let message = match &chunk {
+        Some((_, content)) => message_from_chunk_content(content),
+        None => Repository::open(project_path)
+            .ok()
+            .and_then(|repo| {
+                let commit = repo.find_commit(Oid::from_str(commit_hash).ok()?).ok()?;
+                commit.message().map(|m| m.to_string())
+            })
+            .unwrap_or_default(),
+    };
+
+    let metadata = chunk
+        .and_then(|(metadata, _)| metadata)
+        .and_then(|m| serde_json::from_str::<CommitMetadata>(&m).ok());
+    Ok(build_entry(commit_hash, &message, metadata.as_ref()))
+}
+
+/// Commits indexados cuya fecha cae entre los dos snapshots (sin acceso a Git)
+fn entries_between_dates(
+    conn: &Connection,
+    from: &Snapshot,
+    to: &Snapshot,
+) -> Result<Vec<ChangelogEntry>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT metadata, {} FROM chunks
+         WHERE project_path = ?1 AND chunk_type = 'commit_history' AND metadata IS NOT NULL",
+        CHUNK_CONTENT
+    ))?;
+    let rows = stmt
+        .query_map(params![&from.project_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut commits: Vec<(CommitMetadata, String)> = rows
+        .into_iter()
+        .filter_map(|(metadata, content)| {
+            let metadata: CommitMetadata = serde_json::from_str(&metadata).ok()?;
+            (metadata.commit_date > from.created_at && metadata.commit_date <= to.created_at)
+                .then(|| (metadata, message_from_chunk_content(&content)))
+        })
+        .collect();
+    commits.sort_by_key(|(metadata, _)| std::cmp::Reverse(metadata.commit_date));
+
+    Ok(commits
+        .iter()
+        .filter_map(|(metadata, message)| {
+            build_entry(&metadata.commit_hash, message, Some(metadata))
+        })
+        .collect())
+}
+
+/// Snapshots creados después de `from` y hasta `to` (inclusive), en orden cronológico
+fn snapshots_between(conn: &Connection, from: &Snapshot, to: &Snapshot) -> Result<Vec<Snapshot>> {
+    let mut snapshots: Vec<Snapshot> = get_snapshots(conn, &from.project_path, None)?
+        .into_iter()
+        .filter(|s| s.created_at > from.created_at && s.created_at <= to.created_at)
+        .collect();
+    snapshots.sort_by_key(|s| (s.created_at, s.id));
+    Ok(snapshots)
+}
+
+/// Mensaje de commit a partir del contenido del chunk (sección "Message:")
+fn message_from_chunk_content(content: &str) -> String {
+    content
+        .split_once("Message:\n")
+        .map(|(_, rest)| rest.split("\nFiles Modified").next().unwrap_or(rest))
+        .unwrap_or("")
+        .trim()
+        .to_string()
+}
+
+/// Construye la entrada de un commit. Los merges no aparecen en el changelog
+fn build_entry(
+    commit_hash: &str,
+    message: &str,
+    metadata: Option<&CommitMetadata>,
+) -> Option<ChangelogEntry> {
+    let classification = classify_commit_message(message);
+    let commit_type = metadata
+        .and_then(|m| m.commit_type.clone())
+        .unwrap_or(classification.commit_type);
+    if commit_type == "merge" {
+        return None;
+    }
+
+    let subject = message.lines().next().unwrap_or("").trim();
+    let description = if classification.conventional {
+        subject
+            .split_once(':')
+            .map(|(_, d)| d.trim())
+            .unwrap_or(subject)
+    } else {
+        subject
+    };
+
+    Some(ChangelogEntry {
+        commit_hash: commit_hash.to_string(),
+        commit_type,
+        scope: metadata
+            .and_then(|m| m.scope.clone())
+            .or(classification.scope),
+        breaking_change: metadata.map(|m| m.breaking_change).unwrap_or(false)
+            || classification.breaking_change,
+        description: description.to_string(),
+    })
+}
+
+/// Renderiza el changelog en Markdown agrupado por tipo de commit
+pub fn render_changelog(
+    from: &Snapshot,
+    to: &Snapshot,
+    entries: &[ChangelogEntry],
+    snapshots: &[Snapshot],
+) -> String {
+    let mut md = format!(
+        "## Changelog {} → {}\n",
+        version_label(from),
+        version_label(to)
+    );
+
+    let breaking: Vec<&ChangelogEntry> = entries.iter().filter(|e| e.breaking_change).collect();
+    push_section(&mut md, "⚠ Breaking Changes", &breaking);
+
+    for (commit_type, title) in SECTIONS {
+        let section: Vec<&ChangelogEntry> = entries
+            .iter()
+            .filter(|e| e.commit_type == *commit_type)
+            .collect();
+        push_section(&mut md, title, &section);
+    }
+
+    let other: Vec<&ChangelogEntry> = entries
+        .iter()
+        .filter(|e| !SECTIONS.iter().any(|(t, _)| e.commit_type == *t))
+        .collect();
+    push_section(&mut md, "Other Changes", &other);
+
+    if !snapshots.is_empty() {
+        md.push_str("\n### Snapshots\n");
+        for snapshot in snapshots {
+            let message = snapshot
+                .user_message
+                .as_deref()
+                .unwrap_or(&snapshot.message);
+            md.push_str(&format!(
+                "- {}: {}\n",
+                version_label(snapshot),
+                message.lines().next().unwrap_or("").trim()
+            ));
+        }
+    }
+
+    if entries.is_empty() && snapshots.is_empty() {
+        md.push_str("\n_No changes._\n");
+    }
+
+    md
+}
+
+fn push_section(md: &mut String, title: &str, entries: &[&ChangelogEntry]) {
+    if entries.is_empty() {
+        return;
+    }
+    md.push_str(&format!("\n### {}\n", title));
+    for entry in entries {
+        let scope = entry
+            .scope
+            .as_ref()
+            .map(|s| format!("**{}:** ", s))
+            .unwrap_or_default();
+        let short_hash: String = entry.commit_hash.chars().take(7).collect();
+        md.push_str(&format!(
+            "- {}{} ({})\n",
+            scope, entry.description, short_hash
+        ));
+    }
+}
+
+/// Etiqueta de versión de un snapshot (V2 o V2.1)
+fn version_label(snapshot: &Snapshot) -> String {
+    match snapshot.version_minor {
+        Some(minor) => format!("V{}.{}", snapshot.version_major, minor),
+        None => format!("V{}", snapshot.version_major),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SnapshotType;
+    use chrono::Utc;
+
+    fn snapshot(version: i32, user_message: &str) -> Snapshot {
+        Snapshot {
+            id: Some(version as i64),
+            project_path: "/p".to_string(),
+            snapshot_type: SnapshotType::Master,
+            parent_snapshot_id: None,
+            message: format!("V{}", version),
+            user_message: Some(user_message.to_string()),
+            changed_files: "[]".to_string(),
+            diff_summary: None,
+            metadata: None,
+            git_commit_hash: None,
+            git_tag: None,
+            git_branch: None,
+            version_major: version,
+            version_minor: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_render_grouped_changelog() {
+        let entries: Vec<ChangelogEntry> = [
+            ("aaaaaaa1", "feat(auth): add OAuth login"),
+            ("bbbbbbb2", "fix: handle empty token"),
+            ("ccccccc3", "refactor(api)!: drop v1 endpoints"),
+            ("ddddddd4", "Merge branch 'dev'"),
+            ("eeeeeee5", "Bump deps"),
+        ]
+        .iter()
+        .filter_map(|(hash, message)| build_entry(hash, message, None))
+        .collect();
+        assert_eq!(entries.len(), 4);
+
+        let md = render_changelog(
+            &snapshot(1, "inicio"),
+            &snapshot(3, "fin"),
+            &entries,
+            &[snapshot(2, "agregar login"), snapshot(3, "quitar v1")],
+        );
+
+        assert!(md.starts_with("## Changelog V1 → V3\n"));
+        assert!(md.contains("### ⚠ Breaking Changes\n- **api:** drop v1 endpoints (ccccccc)"));
+        assert!(md.contains("### Features\n- **auth:** add OAuth login (aaaaaaa)"));
+        assert!(md.contains("### Fixes\n- handle empty token (bbbbbbb)"));
+        assert!(md.contains("### Refactors\n- **api:** drop v1 endpoints"));
+        assert!(md.contains("### Other Changes\n- Bump deps (eeeeeee)"));
+        assert!(md.contains("### Snapshots\n- V2: agregar login\n- V3: quitar v1\n"));
+        assert!(!md.contains("Merge"));
+    }
+}
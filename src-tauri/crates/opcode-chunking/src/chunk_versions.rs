@@ -0,0 +1,110 @@
+use super::snapshots::{truncate_patch, MAX_FILE_PATCH_BYTES};
+use super::storage::{get_chunk_by_id, get_chunk_versions};
+use super::types::{ChunkVersion, ChunkVersionDiff};
+use anyhow::{anyhow, Context, Result};
+use rusqlite::Connection;
+use std::path::Path;
+
+/// Diff unificado del contenido (raw source, AST...) entre las versiones `from` y `to`
+/// de un chunk. Las versiones se numeran desde 1 como en [`get_chunk_versions`]
+pub fn diff_chunk_versions(
+    conn: &Connection,
+    chunk_id: i64,
+    from: usize,
+    to: usize,
+) -> Result<ChunkVersionDiff> {
+    let versions = get_chunk_versions(conn, chunk_id)?;
+    if versions.is_empty() {
+        return Err(anyhow!("Chunk not found: {}", chunk_id));
+    }
+    let find = |number: usize| -> Result<ChunkVersion> {
+        versions
+            .iter()
+            .find(|v| v.version == number)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Chunk {} has no version {} (versions 1..={})",
+                    chunk_id,
+                    number,
+                    versions.len()
+                )
+            })
+    };
+    let (from, to) = (find(from)?, find(to)?);
+
+    let old = get_chunk_by_id(conn, from.chunk_id)?.context("Chunk version disappeared")?;
+    let new = get_chunk_by_id(conn, to.chunk_id)?.context("Chunk version disappeared")?;
+
+    let (additions, deletions, patch, truncated) = if old.content == new.content {
+        (0, 0, String::new(), false)
+    } else {
+        // La ruta solo da nombre a las cabeceras del diff
+        let label = old
+            .file_path
+            .clone()
+            .or_else(|| old.entity_name.clone())
+            .unwrap_or_else(|| old.chunk_type.as_str().to_string());
+        let path = Path::new(&label);
+        let mut patch = git2::Patch::from_buffers(
+            old.content.as_bytes(),
+            Some(path),
+            new.content.as_bytes(),
+            Some(path),
+            None,
+        )?;
+        let (_, additions, deletions) = patch.line_stats()?;
+        let text = String::from_utf8_lossy(&patch.to_buf()?).to_string();
+        let (text, truncated) = truncate_patch(text, MAX_FILE_PATCH_BYTES);
+        (additions, deletions, text, truncated)
+    };
+
+    Ok(ChunkVersionDiff {
+        chunk_type: new.chunk_type,
+        file_path: new.file_path,
+        entity_name: new.entity_name,
+        from,
+        to,
+        additions,
+        deletions,
+        patch,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+
+    fn index_version(conn: &Connection, content: &str) -> i64 {
+        chunk().file("src/lib.rs").content(content).insert(conn)
+    }
+
+    #[test]
+    fn test_diff_between_chunk_versions() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let first = index_version(&conn, "fn a() {}\nfn b() {}\n");
+        index_version(&conn, "fn a() {}\nfn b() { todo!() }\nfn c() {}\n");
+        let latest = index_version(&conn, "fn c() {}\n");
+
+        let versions = get_chunk_versions(&conn, first).unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[2].chunk_id, latest);
+
+        let diff = diff_chunk_versions(&conn, latest, 1, 2).unwrap();
+        assert_eq!(diff.file_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!((diff.additions, diff.deletions), (2, 1));
+        assert!(diff.patch.contains("--- a/src/lib.rs"));
+        assert!(diff.patch.contains("-fn b() {}"));
+        assert!(diff.patch.contains("+fn b() { todo!() }"));
+
+        assert!(diff_chunk_versions(&conn, first, 1, 1)
+            .unwrap()
+            .patch
+            .is_empty());
+        assert!(diff_chunk_versions(&conn, first, 1, 4).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use super::project_config::normalize_extension;
+use anyhow::{bail, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+
+/// Agrega una extensión a las que el proyecto indexa como código fuente
+pub fn add_code_extension(conn: &Connection, project_path: &str, extension: &str) -> Result<()> {
+    set_override(conn, project_path, extension, true)
+}
+
+/// Quita una extensión de las que el proyecto indexa, aunque sea de las por defecto
+pub fn remove_code_extension(conn: &Connection, project_path: &str, extension: &str) -> Result<()> {
+    set_override(conn, project_path, extension, false)
+}
+
+/// Extensiones efectivas del proyecto: las de las opciones (app + `opcode.toml`)
+/// con las agregadas y quitadas desde la app encima, ordenadas
+pub fn resolve_code_extensions(
+    conn: &Connection,
+    project_path: &str,
+    base: &[String],
+) -> Result<Vec<String>> {
+    let mut extensions: Vec<String> = base.iter().map(|ext| normalize_extension(ext)).collect();
+
+    let mut stmt = conn.prepare(
+        "SELECT extension, enabled FROM code_extension_overrides WHERE project_path = ?1",
+    )?;
+    let overrides = stmt
+        .query_map(params![project_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (extension, enabled) in overrides {
+        if enabled {
+            extensions.push(extension);
+        } else {
+            extensions.retain(|ext| *ext != extension);
+        }
+    }
+
+    extensions.sort();
+    extensions.dedup();
+    Ok(extensions)
+}
+
+fn set_override(
+    conn: &Connection,
+    project_path: &str,
+    extension: &str,
+    enabled: bool,
+) -> Result<()> {
+    let extension = normalize_extension(extension);
+    if extension.is_empty()
+        || extension
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '.' | '/' | '\\' | '*'))
+    {
+        bail!("Invalid file extension: '{}'", extension);
+    }
+    conn.execute(
+        "INSERT INTO code_extension_overrides (project_path, extension, enabled, updated_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(project_path, extension) DO UPDATE SET
+             enabled = excluded.enabled, updated_at = excluded.updated_at",
+        params![project_path, extension, enabled, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::types::default_code_extensions;
+
+    #[test]
+    fn test_overrides_add_and_remove_extensions() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let defaults = default_code_extensions();
+
+        add_code_extension(&conn, "/p", ".ZIG").unwrap();
+        add_code_extension(&conn, "/p", "ex").unwrap();
+        remove_code_extension(&conn, "/p", "json").unwrap();
+        assert!(add_code_extension(&conn, "/p", "").is_err());
+        assert!(add_code_extension(&conn, "/p", "tar.gz").is_err());
+
+        let extensions = resolve_code_extensions(&conn, "/p", &defaults).unwrap();
+        assert!(extensions.contains(&"zig".to_string()));
+        assert!(extensions.contains(&"ex".to_string()));
+        assert!(extensions.contains(&"rs".to_string()));
+        assert!(!extensions.contains(&"json".to_string()));
+
+        // Quitar una extensión agregada la desactiva; los demás proyectos no cambian
+        remove_code_extension(&conn, "/p", "zig").unwrap();
+        let extensions = resolve_code_extensions(&conn, "/p", &defaults).unwrap();
+        assert!(!extensions.contains(&"zig".to_string()));
+        let other = resolve_code_extensions(&conn, "/other", &defaults).unwrap();
+        assert!(other.contains(&"json".to_string()) && !other.contains(&"ex".to_string()));
+    }
+}
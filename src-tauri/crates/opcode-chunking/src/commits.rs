@@ -0,0 +1,430 @@
+use super::storage::{calculate_content_hash, upsert_chunk};
+use super::types::{Chunk, ChunkType, CommitMetadata};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use git2::{BranchType, FetchOptions, Oid, Repository, Time};
+use regex::Regex;
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+/// Tipos reconocidos de conventional commits
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Clasificación de un mensaje de commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitClassification {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking_change: bool,
+    pub conventional: bool,
+}
+
+/// Resultado de indexar el historial de commits
+#[derive(Debug, Clone, Default)]
+pub struct CommitChunkingStats {
+    pub chunks_created: usize,
+    /// El repositorio es un clon superficial y el recorrido llegó al límite del historial
+    pub shallow_boundary_reached: bool,
+}
+
+/// Genera chunks de commit history.
+/// Con `all_branches` recorre todas las ramas locales; el historial compartido se indexa una vez.
+/// En clones superficiales el recorrido se detiene limpiamente en el límite (`.git/shallow`)
+pub fn generate_commit_chunks(
+    conn: &Connection,
+    project_path: &str,
+    max_commits: Option<usize>,
+    all_branches: bool,
+) -> Result<CommitChunkingStats> {
+    let repo = Repository::open(project_path).context("Failed to open git repository")?;
+    let branch_tips = local_branch_tips(&repo)?;
+    let shallow_boundaries = shallow_boundaries(&repo);
+    let mut shallow_boundary_reached = false;
+
+    let mut revwalk = repo.revwalk()?;
+    if all_branches {
+        for (_, tip) in &branch_tips {
+            revwalk.push(*tip)?;
+        }
+    } else {
+        revwalk.push_head()?;
+    }
+    revwalk.set_sorting(git2::Sort::TIME)?;
+
+    let mut chunks_created = 0;
+    let limit = max_commits.unwrap_or(100);
+
+    for (idx, oid) in revwalk.enumerate() {
+        if idx >= limit {
+            break;
+        }
+
+        let oid = match oid {
+            Ok(oid) => oid,
+            // Sin objetos más allá del límite superficial: fin del historial disponible
+            Err(_) if repo.is_shallow() => {
+                shallow_boundary_reached = true;
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let commit = match repo.find_commit(oid) {
+            Ok(commit) => commit,
+            Err(_) if repo.is_shallow() => {
+                shallow_boundary_reached = true;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let is_boundary = shallow_boundaries.contains(&oid);
+        if is_boundary {
+            shallow_boundary_reached = true;
+        }
+
+        let message = commit.message().unwrap_or("").to_string();
+        let author = commit.author();
+        let time = commit.time();
+
+        // Obtener archivos modificados
+        let mut files_modified = Vec::new();
+        let tree = commit.tree()?;
+
+        // En el límite superficial el padre no existe localmente: no hay diff disponible
+        if commit.parent_count() > 0 && !is_boundary {
+            let parent = commit.parent(0)?;
+            let parent_tree = parent.tree()?;
+            let diff = repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)?;
+
+            diff.foreach(
+                &mut |delta, _| {
+                    if let Some(path) = delta.new_file().path() {
+                        files_modified.push(path.to_string_lossy().to_string());
+                    }
+                    true
+                },
+                None,
+                None,
+                None,
+            )?;
+        }
+
+        // Crear representación del commit
+        let mut commit_repr = String::new();
+        commit_repr.push_str(&format!("Commit: {}\n", oid));
+        commit_repr.push_str(&format!("Author: {} <{}>\n", author.name().unwrap_or(""), author.email().unwrap_or("")));
+        commit_repr.push_str(&format!("Date: {}\n\n", time_to_datetime(time)));
+        commit_repr.push_str(&format!("Message:\n{}\n\n", message));
+        commit_repr.push_str(&format!("Files Modified ({}):\n", files_modified.len()));
+        for file in &files_modified {
+            commit_repr.push_str(&format!("  - {}\n", file));
+        }
+
+        let content_hash = calculate_content_hash(&commit_repr);
+        let classification = classify_commit_message(&message);
+
+        let metadata = CommitMetadata {
+            commit_hash: oid.to_string(),
+            author: author.name().unwrap_or("").to_string(),
+            author_email: author.email().unwrap_or("").to_string(),
+            commit_date: time_to_datetime(time),
+            files_modified: files_modified.clone(),
+            insertions: 0, // Podríamos calcular esto con diff stats
+            deletions: 0,
+            commit_type: Some(classification.commit_type),
+            scope: classification.scope,
+            breaking_change: classification.breaking_change,
+            conventional: classification.conventional,
+            branches: branches_containing(&repo, &branch_tips, oid),
+        };
+
+        let chunk = Chunk {
+            id: None,
+            project_path: project_path.to_string(),
+            chunk_type: ChunkType::CommitHistory,
+            file_path: None,
+            entity_name: Some(oid.to_string()),
+            content: commit_repr,
+            content_hash,
+            metadata: Some(serde_json::to_string(&metadata)?),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        upsert_chunk(conn, &chunk, None)?;
+        chunks_created += 1;
+    }
+
+    Ok(CommitChunkingStats {
+        chunks_created,
+        shallow_boundary_reached,
+    })
+}
+
+/// Commits en el límite de un clon superficial (listados en `.git/shallow`)
+fn shallow_boundaries(repo: &Repository) -> HashSet<Oid> {
+    if !repo.is_shallow() {
+        return HashSet::new();
+    }
+    std::fs::read_to_string(repo.path().join("shallow"))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| Oid::from_str(line.trim()).ok())
+        .collect()
+}
+
+/// Profundiza un clon superficial desde `origin`. `depth` = None trae el historial completo.
+/// Retorna si el repositorio sigue siendo superficial
+pub fn deepen_commit_history(project_path: &str, depth: Option<i32>) -> Result<bool> {
+    let repo = Repository::open(project_path).context("Failed to open git repository")?;
+    if !repo.is_shallow() {
+        return Ok(false);
+    }
+
+    let mut remote = repo
+        .find_remote("origin")
+        .context("Shallow repository has no 'origin' remote")?;
+    let mut fetch_options = FetchOptions::new();
+    // libgit2 interpreta depth <= 0 como historial completo
+    fetch_options.depth(depth.unwrap_or(0));
+    remote
+        .fetch::<&str>(&[], Some(&mut fetch_options), None)
+        .context("Failed to fetch deeper history")?;
+
+    Ok(repo.is_shallow())
+}
+
+/// Ramas locales y el commit al que apunta cada una
+fn local_branch_tips(repo: &Repository) -> Result<Vec<(String, Oid)>> {
+    let mut tips = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        if let (Some(name), Some(oid)) = (branch.name()?, branch.get().target()) {
+            tips.push((name.to_string(), oid));
+        }
+    }
+    Ok(tips)
+}
+
+/// Ramas cuyo historial contiene el commit
+fn branches_containing(repo: &Repository, branch_tips: &[(String, Oid)], oid: Oid) -> Vec<String> {
+    branch_tips
+        .iter()
+        .filter(|(_, tip)| *tip == oid || repo.graph_descendant_of(*tip, oid).unwrap_or(false))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Convierte git2::Time a DateTime<Utc>
+fn time_to_datetime(time: Time) -> DateTime<Utc> {
+    DateTime::from_timestamp(time.seconds(), 0).unwrap_or_else(Utc::now)
+}
+
+/// Clasifica un mensaje de commit: formato conventional (`tipo(scope)!: descripción`)
+/// o, para mensajes libres, un tipo inferido de la primera palabra
+pub fn classify_commit_message(message: &str) -> CommitClassification {
+    let subject = message.lines().next().unwrap_or("").trim();
+    let breaking_footer = message.lines().any(|line| {
+        let line = line.trim_start();
+        line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+    });
+
+    let header_re = Regex::new(r"^([A-Za-z]+)(?:\(([^)]*)\))?(!)?:\s*\S").unwrap();
+    if let Some(cap) = header_re.captures(subject) {
+        let commit_type = normalize_commit_type(&cap[1].to_lowercase());
+        if CONVENTIONAL_TYPES.contains(&commit_type.as_str()) {
+            return CommitClassification {
+                commit_type,
+                scope: cap
+                    .get(2)
+                    .map(|m| m.as_str().trim().to_string())
+                    .filter(|s| !s.is_empty()),
+                breaking_change: cap.get(3).is_some() || breaking_footer,
+                conventional: true,
+            };
+        }
+    }
+
+    CommitClassification {
+        commit_type: infer_commit_type(subject),
+        scope: None,
+        breaking_change: breaking_footer,
+        conventional: false,
+    }
+}
+
+/// Alias frecuentes de tipos conventional
+fn normalize_commit_type(commit_type: &str) -> String {
+    match commit_type {
+        "feature" | "features" => "feat",
+        "bugfix" | "hotfix" | "bug" => "fix",
+        "doc" => "docs",
+        "tests" => "test",
+        "performance" => "perf",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Tipo inferido de un mensaje libre ("Fix crash on...", "Add support for...")
+fn infer_commit_type(subject: &str) -> String {
+    let lower = subject.to_lowercase();
+    if lower.starts_with("merge ") {
+        return "merge".to_string();
+    }
+    if lower.starts_with("revert") {
+        return "revert".to_string();
+    }
+
+    let first_word = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|w| !w.is_empty())
+        .unwrap_or("");
+    let commit_type = match first_word {
+        "fix" | "fixed" | "fixes" | "fixing" | "bugfix" | "hotfix" | "resolve" | "resolved"
+        | "resolves" | "correct" | "patch" => "fix",
+        "add" | "added" | "adds" | "adding" | "implement" | "implemented" | "implements"
+        | "introduce" | "introduced" | "support" | "create" | "created" | "new" | "feat"
+        | "feature" => "feat",
+        "refactor" | "refactored" | "rename" | "renamed" | "move" | "moved" | "extract"
+        | "cleanup" | "clean" | "simplify" | "restructure" => "refactor",
+        "doc" | "docs" | "document" | "documented" | "readme" => "docs",
+        "test" | "tests" | "testing" => "test",
+        "perf" | "optimize" | "optimized" | "speed" => "perf",
+        "bump" | "upgrade" | "upgraded" | "chore" => "chore",
+        "ci" => "ci",
+        "build" => "build",
+        "style" | "format" | "formatted" | "lint" => "style",
+        _ if lower.contains("bug") || lower.contains("crash") => "fix",
+        _ => "other",
+    };
+    commit_type.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{init_chunk_database, query_chunks};
+    use crate::types::ChunkQuery;
+    use git2::Signature;
+    use std::path::Path;
+
+    fn commit_file(repo: &Repository, name: &str, message: &str) -> Oid {
+        let workdir = repo.workdir().unwrap().to_path_buf();
+        std::fs::write(workdir.join(name), message).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_commit_chunks_stop_at_shallow_boundary() {
+        let dir = std::env::temp_dir().join(format!("opcode-shallow-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        commit_file(&repo, "a.txt", "feat: first");
+        let second = commit_file(&repo, "a.txt", "fix: second");
+        commit_file(&repo, "a.txt", "fix: third");
+        // Simula un clon `--depth 2`: el segundo commit es el límite
+        std::fs::write(repo.path().join("shallow"), format!("{}\n", second)).unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let stats = generate_commit_chunks(&conn, dir.to_str().unwrap(), None, false).unwrap();
+        assert!(stats.shallow_boundary_reached);
+        assert_eq!(stats.chunks_created, 2);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_commit_chunks_from_all_branches() {
+        let dir = std::env::temp_dir().join(format!("opcode-commits-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        let base = commit_file(&repo, "a.txt", "feat: base");
+        let main_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        repo.branch("agent/v1.1", &repo.find_commit(base).unwrap(), false)
+            .unwrap();
+        repo.set_head("refs/heads/agent/v1.1").unwrap();
+        commit_file(&repo, "b.txt", "fix: agent change");
+        repo.set_head(&format!("refs/heads/{}", main_branch)).unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let project = dir.to_str().unwrap();
+
+        assert_eq!(
+            generate_commit_chunks(&conn, project, None, false).unwrap().chunks_created,
+            1
+        );
+        let stats = generate_commit_chunks(&conn, project, None, true).unwrap();
+        assert_eq!(stats.chunks_created, 2);
+        assert!(!stats.shallow_boundary_reached);
+
+        let query = |branch: &str| ChunkQuery {
+            project_path: Some(project.to_string()),
+            chunk_types: None,
+            file_path: None,
+            entity_name: None,
+            dependency_kind: None,
+            dependency_package: None,
+            commit_type: None,
+            breaking_change: None,
+            touches_path: None,
+            branch: Some(branch.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_chunks(&conn, &query("agent/v1.1")).unwrap().len(), 2);
+        let on_main = query_chunks(&conn, &query(&main_branch)).unwrap();
+        assert_eq!(on_main.len(), 1);
+        assert_eq!(on_main[0].entity_name.as_deref(), Some(base.to_string().as_str()));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+
+    #[test]
+    fn test_classify_conventional_commits() {
+        let c = classify_commit_message("feat(auth): add OAuth login\n\nDetalles");
+        assert_eq!(c.commit_type, "feat");
+        assert_eq!(c.scope.as_deref(), Some("auth"));
+        assert!(!c.breaking_change);
+        assert!(c.conventional);
+
+        let c = classify_commit_message("refactor(api)!: drop v1 endpoints");
+        assert_eq!(c.commit_type, "refactor");
+        assert!(c.breaking_change);
+
+        let c = classify_commit_message("Fix: handle empty input\n\nBREAKING CHANGE: input is required");
+        assert_eq!(c.commit_type, "fix");
+        assert_eq!(c.scope, None);
+        assert!(c.breaking_change);
+        assert!(c.conventional);
+    }
+
+    #[test]
+    fn test_classify_freeform_commits() {
+        let c = classify_commit_message("Fixed crash when opening settings");
+        assert_eq!(c.commit_type, "fix");
+        assert!(!c.conventional);
+
+        assert_eq!(classify_commit_message("Add dark mode toggle").commit_type, "feat");
+        assert_eq!(classify_commit_message("Merge branch 'main' into dev").commit_type, "merge");
+        assert_eq!(classify_commit_message("WIP: stuff").commit_type, "other");
+        assert_eq!(classify_commit_message("").commit_type, "other");
+    }
+}
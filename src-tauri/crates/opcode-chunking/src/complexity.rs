@@ -0,0 +1,96 @@
+use super::types::{AstMetadata, ChunkType, ComplexityReport, FileComplexity};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+
+/// Archivos del proyecto ordenados de mayor a menor complejidad ciclomática, según la
+/// metadata del chunk AST más reciente de cada archivo
+pub fn get_complexity_report(
+    conn: &Connection,
+    project_path: &str,
+    limit: Option<usize>,
+) -> Result<ComplexityReport> {
+    let mut stmt = conn.prepare(
+        "SELECT file_path, metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND file_path IS NOT NULL
+           AND entity_name IS NULL AND metadata IS NOT NULL AND deleted_at IS NULL
+         ORDER BY updated_at DESC, id DESC",
+    )?;
+    let rows = stmt
+        .query_map(params![project_path, ChunkType::Ast.as_str()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut seen = HashSet::new();
+    let mut files: Vec<FileComplexity> = rows
+        .into_iter()
+        .filter(|(file_path, _)| seen.insert(file_path.clone()))
+        .filter_map(|(file_path, metadata)| {
+            let metadata: AstMetadata = serde_json::from_str(&metadata).ok()?;
+            Some(FileComplexity {
+                file_path,
+                language: metadata.language,
+                cyclomatic_complexity: metadata.cyclomatic_complexity,
+                function_count: metadata.function_count,
+                longest_function_lines: metadata.longest_function_lines,
+                node_count: metadata.node_count,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| {
+        b.cyclomatic_complexity
+            .cmp(&a.cyclomatic_complexity)
+            .then(b.longest_function_lines.cmp(&a.longest_function_lines))
+            .then(a.file_path.cmp(&b.file_path))
+    });
+    let total_files = files.len();
+    if let Some(limit) = limit {
+        files.truncate(limit);
+    }
+
+    Ok(ComplexityReport {
+        project_path: project_path.to_string(),
+        total_files,
+        files,
+        generated_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::generate_ast_chunks;
+    use crate::storage::init_chunk_database;
+    use crate::types::ChunkingOptions;
+
+    #[test]
+    fn test_complexity_report_ranks_files() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let options = ChunkingOptions::default();
+        let simple = "fn main() {}\n";
+        let branchy = "fn check(x: i32) -> i32 {\n    if x > 0 && x < 10 {\n        1\n    } else {\n        0\n    }\n}\n";
+        generate_ast_chunks(&conn, "/p", "src/main.rs", simple, &options).unwrap();
+        generate_ast_chunks(&conn, "/p", "src/check.rs", branchy, &options).unwrap();
+
+        let report = get_complexity_report(&conn, "/p", None).unwrap();
+        let ranked: Vec<(&str, usize)> = report
+            .files
+            .iter()
+            .map(|f| (f.file_path.as_str(), f.cyclomatic_complexity))
+            .collect();
+        assert_eq!(ranked, vec![("src/check.rs", 3), ("src/main.rs", 1)]);
+        assert_eq!(report.files[0].longest_function_lines, 7);
+
+        // Solo cuenta la versión más reciente de cada archivo (con un AST distinto al
+        // de main.rs: el hash del chunk AST no incluye la ruta)
+        let flat = "fn check() -> i32 {\n    1\n}\n";
+        generate_ast_chunks(&conn, "/p", "src/check.rs", flat, &options).unwrap();
+        let report = get_complexity_report(&conn, "/p", Some(1)).unwrap();
+        assert_eq!(report.total_files, 2);
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].cyclomatic_complexity, 1);
+    }
+}
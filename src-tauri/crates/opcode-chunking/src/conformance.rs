@@ -0,0 +1,297 @@
+use super::ast::{detect_language, language_by_name, parse_source};
+use super::callgraph::extract_file_dependencies;
+use super::lint::{definitions, enclosing_entity};
+use super::raw_source::should_ignore;
+use super::storage::{get_business_rules, get_latest_file_contents, set_business_rule_check};
+use super::types::{BusinessRule, ChunkType, RuleCheck, RuleConformance, RuleViolation};
+use anyhow::{anyhow, bail, Result};
+use regex::Regex;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tree_sitter::{Query, QueryCursor};
+
+/// Lenguajes contra los que se valida una query de `RequiredPattern`
+const QUERY_LANGUAGES: [&str; 5] = ["rust", "javascript", "typescript", "tsx", "python"];
+
+/// Valida y asigna (o quita, con None) la comprobación automática de una regla
+pub fn save_rule_check(conn: &Connection, rule_id: i64, check: Option<&RuleCheck>) -> Result<()> {
+    if let Some(check) = check {
+        validate_check(check)?;
+    }
+    set_business_rule_check(conn, rule_id, check)
+}
+
+fn validate_check(check: &RuleCheck) -> Result<()> {
+    if let Some(files) = check.files() {
+        glob::Pattern::new(files.trim_start_matches('/'))
+            .map_err(|e| anyhow!("Invalid files glob {}: {}", files, e))?;
+    }
+    match check {
+        RuleCheck::Naming { pattern, .. } => {
+            Regex::new(pattern).map_err(|e| anyhow!("Invalid naming pattern: {}", e))?;
+        }
+        RuleCheck::ForbiddenImport { module, .. } => {
+            if module.trim().is_empty() {
+                bail!("Forbidden import needs a module");
+            }
+        }
+        RuleCheck::RequiredPattern { query, .. } => {
+            let mut last_error = String::new();
+            for name in QUERY_LANGUAGES {
+                match Query::new(&language_by_name(name)?, query) {
+                    Ok(_) => return Ok(()),
+                    Err(e) => last_error = e.to_string(),
+                }
+            }
+            bail!(
+                "Query does not compile for any supported language: {}",
+                last_error
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Evalúa las reglas de negocio con comprobación automática contra la versión más
+/// reciente del código indexado y reporta las violaciones de cada una
+pub fn check_rule_conformance(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Vec<RuleConformance>> {
+    let rules: Vec<(BusinessRule, RuleCheck)> = get_business_rules(conn, project_path)?
+        .into_iter()
+        .filter_map(|rule| rule.check.clone().map(|check| (rule, check)))
+        .collect();
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+    let files = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+
+    Ok(rules
+        .into_iter()
+        .map(|(rule, check)| match evaluate(&check, &files) {
+            Ok((files_checked, violations)) => RuleConformance {
+                rule,
+                files_checked,
+                violations,
+                error: None,
+            },
+            Err(e) => RuleConformance {
+                rule,
+                files_checked: 0,
+                violations: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        })
+        .collect())
+}
+
+fn evaluate(
+    check: &RuleCheck,
+    files: &BTreeMap<String, (i64, String)>,
+) -> Result<(usize, Vec<RuleViolation>)> {
+    validate_check(check)?;
+    let in_scope = files.iter().filter(|(file_path, _)| match check.files() {
+        Some(glob) => should_ignore(file_path, &[glob.to_string()]),
+        None => true,
+    });
+
+    let mut files_checked = 0;
+    let mut violations = Vec::new();
+    match check {
+        RuleCheck::Naming { pattern, .. } => {
+            let re = Regex::new(pattern)?;
+            for (file_path, (chunk_id, content)) in in_scope {
+                files_checked += 1;
+                for (line, name) in definitions(content) {
+                    if !re.is_match(&name) {
+                        violations.push(RuleViolation {
+                            file_path: file_path.clone(),
+                            chunk_id: *chunk_id,
+                            line: Some(line),
+                            message: format!("`{}` does not match `{}`", name, pattern),
+                            entity_name: Some(name),
+                        });
+                    }
+                }
+            }
+        }
+        RuleCheck::ForbiddenImport { module, .. } => {
+            for (file_path, (chunk_id, content)) in in_scope {
+                files_checked += 1;
+                for dependency in extract_file_dependencies(file_path, content) {
+                    if !imports_module(&dependency, module) {
+                        continue;
+                    }
+                    let line = content
+                        .lines()
+                        .position(|l| l.contains(&dependency))
+                        .map(|i| i + 1);
+                    violations.push(RuleViolation {
+                        file_path: file_path.clone(),
+                        chunk_id: *chunk_id,
+                        line,
+                        entity_name: line.and_then(|line| enclosing_entity(content, line)),
+                        message: format!("imports forbidden module `{}`", dependency),
+                    });
+                }
+            }
+        }
+        RuleCheck::RequiredPattern { query, .. } => {
+            // Una query por extensión; None si no compila para ese lenguaje
+            let mut queries: HashMap<String, Option<(tree_sitter::Language, Query)>> =
+                HashMap::new();
+            for (file_path, (chunk_id, content)) in in_scope {
+                let extension = Path::new(file_path)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or_default()
+                    .to_lowercase();
+                let compiled = queries.entry(extension).or_insert_with(|| {
+                    let language = detect_language(file_path).ok()?;
+                    let query = Query::new(&language, query).ok()?;
+                    Some((language, query))
+                });
+                let Some((language, query)) = compiled else {
+                    continue;
+                };
+                let Ok(tree) = parse_source(language, content) else {
+                    continue;
+                };
+                files_checked += 1;
+                let mut cursor = QueryCursor::new();
+                if cursor
+                    .matches(query, tree.root_node(), content.as_bytes())
+                    .next()
+                    .is_none()
+                {
+                    violations.push(RuleViolation {
+                        file_path: file_path.clone(),
+                        chunk_id: *chunk_id,
+                        line: None,
+                        entity_name: None,
+                        message: "missing required pattern".to_string(),
+                    });
+                }
+            }
+        }
+    }
+    Ok((files_checked, violations))
+}
+
+/// True si el import es el módulo o uno de sus submódulos (`a::b`, `a/b`, `a.b`)
+fn imports_module(dependency: &str, module: &str) -> bool {
+    dependency
+        .strip_prefix(module)
+        .is_some_and(|rest| rest.is_empty() || ["::", "/", "."].iter().any(|s| rest.starts_with(s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::business_rules::propose_business_rule;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+
+    fn insert_source(conn: &Connection, file_path: &str, content: &str) {
+        chunk().file(file_path).content(content).insert(conn);
+    }
+
+    fn rule_with_check(conn: &Connection, entity: &str, check: RuleCheck) -> i64 {
+        let id = propose_business_rule(conn, "/p", entity, "src", "check").unwrap();
+        save_rule_check(conn, id, Some(&check)).unwrap();
+        id
+    }
+
+    #[test]
+    fn test_rule_conformance_reports_violations_per_rule() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_source(
+            &conn,
+            "src/handlers.rs",
+            "use crate::db::raw;\n\npub fn get_user() {\n    authorize();\n}\n\npub fn DeleteUser() {}\n",
+        );
+        insert_source(&conn, "src/admin.rs", "pub fn reset() {\n    wipe();\n}\n");
+        insert_source(&conn, "src/ui.py", "def render():\n    pass\n");
+
+        rule_with_check(
+            &conn,
+            "naming",
+            RuleCheck::Naming {
+                pattern: "^[a-z_]+$".to_string(),
+                files: Some("*.rs".to_string()),
+            },
+        );
+        rule_with_check(
+            &conn,
+            "db access",
+            RuleCheck::ForbiddenImport {
+                module: "crate::db".to_string(),
+                files: Some("src/handlers.rs".to_string()),
+            },
+        );
+        rule_with_check(
+            &conn,
+            "auth",
+            RuleCheck::RequiredPattern {
+                query: r#"(call_expression function: (identifier) @f (#eq? @f "authorize"))"#
+                    .to_string(),
+                files: None,
+            },
+        );
+        propose_business_rule(&conn, "/p", "manual", "src", "not checkable").unwrap();
+
+        let report = check_rule_conformance(&conn, "/p").unwrap();
+        assert_eq!(report.len(), 3);
+        let by_entity = |entity: &str| {
+            report
+                .iter()
+                .find(|r| r.rule.entity_name == entity)
+                .unwrap()
+        };
+
+        let naming = by_entity("naming");
+        assert_eq!(naming.files_checked, 2);
+        assert_eq!(naming.violations.len(), 1);
+        assert_eq!(
+            naming.violations[0].entity_name.as_deref(),
+            Some("DeleteUser")
+        );
+        assert_eq!(naming.violations[0].line, Some(7));
+
+        let imports = by_entity("db access");
+        assert_eq!(imports.files_checked, 1);
+        assert_eq!(imports.violations[0].line, Some(1));
+        assert!(imports.violations[0].message.contains("crate::db::raw"));
+
+        // La query no compila para Python: ese archivo no se revisa
+        let auth = by_entity("auth");
+        assert_eq!(auth.files_checked, 2);
+        assert_eq!(auth.violations.len(), 1);
+        assert_eq!(auth.violations[0].file_path, "src/admin.rs");
+    }
+
+    #[test]
+    fn test_invalid_checks_are_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let id = propose_business_rule(&conn, "/p", "x", "src", "check").unwrap();
+
+        for check in [
+            RuleCheck::Naming {
+                pattern: "([".to_string(),
+                files: None,
+            },
+            RuleCheck::RequiredPattern {
+                query: "(not_a_node_kind".to_string(),
+                files: None,
+            },
+        ] {
+            assert!(save_rule_check(&conn, id, Some(&check)).is_err());
+        }
+        assert!(imports_module("crate::db::raw", "crate::db"));
+        assert!(!imports_module("crate::dbx", "crate::db"));
+    }
+}
@@ -0,0 +1,174 @@
+use super::storage::{delete_orphan_blobs, store_content_blob};
+use super::types::{ContentCompactionReport, ContentStoreStats};
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Chunks con contenido en línea que se mueven a `content_blobs` por lote
+const COMPACTION_BATCH_SIZE: usize = 500;
+
+/// Tamaño del almacén de contenido: bytes guardados en blobs frente a los que ocuparían
+/// si cada chunk guardara su propia copia
+pub fn get_content_store_stats(conn: &Connection) -> Result<ContentStoreStats> {
+    let (blob_count, blob_bytes): (i64, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM content_blobs",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let referenced_bytes: i64 = conn.query_row(
+        "SELECT COALESCE(SUM(b.size), 0) FROM chunks c JOIN content_blobs b ON b.hash = c.blob_hash",
+        [],
+        |row| row.get(0),
+    )?;
+    let (inline_chunks, inline_bytes): (i64, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(length(CAST(content AS BLOB))), 0) FROM chunks
+         WHERE blob_hash IS NULL",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok(ContentStoreStats {
+        blob_count: blob_count as usize,
+        blob_bytes: blob_bytes as u64,
+        referenced_bytes: referenced_bytes as u64,
+        inline_chunks: inline_chunks as usize,
+        inline_bytes: inline_bytes as u64,
+        saved_bytes: (referenced_bytes - blob_bytes).max(0) as u64,
+    })
+}
+
+/// Mueve a `content_blobs` el contenido de los chunks guardados antes de la
+/// deduplicación (salvo los de índices de solo lectura) y elimina los blobs que ya
+/// nadie referencia
+pub fn compact_chunk_contents(conn: &Connection) -> Result<ContentCompactionReport> {
+    let mut chunks_migrated = 0;
+    let mut last_id = 0;
+    loop {
+        let mut stmt = conn.prepare(
+            "SELECT id, content FROM chunks WHERE blob_hash IS NULL AND id > ?1
+               AND project_path NOT IN (SELECT project_path FROM read_only_projects)
+             ORDER BY id LIMIT ?2",
+        )?;
+        let batch = stmt
+            .query_map(params![last_id, COMPACTION_BATCH_SIZE as i64], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        drop(stmt);
+        let Some((batch_last_id, _)) = batch.last() else {
+            break;
+        };
+        last_id = *batch_last_id;
+
+        let tx = conn.unchecked_transaction()?;
+        for (id, content) in &batch {
+            let hash = store_content_blob(&tx, content)?;
+            tx.execute(
+                "UPDATE chunks SET blob_hash = ?1, content = '' WHERE id = ?2",
+                params![hash, id],
+            )?;
+        }
+        tx.commit()?;
+        chunks_migrated += batch.len();
+    }
+
+    let blobs_removed = delete_orphan_blobs(conn)?;
+    if chunks_migrated > 0 || blobs_removed > 0 {
+        log::info!(
+            "Compacted chunk contents: {} chunks moved to blobs, {} orphan blobs removed",
+            chunks_migrated,
+            blobs_removed
+        );
+    }
+    Ok(ContentCompactionReport {
+        chunks_migrated,
+        blobs_removed,
+        stats: get_content_store_stats(conn)?,
+    })
+}
+
+/// Contenido de un blob por su hash
+pub fn get_content_blob(conn: &Connection, hash: &str) -> Result<Option<String>> {
+    let content = conn
+        .query_row(
+            "SELECT content FROM content_blobs WHERE hash = ?1",
+            params![hash],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{calculate_content_hash, init_chunk_database, query_chunks};
+    use crate::test_support::chunk;
+    use crate::types::ChunkQuery;
+    use chrono::Utc;
+
+    #[test]
+    fn test_identical_contents_share_a_blob() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let content = "fn main() {}\n".repeat(10);
+        chunk()
+            .project("/a")
+            .file("src/main.rs")
+            .content(&content)
+            .insert(&conn);
+        chunk()
+            .project("/b")
+            .file("src/main.rs")
+            .content(&content)
+            .insert(&conn);
+
+        let stats = get_content_store_stats(&conn).unwrap();
+        assert_eq!(stats.blob_count, 1);
+        assert_eq!(stats.saved_bytes, content.len() as u64);
+        assert_eq!(
+            get_content_blob(&conn, &calculate_content_hash(&content)).unwrap(),
+            Some(content.clone())
+        );
+
+        let chunks = query_chunks(
+            &conn,
+            &ChunkQuery {
+                project_path: Some("/b".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(chunks[0].content, content);
+    }
+
+    #[test]
+    fn test_compact_inline_contents() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        // Filas guardadas antes de la deduplicación
+        let now = Utc::now().to_rfc3339();
+        for (i, project) in ["/a", "/b"].iter().enumerate() {
+            conn.execute(
+                "INSERT INTO chunks (project_path, chunk_type, file_path, content, content_hash, created_at, updated_at)
+                 VALUES (?1, 'raw_source', 'lib.rs', 'pub fn lib() {}', ?2, ?3, ?3)",
+                params![project, format!("legacy-{}", i), &now],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO content_blobs (hash, content, size, created_at) VALUES ('orphan', 'x', 1, ?1)",
+            params![&now],
+        )
+        .unwrap();
+        assert_eq!(get_content_store_stats(&conn).unwrap().inline_chunks, 2);
+
+        let report = compact_chunk_contents(&conn).unwrap();
+        assert_eq!(report.chunks_migrated, 2);
+        assert_eq!(report.blobs_removed, 1);
+        assert_eq!(report.stats.blob_count, 1);
+        assert_eq!(report.stats.inline_chunks, 0);
+
+        let chunks = query_chunks(&conn, &ChunkQuery::default()).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|c| c.content == "pub fn lib() {}"));
+    }
+}
@@ -0,0 +1,535 @@
+use super::business_rules::pinned_rules;
+use super::ranking::rank_chunks_for_task;
+use super::storage::{
+    get_active_snapshot_id, get_context_budget_weights, get_context_rule_pinning,
+    insert_context_delivery, set_context_budget_weights,
+};
+use super::types::{
+    BusinessRule, Chunk, ChunkType, ChunkTypeWeight, ContextDelivery, ContextPack, PackedChunk,
+    SourceRegion,
+};
+use anyhow::{bail, Result};
+use chrono::Utc;
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+
+/// Presupuesto por defecto de un paquete de contexto (tokens estimados)
+pub const DEFAULT_TOKEN_BUDGET: usize = 8000;
+
+/// Candidatos del ranking que se consideran al armar el paquete
+const MAX_PACK_CANDIDATES: usize = 200;
+
+/// Arma un paquete de contexto para una tarea: toma los chunks mejor puntuados
+/// hasta agotar el presupuesto, sin repetir regiones de código. Raw source, AST
+/// y chunks de entidad del mismo archivo/rango solo aparecen una vez (gana el de
+/// mayor puntuación).
+///
+/// Si el proyecto configuró un reparto por tipo, cada tipo tiene reservada su
+/// fracción del presupuesto y los tipos sin peso no entran. Lo que un tipo no usa
+/// se reparte al final entre los chunks que excedieron la fracción de su tipo.
+///
+/// Las reglas de negocio que fija el proyecto (`RulePinning`) van primero y siempre
+/// entran: sus tokens se descuentan del presupuesto antes que los chunks
+pub fn build_context_pack(
+    conn: &Connection,
+    project_path: &str,
+    task_text: &str,
+    token_budget: usize,
+    session_id: Option<&str>,
+) -> Result<ContextPack> {
+    let budget_weights = get_context_budget_weights(conn, project_path)?;
+    let ranked = rank_chunks_for_task(conn, project_path, task_text, MAX_PACK_CANDIDATES)?;
+    let pinned_rules = pinned_rules(
+        conn,
+        project_path,
+        get_context_rule_pinning(conn, project_path)?,
+    )?;
+
+    let mut chunks: Vec<PackedChunk> = Vec::new();
+    let mut total_tokens = pinned_rules
+        .iter()
+        .map(|rule| estimate_tokens(&render_rule(rule)))
+        .sum();
+    let mut overlaps_skipped = 0;
+    let mut over_budget_skipped = 0;
+    let mut tokens_by_type: HashMap<&'static str, usize> = HashMap::new();
+    let mut deferred = Vec::new();
+
+    for scored in ranked {
+        let type_budget = budget_weights
+            .as_ref()
+            .map(|w| type_share(w, &scored.chunk.chunk_type, token_budget));
+        if type_budget == Some(0) {
+            continue;
+        }
+        let region = source_region(&scored.chunk);
+        if overlaps_included(&chunks, region.as_ref()) {
+            overlaps_skipped += 1;
+            continue;
+        }
+
+        let tokens = estimate_tokens(&scored.chunk.content);
+        if total_tokens + tokens > token_budget {
+            over_budget_skipped += 1;
+            continue;
+        }
+        let used = tokens_by_type
+            .entry(scored.chunk.chunk_type.as_str())
+            .or_default();
+        if type_budget.is_some_and(|budget| *used + tokens > budget) {
+            deferred.push((scored, region, tokens));
+            continue;
+        }
+        *used += tokens;
+        total_tokens += tokens;
+        chunks.push(PackedChunk {
+            chunk: scored.chunk,
+            score: scored.score,
+            tokens,
+            region,
+        });
+    }
+
+    // Presupuesto que los demás tipos no usaron
+    for (scored, region, tokens) in deferred {
+        if overlaps_included(&chunks, region.as_ref()) {
+            overlaps_skipped += 1;
+        } else if total_tokens + tokens > token_budget {
+            over_budget_skipped += 1;
+        } else {
+            total_tokens += tokens;
+            chunks.push(PackedChunk {
+                chunk: scored.chunk,
+                score: scored.score,
+                tokens,
+                region,
+            });
+        }
+    }
+
+    let mut pack = ContextPack {
+        project_path: project_path.to_string(),
+        task_text: task_text.to_string(),
+        token_budget,
+        total_tokens,
+        pinned_rules,
+        chunks,
+        budget_weights,
+        overlaps_skipped,
+        over_budget_skipped,
+        delivery_id: None,
+        markdown: String::new(),
+    };
+    pack.markdown = render_context_pack(&pack);
+    pack.delivery_id = Some(record_delivery(conn, &pack, session_id)?);
+    Ok(pack)
+}
+
+/// Registra qué chunks se entregaron, a qué sesión y sobre qué snapshot
+fn record_delivery(conn: &Connection, pack: &ContextPack, session_id: Option<&str>) -> Result<i64> {
+    let (chunk_ids, chunk_tokens) = pack
+        .chunks
+        .iter()
+        .filter_map(|c| c.chunk.id.map(|id| (id, c.tokens)))
+        .unzip();
+    insert_context_delivery(
+        conn,
+        &ContextDelivery {
+            id: None,
+            project_path: pack.project_path.clone(),
+            session_id: session_id.map(str::to_string),
+            snapshot_id: get_active_snapshot_id(conn, &pack.project_path)?,
+            task_text: pack.task_text.clone(),
+            chunk_ids,
+            chunk_tokens,
+            total_tokens: pack.total_tokens,
+            token_budget: pack.token_budget,
+            created_at: Utc::now(),
+        },
+    )
+}
+
+/// Valida y guarda el reparto del presupuesto de contexto de un proyecto.
+/// Una lista vacía vuelve al comportamiento sin reparto
+pub fn save_budget_weights(
+    conn: &Connection,
+    project_path: &str,
+    weights: &[ChunkTypeWeight],
+) -> Result<()> {
+    let mut seen = HashSet::new();
+    for weight in weights {
+        if !weight.weight.is_finite() || weight.weight < 0.0 {
+            bail!(
+                "Invalid weight {} for {}",
+                weight.weight,
+                weight.chunk_type.as_str()
+            );
+        }
+        if !seen.insert(weight.chunk_type.as_str()) {
+            bail!("Duplicate weight for {}", weight.chunk_type.as_str());
+        }
+    }
+    if !weights.is_empty() && weights.iter().all(|w| w.weight == 0.0) {
+        bail!("At least one chunk type needs a positive weight");
+    }
+    set_context_budget_weights(conn, project_path, weights)
+}
+
+/// Tokens reservados para un tipo de chunk según los pesos (normalizados a 1)
+fn type_share(weights: &[ChunkTypeWeight], chunk_type: &ChunkType, token_budget: usize) -> usize {
+    let total: f64 = weights.iter().map(|w| w.weight).sum();
+    if total <= 0.0 {
+        return 0;
+    }
+    weights
+        .iter()
+        .find(|w| &w.chunk_type == chunk_type)
+        .map(|w| (token_budget as f64 * w.weight / total).floor() as usize)
+        .unwrap_or(0)
+}
+
+fn overlaps_included(chunks: &[PackedChunk], region: Option<&SourceRegion>) -> bool {
+    region.is_some_and(|region| {
+        chunks
+            .iter()
+            .filter_map(|c| c.region.as_ref())
+            .any(|included| included.overlaps(region))
+    })
+}
+
+/// Región de código que representa un chunk. Raw source, AST, callgraph y tests
+/// cubren el archivo completo salvo que su metadata indique `start_line`/`end_line`
+/// (chunks de entidad); el resto de tipos no ocupa código fuente
+pub fn source_region(chunk: &Chunk) -> Option<SourceRegion> {
+    if !matches!(
+        chunk.chunk_type,
+        ChunkType::RawSource | ChunkType::Ast | ChunkType::Callgraph | ChunkType::Tests
+    ) {
+        return None;
+    }
+    let file_path = chunk.file_path.clone()?;
+
+    let metadata = chunk
+        .metadata
+        .as_deref()
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok());
+    let line = |key: &str| {
+        metadata
+            .as_ref()
+            .and_then(|m| m.get(key))
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+    };
+
+    Some(match (line("start_line"), line("end_line")) {
+        (Some(start_line), end_line) => SourceRegion {
+            file_path,
+            start_line,
+            end_line: end_line.or(Some(start_line)),
+        },
+        _ => SourceRegion {
+            file_path,
+            start_line: 1,
+            end_line: None,
+        },
+    })
+}
+
+impl SourceRegion {
+    /// Dos regiones se solapan si son del mismo archivo y sus rangos de líneas se cruzan
+    pub fn overlaps(&self, other: &SourceRegion) -> bool {
+        self.file_path == other.file_path
+            && self.start_line <= other.end_line.unwrap_or(usize::MAX)
+            && other.start_line <= self.end_line.unwrap_or(usize::MAX)
+    }
+}
+
+/// Estimación de tokens (~4 caracteres por token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Renderiza el paquete como Markdown para el prompt del agente
+pub fn render_context_pack(pack: &ContextPack) -> String {
+    let mut md = format!(
+        "# Context Pack\n\nTask: {}\n\n{} chunks, ~{} / {} tokens\n",
+        pack.task_text.trim(),
+        pack.chunks.len(),
+        pack.total_tokens,
+        pack.token_budget
+    );
+    if !pack.pinned_rules.is_empty() {
+        md.push_str("\n## Business rules (always included)\n\n");
+        for rule in &pack.pinned_rules {
+            md.push_str(&render_rule(rule));
+        }
+    }
+    for packed in &pack.chunks {
+        let chunk = &packed.chunk;
+        md.push_str(&format!(
+            "\n## `{}` ({}",
+            chunk.file_path.as_deref().unwrap_or("-"),
+            chunk.chunk_type.as_str()
+        ));
+        if let Some(entity) = &chunk.entity_name {
+            md.push_str(&format!(", {}", entity));
+        }
+        if let Some(SourceRegion {
+            start_line,
+            end_line: Some(end_line),
+            ..
+        }) = &packed.region
+        {
+            md.push_str(&format!(", lines {}-{}", start_line, end_line));
+        }
+        md.push_str(")\n\n```\n");
+        md.push_str(chunk.content.trim_end());
+        md.push_str("\n```\n");
+    }
+    md
+}
+
+/// Línea Markdown de una regla fijada (también la base de su estimación de tokens)
+fn render_rule(rule: &BusinessRule) -> String {
+    format!(
+        "- **{}** (`{}`): {}\n",
+        rule.entity_name,
+        rule.file_path,
+        rule.rule_description.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::business_rules::{propose_business_rule, tag_business_rule, validate_business_rule};
+    use crate::storage::{
+        get_context_deliveries, init_chunk_database, set_context_rule_pinning, upsert_chunk,
+    };
+    use crate::test_support;
+    use crate::types::RulePinning;
+
+    fn chunk(
+        chunk_type: ChunkType,
+        file_path: &str,
+        content: &str,
+        metadata: Option<&str>,
+    ) -> Chunk {
+        let builder = test_support::chunk()
+            .chunk_type(chunk_type)
+            .file(file_path)
+            .content(content);
+        match metadata {
+            Some(metadata) => builder.metadata(metadata).build(),
+            None => builder.build(),
+        }
+    }
+
+    #[test]
+    fn test_source_region_overlap() {
+        let file = source_region(&chunk(ChunkType::RawSource, "a.rs", "", None)).unwrap();
+        let entity = source_region(&chunk(
+            ChunkType::Ast,
+            "a.rs",
+            "",
+            Some(r#"{"start_line":10,"end_line":20}"#),
+        ))
+        .unwrap();
+        let other = source_region(&chunk(
+            ChunkType::Ast,
+            "a.rs",
+            "",
+            Some(r#"{"start_line":21,"end_line":30}"#),
+        ))
+        .unwrap();
+        assert!(file.overlaps(&entity));
+        assert!(!entity.overlaps(&other));
+        assert!(!file.overlaps(&source_region(&chunk(ChunkType::Ast, "b.rs", "", None)).unwrap()));
+        assert!(source_region(&chunk(ChunkType::ErrorLog, "a.rs", "", None)).is_none());
+    }
+
+    #[test]
+    fn test_build_context_pack_skips_overlaps() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        for c in [
+            chunk(
+                ChunkType::RawSource,
+                "src/login.rs",
+                "pub fn login(user: &User) {}",
+                None,
+            ),
+            chunk(ChunkType::Ast, "src/login.rs", "function_item login", None),
+            chunk(
+                ChunkType::RawSource,
+                "src/session.rs",
+                "pub fn login_session() {}",
+                None,
+            ),
+        ] {
+            upsert_chunk(&conn, &c, None).unwrap();
+        }
+
+        let pack =
+            build_context_pack(&conn, "/p", "fix login", DEFAULT_TOKEN_BUDGET, Some("s1")).unwrap();
+        let files: Vec<_> = pack
+            .chunks
+            .iter()
+            .map(|c| c.chunk.file_path.clone().unwrap())
+            .collect();
+        assert_eq!(files.len(), 2);
+        assert!(files.contains(&"src/login.rs".to_string()));
+        assert!(files.contains(&"src/session.rs".to_string()));
+        assert_eq!(pack.overlaps_skipped, 1);
+        assert_eq!(
+            pack.total_tokens,
+            pack.chunks.iter().map(|c| c.tokens).sum::<usize>()
+        );
+        assert!(pack.markdown.contains("## `src/session.rs` (raw_source)"));
+
+        let deliveries = get_context_deliveries(&conn, "/p", Some("s1"), None).unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].id, pack.delivery_id);
+        assert_eq!(
+            deliveries[0].chunk_ids,
+            pack.chunks
+                .iter()
+                .map(|c| c.chunk.id.unwrap())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            deliveries[0].chunk_tokens.iter().sum::<usize>(),
+            pack.total_tokens
+        );
+
+        let tiny = build_context_pack(&conn, "/p", "fix login", 1, None).unwrap();
+        assert!(tiny.chunks.is_empty());
+        assert_eq!(tiny.over_budget_skipped, 3);
+        assert_eq!(
+            get_context_deliveries(&conn, "/p", None, None)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_budget_weights_reserve_share_per_type() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let mut error = chunk(ChunkType::ErrorLog, "", "login failed: timeout", None);
+        error.file_path = None;
+        for c in [
+            chunk(
+                ChunkType::RawSource,
+                "src/login.rs",
+                "pub fn login() { verify() }",
+                None,
+            ),
+            chunk(
+                ChunkType::RawSource,
+                "src/login_form.rs",
+                "fn render_login() { form() }",
+                None,
+            ),
+            chunk(
+                ChunkType::Tests,
+                "tests/login_test.rs",
+                "fn test_login() {}",
+                None,
+            ),
+            error,
+        ] {
+            upsert_chunk(&conn, &c, None).unwrap();
+        }
+
+        assert!(save_budget_weights(
+            &conn,
+            "/p",
+            &[ChunkTypeWeight {
+                chunk_type: ChunkType::Tests,
+                weight: -1.0
+            }]
+        )
+        .is_err());
+        save_budget_weights(
+            &conn,
+            "/p",
+            &[
+                ChunkTypeWeight {
+                    chunk_type: ChunkType::RawSource,
+                    weight: 0.5,
+                },
+                ChunkTypeWeight {
+                    chunk_type: ChunkType::Tests,
+                    weight: 0.5,
+                },
+            ],
+        )
+        .unwrap();
+
+        // 16 tokens: 8 reservados para raw source (un archivo de 7) y 8 para tests
+        let pack = build_context_pack(&conn, "/p", "login", 16, None).unwrap();
+        let types: Vec<_> = pack
+            .chunks
+            .iter()
+            .map(|c| c.chunk.chunk_type.as_str())
+            .collect();
+        assert_eq!(types.iter().filter(|t| **t == "raw_source").count(), 1);
+        assert!(types.contains(&"tests"));
+        assert!(!types.contains(&"error_log"));
+        assert_eq!(pack.over_budget_skipped, 1);
+        assert_eq!(pack.budget_weights.as_ref().map(|w| w.len()), Some(2));
+
+        save_budget_weights(&conn, "/p", &[]).unwrap();
+        let pack = build_context_pack(&conn, "/p", "login", 16, None).unwrap();
+        assert!(pack.budget_weights.is_none());
+    }
+
+    #[test]
+    fn test_pinned_rules_lead_every_pack() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        upsert_chunk(
+            &conn,
+            &chunk(
+                ChunkType::RawSource,
+                "src/login.rs",
+                "pub fn login() {}",
+                None,
+            ),
+            None,
+        )
+        .unwrap();
+        let refunds =
+            propose_business_rule(&conn, "/p", "refund", "src/billing.rs", "refunds").unwrap();
+        validate_business_rule(&conn, refunds, "Refunds need two approvals", None).unwrap();
+        let audit = propose_business_rule(&conn, "/p", "audit", "src/audit.rs", "audit").unwrap();
+        validate_business_rule(&conn, audit, "Audit log is append-only", None).unwrap();
+        tag_business_rule(&conn, audit, &[" Critical ".to_string()]).unwrap();
+        propose_business_rule(&conn, "/p", "login", "src/login.rs", "pending").unwrap();
+
+        let pack =
+            build_context_pack(&conn, "/p", "fix login", DEFAULT_TOKEN_BUDGET, None).unwrap();
+        assert!(pack.pinned_rules.is_empty());
+
+        set_context_rule_pinning(&conn, "/p", RulePinning::Validated).unwrap();
+        let pack =
+            build_context_pack(&conn, "/p", "fix login", DEFAULT_TOKEN_BUDGET, None).unwrap();
+        assert_eq!(pack.pinned_rules.len(), 2);
+        let rules_at = pack
+            .markdown
+            .find("Business rules (always included)")
+            .unwrap();
+        assert!(rules_at < pack.markdown.find("src/login.rs").unwrap());
+        assert!(pack.markdown.contains("Refunds need two approvals"));
+        assert!(pack.total_tokens > pack.chunks.iter().map(|c| c.tokens).sum::<usize>());
+
+        // Las reglas críticas entran aunque no quede presupuesto para chunks
+        set_context_rule_pinning(&conn, "/p", RulePinning::Critical).unwrap();
+        let pack = build_context_pack(&conn, "/p", "fix login", 1, None).unwrap();
+        assert_eq!(pack.pinned_rules.len(), 1);
+        assert_eq!(pack.pinned_rules[0].tags, vec!["critical"]);
+        assert!(pack.chunks.is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+use super::storage::{calculate_content_hash, get_chunk_by_id, get_chunk_id_by_hash, upsert_chunk};
+use super::types::{Chunk, ChunkType, CustomChunkInput};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde_json::{Map, Value};
+use std::path::{Component, Path};
+
+/// Tamaño máximo del contenido de un chunk de usuario
+pub const MAX_CUSTOM_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Largo máximo del título (entity_name) de un chunk de usuario
+pub const MAX_CUSTOM_CHUNK_TITLE: usize = 200;
+
+/// Guarda un chunk `user_content` escrito por el usuario o un agente. Repetir el mismo
+/// título y contenido en el proyecto actualiza la metadata en vez de duplicarlo
+pub fn create_custom_chunk(conn: &Connection, input: &CustomChunkInput) -> Result<Chunk> {
+    let title = input.title.trim();
+    if title.is_empty() || title.chars().count() > MAX_CUSTOM_CHUNK_TITLE {
+        bail!(
+            "Title must have between 1 and {} characters",
+            MAX_CUSTOM_CHUNK_TITLE
+        );
+    }
+    if input.content.trim().is_empty() {
+        bail!("Content must not be empty");
+    }
+    if input.content.len() > MAX_CUSTOM_CHUNK_BYTES {
+        bail!("Content is larger than {} bytes", MAX_CUSTOM_CHUNK_BYTES);
+    }
+    let file_path = match input.file_path.as_deref().map(str::trim) {
+        Some(file_path) if !file_path.is_empty() => {
+            let path = Path::new(file_path);
+            if path.is_absolute()
+                || path
+                    .components()
+                    .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+            {
+                bail!("File path must be relative to the project: {}", file_path);
+            }
+            Some(file_path.trim_start_matches("./").to_string())
+        }
+        _ => None,
+    };
+
+    let mut metadata = match &input.metadata {
+        Some(Value::Object(map)) => map.clone(),
+        Some(Value::Null) | None => Map::new(),
+        Some(_) => bail!("Metadata must be a JSON object"),
+    };
+    metadata.insert("source".to_string(), Value::from("user"));
+    if let Some(kind) = input
+        .kind
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+    {
+        metadata.insert("kind".to_string(), Value::from(kind));
+    }
+    let mut tags: Vec<String> = Vec::new();
+    for tag in &input.tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    if !tags.is_empty() {
+        metadata.insert("tags".to_string(), Value::from(tags));
+    }
+
+    let content_hash = calculate_content_hash(&format!(
+        "{}\n{}\n{}\n{}",
+        input.project_path,
+        ChunkType::UserContent.as_str(),
+        title,
+        input.content
+    ));
+    upsert_chunk(
+        conn,
+        &Chunk {
+            id: None,
+            project_path: input.project_path.clone(),
+            chunk_type: ChunkType::UserContent,
+            file_path,
+            entity_name: Some(title.to_string()),
+            content: input.content.clone(),
+            content_hash: content_hash.clone(),
+            metadata: Some(Value::Object(metadata).to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        },
+        None,
+    )?;
+
+    let chunk_id = get_chunk_id_by_hash(conn, &input.project_path, &content_hash)?
+        .context("Chunk not stored")?;
+    get_chunk_by_id(conn, chunk_id)?.context("Chunk not stored")
+}
+
+/// Reemplaza la metadata de un chunk vigente por un objeto JSON, o la combina con la
+/// actual si `merge` (JSON merge patch: las claves en null se eliminan)
+pub fn update_chunk_metadata(
+    conn: &Connection,
+    chunk_id: i64,
+    metadata: &Value,
+    merge: bool,
+) -> Result<Chunk> {
+    if !metadata.is_object() {
+        bail!("Metadata must be a JSON object");
+    }
+    let sql = if merge {
+        "UPDATE chunks SET updated_at = ?3,
+                metadata = json_patch(
+                    CASE WHEN (CASE WHEN json_valid(metadata) THEN json_type(metadata) END)
+                              = 'object'
+                         THEN metadata ELSE '{}' END,
+                    ?2)
+         WHERE id = ?1 AND deleted_at IS NULL"
+    } else {
+        "UPDATE chunks SET metadata = json(?2), updated_at = ?3
+         WHERE id = ?1 AND deleted_at IS NULL"
+    };
+    let updated = conn.execute(
+        sql,
+        params![chunk_id, metadata.to_string(), Utc::now().to_rfc3339()],
+    )?;
+    if updated == 0 {
+        bail!("Chunk {} not found or deleted", chunk_id);
+    }
+    get_chunk_by_id(conn, chunk_id)?.context("Chunk not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use serde_json::json;
+
+    fn input(title: &str, content: &str) -> CustomChunkInput {
+        CustomChunkInput {
+            project_path: "/p".to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            file_path: Some("./docs/auth.md".to_string()),
+            kind: Some("design_decision".to_string()),
+            tags: vec!["Auth".to_string(), "auth ".to_string()],
+            metadata: Some(json!({"decided_by": "team"})),
+        }
+    }
+
+    #[test]
+    fn test_create_and_update_custom_chunk() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        let chunk = create_custom_chunk(&conn, &input("Use JWT", "Tokens expire in 1h")).unwrap();
+        assert_eq!(chunk.chunk_type, ChunkType::UserContent);
+        assert_eq!(chunk.entity_name.as_deref(), Some("Use JWT"));
+        assert_eq!(chunk.file_path.as_deref(), Some("docs/auth.md"));
+        let metadata: Value = serde_json::from_str(chunk.metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata["kind"], "design_decision");
+        assert_eq!(metadata["tags"], json!(["auth"]));
+        assert_eq!(metadata["decided_by"], "team");
+
+        // El mismo título y contenido no se duplica
+        let again = create_custom_chunk(&conn, &input("Use JWT", "Tokens expire in 1h")).unwrap();
+        assert_eq!(again.id, chunk.id);
+
+        assert!(create_custom_chunk(&conn, &input(" ", "x")).is_err());
+        assert!(create_custom_chunk(&conn, &input("t", "")).is_err());
+        let mut outside = input("t", "x");
+        outside.file_path = Some("../secrets".to_string());
+        assert!(create_custom_chunk(&conn, &outside).is_err());
+        let mut not_object = input("t", "x");
+        not_object.metadata = Some(json!([1]));
+        assert!(create_custom_chunk(&conn, &not_object).is_err());
+
+        let chunk_id = chunk.id.unwrap();
+        let merged = update_chunk_metadata(
+            &conn,
+            chunk_id,
+            &json!({"status": "accepted", "decided_by": null}),
+            true,
+        )
+        .unwrap();
+        let metadata: Value = serde_json::from_str(merged.metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata["status"], "accepted");
+        assert_eq!(metadata["kind"], "design_decision");
+        assert!(metadata.get("decided_by").is_none());
+
+        let replaced = update_chunk_metadata(&conn, chunk_id, &json!({"a": 1}), false).unwrap();
+        assert_eq!(replaced.metadata.as_deref(), Some(r#"{"a":1}"#));
+        assert!(update_chunk_metadata(&conn, chunk_id, &json!("x"), false).is_err());
+        assert!(update_chunk_metadata(&conn, -1, &json!({}), false).is_err());
+    }
+}
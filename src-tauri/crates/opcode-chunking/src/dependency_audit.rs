@@ -0,0 +1,475 @@
+use super::resolver::parent_dir;
+use super::types::{
+    CallgraphMetadata, ChunkType, DependencyAuditReport, DependencyKind, DependencyScope,
+    ManifestMetadata, UndeclaredDependency,
+};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Módulos de la librería estándar de Python más comunes (nunca se declaran)
+const PYTHON_STDLIB: &[&str] = &[
+    "__future__",
+    "abc",
+    "argparse",
+    "asyncio",
+    "base64",
+    "bisect",
+    "collections",
+    "contextlib",
+    "copy",
+    "csv",
+    "dataclasses",
+    "datetime",
+    "decimal",
+    "email",
+    "enum",
+    "fractions",
+    "functools",
+    "glob",
+    "gzip",
+    "hashlib",
+    "heapq",
+    "html",
+    "http",
+    "importlib",
+    "inspect",
+    "io",
+    "itertools",
+    "json",
+    "logging",
+    "math",
+    "multiprocessing",
+    "operator",
+    "os",
+    "pathlib",
+    "pickle",
+    "platform",
+    "pprint",
+    "queue",
+    "random",
+    "re",
+    "shutil",
+    "signal",
+    "socket",
+    "sqlite3",
+    "statistics",
+    "string",
+    "struct",
+    "subprocess",
+    "sys",
+    "tarfile",
+    "tempfile",
+    "textwrap",
+    "threading",
+    "time",
+    "traceback",
+    "types",
+    "typing",
+    "unittest",
+    "urllib",
+    "uuid",
+    "warnings",
+    "weakref",
+    "xml",
+    "zipfile",
+];
+
+/// Módulos built-in de Node.js
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "buffer",
+    "child_process",
+    "crypto",
+    "events",
+    "fs",
+    "http",
+    "https",
+    "net",
+    "os",
+    "path",
+    "process",
+    "querystring",
+    "readline",
+    "stream",
+    "timers",
+    "tty",
+    "url",
+    "util",
+    "worker_threads",
+    "zlib",
+];
+
+/// Crates del toolchain de Rust y raíces de rutas que no son paquetes
+const RUST_BUILTINS: &[&str] = &[
+    "std",
+    "core",
+    "alloc",
+    "proc_macro",
+    "test",
+    "crate",
+    "self",
+    "super",
+];
+
+/// Manifiesto con sus dependencias ya normalizadas
+struct Manifest {
+    path: String,
+    dir: String,
+    ecosystem: &'static str,
+    metadata: ManifestMetadata,
+}
+
+/// Cruza las dependencias declaradas en los manifiestos (chunks de metadata) con los
+/// imports resueltos (chunks de callgraph) y reporta por paquete las dependencias
+/// declaradas sin uso y las usadas sin declarar.
+///
+/// Solo las dependencias de runtime se reportan como no usadas: las de desarrollo
+/// suelen ser herramientas (linters, bundlers) que nunca se importan.
+pub fn audit_dependencies(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Vec<DependencyAuditReport>> {
+    let manifests: Vec<Manifest> =
+        latest_metadata_by_file(conn, project_path, &ChunkType::ProjectMetadata)?
+            .into_iter()
+            .filter_map(|(path, metadata)| {
+                let metadata: ManifestMetadata = serde_json::from_str(&metadata).ok()?;
+                let ecosystem = ecosystem_of_manifest(&metadata.manifest_type)?;
+                Some(Manifest {
+                    dir: parent_dir(&path),
+                    path,
+                    ecosystem,
+                    metadata,
+                })
+            })
+            .collect();
+
+    let imports: Vec<(String, CallgraphMetadata)> =
+        latest_metadata_by_file(conn, project_path, &ChunkType::Callgraph)?
+            .into_iter()
+            .filter_map(|(path, metadata)| {
+                serde_json::from_str::<CallgraphMetadata>(&metadata)
+                    .ok()
+                    .map(|m| (path, m))
+            })
+            .collect();
+
+    Ok(build_reports(&manifests, &imports))
+}
+
+/// Construye los reportes a partir de manifiestos e imports ya cargados
+fn build_reports(
+    manifests: &[Manifest],
+    imports: &[(String, CallgraphMetadata)],
+) -> Vec<DependencyAuditReport> {
+    // Paquetes usados por manifiesto: paquete normalizado -> archivos que lo importan
+    let mut used: Vec<BTreeMap<String, BTreeSet<String>>> = vec![BTreeMap::new(); manifests.len()];
+
+    for (file_path, metadata) in imports {
+        let Some(ecosystem) = ecosystem_of_file(file_path) else {
+            continue;
+        };
+        let Some(owner) = owning_manifest(manifests, file_path, ecosystem) else {
+            continue;
+        };
+
+        for dep in &metadata.dependencies {
+            if dep.kind == DependencyKind::Internal {
+                continue;
+            }
+            let Some(package) = &dep.package else {
+                continue;
+            };
+            if is_builtin(ecosystem, package) {
+                continue;
+            }
+            used[owner]
+                .entry(normalize_name(ecosystem, package))
+                .or_default()
+                .insert(file_path.clone());
+        }
+    }
+
+    let mut reports = Vec::new();
+    for (index, manifest) in manifests.iter().enumerate() {
+        let own_name = manifest
+            .metadata
+            .package_name
+            .as_deref()
+            .map(|n| normalize_name(manifest.ecosystem, n));
+
+        let declared: HashSet<String> = manifest
+            .metadata
+            .dependencies
+            .iter()
+            .map(|d| normalize_name(manifest.ecosystem, &d.name))
+            .collect();
+
+        // Declaraciones heredadas de manifiestos ancestros (ej: raíz de un monorepo)
+        let inherited: HashSet<String> = manifests
+            .iter()
+            .filter(|m| {
+                m.ecosystem == manifest.ecosystem
+                    && m.path != manifest.path
+                    && is_ancestor_dir(&m.dir, &manifest.dir)
+            })
+            .flat_map(|m| {
+                m.metadata
+                    .dependencies
+                    .iter()
+                    .map(|d| normalize_name(m.ecosystem, &d.name))
+            })
+            .collect();
+
+        let mut unused: Vec<String> = manifest
+            .metadata
+            .dependencies
+            .iter()
+            .filter(|d| d.scope == DependencyScope::Normal)
+            .filter(|d| {
+                let name = normalize_name(manifest.ecosystem, &d.name);
+                !used[index].contains_key(&name) && !name.starts_with("@types/")
+            })
+            .map(|d| d.name.clone())
+            .collect();
+        unused.sort();
+        unused.dedup();
+
+        let undeclared: Vec<UndeclaredDependency> = used[index]
+            .iter()
+            .filter(|(package, _)| {
+                !declared.contains(*package)
+                    && !inherited.contains(*package)
+                    && own_name.as_deref() != Some(package.as_str())
+            })
+            .map(|(package, files)| UndeclaredDependency {
+                package: package.clone(),
+                files: files.iter().cloned().collect(),
+            })
+            .collect();
+
+        reports.push(DependencyAuditReport {
+            manifest_path: manifest.path.clone(),
+            package_name: manifest.metadata.package_name.clone(),
+            unused,
+            undeclared,
+        });
+    }
+
+    reports
+}
+
+/// Metadata del chunk más reciente de cada archivo para un tipo de chunk
+pub(crate) fn latest_metadata_by_file(
+    conn: &Connection,
+    project_path: &str,
+    chunk_type: &ChunkType,
+) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT file_path, metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2
+           AND file_path IS NOT NULL AND metadata IS NOT NULL AND deleted_at IS NULL
+         ORDER BY updated_at DESC, id DESC",
+    )?;
+
+    let rows = stmt
+        .query_map(params![project_path, chunk_type.as_str()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut seen = HashSet::new();
+    let mut latest: Vec<(String, String)> = rows
+        .into_iter()
+        .filter(|(path, _)| seen.insert(path.clone()))
+        .collect();
+    latest.sort();
+    Ok(latest)
+}
+
+/// Manifiesto más cercano (ancestro) del mismo ecosistema que el archivo
+fn owning_manifest(manifests: &[Manifest], file_path: &str, ecosystem: &str) -> Option<usize> {
+    let file_dir = parent_dir(file_path);
+    manifests
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.ecosystem == ecosystem && is_ancestor_dir(&m.dir, &file_dir))
+        .max_by_key(|(_, m)| if m.dir == "." { 0 } else { m.dir.len() + 1 })
+        .map(|(index, _)| index)
+}
+
+/// true si `dir` es `child` o uno de sus ancestros
+pub(crate) fn is_ancestor_dir(dir: &str, child: &str) -> bool {
+    dir == "." || child == dir || child.starts_with(&format!("{}/", dir))
+}
+
+fn ecosystem_of_manifest(manifest_type: &str) -> Option<&'static str> {
+    match manifest_type {
+        "package.json" => Some("js"),
+        "Cargo.toml" => Some("rust"),
+        "pyproject.toml" | "requirements.txt" => Some("python"),
+        _ => None,
+    }
+}
+
+pub(crate) fn ecosystem_of_file(file_path: &str) -> Option<&'static str> {
+    let extension = file_path.rsplit_once('.').map(|(_, ext)| ext)?;
+    match extension {
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" => Some("js"),
+        "rs" => Some("rust"),
+        "py" => Some("python"),
+        _ => None,
+    }
+}
+
+fn is_builtin(ecosystem: &str, package: &str) -> bool {
+    match ecosystem {
+        "rust" => RUST_BUILTINS.contains(&package),
+        "python" => PYTHON_STDLIB.contains(&package),
+        "js" => package.starts_with("node:") || NODE_BUILTINS.contains(&package),
+        _ => false,
+    }
+}
+
+/// Normaliza nombres de paquete: Rust y Python importan con `_` lo que se declara con `-`
+pub(crate) fn normalize_name(ecosystem: &str, name: &str) -> String {
+    match ecosystem {
+        "rust" => name.replace('-', "_"),
+        "python" => name.to_lowercase().replace(['-', '.'], "_"),
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::parse_manifest_dependencies;
+    use crate::types::ClassifiedDependency;
+
+    fn manifest(path: &str, content: &str) -> Manifest {
+        let file_name = path.rsplit('/').next().unwrap();
+        let metadata = parse_manifest_dependencies(file_name, content).unwrap();
+        Manifest {
+            path: path.to_string(),
+            dir: parent_dir(path),
+            ecosystem: ecosystem_of_manifest(file_name).unwrap(),
+            metadata,
+        }
+    }
+
+    fn imports(file: &str, deps: &[(&str, DependencyKind)]) -> (String, CallgraphMetadata) {
+        let dependencies = deps
+            .iter()
+            .map(|(package, kind)| ClassifiedDependency {
+                specifier: package.to_string(),
+                kind: kind.clone(),
+                package: Some(package.to_string()),
+                resolved_file: None,
+            })
+            .collect();
+        (
+            file.to_string(),
+            CallgraphMetadata {
+                is_static: true,
+                entry_points: vec![],
+                external_calls: vec![],
+                call_count: 0,
+                dependencies,
+            },
+        )
+    }
+
+    #[test]
+    fn test_cargo_unused_and_undeclared() {
+        let manifests = vec![manifest(
+            "Cargo.toml",
+            "[package]\nname = \"my-app\"\n\n[dependencies]\nserde = \"1\"\nserde-json = \"1\"\nregex = \"1\"\n\n[dev-dependencies]\ntempfile = \"3\"\n",
+        )];
+        let imports = vec![
+            imports(
+                "src/main.rs",
+                &[
+                    ("serde", DependencyKind::External),
+                    ("std", DependencyKind::External),
+                ],
+            ),
+            imports(
+                "src/lib.rs",
+                &[
+                    ("serde_json", DependencyKind::External),
+                    ("anyhow", DependencyKind::External),
+                ],
+            ),
+            imports("src/bin.rs", &[("my_app", DependencyKind::Workspace)]),
+        ];
+
+        let reports = build_reports(&manifests, &imports);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].unused, vec!["regex".to_string()]);
+        assert_eq!(reports[0].undeclared.len(), 1);
+        assert_eq!(reports[0].undeclared[0].package, "anyhow");
+        assert_eq!(
+            reports[0].undeclared[0].files,
+            vec!["src/lib.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_monorepo_packages_use_closest_manifest() {
+        let manifests = vec![
+            manifest(
+                "package.json",
+                r#"{"devDependencies": {"typescript": "5"}, "dependencies": {"lodash": "4"}}"#,
+            ),
+            manifest(
+                "packages/ui/package.json",
+                r#"{"name": "@acme/ui", "dependencies": {"react": "18", "left-pad": "1"}}"#,
+            ),
+        ];
+        let imports = vec![
+            imports(
+                "packages/ui/src/Button.tsx",
+                &[
+                    ("react", DependencyKind::External),
+                    ("lodash", DependencyKind::External),
+                    ("fs", DependencyKind::External),
+                ],
+            ),
+            imports("scripts/build.ts", &[("zod", DependencyKind::External)]),
+        ];
+
+        let reports = build_reports(&manifests, &imports);
+        let root = reports
+            .iter()
+            .find(|r| r.manifest_path == "package.json")
+            .unwrap();
+        let ui = reports
+            .iter()
+            .find(|r| r.manifest_path == "packages/ui/package.json")
+            .unwrap();
+
+        assert_eq!(root.unused, vec!["lodash".to_string()]);
+        assert_eq!(root.undeclared[0].package, "zod");
+        assert_eq!(ui.unused, vec!["left-pad".to_string()]);
+        // lodash está declarado en el manifiesto raíz y fs es built-in
+        assert!(ui.undeclared.is_empty());
+    }
+
+    #[test]
+    fn test_parse_python_manifests() {
+        let pyproject = "[project]\nname = \"svc\"\ndependencies = [\n  \"requests>=2\",\n  \"PyYAML\",\n]\n\n[project.optional-dependencies]\ndev = [\"pytest\"]\n";
+        let parsed = parse_manifest_dependencies("pyproject.toml", pyproject).unwrap();
+        let names: Vec<&str> = parsed
+            .dependencies
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["requests", "PyYAML", "pytest"]);
+        assert_eq!(parsed.package_name.as_deref(), Some("svc"));
+
+        let requirements = "# comment\nflask==2.0\n-r other.txt\nnumpy\n";
+        let parsed = parse_manifest_dependencies("requirements.txt", requirements).unwrap();
+        assert_eq!(parsed.dependencies.len(), 2);
+    }
+}
@@ -0,0 +1,625 @@
+use super::resolver::{normalize_path, package_name, parent_dir, ImportResolver};
+use super::storage::{
+    calculate_content_hash, delete_relationships_by_source, get_chunk_id_by_hash,
+    get_latest_file_chunk_id, insert_relationship, replace_file_relationships, upsert_chunk,
+};
+use super::types::{
+    Chunk, ChunkRelationship, ChunkType, ModuleDependencyMetadata, ModuleGraphMetadata,
+    RelationshipType,
+};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// entity_name del chunk de proyecto que contiene el grafo completo
+pub const MODULE_GRAPH_ENTITY: &str = "<module_graph>";
+
+/// Valor de `source` en la metadata de las relaciones generadas aquí
+const RELATIONSHIP_SOURCE: &str = "module_graph";
+
+/// Valor de `source` de las relaciones DependsOn entre archivos
+pub(crate) const IMPORT_RELATIONSHIP_SOURCE: &str = "import_resolution";
+
+/// Destino de un import una vez clasificado
+#[derive(Debug, PartialEq)]
+enum ImportTarget {
+    /// Módulo interno del proyecto (directorio relativo)
+    Internal(String),
+    /// Paquete de terceros
+    External(String),
+}
+
+/// Grafo de dependencias agregado a nivel de módulo (directorio)
+#[derive(Debug, Default)]
+pub struct ModuleGraph {
+    /// módulo -> archivos que contiene
+    pub files: BTreeMap<String, BTreeSet<String>>,
+    /// módulo -> (módulo interno -> número de imports)
+    pub edges: BTreeMap<String, BTreeMap<String, usize>>,
+    /// módulo -> paquetes externos
+    pub external: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ModuleGraph {
+    /// Construye el grafo a partir de los imports de cada archivo
+    pub fn build(file_imports: &[(String, Vec<String>)], resolver: &ImportResolver) -> Self {
+        let mut graph = ModuleGraph::default();
+
+        for (file_path, _) in file_imports {
+            graph
+                .files
+                .entry(module_of(file_path))
+                .or_default()
+                .insert(file_path.clone());
+        }
+
+        let modules: BTreeSet<String> = graph.files.keys().cloned().collect();
+
+        for (file_path, imports) in file_imports {
+            let module = module_of(file_path);
+            for import in imports {
+                let target = match resolver.resolve(file_path, import) {
+                    Some(resolved) => ImportTarget::Internal(module_of(&resolved)),
+                    None => resolve_import(file_path, import, &modules),
+                };
+                match target {
+                    ImportTarget::Internal(target) => {
+                        if target != module {
+                            *graph
+                                .edges
+                                .entry(module.clone())
+                                .or_default()
+                                .entry(target)
+                                .or_insert(0) += 1;
+                        }
+                    }
+                    ImportTarget::External(package) => {
+                        graph
+                            .external
+                            .entry(module.clone())
+                            .or_default()
+                            .insert(package);
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Módulos que dependen del módulo dado
+    pub fn dependents_of(&self, module: &str) -> Vec<String> {
+        self.edges
+            .iter()
+            .filter(|(_, targets)| targets.contains_key(module))
+            .map(|(from, _)| from.clone())
+            .collect()
+    }
+
+    /// Todas las aristas internas (origen, destino)
+    pub fn edge_list(&self) -> Vec<(String, String)> {
+        self.edges
+            .iter()
+            .flat_map(|(from, targets)| targets.keys().map(move |to| (from.clone(), to.clone())))
+            .collect()
+    }
+}
+
+/// Indica si el archivo es un lenguaje cuyos imports sabemos extraer
+pub fn is_supported_source(file_path: &str) -> bool {
+    matches!(
+        extension_of(file_path).as_str(),
+        "rs" | "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" | "py"
+    )
+}
+
+/// Genera los chunks de módulo, el chunk de proyecto con el grafo completo
+/// y las relaciones DependsOn entre módulos.
+/// Retorna (chunks generados, relaciones creadas)
+pub fn generate_module_graph_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_imports: &[(String, Vec<String>)],
+    resolver: &ImportResolver,
+) -> Result<(usize, usize)> {
+    let graph = ModuleGraph::build(file_imports, resolver);
+
+    // Limpiar el grafo anterior para no acumular módulos ni aristas obsoletas
+    delete_relationships_by_source(conn, project_path, RELATIONSHIP_SOURCE)?;
+    conn.execute(
+        "DELETE FROM chunks WHERE project_path = ?1 AND chunk_type = 'callgraph'
+         AND json_extract(metadata, '$.kind') IN ('module', 'module_graph')",
+        rusqlite::params![project_path],
+    )?;
+
+    let mut chunks_created = 0;
+    let mut module_chunk_ids: BTreeMap<String, i64> = BTreeMap::new();
+
+    for (module, files) in &graph.files {
+        let depends_on: Vec<String> = graph
+            .edges
+            .get(module)
+            .map(|targets| targets.keys().cloned().collect())
+            .unwrap_or_default();
+        let dependents = graph.dependents_of(module);
+        let external: Vec<String> = graph
+            .external
+            .get(module)
+            .map(|packages| packages.iter().cloned().collect())
+            .unwrap_or_default();
+
+        let mut repr = String::new();
+        repr.push_str(&format!("# Module: {}\n", module));
+        repr.push_str(&format!("\n# Files ({})\n", files.len()));
+        for file in files {
+            repr.push_str(&format!("  - {}\n", file));
+        }
+        repr.push_str(&format!("\n# Depends On ({})\n", depends_on.len()));
+        for target in &depends_on {
+            repr.push_str(&format!("  - {}\n", target));
+        }
+        repr.push_str(&format!("\n# Dependents ({})\n", dependents.len()));
+        for source in &dependents {
+            repr.push_str(&format!("  - {}\n", source));
+        }
+        repr.push_str(&format!("\n# External ({})\n", external.len()));
+        for package in &external {
+            repr.push_str(&format!("  - {}\n", package));
+        }
+
+        let metadata = ModuleDependencyMetadata {
+            kind: "module".to_string(),
+            module: module.clone(),
+            files: files.iter().cloned().collect(),
+            depends_on,
+            dependents,
+            external,
+        };
+
+        let chunk = build_chunk(
+            project_path,
+            module,
+            repr,
+            serde_json::to_string(&metadata)?,
+        );
+        upsert_chunk(conn, &chunk, None)?;
+        chunks_created += 1;
+
+        if let Some(id) = get_chunk_id_by_hash(conn, &chunk.project_path, &chunk.content_hash)? {
+            module_chunk_ids.insert(module.clone(), id);
+        }
+    }
+
+    // Chunk de proyecto con el grafo completo
+    let edges = graph.edge_list();
+    let external_packages: BTreeSet<String> = graph.external.values().flatten().cloned().collect();
+
+    let mut repr = String::new();
+    repr.push_str(&format!(
+        "# Module Graph ({} modules, {} edges)\n",
+        graph.files.len(),
+        edges.len()
+    ));
+    for (module, targets) in &graph.edges {
+        let targets: Vec<&str> = targets.keys().map(|t| t.as_str()).collect();
+        repr.push_str(&format!("{} -> {}\n", module, targets.join(", ")));
+    }
+    repr.push_str(&format!(
+        "\n# External Packages ({})\n",
+        external_packages.len()
+    ));
+    for package in &external_packages {
+        let users: Vec<&str> = graph
+            .external
+            .iter()
+            .filter(|(_, packages)| packages.contains(package))
+            .map(|(module, _)| module.as_str())
+            .collect();
+        repr.push_str(&format!("{}: {}\n", package, users.join(", ")));
+    }
+
+    let metadata = ModuleGraphMetadata {
+        kind: "module_graph".to_string(),
+        module_count: graph.files.len(),
+        edge_count: edges.len(),
+        edges: edges.clone(),
+        external_packages: external_packages.into_iter().collect(),
+    };
+
+    let chunk = build_chunk(
+        project_path,
+        MODULE_GRAPH_ENTITY,
+        repr,
+        serde_json::to_string(&metadata)?,
+    );
+    upsert_chunk(conn, &chunk, None)?;
+    chunks_created += 1;
+
+    // Relaciones DependsOn entre módulos
+    let mut relationships_created = 0;
+    for (from, targets) in &graph.edges {
+        for (to, import_count) in targets {
+            let (Some(&from_id), Some(&to_id)) =
+                (module_chunk_ids.get(from), module_chunk_ids.get(to))
+            else {
+                continue;
+            };
+
+            let rel = ChunkRelationship {
+                id: None,
+                from_chunk_id: from_id,
+                to_chunk_id: to_id,
+                relationship_type: RelationshipType::DependsOn,
+                metadata: Some(
+                    serde_json::json!({
+                        "source": RELATIONSHIP_SOURCE,
+                        "import_count": import_count,
+                    })
+                    .to_string(),
+                ),
+                created_at: Utc::now(),
+            };
+            insert_relationship(conn, &rel)?;
+            relationships_created += 1;
+        }
+    }
+
+    Ok((chunks_created, relationships_created))
+}
+
+/// Crea relaciones DependsOn entre los chunks de archivo (raw source, o callgraph si
+/// no existe) del archivo que importa y del archivo importado ya resuelto.
+/// Retorna el número de relaciones creadas
+pub fn generate_file_dependency_relationships(
+    conn: &Connection,
+    project_path: &str,
+    file_imports: &[(String, Vec<String>)],
+    resolver: &ImportResolver,
+) -> Result<usize> {
+    delete_relationships_by_source(conn, project_path, IMPORT_RELATIONSHIP_SOURCE)?;
+
+    let mut file_chunk_id = file_chunk_ids(conn, project_path);
+    let mut relationships_created = 0;
+    for (file_path, imports) in file_imports {
+        for rel in file_dependency_edges(file_path, imports, resolver, &mut file_chunk_id)? {
+            insert_relationship(conn, &rel)?;
+            relationships_created += 1;
+        }
+    }
+
+    Ok(relationships_created)
+}
+
+/// Reemplaza las relaciones DependsOn que salen de `files` (y solo esas) por las de
+/// sus imports actuales; un archivo sin entrada en `file_imports` se queda sin ellas.
+/// Retorna el número de relaciones creadas
+pub fn replace_file_dependency_relationships(
+    conn: &Connection,
+    project_path: &str,
+    file_imports: &[(String, Vec<String>)],
+    files: &BTreeSet<String>,
+    resolver: &ImportResolver,
+) -> Result<usize> {
+    let mut file_chunk_id = file_chunk_ids(conn, project_path);
+    let mut relationships_created = 0;
+    for file_path in files {
+        let imports = file_imports
+            .iter()
+            .find(|(f, _)| f == file_path)
+            .map(|(_, imports)| imports.as_slice())
+            .unwrap_or_default();
+        let edges = file_dependency_edges(file_path, imports, resolver, &mut file_chunk_id)?;
+        relationships_created += replace_file_relationships(
+            conn,
+            project_path,
+            IMPORT_RELATIONSHIP_SOURCE,
+            file_path,
+            &edges,
+        )?;
+    }
+    Ok(relationships_created)
+}
+
+/// Id del chunk de cada archivo (raw source, o callgraph si no existe), cacheado
+fn file_chunk_ids<'a>(
+    conn: &'a Connection,
+    project_path: &'a str,
+) -> impl FnMut(&str) -> Result<Option<i64>> + 'a {
+    let mut chunk_ids: BTreeMap<String, Option<i64>> = BTreeMap::new();
+    move |file: &str| -> Result<Option<i64>> {
+        if let Some(id) = chunk_ids.get(file) {
+            return Ok(*id);
+        }
+        let id = match get_latest_file_chunk_id(conn, project_path, file, &ChunkType::RawSource)? {
+            Some(id) => Some(id),
+            None => get_latest_file_chunk_id(conn, project_path, file, &ChunkType::Callgraph)?,
+        };
+        chunk_ids.insert(file.to_string(), id);
+        Ok(id)
+    }
+}
+
+/// Relaciones DependsOn de un archivo hacia cada archivo que importa
+fn file_dependency_edges(
+    file_path: &str,
+    imports: &[String],
+    resolver: &ImportResolver,
+    file_chunk_id: &mut impl FnMut(&str) -> Result<Option<i64>>,
+) -> Result<Vec<ChunkRelationship>> {
+    let mut edges = Vec::new();
+    let mut seen = BTreeSet::new();
+    for import in imports {
+        let Some(target) = resolver.resolve(file_path, import) else {
+            continue;
+        };
+        if target == file_path || !seen.insert(target.clone()) {
+            continue;
+        }
+
+        let (Some(from_id), Some(to_id)) = (file_chunk_id(file_path)?, file_chunk_id(&target)?)
+        else {
+            continue;
+        };
+
+        edges.push(ChunkRelationship {
+            id: None,
+            from_chunk_id: from_id,
+            to_chunk_id: to_id,
+            relationship_type: RelationshipType::DependsOn,
+            metadata: Some(
+                serde_json::json!({
+                    "source": IMPORT_RELATIONSHIP_SOURCE,
+                    "specifier": import,
+                    "from_file": file_path,
+                    "to_file": target,
+                })
+                .to_string(),
+            ),
+            created_at: Utc::now(),
+        });
+    }
+    Ok(edges)
+}
+
+fn build_chunk(project_path: &str, entity_name: &str, content: String, metadata: String) -> Chunk {
+    let content_hash = calculate_content_hash(&format!("{}\n{}", project_path, content));
+
+    Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::Callgraph,
+        file_path: None,
+        entity_name: Some(entity_name.to_string()),
+        content,
+        content_hash,
+        metadata: Some(metadata),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// Módulo al que pertenece un archivo: su directorio padre ("." para la raíz)
+fn module_of(file_path: &str) -> String {
+    parent_dir(file_path)
+}
+
+fn extension_of(file_path: &str) -> String {
+    Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Clasifica un import como módulo interno o paquete externo
+fn resolve_import(file_path: &str, import: &str, modules: &BTreeSet<String>) -> ImportTarget {
+    let module = module_of(file_path);
+
+    match extension_of(file_path).as_str() {
+        "rs" => {
+            if import.starts_with("self::") || import.starts_with("super::") {
+                return ImportTarget::Internal(module);
+            }
+            if let Some(rest) = import.strip_prefix("crate::") {
+                let first = rest.split("::").next().unwrap_or("");
+                let src_root = rust_src_root(&module);
+                let candidate = join_module(&src_root, first);
+                if modules.contains(&candidate) {
+                    return ImportTarget::Internal(candidate);
+                }
+                return ImportTarget::Internal(src_root);
+            }
+            ImportTarget::External(package_name(file_path, import))
+        }
+        "py" => {
+            if import.starts_with('.') {
+                let dots = import.chars().take_while(|c| *c == '.').count();
+                let mut base = module.clone();
+                for _ in 1..dots {
+                    base = module_of(&base);
+                }
+                let rest = import[dots..].replace('.', "/");
+                return ImportTarget::Internal(closest_module(&join_module(&base, &rest), modules));
+            }
+            let as_path = import.replace('.', "/");
+            if let Some(found) = modules
+                .iter()
+                .find(|m| *m == &as_path || m.ends_with(&format!("/{}", as_path)))
+            {
+                return ImportTarget::Internal(found.clone());
+            }
+            let parent = module_of(&as_path);
+            if parent != "."
+                && modules
+                    .iter()
+                    .any(|m| m == &parent || m.ends_with(&format!("/{}", parent)))
+            {
+                return ImportTarget::Internal(closest_module(&parent, modules));
+            }
+            ImportTarget::External(package_name(file_path, import))
+        }
+        _ => {
+            if import.starts_with('.') {
+                let target = normalize_path(&module, import);
+                return ImportTarget::Internal(closest_module(&target, modules));
+            }
+            ImportTarget::External(package_name(file_path, import))
+        }
+    }
+}
+
+/// Directorio raíz de un crate Rust (el ancestro `src` más cercano)
+fn rust_src_root(module: &str) -> String {
+    let mut current = module.to_string();
+    loop {
+        if current == "src" || current.ends_with("/src") {
+            return current;
+        }
+        if current == "." {
+            return module.to_string();
+        }
+        current = module_of(&current);
+    }
+}
+
+/// Si la ruta es un módulo conocido se usa tal cual; si no, su directorio padre
+fn closest_module(path: &str, modules: &BTreeSet<String>) -> String {
+    if modules.contains(path) {
+        path.to_string()
+    } else {
+        module_of(path)
+    }
+}
+
+fn join_module(base: &str, rest: &str) -> String {
+    if rest.is_empty() {
+        base.to_string()
+    } else if base == "." {
+        rest.to_string()
+    } else {
+        format!("{}/{}", base, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+
+    fn imports(file: &str, deps: &[&str]) -> (String, Vec<String>) {
+        (
+            file.to_string(),
+            deps.iter().map(|d| d.to_string()).collect(),
+        )
+    }
+
+    fn resolver_for(file_imports: &[(String, Vec<String>)]) -> ImportResolver {
+        ImportResolver::new(file_imports.iter().map(|(f, _)| f.clone()).collect())
+    }
+
+    #[test]
+    fn test_build_js_module_graph() {
+        let file_imports = [
+            imports(
+                "src/components/App.tsx",
+                &["../lib/api", "react", "@tauri-apps/api/core"],
+            ),
+            imports("src/lib/api.ts", &["../types/chunking"]),
+            imports("src/types/chunking.ts", &[]),
+        ];
+        let graph = ModuleGraph::build(&file_imports, &resolver_for(&file_imports));
+
+        assert_eq!(graph.files.len(), 3);
+        assert!(graph.edges["src/components"].contains_key("src/lib"));
+        assert!(graph.edges["src/lib"].contains_key("src/types"));
+        assert!(graph.external["src/components"].contains("react"));
+        assert!(graph.external["src/components"].contains("@tauri-apps/api"));
+        assert_eq!(
+            graph.dependents_of("src/lib"),
+            vec!["src/components".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_build_rust_module_graph() {
+        let file_imports = [
+            imports(
+                "src/commands/chunking.rs",
+                &["crate::chunking::types", "tauri::State"],
+            ),
+            imports("src/chunking/mod.rs", &["self::types", "std::path::Path"]),
+        ];
+        let graph = ModuleGraph::build(&file_imports, &resolver_for(&file_imports));
+
+        assert!(graph.edges["src/commands"].contains_key("src/chunking"));
+        assert!(!graph.edges.contains_key("src/chunking"));
+        assert!(graph.external["src/chunking"].contains("std"));
+        assert!(graph.external["src/commands"].contains("tauri"));
+    }
+
+    #[test]
+    fn test_generate_module_graph_chunks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        let file_imports = vec![
+            imports("app/views/home.py", &["..models.user", "flask"]),
+            imports("app/models/user.py", &["sqlalchemy"]),
+        ];
+
+        let resolver = resolver_for(&file_imports);
+        let (chunks, relationships) =
+            generate_module_graph_chunks(&conn, "/tmp/project", &file_imports, &resolver).unwrap();
+        assert_eq!(chunks, 3);
+        assert_eq!(relationships, 1);
+
+        // Regenerar no duplica aristas ni módulos
+        let (chunks, relationships) =
+            generate_module_graph_chunks(&conn, "/tmp/project", &file_imports, &resolver).unwrap();
+        assert_eq!((chunks, relationships), (3, 1));
+        let total: i64 = conn
+            .query_row("SELECT COUNT(*) FROM chunk_relationships", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(total, 1);
+    }
+
+    #[test]
+    fn test_generate_file_dependency_relationships() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        let project = "/tmp/project";
+        for file in ["src/main.ts", "src/lib/api.ts"] {
+            chunk()
+                .project(project)
+                .file(file)
+                .content(file)
+                .insert(&conn);
+        }
+
+        let file_imports = vec![
+            imports("src/main.ts", &["./lib/api", "react"]),
+            imports("src/lib/api.ts", &[]),
+        ];
+        let resolver = resolver_for(&file_imports);
+
+        let created =
+            generate_file_dependency_relationships(&conn, project, &file_imports, &resolver)
+                .unwrap();
+        assert_eq!(created, 1);
+
+        let to_file: String = conn
+            .query_row(
+                "SELECT c.file_path FROM chunk_relationships r JOIN chunks c ON c.id = r.to_chunk_id",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(to_file, "src/lib/api.ts");
+    }
+}
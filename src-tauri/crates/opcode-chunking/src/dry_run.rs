@@ -0,0 +1,117 @@
+use super::types::{DryRunChanges, DryRunFileChanges, DryRunReport};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, HashMap};
+
+/// Estado de un chunk vigente antes/después de indexar (sin contenido); los que
+/// van a la papelera cuentan como eliminados
+#[derive(PartialEq)]
+struct ChunkState {
+    chunk_type: String,
+    file_path: Option<String>,
+    updated_at: String,
+}
+
+fn chunk_states(conn: &Connection, project_path: &str) -> Result<HashMap<i64, ChunkState>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, chunk_type, file_path, updated_at FROM chunks
+         WHERE project_path = ?1 AND deleted_at IS NULL",
+    )?;
+    let states = stmt
+        .query_map(params![project_path], |row| {
+            Ok((
+                row.get(0)?,
+                ChunkState {
+                    chunk_type: row.get(1)?,
+                    file_path: row.get(2)?,
+                    updated_at: row.get(3)?,
+                },
+            ))
+        })?
+        .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+    Ok(states)
+}
+
+/// Ejecuta `index` dentro de una transacción que siempre se revierte y reporta
+/// los chunks del proyecto que habría creado, actualizado o eliminado
+pub fn preview<T>(
+    conn: &Connection,
+    project_path: &str,
+    index: impl FnOnce() -> Result<T>,
+) -> Result<(T, DryRunReport)> {
+    let tx = conn.unchecked_transaction()?;
+    let before = chunk_states(&tx, project_path)?;
+    let output = index();
+    let after = chunk_states(&tx, project_path);
+    tx.rollback()?;
+
+    Ok((output?, compare_states(&before, &after?)))
+}
+
+#[derive(Clone, Copy)]
+enum Change {
+    Created,
+    Updated,
+    Deleted,
+}
+
+fn compare_states(
+    before: &HashMap<i64, ChunkState>,
+    after: &HashMap<i64, ChunkState>,
+) -> DryRunReport {
+    let mut changes: Vec<(&ChunkState, Change)> = Vec::new();
+    for (id, state) in after {
+        match before.get(id) {
+            None => changes.push((state, Change::Created)),
+            Some(previous) if previous != state => changes.push((state, Change::Updated)),
+            Some(_) => {}
+        }
+    }
+    for (id, state) in before {
+        if !after.contains_key(id) {
+            changes.push((state, Change::Deleted));
+        }
+    }
+
+    let mut report = DryRunReport::default();
+    let mut by_type: BTreeMap<&str, DryRunChanges> = BTreeMap::new();
+    let mut by_file: BTreeMap<(Option<&str>, &str), DryRunFileChanges> = BTreeMap::new();
+
+    for (state, change) in changes {
+        let type_changes = by_type
+            .entry(&state.chunk_type)
+            .or_insert_with(|| DryRunChanges {
+                chunk_type: state.chunk_type.clone(),
+                ..Default::default()
+            });
+        let file_changes = by_file
+            .entry((state.file_path.as_deref(), &state.chunk_type))
+            .or_insert_with(|| DryRunFileChanges {
+                file_path: state.file_path.clone(),
+                chunk_type: state.chunk_type.clone(),
+                ..Default::default()
+            });
+
+        match change {
+            Change::Created => {
+                report.chunks_created += 1;
+                type_changes.created += 1;
+                file_changes.created += 1;
+            }
+            Change::Updated => {
+                report.chunks_updated += 1;
+                type_changes.updated += 1;
+                file_changes.updated += 1;
+            }
+            Change::Deleted => {
+                report.chunks_deleted += 1;
+                type_changes.deleted += 1;
+                file_changes.deleted += 1;
+            }
+        }
+    }
+
+    report.by_type = by_type.into_values().collect();
+    report.files = by_file.into_values().collect();
+    report
+}
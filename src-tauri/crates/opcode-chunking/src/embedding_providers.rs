@@ -0,0 +1,274 @@
+use super::embeddings::EmbeddingProvider;
+use super::read_only::ensure_index_writable;
+use super::types::{EmbeddingBackend, EmbeddingSettings};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+
+/// El binario incluye el backend local (feature `local-embeddings`)
+pub const LOCAL_EMBEDDINGS_AVAILABLE: bool = cfg!(feature = "local-embeddings");
+
+const LOCAL_UNAVAILABLE: &str =
+    "This build does not include local embeddings (feature `local-embeddings`)";
+
+/// Backend de embeddings configurado para el proyecto
+pub fn get_embedding_settings(conn: &Connection, project_path: &str) -> Result<EmbeddingSettings> {
+    let backend: Option<String> = conn
+        .query_row(
+            "SELECT backend FROM embedding_settings WHERE project_path = ?1",
+            params![project_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(EmbeddingSettings {
+        project_path: project_path.to_string(),
+        backend: backend
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .context("Invalid embedding backend")?,
+        local_available: LOCAL_EMBEDDINGS_AVAILABLE,
+    })
+}
+
+/// Elige el backend de embeddings del proyecto (`None` lo quita). Los embeddings ya
+/// guardados se recalculan en el próximo refresco si el modelo o su versión cambian
+pub fn set_embedding_backend(
+    conn: &Connection,
+    project_path: &str,
+    backend: Option<EmbeddingBackend>,
+) -> Result<EmbeddingSettings> {
+    ensure_index_writable(conn, project_path)?;
+    match &backend {
+        Some(backend) => {
+            if matches!(backend, EmbeddingBackend::Local { .. }) && !LOCAL_EMBEDDINGS_AVAILABLE {
+                bail!(LOCAL_UNAVAILABLE);
+            }
+            conn.execute(
+                "INSERT INTO embedding_settings (project_path, backend, updated_at)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(project_path) DO UPDATE SET
+                     backend = excluded.backend, updated_at = excluded.updated_at",
+                params![
+                    project_path,
+                    serde_json::to_string(backend)?,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+        }
+        None => {
+            conn.execute(
+                "DELETE FROM embedding_settings WHERE project_path = ?1",
+                params![project_path],
+            )?;
+        }
+    }
+    get_embedding_settings(conn, project_path)
+}
+
+/// Proveedor del backend configurado para el proyecto, o `None` si no tiene.
+/// El modelo local se carga en cada llamada
+pub fn project_provider(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Option<Box<dyn EmbeddingProvider>>> {
+    get_embedding_settings(conn, project_path)?
+        .backend
+        .map(|backend| create_provider(&backend))
+        .transpose()
+}
+
+pub fn create_provider(backend: &EmbeddingBackend) -> Result<Box<dyn EmbeddingProvider>> {
+    match backend {
+        EmbeddingBackend::Http {
+            endpoint,
+            model,
+            api_key_env,
+        } => {
+            let api_key = match api_key_env {
+                Some(var) => Some(
+                    std::env::var(var)
+                        .with_context(|| format!("Environment variable {} is not set", var))?,
+                ),
+                None => None,
+            };
+            Ok(Box::new(HttpEmbedder {
+                endpoint: endpoint.trim_end_matches('/').to_string(),
+                model: model.clone(),
+                api_key,
+                client: reqwest::blocking::Client::new(),
+            }))
+        }
+        #[cfg(feature = "local-embeddings")]
+        EmbeddingBackend::Local { model_dir } => Ok(Box::new(local::LocalEmbedder::load(
+            std::path::Path::new(model_dir),
+        )?)),
+        #[cfg(not(feature = "local-embeddings"))]
+        EmbeddingBackend::Local { .. } => bail!(LOCAL_UNAVAILABLE),
+    }
+}
+
+/// API de embeddings compatible con OpenAI (OpenAI, Ollama, LM Studio, vLLM...).
+/// La versión del modelo es el endpoint: el mismo modelo servido por otro servidor
+/// se vuelve a embeber
+struct HttpEmbedder {
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    client: reqwest::blocking::Client,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl EmbeddingProvider for HttpEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn model_version(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut request = self
+            .client
+            .post(format!("{}/embeddings", self.endpoint))
+            .header("User-Agent", "opcode-App")
+            .json(&serde_json::json!({ "model": self.model, "input": texts }));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request
+            .send()
+            .with_context(|| format!("Failed to reach {}", self.endpoint))?;
+        let status = response.status();
+        let text = response.text().unwrap_or_default();
+        if !status.is_success() {
+            bail!("Embedding API returned {}: {}", status, text);
+        }
+
+        let mut data = serde_json::from_str::<EmbeddingResponse>(&text)
+            .context("Invalid embedding API response")?
+            .data;
+        data.sort_by_key(|d| d.index);
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// Modelo ONNX local (fastembed), sin red ni descargas en tiempo de ejecución
+#[cfg(feature = "local-embeddings")]
+mod local {
+    use super::EmbeddingProvider;
+    use anyhow::{Context, Result};
+    use fastembed::{
+        InitOptionsUserDefined, TextEmbedding, TokenizerFiles, UserDefinedEmbeddingModel,
+    };
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    pub struct LocalEmbedder {
+        model: String,
+        /// Prefijo del BLAKE3 de `model.onnx`: reemplazar el archivo re-embebe el proyecto
+        version: String,
+        /// `TextEmbedding::embed` pide `&mut self`
+        embedding: Mutex<TextEmbedding>,
+    }
+
+    impl LocalEmbedder {
+        pub fn load(model_dir: &Path) -> Result<Self> {
+            let read = |name: &str| {
+                std::fs::read(model_dir.join(name))
+                    .with_context(|| format!("Missing {} in {}", name, model_dir.display()))
+            };
+            let onnx = read("model.onnx")?;
+            let version = blake3::hash(&onnx).to_hex()[..16].to_string();
+            let tokenizer_files = TokenizerFiles {
+                tokenizer_file: read("tokenizer.json")?,
+                config_file: read("config.json")?,
+                special_tokens_map_file: read("special_tokens_map.json")?,
+                tokenizer_config_file: read("tokenizer_config.json")?,
+            };
+            let embedding = TextEmbedding::try_new_from_user_defined(
+                UserDefinedEmbeddingModel::new(onnx, tokenizer_files),
+                InitOptionsUserDefined::default(),
+            )
+            .with_context(|| format!("Failed to load embedding model {}", model_dir.display()))?;
+
+            let model = model_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| model_dir.display().to_string());
+            log::info!("Loaded local embedding model {} ({})", model, version);
+            Ok(Self {
+                model,
+                version,
+                embedding: Mutex::new(embedding),
+            })
+        }
+    }
+
+    impl EmbeddingProvider for LocalEmbedder {
+        fn model(&self) -> &str {
+            &self.model
+        }
+
+        fn model_version(&self) -> &str {
+            &self.version
+        }
+
+        fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            self.embedding
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Embedding model lock poisoned"))?
+                .embed(texts, Some(self.batch_size()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+
+    #[test]
+    fn test_embedding_backend_per_project() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        assert_eq!(get_embedding_settings(&conn, "/p").unwrap().backend, None);
+        assert!(project_provider(&conn, "/p").unwrap().is_none());
+
+        let http = EmbeddingBackend::Http {
+            endpoint: "http://localhost:11434/v1/".to_string(),
+            model: "nomic-embed-text".to_string(),
+            api_key_env: None,
+        };
+        let settings = set_embedding_backend(&conn, "/p", Some(http.clone())).unwrap();
+        assert_eq!(settings.backend, Some(http));
+        assert_eq!(
+            get_embedding_settings(&conn, "/other").unwrap().backend,
+            None
+        );
+
+        let provider = project_provider(&conn, "/p").unwrap().unwrap();
+        assert_eq!(provider.model(), "nomic-embed-text");
+        assert_eq!(provider.model_version(), "http://localhost:11434/v1");
+
+        let local = EmbeddingBackend::Local {
+            model_dir: "/models/all-MiniLM-L6-v2".to_string(),
+        };
+        let result = set_embedding_backend(&conn, "/p", Some(local));
+        assert_eq!(result.is_ok(), LOCAL_EMBEDDINGS_AVAILABLE);
+
+        set_embedding_backend(&conn, "/p", None).unwrap();
+        assert!(project_provider(&conn, "/p").unwrap().is_none());
+    }
+}
@@ -0,0 +1,444 @@
+use super::read_only::ensure_index_writable;
+use super::storage::{row_timestamp, CHUNK_CONTENT};
+use super::types::{ChunkEmbedding, ChunkType, EmbeddingRefreshReport, SemanticMatch};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Textos por llamada al proveedor si no indica otro tamaño de lote
+pub const DEFAULT_EMBEDDING_BATCH: usize = 64;
+
+/// Modelo que calcula los embeddings. `model` y `model_version` quedan guardados con
+/// cada vector: al cambiar cualquiera de los dos se recalculan los embeddings del modelo
+/// anterior
+pub trait EmbeddingProvider: Send {
+    fn model(&self) -> &str;
+    fn model_version(&self) -> &str;
+
+    /// Textos por llamada a [`Self::embed`]
+    fn batch_size(&self) -> usize {
+        DEFAULT_EMBEDDING_BATCH
+    }
+
+    /// Un vector por texto, en el mismo orden
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// El proyecto tiene al menos un embedding guardado
+pub fn has_embeddings(conn: &Connection, project_path: &str) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM chunk_embeddings WHERE project_path = ?1)",
+        params![project_path],
+        |row| row.get(0),
+    )?)
+}
+
+/// Recalcula solo los embeddings que hacen falta: chunks vigentes (la última versión
+/// de cada archivo y entidad) sin embedding, con otro hash de contenido o calculados
+/// con otro modelo o versión. Los de chunks borrados o reemplazados se eliminan.
+/// Las llamadas al proveedor se agrupan en lotes de [`EmbeddingProvider::batch_size`]
+pub fn refresh_embeddings(
+    conn: &Connection,
+    project_path: &str,
+    provider: &dyn EmbeddingProvider,
+) -> Result<EmbeddingRefreshReport> {
+    ensure_index_writable(conn, project_path)?;
+    let (model, model_version) = (provider.model(), provider.model_version());
+    let current = current_chunks(conn, project_path)?;
+    let stored = stored_embeddings(conn, project_path)?;
+
+    let mut report = EmbeddingRefreshReport {
+        project_path: project_path.to_string(),
+        model: model.to_string(),
+        model_version: model_version.to_string(),
+        chunks_checked: current.len(),
+        ..Default::default()
+    };
+
+    let current_ids: BTreeSet<i64> = current.iter().map(|(id, _)| *id).collect();
+    for chunk_id in stored.keys().filter(|id| !current_ids.contains(id)) {
+        conn.execute(
+            "DELETE FROM chunk_embeddings WHERE chunk_id = ?1",
+            params![chunk_id],
+        )?;
+        report.removed += 1;
+    }
+
+    let pending: Vec<&(i64, String)> = current
+        .iter()
+        .filter(|(id, hash)| match stored.get(id) {
+            Some((stored_hash, stored_model, stored_version)) => {
+                stored_hash != hash || stored_model != model || stored_version != model_version
+            }
+            None => true,
+        })
+        .collect();
+    report.unchanged = current.len() - pending.len();
+
+    for batch in pending.chunks(provider.batch_size().max(1)) {
+        let contents = batch
+            .iter()
+            .map(|(id, _)| chunk_content(conn, *id))
+            .collect::<Result<Vec<_>>>()?;
+        let texts: Vec<&str> = contents.iter().map(String::as_str).collect();
+        let vectors = provider.embed(&texts)?;
+        report.batches += 1;
+        if vectors.len() != batch.len() {
+            bail!(
+                "Embedding provider returned {} vectors for {} texts",
+                vectors.len(),
+                batch.len()
+            );
+        }
+
+        let now = Utc::now().to_rfc3339();
+        for ((chunk_id, content_hash), vector) in batch.iter().zip(vectors) {
+            conn.execute(
+                "INSERT INTO chunk_embeddings
+                     (chunk_id, project_path, content_hash, model, model_version, dimensions, vector, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT(chunk_id) DO UPDATE SET
+                     content_hash = excluded.content_hash, model = excluded.model,
+                     model_version = excluded.model_version, dimensions = excluded.dimensions,
+                     vector = excluded.vector, updated_at = excluded.updated_at",
+                params![
+                    chunk_id,
+                    project_path,
+                    content_hash,
+                    model,
+                    model_version,
+                    vector.len() as i64,
+                    encode_vector(&vector),
+                    now
+                ],
+            )?;
+            report.embedded += 1;
+        }
+    }
+
+    log::info!(
+        "Embeddings of {} ({} {}): {} embedded in {} batches, {} unchanged, {} removed",
+        project_path,
+        model,
+        model_version,
+        report.embedded,
+        report.batches,
+        report.unchanged,
+        report.removed
+    );
+    Ok(report)
+}
+
+/// Embedding guardado de un chunk
+pub fn get_embedding(conn: &Connection, chunk_id: i64) -> Result<Option<ChunkEmbedding>> {
+    Ok(conn
+        .query_row(
+            "SELECT chunk_id, content_hash, model, model_version, vector, updated_at
+             FROM chunk_embeddings WHERE chunk_id = ?1",
+            params![chunk_id],
+            |row| {
+                Ok(ChunkEmbedding {
+                    chunk_id: row.get(0)?,
+                    content_hash: row.get(1)?,
+                    model: row.get(2)?,
+                    model_version: row.get(3)?,
+                    vector: decode_vector(&row.get::<_, Vec<u8>>(4)?),
+                    updated_at: row_timestamp(row, 5)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+/// Vector de una consulta con el modelo que lo calculó
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryEmbedding {
+    pub model: String,
+    pub model_version: String,
+    pub vector: Vec<f32>,
+}
+
+/// Chunks vigentes más parecidos a `query` (similitud coseno), de mayor a menor. Solo
+/// compara con los embeddings calculados con el mismo modelo y versión que `provider`
+pub fn semantic_search(
+    conn: &Connection,
+    project_path: &str,
+    provider: &dyn EmbeddingProvider,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SemanticMatch>> {
+    let query = embed_query(provider, query)?;
+    search_embeddings(conn, project_path, &query, limit)
+}
+
+/// Embebe la consulta de una búsqueda; no toca la base, así la llamada al proveedor
+/// puede hacerse fuera del escritor
+pub fn embed_query(provider: &dyn EmbeddingProvider, query: &str) -> Result<QueryEmbedding> {
+    let vector = provider
+        .embed(&[query])?
+        .into_iter()
+        .next()
+        .context("Embedding provider returned no vector for the query")?;
+    Ok(QueryEmbedding {
+        model: provider.model().to_string(),
+        model_version: provider.model_version().to_string(),
+        vector,
+    })
+}
+
+/// Parte de solo lectura de [`semantic_search`]: compara la consulta ya embebida con
+/// los embeddings guardados de su mismo modelo y versión
+pub fn search_embeddings(
+    conn: &Connection,
+    project_path: &str,
+    query: &QueryEmbedding,
+    limit: usize,
+) -> Result<Vec<SemanticMatch>> {
+    let mut stmt = conn.prepare(
+        "SELECT e.chunk_id, c.chunk_type, c.file_path, c.entity_name, e.vector
+         FROM chunk_embeddings e JOIN chunks c ON c.id = e.chunk_id
+         WHERE e.project_path = ?1 AND e.model = ?2 AND e.model_version = ?3
+           AND c.deleted_at IS NULL",
+    )?;
+    let rows = stmt
+        .query_map(
+            params![project_path, query.model, query.model_version],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Vec<u8>>(4)?,
+                ))
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut matches: Vec<SemanticMatch> = rows
+        .into_iter()
+        .filter_map(|(chunk_id, chunk_type, file_path, entity_name, vector)| {
+            Some(SemanticMatch {
+                chunk_id,
+                chunk_type: ChunkType::from_str(&chunk_type)?,
+                file_path,
+                entity_name,
+                score: cosine_similarity(&query.vector, &decode_vector(&vector)),
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.chunk_id.cmp(&b.chunk_id))
+    });
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+/// (id, hash) de la última versión de cada chunk vigente: los chunks de un mismo
+/// archivo, tipo y entidad son versiones del mismo; los que no tienen archivo
+/// (commits, módulos) son todos distintos
+fn current_chunks(conn: &Connection, project_path: &str) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, chunk_type, file_path, entity_name, content_hash FROM chunks
+         WHERE project_path = ?1 AND deleted_at IS NULL
+         ORDER BY updated_at DESC, id DESC",
+    )?;
+    let rows = stmt
+        .query_map(params![project_path], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut seen = BTreeSet::new();
+    let mut chunks = Vec::new();
+    for (id, chunk_type, file_path, entity_name, content_hash) in rows {
+        if let Some(file_path) = file_path {
+            if !seen.insert((chunk_type, file_path, entity_name)) {
+                continue;
+            }
+        }
+        chunks.push((id, content_hash));
+    }
+    chunks.sort();
+    Ok(chunks)
+}
+
+/// chunk_id -> (hash, modelo, versión) de los embeddings guardados del proyecto
+fn stored_embeddings(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<BTreeMap<i64, (String, String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT chunk_id, content_hash, model, model_version FROM chunk_embeddings
+         WHERE project_path = ?1",
+    )?;
+    let rows = stmt
+        .query_map(params![project_path], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get(3)?)))
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(rows)
+}
+
+fn chunk_content(conn: &Connection, chunk_id: i64) -> Result<String> {
+    Ok(conn.query_row(
+        &format!("SELECT {} FROM chunks WHERE id = ?1", CHUNK_CONTENT),
+        params![chunk_id],
+        |row| row.get(0),
+    )?)
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// 0 si los vectores tienen distinta dimensión o alguno es nulo
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        return 0.0;
+    }
+    a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>() / norms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+    use crate::tombstones::delete_file;
+
+    use std::sync::Mutex;
+
+    /// Proveedor de prueba: el vector es la longitud del texto; registra cada lote
+    struct FakeProvider {
+        version: &'static str,
+        batches: Mutex<Vec<usize>>,
+    }
+
+    impl EmbeddingProvider for FakeProvider {
+        fn model(&self) -> &str {
+            "fake"
+        }
+
+        fn model_version(&self) -> &str {
+            self.version
+        }
+
+        fn batch_size(&self) -> usize {
+            2
+        }
+
+        fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            self.batches.lock().unwrap().push(texts.len());
+            Ok(texts.iter().map(|t| vec![t.len() as f32, 1.0]).collect())
+        }
+    }
+
+    fn provider(version: &'static str) -> FakeProvider {
+        FakeProvider {
+            version,
+            batches: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn insert_source(conn: &Connection, file_path: &str, content: &str) -> i64 {
+        chunk().file(file_path).content(content).insert(conn)
+    }
+
+    #[test]
+    fn test_refresh_only_embeds_changed_chunks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        for (file, content) in [
+            ("a.rs", "fn a() {}"),
+            ("b.rs", "fn b() {}"),
+            ("c.rs", "fn c() {}"),
+        ] {
+            insert_source(&conn, file, content);
+        }
+        assert!(!has_embeddings(&conn, "/p").unwrap());
+
+        let v1 = provider("1");
+        let report = refresh_embeddings(&conn, "/p", &v1).unwrap();
+        assert_eq!((report.embedded, report.batches), (3, 2));
+        assert_eq!(*v1.batches.lock().unwrap(), vec![2, 1]);
+        assert!(has_embeddings(&conn, "/p").unwrap());
+
+        // Sin cambios no se llama al proveedor
+        let report = refresh_embeddings(&conn, "/p", &v1).unwrap();
+        assert_eq!(
+            (report.embedded, report.unchanged, report.batches),
+            (0, 3, 0)
+        );
+
+        // Nueva versión de a.rs y c.rs borrado: solo se embebe la nueva versión
+        let a2 = insert_source(&conn, "a.rs", "fn a() { changed(); }");
+        delete_file(&conn, "/p", "c.rs").unwrap();
+        let report = refresh_embeddings(&conn, "/p", &v1).unwrap();
+        assert_eq!(report.chunks_checked, 2);
+        assert_eq!(
+            (report.embedded, report.unchanged, report.removed),
+            (1, 1, 2)
+        );
+        let embedding = get_embedding(&conn, a2).unwrap().unwrap();
+        assert_eq!(embedding.vector, vec![21.0, 1.0]);
+        assert_eq!(embedding.model_version, "1");
+
+        // Cambiar de versión del modelo recalcula todo lo del modelo anterior
+        let v2 = provider("2");
+        let report = refresh_embeddings(&conn, "/p", &v2).unwrap();
+        assert_eq!((report.embedded, report.unchanged), (2, 0));
+        assert_eq!(
+            get_embedding(&conn, a2).unwrap().unwrap().model_version,
+            "2"
+        );
+    }
+
+    #[test]
+    fn test_semantic_search_ranks_by_similarity() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let short = insert_source(&conn, "a.rs", "fn a() {}");
+        let long = insert_source(&conn, "b.rs", &"x".repeat(100));
+        refresh_embeddings(&conn, "/p", &provider("1")).unwrap();
+
+        // El vector de prueba es [longitud, 1]: la consulta se parece más al texto largo
+        let matches = semantic_search(&conn, "/p", &provider("1"), &"y".repeat(90), 10).unwrap();
+        let ids: Vec<i64> = matches.iter().map(|m| m.chunk_id).collect();
+        assert_eq!(ids, vec![long, short]);
+        assert!(matches[0].score > matches[1].score);
+        assert_eq!(matches[0].file_path.as_deref(), Some("b.rs"));
+
+        assert_eq!(
+            semantic_search(&conn, "/p", &provider("1"), "y", 1)
+                .unwrap()
+                .len(),
+            1
+        );
+        // Otra versión del modelo no compara con los vectores anteriores
+        assert!(semantic_search(&conn, "/p", &provider("2"), "y", 10)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,374 @@
+use super::resolver::{normalize_path, parent_dir};
+use super::types::EntryPoint;
+use anyhow::Result;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use std::collections::BTreeSet;
+
+/// Ejecutable declarado o implícito en un manifiesto (bin de Cargo, `bin` de
+/// package.json, scripts de pyproject)
+pub const ENTRY_KIND_BIN: &str = "bin";
+/// Función `main` o bloque `if __name__ == "__main__"` / `require.main === module`
+pub const ENTRY_KIND_MAIN: &str = "main";
+/// Ruta HTTP registrada (`route:GET /users`)
+pub const ENTRY_KIND_ROUTE: &str = "route";
+/// Handler exportado (lambdas, route handlers de Next.js...)
+pub const ENTRY_KIND_HANDLER: &str = "handler";
+/// Comando invocable desde fuera (`#[tauri::command]`)
+pub const ENTRY_KIND_COMMAND: &str = "command";
+
+const HTTP_METHODS: &str = "get|post|put|delete|patch|head|options|all";
+
+/// Detecta heurísticamente los puntos de entrada de un archivo. Cada uno se representa
+/// como `tipo:nombre` (ej: `main:main`, `route:GET /users`, `bin:opcode`); `bin_name` es
+/// el ejecutable que el manifiesto del proyecto asocia al archivo, si hay uno
+pub fn detect_entry_points(
+    file_path: &str,
+    content: &str,
+    language: &str,
+    bin_name: Option<&str>,
+) -> Vec<String> {
+    let mut entries = Vec::new();
+    if let Some(name) = bin_name {
+        entries.push(format!("{}:{}", ENTRY_KIND_BIN, name));
+    }
+
+    match language {
+        "rust" => {
+            let main_re = Regex::new(r"(?m)^\s*(?:pub\s+)?(?:async\s+)?fn\s+main\s*\(").unwrap();
+            if main_re.is_match(content) {
+                entries.push(format!("{}:main", ENTRY_KIND_MAIN));
+            }
+            let command_re = Regex::new(
+                r"#\[tauri::command[^\]]*\]\s*(?:#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?fn\s+(\w+)",
+            )
+            .unwrap();
+            for cap in command_re.captures_iter(content) {
+                entries.push(format!("{}:{}", ENTRY_KIND_COMMAND, &cap[1]));
+            }
+            // actix-web / rocket: #[get("/path")]
+            let attr_re = Regex::new(&format!(r#"#\[({})\(\s*"([^"]+)""#, HTTP_METHODS)).unwrap();
+            for cap in attr_re.captures_iter(content) {
+                entries.push(route(&cap[1], &cap[2]));
+            }
+            // axum: .route("/path", get(handler))
+            let axum_re = Regex::new(&format!(
+                r#"\.route\(\s*"([^"]+)"\s*,\s*({})\("#,
+                HTTP_METHODS
+            ))
+            .unwrap();
+            for cap in axum_re.captures_iter(content) {
+                entries.push(route(&cap[2], &cap[1]));
+            }
+        }
+        "javascript" | "typescript" => {
+            if content.contains("require.main === module") {
+                entries.push(format!("{}:require.main", ENTRY_KIND_MAIN));
+            }
+            // express / fastify / koa-router: app.get('/path', ...)
+            let route_re = Regex::new(&format!(
+                r#"\b(?:app|router|server|fastify|api)\.({})\(\s*['"`]([^'"`]+)['"`]"#,
+                HTTP_METHODS
+            ))
+            .unwrap();
+            for cap in route_re.captures_iter(content) {
+                entries.push(route(&cap[1], &cap[2]));
+            }
+            // Handlers exportados: lambdas y route handlers de Next.js (export function GET)
+            let export_re = Regex::new(
+                r"(?m)^\s*export\s+(?:async\s+)?(?:function\s*\*?\s*|const\s+|let\s+)(\w*[Hh]andler|GET|POST|PUT|DELETE|PATCH|HEAD|OPTIONS)\b",
+            )
+            .unwrap();
+            let exports_re =
+                Regex::new(r"(?m)^\s*(?:module\.)?exports\.(\w*[Hh]andler)\s*=").unwrap();
+            for cap in export_re
+                .captures_iter(content)
+                .chain(exports_re.captures_iter(content))
+            {
+                entries.push(format!("{}:{}", ENTRY_KIND_HANDLER, &cap[1]));
+            }
+        }
+        "python" => {
+            let main_re = Regex::new(r#"(?m)^if\s+__name__\s*==\s*['"]__main__['"]\s*:"#).unwrap();
+            if main_re.is_match(content) {
+                entries.push(format!("{}:__main__", ENTRY_KIND_MAIN));
+            }
+            // flask / fastapi: @app.route("/path"), @router.get("/path")
+            let route_re = Regex::new(&format!(
+                r#"(?m)^\s*@\w+\.(route|{})\(\s*['"]([^'"]+)['"]"#,
+                HTTP_METHODS
+            ))
+            .unwrap();
+            for cap in route_re.captures_iter(content) {
+                let method = if &cap[1] == "route" { "any" } else { &cap[1] };
+                entries.push(route(method, &cap[2]));
+            }
+            let handler_re =
+                Regex::new(r"(?m)^(?:async\s+)?def\s+(\w*handler)\s*\(\s*event\b").unwrap();
+            for cap in handler_re.captures_iter(content) {
+                entries.push(format!("{}:{}", ENTRY_KIND_HANDLER, &cap[1]));
+            }
+        }
+        _ => {}
+    }
+
+    let mut seen = BTreeSet::new();
+    entries.retain(|entry| seen.insert(entry.clone()));
+    if !entries.is_empty() {
+        log::debug!("Entry points in {}: {:?}", file_path, entries);
+    }
+    entries
+}
+
+fn route(method: &str, path: &str) -> String {
+    format!("{}:{} {}", ENTRY_KIND_ROUTE, method.to_uppercase(), path)
+}
+
+/// Ejecutables que declara un manifiesto (`Cargo.toml`, `package.json`, `pyproject.toml`)
+/// como pares (archivo relativo al proyecto, nombre del ejecutable). Incluye los bins
+/// implícitos de Cargo (`src/main.rs`, `src/bin/*.rs`) presentes en `files`
+pub fn parse_bin_targets(
+    manifest_path: &str,
+    content: &str,
+    files: &BTreeSet<String>,
+) -> Vec<(String, String)> {
+    let dir = parent_dir(manifest_path);
+    let name = manifest_path.rsplit('/').next().unwrap_or(manifest_path);
+    let mut targets = Vec::new();
+
+    match name {
+        "Cargo.toml" => {
+            let Ok(manifest) = content.parse::<toml::Table>() else {
+                return targets;
+            };
+            let package = manifest
+                .get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(|n| n.as_str());
+            if let Some(package) = package {
+                let main = normalize_path(&dir, "src/main.rs");
+                if files.contains(&main) {
+                    targets.push((main, package.to_string()));
+                }
+            }
+            let bin_dir = format!("{}/", normalize_path(&dir, "src/bin"));
+            for file in files.range(bin_dir.clone()..) {
+                let Some(rest) = file.strip_prefix(&bin_dir) else {
+                    break;
+                };
+                let bin = match rest.split_once('/') {
+                    Some((bin, "main.rs")) => bin,
+                    None => match rest.strip_suffix(".rs") {
+                        Some(bin) => bin,
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                targets.push((file.clone(), bin.to_string()));
+            }
+            for bin in manifest
+                .get("bin")
+                .and_then(|b| b.as_array())
+                .into_iter()
+                .flatten()
+            {
+                let (Some(bin_name), Some(path)) = (
+                    bin.get("name").and_then(|n| n.as_str()),
+                    bin.get("path").and_then(|p| p.as_str()),
+                ) else {
+                    continue;
+                };
+                targets.push((normalize_path(&dir, path), bin_name.to_string()));
+            }
+        }
+        "package.json" => {
+            let Ok(manifest) = serde_json::from_str::<serde_json::Value>(content) else {
+                return targets;
+            };
+            match manifest.get("bin") {
+                Some(serde_json::Value::String(path)) => {
+                    let package = manifest.get("name").and_then(|n| n.as_str()).unwrap_or("");
+                    // Un bin único se llama como el paquete, sin el scope
+                    let bin_name = package.rsplit('/').next().unwrap_or(package);
+                    targets.push((normalize_path(&dir, path), bin_name.to_string()));
+                }
+                Some(serde_json::Value::Object(bins)) => {
+                    for (bin_name, path) in bins {
+                        if let Some(path) = path.as_str() {
+                            targets.push((normalize_path(&dir, path), bin_name.clone()));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        "pyproject.toml" => {
+            let Ok(manifest) = content.parse::<toml::Table>() else {
+                return targets;
+            };
+            let scripts = [
+                manifest.get("project").and_then(|p| p.get("scripts")),
+                manifest
+                    .get("tool")
+                    .and_then(|t| t.get("poetry"))
+                    .and_then(|p| p.get("scripts")),
+            ];
+            for (bin_name, target) in scripts
+                .into_iter()
+                .flatten()
+                .filter_map(|s| s.as_table())
+                .flatten()
+            {
+                // "paquete.modulo:funcion" -> paquete/modulo.py (o src/..., o __init__.py)
+                let Some(module) = target.as_str().and_then(|t| t.split(':').next()) else {
+                    continue;
+                };
+                let module = module.trim().replace('.', "/");
+                let candidates = [
+                    format!("{}.py", module),
+                    format!("{}/__init__.py", module),
+                    format!("src/{}.py", module),
+                    format!("src/{}/__init__.py", module),
+                ];
+                if let Some(file) = candidates
+                    .iter()
+                    .map(|candidate| normalize_path(&dir, candidate))
+                    .find(|file| files.contains(file))
+                {
+                    targets.push((file, bin_name.clone()));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    targets
+}
+
+/// Puntos de entrada registrados en los chunks de callgraph vigentes del proyecto,
+/// ordenados por archivo
+pub fn get_entry_points(conn: &Connection, project_path: &str) -> Result<Vec<EntryPoint>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.file_path, e.value
+         FROM chunks c,
+              json_each(CASE WHEN json_valid(c.metadata) THEN c.metadata END, '$.entry_points') e
+         WHERE c.project_path = ?1 AND c.chunk_type = 'callgraph' AND c.file_path IS NOT NULL
+           AND c.deleted_at IS NULL
+         ORDER BY c.file_path, e.key",
+    )?;
+    let rows = stmt
+        .query_map(params![project_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut seen = BTreeSet::new();
+    Ok(rows
+        .into_iter()
+        .filter(|row| seen.insert(row.clone()))
+        .map(|(file_path, entry)| {
+            let (kind, name) = entry.split_once(':').unwrap_or(("", entry.as_str()));
+            EntryPoint {
+                file_path,
+                kind: kind.to_string(),
+                name: name.to_string(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_entry_points() {
+        let rust = r#"
+#[tauri::command]
+pub async fn get_user(id: u64) -> User { todo!() }
+
+#[get("/users/{id}")]
+async fn show() {}
+
+fn main() {
+    Router::new().route("/health", get(health));
+}
+"#;
+        assert_eq!(
+            detect_entry_points("src/main.rs", rust, "rust", Some("server")),
+            vec![
+                "bin:server",
+                "main:main",
+                "command:get_user",
+                "route:GET /users/{id}",
+                "route:GET /health",
+            ]
+        );
+
+        let js = "app.post('/login', login);\nexport async function handler(event) {}\nexport function GET() {}\nexports.uploadHandler = () => {};\n";
+        assert_eq!(
+            detect_entry_points("src/api.ts", js, "typescript", None),
+            vec![
+                "route:POST /login",
+                "handler:handler",
+                "handler:GET",
+                "handler:uploadHandler",
+            ]
+        );
+
+        let py = "@app.route(\"/\")\ndef index(): ...\n\n@router.delete('/items/{id}')\ndef remove(): ...\n\ndef lambda_handler(event, context): ...\n\nif __name__ == '__main__':\n    main()\n";
+        assert_eq!(
+            detect_entry_points("app.py", py, "python", None),
+            vec![
+                "main:__main__",
+                "route:ANY /",
+                "route:DELETE /items/{id}",
+                "handler:lambda_handler",
+            ]
+        );
+
+        assert!(detect_entry_points("src/lib.rs", "pub fn helper() {}", "rust", None).is_empty());
+    }
+
+    #[test]
+    fn test_parse_bin_targets() {
+        let files: BTreeSet<String> = [
+            "cli/src/main.rs",
+            "cli/src/bin/migrate.rs",
+            "cli/src/bin/seed/main.rs",
+            "cli/src/bin/seed/data.rs",
+            "cli/tools/gen.rs",
+            "pkg/bin/cli.js",
+            "src/acme/cli.py",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+
+        let cargo =
+            "[package]\nname = \"acme\"\n\n[[bin]]\nname = \"gen\"\npath = \"tools/gen.rs\"\n";
+        let mut targets = parse_bin_targets("cli/Cargo.toml", cargo, &files);
+        targets.sort();
+        assert_eq!(
+            targets,
+            vec![
+                ("cli/src/bin/migrate.rs".to_string(), "migrate".to_string()),
+                ("cli/src/bin/seed/main.rs".to_string(), "seed".to_string()),
+                ("cli/src/main.rs".to_string(), "acme".to_string()),
+                ("cli/tools/gen.rs".to_string(), "gen".to_string()),
+            ]
+        );
+
+        let package = r#"{"name": "@acme/tool", "bin": "./bin/cli.js"}"#;
+        assert_eq!(
+            parse_bin_targets("pkg/package.json", package, &files),
+            vec![("pkg/bin/cli.js".to_string(), "tool".to_string())]
+        );
+
+        let pyproject = "[project]\nname = \"acme\"\n\n[project.scripts]\nacme = \"acme.cli:main\"\nmissing = \"acme.nope:main\"\n";
+        assert_eq!(
+            parse_bin_targets("pyproject.toml", pyproject, &files),
+            vec![("src/acme/cli.py".to_string(), "acme".to_string())]
+        );
+    }
+}
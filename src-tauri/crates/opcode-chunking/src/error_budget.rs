@@ -0,0 +1,167 @@
+use super::types::{ChunkType, ErrorBudget, IndexingError, IndexingErrorKind, IndexingStatus};
+
+/// Clasifica un error por la primera causa reconocible de su cadena
+pub fn error_kind(error: &anyhow::Error) -> IndexingErrorKind {
+    for cause in error.chain() {
+        if cause.downcast_ref::<rusqlite::Error>().is_some() {
+            return IndexingErrorKind::Storage;
+        }
+        if cause.downcast_ref::<git2::Error>().is_some() {
+            return IndexingErrorKind::Git;
+        }
+        if cause.downcast_ref::<std::io::Error>().is_some() {
+            return IndexingErrorKind::Io;
+        }
+    }
+    IndexingErrorKind::Other
+}
+
+/// True si el error (o alguna de sus causas) viene de SQLite
+pub fn is_storage_error(error: &anyhow::Error) -> bool {
+    error_kind(error) == IndexingErrorKind::Storage
+}
+
+/// Registro estructurado de un error de indexación
+pub fn indexing_error(
+    file_path: Option<&str>,
+    chunk_type: Option<ChunkType>,
+    error: &anyhow::Error,
+) -> IndexingError {
+    IndexingError {
+        file_path: file_path.map(str::to_string),
+        chunk_type,
+        kind: error_kind(error),
+        message: format!("{:#}", error),
+    }
+}
+
+/// Lleva la cuenta de los errores de una indexación frente a su `ErrorBudget`
+pub struct ErrorTracker {
+    budget: ErrorBudget,
+    file_errors: usize,
+    abort_reason: Option<String>,
+}
+
+impl ErrorTracker {
+    pub fn new(budget: &ErrorBudget) -> Self {
+        Self {
+            budget: budget.clone(),
+            file_errors: 0,
+            abort_reason: None,
+        }
+    }
+
+    /// Registra el error de un archivo en `errors`. Retorna true si hay que abortar
+    pub fn file_error(
+        &mut self,
+        errors: &mut Vec<IndexingError>,
+        file_path: &str,
+        chunk_type: Option<ChunkType>,
+        error: &anyhow::Error,
+    ) -> bool {
+        let record = indexing_error(Some(file_path), chunk_type, error);
+        self.file_errors += 1;
+
+        if self.abort_reason.is_none() {
+            if self.budget.fail_on_storage_error && record.kind == IndexingErrorKind::Storage {
+                self.abort_reason = Some(format!("Storage error in {}: {:#}", file_path, error));
+            } else if self
+                .budget
+                .max_file_errors
+                .is_some_and(|max| self.file_errors >= max)
+            {
+                self.abort_reason = Some(format!(
+                    "Error budget exhausted after {} file errors",
+                    self.file_errors
+                ));
+            }
+        }
+        errors.push(record);
+        self.is_aborted()
+    }
+
+    /// Registra el fallo de una fase completa (ya reportado en `errors`).
+    /// Solo aborta si es un error de la base
+    pub fn phase_error(&mut self, phase: &str, error: &anyhow::Error) -> bool {
+        if self.abort_reason.is_none()
+            && self.budget.fail_on_storage_error
+            && is_storage_error(error)
+        {
+            self.abort_reason = Some(format!("Storage error in {}: {:#}", phase, error));
+        }
+        self.is_aborted()
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.abort_reason.is_some()
+    }
+
+    /// Estado final y motivo del aborto, si lo hubo
+    pub fn finish(self) -> (IndexingStatus, Option<String>) {
+        match self.abort_reason {
+            Some(reason) => {
+                log::warn!("[Chunking] Indexing aborted: {}", reason);
+                (IndexingStatus::Aborted, Some(reason))
+            }
+            None => (IndexingStatus::Completed, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    #[test]
+    fn test_aborts_when_file_budget_is_exhausted() {
+        let mut tracker = ErrorTracker::new(&ErrorBudget {
+            max_file_errors: Some(2),
+            fail_on_storage_error: true,
+        });
+        let mut errors = Vec::new();
+
+        assert!(!tracker.file_error(&mut errors, "a.rs", None, &anyhow!("bad")));
+        assert!(tracker.file_error(&mut errors, "b.rs", Some(ChunkType::Ast), &anyhow!("bad")));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].file_path.as_deref(), Some("b.rs"));
+        assert_eq!(errors[1].chunk_type, Some(ChunkType::Ast));
+        assert_eq!(errors[1].kind, IndexingErrorKind::Other);
+
+        let (status, reason) = tracker.finish();
+        assert_eq!(status, IndexingStatus::Aborted);
+        assert!(reason.unwrap().contains("2 file errors"));
+    }
+
+    #[test]
+    fn test_storage_errors_fail_fast() {
+        let storage_error = || {
+            Err::<(), _>(rusqlite::Error::InvalidQuery)
+                .context("Failed to upsert chunk")
+                .unwrap_err()
+        };
+        assert!(is_storage_error(&storage_error()));
+        assert!(!is_storage_error(&anyhow!("Unsupported language: txt")));
+        let io_error = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .context("Failed to read file");
+        assert_eq!(error_kind(&io_error), IndexingErrorKind::Io);
+        assert_eq!(
+            indexing_error(None, None, &io_error).message,
+            "Failed to read file: entity not found"
+        );
+
+        let mut tracker = ErrorTracker::new(&ErrorBudget::default());
+        assert!(!tracker.phase_error("commit_history", &anyhow!("not a git repo")));
+        assert!(tracker.phase_error("raw_source", &storage_error()));
+
+        let mut tolerant = ErrorTracker::new(&ErrorBudget {
+            max_file_errors: None,
+            fail_on_storage_error: false,
+        });
+        let mut errors = Vec::new();
+        for _ in 0..500 {
+            assert!(!tolerant.file_error(&mut errors, "a.rs", None, &storage_error()));
+        }
+        assert_eq!(tolerant.finish().0, IndexingStatus::Completed);
+    }
+}
@@ -0,0 +1,406 @@
+use super::storage::{get_active_snapshot_id, get_error_logs, upsert_error_log};
+use super::types::{ErrorCluster, ErrorLog};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+/// Similitud mínima (Jaccard de shingles) para agrupar dos errores
+pub const DEFAULT_CLUSTER_SIMILARITY: f64 = 0.6;
+
+/// Tamaño de los shingles de tokens
+const SHINGLE_SIZE: usize = 3;
+
+/// Frames del stacktrace que participan en la firma
+const STACK_FRAMES: usize = 5;
+
+/// Ejemplos guardados por cluster además del representante
+const CLUSTER_EXAMPLES: usize = 3;
+
+/// Registra un error/log.
+/// Sin `snapshot_id` explícito se atribuye al snapshot activo del proyecto
+#[allow(clippy::too_many_arguments)]
+pub fn log_error(
+    conn: &Connection,
+    project_path: &str,
+    error_type: &str,
+    message: &str,
+    file_path: Option<&str>,
+    entity_name: Option<&str>,
+    stacktrace: Option<&str>,
+    snapshot_id: Option<i64>,
+) -> Result<i64> {
+    let snapshot_id = match snapshot_id {
+        Some(id) => Some(id),
+        None => get_active_snapshot_id(conn, project_path)?,
+    };
+    let error = ErrorLog {
+        id: None,
+        project_path: project_path.to_string(),
+        snapshot_id,
+        file_path: file_path.map(|s| s.to_string()),
+        entity_name: entity_name.map(|s| s.to_string()),
+        error_type: error_type.to_string(),
+        message: message.to_string(),
+        stacktrace: stacktrace.map(|s| s.to_string()),
+        severity: None,
+        occurrence_count: 1,
+        first_seen: Utc::now(),
+        last_seen: Utc::now(),
+        is_resolved: false,
+    };
+
+    upsert_error_log(conn, &error)
+}
+
+/// Registra un diagnóstico con severidad (lint, compilación)
+pub fn log_diagnostic(
+    conn: &Connection,
+    project_path: &str,
+    error_type: &str,
+    severity: &str,
+    message: &str,
+    file_path: Option<&str>,
+    entity_name: Option<&str>,
+) -> Result<i64> {
+    let error = ErrorLog {
+        id: None,
+        project_path: project_path.to_string(),
+        snapshot_id: get_active_snapshot_id(conn, project_path)?,
+        file_path: file_path.map(|s| s.to_string()),
+        entity_name: entity_name.map(|s| s.to_string()),
+        error_type: error_type.to_string(),
+        message: message.to_string(),
+        stacktrace: None,
+        severity: Some(severity.to_string()),
+        occurrence_count: 1,
+        first_seen: Utc::now(),
+        last_seen: Utc::now(),
+        is_resolved: false,
+    };
+
+    upsert_error_log(conn, &error)
+}
+
+/// Marca como resueltos los errores abiertos cuyo tipo empieza con `prefix`
+/// (ej: "lint:eslint:" antes de reimportar la salida de ESLint)
+pub fn resolve_errors_by_type_prefix(
+    conn: &Connection,
+    project_path: &str,
+    prefix: &str,
+) -> Result<usize> {
+    let count = conn.execute(
+        "UPDATE error_logs SET is_resolved = 1, resolved_at = ?3
+         WHERE project_path = ?1 AND is_resolved = 0 AND substr(error_type, 1, length(?2)) = ?2",
+        rusqlite::params![project_path, prefix, Utc::now().to_rfc3339()],
+    )?;
+    Ok(count)
+}
+
+/// Marca un error como resuelto
+pub fn resolve_error(conn: &Connection, error_id: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE error_logs SET is_resolved = 1, resolved_at = ?2 WHERE id = ?1",
+        rusqlite::params![error_id, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Obtiene errores activos (no resueltos) del proyecto
+pub fn get_active_errors(conn: &Connection, project_path: &str) -> Result<Vec<ErrorLog>> {
+    get_error_logs(conn, project_path, false)
+}
+
+/// Agrupa los errores del proyecto por similitud de mensaje y stacktrace
+pub fn get_error_clusters(
+    conn: &Connection,
+    project_path: &str,
+    include_resolved: bool,
+    similarity: f64,
+) -> Result<Vec<ErrorCluster>> {
+    let errors = get_error_logs(conn, project_path, include_resolved)?;
+    Ok(cluster_errors(errors, similarity))
+}
+
+/// Agrupa errores del mismo tipo cuyos shingles superan el umbral de similitud.
+/// El representante de cada grupo es el error con más ocurrencias
+pub fn cluster_errors(mut errors: Vec<ErrorLog>, similarity: f64) -> Vec<ErrorCluster> {
+    errors.sort_by(|a, b| {
+        b.occurrence_count
+            .cmp(&a.occurrence_count)
+            .then(b.last_seen.cmp(&a.last_seen))
+    });
+
+    let normalizer = SignatureNormalizer::new();
+    let mut groups: Vec<(HashSet<String>, Vec<ErrorLog>)> = Vec::new();
+
+    for error in errors {
+        let shingles = normalizer.shingles(&error);
+        let best = groups
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, members))| members[0].error_type == error.error_type)
+            .map(|(idx, (rep, _))| (idx, jaccard(rep, &shingles)))
+            .filter(|(_, score)| *score >= similarity)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match best {
+            Some((idx, _)) => groups[idx].1.push(error),
+            None => groups.push((shingles, vec![error])),
+        }
+    }
+
+    let mut clusters: Vec<ErrorCluster> = groups
+        .into_iter()
+        .map(|(_, members)| {
+            let representative = members[0].clone();
+            let mut seen_messages = HashSet::from([representative.message.clone()]);
+            let examples = members
+                .iter()
+                .skip(1)
+                .filter(|e| seen_messages.insert(e.message.clone()))
+                .take(CLUSTER_EXAMPLES)
+                .cloned()
+                .collect();
+
+            ErrorCluster {
+                error_type: representative.error_type.clone(),
+                examples,
+                error_ids: members.iter().filter_map(|e| e.id).collect(),
+                total_occurrences: members.iter().map(|e| e.occurrence_count as i64).sum(),
+                first_seen: members
+                    .iter()
+                    .map(|e| e.first_seen)
+                    .min()
+                    .unwrap_or(representative.first_seen),
+                last_seen: members
+                    .iter()
+                    .map(|e| e.last_seen)
+                    .max()
+                    .unwrap_or(representative.last_seen),
+                representative,
+            }
+        })
+        .collect();
+
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.total_occurrences));
+    clusters
+}
+
+/// Normaliza mensajes y stacktraces reemplazando las partes variables
+/// (números, direcciones, literales, rutas) antes de generar shingles
+struct SignatureNormalizer {
+    patterns: Vec<(Regex, &'static str)>,
+}
+
+impl SignatureNormalizer {
+    fn new() -> Self {
+        let patterns = [
+            (r#""[^"]*"|'[^']*'|`[^`]*`"#, " _val_ "),
+            (
+                r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}",
+                " _val_ ",
+            ),
+            (r"0x[0-9a-f]+", " _val_ "),
+            (r"(?:[a-z]:)?(?:[\w.-]*[/\\])+[\w.-]+", " _val_ "),
+            (r"\d+", " _num_ "),
+        ];
+        SignatureNormalizer {
+            patterns: patterns
+                .into_iter()
+                .map(|(pattern, placeholder)| (Regex::new(pattern).unwrap(), placeholder))
+                .collect(),
+        }
+    }
+
+    fn tokens(&self, text: &str) -> Vec<String> {
+        let mut normalized = text.to_lowercase();
+        for (re, placeholder) in &self.patterns {
+            normalized = re.replace_all(&normalized, *placeholder).into_owned();
+        }
+        normalized
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string())
+            .collect()
+    }
+
+    fn shingles(&self, error: &ErrorLog) -> HashSet<String> {
+        let mut tokens = self.tokens(&error.message);
+        if let Some(stacktrace) = &error.stacktrace {
+            let frames: Vec<&str> = stacktrace
+                .lines()
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty())
+                .take(STACK_FRAMES)
+                .collect();
+            tokens.extend(self.tokens(&frames.join("\n")));
+        }
+
+        if tokens.len() < SHINGLE_SIZE {
+            return HashSet::from([tokens.join(" ")]);
+        }
+        tokens.windows(SHINGLE_SIZE).map(|w| w.join(" ")).collect()
+    }
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{create_snapshot, init_chunk_database};
+    use crate::types::{Snapshot, SnapshotType};
+
+    fn snapshot(project_path: &str, version: i32) -> Snapshot {
+        Snapshot {
+            id: None,
+            project_path: project_path.to_string(),
+            snapshot_type: SnapshotType::Master,
+            parent_snapshot_id: None,
+            message: format!("V{}", version),
+            user_message: None,
+            changed_files: "[]".to_string(),
+            diff_summary: None,
+            metadata: None,
+            git_commit_hash: None,
+            git_tag: None,
+            git_branch: None,
+            version_major: version,
+            version_minor: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn error(id: i64, error_type: &str, message: &str, occurrences: i32) -> ErrorLog {
+        ErrorLog {
+            id: Some(id),
+            project_path: "/p".to_string(),
+            snapshot_id: None,
+            file_path: None,
+            entity_name: None,
+            error_type: error_type.to_string(),
+            message: message.to_string(),
+            stacktrace: None,
+            severity: None,
+            occurrence_count: occurrences,
+            first_seen: Utc::now(),
+            last_seen: Utc::now(),
+            is_resolved: false,
+        }
+    }
+
+    #[test]
+    fn test_cluster_similar_errors() {
+        let errors = vec![
+            error(
+                1,
+                "runtime",
+                "Failed to read file /tmp/a/config.json: permission denied (os error 13)",
+                2,
+            ),
+            error(
+                2,
+                "runtime",
+                "Failed to read file /home/u/b.json: permission denied (os error 13)",
+                5,
+            ),
+            error(
+                3,
+                "runtime",
+                "Failed to read file 'x.json': permission denied (os error 13)",
+                1,
+            ),
+            error(
+                4,
+                "runtime",
+                "Connection refused while contacting server at port 8080",
+                1,
+            ),
+            error(
+                5,
+                "panic",
+                "Failed to read file /tmp/a/config.json: permission denied (os error 13)",
+                1,
+            ),
+        ];
+
+        let clusters = cluster_errors(errors, DEFAULT_CLUSTER_SIMILARITY);
+        assert_eq!(clusters.len(), 3);
+
+        let main = &clusters[0];
+        assert_eq!(main.representative.id, Some(2));
+        assert_eq!(main.total_occurrences, 8);
+        assert_eq!(main.examples.len(), 2);
+        let mut ids = main.error_ids.clone();
+        ids.sort();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        // Mismo mensaje con otro tipo no se agrupa
+        assert!(clusters
+            .iter()
+            .any(|c| c.error_type == "panic" && c.error_ids == vec![5]));
+    }
+
+    #[test]
+    fn test_errors_attributed_to_active_snapshot() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        log_error(
+            &conn,
+            "/p",
+            "runtime",
+            "sin snapshot",
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let first = create_snapshot(&conn, &snapshot("/p", 1)).unwrap();
+        log_error(&conn, "/p", "runtime", "tras V1", None, None, None, None).unwrap();
+        let second = create_snapshot(&conn, &snapshot("/p", 2)).unwrap();
+        log_diagnostic(
+            &conn,
+            "/p",
+            "lint:clippy:x",
+            "warning",
+            "tras V2",
+            None,
+            None,
+        )
+        .unwrap();
+        log_error(
+            &conn,
+            "/p",
+            "runtime",
+            "explícito",
+            None,
+            None,
+            None,
+            Some(first),
+        )
+        .unwrap();
+
+        let errors = get_active_errors(&conn, "/p").unwrap();
+        let snapshot_of = |message: &str| {
+            errors
+                .iter()
+                .find(|e| e.message == message)
+                .map(|e| e.snapshot_id)
+                .unwrap()
+        };
+        assert_eq!(snapshot_of("sin snapshot"), None);
+        assert_eq!(snapshot_of("tras V1"), Some(first));
+        assert_eq!(snapshot_of("tras V2"), Some(second));
+        assert_eq!(snapshot_of("explícito"), Some(first));
+    }
+}
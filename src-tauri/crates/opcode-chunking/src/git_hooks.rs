@@ -0,0 +1,313 @@
+use super::reindex_files;
+use super::types::ChunkingResult;
+use anyhow::{bail, Context, Result};
+use git2::Repository;
+use rusqlite::Connection;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Archivo (dentro de `.git`) donde los hooks registran los archivos a reindexar
+pub const REINDEX_TRIGGER_FILE: &str = "opcode-reindex";
+
+const BLOCK_BEGIN: &str = "# >>> opcode reindex hook >>>";
+const BLOCK_END: &str = "# <<< opcode reindex hook <<<";
+
+/// Hooks instalados y el comando que lista los archivos modificados
+const HOOKS: &[(&str, &str)] = &[
+    (
+        "post-commit",
+        "git diff-tree --root --no-commit-id --name-only -r HEAD",
+    ),
+    ("post-merge", "git diff-tree --name-only -r ORIG_HEAD HEAD"),
+];
+
+/// Instala hooks post-commit/post-merge que registran los archivos modificados en
+/// `.git/opcode-reindex`, para reindexar aunque el commit se haga fuera de la app.
+/// Los hooks existentes se conservan: el bloque de opcode se agrega o se actualiza.
+/// Si algún hook existente no es un script sh/bash o termina con `exit`, el bloque
+/// no llegaría a ejecutarse: no se instala nada y se informa qué hooks lo impiden
+pub fn install_git_hooks(project_path: &str) -> Result<Vec<String>> {
+    let repo = Repository::open(project_path).context("Failed to open git repository")?;
+    let dir = hooks_dir(&repo);
+    std::fs::create_dir_all(&dir)?;
+
+    let mut existing = Vec::new();
+    let mut refused = Vec::new();
+    for (name, _) in HOOKS {
+        let script = std::fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|s| strip_block(&s));
+        if let Some(script) = &script {
+            if !can_append_to(script) {
+                refused.push(dir.join(name).display().to_string());
+            }
+        }
+        existing.push(script);
+    }
+    if !refused.is_empty() {
+        bail!(
+            "Existing hooks are not sh/bash scripts or end with exit, add the opcode block manually: {}",
+            refused.join(", ")
+        );
+    }
+
+    let mut installed = Vec::new();
+    for ((name, list_command), existing) in HOOKS.iter().zip(existing) {
+        let path = dir.join(name);
+        let block = hook_block(list_command);
+        let script = match existing {
+            Some(without) => format!("{}\n\n{}", without.trim_end(), block),
+            None => format!("#!/bin/sh\n\n{}", block),
+        };
+        std::fs::write(&path, script)?;
+        make_executable(&path)?;
+        installed.push(name.to_string());
+    }
+
+    Ok(installed)
+}
+
+/// Quita el bloque de opcode de los hooks; borra el archivo si no queda nada más
+pub fn uninstall_git_hooks(project_path: &str) -> Result<Vec<String>> {
+    let repo = Repository::open(project_path).context("Failed to open git repository")?;
+    let dir = hooks_dir(&repo);
+
+    let mut removed = Vec::new();
+    for (name, _) in HOOKS {
+        let path = dir.join(name);
+        let Ok(existing) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        if !existing.contains(BLOCK_BEGIN) {
+            continue;
+        }
+        let remaining = strip_block(&existing);
+        let only_shebang = remaining
+            .lines()
+            .all(|l| l.trim().is_empty() || l.starts_with("#!"));
+        if only_shebang {
+            std::fs::remove_file(&path)?;
+        } else {
+            std::fs::write(&path, format!("{}\n", remaining.trim_end()))?;
+        }
+        removed.push(name.to_string());
+    }
+
+    Ok(removed)
+}
+
+/// Ruta del archivo que escriben los hooks, o None si el proyecto no es un repo git
+pub fn reindex_trigger_path(project_path: &str) -> Option<PathBuf> {
+    Repository::open(project_path)
+        .ok()
+        .map(|repo| repo.path().join(REINDEX_TRIGGER_FILE))
+}
+
+/// Toma (y vacía) la lista de archivos registrada por los hooks
+pub fn take_reindex_trigger(project_path: &str) -> Result<Vec<String>> {
+    let repo = Repository::open(project_path).context("Failed to open git repository")?;
+    let trigger = repo.path().join(REINDEX_TRIGGER_FILE);
+    if !trigger.exists() {
+        return Ok(Vec::new());
+    }
+
+    // Renombrar primero: un hook que corra mientras tanto escribe en un archivo nuevo
+    let processing = repo
+        .path()
+        .join(format!("{}.processing", REINDEX_TRIGGER_FILE));
+    std::fs::rename(&trigger, &processing)?;
+    let content = std::fs::read_to_string(&processing).unwrap_or_default();
+    std::fs::remove_file(&processing)?;
+
+    let files: BTreeSet<String> = content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect();
+    Ok(files.into_iter().collect())
+}
+
+/// Reindexa los archivos registrados por los hooks desde la última llamada.
+/// Retorna None si no hay nada pendiente
+pub fn process_reindex_trigger(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Option<ChunkingResult>> {
+    let files = take_reindex_trigger(project_path)?;
+    if files.is_empty() {
+        return Ok(None);
+    }
+    reindex_files(conn, project_path, &files, None).map(Some)
+}
+
+/// Directorio de hooks, respetando `core.hooksPath`
+fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|c| c.get_path("core.hooksPath").ok());
+    match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo
+            .workdir()
+            .map(|w| w.join(&path))
+            .unwrap_or_else(|| repo.path().join(path)),
+        None => repo.path().join("hooks"),
+    }
+}
+
+fn hook_block(list_command: &str) -> String {
+    format!(
+        "{}\n# Registra los archivos modificados para que opcode los reindexe\n{} >> \"$(git rev-parse --git-dir)/{}\" 2>/dev/null || true\n{}\n",
+        BLOCK_BEGIN, list_command, REINDEX_TRIGGER_FILE, BLOCK_END
+    )
+}
+
+/// Un bloque agregado al final solo se ejecuta si el hook lo interpreta sh/bash
+/// y la última instrucción no termina el script antes de llegar a él
+fn can_append_to(script: &str) -> bool {
+    let shell = script.lines().next().is_some_and(|shebang| {
+        let interpreter: Vec<&str> = shebang
+            .trim_start_matches("#!")
+            .split_whitespace()
+            .collect();
+        let program = match interpreter.as_slice() {
+            [env, program, ..] if env.ends_with("/env") => *program,
+            [program, ..] => program.rsplit('/').next().unwrap_or(program),
+            [] => "",
+        };
+        shebang.starts_with("#!") && matches!(program, "sh" | "bash")
+    });
+    let last = script
+        .lines()
+        .map(str::trim)
+        .rfind(|l| !l.is_empty() && !l.starts_with('#'))
+        .unwrap_or("");
+    let exits = ["exit", "exec"]
+        .iter()
+        .any(|kw| last == *kw || last.starts_with(&format!("{} ", kw)));
+    shell && !exits
+}
+
+/// Contenido del hook sin el bloque de opcode
+fn strip_block(script: &str) -> String {
+    let mut result = String::new();
+    let mut inside = false;
+    for line in script.lines() {
+        if line.trim() == BLOCK_BEGIN {
+            inside = true;
+            continue;
+        }
+        if line.trim() == BLOCK_END {
+            inside = false;
+            continue;
+        }
+        if !inside {
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    result
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    permissions.set_mode(0o755);
+    std::fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_and_uninstall_hooks() {
+        let dir = std::env::temp_dir().join(format!("opcode-hooks-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        let hooks = repo.path().join("hooks");
+        std::fs::create_dir_all(&hooks).unwrap();
+        std::fs::write(hooks.join("post-merge"), "#!/bin/sh\nnpm install\n").unwrap();
+        let project = dir.to_str().unwrap();
+
+        install_git_hooks(project).unwrap();
+        install_git_hooks(project).unwrap();
+
+        let post_commit = std::fs::read_to_string(hooks.join("post-commit")).unwrap();
+        assert!(post_commit.starts_with("#!/bin/sh"));
+        assert!(post_commit.contains("diff-tree --root"));
+        assert_eq!(post_commit.matches(BLOCK_BEGIN).count(), 1);
+        let post_merge = std::fs::read_to_string(hooks.join("post-merge")).unwrap();
+        assert!(post_merge.contains("npm install"));
+        assert!(post_merge.contains("ORIG_HEAD HEAD"));
+        assert_eq!(post_merge.matches(BLOCK_BEGIN).count(), 1);
+
+        uninstall_git_hooks(project).unwrap();
+        assert!(!hooks.join("post-commit").exists());
+        assert_eq!(
+            std::fs::read_to_string(hooks.join("post-merge")).unwrap(),
+            "#!/bin/sh\nnpm install\n"
+        );
+
+        std::fs::write(
+            repo.path().join(REINDEX_TRIGGER_FILE),
+            "src/a.rs\nsrc/b.rs\nsrc/a.rs\n\n",
+        )
+        .unwrap();
+        assert_eq!(
+            take_reindex_trigger(project).unwrap(),
+            vec!["src/a.rs", "src/b.rs"]
+        );
+        assert!(take_reindex_trigger(project).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_install_refuses_hooks_that_would_skip_the_block() {
+        let dir = std::env::temp_dir().join(format!("opcode-hooks-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        let hooks = repo.path().join("hooks");
+        std::fs::create_dir_all(&hooks).unwrap();
+        let project = dir.to_str().unwrap();
+
+        let python = "#!/usr/bin/env python3\nprint('done')\n";
+        std::fs::write(hooks.join("post-commit"), python).unwrap();
+        let error = install_git_hooks(project).unwrap_err().to_string();
+        assert!(error.contains("post-commit"));
+        assert_eq!(
+            std::fs::read_to_string(hooks.join("post-commit")).unwrap(),
+            python
+        );
+        assert!(!hooks.join("post-merge").exists());
+
+        std::fs::write(
+            hooks.join("post-commit"),
+            "#!/bin/bash\nmake lint\nexit 0\n",
+        )
+        .unwrap();
+        assert!(install_git_hooks(project).is_err());
+
+        std::fs::write(
+            hooks.join("post-commit"),
+            "#!/usr/bin/env bash\nmake lint\n",
+        )
+        .unwrap();
+        assert_eq!(
+            install_git_hooks(project).unwrap(),
+            vec!["post-commit", "post-merge"]
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
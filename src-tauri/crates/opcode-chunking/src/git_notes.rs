@@ -0,0 +1,326 @@
+use super::commits::classify_commit_message;
+use super::storage::{
+    create_snapshot, get_active_snapshot_id, get_snapshots, set_active_snapshot, CHUNK_CONTENT,
+};
+use super::types::{CommitMetadata, GitNotesResult, Snapshot, SnapshotType};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use git2::{Oid, Repository, Signature};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Referencia de notas donde opcode guarda su conocimiento por commit
+pub const NOTES_REF: &str = "refs/notes/opcode";
+
+/// Contenido (JSON) de una nota de opcode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OpcodeNote {
+    pub summary: String,
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub files_modified: usize,
+    #[serde(default)]
+    pub snapshots: Vec<NoteSnapshot>,
+}
+
+/// Snapshot de opcode asociado al commit
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteSnapshot {
+    pub snapshot_type: String,
+    pub version_major: i32,
+    pub version_minor: Option<i32>,
+    pub message: String,
+    pub user_message: Option<String>,
+    pub diff_summary: Option<String>,
+    pub git_tag: Option<String>,
+    pub git_branch: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Escribe en `refs/notes/opcode` el resumen de cada commit indexado y los snapshots
+/// que apuntan a él. Las notas sin cambios no se reescriben
+pub fn write_commit_notes(conn: &Connection, project_path: &str) -> Result<usize> {
+    let repo = Repository::open(project_path).context("Failed to open git repository")?;
+    let sig = repo
+        .signature()
+        .or_else(|_| Signature::now("Opcode", "opcode@opcode.local"))?;
+
+    let mut snapshots_by_commit: HashMap<String, Vec<NoteSnapshot>> = HashMap::new();
+    for snapshot in get_snapshots(conn, project_path, None)? {
+        if let Some(hash) = &snapshot.git_commit_hash {
+            snapshots_by_commit
+                .entry(hash.clone())
+                .or_default()
+                .push(note_snapshot(&snapshot));
+        }
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT metadata, {} FROM chunks
+         WHERE project_path = ?1 AND chunk_type = 'commit_history' AND metadata IS NOT NULL",
+        CHUNK_CONTENT
+    ))?;
+    let commits = stmt
+        .query_map(params![project_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut written = 0;
+    for (metadata, content) in commits {
+        let Ok(metadata) = serde_json::from_str::<CommitMetadata>(&metadata) else {
+            continue;
+        };
+        let Ok(oid) = Oid::from_str(&metadata.commit_hash) else {
+            continue;
+        };
+        if repo.find_commit(oid).is_err() {
+            continue;
+        }
+
+        let mut snapshots = snapshots_by_commit
+            .remove(&metadata.commit_hash)
+            .unwrap_or_default();
+        snapshots.sort_by_key(|s| (s.version_major, s.version_minor));
+        let note = build_note(&metadata, &content, snapshots);
+
+        if let Ok(existing) = repo.find_note(Some(NOTES_REF), oid) {
+            let unchanged = existing
+                .message()
+                .and_then(|m| serde_json::from_str::<OpcodeNote>(m).ok())
+                .map(|n| n == note)
+                .unwrap_or(false);
+            if unchanged {
+                continue;
+            }
+        }
+
+        repo.note(
+            &sig,
+            &sig,
+            Some(NOTES_REF),
+            oid,
+            &serde_json::to_string_pretty(&note)?,
+            true,
+        )?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+/// Importa las notas de `refs/notes/opcode` (ej: tras clonar en otra máquina),
+/// restaurando los snapshots que no existan en la base de datos
+pub fn import_commit_notes(conn: &Connection, project_path: &str) -> Result<GitNotesResult> {
+    let repo = Repository::open(project_path).context("Failed to open git repository")?;
+    let mut result = GitNotesResult {
+        notes_written: 0,
+        notes_imported: 0,
+        snapshots_restored: 0,
+    };
+
+    let notes = match repo.notes(Some(NOTES_REF)) {
+        Ok(notes) => notes,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(result),
+        Err(e) => return Err(e.into()),
+    };
+
+    let existing: Vec<Snapshot> = get_snapshots(conn, project_path, None)?;
+    let previous_active = get_active_snapshot_id(conn, project_path)?;
+
+    for entry in notes {
+        let (_, annotated_oid) = entry?;
+        let note = repo.find_note(Some(NOTES_REF), annotated_oid)?;
+        let Some(note) = note
+            .message()
+            .and_then(|m| serde_json::from_str::<OpcodeNote>(m).ok())
+        else {
+            continue;
+        };
+        result.notes_imported += 1;
+
+        let commit_hash = annotated_oid.to_string();
+        for noted in note.snapshots {
+            let snapshot_type = if noted.snapshot_type == "master" {
+                SnapshotType::Master
+            } else {
+                SnapshotType::Agent
+            };
+            let already_present = existing.iter().any(|s| {
+                s.git_commit_hash.as_deref() == Some(commit_hash.as_str())
+                    && s.snapshot_type == snapshot_type
+                    && s.version_major == noted.version_major
+                    && s.version_minor == noted.version_minor
+            });
+            if already_present {
+                continue;
+            }
+
+            let created_at = noted.created_at;
+            let snapshot_id = create_snapshot(
+                conn,
+                &Snapshot {
+                    id: None,
+                    project_path: project_path.to_string(),
+                    snapshot_type,
+                    parent_snapshot_id: None,
+                    message: noted.message,
+                    user_message: noted.user_message,
+                    changed_files: "[]".to_string(),
+                    diff_summary: noted.diff_summary,
+                    metadata: Some(r#"{"source":"git_notes"}"#.to_string()),
+                    git_commit_hash: Some(commit_hash.clone()),
+                    git_tag: noted.git_tag,
+                    git_branch: noted.git_branch,
+                    version_major: noted.version_major,
+                    version_minor: noted.version_minor,
+                    created_at,
+                },
+            )?;
+            // create_snapshot usa la hora actual; se conserva la fecha original
+            conn.execute(
+                "UPDATE snapshots SET created_at = ?1 WHERE id = ?2",
+                params![created_at.to_rfc3339(), snapshot_id],
+            )?;
+            result.snapshots_restored += 1;
+        }
+    }
+
+    // Restaurar snapshots históricos no debe cambiar el snapshot activo
+    if let Some(active) = previous_active {
+        set_active_snapshot(conn, project_path, active)?;
+    }
+
+    Ok(result)
+}
+
+/// Resumen de un commit para su nota: `tipo(scope): descripción`
+fn build_note(
+    metadata: &CommitMetadata,
+    content: &str,
+    snapshots: Vec<NoteSnapshot>,
+) -> OpcodeNote {
+    let message = content
+        .split_once("Message:\n")
+        .map(|(_, rest)| rest.trim_start())
+        .unwrap_or("");
+    let subject = message.lines().next().unwrap_or("").trim();
+    let classification = classify_commit_message(message);
+    let description = if classification.conventional {
+        subject
+            .split_once(':')
+            .map(|(_, d)| d.trim())
+            .unwrap_or(subject)
+    } else {
+        subject
+    };
+
+    let commit_type = metadata
+        .commit_type
+        .clone()
+        .unwrap_or(classification.commit_type);
+    let scope = metadata.scope.clone().or(classification.scope);
+    let summary = match &scope {
+        Some(scope) => format!("{}({}): {}", commit_type, scope, description),
+        None => format!("{}: {}", commit_type, description),
+    };
+
+    OpcodeNote {
+        summary,
+        commit_type: Some(commit_type),
+        scope,
+        files_modified: metadata.files_modified.len(),
+        snapshots,
+    }
+}
+
+fn note_snapshot(snapshot: &Snapshot) -> NoteSnapshot {
+    NoteSnapshot {
+        snapshot_type: snapshot.snapshot_type.as_str().to_string(),
+        version_major: snapshot.version_major,
+        version_minor: snapshot.version_minor,
+        message: snapshot.message.clone(),
+        user_message: snapshot.user_message.clone(),
+        diff_summary: snapshot.diff_summary.clone(),
+        git_tag: snapshot.git_tag.clone(),
+        git_branch: snapshot.git_branch.clone(),
+        created_at: snapshot.created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commits::generate_commit_chunks;
+    use crate::storage::init_chunk_database;
+    use std::path::Path;
+
+    #[test]
+    fn test_write_and_import_commit_notes() {
+        let dir = std::env::temp_dir().join(format!("opcode-notes-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "feat(core): add a", &tree, &[])
+            .unwrap();
+        let project = dir.to_str().unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        generate_commit_chunks(&conn, project, None, false).unwrap();
+        create_snapshot(
+            &conn,
+            &Snapshot {
+                id: None,
+                project_path: project.to_string(),
+                snapshot_type: SnapshotType::Master,
+                parent_snapshot_id: None,
+                message: "Master snapshot V1: add a".to_string(),
+                user_message: Some("add a".to_string()),
+                changed_files: "[]".to_string(),
+                diff_summary: None,
+                metadata: None,
+                git_commit_hash: Some(oid.to_string()),
+                git_tag: Some("v1".to_string()),
+                git_branch: Some("main".to_string()),
+                version_major: 1,
+                version_minor: None,
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(write_commit_notes(&conn, project).unwrap(), 1);
+        // Sin cambios no se reescribe
+        assert_eq!(write_commit_notes(&conn, project).unwrap(), 0);
+
+        let note = repo.find_note(Some(NOTES_REF), oid).unwrap();
+        let note: OpcodeNote = serde_json::from_str(note.message().unwrap()).unwrap();
+        assert_eq!(note.summary, "feat(core): add a");
+        assert_eq!(note.snapshots.len(), 1);
+
+        // Otra máquina: base de datos vacía
+        let other = Connection::open_in_memory().unwrap();
+        init_chunk_database(&other).unwrap();
+        let result = import_commit_notes(&other, project).unwrap();
+        assert_eq!(result.notes_imported, 1);
+        assert_eq!(result.snapshots_restored, 1);
+        let restored = get_snapshots(&other, project, None).unwrap();
+        assert_eq!(restored[0].user_message.as_deref(), Some("add a"));
+        assert_eq!(restored[0].git_commit_hash, Some(oid.to_string()));
+        assert_eq!(
+            import_commit_notes(&other, project)
+                .unwrap()
+                .snapshots_restored,
+            0
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,435 @@
+use super::storage::CHUNK_CONTENT;
+use super::types::GraphFormat;
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Chunk vigente del proyecto como nodo del grafo
+struct GraphNode {
+    id: i64,
+    chunk_type: String,
+    file_path: Option<String>,
+    entity_name: Option<String>,
+    content_hash: String,
+    content: String,
+    created_at: String,
+    updated_at: String,
+}
+
+/// Relación entre dos chunks vigentes del proyecto
+struct GraphEdge {
+    from_chunk_id: i64,
+    to_chunk_id: i64,
+    relationship_type: String,
+    metadata: Option<String>,
+}
+
+/// Genera un script Cypher que carga el grafo de conocimiento del proyecto en Neo4j:
+/// un nodo `:Chunk` (más una etiqueta por tipo, ej. `:RawSource`) por chunk vigente y
+/// una arista por relación (ej. `DEPENDS_ON`). Usa MERGE, así que puede ejecutarse
+/// varias veces sobre la misma base. El contenido de los chunks solo se incluye con
+/// `include_content`
+pub fn export_cypher(
+    conn: &Connection,
+    project_path: &str,
+    include_content: bool,
+) -> Result<String> {
+    let (nodes, edges) = load_graph(conn, project_path)?;
+
+    let mut script = String::new();
+    writeln!(script, "// opcode knowledge graph: {}", project_path)?;
+    writeln!(
+        script,
+        "CREATE CONSTRAINT opcode_chunk_id IF NOT EXISTS FOR (c:Chunk) REQUIRE c.id IS UNIQUE;"
+    )?;
+
+    for node in &nodes {
+        let mut properties = vec![
+            format!("project_path: {}", cypher_string(project_path)),
+            format!("chunk_type: {}", cypher_string(&node.chunk_type)),
+            format!("content_hash: {}", cypher_string(&node.content_hash)),
+            format!("created_at: datetime({})", cypher_string(&node.created_at)),
+            format!("updated_at: datetime({})", cypher_string(&node.updated_at)),
+        ];
+        if let Some(file_path) = &node.file_path {
+            properties.push(format!("file_path: {}", cypher_string(file_path)));
+        }
+        if let Some(entity_name) = &node.entity_name {
+            properties.push(format!("entity_name: {}", cypher_string(entity_name)));
+        }
+        if include_content {
+            properties.push(format!("content: {}", cypher_string(&node.content)));
+        }
+        writeln!(
+            script,
+            "MERGE (c:Chunk {{id: {}}}) SET c:{}, c += {{{}}};",
+            node.id,
+            cypher_label(&node.chunk_type),
+            properties.join(", ")
+        )?;
+    }
+
+    for edge in &edges {
+        let metadata = edge
+            .metadata
+            .as_deref()
+            .map(|m| format!(" SET r.metadata = {}", cypher_string(m)))
+            .unwrap_or_default();
+        writeln!(
+            script,
+            "MATCH (a:Chunk {{id: {}}}), (b:Chunk {{id: {}}}) MERGE (a)-[r:{}]->(b){};",
+            edge.from_chunk_id,
+            edge.to_chunk_id,
+            edge.relationship_type.to_uppercase(),
+            metadata
+        )?;
+    }
+
+    log::info!(
+        "Exported {} nodes and {} relationships of {} to Cypher",
+        nodes.len(),
+        edges.len(),
+        project_path
+    );
+    Ok(script)
+}
+
+/// Exporta las relaciones del proyecto (`chunk_relationships`) como grafo DOT o
+/// GraphML para verlo en Graphviz o Gephi. Solo incluye los chunks con alguna
+/// relación; el nodo se etiqueta con la entidad y/o el archivo del chunk
+pub fn export_callgraph(
+    conn: &Connection,
+    project_path: &str,
+    format: GraphFormat,
+) -> Result<String> {
+    let (nodes, edges) = load_graph(conn, project_path)?;
+    let linked: BTreeSet<i64> = edges
+        .iter()
+        .flat_map(|edge| [edge.from_chunk_id, edge.to_chunk_id])
+        .collect();
+    let nodes: Vec<&GraphNode> = nodes.iter().filter(|n| linked.contains(&n.id)).collect();
+
+    let graph = match format {
+        GraphFormat::Dot => dot_graph(project_path, &nodes, &edges)?,
+        GraphFormat::Graphml => graphml_graph(project_path, &nodes, &edges)?,
+    };
+    log::info!(
+        "Exported {} nodes and {} relationships of {} to {}",
+        nodes.len(),
+        edges.len(),
+        project_path,
+        format.as_str()
+    );
+    Ok(graph)
+}
+
+fn dot_graph(project_path: &str, nodes: &[&GraphNode], edges: &[GraphEdge]) -> Result<String> {
+    let mut graph = String::new();
+    writeln!(graph, "digraph {} {{", dot_string(project_path))?;
+    writeln!(graph, "  node [shape=box];")?;
+    for node in nodes {
+        writeln!(
+            graph,
+            "  n{} [label={}, chunk_type={}];",
+            node.id,
+            dot_string(&node_label(node)),
+            dot_string(&node.chunk_type)
+        )?;
+    }
+    for edge in edges {
+        writeln!(
+            graph,
+            "  n{} -> n{} [label={}];",
+            edge.from_chunk_id,
+            edge.to_chunk_id,
+            dot_string(&edge.relationship_type)
+        )?;
+    }
+    writeln!(graph, "}}")?;
+    Ok(graph)
+}
+
+fn graphml_graph(project_path: &str, nodes: &[&GraphNode], edges: &[GraphEdge]) -> Result<String> {
+    let mut graph = String::new();
+    writeln!(graph, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        graph,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    for (id, target, name) in [
+        ("label", "node", "label"),
+        ("chunk_type", "node", "chunk_type"),
+        ("file_path", "node", "file_path"),
+        ("entity_name", "node", "entity_name"),
+        ("relationship_type", "edge", "relationship_type"),
+    ] {
+        writeln!(
+            graph,
+            r#"  <key id="{}" for="{}" attr.name="{}" attr.type="string"/>"#,
+            id, target, name
+        )?;
+    }
+    writeln!(
+        graph,
+        r#"  <graph id="{}" edgedefault="directed">"#,
+        xml_escape(project_path)
+    )?;
+    for node in nodes {
+        writeln!(graph, r#"    <node id="n{}">"#, node.id)?;
+        let mut data = vec![
+            ("label", node_label(node)),
+            ("chunk_type", node.chunk_type.clone()),
+        ];
+        if let Some(file_path) = &node.file_path {
+            data.push(("file_path", file_path.clone()));
+        }
+        if let Some(entity_name) = &node.entity_name {
+            data.push(("entity_name", entity_name.clone()));
+        }
+        for (key, value) in data {
+            writeln!(
+                graph,
+                r#"      <data key="{}">{}</data>"#,
+                key,
+                xml_escape(&value)
+            )?;
+        }
+        writeln!(graph, "    </node>")?;
+    }
+    for (i, edge) in edges.iter().enumerate() {
+        writeln!(
+            graph,
+            r#"    <edge id="e{}" source="n{}" target="n{}"><data key="relationship_type">{}</data></edge>"#,
+            i,
+            edge.from_chunk_id,
+            edge.to_chunk_id,
+            xml_escape(&edge.relationship_type)
+        )?;
+    }
+    writeln!(graph, "  </graph>")?;
+    writeln!(graph, "</graphml>")?;
+    Ok(graph)
+}
+
+/// Entidad y archivo del chunk, o su tipo e id si no tiene ninguno
+fn node_label(node: &GraphNode) -> String {
+    match (&node.entity_name, &node.file_path) {
+        (Some(entity), Some(file)) => format!("{} ({})", entity, file),
+        (Some(entity), None) => entity.clone(),
+        (None, Some(file)) => file.clone(),
+        (None, None) => format!("{} #{}", node.chunk_type, node.id),
+    }
+}
+
+/// ID DOT entre comillas dobles
+fn dot_string(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => {}
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Chunks vigentes del proyecto y las relaciones entre ellos
+fn load_graph(conn: &Connection, project_path: &str) -> Result<(Vec<GraphNode>, Vec<GraphEdge>)> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, chunk_type, file_path, entity_name, content_hash, {}, created_at, updated_at
+         FROM chunks WHERE project_path = ?1 AND deleted_at IS NULL ORDER BY id",
+        CHUNK_CONTENT
+    ))?;
+    let nodes = stmt
+        .query_map(params![project_path], |row| {
+            Ok(GraphNode {
+                id: row.get(0)?,
+                chunk_type: row.get(1)?,
+                file_path: row.get(2)?,
+                entity_name: row.get(3)?,
+                content_hash: row.get(4)?,
+                content: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT r.from_chunk_id, r.to_chunk_id, r.relationship_type, r.metadata
+         FROM chunk_relationships r
+         JOIN chunks src ON src.id = r.from_chunk_id
+         JOIN chunks dst ON dst.id = r.to_chunk_id
+         WHERE src.project_path = ?1 AND dst.project_path = ?1
+           AND src.deleted_at IS NULL AND dst.deleted_at IS NULL
+         ORDER BY r.id",
+    )?;
+    let edges = stmt
+        .query_map(params![project_path], |row| {
+            Ok(GraphEdge {
+                from_chunk_id: row.get(0)?,
+                to_chunk_id: row.get(1)?,
+                relationship_type: row.get(2)?,
+                metadata: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok((nodes, edges))
+}
+
+/// Etiqueta Neo4j de un tipo de chunk: `raw_source` -> `RawSource`
+fn cypher_label(chunk_type: &str) -> String {
+    chunk_type
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Literal de string Cypher entre comillas simples
+fn cypher_string(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('\'');
+    for c in value.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '\'' => literal.push_str("\\'"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{init_chunk_database, insert_relationship};
+    use crate::test_support::chunk;
+    use crate::types::{ChunkRelationship, ChunkType, RelationshipType};
+    use chrono::Utc;
+
+    fn insert_chunk(
+        conn: &Connection,
+        chunk_type: ChunkType,
+        file_path: &str,
+        content: &str,
+    ) -> i64 {
+        chunk()
+            .chunk_type(chunk_type)
+            .file(file_path)
+            .content(content)
+            .insert(conn)
+    }
+
+    #[test]
+    fn test_export_cypher() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let main = insert_chunk(
+            &conn,
+            ChunkType::RawSource,
+            "src/main.rs",
+            "println!(\"it's\");\n",
+        );
+        let tests = insert_chunk(&conn, ChunkType::Tests, "tests/main.rs", "#[test]");
+        insert_relationship(
+            &conn,
+            &ChunkRelationship {
+                id: None,
+                from_chunk_id: main,
+                to_chunk_id: tests,
+                relationship_type: RelationshipType::TestedBy,
+                metadata: Some(r#"{"source":"test_inference"}"#.to_string()),
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+
+        let script = export_cypher(&conn, "/p", false).unwrap();
+        assert!(script.contains(&format!(
+            "MERGE (c:Chunk {{id: {}}}) SET c:RawSource, c += {{",
+            main
+        )));
+        assert!(script.contains("SET c:Tests"));
+        assert!(script.contains("file_path: 'src/main.rs'"));
+        assert!(!script.contains("content:"));
+        assert!(script.contains(&format!(
+            "MATCH (a:Chunk {{id: {}}}), (b:Chunk {{id: {}}}) MERGE (a)-[r:TESTED_BY]->(b) SET r.metadata = '{{\"source\":\"test_inference\"}}';",
+            main, tests
+        )));
+
+        let script = export_cypher(&conn, "/p", true).unwrap();
+        assert!(script.contains(r#"content: 'println!("it\'s");\n'"#));
+        assert_eq!(
+            export_cypher(&conn, "/other", false)
+                .unwrap()
+                .lines()
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_export_callgraph() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let main = insert_chunk(&conn, ChunkType::RawSource, "src/main.rs", "mod lib;");
+        let lib = insert_chunk(&conn, ChunkType::RawSource, "src/\"lib\".rs", "a < b");
+        insert_chunk(&conn, ChunkType::RawSource, "src/unused.rs", "");
+        insert_relationship(
+            &conn,
+            &ChunkRelationship {
+                id: None,
+                from_chunk_id: main,
+                to_chunk_id: lib,
+                relationship_type: RelationshipType::DependsOn,
+                metadata: None,
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+
+        let dot = export_callgraph(&conn, "/p", GraphFormat::Dot).unwrap();
+        assert!(dot.starts_with("digraph \"/p\" {"));
+        assert!(dot.contains(&format!(
+            "n{} [label=\"src/\\\"lib\\\".rs\", chunk_type=\"raw_source\"];",
+            lib
+        )));
+        assert!(dot.contains(&format!("n{} -> n{} [label=\"depends_on\"];", main, lib)));
+        // Los chunks sin relaciones no se exportan
+        assert!(!dot.contains("unused"));
+
+        let graphml = export_callgraph(&conn, "/p", GraphFormat::Graphml).unwrap();
+        assert!(graphml.contains(r#"<graph id="/p" edgedefault="directed">"#));
+        assert!(graphml.contains(r#"<data key="file_path">src/&quot;lib&quot;.rs</data>"#));
+        assert!(graphml.contains(&format!(
+            r#"<edge id="e0" source="n{}" target="n{}"><data key="relationship_type">depends_on</data></edge>"#,
+            main, lib
+        )));
+        assert_eq!(graphml.matches("<node ").count(), 2);
+    }
+}
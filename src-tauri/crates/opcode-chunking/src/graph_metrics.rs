@@ -0,0 +1,219 @@
+use super::types::{ChunkType, EntityGraphMetrics, GraphMetrics, RelationshipType};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Clave de la metadata del chunk donde se guardan sus métricas
+pub const GRAPH_METRICS_KEY: &str = "graph_metrics";
+
+/// Relaciones que forman el grafo de arquitectura
+const METRIC_RELATIONSHIPS: [RelationshipType; 2] =
+    [RelationshipType::DependsOn, RelationshipType::Calls];
+
+/// Calcula fan-in, fan-out y centralidad de grado de cada chunk vigente del proyecto
+/// con relaciones `depends_on` o `calls`, y las guarda en su metadata. Los chunks que
+/// ya no están en el grafo pierden las métricas anteriores. Retorna los chunks medidos
+pub fn compute_graph_metrics(conn: &Connection, project_path: &str) -> Result<usize> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT r.from_chunk_id, r.to_chunk_id FROM chunk_relationships r
+         JOIN chunks src ON src.id = r.from_chunk_id
+         JOIN chunks dst ON dst.id = r.to_chunk_id
+         WHERE src.project_path = ?1 AND r.relationship_type IN (?2, ?3)
+           AND src.deleted_at IS NULL AND dst.deleted_at IS NULL
+           AND r.from_chunk_id != r.to_chunk_id",
+    )?;
+    let edges = stmt
+        .query_map(
+            params![
+                project_path,
+                METRIC_RELATIONSHIPS[0].as_str(),
+                METRIC_RELATIONSHIPS[1].as_str()
+            ],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // chunk -> (entrantes, salientes)
+    let mut neighbours: BTreeMap<i64, (BTreeSet<i64>, BTreeSet<i64>)> = BTreeMap::new();
+    for (from, to) in edges {
+        neighbours.entry(from).or_default().1.insert(to);
+        neighbours.entry(to).or_default().0.insert(from);
+    }
+
+    conn.execute(
+        "UPDATE chunks SET metadata = json_remove(metadata, ?2)
+         WHERE project_path = ?1
+           AND CASE WHEN json_valid(metadata) THEN json_type(metadata, ?2) END IS NOT NULL",
+        params![project_path, format!("$.{}", GRAPH_METRICS_KEY)],
+    )?;
+
+    // Centralidad de grado de un grafo dirigido: (entrada + salida) / 2(n - 1)
+    let max_degree = 2 * neighbours.len().saturating_sub(1);
+    for (chunk_id, (incoming, outgoing)) in &neighbours {
+        let metrics = GraphMetrics {
+            fan_in: incoming.len(),
+            fan_out: outgoing.len(),
+            centrality: if max_degree == 0 {
+                0.0
+            } else {
+                (incoming.len() + outgoing.len()) as f64 / max_degree as f64
+            },
+        };
+        // Solo se toca metadata vacía o de tipo objeto
+        conn.execute(
+            "UPDATE chunks SET metadata = json_set(COALESCE(metadata, '{}'), ?2, json(?3))
+             WHERE id = ?1
+               AND (metadata IS NULL
+                    OR CASE WHEN json_valid(metadata) THEN json_type(metadata) END = 'object')",
+            params![
+                chunk_id,
+                format!("$.{}", GRAPH_METRICS_KEY),
+                serde_json::to_string(&metrics)?
+            ],
+        )?;
+    }
+
+    log::info!(
+        "Computed graph metrics for {} chunks of {}",
+        neighbours.len(),
+        project_path
+    );
+    Ok(neighbours.len())
+}
+
+/// Métricas de grafo guardadas de los chunks vigentes del proyecto, de mayor a menor
+/// centralidad
+pub fn get_graph_metrics(
+    conn: &Connection,
+    project_path: &str,
+    limit: Option<usize>,
+) -> Result<Vec<EntityGraphMetrics>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, chunk_type, file_path, entity_name, metrics FROM (
+             SELECT id, chunk_type, file_path, entity_name,
+                    CASE WHEN json_valid(metadata) THEN json_extract(metadata, ?2) END AS metrics
+             FROM chunks WHERE project_path = ?1 AND deleted_at IS NULL
+         ) WHERE metrics IS NOT NULL",
+    )?;
+    let rows = stmt
+        .query_map(
+            params![project_path, format!("$.{}", GRAPH_METRICS_KEY)],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut metrics: Vec<EntityGraphMetrics> = rows
+        .into_iter()
+        .filter_map(|(chunk_id, chunk_type, file_path, entity_name, metrics)| {
+            Some(EntityGraphMetrics {
+                chunk_id,
+                chunk_type: ChunkType::from_str(&chunk_type)?,
+                file_path,
+                entity_name,
+                metrics: serde_json::from_str(&metrics).ok()?,
+            })
+        })
+        .collect();
+    metrics.sort_by(|a, b| {
+        b.metrics
+            .centrality
+            .total_cmp(&a.metrics.centrality)
+            .then(b.metrics.fan_in.cmp(&a.metrics.fan_in))
+            .then(a.chunk_id.cmp(&b.chunk_id))
+    });
+    if let Some(limit) = limit {
+        metrics.truncate(limit);
+    }
+    Ok(metrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{init_chunk_database, insert_relationship};
+    use crate::test_support::chunk;
+    use crate::types::ChunkRelationship;
+    use chrono::Utc;
+
+    fn insert_file(conn: &Connection, file_path: &str, metadata: Option<&str>) -> i64 {
+        let builder = chunk().file(file_path).content(file_path);
+        match metadata {
+            Some(metadata) => builder.metadata(metadata),
+            None => builder,
+        }
+        .insert(conn)
+    }
+
+    fn link(conn: &Connection, from: i64, to: i64, relationship_type: RelationshipType) {
+        insert_relationship(
+            conn,
+            &ChunkRelationship {
+                id: None,
+                from_chunk_id: from,
+                to_chunk_id: to,
+                relationship_type,
+                metadata: None,
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_graph_metrics_are_stored_and_ranked() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let core = insert_file(&conn, "src/core.rs", Some(r#"{"language":"rust"}"#));
+        let api = insert_file(&conn, "src/api.rs", None);
+        let cli = insert_file(&conn, "src/cli.rs", None);
+        let test = insert_file(&conn, "tests/core_test.rs", None);
+        link(&conn, api, core, RelationshipType::DependsOn);
+        link(&conn, cli, core, RelationshipType::Calls);
+        link(&conn, cli, api, RelationshipType::DependsOn);
+        link(&conn, core, test, RelationshipType::TestedBy);
+
+        assert_eq!(compute_graph_metrics(&conn, "/p").unwrap(), 3);
+        let metrics = get_graph_metrics(&conn, "/p", None).unwrap();
+        assert_eq!(metrics.len(), 3);
+        // Con 3 nodos el grado máximo es 4
+        assert_eq!(metrics[0].chunk_id, core);
+        assert_eq!(
+            metrics[0].metrics,
+            GraphMetrics {
+                fan_in: 2,
+                fan_out: 0,
+                centrality: 0.5
+            }
+        );
+        let cli_metrics = metrics.iter().find(|m| m.chunk_id == cli).unwrap();
+        assert_eq!(
+            (cli_metrics.metrics.fan_in, cli_metrics.metrics.fan_out),
+            (0, 2)
+        );
+
+        // La metadata existente se conserva
+        let metadata: String = conn
+            .query_row("SELECT metadata FROM chunks WHERE id = ?1", [core], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert!(metadata.contains(r#""language":"rust""#));
+
+        // Al recalcular, los chunks que salen del grafo pierden sus métricas
+        conn.execute(
+            "DELETE FROM chunk_relationships WHERE from_chunk_id = ?1",
+            [cli],
+        )
+        .unwrap();
+        assert_eq!(compute_graph_metrics(&conn, "/p").unwrap(), 2);
+        assert_eq!(get_graph_metrics(&conn, "/p", Some(10)).unwrap().len(), 2);
+    }
+}
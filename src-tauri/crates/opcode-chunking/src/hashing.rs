@@ -0,0 +1,197 @@
+use super::read_only::ensure_index_writable;
+use super::storage::{calculate_content_hash, calculate_hash, get_chunk_id_by_hash, CHUNK_CONTENT};
+use super::types::{ChunkType, HashAlgorithm, ProjectHashing};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Algoritmo de hash del contenido de los archivos del proyecto (SHA256 si nunca se eligió)
+pub fn get_hash_algorithm(conn: &Connection, project_path: &str) -> Result<HashAlgorithm> {
+    let algorithm: Option<String> = conn
+        .query_row(
+            "SELECT algorithm FROM project_hashing WHERE project_path = ?1",
+            params![project_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match algorithm {
+        Some(name) => HashAlgorithm::from_str(&name)
+            .ok_or_else(|| anyhow!("Unknown hash algorithm '{}' for {}", name, project_path)),
+        None => Ok(HashAlgorithm::default()),
+    }
+}
+
+/// Cambia el algoritmo de hash del proyecto. Los chunks de código fuente se
+/// rehashean en el acto con el nuevo algoritmo, para que la próxima indexación los
+/// actualice en vez de duplicarlos. Las huellas de archivo conservan su hash (el
+/// prefijo dice con qué algoritmo se calculó) hasta que se reindexa el archivo
+pub fn set_hash_algorithm(
+    conn: &Connection,
+    project_path: &str,
+    algorithm: HashAlgorithm,
+) -> Result<ProjectHashing> {
+    ensure_index_writable(conn, project_path)?;
+    let salt = project_salt(conn, project_path)?;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "UPDATE project_hashing SET algorithm = ?1, updated_at = ?2 WHERE project_path = ?3",
+        params![algorithm.as_str(), Utc::now().to_rfc3339(), project_path],
+    )?;
+    rehash_raw_source_chunks(&tx, project_path, algorithm)?;
+    tx.commit()?;
+    Ok(ProjectHashing {
+        project_path: project_path.to_string(),
+        algorithm,
+        project_id: salted_id(&salt, project_path),
+    })
+}
+
+/// Algoritmo e identificador anónimo del proyecto
+pub fn get_project_hashing(conn: &Connection, project_path: &str) -> Result<ProjectHashing> {
+    Ok(ProjectHashing {
+        project_path: project_path.to_string(),
+        algorithm: get_hash_algorithm(conn, project_path)?,
+        project_id: project_id(conn, project_path)?,
+    })
+}
+
+/// Identificador anónimo y estable del proyecto: SHA256 de la ruta con la sal del
+/// proyecto, que se genera al pedirlo por primera vez
+pub fn project_id(conn: &Connection, project_path: &str) -> Result<String> {
+    let salt = project_salt(conn, project_path)?;
+    Ok(salted_id(&salt, project_path))
+}
+
+/// Como [`project_id`] pero sin escribir: `None` si el proyecto aún no tiene sal (la
+/// crea la indexación). Sirve en conexiones de solo lectura
+pub fn find_project_id(conn: &Connection, project_path: &str) -> Result<Option<String>> {
+    let salt: Option<String> = conn
+        .query_row(
+            "SELECT salt FROM project_hashing WHERE project_path = ?1",
+            params![project_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(salt.map(|salt| salted_id(&salt, project_path)))
+}
+
+/// Verifica un hash guardado contra el contenido, con el algoritmo con que se calculó
+pub fn hash_matches(stored_hash: &str, content: &str) -> bool {
+    calculate_hash(content, HashAlgorithm::of_hash(stored_hash)) == stored_hash
+}
+
+/// Recalcula con `algorithm` el hash de los chunks de código fuente del proyecto
+/// calculados con otro. Si el contenido ya tiene un chunk con el nuevo hash (quedó
+/// duplicado por un cambio anterior), sobra el viejo
+fn rehash_raw_source_chunks(
+    conn: &Connection,
+    project_path: &str,
+    algorithm: HashAlgorithm,
+) -> Result<()> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, {}, content_hash FROM chunks WHERE project_path = ?1 AND chunk_type = ?2",
+        CHUNK_CONTENT
+    ))?;
+    let rows = stmt
+        .query_map(
+            params![project_path, ChunkType::RawSource.as_str()],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    drop(stmt);
+
+    for (id, content, content_hash) in rows {
+        if HashAlgorithm::of_hash(&content_hash) == algorithm {
+            continue;
+        }
+        let new_hash = calculate_hash(&content, algorithm);
+        if get_chunk_id_by_hash(conn, project_path, &new_hash)?.is_some() {
+            conn.execute("DELETE FROM chunks WHERE id = ?1", params![id])?;
+        } else {
+            conn.execute(
+                "UPDATE chunks SET content_hash = ?1 WHERE id = ?2",
+                params![new_hash, id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn project_salt(conn: &Connection, project_path: &str) -> Result<String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO project_hashing (project_path, algorithm, salt, updated_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            project_path,
+            HashAlgorithm::default().as_str(),
+            uuid::Uuid::new_v4().simple().to_string(),
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    Ok(conn.query_row(
+        "SELECT salt FROM project_hashing WHERE project_path = ?1",
+        params![project_path],
+        |row| row.get(0),
+    )?)
+}
+
+fn salted_id(salt: &str, project_path: &str) -> String {
+    calculate_content_hash(&format!("{}\n{}", salt, project_path))[..16].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+
+    #[test]
+    fn test_hash_algorithm_per_project() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        assert_eq!(
+            get_hash_algorithm(&conn, "/p").unwrap(),
+            HashAlgorithm::Sha256
+        );
+        let id = project_id(&conn, "/p").unwrap();
+        let hashing = set_hash_algorithm(&conn, "/p", HashAlgorithm::Blake3).unwrap();
+        assert_eq!(hashing.algorithm, HashAlgorithm::Blake3);
+        // Cambiar el algoritmo no cambia el identificador
+        assert_eq!(hashing.project_id, id);
+        assert_eq!(
+            get_hash_algorithm(&conn, "/p").unwrap(),
+            HashAlgorithm::Blake3
+        );
+        assert_eq!(
+            get_hash_algorithm(&conn, "/other").unwrap(),
+            HashAlgorithm::Sha256
+        );
+
+        // Sal aleatoria: el id no es el hash de la ruta y cada proyecto tiene el suyo
+        assert_eq!(id.len(), 16);
+        assert!(!calculate_content_hash("/p").starts_with(&id));
+        assert_ne!(project_id(&conn, "/other").unwrap(), id);
+        assert_eq!(find_project_id(&conn, "/p").unwrap(), Some(id));
+        assert_eq!(find_project_id(&conn, "/never-indexed").unwrap(), None);
+    }
+
+    #[test]
+    fn test_hashes_record_their_algorithm() {
+        let sha = calculate_hash("fn main() {}", HashAlgorithm::Sha256);
+        let blake = calculate_hash("fn main() {}", HashAlgorithm::Blake3);
+        assert_eq!(sha, calculate_content_hash("fn main() {}"));
+        assert_eq!(blake.len(), "blake3:".len() + 64);
+        assert_eq!(HashAlgorithm::of_hash(&sha), HashAlgorithm::Sha256);
+        assert_eq!(HashAlgorithm::of_hash(&blake), HashAlgorithm::Blake3);
+
+        assert!(hash_matches(&sha, "fn main() {}"));
+        assert!(hash_matches(&blake, "fn main() {}"));
+        assert!(!hash_matches(&blake, "fn main() { changed(); }"));
+    }
+}
@@ -0,0 +1,242 @@
+use super::business_rules::get_pending_rules;
+use super::errors::get_active_errors;
+use super::storage::get_latest_file_contents;
+use super::test_results::get_failing_tests;
+use super::types::{
+    ChunkType, CommitMetadata, HealthComponent, ProjectHealthReport, RelationshipType,
+    UncoveredHotspot,
+};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Commits indexados a partir de los cuales un archivo cuenta como hotspot
+pub const MIN_HOTSPOT_COMMITS: usize = 3;
+
+/// Hotspots sin tests que se listan en el reporte
+pub const MAX_HOTSPOTS: usize = 20;
+
+/// Puntos que resta cada hallazgo y máximo por componente; los máximos suman 100
+const PENALTIES: [(&str, f64, f64); 5] = [
+    ("open_errors", 2.0, 25.0),
+    ("failing_tests", 5.0, 30.0),
+    ("uncovered_hotspots", 3.0, 20.0),
+    ("stale_files", 1.0, 15.0),
+    ("unvalidated_rules", 1.0, 10.0),
+];
+
+/// Reúne en un reporte los errores abiertos, los hotspots sin tests, los archivos
+/// desactualizados, las reglas sin validar y los tests fallidos de la última
+/// ejecución, con un puntaje de 0 a 100 que resta puntos por cada hallazgo
+pub fn get_project_health(conn: &Connection, project_path: &str) -> Result<ProjectHealthReport> {
+    let open_errors = get_active_errors(conn, project_path)?;
+    let failing_tests = get_failing_tests(conn, project_path, None)?;
+    let uncovered_hotspots = uncovered_hotspots(conn, project_path)?;
+    let stale_files = stale_files(conn, project_path)?;
+    let unvalidated_rules = get_pending_rules(conn, project_path)?;
+
+    let counts = [
+        open_errors.len(),
+        failing_tests.len(),
+        uncovered_hotspots.len(),
+        stale_files.len(),
+        unvalidated_rules.len(),
+    ];
+    let components: Vec<HealthComponent> = PENALTIES
+        .iter()
+        .zip(counts)
+        .map(|((name, weight, max_penalty), count)| HealthComponent {
+            name: name.to_string(),
+            count,
+            penalty: (count as f64 * weight).min(*max_penalty),
+            max_penalty: *max_penalty,
+        })
+        .collect();
+    let penalty: f64 = components.iter().map(|c| c.penalty).sum();
+
+    Ok(ProjectHealthReport {
+        project_path: project_path.to_string(),
+        score: (100.0 - penalty).clamp(0.0, 100.0).round() as u32,
+        components,
+        open_errors,
+        uncovered_hotspots,
+        stale_files,
+        unvalidated_rules,
+        failing_tests,
+        generated_at: Utc::now(),
+    })
+}
+
+/// Archivos de código indexados que aparecen en al menos `MIN_HOTSPOT_COMMITS`
+/// commits y no tienen ninguna relación `tested_by`, de más a menos commits
+fn uncovered_hotspots(conn: &Connection, project_path: &str) -> Result<Vec<UncoveredHotspot>> {
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+    let test_files = get_latest_file_contents(conn, project_path, &ChunkType::Tests)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND metadata IS NOT NULL
+           AND deleted_at IS NULL",
+    )?;
+    let commits = stmt
+        .query_map(
+            params![project_path, ChunkType::CommitHistory.as_str()],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for metadata in commits {
+        let Ok(commit) = serde_json::from_str::<CommitMetadata>(&metadata) else {
+            continue;
+        };
+        let files: BTreeSet<String> = commit.files_modified.into_iter().collect();
+        for file in files {
+            if sources.contains_key(&file) && !test_files.contains_key(&file) {
+                *counts.entry(file).or_default() += 1;
+            }
+        }
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT c.file_path FROM chunk_relationships r
+         JOIN chunks c ON c.id = r.from_chunk_id
+         WHERE c.project_path = ?1 AND r.relationship_type = ?2
+           AND c.file_path IS NOT NULL AND c.deleted_at IS NULL",
+    )?;
+    let tested: BTreeSet<String> = stmt
+        .query_map(
+            params![project_path, RelationshipType::TestedBy.as_str()],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<BTreeSet<_>>>()?;
+
+    let mut hotspots: Vec<UncoveredHotspot> = counts
+        .into_iter()
+        .filter(|(file, commits)| *commits >= MIN_HOTSPOT_COMMITS && !tested.contains(file))
+        .map(|(file_path, commits)| UncoveredHotspot { file_path, commits })
+        .collect();
+    hotspots.sort_by_key(|h| std::cmp::Reverse(h.commits));
+    hotspots.truncate(MAX_HOTSPOTS);
+    Ok(hotspots)
+}
+
+/// Archivos cuyo raw source indexado ya no coincide con el disco (cambiados o eliminados)
+fn stale_files(conn: &Connection, project_path: &str) -> Result<Vec<String>> {
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+    let root = Path::new(project_path);
+    Ok(sources
+        .into_iter()
+        .filter(|(file_path, (_, content))| {
+            !std::fs::read_to_string(root.join(file_path)).is_ok_and(|current| current == *content)
+        })
+        .map(|(file_path, _)| file_path)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+
+    fn insert_chunk(
+        conn: &Connection,
+        project_path: &str,
+        chunk_type: ChunkType,
+        file_path: Option<&str>,
+        content: &str,
+        metadata: Option<String>,
+    ) {
+        let mut builder = chunk()
+            .project(project_path)
+            .chunk_type(chunk_type)
+            .content(content);
+        if let Some(file_path) = file_path {
+            builder = builder.file(file_path);
+        }
+        if let Some(metadata) = metadata {
+            builder = builder.metadata(metadata);
+        }
+        builder.insert(conn);
+    }
+
+    #[test]
+    fn test_project_health_report() {
+        let dir = std::env::temp_dir().join(format!("opcode-health-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let project = dir.to_str().unwrap();
+        std::fs::write(dir.join("core.rs"), "fn core() {}\n").unwrap();
+        std::fs::write(dir.join("util.rs"), "fn util() { changed() }\n").unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_chunk(
+            &conn,
+            project,
+            ChunkType::RawSource,
+            Some("core.rs"),
+            "fn core() {}\n",
+            None,
+        );
+        insert_chunk(
+            &conn,
+            project,
+            ChunkType::RawSource,
+            Some("util.rs"),
+            "fn util() {}\n",
+            None,
+        );
+        for i in 0..MIN_HOTSPOT_COMMITS {
+            let metadata = CommitMetadata {
+                commit_hash: format!("c{}", i),
+                author: "dev".to_string(),
+                author_email: "dev@example.com".to_string(),
+                commit_date: Utc::now(),
+                files_modified: vec!["core.rs".to_string(), "util.rs".to_string()],
+                insertions: 0,
+                deletions: 0,
+                commit_type: None,
+                scope: None,
+                breaking_change: false,
+                conventional: false,
+                branches: Vec::new(),
+            };
+            insert_chunk(
+                &conn,
+                project,
+                ChunkType::CommitHistory,
+                None,
+                &format!("commit {}", i),
+                Some(serde_json::to_string(&metadata).unwrap()),
+            );
+        }
+
+        let report = get_project_health(&conn, project).unwrap();
+        let hotspots: Vec<(&str, usize)> = report
+            .uncovered_hotspots
+            .iter()
+            .map(|h| (h.file_path.as_str(), h.commits))
+            .collect();
+        assert_eq!(
+            hotspots,
+            vec![
+                ("core.rs", MIN_HOTSPOT_COMMITS),
+                ("util.rs", MIN_HOTSPOT_COMMITS)
+            ]
+        );
+        assert_eq!(report.stale_files, vec!["util.rs"]);
+        assert!(report.open_errors.is_empty() && report.failing_tests.is_empty());
+        // 2 hotspots * 3 + 1 archivo desactualizado * 1
+        assert_eq!(report.score, 93);
+        assert_eq!(report.components.len(), 5);
+
+        std::fs::remove_file(dir.join("core.rs")).unwrap();
+        let report = get_project_health(&conn, project).unwrap();
+        assert_eq!(report.stale_files, vec!["core.rs", "util.rs"]);
+        assert_eq!(report.score, 92);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,703 @@
+use super::relationships::relationship;
+use super::storage::{
+    calculate_content_hash, delete_relationships_by_source, get_latest_file_contents,
+    insert_relationship, normalize_file_path, upsert_chunk,
+};
+use super::types::{
+    Chunk, ChunkType, I18nKeyMetadata, I18nMissingKey, I18nReport, I18nUsage, RelationshipType,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Valor de `source` en la metadata de las relaciones clave → código
+pub const I18N_USAGE_SOURCE: &str = "i18n_usage";
+
+/// Directorios donde se buscan archivos de traducción JSON/YAML
+const I18N_DIRS: &[&str] = &[
+    "locales",
+    "locale",
+    "i18n",
+    "l10n",
+    "lang",
+    "langs",
+    "translations",
+    "messages",
+];
+
+/// Clave de traducción de un archivo de locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct I18nEntry {
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+/// (namespace, clave) de una traducción
+type KeyId = (Option<String>, String);
+
+/// Locale y namespace de un archivo de traducción: `locales/en.json`,
+/// `public/locales/en/common.json`, `config/locales/es.yml` o cualquier `.po`
+/// (`locale/es/LC_MESSAGES/django.po`, `po/es.po`). None si no lo es
+pub fn i18n_file_locale(file_path: &str) -> Option<(String, Option<String>)> {
+    let path = Path::new(file_path);
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    let stem = path.file_stem()?.to_str()?.to_string();
+    let dirs: Vec<&str> = file_path.split('/').collect();
+    let dirs = &dirs[..dirs.len() - 1];
+
+    match extension.as_str() {
+        "po" => {
+            let locale = if is_locale_code(&stem) {
+                stem
+            } else {
+                // locale/<locale>/LC_MESSAGES/<dominio>.po
+                let index = dirs.iter().position(|d| *d == "LC_MESSAGES")?;
+                dirs[..index].last()?.to_string()
+            };
+            Some((locale, None))
+        }
+        "json" | "yaml" | "yml" => {
+            let root = dirs.iter().rposition(|d| I18N_DIRS.contains(d))?;
+            let below = &dirs[root + 1..];
+            if below.is_empty() && is_locale_code(&stem) {
+                Some((stem, None))
+            } else if below.len() == 1 && is_locale_code(below[0]) {
+                Some((below[0].to_string(), Some(stem)))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `en`, `es-ES`, `pt_BR`, `zh-Hans`
+fn is_locale_code(name: &str) -> bool {
+    let locale_re = Regex::new(r"^[a-z]{2}(?:[-_][A-Za-z]{2,4})?$").unwrap();
+    locale_re.is_match(name)
+}
+
+/// Claves de un archivo de traducción. JSON y YAML se aplanan con puntos
+/// (`settings.title`) y una raíz con el nombre del locale (estilo Rails) se omite;
+/// en `.po` la clave es el msgid
+pub fn parse_i18n_entries(file_path: &str, content: &str, locale: &str) -> Result<Vec<I18nEntry>> {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let value: serde_json::Value = match extension.as_str() {
+        "po" => return Ok(parse_po(content)),
+        "json" => serde_json::from_str(content).context("Invalid translation JSON")?,
+        _ => serde_yaml::from_str(content).context("Invalid translation YAML")?,
+    };
+
+    let mut value = &value;
+    let mut root_prefix = String::new();
+    if let Some(map) = value.as_object() {
+        if map.len() == 1 {
+            let (root, inner) = map.iter().next().unwrap();
+            if same_locale(root, locale) && inner.is_object() {
+                value = inner;
+                root_prefix = format!("{}.", root);
+            }
+        }
+    }
+
+    let mut leaves = Vec::new();
+    flatten(value, String::new(), &mut leaves);
+    let lines = if extension == "json" {
+        json_key_lines(content)
+    } else {
+        yaml_key_lines(content)
+    };
+    Ok(leaves
+        .into_iter()
+        .map(|(key, value)| I18nEntry {
+            line: lines
+                .get(&format!("{}{}", root_prefix, key))
+                .copied()
+                .unwrap_or(1),
+            key,
+            value,
+        })
+        .collect())
+}
+
+fn same_locale(a: &str, b: &str) -> bool {
+    a.replace('_', "-")
+        .eq_ignore_ascii_case(&b.replace('_', "-"))
+}
+
+fn flatten(value: &serde_json::Value, prefix: String, leaves: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                flatten(child, join(key), leaves);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                flatten(child, join(&index.to_string()), leaves);
+            }
+        }
+        serde_json::Value::String(text) if !prefix.is_empty() => {
+            leaves.push((prefix, text.clone()));
+        }
+        serde_json::Value::Null => {}
+        other if !prefix.is_empty() => leaves.push((prefix, other.to_string())),
+        _ => {}
+    }
+}
+
+/// Línea de cada clave de un JSON, por su ruta con puntos (las claves dentro de
+/// arrays no se registran)
+fn json_key_lines(content: &str) -> BTreeMap<String, usize> {
+    let mut lines = BTreeMap::new();
+    // Un nivel por contenedor abierto: la clave actual si es un objeto
+    let mut path: Vec<(char, Option<String>)> = Vec::new();
+    let mut line = 1;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '{' | '[' => path.push((c, None)),
+            '}' | ']' => {
+                path.pop();
+            }
+            '"' => {
+                let start_line = line;
+                let mut text = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                text.push(escaped);
+                            }
+                        }
+                        '"' => break,
+                        '\n' => {
+                            line += 1;
+                            text.push(c);
+                        }
+                        _ => text.push(c),
+                    }
+                }
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    if chars.next() == Some('\n') {
+                        line += 1;
+                    }
+                }
+                if chars.peek() == Some(&':') && path.last().is_some_and(|(c, _)| *c == '{') {
+                    path.last_mut().unwrap().1 = Some(text);
+                    if path.iter().all(|(c, _)| *c == '{') {
+                        let key: Vec<&str> =
+                            path.iter().filter_map(|(_, k)| k.as_deref()).collect();
+                        lines.entry(key.join(".")).or_insert(start_line);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    lines
+}
+
+/// Línea de cada clave de un YAML de traducciones (mapas anidados por indentación)
+fn yaml_key_lines(content: &str) -> BTreeMap<String, usize> {
+    let key_re =
+        Regex::new(r#"^(\s*)(?:"([^"]+)"|'([^']+)'|([^\s#'"\-][^:]*?))\s*:(?:\s|$)"#).unwrap();
+    let mut lines = BTreeMap::new();
+    let mut path: Vec<(usize, String)> = Vec::new();
+    for (index, text) in content.lines().enumerate() {
+        let Some(cap) = key_re.captures(text) else {
+            continue;
+        };
+        let indent = cap[1].len();
+        let key = cap.get(2).or(cap.get(3)).or(cap.get(4)).unwrap().as_str();
+        while path.last().is_some_and(|(i, _)| *i >= indent) {
+            path.pop();
+        }
+        path.push((indent, key.to_string()));
+        let key: Vec<&str> = path.iter().map(|(_, k)| k.as_str()).collect();
+        lines.entry(key.join(".")).or_insert(index + 1);
+    }
+    lines
+}
+
+/// Entradas msgid/msgstr de un catálogo gettext (las cadenas partidas en varias
+/// líneas se concatenan; de los plurales se toma `msgstr[0]`)
+fn parse_po(content: &str) -> Vec<I18nEntry> {
+    #[derive(PartialEq)]
+    enum Field {
+        Id,
+        Str,
+        Other,
+    }
+
+    let mut entries = Vec::new();
+    let mut current: Option<(String, String, usize)> = None;
+    let mut field = Field::Other;
+    let mut push = |current: &mut Option<(String, String, usize)>| {
+        if let Some((key, value, line)) = current.take() {
+            if !key.is_empty() {
+                entries.push(I18nEntry { key, value, line });
+            }
+        }
+    };
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("msgid ") {
+            push(&mut current);
+            current = Some((po_string(rest), String::new(), index + 1));
+            field = Field::Id;
+        } else if let Some(rest) = line
+            .strip_prefix("msgstr[0] ")
+            .or_else(|| line.strip_prefix("msgstr "))
+        {
+            if let Some((_, value, _)) = current.as_mut() {
+                *value = po_string(rest);
+            }
+            field = Field::Str;
+        } else if line.starts_with('"') {
+            if let Some((key, value, _)) = current.as_mut() {
+                match field {
+                    Field::Id => key.push_str(&po_string(line)),
+                    Field::Str => value.push_str(&po_string(line)),
+                    Field::Other => {}
+                }
+            }
+        } else if !line.is_empty() && !line.starts_with('#') {
+            // msgid_plural, msgstr[n>0], msgctxt
+            field = Field::Other;
+        }
+    }
+    push(&mut current);
+    entries
+}
+
+fn po_string(quoted: &str) -> String {
+    quoted
+        .trim()
+        .trim_start_matches('"')
+        .trim_end_matches('"')
+        .replace("\\n", "\n")
+        .replace("\\\"", "\"")
+}
+
+/// Reemplaza los chunks de clave (state_config, metadata `kind: "i18n_key"`) de un
+/// archivo de traducción: las claves sin cambios conservan su chunk y las que
+/// desaparecieron se borran con sus relaciones.
+/// Retorna el número de chunks nuevos
+pub fn generate_i18n_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+) -> Result<usize> {
+    let Some((locale, namespace)) = i18n_file_locale(file_path) else {
+        return Ok(0);
+    };
+    let entries = parse_i18n_entries(file_path, content, &locale)?;
+
+    let chunks: Vec<Chunk> = entries
+        .into_iter()
+        .map(|entry| -> Result<Chunk> {
+            let id = key_id_string(&namespace, &entry.key);
+            let content = format!("[{}] {} = {}", locale, id, entry.value);
+            let metadata = I18nKeyMetadata {
+                kind: "i18n_key".to_string(),
+                locale: locale.clone(),
+                namespace: namespace.clone(),
+                key: entry.key.clone(),
+                line: entry.line,
+            };
+            Ok(Chunk {
+                id: None,
+                project_path: project_path.to_string(),
+                chunk_type: ChunkType::StateConfig,
+                file_path: Some(file_path.to_string()),
+                entity_name: Some(id),
+                content_hash: calculate_content_hash(&format!(
+                    "{}\n{}\n{}",
+                    project_path, file_path, content
+                )),
+                content,
+                metadata: Some(serde_json::to_string(&metadata)?),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let hashes: BTreeSet<&str> = chunks.iter().map(|c| c.content_hash.as_str()).collect();
+    let mut stmt = conn.prepare(
+        "SELECT id, content_hash FROM chunks
+         WHERE project_path = ?1 AND file_path = ?2 AND chunk_type = ?3
+           AND json_extract(metadata, '$.kind') = 'i18n_key'",
+    )?;
+    let stale: Vec<i64> = stmt
+        .query_map(
+            params![
+                project_path,
+                normalize_file_path(file_path),
+                ChunkType::StateConfig.as_str()
+            ],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, hash)| !hashes.contains(hash.as_str()))
+        .map(|(id, _)| id)
+        .collect();
+    for id in stale {
+        conn.execute(
+            "DELETE FROM chunk_relationships WHERE from_chunk_id = ?1 OR to_chunk_id = ?1",
+            params![id],
+        )?;
+        conn.execute("DELETE FROM chunks WHERE id = ?1", params![id])?;
+    }
+
+    let mut created = 0;
+    for chunk in &chunks {
+        if upsert_chunk(conn, chunk, None)? {
+            created += 1;
+        }
+    }
+    Ok(created)
+}
+
+/// `namespace:clave` o solo la clave
+fn key_id_string(namespace: &Option<String>, key: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{}:{}", namespace, key),
+        None => key.to_string(),
+    }
+}
+
+/// Claves usadas en un archivo de código: (clave, línea, segura). Las llamadas
+/// `t('x')`, `$t('x')`, `i18n.t('x')`, `_('x')`, `gettext('x')` son seguras; `id="x"`
+/// e `i18nKey="x"` (react-intl, `<Trans>`) solo cuentan si la clave existe
+pub fn extract_key_usages(content: &str) -> Vec<(String, usize, bool)> {
+    let call_re = Regex::new(
+        r#"(?:^|[^\w$])(?:\$t|\$tc|t|tc|i18n\.t|i18next\.t|translate|_|gettext|ngettext|pgettext)\s*\(\s*['"`]([^'"`$\n]+)['"`]"#,
+    )
+    .unwrap();
+    let id_re = Regex::new(r#"\b(?:id|i18nKey)\s*[:=]\s*\{?\s*['"]([^'"\n]+)['"]"#).unwrap();
+
+    let line_of = |offset: usize| content[..offset].matches('\n').count() + 1;
+    let mut usages: Vec<(String, usize, bool)> = call_re
+        .captures_iter(content)
+        .map(|cap| {
+            (
+                cap[1].to_string(),
+                line_of(cap.get(1).unwrap().start()),
+                true,
+            )
+        })
+        .collect();
+    usages.extend(id_re.captures_iter(content).map(|cap| {
+        (
+            cap[1].to_string(),
+            line_of(cap.get(1).unwrap().start()),
+            false,
+        )
+    }));
+    usages.sort_by_key(|(_, line, _)| *line);
+    usages
+}
+
+/// Archivos de código donde se buscan usos de claves
+fn is_usage_source(file_path: &str) -> bool {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    matches!(
+        extension.as_str(),
+        "js" | "jsx"
+            | "mjs"
+            | "cjs"
+            | "ts"
+            | "tsx"
+            | "vue"
+            | "svelte"
+            | "py"
+            | "rb"
+            | "php"
+            | "html"
+    )
+}
+
+/// Claves definidas a las que se refiere un uso: `ns:clave` exacto, o la clave en
+/// cualquier namespace
+fn resolve_usage<'a>(keys: &'a KeyLocales, usage: &str) -> Vec<&'a KeyId> {
+    if let Some((namespace, key)) = usage.split_once(':') {
+        let id = (Some(namespace.to_string()), key.to_string());
+        if let Some((id, _)) = keys.get_key_value(&id) {
+            return vec![id];
+        }
+    }
+    keys.keys().filter(|(_, key)| key == usage).collect()
+}
+
+/// (namespace, clave) → locales que la definen
+type KeyLocales = BTreeMap<KeyId, BTreeSet<String>>;
+
+/// Claves de traducción del proyecto: (namespace, clave) → locales que la definen,
+/// y id del chunk de cada (namespace, clave, locale)
+fn load_keys(conn: &Connection, project_path: &str) -> Result<(KeyLocales, Vec<(KeyId, i64)>)> {
+    let mut stmt = conn.prepare(
+        "SELECT id, metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND deleted_at IS NULL
+           AND json_extract(metadata, '$.kind') = 'i18n_key'",
+    )?;
+    let rows = stmt
+        .query_map(
+            params![project_path, ChunkType::StateConfig.as_str()],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut keys: BTreeMap<KeyId, BTreeSet<String>> = BTreeMap::new();
+    let mut chunk_ids = Vec::new();
+    for (chunk_id, metadata) in rows {
+        let Ok(metadata) = serde_json::from_str::<I18nKeyMetadata>(&metadata) else {
+            continue;
+        };
+        let id = (metadata.namespace, metadata.key);
+        keys.entry(id.clone()).or_default().insert(metadata.locale);
+        chunk_ids.push((id, chunk_id));
+    }
+    Ok((keys, chunk_ids))
+}
+
+/// `configures_for` desde cada chunk de clave (en todos sus locales) hacia el chunk
+/// raw de los archivos que la usan. Retorna el número de relaciones creadas
+pub fn rebuild_i18n_relationships(
+    conn: &Connection,
+    project_path: &str,
+    sources: &BTreeMap<String, (i64, String)>,
+) -> Result<usize> {
+    delete_relationships_by_source(conn, project_path, I18N_USAGE_SOURCE)?;
+    let (keys, chunk_ids) = load_keys(conn, project_path)?;
+    if keys.is_empty() {
+        return Ok(0);
+    }
+
+    let mut created = 0;
+    for (file_path, (chunk_id, content)) in sources {
+        if !is_usage_source(file_path) {
+            continue;
+        }
+        let mut lines: BTreeMap<&KeyId, Vec<usize>> = BTreeMap::new();
+        for (usage, line, _) in extract_key_usages(content) {
+            for id in resolve_usage(&keys, &usage) {
+                lines.entry(id).or_default().push(line);
+            }
+        }
+        for (id, lines) in lines {
+            for (_, key_chunk_id) in chunk_ids.iter().filter(|(key, _)| key == id) {
+                insert_relationship(
+                    conn,
+                    &relationship(
+                        *key_chunk_id,
+                        *chunk_id,
+                        RelationshipType::ConfiguresFor,
+                        serde_json::json!({
+                            "source": I18N_USAGE_SOURCE,
+                            "key": key_id_string(&id.0, &id.1),
+                            "to_file": file_path,
+                            "lines": lines,
+                        }),
+                    ),
+                )?;
+                created += 1;
+            }
+        }
+    }
+    Ok(created)
+}
+
+/// Claves sin traducir en algún locale, sin usar en el código y usadas sin definir.
+/// Las claves construidas dinámicamente (`t(`items.${id}`)`) no se detectan, así que
+/// las "sin usar" son candidatas a revisar, no a borrar a ciegas
+pub fn get_i18n_report(conn: &Connection, project_path: &str) -> Result<I18nReport> {
+    let (keys, _) = load_keys(conn, project_path)?;
+    let mut report = I18nReport {
+        project_path: project_path.to_string(),
+        ..Default::default()
+    };
+    if keys.is_empty() {
+        return Ok(report);
+    }
+
+    let locales: BTreeSet<String> = keys.values().flatten().cloned().collect();
+    let mut used: BTreeSet<&KeyId> = BTreeSet::new();
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+    for (file_path, (_, content)) in &sources {
+        if !is_usage_source(file_path) {
+            continue;
+        }
+        for (usage, line, certain) in extract_key_usages(content) {
+            let ids = resolve_usage(&keys, &usage);
+            if ids.is_empty() && certain && !usage.starts_with('.') {
+                report.undefined.push(I18nUsage {
+                    key: usage,
+                    file_path: file_path.clone(),
+                    line,
+                });
+            }
+            used.extend(ids);
+        }
+    }
+
+    for (id, defined_in) in &keys {
+        let missing_locales: Vec<String> = locales.difference(defined_in).cloned().collect();
+        if !missing_locales.is_empty() {
+            report.missing.push(I18nMissingKey {
+                key: key_id_string(&id.0, &id.1),
+                missing_locales,
+            });
+        }
+        if !used.contains(id) {
+            report.unused.push(key_id_string(&id.0, &id.1));
+        }
+    }
+    report.locales = locales.into_iter().collect();
+    report.key_count = keys.len();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+
+    #[test]
+    fn test_i18n_file_locale() {
+        assert_eq!(
+            i18n_file_locale("src/locales/en.json"),
+            Some(("en".to_string(), None))
+        );
+        assert_eq!(
+            i18n_file_locale("public/locales/pt-BR/common.json"),
+            Some(("pt-BR".to_string(), Some("common".to_string())))
+        );
+        assert_eq!(
+            i18n_file_locale("config/locales/es.yml"),
+            Some(("es".to_string(), None))
+        );
+        assert_eq!(
+            i18n_file_locale("locale/fr/LC_MESSAGES/django.po"),
+            Some(("fr".to_string(), None))
+        );
+        assert_eq!(i18n_file_locale("src/config/en.json"), None);
+        assert_eq!(i18n_file_locale("src/locales/index.json"), None);
+        assert_eq!(i18n_file_locale("package.json"), None);
+    }
+
+    #[test]
+    fn test_parse_i18n_entries() {
+        let json = "{\n  \"settings\": {\n    \"title\": \"Settings\",\n    \"save\": \"Save\"\n  },\n  \"title\": \"Home\"\n}\n";
+        let entries = parse_i18n_entries("locales/en.json", json, "en").unwrap();
+        let keys: Vec<(&str, &str, usize)> = entries
+            .iter()
+            .map(|e| (e.key.as_str(), e.value.as_str(), e.line))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("settings.save", "Save", 4),
+                ("settings.title", "Settings", 3),
+                ("title", "Home", 6),
+            ]
+        );
+
+        let yaml = "es:\n  settings:\n    title: Ajustes\n";
+        let entries = parse_i18n_entries("config/locales/es.yml", yaml, "es").unwrap();
+        assert_eq!(entries[0].key, "settings.title");
+        assert_eq!(entries[0].value, "Ajustes");
+        assert_eq!(entries[0].line, 3);
+
+        let po = "msgid \"\"\nmsgstr \"Content-Type: text/plain\"\n\n#: app.py:3\nmsgid \"Hello\"\nmsgstr \"\"\n\"Hola\"\n";
+        let entries = parse_i18n_entries("po/es.po", po, "es").unwrap();
+        assert_eq!(
+            entries,
+            vec![I18nEntry {
+                key: "Hello".to_string(),
+                value: "Hola".to_string(),
+                line: 5,
+            }]
+        );
+
+        assert!(parse_i18n_entries("locales/en.json", "{", "en").is_err());
+    }
+
+    #[test]
+    fn test_i18n_report_and_relationships() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let en = r#"{"settings": {"title": "Settings", "save": "Save"}, "old": "Old"}"#;
+        let es = r#"{"settings": {"title": "Ajustes"}}"#;
+        assert_eq!(
+            generate_i18n_chunks(&conn, "/p", "src/locales/en.json", en).unwrap(),
+            3
+        );
+        assert_eq!(
+            generate_i18n_chunks(&conn, "/p", "src/locales/es.json", es).unwrap(),
+            1
+        );
+
+        let code = "const title = t('settings.title');\nconst x = t(\"settings.missing\");\nconst y = t(`items.${id}`);\n<FormattedMessage id=\"settings.save\" />\n";
+        chunk().file("src/Settings.tsx").content(code).insert(&conn);
+
+        let report = get_i18n_report(&conn, "/p").unwrap();
+        assert_eq!(report.locales, vec!["en", "es"]);
+        assert_eq!(report.key_count, 3);
+        let missing: Vec<&str> = report.missing.iter().map(|m| m.key.as_str()).collect();
+        assert_eq!(missing, vec!["old", "settings.save"]);
+        assert_eq!(report.missing[0].missing_locales, vec!["es"]);
+        assert_eq!(report.unused, vec!["old"]);
+        assert_eq!(report.undefined.len(), 1);
+        assert_eq!(report.undefined[0].key, "settings.missing");
+        assert_eq!(report.undefined[0].line, 2);
+
+        let sources = get_latest_file_contents(&conn, "/p", &ChunkType::RawSource).unwrap();
+        // settings.title en en y es, settings.save en en
+        assert_eq!(
+            rebuild_i18n_relationships(&conn, "/p", &sources).unwrap(),
+            3
+        );
+
+        // Quitar una clave borra su chunk y sus relaciones
+        let en = r#"{"settings": {"title": "Settings"}}"#;
+        assert_eq!(
+            generate_i18n_chunks(&conn, "/p", "src/locales/en.json", en).unwrap(),
+            0
+        );
+        let report = get_i18n_report(&conn, "/p").unwrap();
+        assert_eq!(report.key_count, 1);
+        assert!(report.missing.is_empty() && report.unused.is_empty());
+        let relationships: i64 = conn
+            .query_row("SELECT COUNT(*) FROM chunk_relationships", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(relationships, 2);
+    }
+}
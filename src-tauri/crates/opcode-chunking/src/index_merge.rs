@@ -0,0 +1,520 @@
+use super::read_only::ensure_index_writable;
+use super::storage::{
+    get_business_rules, insert_relationship, normalize_file_path, query_chunks, row_timestamp,
+    store_content_blob,
+};
+use super::types::{
+    BusinessRule, Chunk, ChunkQuery, ChunkRelationship, ExportedRelationship, IndexExport,
+    IndexMergeConflict, IndexMergeReport, MergeConflictKind, MergeResolution, RelationshipType,
+};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+/// Versión del formato de [`IndexExport`]; se rechazan los archivos de versiones futuras
+pub const INDEX_EXPORT_VERSION: u32 = 1;
+
+/// Exporta los chunks vigentes, las reglas de negocio y las relaciones del proyecto
+pub fn export_index(conn: &Connection, project_path: &str) -> Result<IndexExport> {
+    let chunks = query_chunks(
+        conn,
+        &ChunkQuery {
+            project_path: Some(project_path.to_string()),
+            ..Default::default()
+        },
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT f.content_hash, t.content_hash, r.relationship_type, r.metadata
+         FROM chunk_relationships r
+         JOIN chunks f ON f.id = r.from_chunk_id
+         JOIN chunks t ON t.id = r.to_chunk_id
+         WHERE f.project_path = ?1 AND f.deleted_at IS NULL AND t.deleted_at IS NULL
+         ORDER BY r.id",
+    )?;
+    let relationships = stmt
+        .query_map(params![project_path], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .into_iter()
+        .filter_map(|(from_hash, to_hash, kind, metadata)| {
+            Some(ExportedRelationship {
+                from_hash,
+                to_hash,
+                relationship_type: RelationshipType::from_str(&kind)?,
+                metadata,
+            })
+        })
+        .collect();
+
+    Ok(IndexExport {
+        format_version: INDEX_EXPORT_VERSION,
+        source_project_path: project_path.to_string(),
+        exported_at: Utc::now(),
+        chunks,
+        business_rules: get_business_rules(conn, project_path)?,
+        relationships,
+    })
+}
+
+/// Escribe el índice exportado como JSON
+pub fn save_index_export(export: &IndexExport, path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(export)?;
+    std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Lee un índice exportado, rechazando versiones del formato más nuevas
+pub fn load_index_export(path: &Path) -> Result<IndexExport> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let export: IndexExport = serde_json::from_str(&json).context("Invalid index export")?;
+    if export.format_version > INDEX_EXPORT_VERSION {
+        bail!(
+            "Index export format {} is newer than the supported {}",
+            export.format_version,
+            INDEX_EXPORT_VERSION
+        );
+    }
+    Ok(export)
+}
+
+/// Fusiona el índice exportado por otro usuario en `project_path`, en una transacción.
+/// Los chunks se emparejan por hash de contenido: los nuevos se insertan con sus fechas
+/// (la versión más reciente de un archivo sigue siendo la de `updated_at` mayor) y si
+/// la metadata del mismo chunk diverge gana la más reciente. Las reglas se emparejan
+/// por archivo y entidad: una validada gana a una propuesta y, entre iguales, la más
+/// reciente; las etiquetas se suman. Las divergencias quedan en `conflicts`
+pub fn merge_index(
+    conn: &Connection,
+    project_path: &str,
+    export: &IndexExport,
+) -> Result<IndexMergeReport> {
+    ensure_index_writable(conn, project_path)?;
+    let mut report = IndexMergeReport {
+        project_path: project_path.to_string(),
+        source_project_path: export.source_project_path.clone(),
+        ..Default::default()
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    for chunk in &export.chunks {
+        merge_chunk(&tx, project_path, chunk, &mut report)?;
+    }
+
+    let mut local_rules: HashMap<(String, String), BusinessRule> =
+        get_business_rules(&tx, project_path)?
+            .into_iter()
+            .map(|rule| ((rule.file_path.clone(), rule.entity_name.clone()), rule))
+            .collect();
+    for rule in &export.business_rules {
+        let key = (
+            normalize_file_path(&rule.file_path),
+            rule.entity_name.clone(),
+        );
+        match local_rules.remove(&key) {
+            Some(local) => merge_business_rule(&tx, &local, rule, &mut report)?,
+            None => {
+                insert_business_rule(&tx, project_path, rule)?;
+                report.rules_imported += 1;
+            }
+        }
+    }
+
+    for relationship in &export.relationships {
+        let (Some(from_id), Some(to_id)) = (
+            project_chunk_id(&tx, project_path, &relationship.from_hash)?,
+            project_chunk_id(&tx, project_path, &relationship.to_hash)?,
+        ) else {
+            continue;
+        };
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS (SELECT 1 FROM chunk_relationships
+                            WHERE from_chunk_id = ?1 AND to_chunk_id = ?2 AND relationship_type = ?3)",
+            params![from_id, to_id, relationship.relationship_type.as_str()],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            insert_relationship(
+                &tx,
+                &ChunkRelationship {
+                    id: None,
+                    from_chunk_id: from_id,
+                    to_chunk_id: to_id,
+                    relationship_type: relationship.relationship_type.clone(),
+                    metadata: relationship.metadata.clone(),
+                    created_at: Utc::now(),
+                },
+            )?;
+            report.relationships_imported += 1;
+        }
+    }
+    tx.commit()?;
+
+    log::info!(
+        "Merged index of {} into {}: {} chunks imported, {} rules imported, {} conflicts",
+        report.source_project_path,
+        project_path,
+        report.chunks_imported,
+        report.rules_imported,
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
+/// Id del chunk con ese hash si pertenece al proyecto
+fn project_chunk_id(conn: &Connection, project_path: &str, hash: &str) -> Result<Option<i64>> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM chunks WHERE content_hash = ?1 AND project_path = ?2",
+            params![hash, project_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+fn merge_chunk(
+    conn: &Connection,
+    project_path: &str,
+    chunk: &Chunk,
+    report: &mut IndexMergeReport,
+) -> Result<()> {
+    let local = conn
+        .query_row(
+            "SELECT id, metadata, updated_at FROM chunks WHERE project_path = ?1 AND content_hash = ?2",
+            params![project_path, &chunk.content_hash],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row_timestamp(row, 2)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    let Some((local_id, local_metadata, local_updated_at)) = local else {
+        insert_chunk(conn, project_path, chunk)?;
+        report.chunks_imported += 1;
+        return Ok(());
+    };
+    if local_metadata == chunk.metadata {
+        report.chunks_unchanged += 1;
+        return Ok(());
+    }
+
+    let take_imported = chunk.updated_at > local_updated_at;
+    report.conflicts.push(IndexMergeConflict {
+        kind: MergeConflictKind::Annotation,
+        key: chunk.content_hash.clone(),
+        local: local_metadata.unwrap_or_default(),
+        imported: chunk.metadata.clone().unwrap_or_default(),
+        local_updated_at,
+        imported_updated_at: chunk.updated_at,
+        resolution: resolution(take_imported),
+    });
+    if take_imported {
+        conn.execute(
+            "UPDATE chunks SET metadata = ?1, updated_at = ?2 WHERE id = ?3",
+            params![&chunk.metadata, chunk.updated_at.to_rfc3339(), local_id],
+        )?;
+        report.chunks_updated += 1;
+    } else {
+        report.chunks_unchanged += 1;
+    }
+    Ok(())
+}
+
+/// Inserta el chunk importado en el proyecto conservando sus fechas
+fn insert_chunk(conn: &Connection, project_path: &str, chunk: &Chunk) -> Result<()> {
+    let blob_hash = store_content_blob(conn, &chunk.content)?;
+    conn.execute(
+        "INSERT INTO chunks (project_path, chunk_type, file_path, entity_name, content, content_hash, metadata, created_at, updated_at, blob_hash)
+         VALUES (?1, ?2, ?3, ?4, '', ?5, ?6, ?7, ?8, ?9)",
+        params![
+            project_path,
+            chunk.chunk_type.as_str(),
+            chunk.file_path.as_deref().map(normalize_file_path),
+            &chunk.entity_name,
+            &chunk.content_hash,
+            &chunk.metadata,
+            chunk.created_at.to_rfc3339(),
+            chunk.updated_at.to_rfc3339(),
+            &blob_hash,
+        ],
+    )?;
+    Ok(())
+}
+
+fn resolution(take_imported: bool) -> MergeResolution {
+    if take_imported {
+        MergeResolution::TookImported
+    } else {
+        MergeResolution::KeptLocal
+    }
+}
+
+/// Texto efectivo de una regla: la corrección del usuario o la descripción
+fn rule_text(rule: &BusinessRule) -> String {
+    let text = rule
+        .user_correction
+        .as_deref()
+        .unwrap_or(&rule.rule_description);
+    if rule.is_validated {
+        format!("{} (validated)", text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn merge_business_rule(
+    conn: &Connection,
+    local: &BusinessRule,
+    imported: &BusinessRule,
+    report: &mut IndexMergeReport,
+) -> Result<()> {
+    let tags: BTreeSet<String> = local.tags.iter().chain(&imported.tags).cloned().collect();
+    let tags: Vec<String> = tags.into_iter().collect();
+    let same_rule = local.rule_description == imported.rule_description
+        && local.user_correction == imported.user_correction
+        && local.is_validated == imported.is_validated
+        && local.check == imported.check;
+
+    let take_imported = if same_rule {
+        false
+    } else if local.is_validated != imported.is_validated {
+        // Una regla validada gana a una propuesta sin validar; no es un conflicto
+        imported.is_validated
+    } else {
+        let take_imported = imported.updated_at > local.updated_at;
+        report.conflicts.push(IndexMergeConflict {
+            kind: MergeConflictKind::BusinessRule,
+            key: format!("{}#{}", local.file_path, local.entity_name),
+            local: rule_text(local),
+            imported: rule_text(imported),
+            local_updated_at: local.updated_at,
+            imported_updated_at: imported.updated_at,
+            resolution: resolution(take_imported),
+        });
+        take_imported
+    };
+
+    let rule_id = local.id.context("Business rule without id")?;
+    if take_imported {
+        conn.execute(
+            "UPDATE business_rules SET rule_description = ?1, ai_interpretation = ?2,
+                 user_correction = ?3, is_validated = ?4, validation_date = ?5, check_spec = ?6,
+                 tags = ?7, updated_at = ?8
+             WHERE id = ?9",
+            params![
+                &imported.rule_description,
+                &imported.ai_interpretation,
+                &imported.user_correction,
+                imported.is_validated,
+                imported.validation_date.map(|d| d.to_rfc3339()),
+                imported
+                    .check
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+                serde_json::to_string(&tags)?,
+                imported.updated_at.max(local.updated_at).to_rfc3339(),
+                rule_id,
+            ],
+        )?;
+        report.rules_updated += 1;
+    } else if tags != local.tags {
+        conn.execute(
+            "UPDATE business_rules SET tags = ?1 WHERE id = ?2",
+            params![serde_json::to_string(&tags)?, rule_id],
+        )?;
+        report.rules_updated += 1;
+    } else {
+        report.rules_unchanged += 1;
+    }
+    Ok(())
+}
+
+/// Inserta la regla importada en el proyecto conservando sus fechas
+fn insert_business_rule(conn: &Connection, project_path: &str, rule: &BusinessRule) -> Result<()> {
+    conn.execute(
+        "INSERT INTO business_rules (project_path, entity_name, file_path, rule_description, ai_interpretation, user_correction, is_validated, validation_date, created_at, updated_at, tags, check_spec)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            project_path,
+            &rule.entity_name,
+            normalize_file_path(&rule.file_path),
+            &rule.rule_description,
+            &rule.ai_interpretation,
+            &rule.user_correction,
+            rule.is_validated,
+            rule.validation_date.map(|d| d.to_rfc3339()),
+            rule.created_at.to_rfc3339(),
+            rule.updated_at.to_rfc3339(),
+            serde_json::to_string(&rule.tags)?,
+            rule.check.as_ref().map(serde_json::to_string).transpose()?,
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        calculate_content_hash, init_chunk_database, upsert_business_rule, upsert_chunk,
+    };
+    use crate::types::ChunkType;
+    use chrono::Duration;
+
+    fn chunk(project_path: &str, file_path: &str, content: &str, metadata: Option<&str>) -> Chunk {
+        Chunk {
+            id: None,
+            project_path: project_path.to_string(),
+            chunk_type: ChunkType::RawSource,
+            file_path: Some(file_path.to_string()),
+            entity_name: None,
+            content: content.to_string(),
+            content_hash: calculate_content_hash(&format!("{}{}", file_path, content)),
+            metadata: metadata.map(str::to_string),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn rule(
+        project_path: &str,
+        entity_name: &str,
+        description: &str,
+        validated: bool,
+    ) -> BusinessRule {
+        BusinessRule {
+            id: None,
+            project_path: project_path.to_string(),
+            entity_name: entity_name.to_string(),
+            file_path: "src/billing.rs".to_string(),
+            rule_description: description.to_string(),
+            ai_interpretation: description.to_string(),
+            user_correction: None,
+            is_validated: validated,
+            validation_date: None,
+            tags: vec![],
+            check: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_merge_teammate_index() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        upsert_chunk(
+            &conn,
+            &chunk("/mine", "src/a.rs", "fn a() {}", Some(r#"{"note":"mine"}"#)),
+            None,
+        )
+        .unwrap();
+        upsert_chunk(&conn, &chunk("/mine", "src/b.rs", "fn b() {}", None), None).unwrap();
+        upsert_business_rule(
+            &conn,
+            &rule("/mine", "invoice", "Invoices are immutable", true),
+        )
+        .unwrap();
+        upsert_business_rule(
+            &conn,
+            &rule("/mine", "refund", "Refunds need approval", false),
+        )
+        .unwrap();
+
+        // El compañero exporta desde otra ruta, con anotaciones y reglas más recientes
+        let later = Utc::now() + Duration::minutes(5);
+        let export = IndexExport {
+            format_version: INDEX_EXPORT_VERSION,
+            source_project_path: "/theirs".to_string(),
+            exported_at: later,
+            chunks: vec![
+                Chunk {
+                    updated_at: later,
+                    ..chunk(
+                        "/theirs",
+                        "src/a.rs",
+                        "fn a() {}",
+                        Some(r#"{"note":"theirs"}"#),
+                    )
+                },
+                chunk("/theirs", "src/b.rs", "fn b() {}", None),
+                chunk("/theirs", "src/c.rs", "fn c() {}", None),
+            ],
+            business_rules: vec![
+                BusinessRule {
+                    updated_at: later,
+                    tags: vec!["critical".to_string()],
+                    ..rule("/theirs", "invoice", "Invoices can be voided", true)
+                },
+                rule("/theirs", "refund", "Refunds need approval", true),
+                rule("/theirs", "payout", "Payouts run weekly", false),
+            ],
+            relationships: vec![ExportedRelationship {
+                from_hash: calculate_content_hash("src/c.rsfn c() {}"),
+                to_hash: calculate_content_hash("src/a.rsfn a() {}"),
+                relationship_type: RelationshipType::DependsOn,
+                metadata: None,
+            }],
+        };
+
+        let report = merge_index(&conn, "/mine", &export).unwrap();
+        assert_eq!(report.chunks_imported, 1);
+        assert_eq!(report.chunks_updated, 1);
+        assert_eq!(report.chunks_unchanged, 1);
+        assert_eq!(report.rules_imported, 1);
+        assert_eq!(report.rules_updated, 2);
+        assert_eq!(report.relationships_imported, 1);
+        let kinds: Vec<_> = report
+            .conflicts
+            .iter()
+            .map(|c| (c.kind, c.resolution))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (MergeConflictKind::Annotation, MergeResolution::TookImported),
+                (
+                    MergeConflictKind::BusinessRule,
+                    MergeResolution::TookImported
+                ),
+            ]
+        );
+
+        let rules = get_business_rules(&conn, "/mine").unwrap();
+        assert_eq!(rules.len(), 3);
+        let invoice = rules.iter().find(|r| r.entity_name == "invoice").unwrap();
+        assert_eq!(invoice.rule_description, "Invoices can be voided");
+        assert_eq!(invoice.tags, vec!["critical"]);
+        assert!(
+            rules
+                .iter()
+                .find(|r| r.entity_name == "refund")
+                .unwrap()
+                .is_validated
+        );
+
+        // Importar dos veces no duplica nada
+        let report = merge_index(&conn, "/mine", &export).unwrap();
+        assert_eq!(report.chunks_imported + report.rules_imported, 0);
+        assert_eq!(report.relationships_imported, 0);
+        assert!(report.conflicts.is_empty());
+        let exported = export_index(&conn, "/mine").unwrap();
+        assert_eq!(exported.chunks.len(), 3);
+        assert_eq!(exported.relationships.len(), 1);
+    }
+}
@@ -0,0 +1,343 @@
+use super::hashing::{find_project_id, get_hash_algorithm};
+use super::index_runs::parse_status;
+use super::storage::row_timestamp;
+use super::types::{ChunkType, ChunkTypeProfile, IndexProfile, IndexRunProfile, SizeDistribution};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Versión del formato de [`IndexProfile`]
+pub const INDEX_PROFILE_VERSION: u32 = 2;
+
+/// Indexaciones recientes incluidas en el perfil
+const PROFILE_RECENT_RUNS: usize = 20;
+
+/// Extensiones más largas (o con otros caracteres) se agrupan como "other" para no
+/// filtrar nombres de archivo
+const MAX_EXTENSION_CHARS: usize = 8;
+
+/// Tamaño del contenido de un chunk, esté en `content_blobs` o en línea
+const CONTENT_SIZE: &str = "COALESCE(b.size, length(CAST(c.content AS BLOB)))";
+
+/// Perfil agregado del índice del proyecto: conteos, tamaños y tiempos sin rutas,
+/// nombres, mensajes ni contenido, para adjuntar a reportes de rendimiento
+pub fn build_index_profile(conn: &Connection, project_path: &str) -> Result<IndexProfile> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT c.chunk_type, COUNT(*), COALESCE(SUM({size}), 0), COALESCE(MAX({size}), 0)
+         FROM chunks c LEFT JOIN content_blobs b ON b.hash = c.blob_hash
+         WHERE c.project_path = ?1 AND c.deleted_at IS NULL
+         GROUP BY c.chunk_type",
+        size = CONTENT_SIZE
+    ))?;
+    let chunks_by_type = stmt
+        .query_map(params![project_path], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                ChunkTypeProfile {
+                    count: row.get::<_, i64>(1)? as usize,
+                    total_bytes: row.get::<_, i64>(2)? as u64,
+                    max_bytes: row.get::<_, i64>(3)? as u64,
+                },
+            ))
+        })?
+        .collect::<rusqlite::Result<BTreeMap<_, _>>>()?;
+
+    let (deleted_chunks, stale_chunks): (i64, i64) = conn.query_row(
+        "SELECT COALESCE(SUM(deleted_at IS NOT NULL), 0),
+                COALESCE(SUM(deleted_at IS NULL AND stale = 1), 0)
+         FROM chunks WHERE project_path = ?1",
+        params![project_path],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    // Tamaño del raw source por archivo; las rutas solo se usan para la extensión
+    let mut stmt = conn.prepare(&format!(
+        "SELECT c.file_path, COALESCE(SUM(CASE WHEN c.chunk_type = ?2 THEN {} END), 0)
+         FROM chunks c LEFT JOIN content_blobs b ON b.hash = c.blob_hash
+         WHERE c.project_path = ?1 AND c.deleted_at IS NULL AND c.file_path IS NOT NULL
+         GROUP BY c.file_path",
+        CONTENT_SIZE
+    ))?;
+    let files = stmt
+        .query_map(
+            params![project_path, ChunkType::RawSource.as_str()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let mut files_by_extension = BTreeMap::new();
+    for (file_path, _) in &files {
+        *files_by_extension
+            .entry(extension_bucket(file_path))
+            .or_insert(0) += 1;
+    }
+    let sizes: Vec<u64> = files
+        .iter()
+        .map(|(_, size)| *size)
+        .filter(|size| *size > 0)
+        .collect();
+
+    let mut stmt = conn.prepare(
+        "SELECT json_extract(metadata, '$.language'), COUNT(*) FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND entity_name IS NULL
+           AND deleted_at IS NULL AND json_valid(metadata)
+         GROUP BY 1",
+    )?;
+    let ast_languages = stmt
+        .query_map(params![project_path, ChunkType::Ast.as_str()], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, i64>(1)? as usize,
+            ))
+        })?
+        .filter_map(|row| match row {
+            Ok((language, count)) => language.map(|language| Ok((language, count))),
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<rusqlite::Result<BTreeMap<_, _>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT r.relationship_type, COUNT(*) FROM chunk_relationships r
+         JOIN chunks f ON f.id = r.from_chunk_id
+         WHERE f.project_path = ?1 AND f.deleted_at IS NULL
+         GROUP BY r.relationship_type",
+    )?;
+    let relationships_by_type = stmt
+        .query_map(params![project_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?
+        .collect::<rusqlite::Result<BTreeMap<_, _>>>()?;
+
+    let count = |sql: &str| -> Result<usize> {
+        let count: i64 = conn.query_row(sql, params![project_path], |row| row.get(0))?;
+        Ok(count as usize)
+    };
+    let database_bytes: i64 = conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    )?;
+
+    Ok(IndexProfile {
+        format_version: INDEX_PROFILE_VERSION,
+        generated_at: Utc::now(),
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        project_id: find_project_id(conn, project_path)?,
+        hash_algorithm: get_hash_algorithm(conn, project_path)?,
+        files: files.len(),
+        files_by_extension,
+        chunks_by_type,
+        deleted_chunks: deleted_chunks as usize,
+        stale_chunks: stale_chunks as usize,
+        ast_languages,
+        file_sizes: size_distribution(sizes),
+        relationships_by_type,
+        snapshots: count("SELECT COUNT(*) FROM snapshots WHERE project_path = ?1")?,
+        business_rules: count("SELECT COUNT(*) FROM business_rules WHERE project_path = ?1")?,
+        open_errors: count(
+            "SELECT COUNT(*) FROM error_logs WHERE project_path = ?1 AND is_resolved = 0",
+        )?,
+        parse_failures: count("SELECT COUNT(*) FROM parse_failures WHERE project_path = ?1")?,
+        database_bytes: database_bytes as u64,
+        recent_runs: recent_runs(conn, project_path)?,
+    })
+}
+
+fn recent_runs(conn: &Connection, project_path: &str) -> Result<Vec<IndexRunProfile>> {
+    let mut stmt = conn.prepare(
+        "SELECT kind, status, chunks_created, chunks_updated, chunks_deleted, errors,
+                started_at, completed_at, phase_durations
+         FROM indexing_runs WHERE project_path = ?1
+         ORDER BY completed_at DESC, rowid DESC LIMIT ?2",
+    )?;
+    let runs = stmt
+        .query_map(params![project_path, PROFILE_RECENT_RUNS as i64], |row| {
+            let status: String = row.get(1)?;
+            let started_at = row_timestamp(row, 6)?;
+            let completed_at = row_timestamp(row, 7)?;
+            let phase_durations: Option<String> = row.get(8)?;
+            Ok(IndexRunProfile {
+                kind: row.get(0)?,
+                status: parse_status(&status),
+                chunks_created: row.get::<_, i64>(2)? as usize,
+                chunks_updated: row.get::<_, i64>(3)? as usize,
+                chunks_deleted: row.get::<_, i64>(4)? as usize,
+                errors: row.get::<_, i64>(5)? as usize,
+                duration_ms: (completed_at - started_at).num_milliseconds(),
+                phase_durations: phase_durations
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(runs)
+}
+
+/// Extensión en minúsculas del archivo, "(none)" si no tiene y "other" si podría
+/// revelar el nombre (larga o con caracteres no alfanuméricos)
+fn extension_bucket(file_path: &str) -> String {
+    match Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+    {
+        None => "(none)".to_string(),
+        Some(ext)
+            if ext.len() <= MAX_EXTENSION_CHARS
+                && ext.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            ext.to_ascii_lowercase()
+        }
+        Some(_) => "other".to_string(),
+    }
+}
+
+fn size_distribution(mut sizes: Vec<u64>) -> SizeDistribution {
+    if sizes.is_empty() {
+        return SizeDistribution::default();
+    }
+    sizes.sort_unstable();
+    let percentile = |p: usize| sizes[(sizes.len() - 1) * p / 100];
+    SizeDistribution {
+        p50: percentile(50),
+        p90: percentile(90),
+        p99: percentile(99),
+        max: sizes[sizes.len() - 1],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index_runs::{record_indexing_run, RUN_KIND_FULL};
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+    use crate::types::{ChunkingResult, IndexingStatus, PhaseDuration};
+    use chrono::Duration;
+
+    fn insert_chunk(conn: &Connection, chunk_type: ChunkType, file_path: &str, content: &str) {
+        chunk()
+            .project("/home/alice/secret-project")
+            .chunk_type(chunk_type)
+            .file(file_path)
+            .entity("charge_customer")
+            .content(content)
+            .insert(conn);
+    }
+
+    #[test]
+    fn test_profile_has_no_paths_names_or_content() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let project = "/home/alice/secret-project";
+        insert_chunk(
+            &conn,
+            ChunkType::RawSource,
+            "src/billing.rs",
+            "fn charge_customer() {}",
+        );
+        insert_chunk(
+            &conn,
+            ChunkType::RawSource,
+            "web/app.TSX",
+            "export const App = 1;",
+        );
+        insert_chunk(
+            &conn,
+            ChunkType::Tests,
+            "src/billing.rs",
+            "#[test] fn t() {}",
+        );
+        insert_chunk(&conn, ChunkType::RawSource, "Makefile", "all:");
+
+        let started_at = Utc::now();
+        let result = ChunkingResult {
+            project_path: project.to_string(),
+            chunks_created: 4,
+            chunks_updated: 0,
+            chunks_deleted: 0,
+            relationships_created: 0,
+            secrets_found: 0,
+            shallow_history: false,
+            errors: Vec::new(),
+            started_at,
+            completed_at: started_at + Duration::milliseconds(1500),
+            dry_run: None,
+            by_type: BTreeMap::new(),
+            phase_durations: vec![PhaseDuration {
+                phase: "ast".to_string(),
+                duration_ms: 900,
+            }],
+            status: IndexingStatus::Completed,
+            abort_reason: None,
+            run_id: None,
+            embeddings: None,
+        };
+        record_indexing_run(&conn, &result, RUN_KIND_FULL).unwrap();
+        crate::hashing::project_id(&conn, project).unwrap();
+
+        let profile = build_index_profile(&conn, project).unwrap();
+        assert_eq!(profile.files, 3);
+        assert_eq!(profile.files_by_extension["rs"], 1);
+        assert_eq!(profile.files_by_extension["tsx"], 1);
+        assert_eq!(profile.files_by_extension["(none)"], 1);
+        assert_eq!(profile.chunks_by_type["raw_source"].count, 3);
+        assert_eq!(profile.chunks_by_type["tests"].count, 1);
+        assert_eq!(profile.file_sizes.max, 23);
+        assert_eq!(profile.recent_runs.len(), 1);
+        assert_eq!(profile.recent_runs[0].duration_ms, 1500);
+        assert_eq!(
+            profile.recent_runs[0].phase_durations,
+            result.phase_durations
+        );
+        // El id anónimo se mantiene entre perfiles del mismo proyecto
+        assert_eq!(
+            build_index_profile(&conn, project).unwrap().project_id,
+            profile.project_id
+        );
+
+        assert!(profile.project_id.is_some());
+
+        let json = serde_json::to_string(&profile).unwrap();
+        for leaked in [
+            "alice",
+            "secret-project",
+            "billing",
+            "charge_customer",
+            "App",
+        ] {
+            assert!(!json.contains(leaked), "profile leaks {}", leaked);
+        }
+    }
+
+    #[test]
+    fn test_profile_from_read_only_connection() {
+        let dir = std::env::temp_dir().join(format!("opcode-profile-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("chunks.db");
+        let project = "/home/alice/secret-project";
+        let id = {
+            let conn = Connection::open(&db_path).unwrap();
+            init_chunk_database(&conn).unwrap();
+            insert_chunk(&conn, ChunkType::RawSource, "src/lib.rs", "fn f() {}");
+            // La indexación crea la sal
+            crate::hashing::project_id(&conn, project).unwrap()
+        };
+
+        let conn =
+            Connection::open_with_flags(&db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+                .unwrap();
+        let profile = build_index_profile(&conn, project).unwrap();
+        assert_eq!(profile.project_id, Some(id));
+        assert_eq!(profile.files, 1);
+        // Un proyecto sin sal no falla: el perfil sale sin id
+        assert_eq!(
+            build_index_profile(&conn, "/other").unwrap().project_id,
+            None
+        );
+
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use super::storage::row_optional_timestamp;
+use super::types::{ChunkingResult, IndexState, IndexingStatus};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+
+/// Indexación completa del proyecto (`process_project`, `reset_project`)
+pub const RUN_KIND_FULL: &str = "full";
+
+/// Reindexación de los archivos modificados (`reindex_files`)
+pub const RUN_KIND_INCREMENTAL: &str = "incremental";
+
+/// Indexación de un subdirectorio del proyecto (`process_project_path`)
+pub const RUN_KIND_PARTIAL: &str = "partial";
+
+/// Registra una ejecución de indexación terminada y retorna su id. Dentro de un dry
+/// run el registro se descarta junto con el resto de la transacción
+pub fn record_indexing_run(
+    conn: &Connection,
+    result: &ChunkingResult,
+    kind: &str,
+) -> Result<String> {
+    let run_id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO indexing_runs (run_id, project_path, kind, status, chunks_created,
+             chunks_updated, chunks_deleted, errors, started_at, completed_at, phase_durations)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            run_id,
+            result.project_path,
+            kind,
+            status_str(result.status),
+            result.chunks_created as i64,
+            result.chunks_updated as i64,
+            result.chunks_deleted as i64,
+            result.errors.len() as i64,
+            result.started_at.to_rfc3339(),
+            result.completed_at.to_rfc3339(),
+            serde_json::to_string(&result.phase_durations)?,
+        ],
+    )?;
+    Ok(run_id)
+}
+
+/// Estado actual del índice del proyecto: chunks vigentes por tipo y la última
+/// ejecución de indexación registrada (campos en None si nunca se indexó)
+pub fn get_index_state(conn: &Connection, project_path: &str) -> Result<IndexState> {
+    let mut stmt = conn.prepare(
+        "SELECT chunk_type, COUNT(*) FROM chunks
+         WHERE project_path = ?1 AND deleted_at IS NULL
+         GROUP BY chunk_type",
+    )?;
+    let chunk_counts: BTreeMap<String, usize> = stmt
+        .query_map(params![project_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let last_run = conn
+        .query_row(
+            "SELECT run_id, kind, status, completed_at FROM indexing_runs
+             WHERE project_path = ?1 ORDER BY completed_at DESC, rowid DESC LIMIT 1",
+            params![project_path],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row_optional_timestamp(row, 3)?,
+                ))
+            },
+        )
+        .optional()?;
+    let (last_run_id, last_run_kind, last_run_status, last_indexed_at) = match last_run {
+        Some((run_id, kind, status, completed_at)) => (
+            Some(run_id),
+            Some(kind),
+            Some(parse_status(&status)),
+            completed_at,
+        ),
+        None => (None, None, None, None),
+    };
+
+    Ok(IndexState {
+        total_chunks: chunk_counts.values().sum(),
+        chunk_counts,
+        last_run_id,
+        last_run_kind,
+        last_run_status,
+        last_indexed_at,
+        captured_at: Utc::now(),
+    })
+}
+
+fn status_str(status: IndexingStatus) -> &'static str {
+    match status {
+        IndexingStatus::Completed => "completed",
+        IndexingStatus::Aborted => "aborted",
+    }
+}
+
+pub(crate) fn parse_status(status: &str) -> IndexingStatus {
+    match status {
+        "aborted" => IndexingStatus::Aborted,
+        _ => IndexingStatus::Completed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+    use crate::types::ChunkType;
+
+    fn insert_chunk(conn: &Connection, chunk_type: ChunkType, content: &str) {
+        chunk()
+            .chunk_type(chunk_type)
+            .file("src/lib.rs")
+            .content(content)
+            .insert(conn);
+    }
+
+    #[test]
+    fn test_index_state_tracks_last_run() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        let state = get_index_state(&conn, "/p").unwrap();
+        assert_eq!(state.total_chunks, 0);
+        assert!(state.last_run_id.is_none() && state.last_indexed_at.is_none());
+
+        insert_chunk(&conn, ChunkType::RawSource, "fn a() {}");
+        insert_chunk(&conn, ChunkType::RawSource, "fn b() {}");
+        insert_chunk(&conn, ChunkType::Tests, "#[test]");
+        let result = ChunkingResult {
+            project_path: "/p".to_string(),
+            chunks_created: 3,
+            chunks_updated: 0,
+            chunks_deleted: 0,
+            relationships_created: 0,
+            secrets_found: 0,
+            shallow_history: false,
+            errors: Vec::new(),
+            started_at: Utc::now(),
+            completed_at: Utc::now(),
+            dry_run: None,
+            by_type: BTreeMap::new(),
+            phase_durations: Vec::new(),
+            status: IndexingStatus::Aborted,
+            abort_reason: Some("budget".to_string()),
+            run_id: None,
+            embeddings: None,
+        };
+        record_indexing_run(&conn, &result, RUN_KIND_FULL).unwrap();
+        let run_id = record_indexing_run(&conn, &result, RUN_KIND_INCREMENTAL).unwrap();
+
+        let state = get_index_state(&conn, "/p").unwrap();
+        assert_eq!(state.chunk_counts["raw_source"], 2);
+        assert_eq!(state.chunk_counts["tests"], 1);
+        assert_eq!(state.total_chunks, 3);
+        assert_eq!(state.last_run_id, Some(run_id));
+        assert_eq!(state.last_run_kind.as_deref(), Some(RUN_KIND_INCREMENTAL));
+        assert_eq!(state.last_run_status, Some(IndexingStatus::Aborted));
+        assert_eq!(state.last_indexed_at, Some(result.completed_at));
+        assert!(get_index_state(&conn, "/other")
+            .unwrap()
+            .last_run_id
+            .is_none());
+    }
+}
@@ -0,0 +1,386 @@
+use super::lint::indexed_files;
+use super::publish::{provider_response, repo_api_url, RemoteRepo};
+use super::storage::{
+    calculate_content_hash, get_chunk_id_by_hash, get_latest_file_chunk_id, insert_relationship,
+    upsert_chunk,
+};
+use super::types::{
+    Chunk, ChunkRelationship, ChunkType, GitProvider, IssueImportResult, IssueItem,
+    RelationshipType,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet};
+
+/// Origen de las relaciones `mentions` creadas por este módulo
+pub const RELATIONSHIP_SOURCE: &str = "issues";
+
+/// Issues pedidos por página a la API del proveedor
+const PAGE_SIZE: usize = 100;
+
+/// Descarga issues del repositorio remoto. `state`: "open", "closed" o "all".
+/// El token es opcional para repositorios públicos
+pub async fn fetch_issues(
+    remote: &RemoteRepo,
+    token: Option<&str>,
+    state: &str,
+    limit: usize,
+) -> Result<Vec<IssueItem>> {
+    let client = reqwest::Client::new();
+    let state = match (remote.provider, state) {
+        (GitProvider::GitLab, "open") => "opened",
+        (_, state) => state,
+    };
+
+    let mut issues = Vec::new();
+    let mut page = 1;
+    while issues.len() < limit {
+        let mut request = client
+            .get(format!("{}/issues", repo_api_url(remote)))
+            .query(&[
+                ("state", state.to_string()),
+                ("per_page", PAGE_SIZE.to_string()),
+                ("page", page.to_string()),
+            ])
+            .header("User-Agent", "opcode-App");
+        request = match (remote.provider, token) {
+            (GitProvider::GitHub, Some(token)) => request
+                .header("Accept", "application/vnd.github+json")
+                .bearer_auth(token),
+            (GitProvider::GitHub, None) => request.header("Accept", "application/vnd.github+json"),
+            (GitProvider::GitLab, Some(token)) => request.header("PRIVATE-TOKEN", token),
+            (GitProvider::GitLab, None) => request,
+        };
+
+        let json = provider_response(
+            request
+                .send()
+                .await
+                .context("Failed to reach the issue tracker API")?,
+        )
+        .await?;
+        let Some(items) = json.as_array() else {
+            break;
+        };
+        let fetched = items.len();
+        issues.extend(match remote.provider {
+            GitProvider::GitHub => parse_github_issues(items),
+            GitProvider::GitLab => parse_gitlab_issues(items),
+        });
+        if fetched < PAGE_SIZE {
+            break;
+        }
+        page += 1;
+    }
+
+    issues.truncate(limit);
+    Ok(issues)
+}
+
+/// Issues de la API REST de GitHub (se omiten los pull requests que la API también lista)
+pub fn parse_github_issues(items: &[Value]) -> Vec<IssueItem> {
+    items
+        .iter()
+        .filter(|item| item.get("pull_request").is_none())
+        .filter_map(|item| {
+            Some(IssueItem {
+                provider: GitProvider::GitHub,
+                number: item["number"].as_u64()?,
+                title: item["title"].as_str()?.to_string(),
+                body: item["body"].as_str().unwrap_or("").to_string(),
+                state: item["state"].as_str().unwrap_or("open").to_string(),
+                labels: item["labels"]
+                    .as_array()
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .filter_map(|l| l["name"].as_str().or_else(|| l.as_str()))
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                url: item["html_url"].as_str().unwrap_or("").to_string(),
+                author: item["user"]["login"].as_str().map(String::from),
+                updated_at: item["updated_at"].as_str().map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// Issues de la API REST de GitLab (`iid` es el número visible en el proyecto)
+pub fn parse_gitlab_issues(items: &[Value]) -> Vec<IssueItem> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let state = match item["state"].as_str().unwrap_or("opened") {
+                "opened" => "open",
+                other => other,
+            };
+            Some(IssueItem {
+                provider: GitProvider::GitLab,
+                number: item["iid"].as_u64()?,
+                title: item["title"].as_str()?.to_string(),
+                body: item["description"].as_str().unwrap_or("").to_string(),
+                state: state.to_string(),
+                labels: item["labels"]
+                    .as_array()
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .filter_map(|l| l.as_str().or_else(|| l["name"].as_str()))
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                url: item["web_url"].as_str().unwrap_or("").to_string(),
+                author: item["author"]["username"].as_str().map(String::from),
+                updated_at: item["updated_at"].as_str().map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// Guarda cada issue como chunk `issue` y lo enlaza (`mentions`) con los archivos
+/// indexados que menciona. Un issue editado reemplaza su chunk anterior
+pub fn import_issues(
+    conn: &Connection,
+    project_path: &str,
+    issues: &[IssueItem],
+) -> Result<IssueImportResult> {
+    let known_files = indexed_files(conn, project_path)?;
+    let mut result = IssueImportResult {
+        issues_imported: 0,
+        issues_unchanged: 0,
+        relationships_created: 0,
+    };
+
+    for issue in issues {
+        let linked_files =
+            mentioned_files(&known_files, &format!("{}\n{}", issue.title, issue.body));
+        let content = issue_content(issue);
+        let content_hash = calculate_content_hash(&format!("{}\n{}", project_path, content));
+        let entity_name = format!("#{}", issue.number);
+
+        if get_chunk_id_by_hash(conn, project_path, &content_hash)?.is_some() {
+            result.issues_unchanged += 1;
+            continue;
+        }
+        remove_issue_chunks(conn, project_path, &entity_name)?;
+
+        let metadata = serde_json::json!({
+            "source": RELATIONSHIP_SOURCE,
+            "provider": issue.provider,
+            "number": issue.number,
+            "state": issue.state,
+            "labels": issue.labels,
+            "url": issue.url,
+            "author": issue.author,
+            "updated_at": issue.updated_at,
+            "linked_files": linked_files,
+        });
+        upsert_chunk(
+            conn,
+            &Chunk {
+                id: None,
+                project_path: project_path.to_string(),
+                chunk_type: ChunkType::Issue,
+                file_path: None,
+                entity_name: Some(entity_name),
+                content,
+                content_hash: content_hash.clone(),
+                metadata: Some(metadata.to_string()),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            None,
+        )?;
+        result.issues_imported += 1;
+
+        let Some(issue_chunk_id) = get_chunk_id_by_hash(conn, project_path, &content_hash)? else {
+            continue;
+        };
+        for file_path in &linked_files {
+            let target = match get_latest_file_chunk_id(
+                conn,
+                project_path,
+                file_path,
+                &ChunkType::RawSource,
+            )? {
+                Some(id) => Some(id),
+                None => get_latest_file_chunk_id(conn, project_path, file_path, &ChunkType::Ast)?,
+            };
+            let Some(to_chunk_id) = target else {
+                continue;
+            };
+            insert_relationship(
+                conn,
+                &ChunkRelationship {
+                    id: None,
+                    from_chunk_id: issue_chunk_id,
+                    to_chunk_id,
+                    relationship_type: RelationshipType::Mentions,
+                    metadata: Some(
+                        serde_json::json!({
+                            "source": RELATIONSHIP_SOURCE,
+                            "issue": issue.number,
+                        })
+                        .to_string(),
+                    ),
+                    created_at: Utc::now(),
+                },
+            )?;
+            result.relationships_created += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+fn issue_content(issue: &IssueItem) -> String {
+    let mut content = format!(
+        "Issue #{} [{}]: {}\n",
+        issue.number, issue.state, issue.title
+    );
+    if !issue.labels.is_empty() {
+        content.push_str(&format!("Labels: {}\n", issue.labels.join(", ")));
+    }
+    if !issue.url.is_empty() {
+        content.push_str(&format!("URL: {}\n", issue.url));
+    }
+    if !issue.body.trim().is_empty() {
+        content.push('\n');
+        content.push_str(issue.body.trim());
+        content.push('\n');
+    }
+    content
+}
+
+/// Elimina el chunk anterior de un issue y sus relaciones
+fn remove_issue_chunks(conn: &Connection, project_path: &str, entity_name: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM chunk_relationships WHERE from_chunk_id IN (
+            SELECT id FROM chunks WHERE project_path = ?1 AND chunk_type = 'issue' AND entity_name = ?2
+        )",
+        params![project_path, entity_name],
+    )?;
+    conn.execute(
+        "DELETE FROM chunks WHERE project_path = ?1 AND chunk_type = 'issue' AND entity_name = ?2",
+        params![project_path, entity_name],
+    )?;
+    Ok(())
+}
+
+/// Archivos indexados mencionados en el texto: rutas (`src/auth/login.rs`), enlaces
+/// `.../blob/<ref>/ruta#L10` y nombres de archivo sueltos si son únicos en el proyecto
+pub fn mentioned_files(known_files: &HashSet<String>, text: &str) -> Vec<String> {
+    let blob_re = Regex::new(r"/blob/[^/\s]+/([^\s#?)\]]+)").unwrap();
+    let path_re = Regex::new(r"(?:[\w.-]+/)*[\w-][\w.-]*\.[A-Za-z0-9]+").unwrap();
+
+    let mut candidates: Vec<String> = blob_re
+        .captures_iter(text)
+        .map(|cap| cap[1].to_string())
+        .collect();
+    let without_urls = blob_re.replace_all(text, " ");
+    candidates.extend(
+        path_re
+            .find_iter(&without_urls)
+            .map(|m| m.as_str().to_string()),
+    );
+
+    let mut files = BTreeSet::new();
+    for candidate in candidates {
+        let candidate = candidate.trim_start_matches("./").trim_end_matches('.');
+        if known_files.contains(candidate) {
+            files.insert(candidate.to_string());
+            continue;
+        }
+        let suffix = format!("/{}", candidate);
+        let matches: Vec<&String> = known_files
+            .iter()
+            .filter(|f| f.ends_with(&suffix))
+            .collect();
+        if matches.len() == 1 {
+            files.insert(matches[0].clone());
+        }
+    }
+
+    files.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{get_relationships, init_chunk_database};
+    use crate::test_support::chunk;
+
+    fn source_chunk(conn: &Connection, file_path: &str) -> i64 {
+        chunk()
+            .file(file_path)
+            .content(&format!("// {}", file_path))
+            .insert(conn)
+    }
+
+    #[test]
+    fn test_mentioned_files() {
+        let known: HashSet<String> = ["src/auth/login.rs", "src/main.rs", "web/src/main.rs"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let text = "Login breaks in login.rs (see https://github.com/a/b/blob/main/src/auth/login.rs#L10). main.rs is fine, v1.2 too.";
+        assert_eq!(mentioned_files(&known, text), vec!["src/auth/login.rs"]);
+        assert_eq!(
+            mentioned_files(&known, "Check `web/src/main.rs`."),
+            vec!["web/src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_import_issues() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let login_id = source_chunk(&conn, "src/auth/login.rs");
+
+        let items: Vec<Value> = serde_json::from_str(
+            r#"[
+                {"number": 12, "title": "Login fails", "body": "Error in src/auth/login.rs", "state": "open",
+                 "labels": [{"name": "bug"}], "html_url": "https://github.com/a/b/issues/12", "user": {"login": "ana"}},
+                {"number": 13, "title": "A PR", "body": null, "state": "open", "labels": [],
+                 "html_url": "https://github.com/a/b/pull/13", "pull_request": {}}
+            ]"#,
+        )
+        .unwrap();
+        let mut issues = parse_github_issues(&items);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].labels, vec!["bug"]);
+
+        let result = import_issues(&conn, "/p", &issues).unwrap();
+        assert_eq!(result.issues_imported, 1);
+        assert_eq!(result.relationships_created, 1);
+        let incoming = get_relationships(&conn, login_id, false).unwrap();
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(incoming[0].relationship_type, RelationshipType::Mentions);
+
+        assert_eq!(
+            import_issues(&conn, "/p", &issues)
+                .unwrap()
+                .issues_unchanged,
+            1
+        );
+
+        // Un issue editado reemplaza su chunk anterior
+        issues[0].state = "closed".to_string();
+        import_issues(&conn, "/p", &issues).unwrap();
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM chunks WHERE chunk_type = 'issue'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(get_relationships(&conn, login_id, false).unwrap().len(), 1);
+    }
+}
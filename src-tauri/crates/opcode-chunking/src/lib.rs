@@ -0,0 +1,1855 @@
+//! Sistema de chunking de opcode: indexa proyectos en chunks (código, AST,
+//! callgraph, tests, commits, configuración, reglas de negocio, errores) sobre
+//! SQLite y mantiene snapshots versionados con Git.
+//!
+//! No depende de Tauri: la app lo reexporta como `chunking` y otras herramientas
+//! pueden usarlo directamente con [`Orchestrator`], el trait [`Storage`] y los
+//! tipos de [`types`]. Las operaciones que el trait no cubre son funciones de cada
+//! módulo sobre la conexión del orquestador ([`Orchestrator::connection`]). Con varios hilos, [`ChunkStore`] serializa las escrituras
+//! en un único actor escritor y sirve las lecturas desde un pool de solo lectura.
+
+// Los enums persistidos exponen `from_str(&str) -> Option<Self>` en vez de `FromStr`
+#![allow(clippy::should_implement_trait)]
+
+pub mod advisories;
+pub mod analytics;
+pub mod api_surface;
+pub mod ast;
+pub mod bench;
+pub mod boundaries;
+pub mod build_errors;
+pub mod business_rules;
+pub mod callgraph;
+pub mod changelog;
+pub mod chunk_versions;
+pub mod code_extensions;
+pub mod commits;
+pub mod complexity;
+pub mod config;
+pub mod conformance;
+pub mod content_store;
+pub mod context_pack;
+pub mod custom_chunks;
+pub mod dependency_audit;
+pub mod dependency_graph;
+pub mod dry_run;
+pub mod embedding_providers;
+pub mod embeddings;
+pub mod entry_points;
+pub mod error_budget;
+pub mod errors;
+pub mod git_hooks;
+pub mod git_notes;
+pub mod graph_export;
+pub mod graph_metrics;
+pub mod hashing;
+pub mod health;
+pub mod i18n;
+pub mod index_merge;
+pub mod index_profile;
+pub mod index_runs;
+pub mod issues;
+pub mod lint;
+pub mod logical_projects;
+pub mod lsp_diagnostics;
+pub mod metadata;
+pub mod parse_failures;
+pub mod path_guard;
+pub mod project_config;
+pub mod publish;
+pub mod ranking;
+pub mod raw_source;
+pub mod read_only;
+pub mod relationships;
+pub mod release_notes;
+pub mod remote_repos;
+pub mod resolver;
+pub mod review;
+pub mod risk;
+pub mod rule_proposals;
+pub mod secrets;
+pub mod sessions;
+pub mod snapshots;
+pub mod source_range;
+pub mod sql_queries;
+pub mod staleness;
+pub mod storage;
+pub mod syntax_errors;
+pub mod tauri_surface;
+pub mod test_results;
+pub mod test_selection;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod tests;
+pub mod tombstones;
+pub mod types;
+pub mod ui_components;
+pub mod undo;
+pub mod version_bump;
+pub mod watch;
+pub mod writer;
+
+use anyhow::Result;
+use chrono::Utc;
+use ignore::WalkBuilder;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use embeddings::EmbeddingProvider;
+use error_budget::{indexing_error, is_storage_error, ErrorTracker};
+use resolver::ImportResolver;
+use storage::init_chunk_database;
+use types::{
+    BenchmarkRun, ChunkType, ChunkTypeStats, ChunkingOptions, ChunkingResult, DryRunReport,
+    EmbeddingRefreshReport, IndexingStatus, LogicalSnapshot, PhaseDuration, RemoteIndexResult,
+    SemanticMatch,
+};
+
+pub use storage::Storage;
+pub use writer::ChunkStore;
+pub use ChunkingOrchestrator as Orchestrator;
+
+/// Orquestador principal del sistema de chunking
+pub struct ChunkingOrchestrator {
+    conn: Connection,
+    /// Proveedor que reemplaza al backend configurado en cada proyecto
+    /// (ver [`embedding_providers::project_provider`])
+    embedder: Option<Box<dyn EmbeddingProvider>>,
+}
+
+impl ChunkingOrchestrator {
+    /// Crea una nueva instancia del orquestador
+    pub fn new(conn: Connection) -> Result<Self> {
+        init_chunk_database(&conn)?;
+        Ok(Self {
+            conn,
+            embedder: None,
+        })
+    }
+
+    /// El índice a través del trait [`Storage`] (chunks, relaciones, snapshots,
+    /// errores y reglas)
+    pub fn storage(&self) -> &dyn Storage {
+        &self.conn
+    }
+
+    /// Conexión del índice, para las funciones de los módulos (reportes,
+    /// analíticas, snapshots con Git...) que [`Storage`] no cubre y reciben
+    /// `&Connection`
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Configura el modelo de embeddings de todos los proyectos, en lugar del backend
+    /// configurado en cada uno
+    pub fn set_embedding_provider(&mut self, provider: Box<dyn EmbeddingProvider>) {
+        self.embedder = Some(provider);
+    }
+
+    /// Calcula los embeddings pendientes del proyecto con su proveedor. `None` si el
+    /// proyecto no tiene backend configurado
+    pub fn refresh_embeddings(&self, project_path: &str) -> Result<Option<EmbeddingRefreshReport>> {
+        self.with_embedding_provider(project_path, |provider| {
+            embeddings::refresh_embeddings(&self.conn, project_path, provider)
+        })
+    }
+
+    /// Búsqueda semántica sobre los embeddings del proyecto. `None` si el proyecto no
+    /// tiene backend configurado
+    pub fn semantic_search(
+        &self,
+        project_path: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Option<Vec<SemanticMatch>>> {
+        self.with_embedding_provider(project_path, |provider| {
+            embeddings::semantic_search(&self.conn, project_path, provider, query, limit)
+        })
+    }
+
+    fn with_embedding_provider<T>(
+        &self,
+        project_path: &str,
+        op: impl FnOnce(&dyn EmbeddingProvider) -> Result<T>,
+    ) -> Result<Option<T>> {
+        if let Some(provider) = &self.embedder {
+            return op(provider.as_ref()).map(Some);
+        }
+        embedding_providers::project_provider(&self.conn, project_path)?
+            .map(|provider| op(provider.as_ref()))
+            .transpose()
+    }
+
+    /// Recalcula los embeddings que cambiaron con la indexación, si el proyecto ya
+    /// tiene embeddings y hay proveedor. Un fallo del proveedor no invalida la indexación
+    fn refresh_embeddings_after_index(
+        &self,
+        project_path: &str,
+        mut result: ChunkingResult,
+    ) -> ChunkingResult {
+        let refreshed = embeddings::has_embeddings(&self.conn, project_path).and_then(|has| {
+            if has {
+                self.refresh_embeddings(project_path)
+            } else {
+                Ok(None)
+            }
+        });
+        match refreshed {
+            Ok(report) => result.embeddings = report,
+            Err(e) => {
+                let e = e.context("Failed to refresh embeddings");
+                log::warn!("{:#}", e);
+                result.errors.push(indexing_error(None, None, &e));
+            }
+        }
+        result
+    }
+
+    /// Procesa un proyecto completo generando todos los tipos de chunks configurados.
+    /// Con `options.dry_run` solo reporta los cambios en `ChunkingResult.dry_run`
+    pub fn process_project(
+        &self,
+        project_path: &str,
+        options: &ChunkingOptions,
+    ) -> Result<ChunkingResult> {
+        if options.dry_run {
+            let (mut result, report) = dry_run::preview(&self.conn, project_path, || {
+                self.index_project(project_path, None, options)
+            })?;
+            result.dry_run = Some(report);
+            result.run_id = None;
+            return Ok(result);
+        }
+        self.index_project(project_path, None, options)
+            .map(|result| self.refresh_embeddings_after_index(project_path, result))
+    }
+
+    /// Indexa o reindexa solo un subdirectorio del proyecto (`services/api` en un
+    /// monorepo) con las mismas opciones que [`Self::process_project`]. Las fases
+    /// globales se acotan a los archivos del subdirectorio: no se regenera el historial
+    /// de commits y las relaciones se refrescan como en una reindexación incremental
+    pub fn process_project_path(
+        &self,
+        project_path: &str,
+        subpath: &str,
+        options: &ChunkingOptions,
+    ) -> Result<ChunkingResult> {
+        let scope = path_guard::normalize_subpath(subpath)?;
+        if let Some(scope) = &scope {
+            if !Path::new(project_path).join(scope).exists() {
+                anyhow::bail!("Subpath not found in {}: {}", project_path, scope);
+            }
+        }
+        if options.dry_run {
+            let (mut result, report) = dry_run::preview(&self.conn, project_path, || {
+                self.index_project(project_path, scope.as_deref(), options)
+            })?;
+            result.dry_run = Some(report);
+            result.run_id = None;
+            return Ok(result);
+        }
+        self.index_project(project_path, scope.as_deref(), options)
+            .map(|result| self.refresh_embeddings_after_index(project_path, result))
+    }
+
+    /// Reindexa un proyecto desde cero: manda todos sus chunks a la papelera como una
+    /// operación `Reset` (deshacible con [`undo::undo_last_operation`]) y vuelve a
+    /// indexar. Quedan borrados solo los chunks que no se regeneran
+    pub fn reset_project(
+        &self,
+        project_path: &str,
+        options: &ChunkingOptions,
+    ) -> Result<ChunkingResult> {
+        let reset = || {
+            let deleted = undo::begin_reset(&self.conn, project_path)?;
+            log::info!("Reset {}: {} chunks moved to the trash", project_path, deleted);
+            self.index_project(project_path, None, options)
+        };
+        if options.dry_run {
+            let (mut result, report) = dry_run::preview(&self.conn, project_path, reset)?;
+            result.dry_run = Some(report);
+            result.run_id = None;
+            return Ok(result);
+        }
+        reset().map(|result| self.refresh_embeddings_after_index(project_path, result))
+    }
+
+    /// Con `scope` solo se recorre ese subdirectorio (ver [`Self::process_project_path`])
+    fn index_project(
+        &self,
+        project_path: &str,
+        scope: Option<&str>,
+        options: &ChunkingOptions,
+    ) -> Result<ChunkingResult> {
+        read_only::ensure_index_writable(&self.conn, project_path)?;
+        let started_at = Utc::now();
+        let mut chunks_created = 0;
+        let chunks_updated = 0;
+        let mut chunks_deleted = 0;
+        let mut relationships_created = 0;
+        let mut secrets_found = 0;
+        let mut shallow_history = false;
+        let mut errors = Vec::new();
+        let mut breakdown = IndexStats::default();
+        let mut file_imports: Vec<(String, Vec<String>)> = Vec::new();
+
+        // opcode.toml del proyecto sobreescribe las opciones de la app
+        let (mut options, config_error) = project_config::resolve_options(project_path, options);
+        if let Some(err) = config_error {
+            log::warn!("Ignoring {}: {}", project_config::PROJECT_CONFIG_FILE, err);
+            errors.push(project_config::config_error(err));
+        }
+        options.code_extensions = code_extensions::resolve_code_extensions(
+            &self.conn,
+            project_path,
+            &options.code_extensions,
+        )?;
+        let options = &options;
+        let hash_algorithm = hashing::get_hash_algorithm(&self.conn, project_path)?;
+        // Crea la sal del id anónimo: el perfil del índice la lee sin escribir
+        hashing::project_id(&self.conn, project_path)?;
+        let mut tracker = ErrorTracker::new(&options.error_budget);
+
+        // Listado previo de archivos para poder resolver y clasificar imports
+        // (siempre del proyecto completo: los imports pueden salir del subdirectorio)
+        let (resolver, scope_files) = breakdown.time("discovery", || {
+            let files = list_project_files(project_path);
+            let scope_files: BTreeSet<String> = match scope {
+                Some(scope) => files
+                    .iter()
+                    .filter(|f| path_guard::is_within_subpath(f, scope))
+                    .cloned()
+                    .collect(),
+                None => BTreeSet::new(),
+            };
+            (ImportResolver::load(project_path, files), scope_files)
+        });
+
+        // 1. Raw Source Chunks
+        if options.chunk_types.contains(&ChunkType::RawSource) {
+            match breakdown.time("raw_source", || {
+                raw_source::generate_raw_source_chunks(
+                    &self.conn,
+                    project_path,
+                    scope,
+                    &options.ignore_patterns,
+                    &options.code_extensions,
+                    hash_algorithm,
+                )
+            }) {
+                Ok(count) => {
+                    chunks_created += count;
+                    breakdown.entry(ChunkType::RawSource).created += count;
+                    log::info!("Created {} raw source chunks", count);
+                }
+                Err(e) => {
+                    let e = e.context("Failed to generate raw source chunks");
+                    log::error!("{:#}", e);
+                    errors.push(indexing_error(None, Some(ChunkType::RawSource), &e));
+                    breakdown.entry(ChunkType::RawSource).errored += 1;
+                    tracker.phase_error("raw_source", &e);
+                }
+            }
+        }
+
+        // 2. AST Chunks + 3. Callgraph + 4. Tests + 5. Config + 6. Metadata
+        // Los procesamos en un solo pass del filesystem
+        let walker = WalkBuilder::new(Path::new(project_path).join(scope.unwrap_or_default()))
+            .git_ignore(true)
+            .git_global(true)
+            .git_exclude(true)
+            .hidden(false)
+            .build();
+
+        for entry in walker.filter_map(|e| e.ok()) {
+            if tracker.is_aborted() {
+                break;
+            }
+
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+
+            let rel_path = match path.strip_prefix(project_path) {
+                Ok(p) => storage::normalize_file_path(&p.to_string_lossy()),
+                Err(_) => continue,
+            };
+
+            if raw_source::should_ignore(&rel_path, &options.ignore_patterns) {
+                continue;
+            }
+
+            // Leer contenido una sola vez
+            let content = match std::fs::read_to_string(path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            if let Err(e) = staleness::record_fingerprint(
+                &self.conn,
+                project_path,
+                &rel_path,
+                &content,
+                hash_algorithm,
+            ) {
+                log::warn!("Failed to record fingerprint for {}: {}", rel_path, e);
+            }
+
+            // AST Chunks (los archivos de la skip list de parseo se omiten)
+            if options.chunk_types.contains(&ChunkType::Ast) {
+                let skip = parse_failures::should_skip(
+                    &self.conn,
+                    project_path,
+                    &rel_path,
+                    &content,
+                    options.max_parse_attempts,
+                )
+                .unwrap_or(false);
+
+                if skip {
+                    log::debug!("Skipped AST for {}: in the parse-failure skip list", rel_path);
+                    breakdown.entry(ChunkType::Ast).skipped += 1;
+                } else {
+                    let outcome = breakdown.time("ast", || {
+                        ast::generate_ast_chunks(
+                            &self.conn,
+                            project_path,
+                            &rel_path,
+                            &content,
+                            options,
+                        )
+                    });
+                    if let Err(e) = parse_failures::record_outcome(
+                        &self.conn,
+                        project_path,
+                        &rel_path,
+                        &content,
+                        &outcome,
+                    ) {
+                        log::warn!("Failed to update parse failures for {}: {}", rel_path, e);
+                    }
+
+                    match outcome {
+                        Ok(count) => {
+                            chunks_created += count;
+                            breakdown.entry(ChunkType::Ast).created += count;
+                        }
+                        Err(e) if is_storage_error(&e) => {
+                            breakdown.entry(ChunkType::Ast).errored += 1;
+                            tracker.file_error(&mut errors, &rel_path, Some(ChunkType::Ast), &e);
+                        }
+                        Err(e) => {
+                            log::debug!("Skipped AST for {}: {}", rel_path, e);
+                            breakdown.entry(ChunkType::Ast).skipped += 1;
+                        }
+                    }
+                }
+            }
+
+            // Callgraph Chunks
+            if options.chunk_types.contains(&ChunkType::Callgraph) {
+                if let Err(e) = breakdown.time("callgraph", || {
+                    callgraph::generate_callgraph_chunks(
+                        &self.conn,
+                        project_path,
+                        &rel_path,
+                        &content,
+                        &resolver,
+                    )
+                }) {
+                    if is_storage_error(&e) {
+                        breakdown.entry(ChunkType::Callgraph).errored += 1;
+                        tracker.file_error(&mut errors, &rel_path, Some(ChunkType::Callgraph), &e);
+                    } else {
+                        log::debug!("Skipped callgraph for {}: {}", rel_path, e);
+                        breakdown.entry(ChunkType::Callgraph).skipped += 1;
+                    }
+                } else {
+                    chunks_created += 1;
+                    breakdown.entry(ChunkType::Callgraph).created += 1;
+                }
+
+                if dependency_graph::is_supported_source(&rel_path) {
+                    file_imports.push((
+                        rel_path.clone(),
+                        callgraph::extract_file_dependencies(&rel_path, &content),
+                    ));
+                }
+            }
+
+            // Test Chunks
+            if options.chunk_types.contains(&ChunkType::Tests) {
+                match breakdown.time("tests", || {
+                    tests::generate_test_chunks(&self.conn, project_path, &rel_path, &content)
+                }) {
+                    Ok(count) => {
+                        chunks_created += count;
+                        breakdown.entry(ChunkType::Tests).created += count;
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to generate tests chunks for {}: {}", rel_path, e);
+                        breakdown.entry(ChunkType::Tests).errored += 1;
+                        tracker.file_error(&mut errors, &rel_path, Some(ChunkType::Tests), &e);
+                    }
+                }
+            }
+
+            // Config Chunks
+            if options.chunk_types.contains(&ChunkType::StateConfig) {
+                match breakdown.time("state_config", || {
+                    config::generate_config_chunks(&self.conn, project_path, &rel_path, &content)
+                }) {
+                    Ok(count) => {
+                        chunks_created += count;
+                        breakdown.entry(ChunkType::StateConfig).created += count;
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to generate config chunks for {}: {}", rel_path, e);
+                        breakdown.entry(ChunkType::StateConfig).errored += 1;
+                        tracker.file_error(
+                            &mut errors,
+                            &rel_path,
+                            Some(ChunkType::StateConfig),
+                            &e,
+                        );
+                    }
+                }
+
+                // Claves de traducción de los archivos de locale
+                match breakdown.time("i18n", || {
+                    i18n::generate_i18n_chunks(&self.conn, project_path, &rel_path, &content)
+                }) {
+                    Ok(count) => {
+                        chunks_created += count;
+                        breakdown.entry(ChunkType::StateConfig).created += count;
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to generate i18n chunks for {}: {}", rel_path, e);
+                        breakdown.entry(ChunkType::StateConfig).errored += 1;
+                        tracker.file_error(
+                            &mut errors,
+                            &rel_path,
+                            Some(ChunkType::StateConfig),
+                            &e,
+                        );
+                    }
+                }
+            }
+
+            // Metadata Chunks
+            if options.chunk_types.contains(&ChunkType::ProjectMetadata) {
+                match breakdown.time("project_metadata", || {
+                    metadata::generate_metadata_chunks(&self.conn, project_path, &rel_path, &content)
+                }) {
+                    Ok(count) => {
+                        chunks_created += count;
+                        breakdown.entry(ChunkType::ProjectMetadata).created += count;
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to generate metadata chunks for {}: {}", rel_path, e);
+                        breakdown.entry(ChunkType::ProjectMetadata).errored += 1;
+                        tracker.file_error(
+                            &mut errors,
+                            &rel_path,
+                            Some(ChunkType::ProjectMetadata),
+                            &e,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Grafo de dependencias: imports resueltos a archivos y agregados por módulo.
+        // Con subdirectorio solo se reemplazan las dependencias de sus archivos; el grafo
+        // de módulos necesita todos los imports y lo regenera el refresco de relaciones
+        if options.chunk_types.contains(&ChunkType::Callgraph) && !tracker.is_aborted() {
+            match breakdown.time("dependency_graph", || match scope {
+                Some(_) => dependency_graph::replace_file_dependency_relationships(
+                    &self.conn,
+                    project_path,
+                    &file_imports,
+                    &scope_files,
+                    &resolver,
+                ),
+                None => dependency_graph::generate_file_dependency_relationships(
+                    &self.conn,
+                    project_path,
+                    &file_imports,
+                    &resolver,
+                ),
+            }) {
+                Ok(relationships) => {
+                    relationships_created += relationships;
+                    log::info!("Created {} file dependency relationships", relationships);
+                }
+                Err(e) => {
+                    let e = e.context("Failed to resolve file dependencies");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, Some(ChunkType::Callgraph), &e));
+                    breakdown.entry(ChunkType::Callgraph).errored += 1;
+                    tracker.phase_error("dependency_graph", &e);
+                }
+            }
+
+            let module_graph = match scope {
+                Some(_) => Ok((0, 0)),
+                None => breakdown.time("dependency_graph", || {
+                    dependency_graph::generate_module_graph_chunks(
+                        &self.conn,
+                        project_path,
+                        &file_imports,
+                        &resolver,
+                    )
+                }),
+            };
+            match module_graph {
+                Ok((chunks, relationships)) => {
+                    chunks_created += chunks;
+                    relationships_created += relationships;
+                    breakdown.entry(ChunkType::Callgraph).created += chunks;
+                    log::info!(
+                        "Created module graph: {} chunks, {} relationships",
+                        chunks,
+                        relationships
+                    );
+                }
+                Err(e) => {
+                    let e = e.context("Failed to generate module graph");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, Some(ChunkType::Callgraph), &e));
+                    breakdown.entry(ChunkType::Callgraph).errored += 1;
+                    tracker.phase_error("dependency_graph", &e);
+                }
+            }
+
+            match breakdown.time("tauri_surface", || {
+                tauri_surface::generate_tauri_surface_chunk(&self.conn, project_path)
+            }) {
+                Ok(commands) => {
+                    if commands > 0 {
+                        chunks_created += 1;
+                        breakdown.entry(ChunkType::Callgraph).created += 1;
+                        log::info!("Created Tauri surface: {} commands", commands);
+                    }
+                }
+                Err(e) => {
+                    let e = e.context("Failed to generate Tauri command surface");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, Some(ChunkType::Callgraph), &e));
+                    breakdown.entry(ChunkType::Callgraph).errored += 1;
+                    tracker.phase_error("tauri_surface", &e);
+                }
+            }
+
+            match breakdown.time("ui_components", || {
+                ui_components::generate_ui_chunks(&self.conn, project_path)
+            }) {
+                Ok((chunks, relationships)) => {
+                    chunks_created += chunks;
+                    relationships_created += relationships;
+                    breakdown.entry(ChunkType::Callgraph).created += chunks;
+                    if chunks > 0 {
+                        log::info!(
+                            "Created UI components and routes: {} chunks, {} relationships",
+                            chunks,
+                            relationships
+                        );
+                    }
+                }
+                Err(e) => {
+                    let e = e.context("Failed to extract UI components and routes");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, Some(ChunkType::Callgraph), &e));
+                    breakdown.entry(ChunkType::Callgraph).errored += 1;
+                    tracker.phase_error("ui_components", &e);
+                }
+            }
+
+            match breakdown.time("sql_queries", || {
+                sql_queries::generate_sql_chunks(&self.conn, project_path)
+            }) {
+                Ok((chunks, relationships)) => {
+                    chunks_created += chunks;
+                    relationships_created += relationships;
+                    breakdown.entry(ChunkType::Callgraph).created += chunks;
+                    if chunks > 0 {
+                        log::info!(
+                            "Created SQL schema and query chunks: {} chunks, {} relationships",
+                            chunks,
+                            relationships
+                        );
+                    }
+                }
+                Err(e) => {
+                    let e = e.context("Failed to extract SQL queries");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, Some(ChunkType::Callgraph), &e));
+                    breakdown.entry(ChunkType::Callgraph).errored += 1;
+                    tracker.phase_error("sql_queries", &e);
+                }
+            }
+        }
+
+        // 5. Commit History Chunks (el historial es del proyecto completo)
+        if options.chunk_types.contains(&ChunkType::CommitHistory)
+            && scope.is_none()
+            && !tracker.is_aborted()
+        {
+            match breakdown.time("commit_history", || {
+                commits::generate_commit_chunks(
+                    &self.conn,
+                    project_path,
+                    options.max_commits,
+                    options.all_branches,
+                )
+            }) {
+                Ok(stats) => {
+                    chunks_created += stats.chunks_created;
+                    shallow_history = stats.shallow_boundary_reached;
+                    breakdown.entry(ChunkType::CommitHistory).created += stats.chunks_created;
+                    log::info!("Created {} commit history chunks", stats.chunks_created);
+                    if stats.shallow_boundary_reached {
+                        log::warn!(
+                            "Shallow clone: commit history stops at the shallow boundary in {}",
+                            project_path
+                        );
+                    }
+                }
+                Err(e) => {
+                    let e = e.context("Failed to generate commit chunks");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, Some(ChunkType::CommitHistory), &e));
+                    breakdown.entry(ChunkType::CommitHistory).errored += 1;
+                    tracker.phase_error("commit_history", &e);
+                }
+            }
+
+            // Los clones de referencia (remote_repos) son de solo lectura
+            if options.write_git_notes
+                && !options.dry_run
+                && !tracker.is_aborted()
+                && remote_repos::ensure_writable_project(&self.conn, project_path).is_ok()
+            {
+                match breakdown.time("git_notes", || {
+                    git_notes::write_commit_notes(&self.conn, project_path)
+                }) {
+                    Ok(count) => log::info!("Wrote {} git notes to {}", count, git_notes::NOTES_REF),
+                    Err(e) => {
+                        let e = e.context("Failed to write git notes");
+                        log::warn!("{:#}", e);
+                        errors.push(indexing_error(None, None, &e));
+                        tracker.phase_error("git_notes", &e);
+                    }
+                }
+            }
+        }
+
+        // Papelera: chunks de archivos que ya no existen (antes del escaneo de secretos)
+        // y purga de los tombstones vencidos
+        if !tracker.is_aborted() {
+            match breakdown.time("tombstones", || {
+                let deleted = tombstones::prune_missing_files(&self.conn, project_path)?;
+                tombstones::purge_expired(
+                    &self.conn,
+                    Some(project_path),
+                    options.tombstone_grace_days,
+                )?;
+                Ok::<_, anyhow::Error>(deleted)
+            }) {
+                Ok(deleted) => {
+                    chunks_deleted = deleted;
+                    if deleted > 0 {
+                        log::info!("Soft-deleted {} chunks of removed files", deleted);
+                    }
+                }
+                Err(e) => {
+                    let e = e.context("Failed to prune chunks of removed files");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, None, &e));
+                    tracker.phase_error("tombstones", &e);
+                }
+            }
+        }
+
+        // Relaciones inferidas de los chunks vigentes (las de imports ya se generaron);
+        // con subdirectorio solo se refrescan las de sus archivos y vecinos
+        if options.chunk_types.contains(&ChunkType::RawSource) && !tracker.is_aborted() {
+            match breakdown.time("relationships", || match scope {
+                Some(_) => {
+                    let changed: Vec<String> = scope_files.iter().cloned().collect();
+                    relationships::refresh_relationships(&self.conn, project_path, &changed)
+                }
+                None => relationships::infer_relationships(&self.conn, project_path),
+            }) {
+                Ok(report) => {
+                    relationships_created += report.relationships_created;
+                    log::info!("Inferred {} relationships", report.relationships_created);
+                }
+                Err(e) => {
+                    let e = e.context("Failed to infer relationships");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, None, &e));
+                    tracker.phase_error("relationships", &e);
+                }
+            }
+        }
+
+        // Métricas de grafo (fan-in, fan-out, centralidad) sobre las relaciones finales
+        if options.chunk_types.contains(&ChunkType::RawSource) && !tracker.is_aborted() {
+            if let Err(e) = breakdown.time("graph_metrics", || {
+                graph_metrics::compute_graph_metrics(&self.conn, project_path)
+            }) {
+                let e = e.context("Failed to compute graph metrics");
+                log::warn!("{:#}", e);
+                errors.push(indexing_error(None, None, &e));
+                tracker.phase_error("graph_metrics", &e);
+            }
+        }
+
+        // 6. Secret scanning sobre los chunks de código y configuración
+        if (options.chunk_types.contains(&ChunkType::RawSource)
+            || options.chunk_types.contains(&ChunkType::StateConfig))
+            && !tracker.is_aborted()
+        {
+            match breakdown.time("secret_scan", || {
+                secrets::scan_project_secrets(
+                    &self.conn,
+                    project_path,
+                    scope,
+                    &options.secret_redaction,
+                )
+            }) {
+                Ok(count) => {
+                    secrets_found = count;
+                    if count > 0 {
+                        log::warn!("Found {} potential secrets in {}", count, project_path);
+                    }
+                }
+                Err(e) => {
+                    let e = e.context("Failed to scan secrets");
+                    log::warn!("{:#}", e);
+                    errors.push(indexing_error(None, None, &e));
+                    tracker.phase_error("secret_scan", &e);
+                }
+            }
+        }
+
+        let completed_at = Utc::now();
+        breakdown.log_summary(project_path);
+        let (by_type, phase_durations) = breakdown.into_parts();
+        let (status, abort_reason) = tracker.finish();
+
+        let mut result = ChunkingResult {
+            project_path: project_path.to_string(),
+            chunks_created,
+            chunks_updated,
+            chunks_deleted,
+            relationships_created,
+            secrets_found,
+            shallow_history,
+            errors,
+            started_at,
+            completed_at,
+            dry_run: None,
+            by_type,
+            phase_durations,
+            status,
+            abort_reason,
+            run_id: None,
+            embeddings: None,
+        };
+        result.run_id = Some(index_runs::record_indexing_run(
+            &self.conn,
+            &result,
+            match scope {
+                Some(_) => index_runs::RUN_KIND_PARTIAL,
+                None => index_runs::RUN_KIND_FULL,
+            },
+        )?);
+        Ok(result)
+    }
+
+    /// Indexa cada raíz de un proyecto lógico con sus propios ajustes y combina los resultados
+    pub fn process_logical_project(
+        &self,
+        name: &str,
+        options: &ChunkingOptions,
+    ) -> Result<ChunkingResult> {
+        let project = storage::get_logical_project(&self.conn, name)?
+            .ok_or_else(|| anyhow::anyhow!("Logical project not found: {}", name))?;
+        let started_at = Utc::now();
+        let mut combined = ChunkingResult {
+            project_path: project.project_path.clone(),
+            chunks_created: 0,
+            chunks_updated: 0,
+            chunks_deleted: 0,
+            relationships_created: 0,
+            secrets_found: 0,
+            shallow_history: false,
+            errors: Vec::new(),
+            started_at,
+            completed_at: started_at,
+            dry_run: options.dry_run.then(DryRunReport::default),
+            by_type: BTreeMap::new(),
+            phase_durations: Vec::new(),
+            status: IndexingStatus::Completed,
+            abort_reason: None,
+            run_id: None,
+            embeddings: None,
+        };
+
+        for root in &project.roots {
+            let root_options = logical_projects::root_options(options, root);
+            match self.process_project(&root.path, &root_options) {
+                Ok(result) => {
+                    combined.chunks_created += result.chunks_created;
+                    combined.chunks_updated += result.chunks_updated;
+                    combined.chunks_deleted += result.chunks_deleted;
+                    combined.relationships_created += result.relationships_created;
+                    combined.secrets_found += result.secrets_found;
+                    combined.shallow_history |= result.shallow_history;
+                    if let (Some(merged), Some(report)) = (&mut combined.dry_run, result.dry_run) {
+                        merged.merge_root(&root.label, report);
+                    }
+                    for (chunk_type, stats) in &result.by_type {
+                        combined.by_type.entry(chunk_type.clone()).or_default().merge(stats);
+                    }
+                    for phase in result.phase_durations {
+                        match combined.phase_durations.iter_mut().find(|p| p.phase == phase.phase) {
+                            Some(existing) => existing.duration_ms += phase.duration_ms,
+                            None => combined.phase_durations.push(phase),
+                        }
+                    }
+                    combined
+                        .errors
+                        .extend(result.errors.into_iter().map(|e| e.in_root(&root.label)));
+                    // Fail-fast: una raíz abortada detiene el resto
+                    if result.status == IndexingStatus::Aborted {
+                        combined.status = IndexingStatus::Aborted;
+                        combined.abort_reason = result
+                            .abort_reason
+                            .map(|reason| format!("[{}] {}", root.label, reason));
+                        break;
+                    }
+                }
+                Err(e) => combined.errors.push(indexing_error(None, None, &e).in_root(&root.label)),
+            }
+        }
+
+        combined.completed_at = Utc::now();
+        Ok(combined)
+    }
+
+    /// Crea un snapshot master (con reindexación) en cada raíz del proyecto lógico
+    pub fn create_logical_snapshot(&self, name: &str, user_message: &str) -> Result<Vec<LogicalSnapshot>> {
+        logical_projects::create_logical_snapshot(&self.conn, name, |root| {
+            self.create_user_snapshot(&root.path, user_message, &[], None)
+        })
+    }
+
+    /// Clona (shallow con `depth`) una URL en `cache_root` y la indexa en modo solo lectura:
+    /// no se crean snapshots ni notas Git en el clon
+    pub fn index_remote_repository(
+        &self,
+        url: &str,
+        cache_root: &Path,
+        depth: Option<i32>,
+        options: &ChunkingOptions,
+    ) -> Result<RemoteIndexResult> {
+        let mut repository =
+            remote_repos::prepare_remote_repository(&self.conn, url, cache_root, depth)?;
+
+        let result = self.process_project(&repository.project_path, options)?;
+
+        if !options.dry_run {
+            repository.last_indexed_at = Some(result.completed_at);
+            storage::upsert_remote_repository(&self.conn, &repository)?;
+        }
+
+        Ok(RemoteIndexResult { repository, result })
+    }
+
+    /// Benchmark del índice: mide el throughput de una indexación completa en dry run
+    /// (se revierte; se omite si el índice es de solo lectura) y la latencia de las
+    /// consultas representativas, y guarda la ejecución con las regresiones respecto
+    /// a la anterior del proyecto
+    pub fn bench_index(
+        &self,
+        project_path: &str,
+        options: &ChunkingOptions,
+        iterations: usize,
+    ) -> Result<BenchmarkRun> {
+        let indexing = if storage::get_read_only_project(&self.conn, project_path)?.is_some() {
+            None
+        } else {
+            let options = ChunkingOptions {
+                dry_run: true,
+                ..options.clone()
+            };
+            let started = Instant::now();
+            let result = self.process_project(project_path, &options)?;
+            Some(bench::indexing_benchmark(&result, started.elapsed()))
+        };
+        bench::record_benchmark(&self.conn, project_path, iterations, indexing)
+    }
+
+    /// Reindexación incremental: solo procesa los archivos modificados
+    /// Se ejecuta automáticamente después de crear snapshots.
+    /// Con `dry_run` solo reporta los cambios en `ChunkingResult.dry_run`
+    pub fn reindex_changed_files(
+        &self,
+        project_path: &str,
+        changed_files: &[String],
+        snapshot_id: Option<i64>,
+        dry_run: bool,
+    ) -> Result<ChunkingResult> {
+        if dry_run {
+            let (mut result, report) = dry_run::preview(&self.conn, project_path, || {
+                reindex_files(&self.conn, project_path, changed_files, snapshot_id)
+            })?;
+            result.dry_run = Some(report);
+            result.run_id = None;
+            return Ok(result);
+        }
+        reindex_files(&self.conn, project_path, changed_files, snapshot_id)
+            .map(|result| self.refresh_embeddings_after_index(project_path, result))
+    }
+
+    /// Marca los chunks desactualizados según las huellas de archivo y reindexa
+    /// solo esos archivos (los eliminados van a la papelera)
+    pub fn refresh_stale_chunks(&self, project_path: &str) -> Result<ChunkingResult> {
+        read_only::ensure_index_writable(&self.conn, project_path)?;
+        let report = staleness::mark_stale_chunks(&self.conn, project_path)?;
+        log::info!(
+            "[Chunking] {} stale files ({} chunks) in project {}",
+            report.stale_files.len(),
+            report.stale_chunks,
+            project_path
+        );
+        reindex_files(&self.conn, project_path, &report.stale_files, None)
+            .map(|result| self.refresh_embeddings_after_index(project_path, result))
+    }
+
+    /// Crea un snapshot master del estado actual del proyecto
+    /// Automáticamente reindexingdex los archivos modificados
+    pub fn create_user_snapshot(
+        &self,
+        project_path: &str,
+        user_message: &str,
+        _changed_files: &[String],
+        _parent_snapshot_id: Option<i64>,
+    ) -> Result<i64> {
+        // Crear snapshot y obtener archivos modificados desde Git
+        let snapshot_id = snapshots::create_master_snapshot_with_git(
+            &self.conn,
+            project_path,
+            user_message,
+        )?;
+
+        // Obtener archivos modificados del snapshot recién creado
+        let changed_files: Vec<String> = self.conn
+            .query_row(
+                "SELECT changed_files FROM snapshots WHERE id = ?1",
+                rusqlite::params![snapshot_id],
+                |row| row.get::<_, String>(0),
+            )
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        let (options, _) = project_config::resolve_options(project_path, &ChunkingOptions::default());
+
+        if options.snapshot_policy.write_git_notes {
+            if let Err(e) = git_notes::write_commit_notes(&self.conn, project_path) {
+                log::warn!("Failed to write git notes after master snapshot: {}", e);
+            }
+        }
+
+        // Reindexar archivos modificados automáticamente
+        if options.snapshot_policy.auto_reindex && !changed_files.is_empty() {
+            println!(
+                "[Chunking] Auto-reindexing {} changed files after master snapshot",
+                changed_files.len()
+            );
+
+            match self.reindex_changed_files(project_path, &changed_files, Some(snapshot_id), false) {
+                Ok(result) => {
+                    println!(
+                        "[Chunking] Auto-reindex complete: {} created, {} updated",
+                        result.chunks_created, result.chunks_updated
+                    );
+                }
+                Err(e) => {
+                    log::warn!("Auto-reindex failed: {}", e);
+                }
+            }
+        }
+
+        Ok(snapshot_id)
+    }
+
+    /// Crea un snapshot agent de un cambio realizado por el agente
+    /// Automáticamente reindexingdex los archivos modificados
+    pub fn create_agent_snapshot(
+        &self,
+        project_path: &str,
+        message: &str,
+        changed_files: &[String],
+        master_snapshot_id: i64,
+    ) -> Result<i64> {
+        // Crear snapshot agent
+        let snapshot_id = snapshots::create_agent_snapshot_with_git(
+            &self.conn,
+            project_path,
+            master_snapshot_id,
+            message,
+            Some(changed_files.to_vec()),
+        )?;
+
+        // Reglas propuestas para la API pública nueva, pendientes de validación
+        rule_proposals::propose_rules_after_agent_snapshot(&self.conn, project_path, snapshot_id);
+
+        let (options, _) = project_config::resolve_options(project_path, &ChunkingOptions::default());
+
+        // Reindexar archivos modificados automáticamente
+        if options.snapshot_policy.auto_reindex && !changed_files.is_empty() {
+            println!(
+                "[Chunking] Auto-reindexing {} changed files after agent snapshot",
+                changed_files.len()
+            );
+
+            match self.reindex_changed_files(project_path, changed_files, Some(snapshot_id), false) {
+                Ok(result) => {
+                    println!(
+                        "[Chunking] Auto-reindex complete: {} created, {} updated",
+                        result.chunks_created, result.chunks_updated
+                    );
+                }
+                Err(e) => {
+                    log::warn!("Auto-reindex failed: {}", e);
+                }
+            }
+        }
+
+        Ok(snapshot_id)
+    }
+
+    /// Propone una regla de negocio para validación del usuario
+    pub fn propose_business_rule(
+        &self,
+        project_path: &str,
+        entity_name: &str,
+        file_path: &str,
+        ai_interpretation: &str,
+    ) -> Result<i64> {
+        business_rules::propose_business_rule(
+            &self.conn,
+            project_path,
+            entity_name,
+            file_path,
+            ai_interpretation,
+        )
+    }
+
+    /// Registra un error en el sistema
+    pub fn log_error(
+        &self,
+        project_path: &str,
+        error_type: &str,
+        message: &str,
+        file_path: Option<&str>,
+        stacktrace: Option<&str>,
+    ) -> Result<i64> {
+        errors::log_error(
+            &self.conn,
+            project_path,
+            error_type,
+            message,
+            file_path,
+            None,
+            stacktrace,
+            None,
+        )
+    }
+}
+
+/// Conteos por tipo de chunk y tiempos por fase de una indexación
+#[derive(Default)]
+struct IndexStats {
+    by_type: BTreeMap<String, ChunkTypeStats>,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl IndexStats {
+    fn entry(&mut self, chunk_type: ChunkType) -> &mut ChunkTypeStats {
+        self.by_type.entry(chunk_type.as_str().to_string()).or_default()
+    }
+
+    /// Ejecuta `f` y suma su duración a la fase (las fases por archivo se acumulan)
+    fn time<T>(&mut self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let output = f();
+        let elapsed = start.elapsed();
+        match self.phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => self.phases.push((phase, elapsed)),
+        }
+        output
+    }
+
+    fn log_summary(&self, project_path: &str) {
+        for (chunk_type, stats) in &self.by_type {
+            log::info!(
+                "[Chunking] {} in {}: {} created, {} updated, {} skipped, {} errored",
+                chunk_type,
+                project_path,
+                stats.created,
+                stats.updated,
+                stats.skipped,
+                stats.errored
+            );
+        }
+        for (phase, duration) in &self.phases {
+            log::debug!("[Chunking] Phase {} took {:?}", phase, duration);
+        }
+    }
+
+    fn into_parts(self) -> (BTreeMap<String, ChunkTypeStats>, Vec<PhaseDuration>) {
+        let phases = self
+            .phases
+            .into_iter()
+            .map(|(phase, duration)| PhaseDuration {
+                phase: phase.to_string(),
+                duration_ms: duration.as_millis() as u64,
+            })
+            .collect();
+        (self.by_type, phases)
+    }
+}
+
+/// Lista los archivos del proyecto (rutas relativas) respetando .gitignore
+fn list_project_files(project_path: &str) -> BTreeSet<String> {
+    WalkBuilder::new(project_path)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(false)
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| {
+            e.path()
+                .strip_prefix(project_path)
+                .ok()
+                .map(|p| storage::normalize_file_path(&p.to_string_lossy()))
+        })
+        .collect()
+}
+
+/// Reindexación incremental sobre una conexión existente (usada por el orquestador
+/// y por los comandos que comparten la conexión del estado)
+pub fn reindex_files(
+    conn: &Connection,
+    project_path: &str,
+    changed_files: &[String],
+    snapshot_id: Option<i64>,
+) -> Result<ChunkingResult> {
+    read_only::ensure_index_writable(conn, project_path)?;
+    let started_at = Utc::now();
+    let mut chunks_created = 0;
+    let mut chunks_updated = 0;
+    let mut chunks_deleted = 0;
+    let mut relationships_created = 0;
+    let mut secrets_found = 0;
+    let mut errors = Vec::new();
+    let mut breakdown = IndexStats::default();
+
+    log::info!(
+        "[Chunking] Incremental reindex: {} files changed in project {}",
+        changed_files.len(),
+        project_path
+    );
+
+    let (mut options, config_error) =
+        project_config::resolve_options(project_path, &ChunkingOptions::default());
+    errors.extend(config_error.map(project_config::config_error));
+    options.code_extensions =
+        code_extensions::resolve_code_extensions(conn, project_path, &options.code_extensions)?;
+    let hash_algorithm = hashing::get_hash_algorithm(conn, project_path)?;
+    let mut tracker = ErrorTracker::new(&options.error_budget);
+
+    // Procesar solo los archivos que cambiaron
+    for file_path in changed_files {
+        if tracker.is_aborted() {
+            break;
+        }
+
+        let full_path = Path::new(project_path).join(file_path);
+
+        // Archivo borrado: sus chunks van a la papelera
+        if !full_path.exists() {
+            match tombstones::delete_file(conn, project_path, file_path) {
+                Ok(count) => chunks_deleted += count,
+                Err(e) => {
+                    let e = e.context("Failed to soft-delete chunks");
+                    tracker.file_error(&mut errors, file_path, None, &e);
+                }
+            }
+            if let Err(e) = staleness::forget_file(conn, project_path, file_path) {
+                log::warn!("Failed to forget fingerprint for {}: {}", file_path, e);
+            }
+            if let Err(e) = syntax_errors::resolve_file_syntax_errors(conn, project_path, file_path)
+            {
+                log::warn!("Failed to resolve syntax errors for {}: {}", file_path, e);
+            }
+            ast::forget_parse_tree(project_path, file_path);
+            continue;
+        }
+
+        if raw_source::should_ignore(file_path, &options.ignore_patterns) {
+            continue;
+        }
+
+        // Read file content
+        match std::fs::read_to_string(&full_path) {
+            Ok(content) => {
+                if let Err(e) = staleness::record_fingerprint(
+                    conn,
+                    project_path,
+                    file_path,
+                    &content,
+                    hash_algorithm,
+                ) {
+                    log::warn!("Failed to record fingerprint for {}: {}", file_path, e);
+                }
+
+                // Generate all chunk types for this file
+                // RawSource chunk (solo para las extensiones de código configuradas)
+                let raw_chunk = if raw_source::is_code_file(&full_path, &options.code_extensions) {
+                    raw_source::create_raw_source_chunk(
+                        project_path,
+                        file_path,
+                        &content,
+                        hash_algorithm,
+                    )
+                    .ok()
+                } else {
+                    None
+                };
+                if let Some(chunk) = raw_chunk {
+                    let stats = breakdown.entry(ChunkType::RawSource);
+                    match storage::upsert_chunk(conn, &chunk, snapshot_id) {
+                        Ok(created) => {
+                            if created {
+                                chunks_created += 1;
+                                stats.created += 1;
+                            } else {
+                                chunks_updated += 1;
+                                stats.updated += 1;
+                            }
+                        }
+                        Err(e) => {
+                            stats.errored += 1;
+                            tracker.file_error(
+                                &mut errors,
+                                file_path,
+                                Some(ChunkType::RawSource),
+                                &e,
+                            );
+                        }
+                    }
+
+                    let chunk_id = storage::get_chunk_id_by_hash(
+                        conn,
+                        &chunk.project_path,
+                        &chunk.content_hash,
+                    )
+                    .ok()
+                    .flatten();
+                    match breakdown.time("secret_scan", || {
+                        secrets::scan_file_secrets(
+                            conn,
+                            project_path,
+                            file_path,
+                            &content,
+                            chunk_id,
+                            &options.secret_redaction,
+                        )
+                    }) {
+                        Ok(count) => secrets_found += count,
+                        Err(e) => {
+                            tracker.file_error(&mut errors, file_path, None, &e);
+                        }
+                    }
+                }
+
+                // AST chunks (salvo los archivos de la skip list de parseo)
+                let skip_ast = parse_failures::should_skip(
+                    conn,
+                    project_path,
+                    file_path,
+                    &content,
+                    options.max_parse_attempts,
+                )
+                .unwrap_or(false);
+                if skip_ast {
+                    log::debug!("[Chunking] Skipping AST for {}: parse-failure skip list", file_path);
+                    breakdown.entry(ChunkType::Ast).skipped += 1;
+                } else {
+                    let outcome = breakdown.time("ast", || {
+                        ast::create_ast_chunks(project_path, file_path, &content, &options)
+                    });
+                    if let Err(e) = parse_failures::record_outcome(
+                        conn,
+                        project_path,
+                        file_path,
+                        &content,
+                        &outcome,
+                    ) {
+                        log::warn!("Failed to update parse failures for {}: {}", file_path, e);
+                    }
+                    match outcome {
+                        Ok(ast_chunks) => {
+                            for chunk in &ast_chunks {
+                                let stats = breakdown.entry(ChunkType::Ast);
+                                match storage::upsert_chunk(conn, chunk, snapshot_id) {
+                                    Ok(created) => {
+                                        if created {
+                                            chunks_created += 1;
+                                            stats.created += 1;
+                                        } else {
+                                            chunks_updated += 1;
+                                            stats.updated += 1;
+                                        }
+                                    }
+                                    Err(e) => {
+                                        stats.errored += 1;
+                                        tracker.file_error(
+                                            &mut errors,
+                                            file_path,
+                                            Some(ChunkType::Ast),
+                                            &e,
+                                        );
+                                    }
+                                }
+                            }
+                            match ast::remove_stale_entity_chunks(
+                                conn,
+                                project_path,
+                                file_path,
+                                &ast_chunks,
+                            ) {
+                                Ok(count) => chunks_deleted += count,
+                                Err(e) => {
+                                    tracker.file_error(
+                                        &mut errors,
+                                        file_path,
+                                        Some(ChunkType::Ast),
+                                        &e,
+                                    );
+                                }
+                            }
+                            if let Err(e) = syntax_errors::record_syntax_errors(
+                                conn,
+                                project_path,
+                                file_path,
+                                &ast_chunks,
+                            ) {
+                                tracker.file_error(
+                                    &mut errors,
+                                    file_path,
+                                    Some(ChunkType::Ast),
+                                    &e,
+                                );
+                            }
+                        }
+                        Err(_) => breakdown.entry(ChunkType::Ast).skipped += 1,
+                    }
+                }
+
+                // Claves de traducción (solo archivos de locale)
+                match breakdown.time("i18n", || {
+                    i18n::generate_i18n_chunks(conn, project_path, file_path, &content)
+                }) {
+                    Ok(count) => {
+                        chunks_created += count;
+                        breakdown.entry(ChunkType::StateConfig).created += count;
+                    }
+                    Err(e) => {
+                        breakdown.entry(ChunkType::StateConfig).errored += 1;
+                        tracker.file_error(
+                            &mut errors,
+                            file_path,
+                            Some(ChunkType::StateConfig),
+                            &e,
+                        );
+                    }
+                }
+
+                // Other chunk types as needed...
+            }
+            Err(e) => {
+                let error = anyhow::Error::new(e).context("Failed to read file");
+                tracker.file_error(&mut errors, file_path, None, &error);
+            }
+        }
+    }
+
+    // Los archivos cambiados pueden crear o romper relaciones con el resto del proyecto
+    if chunks_created + chunks_updated + chunks_deleted > 0 && !tracker.is_aborted() {
+        match breakdown.time("relationships", || {
+            relationships::refresh_relationships(conn, project_path, changed_files)
+        }) {
+            Ok(report) => relationships_created = report.relationships_created,
+            Err(e) => {
+                let e = e.context("Failed to refresh relationships");
+                log::warn!("{:#}", e);
+                errors.push(indexing_error(None, None, &e));
+                tracker.phase_error("relationships", &e);
+            }
+        }
+        if let Err(e) = breakdown.time("tauri_surface", || {
+            tauri_surface::generate_tauri_surface_chunk(conn, project_path)
+        }) {
+            let e = e.context("Failed to refresh Tauri command surface");
+            log::warn!("{:#}", e);
+            errors.push(indexing_error(None, Some(ChunkType::Callgraph), &e));
+            tracker.phase_error("tauri_surface", &e);
+        }
+        if let Err(e) = breakdown.time("ui_components", || {
+            ui_components::generate_ui_chunks(conn, project_path)
+        }) {
+            let e = e.context("Failed to refresh UI components and routes");
+            log::warn!("{:#}", e);
+            errors.push(indexing_error(None, Some(ChunkType::Callgraph), &e));
+            tracker.phase_error("ui_components", &e);
+        }
+        if let Err(e) = breakdown.time("sql_queries", || {
+            sql_queries::generate_sql_chunks(conn, project_path)
+        }) {
+            let e = e.context("Failed to refresh SQL queries");
+            log::warn!("{:#}", e);
+            errors.push(indexing_error(None, Some(ChunkType::Callgraph), &e));
+            tracker.phase_error("sql_queries", &e);
+        }
+        if let Err(e) = breakdown.time("graph_metrics", || {
+            graph_metrics::compute_graph_metrics(conn, project_path)
+        }) {
+            let e = e.context("Failed to compute graph metrics");
+            log::warn!("{:#}", e);
+            errors.push(indexing_error(None, None, &e));
+            tracker.phase_error("graph_metrics", &e);
+        }
+    }
+
+    let completed_at = Utc::now();
+
+    log::info!(
+        "[Chunking] Incremental reindex completed: {} created, {} updated, {} deleted, {} errors",
+        chunks_created, chunks_updated, chunks_deleted, errors.len()
+    );
+    breakdown.log_summary(project_path);
+    let (by_type, phase_durations) = breakdown.into_parts();
+    let (status, abort_reason) = tracker.finish();
+
+    let mut result = ChunkingResult {
+        project_path: project_path.to_string(),
+        chunks_created,
+        chunks_updated,
+        chunks_deleted,
+        relationships_created,
+        secrets_found,
+        shallow_history: false,
+        errors,
+        started_at,
+        completed_at,
+        dry_run: None,
+        by_type,
+        phase_durations,
+        status,
+        abort_reason,
+        run_id: None,
+        embeddings: None,
+    };
+    result.run_id = Some(index_runs::record_indexing_run(
+        conn,
+        &result,
+        index_runs::RUN_KIND_INCREMENTAL,
+    )?);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod orchestrator_tests {
+    use super::*;
+    use crate::types::{HashAlgorithm, IndexingErrorKind};
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_chunking_orchestrator_creation() {
+        let conn = Connection::open_in_memory().unwrap();
+        let orchestrator = ChunkingOrchestrator::new(conn);
+        assert!(orchestrator.is_ok());
+    }
+
+    #[test]
+    fn test_default_chunking_options() {
+        let options = ChunkingOptions::default();
+        assert!(options.chunk_types.contains(&ChunkType::RawSource));
+        assert!(options.chunk_types.contains(&ChunkType::Ast));
+        assert_eq!(options.max_commits, Some(100));
+    }
+
+    #[test]
+    fn test_orchestrator_through_public_api() {
+        let dir = std::env::temp_dir().join(format!("opcode-lib-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        let project = dir.to_string_lossy().to_string();
+
+        let orchestrator = Orchestrator::new(Connection::open_in_memory().unwrap()).unwrap();
+        let options = ChunkingOptions {
+            chunk_types: vec![ChunkType::RawSource],
+            ..Default::default()
+        };
+        let result = orchestrator.process_project(&project, &options).unwrap();
+        assert_eq!(result.chunks_created, 1);
+
+        let storage = orchestrator.storage();
+        let chunks = storage
+            .query_chunks(&types::ChunkQuery {
+                project_path: Some(project.clone()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].file_path.as_deref(), Some("src/main.rs"));
+        assert_eq!(storage.delete_project_chunks(&project).unwrap(), 1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_process_project_path_indexes_only_subpath() {
+        let dir = std::env::temp_dir().join(format!("opcode-partial-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("services/api")).unwrap();
+        std::fs::create_dir_all(dir.join("services/api-v2")).unwrap();
+        std::fs::write(dir.join("services/api/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.join("services/api-v2/main.rs"), "fn main() {}\n").unwrap();
+        let project = dir.to_string_lossy().to_string();
+
+        let orchestrator = Orchestrator::new(Connection::open_in_memory().unwrap()).unwrap();
+        let options = ChunkingOptions {
+            chunk_types: vec![ChunkType::RawSource],
+            ..Default::default()
+        };
+        let result = orchestrator
+            .process_project_path(&project, "./services/api/", &options)
+            .unwrap();
+        assert_eq!(result.chunks_created, 1);
+        let conn = &orchestrator.conn;
+        let files =
+            storage::get_latest_file_contents(conn, &project, &ChunkType::RawSource).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files.contains_key("services/api/main.rs"));
+        let state = index_runs::get_index_state(conn, &project).unwrap();
+        assert_eq!(state.last_run_kind.as_deref(), Some("partial"));
+
+        assert!(orchestrator
+            .process_project_path(&project, "../other", &options)
+            .is_err());
+        assert!(orchestrator
+            .process_project_path(&project, "services/missing", &options)
+            .is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_dry_run_reports_without_writing() {
+        let dir = std::env::temp_dir().join(format!("opcode-dry-run-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "pub fn lib() {}\n").unwrap();
+        let project = dir.to_string_lossy().to_string();
+
+        let orchestrator = Orchestrator::new(Connection::open_in_memory().unwrap()).unwrap();
+        let options = ChunkingOptions {
+            chunk_types: vec![ChunkType::RawSource],
+            dry_run: true,
+            ..Default::default()
+        };
+        let result = orchestrator.process_project(&project, &options).unwrap();
+        let report = result.dry_run.unwrap();
+        assert_eq!(report.chunks_created, 2);
+        assert_eq!(report.by_type.len(), 1);
+        assert_eq!(report.by_type[0].chunk_type, "raw_source");
+        assert_eq!(report.files[0].file_path.as_deref(), Some("src/lib.rs"));
+
+        let count: i64 = orchestrator
+            .conn
+            .query_row("SELECT COUNT(*) FROM chunks", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+
+        // Después de indexar de verdad, reindexar un archivo sin cambios solo actualiza
+        // su código fuente (el reindexado genera también los demás tipos de chunk)
+        let options = ChunkingOptions { dry_run: false, ..options };
+        orchestrator.process_project(&project, &options).unwrap();
+        let result = orchestrator
+            .reindex_changed_files(&project, &["src/main.rs".to_string()], None, true)
+            .unwrap();
+        let report = result.dry_run.unwrap();
+        let raw = report
+            .by_type
+            .iter()
+            .find(|changes| changes.chunk_type == "raw_source")
+            .unwrap();
+        assert_eq!((raw.created, raw.updated), (0, 1));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_switching_hash_algorithm_does_not_duplicate_chunks() {
+        let dir = std::env::temp_dir().join(format!("opcode-rehash-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.rs"), "pub fn a() {}\n").unwrap();
+        std::fs::write(dir.join("src/b.rs"), "pub fn b() {}\n").unwrap();
+        let project = dir.to_string_lossy().to_string();
+
+        let orchestrator = Orchestrator::new(Connection::open_in_memory().unwrap()).unwrap();
+        let options = ChunkingOptions::default();
+        let count = |orchestrator: &Orchestrator| -> i64 {
+            orchestrator
+                .conn
+                .query_row(
+                    "SELECT COUNT(*) FROM chunks WHERE project_path = ?1",
+                    [&project],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        orchestrator.process_project(&project, &options).unwrap();
+        let indexed = count(&orchestrator);
+        assert!(indexed > 0);
+
+        hashing::set_hash_algorithm(&orchestrator.conn, &project, HashAlgorithm::Blake3).unwrap();
+        orchestrator.process_project(&project, &options).unwrap();
+        assert_eq!(count(&orchestrator), indexed);
+        let sha256_left: i64 = orchestrator
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM chunks WHERE project_path = ?1 AND chunk_type = 'raw_source'
+                 AND content_hash NOT LIKE 'blake3:%'",
+                [&project],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(sha256_left, 0);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_refresh_stale_chunks_updates_the_project_chunk() {
+        let dir = std::env::temp_dir().join(format!("opcode-refresh-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.rs"), "pub fn a() {}\n").unwrap();
+        let project = dir.to_string_lossy().to_string();
+
+        let orchestrator = Orchestrator::new(Connection::open_in_memory().unwrap()).unwrap();
+        let options = ChunkingOptions {
+            chunk_types: vec![ChunkType::RawSource],
+            ..Default::default()
+        };
+        orchestrator.process_project(&project, &options).unwrap();
+
+        let edited = "pub fn a() -> u32 {\n    1\n}\n";
+        std::fs::write(dir.join("src/a.rs"), edited).unwrap();
+        orchestrator.refresh_stale_chunks(&project).unwrap();
+
+        // El chunk nuevo queda en el proyecto con la ruta relativa a su raíz
+        let range =
+            source_range::get_source_range(&orchestrator.conn, &project, "src/a.rs", 1, 3, None)
+                .unwrap()
+                .unwrap();
+        assert_eq!(range.content, edited);
+        let elsewhere: i64 = orchestrator
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM chunks WHERE project_path != ?1",
+                [&project],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(elsewhere, 0);
+        assert!(staleness::mark_stale_chunks(&orchestrator.conn, &project)
+            .unwrap()
+            .stale_files
+            .is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_result_breaks_down_counts_by_type_and_phase() {
+        let dir = std::env::temp_dir().join(format!("opcode-breakdown-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "sin parser\n").unwrap();
+        let project = dir.to_string_lossy().to_string();
+
+        let orchestrator = Orchestrator::new(Connection::open_in_memory().unwrap()).unwrap();
+        let options = ChunkingOptions {
+            chunk_types: vec![ChunkType::Ast],
+            ..Default::default()
+        };
+        let result = orchestrator.process_project(&project, &options).unwrap();
+        // main.rs: chunk AST del archivo y de la función `main`; notes.txt no tiene parser
+        let ast = &result.by_type["ast"];
+        assert_eq!((ast.created, ast.skipped, ast.errored), (2, 1, 0));
+        assert!(!result.by_type.contains_key("raw_source"));
+
+        let phases: Vec<_> = result.phase_durations.iter().map(|p| p.phase.as_str()).collect();
+        assert_eq!(phases, vec!["discovery", "ast", "tombstones"]);
+
+        let result = orchestrator
+            .reindex_changed_files(&project, &["main.rs".to_string()], None, false)
+            .unwrap();
+        assert_eq!(result.by_type["raw_source"].created, 1);
+        assert!(result.phase_durations.iter().any(|p| p.phase == "ast"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_reindex_aborts_when_error_budget_is_exhausted() {
+        let dir = std::env::temp_dir().join(format!("opcode-budget-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(project_config::PROJECT_CONFIG_FILE), "[errors]\nmax_file_errors = 2\n")
+            .unwrap();
+        let files: Vec<String> = (0..4).map(|i| format!("blob{}.rs", i)).collect();
+        for file in &files {
+            // UTF-8 inválido: la lectura del archivo falla
+            std::fs::write(dir.join(file), [0xff, 0xfe, 0xfd]).unwrap();
+        }
+        let project = dir.to_string_lossy().to_string();
+
+        let orchestrator = Orchestrator::new(Connection::open_in_memory().unwrap()).unwrap();
+        let result = orchestrator
+            .reindex_changed_files(&project, &files, None, false)
+            .unwrap();
+        assert_eq!(result.status, IndexingStatus::Aborted);
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors[0].file_path.as_deref(), Some("blob0.rs"));
+        assert_eq!(result.errors[0].kind, IndexingErrorKind::Io);
+        assert!(result.errors[0].message.starts_with("Failed to read file"));
+        assert!(result.abort_reason.unwrap().contains("2 file errors"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,429 @@
+use super::errors::{log_diagnostic, resolve_errors_by_type_prefix};
+use super::storage::CHUNK_CONTENT;
+use super::types::{ChunkType, LintImportResult, LintOffender};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Diagnóstico normalizado, independiente del linter de origen
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintDiagnostic {
+    pub file_path: String, // Ruta tal como la reporta el linter
+    pub line: usize,
+    pub column: usize,
+    pub rule: String,
+    pub severity: String, // "error" | "warning"
+    pub message: String,
+}
+
+/// Parsea la salida de `cargo clippy --message-format=json` (un objeto JSON por línea).
+/// `cargo build --message-format=json` usa el mismo formato
+pub fn parse_clippy_json(output: &str) -> Vec<LintDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for line in output.lines() {
+        let Ok(json) = serde_json::from_str::<Value>(line.trim()) else {
+            continue;
+        };
+        if json.get("reason").and_then(|r| r.as_str()) != Some("compiler-message") {
+            continue;
+        }
+        let Some(message) = json.get("message") else {
+            continue;
+        };
+
+        let severity = match message.get("level").and_then(|l| l.as_str()) {
+            Some("error") | Some("error: internal compiler error") => "error",
+            Some("warning") => "warning",
+            _ => continue,
+        };
+
+        // Span primario; los resúmenes ("N warnings emitted") no tienen spans
+        let Some(span) = message
+            .get("spans")
+            .and_then(|s| s.as_array())
+            .and_then(|spans| {
+                spans
+                    .iter()
+                    .find(|s| s.get("is_primary").and_then(|p| p.as_bool()) == Some(true))
+                    .or_else(|| spans.first())
+            })
+        else {
+            continue;
+        };
+
+        let Some(file_path) = span.get("file_name").and_then(|f| f.as_str()) else {
+            continue;
+        };
+
+        diagnostics.push(LintDiagnostic {
+            file_path: file_path.to_string(),
+            line: span.get("line_start").and_then(|l| l.as_u64()).unwrap_or(0) as usize,
+            column: span
+                .get("column_start")
+                .and_then(|c| c.as_u64())
+                .unwrap_or(0) as usize,
+            rule: message
+                .get("code")
+                .and_then(|c| c.get("code"))
+                .and_then(|c| c.as_str())
+                .unwrap_or("rustc")
+                .to_string(),
+            severity: severity.to_string(),
+            message: message
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("")
+                .to_string(),
+        });
+    }
+
+    diagnostics
+}
+
+/// Parsea la salida de `eslint -f json`
+pub fn parse_eslint_json(output: &str) -> Result<Vec<LintDiagnostic>> {
+    let results: Vec<Value> = serde_json::from_str(output)?;
+    let mut diagnostics = Vec::new();
+
+    for result in &results {
+        let Some(file_path) = result.get("filePath").and_then(|f| f.as_str()) else {
+            continue;
+        };
+        let Some(messages) = result.get("messages").and_then(|m| m.as_array()) else {
+            continue;
+        };
+
+        for message in messages {
+            let severity = match message.get("severity").and_then(|s| s.as_u64()) {
+                Some(2) => "error",
+                Some(1) => "warning",
+                _ => continue,
+            };
+            let fatal = message
+                .get("fatal")
+                .and_then(|f| f.as_bool())
+                .unwrap_or(false);
+
+            diagnostics.push(LintDiagnostic {
+                file_path: file_path.to_string(),
+                line: message.get("line").and_then(|l| l.as_u64()).unwrap_or(0) as usize,
+                column: message.get("column").and_then(|c| c.as_u64()).unwrap_or(0) as usize,
+                rule: message
+                    .get("ruleId")
+                    .and_then(|r| r.as_str())
+                    .unwrap_or(if fatal { "parse-error" } else { "eslint" })
+                    .to_string(),
+                severity: severity.to_string(),
+                message: message
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+            });
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+/// Importa la salida de un linter ("clippy" o "eslint") a error_logs.
+/// Los diagnósticos abiertos de una importación anterior del mismo linter se marcan resueltos
+pub fn import_lint_output(
+    conn: &Connection,
+    project_path: &str,
+    tool: &str,
+    output: &str,
+) -> Result<LintImportResult> {
+    let diagnostics = match tool {
+        "clippy" => parse_clippy_json(output),
+        "eslint" => parse_eslint_json(output)?,
+        other => return Err(anyhow!("Unsupported lint tool: {}", other)),
+    };
+
+    let known_files = indexed_files(conn, project_path)?;
+    let mut contents: HashMap<String, Option<String>> = HashMap::new();
+    let mut unmatched = BTreeMap::new();
+    let mut result = LintImportResult {
+        tool: tool.to_string(),
+        imported: 0,
+        errors: 0,
+        warnings: 0,
+        unmatched_files: Vec::new(),
+    };
+
+    resolve_errors_by_type_prefix(conn, project_path, &format!("lint:{}:", tool))?;
+
+    for diagnostic in &diagnostics {
+        let Some(file_path) =
+            resolve_project_file(project_path, &known_files, &diagnostic.file_path)
+        else {
+            unmatched.insert(diagnostic.file_path.clone(), ());
+            continue;
+        };
+
+        let content = contents
+            .entry(file_path.clone())
+            .or_insert_with(|| file_content(conn, project_path, &file_path));
+        let entity = content
+            .as_deref()
+            .and_then(|c| enclosing_entity(c, diagnostic.line));
+
+        let location = format!("{}:{}:{}", file_path, diagnostic.line, diagnostic.column);
+        log_diagnostic(
+            conn,
+            project_path,
+            &format!("lint:{}:{}", tool, diagnostic.rule),
+            &diagnostic.severity,
+            &format!("{}: {}", location, diagnostic.message),
+            Some(&file_path),
+            entity.as_deref(),
+        )?;
+
+        result.imported += 1;
+        if diagnostic.severity == "error" {
+            result.errors += 1;
+        } else {
+            result.warnings += 1;
+        }
+    }
+
+    result.unmatched_files = unmatched.into_keys().collect();
+    Ok(result)
+}
+
+/// Archivos con más diagnósticos de lint abiertos (errores primero)
+pub fn get_lint_offenders(
+    conn: &Connection,
+    project_path: &str,
+    limit: usize,
+) -> Result<Vec<LintOffender>> {
+    let mut stmt = conn.prepare(
+        "SELECT file_path, error_type, severity, occurrence_count FROM error_logs
+         WHERE project_path = ?1 AND is_resolved = 0 AND file_path IS NOT NULL
+           AND error_type LIKE 'lint:%'",
+    )?;
+
+    let rows = stmt
+        .query_map(params![project_path], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut by_file: BTreeMap<String, (usize, usize, HashMap<String, usize>)> = BTreeMap::new();
+    for (file_path, error_type, severity, count) in rows {
+        let entry = by_file.entry(file_path).or_default();
+        let count = count.max(1) as usize;
+        if severity.as_deref() == Some("error") {
+            entry.0 += count;
+        } else {
+            entry.1 += count;
+        }
+        let rule = error_type
+            .splitn(3, ':')
+            .nth(2)
+            .unwrap_or(&error_type)
+            .to_string();
+        *entry.2.entry(rule).or_insert(0) += count;
+    }
+
+    let mut offenders: Vec<LintOffender> = by_file
+        .into_iter()
+        .map(|(file_path, (errors, warnings, rules))| {
+            let mut rules: Vec<(String, usize)> = rules.into_iter().collect();
+            rules.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            LintOffender {
+                file_path,
+                errors,
+                warnings,
+                top_rules: rules.into_iter().take(3).map(|(rule, _)| rule).collect(),
+            }
+        })
+        .collect();
+
+    offenders.sort_by_key(|o| Reverse((o.errors, o.errors + o.warnings)));
+    offenders.truncate(limit);
+    Ok(offenders)
+}
+
+/// Archivos conocidos del proyecto según los chunks indexados
+pub(crate) fn indexed_files(conn: &Connection, project_path: &str) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT file_path FROM chunks
+         WHERE project_path = ?1 AND file_path IS NOT NULL AND deleted_at IS NULL",
+    )?;
+    let files = stmt
+        .query_map(params![project_path], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<HashSet<_>>>()?;
+    Ok(files)
+}
+
+/// Convierte la ruta reportada por el linter en una ruta relativa al proyecto.
+/// Clippy reporta rutas relativas al workspace de Cargo, que puede ser un subdirectorio
+pub(crate) fn resolve_project_file(
+    project_path: &str,
+    known_files: &HashSet<String>,
+    reported: &str,
+) -> Option<String> {
+    let reported = reported.replace('\\', "/");
+    let project = project_path.replace('\\', "/");
+    let relative = reported
+        .strip_prefix(&format!("{}/", project.trim_end_matches('/')))
+        .unwrap_or(&reported)
+        .trim_start_matches("./")
+        .to_string();
+
+    if known_files.contains(&relative) {
+        return Some(relative);
+    }
+
+    // Sufijo único entre los archivos indexados (ej: "src/main.rs" -> "src-tauri/src/main.rs")
+    let suffix = format!("/{}", relative);
+    let mut candidates: Vec<&String> = known_files
+        .iter()
+        .filter(|f| f.ends_with(&suffix))
+        .collect();
+    candidates.sort_by_key(|f| f.len());
+    if let Some(best) = candidates.first() {
+        return Some((*best).clone());
+    }
+
+    if !Path::new(&relative).is_absolute() && Path::new(project_path).join(&relative).is_file() {
+        return Some(relative);
+    }
+
+    None
+}
+
+/// Contenido del archivo: chunk de raw source más reciente o, si no existe, el disco
+pub(crate) fn file_content(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+) -> Option<String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM chunks WHERE project_path = ?1 AND file_path = ?2 AND chunk_type = ?3
+               AND deleted_at IS NULL
+             ORDER BY updated_at DESC, id DESC LIMIT 1",
+            CHUNK_CONTENT
+        ),
+        params![project_path, file_path, ChunkType::RawSource.as_str()],
+        |row| row.get(0),
+    )
+    .ok()
+    .or_else(|| std::fs::read_to_string(Path::new(project_path).join(file_path)).ok())
+}
+
+/// Nombre de la función/clase que contiene la línea (1-based), buscando hacia arriba
+pub fn enclosing_entity(content: &str, line: usize) -> Option<String> {
+    definitions(content)
+        .into_iter()
+        .rev()
+        .find(|(definition_line, _)| *definition_line <= line)
+        .map(|(_, name)| name)
+}
+
+/// Definiciones de funciones/clases del archivo: (línea 1-based, nombre)
+pub(crate) fn definitions(content: &str) -> Vec<(usize, String)> {
+    let definition_re = Regex::new(
+        r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|async|unsafe|const|static|public|private|protected)\s+)*(?:fn|function\*?|def|class|struct|enum|trait|impl(?:<[^>]*>)?)\s+([A-Za-z_$][A-Za-z0-9_$]*)",
+    )
+    .unwrap();
+    let arrow_re =
+        Regex::new(r"^\s*(?:export\s+)?(?:const|let|var)\s+([A-Za-z_$][A-Za-z0-9_$]*)\s*=\s*(?:async\s*)?(?:\([^)]*\)|[A-Za-z_$][A-Za-z0-9_$]*)\s*=>")
+            .unwrap();
+
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, l)| {
+            definition_re
+                .captures(l)
+                .or_else(|| arrow_re.captures(l))
+                .map(|cap| (i + 1, cap[1].to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{get_error_logs, init_chunk_database};
+
+    #[test]
+    fn test_parse_clippy_json() {
+        let output = r#"{"reason":"compiler-artifact","target":{}}
+{"reason":"compiler-message","message":{"level":"warning","message":"redundant clone","code":{"code":"clippy::redundant_clone"},"rendered":"warning: redundant clone","spans":[{"file_name":"src/lib.rs","line_start":4,"column_start":9,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"level":"warning","message":"2 warnings emitted","code":null,"spans":[]}}"#;
+        let diagnostics = parse_clippy_json(output);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "clippy::redundant_clone");
+        assert_eq!(diagnostics[0].line, 4);
+    }
+
+    #[test]
+    fn test_enclosing_entity() {
+        let code = "use x;\n\npub async fn load_user(id: u32) {\n    let a = 1;\n}\n\nconst render = (props) => {\n  return 1;\n};\n";
+        assert_eq!(enclosing_entity(code, 4).as_deref(), Some("load_user"));
+        assert_eq!(enclosing_entity(code, 8).as_deref(), Some("render"));
+        assert_eq!(enclosing_entity(code, 1), None);
+    }
+
+    #[test]
+    fn test_import_eslint_and_rank_offenders() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let project = "/work/app";
+        conn.execute(
+            "INSERT INTO chunks (project_path, chunk_type, file_path, content, content_hash, created_at, updated_at)
+             VALUES (?1, 'raw_source', 'src/a.ts', 'function main() {\n  foo;\n}\n', 'h1', '', ''),
+                    (?1, 'raw_source', 'src/b.ts', 'const x = 1;\n', 'h2', '', '')",
+            params![project],
+        )
+        .unwrap();
+
+        let output = r#"[
+          {"filePath": "/work/app/src/a.ts", "messages": [
+            {"ruleId": "no-unused-expressions", "severity": 2, "message": "Expected an assignment", "line": 2, "column": 3},
+            {"ruleId": "semi", "severity": 1, "message": "Missing semicolon", "line": 3, "column": 2}
+          ]},
+          {"filePath": "/work/app/src/b.ts", "messages": [
+            {"ruleId": "semi", "severity": 1, "message": "Missing semicolon", "line": 1, "column": 13}
+          ]},
+          {"filePath": "/elsewhere/c.ts", "messages": [
+            {"ruleId": "semi", "severity": 1, "message": "Missing semicolon", "line": 1, "column": 1}
+          ]}
+        ]"#;
+
+        let result = import_lint_output(&conn, project, "eslint", output).unwrap();
+        assert_eq!(result.imported, 3);
+        assert_eq!(result.errors, 1);
+        assert_eq!(result.unmatched_files, vec!["/elsewhere/c.ts".to_string()]);
+
+        let logs = get_error_logs(&conn, project, false).unwrap();
+        let error = logs
+            .iter()
+            .find(|l| l.severity.as_deref() == Some("error"))
+            .unwrap();
+        assert_eq!(error.entity_name.as_deref(), Some("main"));
+        assert_eq!(error.error_type, "lint:eslint:no-unused-expressions");
+
+        let offenders = get_lint_offenders(&conn, project, 10).unwrap();
+        assert_eq!(offenders[0].file_path, "src/a.ts");
+        assert_eq!(offenders[0].errors, 1);
+
+        // Reimportar reemplaza los diagnósticos abiertos
+        import_lint_output(&conn, project, "eslint", "[]").unwrap();
+        assert!(get_lint_offenders(&conn, project, 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use super::storage::{get_logical_project, query_chunks, upsert_logical_project};
+use super::types::{
+    Chunk, ChunkQuery, ChunkingOptions, LogicalProject, LogicalSnapshot, ProjectRoot,
+};
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Prefijo del project_path de los proyectos lógicos
+pub const LOGICAL_PROJECT_SCHEME: &str = "logical://";
+
+/// Registra (o reemplaza) un proyecto lógico formado por varias raíces
+pub fn register_logical_project(
+    conn: &Connection,
+    name: &str,
+    roots: &[ProjectRoot],
+) -> Result<LogicalProject> {
+    validate_roots(name, roots)?;
+    upsert_logical_project(conn, name, roots)?;
+    get_logical_project(conn, name)?.context("Logical project not found")
+}
+
+fn validate_roots(name: &str, roots: &[ProjectRoot]) -> Result<()> {
+    if name.trim().is_empty() || name.contains('/') {
+        bail!("Invalid logical project name: {:?}", name);
+    }
+    if roots.is_empty() {
+        bail!("A logical project needs at least one root");
+    }
+
+    let mut labels = HashSet::new();
+    let mut paths = HashSet::new();
+    for root in roots {
+        if root.label.is_empty() || root.label.contains('/') || root.label.contains('\\') {
+            bail!("Invalid root label: {:?}", root.label);
+        }
+        if !labels.insert(root.label.as_str()) {
+            bail!("Duplicate root label: {}", root.label);
+        }
+        if !paths.insert(root.path.as_str()) {
+            bail!("Duplicate root path: {}", root.path);
+        }
+        if !Path::new(&root.path).is_dir() {
+            bail!("Root {} is not a directory: {}", root.label, root.path);
+        }
+    }
+    Ok(())
+}
+
+/// Opciones de indexación de una raíz: las generales con los ajustes de la raíz
+pub fn root_options(options: &ChunkingOptions, root: &ProjectRoot) -> ChunkingOptions {
+    let mut merged = options.clone();
+    if let Some(chunk_types) = &root.chunk_types {
+        merged.chunk_types = chunk_types.clone();
+    }
+    merged.ignore_patterns.extend(root.ignore_patterns.iter().cloned());
+    merged
+}
+
+/// Traduce un chunk de una raíz al namespace lógico (`label/ruta`)
+fn to_logical_chunk(project: &LogicalProject, root: &ProjectRoot, mut chunk: Chunk) -> Chunk {
+    chunk.project_path = project.project_path.clone();
+    chunk.file_path = chunk
+        .file_path
+        .map(|file| format!("{}/{}", root.label, file));
+    chunk
+}
+
+/// Búsqueda unificada sobre todas las raíces de un proyecto lógico.
+/// `file_path` se interpreta en el namespace lógico (`frontend/src/app.ts`);
+/// los resultados se ordenan por `updated_at` y `limit`/`offset` aplican al conjunto
+pub fn query_logical_chunks(
+    conn: &Connection,
+    name: &str,
+    query: &ChunkQuery,
+) -> Result<Vec<Chunk>> {
+    let project = get_logical_project(conn, name)?.context("Logical project not found")?;
+
+    let mut chunks = Vec::new();
+    for root in &project.roots {
+        let file_path = match &query.file_path {
+            Some(file) => match file.strip_prefix(&format!("{}/", root.label)) {
+                Some(relative) => Some(relative.to_string()),
+                None => continue,
+            },
+            None => None,
+        };
+
+        let root_query = ChunkQuery {
+            project_path: Some(root.path.clone()),
+            file_path,
+            limit: query.limit.map(|limit| limit + query.offset.unwrap_or(0)),
+            offset: None,
+            ..query.clone()
+        };
+        chunks.extend(
+            query_chunks(conn, &root_query)?
+                .into_iter()
+                .map(|chunk| to_logical_chunk(&project, root, chunk)),
+        );
+    }
+
+    chunks.sort_by_key(|c| std::cmp::Reverse(c.updated_at));
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(usize::MAX);
+    Ok(chunks.into_iter().skip(offset).take(limit).collect())
+}
+
+/// Crea un snapshot en cada raíz con snapshots habilitados usando `snapshot_root`.
+/// Se detiene en la primera raíz que falle
+pub fn create_logical_snapshot(
+    conn: &Connection,
+    name: &str,
+    mut snapshot_root: impl FnMut(&ProjectRoot) -> Result<i64>,
+) -> Result<Vec<LogicalSnapshot>> {
+    let project = get_logical_project(conn, name)?.context("Logical project not found")?;
+
+    let mut snapshots = Vec::new();
+    for root in project.roots.iter().filter(|r| r.snapshots_enabled) {
+        let snapshot_id = snapshot_root(root)
+            .with_context(|| format!("Snapshot failed for root {}", root.label))?;
+        snapshots.push(LogicalSnapshot {
+            root_label: root.label.clone(),
+            root_path: root.path.clone(),
+            snapshot_id,
+        });
+    }
+    Ok(snapshots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+    use crate::types::ChunkType;
+
+    fn root(path: &Path, label: &str) -> ProjectRoot {
+        ProjectRoot {
+            path: path.to_string_lossy().to_string(),
+            label: label.to_string(),
+            chunk_types: None,
+            ignore_patterns: Vec::new(),
+            snapshots_enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_register_validates_roots() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let dir = std::env::temp_dir();
+
+        assert!(register_logical_project(&conn, "app", &[]).is_err());
+        assert!(register_logical_project(&conn, "app", &[root(&dir, "a/b")]).is_err());
+        assert!(
+            register_logical_project(&conn, "app", &[root(&dir, "web"), root(&dir, "web")])
+                .is_err()
+        );
+
+        let project = register_logical_project(&conn, "app", &[root(&dir, "web")]).unwrap();
+        assert_eq!(project.project_path, "logical://app");
+        assert_eq!(project.roots.len(), 1);
+    }
+
+    #[test]
+    fn test_unified_search_prefixes_root_labels() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let base = std::env::temp_dir().join(format!("opcode-logical-{}", uuid::Uuid::new_v4()));
+        let (web, api) = (base.join("web"), base.join("api"));
+        std::fs::create_dir_all(&web).unwrap();
+        std::fs::create_dir_all(&api).unwrap();
+
+        register_logical_project(&conn, "shop", &[root(&web, "web"), root(&api, "api")]).unwrap();
+        let (web_path, api_path) = (web.to_string_lossy(), api.to_string_lossy());
+        // upsert_chunk marca updated_at al insertar: el último insertado es el más reciente
+        for (project, file) in [
+            (&web_path, "src/app.ts"),
+            (&api_path, "src/db.rs"),
+            (&api_path, "src/main.rs"),
+        ] {
+            chunk()
+                .project(project)
+                .file(file)
+                .content(&format!("// {}", file))
+                .insert(&conn);
+        }
+
+        let all = query_logical_chunks(&conn, "shop", &ChunkQuery::default()).unwrap();
+        let files: Vec<_> = all.iter().filter_map(|c| c.file_path.as_deref()).collect();
+        assert_eq!(files, vec!["api/src/main.rs", "api/src/db.rs", "web/src/app.ts"]);
+        assert!(all.iter().all(|c| c.project_path == "logical://shop"));
+
+        let page = query_logical_chunks(
+            &conn,
+            "shop",
+            &ChunkQuery {
+                limit: Some(1),
+                offset: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(page[0].file_path.as_deref(), Some("api/src/db.rs"));
+
+        let by_file = query_logical_chunks(
+            &conn,
+            "shop",
+            &ChunkQuery {
+                file_path: Some("web/src/app.ts".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(by_file.len(), 1);
+
+        let options = root_options(
+            &ChunkingOptions::default(),
+            &ProjectRoot {
+                chunk_types: Some(vec![ChunkType::Ast]),
+                ignore_patterns: vec!["fixtures/**".to_string()],
+                ..root(&web, "web")
+            },
+        );
+        assert_eq!(options.chunk_types, vec![ChunkType::Ast]);
+        assert!(options.ignore_patterns.contains(&"fixtures/**".to_string()));
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+}
@@ -0,0 +1,321 @@
+use super::lint::{enclosing_entity, file_content, indexed_files, resolve_project_file};
+use super::storage::row_timestamp;
+use super::types::{ChunkType, LspDiagnostic, LspPublishResult, ProjectDiagnostic};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+
+/// Guarda un `textDocument/publishDiagnostics` (los `params` o la notificación JSON-RPC
+/// completa) de un editor o language server. Los diagnósticos reemplazan a los
+/// anteriores del archivo y se asocian al raw source vigente y a la función/clase que
+/// los contiene. Una lista vacía limpia el archivo
+pub fn publish_lsp_diagnostics(
+    conn: &Connection,
+    project_path: &str,
+    payload: &Value,
+) -> Result<LspPublishResult> {
+    let params = payload.get("params").unwrap_or(payload);
+    let uri = params
+        .get("uri")
+        .and_then(|u| u.as_str())
+        .ok_or_else(|| anyhow!("publishDiagnostics payload without uri"))?;
+    let diagnostics = params
+        .get("diagnostics")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| anyhow!("publishDiagnostics payload without diagnostics"))?;
+    let document_version = params.get("version").and_then(|v| v.as_i64());
+
+    let mut result = LspPublishResult {
+        file_path: None,
+        uri: uri.to_string(),
+        stored: 0,
+        replaced: 0,
+    };
+    let known_files = indexed_files(conn, project_path)?;
+    let Some(file_path) = resolve_project_file(project_path, &known_files, &uri_to_path(uri))
+    else {
+        return Ok(result);
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    result.replaced = tx.execute(
+        "DELETE FROM lsp_diagnostics WHERE project_path = ?1 AND file_path = ?2",
+        params![project_path, file_path],
+    )?;
+
+    let chunk_id: Option<i64> = tx
+        .query_row(
+            "SELECT id FROM chunks
+             WHERE project_path = ?1 AND file_path = ?2 AND chunk_type = ?3 AND deleted_at IS NULL
+             ORDER BY updated_at DESC, id DESC LIMIT 1",
+            params![project_path, file_path, ChunkType::RawSource.as_str()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let content = file_content(&tx, project_path, &file_path);
+    let received_at = Utc::now().to_rfc3339();
+
+    for diagnostic in diagnostics {
+        let Some(message) = diagnostic.get("message").and_then(|m| m.as_str()) else {
+            continue;
+        };
+        let position = |edge: &str, field: &str| {
+            diagnostic
+                .pointer(&format!("/range/{}/{}", edge, field))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize
+        };
+        // LSP usa posiciones 0-based; se guardan 1-based como los diagnósticos de lint
+        let line = position("start", "line") + 1;
+        let entity = content.as_deref().and_then(|c| enclosing_entity(c, line));
+        let code = match diagnostic.get("code") {
+            Some(Value::String(code)) => Some(code.clone()),
+            Some(Value::Number(code)) => Some(code.to_string()),
+            _ => None,
+        };
+
+        tx.execute(
+            "INSERT INTO lsp_diagnostics (project_path, file_path, chunk_id, entity_name, line,
+                 column, end_line, end_column, severity, code, source, message,
+                 document_version, received_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                project_path,
+                file_path,
+                chunk_id,
+                entity,
+                line as i64,
+                (position("start", "character") + 1) as i64,
+                (position("end", "line") + 1) as i64,
+                (position("end", "character") + 1) as i64,
+                severity_name(diagnostic.get("severity").and_then(|s| s.as_u64())),
+                code,
+                diagnostic.get("source").and_then(|s| s.as_str()),
+                message,
+                document_version,
+                received_at,
+            ],
+        )?;
+        result.stored += 1;
+    }
+    tx.commit()?;
+
+    result.file_path = Some(file_path);
+    Ok(result)
+}
+
+/// Diagnósticos LSP del proyecto (o de un archivo), por archivo y línea
+pub fn get_lsp_diagnostics(
+    conn: &Connection,
+    project_path: &str,
+    file_path: Option<&str>,
+) -> Result<Vec<LspDiagnostic>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, file_path, chunk_id, entity_name, line, column, end_line,
+                end_column, severity, code, source, message, document_version, received_at
+         FROM lsp_diagnostics
+         WHERE project_path = ?1 AND (?2 IS NULL OR file_path = ?2)
+         ORDER BY file_path, line, column",
+    )?;
+    let diagnostics = stmt
+        .query_map(params![project_path, file_path], |row| {
+            Ok(LspDiagnostic {
+                id: Some(row.get(0)?),
+                project_path: row.get(1)?,
+                file_path: row.get(2)?,
+                chunk_id: row.get(3)?,
+                entity_name: row.get(4)?,
+                line: row.get::<_, i64>(5)? as usize,
+                column: row.get::<_, i64>(6)? as usize,
+                end_line: row.get::<_, i64>(7)? as usize,
+                end_column: row.get::<_, i64>(8)? as usize,
+                severity: row.get(9)?,
+                code: row.get(10)?,
+                source: row.get(11)?,
+                message: row.get(12)?,
+                document_version: row.get(13)?,
+                received_at: row_timestamp(row, 14)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(diagnostics)
+}
+
+/// Descarta los diagnósticos LSP del proyecto (o de un archivo), ej. al cerrar el editor
+pub fn clear_lsp_diagnostics(
+    conn: &Connection,
+    project_path: &str,
+    file_path: Option<&str>,
+) -> Result<usize> {
+    let count = conn.execute(
+        "DELETE FROM lsp_diagnostics WHERE project_path = ?1 AND (?2 IS NULL OR file_path = ?2)",
+        params![project_path, file_path],
+    )?;
+    Ok(count)
+}
+
+/// Errores abiertos de error_logs y diagnósticos LSP del proyecto en una sola lista,
+/// los más recientes primero
+pub fn get_open_diagnostics(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Vec<ProjectDiagnostic>> {
+    let mut stmt = conn.prepare(
+        "SELECT origin, id, file_path, entity_name, line, severity, kind, message, seen_at
+         FROM open_diagnostics WHERE project_path = ?1
+         ORDER BY seen_at DESC, origin, id",
+    )?;
+    let diagnostics = stmt
+        .query_map(params![project_path], |row| {
+            Ok(ProjectDiagnostic {
+                origin: row.get(0)?,
+                id: row.get(1)?,
+                file_path: row.get(2)?,
+                entity_name: row.get(3)?,
+                line: row.get::<_, Option<i64>>(4)?.map(|l| l as usize),
+                severity: row.get(5)?,
+                kind: row.get(6)?,
+                message: row.get(7)?,
+                seen_at: row_timestamp(row, 8)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(diagnostics)
+}
+
+/// `DiagnosticSeverity` de LSP; sin severidad el cliente decide, se toma como error
+fn severity_name(severity: Option<u64>) -> &'static str {
+    match severity {
+        Some(2) => "warning",
+        Some(3) => "information",
+        Some(4) => "hint",
+        _ => "error",
+    }
+}
+
+/// Ruta de una URI `file://` (con escapes `%XX` decodificados). Otras URIs quedan igual
+fn uri_to_path(uri: &str) -> String {
+    let Some(path) = uri.strip_prefix("file://") else {
+        return uri.to_string();
+    };
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let hex = if byte == b'%' {
+            tail.get(..2)
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+        } else {
+            None
+        };
+        match hex {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    let path = String::from_utf8_lossy(&bytes).to_string();
+    // file:///C:/proyecto -> C:/proyecto
+    match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => path[1..].to_string(),
+        _ => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::log_diagnostic;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+    use serde_json::json;
+
+    #[test]
+    fn test_uri_to_path() {
+        assert_eq!(
+            uri_to_path("file:///home/me/my%20app/src/a.rs"),
+            "/home/me/my app/src/a.rs"
+        );
+        assert_eq!(uri_to_path("file:///C:/app/src/a.rs"), "C:/app/src/a.rs");
+        assert_eq!(uri_to_path("file:///bad%zz"), "/bad%zz");
+    }
+
+    #[test]
+    fn test_publish_and_query_lsp_diagnostics() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let content = "fn main() {\n    let x = 1;\n}\n";
+        chunk().file("src/main.rs").content(content).insert(&conn);
+
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": "file:///p/src/main.rs",
+                "version": 3,
+                "diagnostics": [
+                    {
+                        "range": {"start": {"line": 1, "character": 8}, "end": {"line": 1, "character": 9}},
+                        "severity": 2,
+                        "code": "unused_variables",
+                        "source": "rust-analyzer",
+                        "message": "unused variable: `x`"
+                    },
+                    {
+                        "range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 2}},
+                        "code": 308,
+                        "message": "mismatched types"
+                    }
+                ]
+            }
+        });
+        let result = publish_lsp_diagnostics(&conn, "/p", &notification).unwrap();
+        assert_eq!(result.file_path.as_deref(), Some("src/main.rs"));
+        assert_eq!((result.stored, result.replaced), (2, 0));
+
+        let diagnostics = get_lsp_diagnostics(&conn, "/p", Some("src/main.rs")).unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (1, 1));
+        assert_eq!(diagnostics[0].severity, "error");
+        assert_eq!(diagnostics[0].code.as_deref(), Some("308"));
+        assert_eq!(diagnostics[1].severity, "warning");
+        assert_eq!(diagnostics[1].entity_name.as_deref(), Some("main"));
+        assert_eq!(diagnostics[1].document_version, Some(3));
+        assert!(diagnostics[1].chunk_id.is_some());
+
+        // Junto a los errores de error_logs
+        log_diagnostic(
+            &conn,
+            "/p",
+            "lint:clippy:x",
+            "warning",
+            "m",
+            Some("src/main.rs"),
+            None,
+        )
+        .unwrap();
+        let open = get_open_diagnostics(&conn, "/p").unwrap();
+        assert_eq!(open.len(), 3);
+        assert_eq!(open.iter().filter(|d| d.origin == "lsp").count(), 2);
+        assert!(open.iter().any(|d| d.kind == "rust-analyzer"));
+
+        // Una nueva publicación reemplaza las anteriores; una lista vacía limpia el archivo
+        let empty = json!({"uri": "file:///p/src/main.rs", "diagnostics": []});
+        let result = publish_lsp_diagnostics(&conn, "/p", &empty).unwrap();
+        assert_eq!((result.stored, result.replaced), (0, 2));
+        assert!(get_lsp_diagnostics(&conn, "/p", None).unwrap().is_empty());
+
+        let outside = json!({"uri": "file:///elsewhere/x.rs", "diagnostics": []});
+        assert!(publish_lsp_diagnostics(&conn, "/p", &outside)
+            .unwrap()
+            .file_path
+            .is_none());
+        assert!(publish_lsp_diagnostics(&conn, "/p", &json!({})).is_err());
+    }
+}
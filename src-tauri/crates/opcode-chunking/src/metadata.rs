@@ -0,0 +1,365 @@
+use super::resolver::parse_manifest_package_name;
+use super::storage::{calculate_content_hash, upsert_chunk};
+use super::types::{Chunk, ChunkType, DeclaredDependency, DependencyScope, ManifestMetadata};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::Connection;
+use serde_json::Value;
+use std::path::Path;
+
+/// Genera chunks de metadata del proyecto
+pub fn generate_metadata_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+) -> Result<usize> {
+    if !is_metadata_file(file_path) {
+        return Ok(0);
+    }
+
+    let content_hash = calculate_content_hash(content);
+    let filename = Path::new(file_path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    let metadata = match parse_manifest_dependencies(filename, content) {
+        Some(manifest) => Some(serde_json::to_string(&manifest)?),
+        None => None,
+    };
+
+    let chunk = Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::ProjectMetadata,
+        file_path: Some(file_path.to_string()),
+        entity_name: None,
+        content: content.to_string(),
+        content_hash,
+        metadata,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    upsert_chunk(conn, &chunk, None)?;
+    Ok(1)
+}
+
+/// Detecta si un archivo es de metadata del proyecto
+fn is_metadata_file(file_path: &str) -> bool {
+    let path = Path::new(file_path);
+    let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+
+    matches!(
+        filename,
+        "package.json"
+            | "package-lock.json"
+            | "yarn.lock"
+            | "pnpm-lock.yaml"
+            | "Cargo.toml"
+            | "Cargo.lock"
+            | "pyproject.toml"
+            | "requirements.txt"
+            | "Pipfile"
+            | "Pipfile.lock"
+            | "go.mod"
+            | "go.sum"
+            | "build.gradle"
+            | "pom.xml"
+            | "composer.json"
+            | "composer.lock"
+            | "Gemfile"
+            | "Gemfile.lock"
+    )
+}
+
+/// Extrae las dependencias declaradas en un manifiesto o las versiones fijadas en un lockfile.
+/// Retorna None para archivos que no declaran dependencias
+pub fn parse_manifest_dependencies(file_name: &str, content: &str) -> Option<ManifestMetadata> {
+    let dependencies = match file_name {
+        "package.json" => parse_package_json(content)?,
+        "Cargo.toml" => parse_cargo_toml(content),
+        "pyproject.toml" => parse_pyproject(content),
+        "requirements.txt" => content
+            .lines()
+            .filter_map(parse_requirement)
+            .map(|(name, version)| DeclaredDependency {
+                name,
+                scope: DependencyScope::Normal,
+                version,
+            })
+            .collect(),
+        "Cargo.lock" => parse_cargo_lock(content),
+        "package-lock.json" => parse_package_lock(content)?,
+        _ => return None,
+    };
+
+    Some(ManifestMetadata {
+        manifest_type: file_name.to_string(),
+        package_name: parse_manifest_package_name(file_name, content),
+        dependencies,
+    })
+}
+
+/// Dependencias de package.json por sección
+fn parse_package_json(content: &str) -> Option<Vec<DeclaredDependency>> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let sections = [
+        ("dependencies", DependencyScope::Normal),
+        ("devDependencies", DependencyScope::Dev),
+        ("peerDependencies", DependencyScope::Optional),
+        ("optionalDependencies", DependencyScope::Optional),
+    ];
+
+    let mut deps = Vec::new();
+    for (section, scope) in sections {
+        if let Some(map) = json.get(section).and_then(|v| v.as_object()) {
+            for (name, version) in map {
+                deps.push(DeclaredDependency {
+                    name: name.clone(),
+                    scope: scope.clone(),
+                    version: version.as_str().map(|v| v.to_string()),
+                });
+            }
+        }
+    }
+    Some(deps)
+}
+
+/// Dependencias de Cargo.toml, incluyendo tablas `[dependencies.foo]` y `[target.*.dependencies]`
+fn parse_cargo_toml(content: &str) -> Vec<DeclaredDependency> {
+    let key_re = Regex::new(r"^([A-Za-z0-9_-]+)\s*(=|\.)").unwrap();
+    let mut deps = Vec::new();
+    let mut scope: Option<DependencyScope> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            let header = trimmed.trim_matches(|c| c == '[' || c == ']');
+            scope = None;
+            if header.starts_with("workspace.") {
+                continue;
+            }
+            for (section, section_scope) in [
+                ("dependencies", DependencyScope::Normal),
+                ("dev-dependencies", DependencyScope::Dev),
+                ("build-dependencies", DependencyScope::Build),
+            ] {
+                let table_prefix = format!("{}.", section);
+                if header == section || header.ends_with(&format!(".{}", section)) {
+                    scope = Some(section_scope);
+                    break;
+                }
+                if let Some(name) = header.strip_prefix(&table_prefix) {
+                    deps.push(DeclaredDependency {
+                        name: name.to_string(),
+                        scope: section_scope,
+                        version: None,
+                    });
+                    break;
+                }
+            }
+            continue;
+        }
+
+        if let Some(scope) = &scope {
+            if let Some(cap) = key_re.captures(trimmed) {
+                deps.push(DeclaredDependency {
+                    name: cap[1].to_string(),
+                    scope: scope.clone(),
+                    version: toml_version(trimmed),
+                });
+            }
+        }
+    }
+
+    deps
+}
+
+/// Dependencias de pyproject.toml (PEP 621 y Poetry)
+fn parse_pyproject(content: &str) -> Vec<DeclaredDependency> {
+    let key_re = Regex::new(r"^([A-Za-z0-9_.-]+)\s*=").unwrap();
+    let quoted_re = Regex::new(r#"["']([^"']+)["']"#).unwrap();
+    let mut deps = Vec::new();
+    let mut section = String::new();
+    let mut array_scope: Option<DependencyScope> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        // Dentro de un array multilínea de requirements
+        if let Some(scope) = &array_scope {
+            for cap in quoted_re.captures_iter(trimmed) {
+                if let Some((name, version)) = parse_requirement(&cap[1]) {
+                    deps.push(DeclaredDependency {
+                        name,
+                        scope: scope.clone(),
+                        version,
+                    });
+                }
+            }
+            if trimmed.contains(']') {
+                array_scope = None;
+            }
+            continue;
+        }
+
+        if trimmed.starts_with('[') {
+            section = trimmed.trim_matches(|c| c == '[' || c == ']').to_string();
+            continue;
+        }
+
+        let Some(cap) = key_re.captures(trimmed) else {
+            continue;
+        };
+        let key = &cap[1];
+
+        let scope = match section.as_str() {
+            "project" if key == "dependencies" => Some(DependencyScope::Normal),
+            "project.optional-dependencies" => Some(DependencyScope::Optional),
+            "tool.poetry.dependencies" | "tool.poetry.dev-dependencies" => {
+                if key != "python" {
+                    deps.push(DeclaredDependency {
+                        name: key.to_string(),
+                        scope: if section.ends_with("dev-dependencies") {
+                            DependencyScope::Dev
+                        } else {
+                            DependencyScope::Normal
+                        },
+                        version: toml_version(trimmed),
+                    });
+                }
+                None
+            }
+            s if s.starts_with("tool.poetry.group.") && s.ends_with(".dependencies") => {
+                deps.push(DeclaredDependency {
+                    name: key.to_string(),
+                    scope: DependencyScope::Dev,
+                    version: toml_version(trimmed),
+                });
+                None
+            }
+            _ => None,
+        };
+
+        // Arrays de requirements: `dependencies = ["requests>=2", ...]`
+        if let Some(scope) = scope {
+            let value = trimmed.split_once('=').map(|(_, v)| v).unwrap_or("");
+            if !value.contains('[') {
+                continue;
+            }
+            for cap in quoted_re.captures_iter(value) {
+                if let Some((name, version)) = parse_requirement(&cap[1]) {
+                    deps.push(DeclaredDependency {
+                        name,
+                        scope: scope.clone(),
+                        version,
+                    });
+                }
+            }
+            if !value.contains(']') {
+                array_scope = Some(scope);
+            }
+        }
+    }
+
+    deps
+}
+
+/// Versiones exactas de Cargo.lock (`[[package]]` con `name` y `version`)
+fn parse_cargo_lock(content: &str) -> Vec<DeclaredDependency> {
+    let field_re = Regex::new(r#"^(name|version)\s*=\s*"([^"]+)""#).unwrap();
+    let mut deps = Vec::new();
+    let mut name: Option<String> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed == "[[package]]" {
+            name = None;
+            continue;
+        }
+        let Some(cap) = field_re.captures(trimmed) else {
+            continue;
+        };
+        if &cap[1] == "name" {
+            name = Some(cap[2].to_string());
+        } else if let Some(package) = name.take() {
+            deps.push(DeclaredDependency {
+                name: package,
+                scope: DependencyScope::Normal,
+                version: Some(cap[2].to_string()),
+            });
+        }
+    }
+
+    deps
+}
+
+/// Versiones exactas de package-lock.json (formato v2/v3 `packages` y v1 `dependencies`)
+fn parse_package_lock(content: &str) -> Option<Vec<DeclaredDependency>> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let mut deps = Vec::new();
+
+    if let Some(packages) = json.get("packages").and_then(|v| v.as_object()) {
+        for (key, entry) in packages {
+            // "node_modules/a/node_modules/@scope/b" -> "@scope/b"
+            let Some((_, name)) = key.rsplit_once("node_modules/") else {
+                continue;
+            };
+            deps.push(DeclaredDependency {
+                name: name.to_string(),
+                scope: lock_scope(entry),
+                version: entry
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string()),
+            });
+        }
+    } else if let Some(dependencies) = json.get("dependencies").and_then(|v| v.as_object()) {
+        for (name, entry) in dependencies {
+            deps.push(DeclaredDependency {
+                name: name.clone(),
+                scope: lock_scope(entry),
+                version: entry
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string()),
+            });
+        }
+    }
+
+    Some(deps)
+}
+
+fn lock_scope(entry: &Value) -> DependencyScope {
+    if entry.get("dev").and_then(|v| v.as_bool()).unwrap_or(false) {
+        DependencyScope::Dev
+    } else {
+        DependencyScope::Normal
+    }
+}
+
+/// Versión de una entrada TOML: `foo = "1.2"` o `foo = { version = "1.2", ... }`
+fn toml_version(line: &str) -> Option<String> {
+    let value = line.split_once('=')?.1.trim();
+    let version_re = Regex::new(r#"version\s*=\s*["']([^"']+)["']"#).unwrap();
+    if let Some(cap) = version_re.captures(value) {
+        return Some(cap[1].to_string());
+    }
+    let quoted_re = Regex::new(r#"^["']([^"']+)["']"#).unwrap();
+    quoted_re.captures(value).map(|cap| cap[1].to_string())
+}
+
+/// Nombre y especificador de versión de una línea de requirement (`requests>=2.0 ; python_version...`)
+fn parse_requirement(line: &str) -> Option<(String, Option<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+        return None;
+    }
+    let requirement_re =
+        Regex::new(r"^([A-Za-z0-9][A-Za-z0-9._-]*)\s*(?:\[[^\]]*\])?\s*([=<>!~][^;#,]*)?").unwrap();
+    let cap = requirement_re.captures(line)?;
+    let version = cap.get(2).map(|v| v.as_str().trim().to_string());
+    Some((cap[1].to_string(), version))
+}
@@ -0,0 +1,131 @@
+use super::ast::AstParseError;
+use super::storage::{
+    calculate_content_hash, delete_parse_failures, get_parse_failure, record_parse_failure,
+};
+use anyhow::Result;
+use rusqlite::Connection;
+
+/// True si el archivo está en la skip list: falló `max_attempts` veces con este mismo
+/// contenido. Un archivo modificado vuelve a intentarse
+pub fn should_skip(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+    max_attempts: usize,
+) -> Result<bool> {
+    let Some(failure) = get_parse_failure(conn, project_path, file_path)? else {
+        return Ok(false);
+    };
+    Ok(failure.attempts >= max_attempts && failure.content_hash == calculate_content_hash(content))
+}
+
+/// Actualiza la skip list con el resultado del AST de un archivo: registra las fallas
+/// de parseo y olvida el archivo cuando se parsea bien. Otros errores no la tocan
+pub fn record_outcome<T>(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+    outcome: &Result<T>,
+) -> Result<()> {
+    match outcome {
+        Ok(_) => {
+            delete_parse_failures(conn, project_path, Some(file_path))?;
+        }
+        Err(e) => {
+            if let Some(parse_error) = e.downcast_ref::<AstParseError>() {
+                let failure = record_parse_failure(
+                    conn,
+                    project_path,
+                    file_path,
+                    &calculate_content_hash(content),
+                    &parse_error.0,
+                )?;
+                log::debug!(
+                    "Parse failure #{} for {}: {}",
+                    failure.attempts,
+                    file_path,
+                    failure.reason
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Limpia la skip list (todo el proyecto si `file_paths` está vacío) para que los
+/// archivos corregidos se vuelvan a parsear en la próxima indexación
+pub fn clear_parse_failures(
+    conn: &Connection,
+    project_path: &str,
+    file_paths: &[String],
+) -> Result<usize> {
+    if file_paths.is_empty() {
+        return delete_parse_failures(conn, project_path, None);
+    }
+    let mut cleared = 0;
+    for file_path in file_paths {
+        cleared += delete_parse_failures(conn, project_path, Some(file_path))?;
+    }
+    Ok(cleared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{get_parse_failures, init_chunk_database};
+    use anyhow::anyhow;
+
+    fn parse_error() -> Result<usize> {
+        Err(AstParseError("timed out or cancelled".to_string()).into())
+    }
+
+    #[test]
+    fn test_file_is_skipped_after_max_attempts_until_it_changes() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let content = "const x = ;;;";
+
+        for _ in 0..2 {
+            record_outcome(&conn, "/p", "min.js", content, &parse_error()).unwrap();
+        }
+        assert!(!should_skip(&conn, "/p", "min.js", content, 3).unwrap());
+        record_outcome(&conn, "/p", "min.js", content, &parse_error()).unwrap();
+        assert!(should_skip(&conn, "/p", "min.js", content, 3).unwrap());
+
+        // El contenido cambió: se reintenta y la cuenta vuelve a empezar
+        assert!(!should_skip(&conn, "/p", "min.js", "const x = 1;", 3).unwrap());
+        record_outcome(&conn, "/p", "min.js", "const x = 1;", &parse_error()).unwrap();
+        assert_eq!(get_parse_failures(&conn, "/p").unwrap()[0].attempts, 1);
+
+        // Otros errores no entran en la skip list; un parseo exitoso la limpia
+        record_outcome(
+            &conn,
+            "/p",
+            "a.txt",
+            "",
+            &Err::<usize, _>(anyhow!("Unsupported language")),
+        )
+        .unwrap();
+        assert_eq!(get_parse_failures(&conn, "/p").unwrap().len(), 1);
+        record_outcome(&conn, "/p", "min.js", "const x = 1;", &Ok(1)).unwrap();
+        assert!(get_parse_failures(&conn, "/p").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clear_parse_failures() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        for file in ["a.js", "b.js", "c.js"] {
+            record_outcome(&conn, "/p", file, "x", &parse_error()).unwrap();
+        }
+
+        assert_eq!(
+            clear_parse_failures(&conn, "/p", &["a.js".to_string()]).unwrap(),
+            1
+        );
+        assert_eq!(clear_parse_failures(&conn, "/p", &[]).unwrap(), 2);
+        assert!(get_parse_failures(&conn, "/p").unwrap().is_empty());
+    }
+}
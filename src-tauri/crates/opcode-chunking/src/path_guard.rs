@@ -0,0 +1,395 @@
+use super::logical_projects::LOGICAL_PROJECT_SCHEME;
+use super::storage::{get_logical_project, get_logical_projects, get_remote_repositories};
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use std::collections::BTreeSet;
+use std::io::{BufRead, BufReader};
+use std::path::{Component, Path, PathBuf};
+
+/// Comprueba que un `project_path` recibido desde el frontend sea un proyecto
+/// registrado antes de indexarlo, leerlo o crearle snapshots:
+/// - `logical://{nombre}` debe ser un proyecto lógico registrado
+/// - el resto debe ser una ruta absoluta sin `..`; se canonicaliza (si existe) y debe
+///   ser exactamente la ruta de un proyecto de `projects_dir` (`~/.claude/projects`),
+///   la raíz de un proyecto lógico o un repositorio remoto clonado por la app
+pub fn validate_project_path(
+    conn: &Connection,
+    project_path: &str,
+    projects_dir: Option<&Path>,
+) -> Result<()> {
+    if let Some(name) = project_path.strip_prefix(LOGICAL_PROJECT_SCHEME) {
+        if get_logical_project(conn, name)?.is_none() {
+            bail!("Logical project not registered: {}", name);
+        }
+        return Ok(());
+    }
+
+    let candidates = path_candidates(project_path)?;
+    let matches = |known_path: &str| {
+        path_candidates(known_path)
+            .is_ok_and(|known| known.iter().any(|path| candidates.contains(path)))
+    };
+
+    if let Some(projects_dir) = projects_dir {
+        let registered = candidates
+            .iter()
+            .flat_map(|path| project_dir_names(path))
+            .map(|name| projects_dir.join(name))
+            .filter(|dir| dir.is_dir())
+            .flat_map(|dir| session_project_paths(&dir));
+        for known_path in registered {
+            if matches(&known_path) {
+                return Ok(());
+            }
+        }
+    }
+
+    let known = get_logical_projects(conn)?
+        .into_iter()
+        .flat_map(|project| project.roots.into_iter().map(|root| root.path))
+        .chain(
+            get_remote_repositories(conn)?
+                .into_iter()
+                .map(|repo| repo.project_path),
+        );
+    for known_path in known {
+        if matches(&known_path) {
+            return Ok(());
+        }
+    }
+
+    bail!("Project path is not a registered project: {}", project_path)
+}
+
+/// Normaliza un subdirectorio relativo a la raíz del proyecto (`services/api`)
+/// para la indexación parcial; rechaza rutas absolutas y con `..`. Una ruta vacía
+/// (o `.`) representa el proyecto completo y devuelve None
+pub fn normalize_subpath(subpath: &str) -> Result<Option<String>> {
+    let path = Path::new(subpath.trim());
+    if path.is_absolute() || path.has_root() {
+        bail!("Subpath must be relative to the project root: {}", subpath);
+    }
+    if path.components().any(|c| c == Component::ParentDir) {
+        bail!("Subpath must not contain '..': {}", subpath);
+    }
+    let parts: Vec<String> = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    Ok((!parts.is_empty()).then(|| parts.join("/")))
+}
+
+/// Indica si un archivo (ruta relativa normalizada) está dentro de `subpath`
+pub fn is_within_subpath(file_path: &str, subpath: &str) -> bool {
+    file_path
+        .strip_prefix(subpath)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Archivo que escribe una exportación (índice, perfil, grafo). Debe ser una ruta
+/// absoluta sin `..` cuyo directorio (con symlinks resueltos) esté dentro de
+/// `exports_dir` o del proyecto exportado. Solo se reemplazan archivos de
+/// `exports_dir`: en el proyecto el archivo no debe existir, y nunca se escribe a
+/// través de un symlink
+pub fn validate_export_path(
+    output_path: &str,
+    exports_dir: &Path,
+    project_path: &str,
+) -> Result<PathBuf> {
+    let path = absolute_file_path(output_path)?;
+    let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+        bail!("Output path must name a file: {}", output_path);
+    };
+    let parent = parent
+        .canonicalize()
+        .with_context(|| format!("Output directory does not exist: {}", parent.display()))?;
+    let target = parent.join(file_name);
+
+    let in_exports = exports_dir
+        .canonicalize()
+        .is_ok_and(|dir| parent.starts_with(dir));
+    if !in_exports && !is_within_project(&parent, project_path) {
+        bail!(
+            "Output path must be inside the exports directory ({}) or the project: {}",
+            exports_dir.display(),
+            output_path
+        );
+    }
+    match std::fs::symlink_metadata(&target) {
+        Ok(meta) if !meta.is_file() => {
+            bail!("Output path is not a regular file: {}", target.display())
+        }
+        Ok(_) if !in_exports => bail!(
+            "Refusing to overwrite an existing file outside the exports directory: {}",
+            target.display()
+        ),
+        _ => Ok(target),
+    }
+}
+
+/// Archivo que lee una importación: debe existir y estar (con symlinks resueltos)
+/// dentro de `exports_dir` o del proyecto destino
+pub fn validate_import_path(
+    input_path: &str,
+    exports_dir: &Path,
+    project_path: &str,
+) -> Result<PathBuf> {
+    let path = absolute_file_path(input_path)?
+        .canonicalize()
+        .with_context(|| format!("Input file does not exist: {}", input_path))?;
+    if !path.is_file() {
+        bail!("Input path is not a file: {}", input_path);
+    }
+    let in_exports = exports_dir
+        .canonicalize()
+        .is_ok_and(|dir| path.starts_with(dir));
+    if !in_exports && !is_within_project(&path, project_path) {
+        bail!(
+            "Input path must be inside the exports directory ({}) or the project: {}",
+            exports_dir.display(),
+            input_path
+        );
+    }
+    Ok(path)
+}
+
+fn absolute_file_path(file_path: &str) -> Result<PathBuf> {
+    let path = Path::new(file_path);
+    if file_path.trim().is_empty() || !path.is_absolute() {
+        bail!("File path must be absolute: {:?}", file_path);
+    }
+    if path.components().any(|c| c == Component::ParentDir) {
+        bail!("File path must not contain '..': {}", file_path);
+    }
+    Ok(path.to_path_buf())
+}
+
+/// Indica si una ruta canónica está dentro del directorio del proyecto (los
+/// proyectos lógicos no tienen directorio propio)
+fn is_within_project(path: &Path, project_path: &str) -> bool {
+    !project_path.starts_with(LOGICAL_PROJECT_SCHEME)
+        && Path::new(project_path)
+            .canonicalize()
+            .is_ok_and(|dir| path.starts_with(dir))
+}
+
+/// La ruta tal como llega y, si existe, su forma canónica (symlinks resueltos)
+fn path_candidates(project_path: &str) -> Result<Vec<PathBuf>> {
+    let path = Path::new(project_path);
+    if project_path.trim().is_empty() || !path.is_absolute() {
+        bail!("Project path must be absolute: {:?}", project_path);
+    }
+    if path.components().any(|c| c == Component::ParentDir) {
+        bail!("Project path must not contain '..': {}", project_path);
+    }
+
+    let mut candidates = vec![path.components().collect::<PathBuf>()];
+    if let Ok(canonical) = path.canonicalize() {
+        if !candidates.contains(&canonical) {
+            candidates.push(canonical);
+        }
+    }
+    Ok(candidates)
+}
+
+/// Nombres posibles del directorio del proyecto en `~/.claude/projects`: el de la app
+/// (`/` -> `-`) y el de Claude Code (todo lo que no es alfanumérico -> `-`).
+/// La codificación es ambigua (`/a/b-c` y `/a/b/c` dan el mismo nombre): solo sirve
+/// para encontrar el directorio, la ruta se compara con `session_project_paths`
+fn project_dir_names(path: &Path) -> [String; 2] {
+    let path = path.to_string_lossy();
+    [
+        path.replace('/', "-"),
+        path.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect(),
+    ]
+}
+
+/// Rutas de proyecto (`cwd`) registradas en las sesiones JSONL de un directorio de
+/// `~/.claude/projects`. Un directorio sin sesiones no autoriza ninguna ruta
+fn session_project_paths(dir: &Path) -> BTreeSet<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return BTreeSet::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|path| std::fs::File::open(path).ok())
+        .flat_map(|file| {
+            // Algunas sesiones tienen `cwd` nulo en las primeras líneas
+            BufReader::new(file)
+                .lines()
+                .take(10)
+                .map_while(|line| line.ok())
+                .filter_map(|line| {
+                    let json: serde_json::Value = serde_json::from_str(&line).ok()?;
+                    let cwd = json.get("cwd")?.as_str()?;
+                    (!cwd.is_empty()).then(|| cwd.to_string())
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_projects::register_logical_project;
+    use crate::storage::init_chunk_database;
+    use crate::types::ProjectRoot;
+
+    #[test]
+    fn test_only_registered_projects_are_allowed() {
+        let base = std::env::temp_dir().join(format!("opcode-guard-{}", uuid::Uuid::new_v4()));
+        let projects_dir = base.join("claude-projects");
+        let project = base.join("my.app");
+        let other = base.join("other");
+        std::fs::create_dir_all(&projects_dir).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        let project = project.canonicalize().unwrap();
+        let other = other.canonicalize().unwrap();
+        // Directorio con la codificación de Claude Code (`.` -> `-`)
+        register_session(
+            &projects_dir.join(&project_dir_names(&project)[1]),
+            &project,
+        );
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let check =
+            |path: &str| validate_project_path(&conn, path, Some(projects_dir.as_path())).is_ok();
+
+        assert!(check(project.to_str().unwrap()));
+        assert!(!check(other.to_str().unwrap()));
+        assert!(!check("/"));
+        assert!(!check("relative/path"));
+        assert!(!check(&format!("{}/../other", project.to_str().unwrap())));
+        assert!(!check("logical://app"));
+
+        // Las raíces de un proyecto lógico registrado quedan permitidas
+        register_logical_project(
+            &conn,
+            "app",
+            &[ProjectRoot {
+                path: other.to_string_lossy().to_string(),
+                label: "other".to_string(),
+                chunk_types: None,
+                ignore_patterns: Vec::new(),
+                snapshots_enabled: true,
+            }],
+        )
+        .unwrap();
+        assert!(check("logical://app"));
+        assert!(check(other.to_str().unwrap()));
+        assert!(validate_project_path(&conn, project.to_str().unwrap(), None).is_err());
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    fn register_session(dir: &Path, project: &Path) {
+        std::fs::create_dir_all(dir).unwrap();
+        let session = serde_json::json!({ "cwd": project.to_string_lossy() });
+        std::fs::write(
+            dir.join("session.jsonl"),
+            format!("{{\"type\":\"summary\"}}\n{}\n", session),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_encoded_name_collision_is_not_allowed() {
+        let base = std::env::temp_dir().join(format!("opcode-guard-{}", uuid::Uuid::new_v4()));
+        let projects_dir = base.join("claude-projects");
+        std::fs::create_dir_all(base.join("a/b-c")).unwrap();
+        std::fs::create_dir_all(base.join("a/b/c")).unwrap();
+        let registered = base.join("a/b-c").canonicalize().unwrap();
+        let colliding = base.join("a/b/c").canonicalize().unwrap();
+        let dir = projects_dir.join(&project_dir_names(&registered)[0]);
+        assert_eq!(dir, projects_dir.join(&project_dir_names(&colliding)[0]));
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let check =
+            |path: &Path| validate_project_path(&conn, path.to_str().unwrap(), Some(&projects_dir));
+
+        // Sin sesiones el nombre del directorio no basta
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(check(&registered).is_err());
+
+        register_session(&dir, &registered);
+        assert!(check(&registered).is_ok());
+        assert!(check(&colliding).is_err());
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn test_export_and_import_paths_stay_in_exports_or_project() {
+        let base = std::env::temp_dir().join(format!("opcode-guard-{}", uuid::Uuid::new_v4()));
+        let exports = base.join("app/exports");
+        let project = base.join("project");
+        let outside = base.join("outside");
+        for dir in [&exports, &project, &outside] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(exports.join("index.json"), "{}").unwrap();
+        std::fs::write(project.join("main.rs"), "fn main() {}").unwrap();
+        std::fs::write(outside.join("index.json"), "{}").unwrap();
+        let project_path = project.to_str().unwrap();
+        let export =
+            |path: &Path| validate_export_path(path.to_str().unwrap(), &exports, project_path);
+        let import =
+            |path: &Path| validate_import_path(path.to_str().unwrap(), &exports, project_path);
+
+        // En exports se puede reemplazar; en el proyecto solo crear archivos nuevos
+        assert!(export(&exports.join("index.json")).is_ok());
+        assert!(export(&project.join("opcode-index.json")).is_ok());
+        assert!(export(&project.join("main.rs")).is_err());
+        assert!(export(&outside.join("new.json")).is_err());
+        assert!(export(&outside.join("index.json")).is_err());
+        assert!(export(&exports.join("../../outside/new.json")).is_err());
+        assert!(export(Path::new("relative.json")).is_err());
+        assert!(export(&project.join("missing/new.json")).is_err());
+        assert!(validate_export_path(
+            exports.join("graph.dot").to_str().unwrap(),
+            &exports,
+            "logical://app"
+        )
+        .is_ok());
+
+        assert!(import(&exports.join("index.json")).is_ok());
+        assert!(import(&project.join("main.rs")).is_ok());
+        assert!(import(&outside.join("index.json")).is_err());
+        assert!(import(&exports.join("missing.json")).is_err());
+
+        #[cfg(unix)]
+        {
+            // Un symlink dentro de exports no sirve para escribir ni leer fuera
+            let link = exports.join("link.json");
+            std::os::unix::fs::symlink(outside.join("index.json"), &link).unwrap();
+            assert!(export(&link).is_err());
+            assert!(import(&link).is_err());
+        }
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn test_normalize_subpath() {
+        assert_eq!(
+            normalize_subpath("./services/api/").unwrap().as_deref(),
+            Some("services/api")
+        );
+        assert_eq!(normalize_subpath(" . ").unwrap(), None);
+        assert!(normalize_subpath("/etc").is_err());
+        assert!(normalize_subpath("services/../..").is_err());
+        assert!(is_within_subpath("services/api/main.rs", "services/api"));
+        assert!(!is_within_subpath("services/api-v2/x.rs", "services/api"));
+    }
+}
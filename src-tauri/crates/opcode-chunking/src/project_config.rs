@@ -0,0 +1,248 @@
+use super::types::{
+    AstDetail, ChunkType, ChunkingOptions, ErrorBudget, IndexingError, IndexingErrorKind,
+    SecretRedactionRules, SnapshotPolicy,
+};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Archivo de configuración versionado en la raíz del proyecto
+pub const PROJECT_CONFIG_FILE: &str = "opcode.toml";
+
+/// Configuración compartida del proyecto (`opcode.toml`).
+/// Los campos ausentes conservan el valor de la app
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub indexing: IndexingConfig,
+    pub languages: HashMap<String, String>, // Extensión -> lenguaje
+    pub snapshots: Option<SnapshotPolicy>,
+    pub secrets: Option<SecretRedactionRules>,
+    pub errors: Option<ErrorBudget>,
+}
+
+/// Sección `[indexing]`
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexingConfig {
+    pub chunk_types: Option<Vec<ChunkType>>,
+    pub ignore: Vec<String>, // Se suman a los patrones de la app
+    pub max_commits: Option<usize>,
+    pub all_branches: Option<bool>,
+    pub write_git_notes: Option<bool>,
+    pub max_parse_attempts: Option<usize>,
+    pub tombstone_grace_days: Option<u32>,
+    pub ast_detail: Option<AstDetail>,
+    pub ast_max_tokens: Option<usize>,
+    pub extensions: Vec<String>, // Se suman a las extensiones de código de la app
+    pub exclude_extensions: Vec<String>,
+}
+
+impl ProjectConfig {
+    /// Aplica la configuración del proyecto sobre las opciones de la app
+    pub fn apply(&self, options: &ChunkingOptions) -> ChunkingOptions {
+        let mut merged = options.clone();
+
+        if let Some(chunk_types) = &self.indexing.chunk_types {
+            merged.chunk_types = chunk_types.clone();
+        }
+        for pattern in &self.indexing.ignore {
+            if !merged.ignore_patterns.contains(pattern) {
+                merged.ignore_patterns.push(pattern.clone());
+            }
+        }
+        if self.indexing.max_commits.is_some() {
+            merged.max_commits = self.indexing.max_commits;
+        }
+        if let Some(all_branches) = self.indexing.all_branches {
+            merged.all_branches = all_branches;
+        }
+        if let Some(write_git_notes) = self.indexing.write_git_notes {
+            merged.write_git_notes = write_git_notes;
+        }
+        if let Some(max_parse_attempts) = self.indexing.max_parse_attempts {
+            merged.max_parse_attempts = max_parse_attempts;
+        }
+        if let Some(grace_days) = self.indexing.tombstone_grace_days {
+            merged.tombstone_grace_days = grace_days;
+        }
+        if let Some(ast_detail) = self.indexing.ast_detail {
+            merged.ast_detail = ast_detail;
+        }
+        if self.indexing.ast_max_tokens.is_some() {
+            merged.ast_max_tokens = self.indexing.ast_max_tokens;
+        }
+        for extension in &self.indexing.extensions {
+            let extension = normalize_extension(extension);
+            if !merged.code_extensions.contains(&extension) {
+                merged.code_extensions.push(extension);
+            }
+        }
+        let excluded: Vec<String> = self
+            .indexing
+            .exclude_extensions
+            .iter()
+            .map(|extension| normalize_extension(extension))
+            .collect();
+        merged
+            .code_extensions
+            .retain(|extension| !excluded.contains(extension));
+        for (extension, language) in &self.languages {
+            merged.language_overrides.insert(
+                extension.trim_start_matches('.').to_lowercase(),
+                language.to_lowercase(),
+            );
+        }
+        if let Some(snapshots) = &self.snapshots {
+            merged.snapshot_policy = snapshots.clone();
+        }
+        if let Some(secrets) = &self.secrets {
+            merged.secret_redaction = secrets.clone();
+        }
+        if let Some(errors) = &self.errors {
+            merged.error_budget = errors.clone();
+        }
+
+        merged
+    }
+}
+
+/// Forma canónica de una extensión: sin punto inicial y en minúsculas
+pub fn normalize_extension(extension: &str) -> String {
+    extension.trim().trim_start_matches('.').to_lowercase()
+}
+
+/// Lee `opcode.toml` de la raíz del proyecto. `None` si el archivo no existe
+pub fn load_project_config(project_path: &str) -> Result<Option<ProjectConfig>> {
+    let path = Path::new(project_path).join(PROJECT_CONFIG_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let config = parse_project_config(&content)
+        .with_context(|| format!("Invalid {}", path.display()))?;
+    Ok(Some(config))
+}
+
+/// Parsea el contenido de un `opcode.toml`
+pub fn parse_project_config(content: &str) -> Result<ProjectConfig> {
+    let config: ProjectConfig = toml::from_str(content)?;
+
+    for rule in config.secrets.iter().flat_map(|s| &s.rules) {
+        regex::Regex::new(&rule.pattern)
+            .with_context(|| format!("Invalid pattern for secret rule '{}'", rule.id))?;
+    }
+
+    Ok(config)
+}
+
+/// Opciones efectivas para un proyecto: las de la app con `opcode.toml` encima.
+/// Si el archivo es inválido se usan las de la app y se retorna el error para reportarlo
+pub fn resolve_options(
+    project_path: &str,
+    options: &ChunkingOptions,
+) -> (ChunkingOptions, Option<String>) {
+    match load_project_config(project_path) {
+        Ok(Some(config)) => (config.apply(options), None),
+        Ok(None) => (options.clone(), None),
+        Err(e) => (options.clone(), Some(format!("{:#}", e))),
+    }
+}
+
+/// Registro del error de un `opcode.toml` inválido
+pub fn config_error(message: String) -> IndexingError {
+    IndexingError {
+        file_path: Some(PROJECT_CONFIG_FILE.to_string()),
+        chunk_type: None,
+        kind: IndexingErrorKind::Config,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+[indexing]
+chunk_types = ["raw_source", "ast"]
+ignore = ["vendor/**"]
+max_commits = 20
+ast_detail = "signatures_only"
+extensions = [".zig", "EX"]
+exclude_extensions = ["json"]
+
+[languages]
+".mjs" = "JavaScript"
+
+[snapshots]
+auto_reindex = false
+
+[secrets]
+disabled_rules = ["jwt"]
+allow_paths = ["fixtures/**"]
+
+[[secrets.rules]]
+id = "internal_token"
+pattern = "itk_[A-Za-z0-9]{32}"
+"#;
+
+    #[test]
+    fn test_config_overrides_app_defaults() {
+        let config = parse_project_config(SAMPLE).unwrap();
+        let options = config.apply(&ChunkingOptions::default());
+
+        assert_eq!(options.chunk_types, vec![ChunkType::RawSource, ChunkType::Ast]);
+        assert!(options.ignore_patterns.contains(&"node_modules/**".to_string()));
+        assert!(options.ignore_patterns.contains(&"vendor/**".to_string()));
+        assert_eq!(options.max_commits, Some(20));
+        assert_eq!(options.ast_detail, AstDetail::SignaturesOnly);
+        assert_eq!(options.ast_max_tokens, None);
+        assert!(options.code_extensions.contains(&"zig".to_string()));
+        assert!(options.code_extensions.contains(&"ex".to_string()));
+        assert!(options.code_extensions.contains(&"rs".to_string()));
+        assert!(!options.code_extensions.contains(&"json".to_string()));
+        assert_eq!(
+            options.language_overrides.get("mjs").map(String::as_str),
+            Some("javascript")
+        );
+        assert!(!options.snapshot_policy.auto_reindex);
+        assert_eq!(options.secret_redaction.disabled_rules, vec!["jwt"]);
+        assert_eq!(options.secret_redaction.rules[0].severity, "high");
+    }
+
+    #[test]
+    fn test_missing_sections_keep_defaults() {
+        let options = parse_project_config("[indexing]\nall_branches = true\n")
+            .unwrap()
+            .apply(&ChunkingOptions::default());
+        let defaults = ChunkingOptions::default();
+
+        assert!(options.all_branches);
+        assert_eq!(options.chunk_types, defaults.chunk_types);
+        assert_eq!(options.snapshot_policy, defaults.snapshot_policy);
+    }
+
+    #[test]
+    fn test_invalid_config_is_reported() {
+        assert!(parse_project_config("[indexing]\nunknown_key = 1\n").is_err());
+        assert!(parse_project_config(
+            "[[secrets.rules]]\nid = \"bad\"\npattern = \"(unclosed\"\n"
+        )
+        .is_err());
+
+        let dir = std::env::temp_dir().join(format!("opcode-config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(PROJECT_CONFIG_FILE), "chunk_types = 3").unwrap();
+
+        let (options, error) =
+            resolve_options(&dir.to_string_lossy(), &ChunkingOptions::default());
+        assert!(error.is_some());
+        assert_eq!(options.max_commits, Some(100));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
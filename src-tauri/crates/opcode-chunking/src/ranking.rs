@@ -0,0 +1,409 @@
+use super::storage::{get_active_snapshot_id, get_snapshot_file_diffs, query_chunks};
+use super::types::{Chunk, ChunkQuery, ChunkType, CommitMetadata, RelationshipType, ScoredChunk};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+/// Peso de la similitud léxica en la puntuación final
+const LEXICAL_WEIGHT: f64 = 0.75;
+
+/// Peso de la cercanía a archivos modificados recientemente
+const PROXIMITY_WEIGHT: f64 = 0.25;
+
+/// Parámetros BM25
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// Los términos en ruta y nombre de entidad cuentan más que los del contenido
+const NAME_TERM_BOOST: usize = 3;
+
+/// Commits recientes cuyos archivos cuentan como "modificados recientemente"
+const RECENT_COMMITS: usize = 10;
+
+/// Distancia máxima (en imports) para la cercanía
+const MAX_GRAPH_DISTANCE: usize = 2;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "into", "is", "it", "of",
+    "on", "or", "that", "the", "this", "to", "when", "with", "add", "fix", "make", "should", "el",
+    "la", "los", "las", "un", "una", "de", "del", "en", "y", "o", "que", "para", "con", "por",
+    "se", "al", "es",
+];
+
+/// Ordena los chunks del proyecto por relevancia para una tarea: BM25 sobre ruta,
+/// entidad y contenido (identificadores separados en camelCase/snake_case) combinado
+/// con la cercanía en el grafo de imports a los archivos modificados recientemente
+pub fn rank_chunks_for_task(
+    conn: &Connection,
+    project_path: &str,
+    task_text: &str,
+    limit: usize,
+) -> Result<Vec<ScoredChunk>> {
+    let query_terms: BTreeSet<String> = tokenize(task_text).into_iter().collect();
+
+    // Solo la versión más reciente de cada chunk (query_chunks ordena por updated_at DESC)
+    let mut seen = HashSet::new();
+    let chunks: Vec<Chunk> = query_chunks(
+        conn,
+        &ChunkQuery {
+            project_path: Some(project_path.to_string()),
+            ..Default::default()
+        },
+    )?
+    .into_iter()
+    .filter(|c| c.chunk_type != ChunkType::Snapshot)
+    .filter(|c| {
+        seen.insert((
+            c.chunk_type.as_str(),
+            c.file_path.clone(),
+            c.entity_name.clone(),
+        ))
+    })
+    .collect();
+
+    let lexical = bm25_scores(&chunks, &query_terms);
+    let max_lexical = lexical.iter().map(|(s, _)| *s).fold(0.0, f64::max);
+
+    let recent = recently_changed_files(conn, project_path)?;
+    let distances = graph_distances(conn, project_path, &recent)?;
+
+    let mut scored: Vec<ScoredChunk> = chunks
+        .into_iter()
+        .zip(lexical)
+        .filter_map(|(chunk, (raw_lexical, matched_terms))| {
+            let lexical_score = if max_lexical > 0.0 {
+                raw_lexical / max_lexical
+            } else {
+                0.0
+            };
+            let proximity_score = chunk
+                .file_path
+                .as_ref()
+                .and_then(|f| distances.get(f))
+                .map(|d| 1.0 / 2f64.powi(*d as i32))
+                .unwrap_or(0.0);
+            // Sin coincidencias léxicas solo se incluyen si la tarea no tiene términos útiles
+            if lexical_score == 0.0 && (!query_terms.is_empty() || proximity_score == 0.0) {
+                return None;
+            }
+            Some(ScoredChunk {
+                chunk,
+                score: LEXICAL_WEIGHT * lexical_score + PROXIMITY_WEIGHT * proximity_score,
+                lexical_score,
+                proximity_score,
+                matched_terms,
+            })
+        })
+        .collect();
+
+    scored.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    scored.truncate(limit);
+    Ok(scored)
+}
+
+/// Separa texto en términos: identificadores camelCase/snake_case, minúsculas, sin stopwords
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let mut current = String::new();
+        let mut prev_lower = false;
+        for c in word.chars() {
+            if c.is_uppercase() && prev_lower && !current.is_empty() {
+                terms.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            current.extend(c.to_lowercase());
+        }
+        if !current.is_empty() {
+            terms.push(current);
+        }
+    }
+    terms
+        .into_iter()
+        .filter(|t| t.chars().count() > 1 && !t.chars().all(|c| c.is_ascii_digit()))
+        .filter(|t| !STOPWORDS.contains(&t.as_str()))
+        .map(|t| stem(&t))
+        .collect()
+}
+
+/// Reducción mínima de plurales/gerundios para que "users" encuentre "user"
+fn stem(term: &str) -> String {
+    for suffix in ["ing", "es", "s"] {
+        if let Some(base) = term.strip_suffix(suffix) {
+            if base.len() >= 3 && !term.ends_with("ss") {
+                return base.to_string();
+            }
+        }
+    }
+    term.to_string()
+}
+
+/// Puntuación BM25 de cada chunk y los términos de la consulta que contiene
+fn bm25_scores(chunks: &[Chunk], query_terms: &BTreeSet<String>) -> Vec<(f64, Vec<String>)> {
+    let documents: Vec<HashMap<String, usize>> = chunks
+        .iter()
+        .map(|chunk| {
+            let mut frequencies: HashMap<String, usize> = HashMap::new();
+            let names = format!(
+                "{} {}",
+                chunk.file_path.as_deref().unwrap_or(""),
+                chunk.entity_name.as_deref().unwrap_or("")
+            );
+            for term in tokenize(&names) {
+                *frequencies.entry(term).or_default() += NAME_TERM_BOOST;
+            }
+            for term in tokenize(&chunk.content) {
+                *frequencies.entry(term).or_default() += 1;
+            }
+            frequencies
+        })
+        .collect();
+
+    let total = documents.len() as f64;
+    let lengths: Vec<f64> = documents
+        .iter()
+        .map(|d| d.values().sum::<usize>() as f64)
+        .collect();
+    let average_length = (lengths.iter().sum::<f64>() / total.max(1.0)).max(1.0);
+    let document_frequency: HashMap<&String, usize> = query_terms
+        .iter()
+        .map(|t| (t, documents.iter().filter(|d| d.contains_key(t)).count()))
+        .collect();
+
+    documents
+        .iter()
+        .zip(lengths)
+        .map(|(frequencies, length)| {
+            let mut score = 0.0;
+            let mut matched = Vec::new();
+            for term in query_terms {
+                let Some(&tf) = frequencies.get(term) else {
+                    continue;
+                };
+                let df = document_frequency[term] as f64;
+                let idf = ((total - df + 0.5) / (df + 0.5) + 1.0).ln();
+                let tf = tf as f64;
+                score += idf * tf * (BM25_K1 + 1.0)
+                    / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * length / average_length));
+                matched.push(term.clone());
+            }
+            (score, matched)
+        })
+        .collect()
+}
+
+/// Archivos del snapshot activo y de los últimos commits indexados
+fn recently_changed_files(conn: &Connection, project_path: &str) -> Result<BTreeSet<String>> {
+    let mut files = BTreeSet::new();
+
+    if let Some(snapshot_id) = get_active_snapshot_id(conn, project_path)? {
+        files.extend(
+            get_snapshot_file_diffs(conn, snapshot_id)?
+                .into_iter()
+                .map(|f| f.file_path),
+        );
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND metadata IS NOT NULL
+         ORDER BY json_extract(metadata, '$.commit_date') DESC LIMIT ?3",
+    )?;
+    let commits = stmt
+        .query_map(
+            params![
+                project_path,
+                ChunkType::CommitHistory.as_str(),
+                RECENT_COMMITS as i64
+            ],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for metadata in commits {
+        if let Ok(metadata) = serde_json::from_str::<CommitMetadata>(&metadata) {
+            files.extend(metadata.files_modified);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Distancia (en imports, sin dirección) de cada archivo a los archivos de origen
+fn graph_distances(
+    conn: &Connection,
+    project_path: &str,
+    sources: &BTreeSet<String>,
+) -> Result<HashMap<String, usize>> {
+    let mut distances: HashMap<String, usize> = HashMap::new();
+    if sources.is_empty() {
+        return Ok(distances);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT json_extract(r.metadata, '$.from_file'), json_extract(r.metadata, '$.to_file')
+         FROM chunk_relationships r JOIN chunks c ON c.id = r.from_chunk_id
+         WHERE c.project_path = ?1 AND r.relationship_type = ?2
+           AND json_extract(r.metadata, '$.from_file') IS NOT NULL
+           AND json_extract(r.metadata, '$.to_file') IS NOT NULL",
+    )?;
+    let edges = stmt
+        .query_map(
+            params![project_path, RelationshipType::DependsOn.as_str()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut neighbours: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (from, to) in &edges {
+        neighbours.entry(from).or_default().push(to);
+        neighbours.entry(to).or_default().push(from);
+    }
+
+    let mut queue: VecDeque<(String, usize)> = sources.iter().map(|f| (f.clone(), 0)).collect();
+    while let Some((file, distance)) = queue.pop_front() {
+        if distances.contains_key(&file) {
+            continue;
+        }
+        distances.insert(file.clone(), distance);
+        if distance == MAX_GRAPH_DISTANCE {
+            continue;
+        }
+        for next in neighbours.get(file.as_str()).into_iter().flatten() {
+            if !distances.contains_key(*next) {
+                queue.push_back((next.to_string(), distance + 1));
+            }
+        }
+    }
+
+    Ok(distances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        create_snapshot, init_chunk_database, insert_relationship, store_snapshot_diffs,
+    };
+    use crate::test_support::chunk;
+    use crate::types::{ChunkRelationship, Snapshot, SnapshotFileDiff, SnapshotType};
+    use chrono::Utc;
+
+    fn source(conn: &Connection, file_path: &str, content: &str) -> i64 {
+        chunk().file(file_path).content(content).insert(conn)
+    }
+
+    #[test]
+    fn test_tokenize_identifiers() {
+        assert_eq!(
+            tokenize("Fix the userLogin in session_store.rs"),
+            vec!["user", "login", "session", "store", "rs"]
+        );
+        assert_eq!(tokenize("HTTPServer users"), vec!["httpserver", "user"]);
+    }
+
+    #[test]
+    fn test_rank_chunks_for_task() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let login = source(
+            &conn,
+            "src/auth/login.rs",
+            "pub fn login(user: &User) { check_password(user) }",
+        );
+        let session = source(
+            &conn,
+            "src/auth/session.rs",
+            "pub fn open_session(user: &User) {}",
+        );
+        source(
+            &conn,
+            "src/billing/invoice.rs",
+            "pub fn total(invoice: &Invoice) {}",
+        );
+        source(
+            &conn,
+            "src/billing/tax.rs",
+            "pub fn user_tax(user: &User) {}",
+        );
+        insert_relationship(
+            &conn,
+            &ChunkRelationship {
+                id: None,
+                from_chunk_id: login,
+                to_chunk_id: session,
+                relationship_type: RelationshipType::DependsOn,
+                metadata: Some(
+                    r#"{"source":"import_resolution","from_file":"src/auth/login.rs","to_file":"src/auth/session.rs"}"#
+                        .to_string(),
+                ),
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+
+        // El snapshot activo modificó session.rs
+        let snapshot_id = create_snapshot(
+            &conn,
+            &Snapshot {
+                id: None,
+                project_path: "/p".to_string(),
+                snapshot_type: SnapshotType::Master,
+                parent_snapshot_id: None,
+                message: "V1".to_string(),
+                user_message: None,
+                changed_files: "[]".to_string(),
+                diff_summary: None,
+                metadata: None,
+                git_commit_hash: None,
+                git_tag: None,
+                git_branch: None,
+                version_major: 1,
+                version_minor: None,
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+        store_snapshot_diffs(
+            &conn,
+            snapshot_id,
+            &[SnapshotFileDiff {
+                file_path: "src/auth/session.rs".to_string(),
+                old_path: None,
+                status: "modified".to_string(),
+                additions: 1,
+                deletions: 0,
+                is_binary: false,
+                patch: None,
+                truncated: false,
+            }],
+        )
+        .unwrap();
+
+        let ranked =
+            rank_chunks_for_task(&conn, "/p", "Users can't login with a valid password", 10)
+                .unwrap();
+        assert_eq!(
+            ranked[0].chunk.file_path.as_deref(),
+            Some("src/auth/login.rs")
+        );
+        assert!(ranked[0].matched_terms.contains(&"login".to_string()));
+        assert_eq!(ranked[0].proximity_score, 0.5);
+        assert!(ranked
+            .iter()
+            .all(|r| r.chunk.file_path.as_deref() != Some("src/billing/invoice.rs")));
+
+        // "user" aparece en session y tax; la cercanía desempata a favor de session
+        let ranked = rank_chunks_for_task(&conn, "/p", "user", 10).unwrap();
+        let position = |file: &str| {
+            ranked
+                .iter()
+                .position(|r| r.chunk.file_path.as_deref() == Some(file))
+                .unwrap()
+        };
+        assert!(position("src/auth/session.rs") < position("src/billing/tax.rs"));
+    }
+}
@@ -0,0 +1,177 @@
+use super::storage::{calculate_hash, normalize_file_path, upsert_chunk};
+use super::types::{Chunk, ChunkType, HashAlgorithm};
+use anyhow::Result;
+use chrono::Utc;
+use ignore::WalkBuilder;
+use rusqlite::Connection;
+use std::path::Path;
+
+/// Genera chunks de código fuente RAW (archivo completo). Con `scope` solo recorre
+/// ese subdirectorio (relativo a la raíz del proyecto)
+pub fn generate_raw_source_chunks(
+    conn: &Connection,
+    project_path: &str,
+    scope: Option<&str>,
+    ignore_patterns: &[String],
+    code_extensions: &[String],
+    hash_algorithm: HashAlgorithm,
+) -> Result<usize> {
+    let mut chunks_created = 0;
+
+    // Construir walker que respeta .gitignore
+    let walker = WalkBuilder::new(Path::new(project_path).join(scope.unwrap_or_default()))
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(false)
+        .build();
+
+    for entry in walker.filter_map(|e| e.ok()) {
+        let path = entry.path();
+
+        // Solo procesar archivos
+        if !path.is_file() {
+            continue;
+        }
+
+        // Verificar que es un archivo de código
+        if !is_code_file(path, code_extensions) {
+            continue;
+        }
+
+        // Verificar patrones de ignore personalizados
+        let rel_path = normalize_file_path(
+            &path
+                .strip_prefix(project_path)
+                .unwrap_or(path)
+                .to_string_lossy(),
+        );
+
+        if should_ignore(&rel_path, ignore_patterns) {
+            continue;
+        }
+
+        // Leer contenido del archivo
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                let content_hash = calculate_hash(&content, hash_algorithm);
+
+                let chunk = Chunk {
+                    id: None,
+                    project_path: project_path.to_string(),
+                    chunk_type: ChunkType::RawSource,
+                    file_path: Some(rel_path),
+                    entity_name: None,
+                    content,
+                    content_hash,
+                    metadata: None,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                };
+
+                match upsert_chunk(conn, &chunk, None) {
+                    Ok(_) => chunks_created += 1,
+                    Err(e) => {
+                        eprintln!("Failed to insert chunk for {}: {}", path.display(), e);
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to read file {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    Ok(chunks_created)
+}
+
+/// Crea un chunk de raw source para un archivo específico (usado en reindexación incremental).
+/// `file_path` es relativo a la raíz del proyecto, igual que en la indexación completa
+pub fn create_raw_source_chunk(
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+    hash_algorithm: HashAlgorithm,
+) -> Result<Chunk> {
+    let content_hash = calculate_hash(content, hash_algorithm);
+
+    Ok(Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::RawSource,
+        file_path: Some(normalize_file_path(file_path)),
+        entity_name: None,
+        content: content.to_string(),
+        content_hash,
+        metadata: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    })
+}
+
+/// Verifica si un archivo es de código según las extensiones configuradas
+/// (`ChunkingOptions.code_extensions`). La comparación ignora mayúsculas
+pub(crate) fn is_code_file(path: &Path, code_extensions: &[String]) -> bool {
+    let Some(ext) = path.extension() else {
+        return false;
+    };
+    let ext = ext.to_string_lossy();
+    code_extensions
+        .iter()
+        .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+}
+
+/// Verifica si un path debe ser ignorado según los patrones (globs).
+/// Como en .gitignore, un patrón sin `/` inicial también aplica en subdirectorios
+pub(crate) fn should_ignore(path: &str, patterns: &[String]) -> bool {
+    let path = path.replace('\\', "/");
+    patterns.iter().any(|pattern| {
+        let Ok(glob) = glob::Pattern::new(pattern.trim_start_matches('/')) else {
+            return false;
+        };
+        if glob.matches(&path) {
+            return true;
+        }
+        !pattern.starts_with('/')
+            && path
+                .match_indices('/')
+                .any(|(i, _)| glob.matches(&path[i + 1..]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::default_code_extensions;
+
+    #[test]
+    fn test_is_code_file() {
+        let defaults = default_code_extensions();
+        assert!(is_code_file(Path::new("src/main.rs"), &defaults));
+        assert!(is_code_file(Path::new("app.tsx"), &defaults));
+        assert!(is_code_file(Path::new("Utils.PY"), &defaults));
+        assert!(!is_code_file(Path::new("image.png"), &defaults));
+        assert!(!is_code_file(Path::new("README.md"), &defaults));
+        assert!(!is_code_file(Path::new("build.zig"), &defaults));
+
+        let custom = vec!["zig".to_string(), ".ex".to_string()];
+        assert!(is_code_file(Path::new("build.zig"), &custom));
+        assert!(is_code_file(Path::new("lib/app.ex"), &custom));
+        assert!(!is_code_file(Path::new("src/main.rs"), &custom));
+        assert!(!is_code_file(Path::new("Makefile"), &custom));
+    }
+
+    #[test]
+    fn test_should_ignore() {
+        let patterns = vec!["node_modules/**".to_string(), "dist/**".to_string()];
+        assert!(should_ignore("node_modules/package/index.js", &patterns));
+        assert!(should_ignore("dist/bundle.js", &patterns));
+        assert!(!should_ignore("src/index.js", &patterns));
+        assert!(should_ignore("packages/app/node_modules/x/index.js", &patterns));
+
+        let patterns = vec!["build/**".to_string(), "/fixtures/*.json".to_string()];
+        assert!(!should_ignore("src/build_errors.rs", &patterns));
+        assert!(should_ignore("fixtures/a.json", &patterns));
+        assert!(!should_ignore("test/fixtures/a.json", &patterns));
+    }
+}
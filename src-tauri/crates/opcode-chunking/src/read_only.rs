@@ -0,0 +1,100 @@
+use super::storage::get_read_only_project;
+use anyhow::{bail, Result};
+use rusqlite::Connection;
+
+/// Falla si el índice del proyecto está marcado como de solo lectura. Se llama antes de
+/// reindexar o crear snapshots para no dejar cambios a medias en git o en el índice;
+/// los triggers de `read_only_projects` rechazan igualmente cualquier escritura
+pub fn ensure_index_writable(conn: &Connection, project_path: &str) -> Result<()> {
+    if let Some(project) = get_read_only_project(conn, project_path)? {
+        match project.reason {
+            Some(reason) => bail!("Index of {} is read-only: {}", project_path, reason),
+            None => bail!("Index of {} is read-only", project_path),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        get_chunk_id_by_hash, init_chunk_database, purge_deleted_chunks, query_chunks,
+        set_read_only_project, upsert_chunk, READ_ONLY_ERROR,
+    };
+    use crate::test_support::chunk;
+    use crate::types::{Chunk, ChunkQuery};
+    use chrono::Utc;
+    use rusqlite::params;
+
+    fn lib_chunk(project_path: &str, content: &str) -> Chunk {
+        chunk()
+            .project(project_path)
+            .file("src/lib.rs")
+            .content(content)
+            .build()
+    }
+
+    #[test]
+    fn test_read_only_project_rejects_writes() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        upsert_chunk(&conn, &lib_chunk("/shared", "pub fn a() {}"), None).unwrap();
+        upsert_chunk(&conn, &lib_chunk("/local", "pub fn a() {}"), None).unwrap();
+        conn.execute(
+            "UPDATE chunks SET deleted_at = ?1",
+            params!["2000-01-01T00:00:00+00:00"],
+        )
+        .unwrap();
+
+        set_read_only_project(&conn, "/shared", true, Some("exported index")).unwrap();
+        let err = ensure_index_writable(&conn, "/shared").unwrap_err();
+        assert!(err.to_string().contains("exported index"));
+        assert!(ensure_index_writable(&conn, "/local").is_ok());
+
+        let err = upsert_chunk(&conn, &lib_chunk("/shared", "pub fn b() {}"), None).unwrap_err();
+        assert!(format!("{:#}", err).contains(READ_ONLY_ERROR));
+        assert!(conn
+            .execute("DELETE FROM chunks WHERE project_path = '/shared'", [])
+            .is_err());
+
+        // La purga global salta el proyecto de solo lectura
+        assert_eq!(purge_deleted_chunks(&conn, None, Utc::now()).unwrap(), 1);
+
+        // Los triggers sobreviven a la reapertura y se levantan al quitar la marca
+        init_chunk_database(&conn).unwrap();
+        assert!(upsert_chunk(&conn, &lib_chunk("/shared", "pub fn b() {}"), None).is_err());
+        set_read_only_project(&conn, "/shared", false, None).unwrap();
+        upsert_chunk(&conn, &lib_chunk("/shared", "pub fn b() {}"), None).unwrap();
+        let chunks = query_chunks(
+            &conn,
+            &ChunkQuery {
+                project_path: Some("/shared".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(chunks.len(), 1);
+    }
+
+    #[test]
+    fn test_read_only_project_does_not_block_identical_content_elsewhere() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let shared = lib_chunk("/shared", "pub fn a() {}");
+        upsert_chunk(&conn, &shared, None).unwrap();
+        set_read_only_project(&conn, "/shared", true, None).unwrap();
+
+        // Mismo contenido y hash en otro proyecto: fila propia, la de solo lectura no se toca
+        let local = Chunk {
+            project_path: "/local".to_string(),
+            ..shared.clone()
+        };
+        assert!(upsert_chunk(&conn, &local, None).unwrap());
+        assert!(!upsert_chunk(&conn, &local, None).unwrap());
+        let shared_id = get_chunk_id_by_hash(&conn, "/shared", &shared.content_hash).unwrap();
+        let local_id = get_chunk_id_by_hash(&conn, "/local", &shared.content_hash).unwrap();
+        assert!(shared_id.is_some() && local_id.is_some());
+        assert_ne!(shared_id, local_id);
+    }
+}
@@ -0,0 +1,985 @@
+use super::boundaries::rebuild_boundary_relationships;
+use super::callgraph::{extract_calls, extract_calls_by_line, extract_file_dependencies};
+use super::dependency_graph::{
+    generate_file_dependency_relationships, generate_module_graph_chunks, is_supported_source,
+    replace_file_dependency_relationships, IMPORT_RELATIONSHIP_SOURCE,
+};
+use super::i18n::rebuild_i18n_relationships;
+use super::lint::definitions;
+use super::resolver::ImportResolver;
+use super::storage::{
+    delete_relationships_by_source, get_files_related_to, get_latest_file_contents,
+    insert_relationship, replace_file_relationships,
+};
+use super::types::{
+    ChunkRelationship, ChunkType, CommitMetadata, RelationshipRebuildReport, RelationshipType,
+};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Valor de `source` en la metadata de cada tipo de relación inferida
+const CALL_SOURCE: &str = "call_inference";
+const ENTITY_CALL_SOURCE: &str = "entity_call_inference";
+const TEST_SOURCE: &str = "test_inference";
+const CONFIG_SOURCE: &str = "config_usage";
+const CO_CHANGE_SOURCE: &str = "co_change";
+
+/// Commits en los que dos archivos deben cambiar juntos para enlazarlos
+pub const MIN_CO_CHANGES: usize = 3;
+
+/// Commits que tocan más archivos (merges, reformateos) no cuentan para co-change
+const MAX_CO_CHANGE_FILES: usize = 50;
+
+type FileContents = BTreeMap<String, (i64, String)>;
+
+/// Chunk AST de una entidad y las líneas (1-based, inclusivas) que ocupa en su archivo
+struct EntityChunk {
+    id: i64,
+    name: String,
+    start_line: usize,
+    end_line: usize,
+}
+
+/// Reconstruye en bloque todas las relaciones inferibles del proyecto a partir de los
+/// chunks existentes: imports, llamadas (también entre lenguajes), tests, uso de
+/// configuración y de claves de traducción, y co-change.
+/// Las relaciones de cada origen se borran y se vuelven a crear
+pub fn rebuild_relationships(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<RelationshipRebuildReport> {
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+    let file_imports = file_imports(&sources);
+    let resolver = ImportResolver::load(project_path, sources.keys().cloned().collect());
+    let file_edges =
+        generate_file_dependency_relationships(conn, project_path, &file_imports, &resolver)?;
+    let (_, module_edges) =
+        generate_module_graph_chunks(conn, project_path, &file_imports, &resolver)?;
+
+    let mut report = infer(conn, project_path, &sources, &resolver)?;
+    report.imports = file_edges + module_edges;
+    report.relationships_created += report.imports;
+    log::info!(
+        "Rebuilt {} relationships for {}",
+        report.relationships_created,
+        project_path
+    );
+    Ok(report)
+}
+
+/// Relaciones que no salen de los imports (llamadas, fronteras entre lenguajes, tests,
+/// configuración, traducciones y co-change),
+/// para la indexación completa que ya generó las de imports
+pub fn infer_relationships(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<RelationshipRebuildReport> {
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+    let resolver = ImportResolver::load(project_path, sources.keys().cloned().collect());
+    infer(conn, project_path, &sources, &resolver)
+}
+
+/// Actualiza las relaciones tras reindexar `changed_files` sin rehacer todo el grafo:
+/// las de imports y llamadas se reemplazan, archivo por archivo, solo en los archivos
+/// afectados (los cambiados, los que apuntaban a ellos y los que ahora los alcanzan).
+/// Fronteras entre lenguajes, tests, configuración, traducciones y co-change se
+/// regeneran completas
+pub fn refresh_relationships(
+    conn: &Connection,
+    project_path: &str,
+    changed_files: &[String],
+) -> Result<RelationshipRebuildReport> {
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+    let file_imports = file_imports(&sources);
+    let resolver = ImportResolver::load(project_path, sources.keys().cloned().collect());
+    let changed: BTreeSet<String> = changed_files.iter().cloned().collect();
+
+    let mut import_files = changed.clone();
+    import_files.extend(get_files_related_to(
+        conn,
+        project_path,
+        IMPORT_RELATIONSHIP_SOURCE,
+        &changed,
+    )?);
+    for (file_path, imports) in &file_imports {
+        if imports.iter().any(|import| {
+            resolver
+                .resolve(file_path, import)
+                .is_some_and(|target| changed.contains(&target))
+        }) {
+            import_files.insert(file_path.clone());
+        }
+    }
+    let file_edges = replace_file_dependency_relationships(
+        conn,
+        project_path,
+        &file_imports,
+        &import_files,
+        &resolver,
+    )?;
+    let (_, module_edges) =
+        generate_module_graph_chunks(conn, project_path, &file_imports, &resolver)?;
+
+    let mut edges = call_edges(project_path, &sources);
+    let mut entity_edges = entity_call_edges(conn, project_path, &sources, &resolver)?;
+    let mut call_files = changed.clone();
+    for source in [CALL_SOURCE, ENTITY_CALL_SOURCE] {
+        call_files.extend(get_files_related_to(conn, project_path, source, &changed)?);
+    }
+    for (file_path, file_edges) in edges.iter().chain(&entity_edges) {
+        if file_edges.iter().any(|rel| {
+            rel.metadata
+                .as_deref()
+                .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+                .and_then(|m| m["to_file"].as_str().map(|f| changed.contains(f)))
+                .unwrap_or(false)
+        }) {
+            call_files.insert(file_path.clone());
+        }
+    }
+    let mut calls = 0;
+    for file_path in &call_files {
+        let file_edges = edges.remove(file_path).unwrap_or_default();
+        calls +=
+            replace_file_relationships(conn, project_path, CALL_SOURCE, file_path, &file_edges)?;
+        let file_edges = entity_edges.remove(file_path).unwrap_or_default();
+        calls += replace_file_relationships(
+            conn,
+            project_path,
+            ENTITY_CALL_SOURCE,
+            file_path,
+            &file_edges,
+        )?;
+    }
+
+    let boundaries = rebuild_boundary_relationships(conn, project_path, &sources)?;
+    let tests = rebuild_test_relationships(conn, project_path, &sources, &resolver)?;
+    let config_usage = rebuild_config_relationships(conn, project_path, &sources)?;
+    let i18n_usage = rebuild_i18n_relationships(conn, project_path, &sources)?;
+    let co_changes = rebuild_co_change_relationships(conn, project_path, &sources)?;
+    let imports = file_edges + module_edges;
+    log::debug!(
+        "Refreshed imports of {} and calls of {} files in {}",
+        import_files.len(),
+        call_files.len(),
+        project_path
+    );
+
+    Ok(RelationshipRebuildReport {
+        project_path: project_path.to_string(),
+        relationships_created: imports
+            + calls
+            + tests
+            + config_usage
+            + co_changes
+            + boundaries
+            + i18n_usage,
+        imports,
+        calls,
+        tests,
+        config_usage,
+        co_changes,
+        boundaries,
+        i18n_usage,
+    })
+}
+
+/// Imports de cada archivo de código: (archivo, especificadores)
+fn file_imports(sources: &FileContents) -> Vec<(String, Vec<String>)> {
+    sources
+        .iter()
+        .filter(|(file_path, _)| is_supported_source(file_path))
+        .map(|(file_path, (_, content))| {
+            (
+                file_path.clone(),
+                extract_file_dependencies(file_path, content),
+            )
+        })
+        .collect()
+}
+
+fn infer(
+    conn: &Connection,
+    project_path: &str,
+    sources: &FileContents,
+    resolver: &ImportResolver,
+) -> Result<RelationshipRebuildReport> {
+    let calls = rebuild_call_relationships(conn, project_path, sources, resolver)?;
+    let boundaries = rebuild_boundary_relationships(conn, project_path, sources)?;
+    let tests = rebuild_test_relationships(conn, project_path, sources, resolver)?;
+    let config_usage = rebuild_config_relationships(conn, project_path, sources)?;
+    let i18n_usage = rebuild_i18n_relationships(conn, project_path, sources)?;
+    let co_changes = rebuild_co_change_relationships(conn, project_path, sources)?;
+
+    Ok(RelationshipRebuildReport {
+        project_path: project_path.to_string(),
+        relationships_created: calls + tests + config_usage + co_changes + boundaries + i18n_usage,
+        imports: 0,
+        calls,
+        tests,
+        config_usage,
+        co_changes,
+        boundaries,
+        i18n_usage,
+    })
+}
+
+pub(crate) fn relationship(
+    from_chunk_id: i64,
+    to_chunk_id: i64,
+    relationship_type: RelationshipType,
+    metadata: serde_json::Value,
+) -> ChunkRelationship {
+    ChunkRelationship {
+        id: None,
+        from_chunk_id,
+        to_chunk_id,
+        relationship_type,
+        metadata: Some(metadata.to_string()),
+        created_at: Utc::now(),
+    }
+}
+
+/// `calls` del archivo que llama al archivo que define la función, y de la entidad
+/// que llama a la entidad llamada de otro archivo. Los nombres definidos en más de
+/// un archivo (o en más de una entidad) son ambiguos y no se enlazan
+fn rebuild_call_relationships(
+    conn: &Connection,
+    project_path: &str,
+    sources: &FileContents,
+    resolver: &ImportResolver,
+) -> Result<usize> {
+    delete_relationships_by_source(conn, project_path, CALL_SOURCE)?;
+    delete_relationships_by_source(conn, project_path, ENTITY_CALL_SOURCE)?;
+
+    let mut created = 0;
+    let entity_edges = entity_call_edges(conn, project_path, sources, resolver)?;
+    for edges in call_edges(project_path, sources)
+        .into_values()
+        .chain(entity_edges.into_values())
+    {
+        for rel in &edges {
+            insert_relationship(conn, rel)?;
+            created += 1;
+        }
+    }
+    Ok(created)
+}
+
+/// Relaciones `calls` de cada archivo de código (incluidos los que no llaman a nada)
+fn call_edges(
+    project_path: &str,
+    sources: &FileContents,
+) -> BTreeMap<String, Vec<ChunkRelationship>> {
+    let code_files = || sources.iter().filter(|(f, _)| is_supported_source(f));
+    let mut defined_in: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    for (file_path, (_, content)) in code_files() {
+        for (_, name) in definitions(content) {
+            defined_in.entry(name).or_default().insert(file_path);
+        }
+    }
+
+    let mut edges = BTreeMap::new();
+    for (file_path, (chunk_id, content)) in code_files() {
+        let mut callees: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+        for call in extract_calls(project_path, file_path, content) {
+            let Some(files) = defined_in.get(&call.name) else {
+                continue;
+            };
+            let Some(&target) = files.first() else {
+                continue;
+            };
+            if files.len() == 1 && target != file_path.as_str() {
+                callees.entry(target).or_default().insert(call.name);
+            }
+        }
+
+        let file_edges: Vec<ChunkRelationship> = callees
+            .into_iter()
+            .filter_map(|(target, functions)| {
+                let (target_id, _) = sources.get(target)?;
+                Some(relationship(
+                    *chunk_id,
+                    *target_id,
+                    RelationshipType::Calls,
+                    serde_json::json!({
+                        "source": CALL_SOURCE,
+                        "from_file": file_path,
+                        "to_file": target,
+                        "functions": functions,
+                    }),
+                ))
+            })
+            .collect();
+        edges.insert(file_path.clone(), file_edges);
+    }
+    edges
+}
+
+/// Relaciones `calls` entre chunks AST de entidad de cada archivo de código: de la
+/// entidad que contiene la llamada (o del raw source del archivo, si la llamada está
+/// fuera de toda entidad) a la única entidad de otro archivo con ese nombre.
+/// `Tipo::f()` se resuelve por el nombre completo y `f()` por el nombre sin el tipo
+/// (`Invoice::sum` se llama como `sum`). `modulo::f()` y `valor.f()` no dicen qué `f`
+/// es: solo se enlazan si el archivo que llama importa el archivo de la entidad
+fn entity_call_edges(
+    conn: &Connection,
+    project_path: &str,
+    sources: &FileContents,
+    resolver: &ImportResolver,
+) -> Result<BTreeMap<String, Vec<ChunkRelationship>>> {
+    let entities = entity_chunks(conn, project_path)?;
+    let mut defined: BTreeMap<&str, BTreeMap<i64, (&str, &EntityChunk)>> = BTreeMap::new();
+    for (file_path, file_entities) in &entities {
+        for entity in file_entities {
+            for name in [entity.name.as_str(), short_name(&entity.name)] {
+                defined
+                    .entry(name)
+                    .or_default()
+                    .insert(entity.id, (file_path.as_str(), entity));
+            }
+        }
+    }
+
+    let mut edges = BTreeMap::new();
+    let code_files = sources.iter().filter(|(f, _)| is_supported_source(f));
+    for (file_path, (chunk_id, content)) in code_files {
+        let callers = entities
+            .get(file_path)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let imported: BTreeSet<String> = extract_file_dependencies(file_path, content)
+            .iter()
+            .filter_map(|import| resolver.resolve(file_path, import))
+            .collect();
+        // (desde, hasta) -> (entidad que llama, archivo y entidad llamados, llamadas, líneas)
+        let mut grouped: BTreeMap<(i64, i64), _> = BTreeMap::new();
+        for (line, call) in extract_calls_by_line(project_path, file_path, content) {
+            let (name, needs_import) = match (&call.receiver, call.separator) {
+                (Some(receiver), "::") if short_name(receiver).starts_with(char::is_uppercase) => {
+                    (format!("{}::{}", short_name(receiver), call.name), false)
+                }
+                (Some(_), _) => (call.name.clone(), true),
+                (None, _) => (call.name.clone(), false),
+            };
+            let Some(candidates) = defined.get(name.as_str()) else {
+                continue;
+            };
+            let Some((&target_id, &(target_file, target))) = candidates.first_key_value() else {
+                continue;
+            };
+            if candidates.len() != 1
+                || target_file == file_path.as_str()
+                || (needs_import && !imported.contains(target_file))
+            {
+                continue;
+            }
+            // La entidad más interna que contiene la línea
+            let caller = callers
+                .iter()
+                .filter(|e| e.start_line <= line && line <= e.end_line)
+                .min_by_key(|e| e.end_line - e.start_line);
+            let from_id = caller.map_or(*chunk_id, |e| e.id);
+            let (_, _, _, calls, lines) =
+                grouped.entry((from_id, target_id)).or_insert_with(|| {
+                    (
+                        caller.map(|e| e.name.as_str()),
+                        target_file,
+                        target.name.as_str(),
+                        BTreeSet::new(),
+                        BTreeSet::new(),
+                    )
+                });
+            calls.insert(call.to_string());
+            lines.insert(line);
+        }
+
+        let file_edges: Vec<ChunkRelationship> = grouped
+            .into_iter()
+            .map(
+                |((from_id, to_id), (from_entity, to_file, to_entity, calls, lines))| {
+                    relationship(
+                        from_id,
+                        to_id,
+                        RelationshipType::Calls,
+                        serde_json::json!({
+                            "source": ENTITY_CALL_SOURCE,
+                            "from_file": file_path,
+                            "from_entity": from_entity,
+                            "to_file": to_file,
+                            "to_entity": to_entity,
+                            "calls": calls,
+                            "lines": lines,
+                        }),
+                    )
+                },
+            )
+            .collect();
+        edges.insert(file_path.clone(), file_edges);
+    }
+    Ok(edges)
+}
+
+/// Chunks AST de entidad vigentes del proyecto, por archivo. Los que no guardan sus
+/// líneas no se pueden ubicar y se omiten
+fn entity_chunks(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<BTreeMap<String, Vec<EntityChunk>>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, file_path, entity_name,
+                json_extract(metadata, '$.start_line'), json_extract(metadata, '$.end_line')
+         FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND file_path IS NOT NULL
+           AND entity_name IS NOT NULL AND deleted_at IS NULL
+         ORDER BY updated_at DESC, id DESC",
+    )?;
+    let rows = stmt
+        .query_map(params![project_path, ChunkType::Ast.as_str()], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<usize>>(3)?,
+                row.get::<_, Option<usize>>(4)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // La versión más reciente de cada entidad
+    let mut seen = BTreeSet::new();
+    let mut entities: BTreeMap<String, Vec<EntityChunk>> = BTreeMap::new();
+    for (id, file_path, name, start_line, end_line) in rows {
+        let (Some(start_line), Some(end_line)) = (start_line, end_line) else {
+            continue;
+        };
+        if !seen.insert((file_path.clone(), name.clone())) {
+            continue;
+        }
+        entities.entry(file_path).or_default().push(EntityChunk {
+            id,
+            name,
+            start_line,
+            end_line,
+        });
+    }
+    Ok(entities)
+}
+
+/// Nombre de la entidad sin el tipo ni la ruta (`Invoice::sum`, `Cart.add` → `sum`, `add`)
+fn short_name(name: &str) -> &str {
+    name.rsplit([':', '.']).next().unwrap_or(name)
+}
+
+/// Nombre base del archivo de código que prueba un archivo de tests por convención
+/// (`login_test.rs`, `test_login.py`, `login.test.ts`, `login.spec.js` → `login`)
+fn tested_stem(file_path: &str) -> Option<String> {
+    let file_name = Path::new(file_path).file_name()?.to_str()?;
+    let (stem, rest) = file_name.split_once('.').unwrap_or((file_name, ""));
+    if rest.starts_with("test.") || rest.starts_with("spec.") {
+        return Some(stem.to_string());
+    }
+    stem.strip_prefix("test_")
+        .or_else(|| stem.strip_suffix("_test"))
+        .or_else(|| stem.strip_suffix("_spec"))
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn file_stem(file_path: &str) -> &str {
+    let file_name = Path::new(file_path)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or(file_path);
+    file_name.split('.').next().unwrap_or(file_name)
+}
+
+/// `tested_by` del código a su chunk de tests: por los imports del archivo de tests,
+/// por el nombre del archivo o porque los tests están en el mismo archivo
+fn rebuild_test_relationships(
+    conn: &Connection,
+    project_path: &str,
+    sources: &FileContents,
+    resolver: &ImportResolver,
+) -> Result<usize> {
+    delete_relationships_by_source(conn, project_path, TEST_SOURCE)?;
+    let test_chunks = get_latest_file_contents(conn, project_path, &ChunkType::Tests)?;
+    let is_code = |file: &str| sources.contains_key(file) && tested_stem(file).is_none();
+
+    let mut created = 0;
+    for (test_file, (test_chunk_id, _)) in &test_chunks {
+        let mut tested: BTreeMap<String, &str> = BTreeMap::new();
+
+        if is_code(test_file) {
+            tested.insert(test_file.clone(), "same_file");
+        }
+        if let Some((_, content)) = sources.get(test_file) {
+            for import in extract_file_dependencies(test_file, content) {
+                if let Some(target) = resolver.resolve(test_file, &import) {
+                    if &target != test_file && is_code(&target) {
+                        tested.entry(target).or_insert("import");
+                    }
+                }
+            }
+        }
+        if let Some(stem) = tested_stem(test_file) {
+            for file in sources.keys() {
+                if file != test_file && file_stem(file) == stem && is_code(file) {
+                    tested.entry(file.clone()).or_insert("name");
+                }
+            }
+        }
+
+        for (file, via) in tested {
+            let Some((source_id, _)) = sources.get(&file) else {
+                continue;
+            };
+            insert_relationship(
+                conn,
+                &relationship(
+                    *source_id,
+                    *test_chunk_id,
+                    RelationshipType::TestedBy,
+                    serde_json::json!({
+                        "source": TEST_SOURCE,
+                        "from_file": file,
+                        "to_file": test_file,
+                        "via": via,
+                    }),
+                ),
+            )?;
+            created += 1;
+        }
+    }
+    Ok(created)
+}
+
+/// `configures_for` de un archivo de configuración al código que usa sus claves
+/// (variables de entorno) o lo menciona por nombre
+fn rebuild_config_relationships(
+    conn: &Connection,
+    project_path: &str,
+    sources: &FileContents,
+) -> Result<usize> {
+    delete_relationships_by_source(conn, project_path, CONFIG_SOURCE)?;
+    let configs = get_latest_file_contents(conn, project_path, &ChunkType::StateConfig)?;
+    let key_re = Regex::new(r"(?m)^\s*(?:export\s+)?([A-Z][A-Z0-9_]{2,})\s*=").unwrap();
+
+    let mut created = 0;
+    for (config_file, (config_chunk_id, config_content)) in &configs {
+        let keys: BTreeSet<&str> = key_re
+            .captures_iter(config_content)
+            .filter_map(|cap| cap.get(1).map(|m| m.as_str()))
+            .collect();
+        let keys_re = if keys.is_empty() {
+            None
+        } else {
+            let alternatives: Vec<String> = keys.iter().map(|k| regex::escape(k)).collect();
+            Some(Regex::new(&format!(r"\b({})\b", alternatives.join("|")))?)
+        };
+        let config_name = Path::new(config_file)
+            .file_name()
+            .and_then(|f| f.to_str())
+            .unwrap_or(config_file);
+
+        for (file_path, (chunk_id, content)) in sources {
+            if file_path == config_file || !is_supported_source(file_path) {
+                continue;
+            }
+            let used_keys: BTreeSet<&str> = keys_re
+                .as_ref()
+                .map(|re| re.find_iter(content).map(|m| m.as_str()).collect())
+                .unwrap_or_default();
+            if used_keys.is_empty() && !content.contains(config_name) {
+                continue;
+            }
+            insert_relationship(
+                conn,
+                &relationship(
+                    *config_chunk_id,
+                    *chunk_id,
+                    RelationshipType::ConfiguresFor,
+                    serde_json::json!({
+                        "source": CONFIG_SOURCE,
+                        "from_file": config_file,
+                        "to_file": file_path,
+                        "keys": used_keys,
+                    }),
+                ),
+            )?;
+            created += 1;
+        }
+    }
+    Ok(created)
+}
+
+/// `modified_with` entre archivos que cambiaron juntos en al menos `MIN_CO_CHANGES`
+/// commits del historial indexado
+fn rebuild_co_change_relationships(
+    conn: &Connection,
+    project_path: &str,
+    sources: &FileContents,
+) -> Result<usize> {
+    delete_relationships_by_source(conn, project_path, CO_CHANGE_SOURCE)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND metadata IS NOT NULL
+           AND deleted_at IS NULL",
+    )?;
+    let commits = stmt
+        .query_map(
+            params![project_path, ChunkType::CommitHistory.as_str()],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut pairs: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for metadata in commits {
+        let Ok(commit) = serde_json::from_str::<CommitMetadata>(&metadata) else {
+            continue;
+        };
+        let files: BTreeSet<&String> = commit
+            .files_modified
+            .iter()
+            .filter(|f| sources.contains_key(*f))
+            .collect();
+        if files.len() < 2 || commit.files_modified.len() > MAX_CO_CHANGE_FILES {
+            continue;
+        }
+        let files: Vec<&String> = files.into_iter().collect();
+        for (i, a) in files.iter().enumerate() {
+            for b in &files[i + 1..] {
+                *pairs.entry(((*a).clone(), (*b).clone())).or_default() += 1;
+            }
+        }
+    }
+
+    let mut created = 0;
+    for ((a, b), commits) in pairs {
+        if commits < MIN_CO_CHANGES {
+            continue;
+        }
+        let (Some((a_id, _)), Some((b_id, _))) = (sources.get(&a), sources.get(&b)) else {
+            continue;
+        };
+        insert_relationship(
+            conn,
+            &relationship(
+                *a_id,
+                *b_id,
+                RelationshipType::ModifiedWith,
+                serde_json::json!({
+                    "source": CO_CHANGE_SOURCE,
+                    "from_file": a,
+                    "to_file": b,
+                    "commits": commits,
+                }),
+            ),
+        )?;
+        created += 1;
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{get_relationships, init_chunk_database};
+    use crate::test_support::chunk;
+
+    fn insert(conn: &Connection, chunk_type: ChunkType, file_path: Option<&str>, content: &str) {
+        insert_with_metadata(conn, chunk_type, file_path, content, None);
+    }
+
+    fn insert_with_metadata(
+        conn: &Connection,
+        chunk_type: ChunkType,
+        file_path: Option<&str>,
+        content: &str,
+        metadata: Option<String>,
+    ) {
+        let mut builder = chunk().chunk_type(chunk_type).content(content);
+        if let Some(file_path) = file_path {
+            builder = builder.file(file_path);
+        }
+        if let Some(metadata) = metadata {
+            builder = builder.metadata(metadata);
+        }
+        builder.insert(conn);
+    }
+
+    /// Chunk AST de una entidad que ocupa `lines` en su archivo; retorna su id
+    fn insert_entity(conn: &Connection, file_path: &str, name: &str, lines: (usize, usize)) -> i64 {
+        chunk()
+            .chunk_type(ChunkType::Ast)
+            .file(file_path)
+            .entity(name)
+            .content(&format!("(entity {})", name))
+            .metadata(serde_json::json!({ "start_line": lines.0, "end_line": lines.1 }))
+            .insert(conn)
+    }
+
+    fn commit(conn: &Connection, hash: &str, files: &[&str]) {
+        let metadata = CommitMetadata {
+            commit_hash: hash.to_string(),
+            author: "dev".to_string(),
+            author_email: "dev@example.com".to_string(),
+            commit_date: Utc::now(),
+            files_modified: files.iter().map(|f| f.to_string()).collect(),
+            insertions: 0,
+            deletions: 0,
+            commit_type: None,
+            scope: None,
+            breaking_change: false,
+            conventional: false,
+            branches: Vec::new(),
+        };
+        insert_with_metadata(
+            conn,
+            ChunkType::CommitHistory,
+            None,
+            hash,
+            Some(serde_json::to_string(&metadata).unwrap()),
+        );
+    }
+
+    fn count(conn: &Connection, relationship_type: RelationshipType) -> usize {
+        conn.query_row(
+            "SELECT COUNT(*) FROM chunk_relationships WHERE relationship_type = ?1",
+            [relationship_type.as_str()],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap() as usize
+    }
+
+    #[test]
+    fn test_rebuild_relationships_from_existing_chunks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let auth = "pub fn verify_token(token: &str) -> bool {\n    std::env::var(\"JWT_SECRET\").is_ok()\n}\n";
+        let api = "mod auth;\n\npub fn handle() {\n    auth::verify_token(\"t\");\n}\n";
+        let auth_test = "#[test]\nfn test_verify_token() {\n    assert!(verify_token(\"x\"));\n}\n";
+        insert(&conn, ChunkType::RawSource, Some("src/auth.rs"), auth);
+        insert(&conn, ChunkType::RawSource, Some("src/api.rs"), api);
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/auth_test.rs"),
+            auth_test,
+        );
+        insert(
+            &conn,
+            ChunkType::Tests,
+            Some("src/auth_test.rs"),
+            "# Test File",
+        );
+        insert(
+            &conn,
+            ChunkType::StateConfig,
+            Some(".env"),
+            "JWT_SECRET=abc\nPORT=8080\n",
+        );
+        for hash in ["c1", "c2", "c3"] {
+            commit(&conn, hash, &["src/auth.rs", "src/api.rs"]);
+        }
+        commit(&conn, "c4", &["src/auth.rs", "src/auth_test.rs"]);
+
+        let report = rebuild_relationships(&conn, "/p").unwrap();
+        assert_eq!(report.calls, 2); // api.rs y auth_test.rs → auth.rs
+        assert_eq!(report.tests, 1);
+        assert_eq!(report.config_usage, 1);
+        assert_eq!(report.co_changes, 1);
+        assert_eq!(
+            report.relationships_created,
+            report.imports
+                + report.calls
+                + report.tests
+                + report.config_usage
+                + report.co_changes
+                + report.boundaries
+                + report.i18n_usage
+        );
+
+        // Reconstruir reemplaza las relaciones en lugar de duplicarlas
+        let again = rebuild_relationships(&conn, "/p").unwrap();
+        assert_eq!(again.relationships_created, report.relationships_created);
+        assert_eq!(count(&conn, RelationshipType::TestedBy), 1);
+        assert_eq!(count(&conn, RelationshipType::ModifiedWith), 1);
+    }
+
+    #[test]
+    fn test_entity_calls_link_caller_and_callee_chunks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/billing.rs"),
+            "pub struct Invoice;\n\nimpl Invoice {\n    pub fn new() -> Self {\n        Invoice\n    }\n}\n",
+        );
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/lib.rs"),
+            "pub fn total() -> Invoice {\n    let items: Vec<u32> = Vec::new();\n    Invoice::new()\n}\n",
+        );
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("app/pricing.py"),
+            "def apply(cents):\n    return cents\n",
+        );
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("app/main.py"),
+            "from app.pricing import apply\n\napply(1)\n",
+        );
+        // Métodos de un valor: solo se enlazan si el archivo importa al que define `apply`
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("app/cart.py"),
+            "def checkout(discount):\n    return discount.apply(2)\n",
+        );
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("app/report.py"),
+            "import app.pricing as pricing\n\npricing.apply(3)\n",
+        );
+        insert_entity(&conn, "src/billing.rs", "Invoice", (1, 1));
+        let new = insert_entity(&conn, "src/billing.rs", "Invoice::new", (4, 6));
+        let total = insert_entity(&conn, "src/lib.rs", "total", (1, 4));
+        let apply = insert_entity(&conn, "app/pricing.py", "apply", (1, 2));
+        rebuild_relationships(&conn, "/p").unwrap();
+
+        let callers = |chunk_id: i64| -> Vec<(i64, serde_json::Value)> {
+            get_relationships(&conn, chunk_id, false)
+                .unwrap()
+                .into_iter()
+                .filter(|rel| rel.relationship_type == RelationshipType::Calls)
+                .map(|rel| {
+                    let metadata = serde_json::from_str(rel.metadata.as_deref().unwrap()).unwrap();
+                    (rel.from_chunk_id, metadata)
+                })
+                .collect()
+        };
+
+        // Quién llama a `Invoice::new`: `total`, por el nombre completo (`Vec::new` no cuenta)
+        let new_callers = callers(new);
+        assert_eq!(new_callers.len(), 1);
+        assert_eq!(new_callers[0].0, total);
+        assert_eq!(
+            new_callers[0].1["calls"],
+            serde_json::json!(["Invoice::new"])
+        );
+        assert_eq!(new_callers[0].1["lines"], serde_json::json!([3]));
+
+        // Llamada fuera de toda entidad: sale del raw source del archivo
+        let mut apply_callers = callers(apply);
+        apply_callers.sort_by_key(|(_, metadata)| metadata["from_file"].to_string());
+        let from_files: Vec<&serde_json::Value> = apply_callers
+            .iter()
+            .map(|(_, metadata)| &metadata["from_file"])
+            .collect();
+        assert_eq!(from_files, vec!["app/main.py", "app/report.py"]);
+        assert!(apply_callers[0].1["from_entity"].is_null());
+        assert_eq!(apply_callers[0].1["to_entity"], "apply");
+    }
+
+    fn call_edges_by_file(conn: &Connection) -> Vec<(String, String, i64)> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT json_extract(metadata, '$.from_file'), json_extract(metadata, '$.to_file'), id
+                 FROM chunk_relationships WHERE relationship_type = 'calls' ORDER BY 1, 2",
+            )
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_refresh_only_replaces_edges_of_affected_files() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/a.rs"),
+            "pub fn run() {\n    helper();\n}\n",
+        );
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/b.rs"),
+            "pub fn helper() {}\n",
+        );
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/x.rs"),
+            "pub fn start() {\n    finish();\n}\n",
+        );
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/y.rs"),
+            "pub fn finish() {}\n",
+        );
+        rebuild_relationships(&conn, "/p").unwrap();
+        let before = call_edges_by_file(&conn);
+        assert_eq!(before.len(), 2);
+        let untouched = before[1].clone();
+        assert_eq!(
+            (untouched.0.as_str(), untouched.1.as_str()),
+            ("src/x.rs", "src/y.rs")
+        );
+
+        // `helper` se mueve de b.rs a un archivo nuevo
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/b.rs"),
+            "pub fn other() {}\n",
+        );
+        insert(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/c.rs"),
+            "pub fn helper() {}\n",
+        );
+        let report = refresh_relationships(
+            &conn,
+            "/p",
+            &["src/b.rs".to_string(), "src/c.rs".to_string()],
+        )
+        .unwrap();
+        assert_eq!(report.calls, 1);
+
+        let after = call_edges_by_file(&conn);
+        assert_eq!(after.len(), 2);
+        assert_eq!(
+            (after[0].0.as_str(), after[0].1.as_str()),
+            ("src/a.rs", "src/c.rs")
+        );
+        // La relación de los archivos no afectados no se ha tocado
+        assert_eq!(after[1], untouched);
+    }
+
+    #[test]
+    fn test_tested_stem() {
+        assert_eq!(tested_stem("src/login_test.rs").as_deref(), Some("login"));
+        assert_eq!(tested_stem("tests/test_login.py").as_deref(), Some("login"));
+        assert_eq!(tested_stem("src/login.test.ts").as_deref(), Some("login"));
+        assert_eq!(tested_stem("src/login.spec.js").as_deref(), Some("login"));
+        assert_eq!(tested_stem("src/login.rs"), None);
+    }
+}
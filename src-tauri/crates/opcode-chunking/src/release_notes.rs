@@ -0,0 +1,207 @@
+use super::storage::{get_error_logs, get_snapshot, get_snapshots};
+use super::types::{DiffSummary, ErrorLog, Snapshot, SnapshotType};
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+/// Máximo de errores listados por sección
+const MAX_LISTED_ERRORS: usize = 10;
+
+/// Genera las release notes en Markdown para los snapshots master entre `from` (excluido)
+/// y `to` (incluido): mensajes del usuario, resúmenes de diff y errores resueltos
+pub fn build_release_notes(
+    conn: &Connection,
+    from_snapshot_id: i64,
+    to_snapshot_id: i64,
+) -> Result<String> {
+    let from = get_snapshot(conn, from_snapshot_id)?
+        .with_context(|| format!("Snapshot {} not found", from_snapshot_id))?;
+    let to = get_snapshot(conn, to_snapshot_id)?
+        .with_context(|| format!("Snapshot {} not found", to_snapshot_id))?;
+    if from.snapshot_type != SnapshotType::Master || to.snapshot_type != SnapshotType::Master {
+        anyhow::bail!("Release notes require master snapshots");
+    }
+    if from.project_path != to.project_path {
+        anyhow::bail!("Snapshots belong to different projects");
+    }
+
+    let mut snapshots: Vec<Snapshot> =
+        get_snapshots(conn, &from.project_path, Some(SnapshotType::Master))?
+            .into_iter()
+            .filter(|s| s.version_major > from.version_major && s.version_major <= to.version_major)
+            .collect();
+    snapshots.sort_by_key(|s| s.version_major);
+
+    let errors = get_error_logs(conn, &from.project_path, true)?;
+    let in_range = |e: &&ErrorLog| e.last_seen > from.created_at && e.last_seen <= to.created_at;
+    let mut fixed: Vec<&ErrorLog> = errors
+        .iter()
+        .filter(|e| e.is_resolved && e.severity.as_deref() != Some("warning"))
+        .filter(in_range)
+        .collect();
+    fixed.sort_by_key(|e| std::cmp::Reverse(e.occurrence_count));
+
+    let range_ids: Vec<Option<i64>> = snapshots.iter().map(|s| s.id).collect();
+    let mut known: Vec<&ErrorLog> = errors
+        .iter()
+        .filter(|e| !e.is_resolved && e.snapshot_id.is_some())
+        .filter(|e| range_ids.contains(&e.snapshot_id))
+        .collect();
+    known.sort_by_key(|e| std::cmp::Reverse(e.occurrence_count));
+
+    Ok(render_release_notes(&from, &to, &snapshots, &fixed, &known))
+}
+
+/// Renderiza el documento de release notes
+pub fn render_release_notes(
+    from: &Snapshot,
+    to: &Snapshot,
+    snapshots: &[Snapshot],
+    fixed: &[&ErrorLog],
+    known: &[&ErrorLog],
+) -> String {
+    let mut md = format!(
+        "# Release Notes V{} → V{}\n\n_{} → {}_\n",
+        from.version_major,
+        to.version_major,
+        from.created_at.format("%Y-%m-%d"),
+        to.created_at.format("%Y-%m-%d")
+    );
+
+    md.push_str("\n## Changes\n");
+    if snapshots.is_empty() {
+        md.push_str("\n_No master snapshots in this range._\n");
+    }
+    for snapshot in snapshots {
+        let title = snapshot
+            .user_message
+            .as_deref()
+            .unwrap_or(&snapshot.message);
+        md.push_str(&format!(
+            "\n### V{} — {}\n",
+            snapshot.version_major,
+            title.lines().next().unwrap_or("").trim()
+        ));
+        if let Some(summary) = snapshot
+            .diff_summary
+            .as_deref()
+            .filter(|s| !s.trim().is_empty())
+        {
+            match DiffSummary::parse(summary) {
+                Some(parsed) => md.push_str(&format!("\n{}\n", parsed.headline())),
+                None => md.push_str(&format!("\n{}\n", summary.trim())),
+            }
+        }
+        let files: Vec<String> = serde_json::from_str(&snapshot.changed_files).unwrap_or_default();
+        if !files.is_empty() {
+            md.push_str(&format!("\nFiles changed: {}\n", files.len()));
+        }
+    }
+
+    push_error_section(&mut md, "Fixed Issues", fixed);
+    push_error_section(&mut md, "Known Issues", known);
+    md
+}
+
+fn push_error_section(md: &mut String, title: &str, errors: &[&ErrorLog]) {
+    if errors.is_empty() {
+        return;
+    }
+    md.push_str(&format!("\n## {}\n\n", title));
+    for error in errors.iter().take(MAX_LISTED_ERRORS) {
+        let location = error
+            .file_path
+            .as_ref()
+            .map(|f| format!(" (`{}`)", f))
+            .unwrap_or_default();
+        md.push_str(&format!(
+            "- **{}**: {}{}\n",
+            error.error_type,
+            error.message.lines().next().unwrap_or("").trim(),
+            location
+        ));
+    }
+    if errors.len() > MAX_LISTED_ERRORS {
+        md.push_str(&format!(
+            "- …and {} more\n",
+            errors.len() - MAX_LISTED_ERRORS
+        ));
+    }
+}
+
+/// Prompt para pulir el borrador con un LLM sin inventar cambios
+pub fn release_notes_prompt(draft: &str) -> String {
+    format!(
+        "Rewrite the following release notes draft into polished, user-facing release notes in Markdown. \
+         Keep every change and issue listed, group related items, do not invent features, and output only the document.\n\n{}",
+        draft
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{log_error, resolve_error};
+    use crate::storage::{create_snapshot, init_chunk_database};
+    use chrono::{Duration, Utc};
+
+    fn master(version: i32, user_message: &str, offset_minutes: i64) -> Snapshot {
+        Snapshot {
+            id: None,
+            project_path: "/p".to_string(),
+            snapshot_type: SnapshotType::Master,
+            parent_snapshot_id: None,
+            message: format!("V{}", version),
+            user_message: Some(user_message.to_string()),
+            changed_files: r#"["src/a.rs","src/b.rs"]"#.to_string(),
+            diff_summary: Some(format!("Resumen V{}", version)),
+            metadata: None,
+            git_commit_hash: None,
+            git_tag: None,
+            git_branch: None,
+            version_major: version,
+            version_minor: None,
+            created_at: Utc::now() + Duration::minutes(offset_minutes),
+        }
+    }
+
+    #[test]
+    fn test_release_notes_between_master_snapshots() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        let v1 = create_snapshot(&conn, &master(1, "Crear proyecto", -60)).unwrap();
+        let v2 = create_snapshot(&conn, &master(2, "Agregar login", -30)).unwrap();
+        let fixed = log_error(
+            &conn,
+            "/p",
+            "runtime",
+            "token vacío",
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        resolve_error(&conn, fixed).unwrap();
+        log_error(
+            &conn,
+            "/p",
+            "runtime",
+            "timeout en login",
+            None,
+            None,
+            None,
+            Some(v2),
+        )
+        .unwrap();
+        let v3 = create_snapshot(&conn, &master(3, "Agregar logout", 30)).unwrap();
+
+        let notes = build_release_notes(&conn, v1, v3).unwrap();
+        assert!(notes.starts_with("# Release Notes V1 → V3"));
+        assert!(notes.contains("### V2 — Agregar login\n\nResumen V2\n\nFiles changed: 2"));
+        assert!(notes.contains("### V3 — Agregar logout"));
+        assert!(!notes.contains("Crear proyecto"));
+        assert!(notes.contains("## Fixed Issues\n\n- **runtime**: token vacío"));
+        assert!(notes.contains("## Known Issues\n\n- **runtime**: timeout en login"));
+    }
+}
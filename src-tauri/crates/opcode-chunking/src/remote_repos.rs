@@ -0,0 +1,235 @@
+use super::read_only::ensure_index_writable;
+use super::storage::{calculate_content_hash, get_remote_repository, upsert_remote_repository};
+use super::types::RemoteRepository;
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use git2::build::RepoBuilder;
+use git2::{FetchOptions, Repository, ResetType};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+/// Profundidad por defecto al clonar (solo el último commit)
+pub const DEFAULT_CLONE_DEPTH: i32 = 1;
+
+/// Carpeta de la caché donde se clona una URL: `<nombre>-<hash de la url>`
+pub fn remote_cache_path(cache_root: &Path, url: &str) -> PathBuf {
+    let name: String = url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or("repo")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let hash = calculate_content_hash(url);
+    cache_root.join(format!("{}-{}", name, &hash[..12]))
+}
+
+/// Solo se clonan URLs `https://`, `ssh://` o SSH abreviadas (`git@host:ruta`).
+/// `file://`, rutas locales y transportes como `ext::` se rechazan
+pub fn validate_remote_url(url: &str) -> Result<()> {
+    let valid_host = |host: &str| {
+        !host.is_empty()
+            && !host.starts_with('-')
+            && host.chars().all(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':' | '@' | '[' | ']')
+            })
+    };
+    let valid = !url.chars().any(|c| c.is_whitespace() || c.is_control())
+        && if let Some(rest) = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("ssh://"))
+        {
+            rest.split_once('/')
+                .is_some_and(|(host, path)| valid_host(host) && !path.is_empty())
+        } else if let Some(rest) = url.strip_prefix("git@") {
+            rest.split_once(':').is_some_and(|(host, path)| {
+                valid_host(host) && !host.contains(['@', ':']) && !path.is_empty()
+            })
+        } else {
+            false
+        };
+    if !valid {
+        bail!(
+            "Unsupported repository URL '{}': use https://, ssh:// or git@host:path",
+            url
+        );
+    }
+    Ok(())
+}
+
+fn fetch_options(depth: Option<i32>) -> FetchOptions<'static> {
+    let mut options = FetchOptions::new();
+    if let Some(depth) = depth.filter(|d| *d > 0) {
+        options.depth(depth);
+    }
+    options
+}
+
+/// Clona la URL en la caché, o actualiza el clon existente al último commit remoto.
+/// Retorna la ruta del clon y el commit de HEAD
+fn clone_or_update(url: &str, path: &Path, depth: Option<i32>) -> Result<String> {
+    let repo = if path.join(".git").exists() {
+        let repo = Repository::open(path).context("Failed to open cached clone")?;
+        {
+            let mut remote = repo.find_remote("origin")?;
+            if remote.url() != Some(url) {
+                bail!("Cached clone at {} points to another remote", path.display());
+            }
+            remote
+                .fetch(&["HEAD"], Some(&mut fetch_options(depth)), None)
+                .with_context(|| format!("Failed to fetch {}", url))?;
+            let fetched = repo.find_reference("FETCH_HEAD")?.peel_to_commit()?;
+            repo.reset(fetched.as_object(), ResetType::Hard, None)?;
+        }
+        repo
+    } else {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        RepoBuilder::new()
+            .fetch_options(fetch_options(depth))
+            .clone(url, path)
+            .with_context(|| format!("Failed to clone {}", url))?
+    };
+
+    let head = repo.head()?.peel_to_commit()?.id().to_string();
+    Ok(head)
+}
+
+/// Clona (o actualiza) un repositorio remoto en la caché y lo registra como solo lectura.
+/// El índice se genera después con el orquestador sobre `project_path`
+pub fn prepare_remote_repository(
+    conn: &Connection,
+    url: &str,
+    cache_root: &Path,
+    depth: Option<i32>,
+) -> Result<RemoteRepository> {
+    validate_remote_url(url)?;
+    register_clone(conn, url, cache_root, depth)
+}
+
+fn register_clone(
+    conn: &Connection,
+    url: &str,
+    cache_root: &Path,
+    depth: Option<i32>,
+) -> Result<RemoteRepository> {
+    let path = remote_cache_path(cache_root, url);
+    let head_commit = clone_or_update(url, &path, depth)?;
+    let project_path = path.to_string_lossy().to_string();
+
+    let previous = get_remote_repository(conn, &project_path)?;
+    let repository = RemoteRepository {
+        url: url.to_string(),
+        project_path,
+        depth,
+        head_commit: Some(head_commit),
+        cloned_at: Utc::now(),
+        last_indexed_at: previous.and_then(|r| r.last_indexed_at),
+    };
+    upsert_remote_repository(conn, &repository)?;
+    Ok(repository)
+}
+
+/// Falla si el proyecto es un repositorio remoto (indexado en modo solo lectura)
+/// o si su índice está marcado como de solo lectura
+pub fn ensure_writable_project(conn: &Connection, project_path: &str) -> Result<()> {
+    ensure_index_writable(conn, project_path)?;
+    if let Some(remote) = get_remote_repository(conn, project_path)? {
+        bail!(
+            "{} is a read-only reference clone of {}; snapshots are disabled",
+            project_path,
+            remote.url
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use git2::Signature;
+
+    #[test]
+    fn test_cache_path_is_stable_per_url() {
+        let root = Path::new("/cache");
+        let a = remote_cache_path(root, "https://github.com/acme/widgets.git");
+        let b = remote_cache_path(root, "https://github.com/acme/widgets.git");
+        let c = remote_cache_path(root, "git@gitlab.com:other/widgets.git");
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.file_name().unwrap().to_string_lossy().starts_with("widgets-"));
+        assert!(c.file_name().unwrap().to_string_lossy().starts_with("widgets-"));
+    }
+
+    #[test]
+    fn test_only_https_and_ssh_urls_are_accepted() {
+        for url in [
+            "https://github.com/acme/widgets.git",
+            "ssh://git@github.com/acme/widgets.git",
+            "ssh://git@example.com:2222/acme/widgets.git",
+            "git@gitlab.com:other/widgets.git",
+        ] {
+            assert!(validate_remote_url(url).is_ok(), "{}", url);
+        }
+        for url in [
+            "file:///etc",
+            "/home/user/repo",
+            "../repo",
+            "http://github.com/acme/widgets.git",
+            "git://github.com/acme/widgets.git",
+            "ext::sh -c touch% /tmp/pwned",
+            "https://",
+            "https://github.com",
+            "ssh://-oProxyCommand=touch/x",
+            "git@-oProxyCommand=x:repo",
+            "git@host:",
+            "user@host:repo",
+        ] {
+            let err = validate_remote_url(url).unwrap_err();
+            assert!(
+                err.to_string().contains("Unsupported repository URL"),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_clone_registers_read_only_project() {
+        let base = std::env::temp_dir().join(format!("opcode-remote-{}", uuid::Uuid::new_v4()));
+        let origin = base.join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        std::fs::write(origin.join("lib.rs"), "pub fn hello() {}\n").unwrap();
+        let repo = Repository::init(&origin).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let oid = repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[]).unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let url = format!("file://{}", origin.display());
+
+        // Las URLs locales no se aceptan desde fuera; el test clona sin validar. El
+        // transporte local de libgit2 tampoco soporta clones superficiales
+        assert!(prepare_remote_repository(&conn, &url, &base.join("cache"), None).is_err());
+        let repository = register_clone(&conn, &url, &base.join("cache"), None).unwrap();
+        assert_eq!(repository.head_commit, Some(oid.to_string()));
+        assert!(Path::new(&repository.project_path).join("lib.rs").exists());
+        assert!(ensure_writable_project(&conn, &repository.project_path).is_err());
+        assert!(ensure_writable_project(&conn, &origin.to_string_lossy()).is_ok());
+
+        // Un segundo llamado reutiliza el clon
+        let again = register_clone(&conn, &url, &base.join("cache"), None).unwrap();
+        assert_eq!(again.project_path, repository.project_path);
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+}
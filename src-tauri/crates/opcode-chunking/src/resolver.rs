@@ -0,0 +1,715 @@
+use super::entry_points::parse_bin_targets;
+use super::types::{ClassifiedDependency, DependencyKind};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Extensiones probadas al resolver imports JS/TS sin extensión
+const JS_EXTENSIONS: &[&str] = &[
+    "ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs", "mts", "cts", "vue", "svelte",
+];
+
+/// Aliases de un tsconfig/jsconfig (`compilerOptions.baseUrl` + `paths`)
+#[derive(Debug, Clone, Default)]
+pub struct TsPathConfig {
+    /// Directorio del tsconfig relativo al proyecto ("." para la raíz)
+    pub config_dir: String,
+    /// baseUrl resuelto relativo al proyecto
+    pub base_url: Option<String>,
+    /// patrón -> destinos, ej: "@/*" -> ["src/*"] (ya relativos al proyecto)
+    pub paths: Vec<(String, Vec<String>)>,
+}
+
+/// Resuelve especificadores de import a archivos del proyecto
+#[derive(Debug, Clone, Default)]
+pub struct ImportResolver {
+    files: BTreeSet<String>,
+    ts_configs: Vec<TsPathConfig>,
+    /// Paquetes del workspace: nombre de import -> directorio del paquete
+    workspace_packages: BTreeMap<String, String>,
+    /// Ejecutables declarados en los manifiestos: archivo -> nombre del bin
+    bin_targets: BTreeMap<String, String>,
+}
+
+impl ImportResolver {
+    /// Crea un resolver sobre el conjunto de archivos del proyecto (rutas relativas)
+    pub fn new(files: BTreeSet<String>) -> Self {
+        Self {
+            files,
+            ts_configs: Vec::new(),
+            workspace_packages: BTreeMap::new(),
+            bin_targets: BTreeMap::new(),
+        }
+    }
+
+    /// Crea un resolver cargando los tsconfig.json/jsconfig.json presentes en el proyecto
+    pub fn load(project_path: &str, files: BTreeSet<String>) -> Self {
+        let mut resolver = Self::new(files);
+
+        let config_files: Vec<String> = resolver
+            .files
+            .iter()
+            .filter(|f| {
+                let name = f.rsplit('/').next().unwrap_or(f);
+                name == "tsconfig.json" || name == "jsconfig.json"
+            })
+            .cloned()
+            .collect();
+
+        for config_file in config_files {
+            let full_path = Path::new(project_path).join(&config_file);
+            let Ok(content) = std::fs::read_to_string(&full_path) else {
+                continue;
+            };
+            match parse_tsconfig(&parent_dir(&config_file), &content) {
+                Some(config) => resolver.add_ts_config(config),
+                None => log::debug!("Could not parse {}", config_file),
+            }
+        }
+
+        let manifests: Vec<String> = resolver
+            .files
+            .iter()
+            .filter(|f| {
+                let name = f.rsplit('/').next().unwrap_or(f);
+                matches!(name, "package.json" | "Cargo.toml" | "pyproject.toml")
+            })
+            .cloned()
+            .collect();
+
+        for manifest in manifests {
+            let full_path = Path::new(project_path).join(&manifest);
+            let Ok(content) = std::fs::read_to_string(&full_path) else {
+                continue;
+            };
+            let name = manifest.rsplit('/').next().unwrap_or(&manifest);
+            if let Some(package) = parse_manifest_package_name(name, &content) {
+                resolver.add_workspace_package(&package, &parent_dir(&manifest));
+            }
+            for (file, bin) in parse_bin_targets(&manifest, &content, &resolver.files) {
+                resolver.bin_targets.insert(file, bin);
+            }
+        }
+
+        resolver
+    }
+
+    /// Registra un paquete del workspace (los crates Rust se importan con `_` en vez de `-`)
+    pub fn add_workspace_package(&mut self, name: &str, dir: &str) {
+        self.workspace_packages
+            .insert(name.to_string(), dir.to_string());
+        if name.contains('-') {
+            self.workspace_packages
+                .insert(name.replace('-', "_"), dir.to_string());
+        }
+    }
+
+    /// Clasifica un import como interno, de workspace o externo
+    pub fn classify(&self, from_file: &str, specifier: &str) -> ClassifiedDependency {
+        if let Some(resolved) = self.resolve(from_file, specifier) {
+            return ClassifiedDependency {
+                specifier: specifier.to_string(),
+                kind: DependencyKind::Internal,
+                package: None,
+                resolved_file: Some(resolved),
+            };
+        }
+
+        if is_relative_specifier(from_file, specifier) {
+            return ClassifiedDependency {
+                specifier: specifier.to_string(),
+                kind: DependencyKind::Internal,
+                package: None,
+                resolved_file: None,
+            };
+        }
+
+        let package = package_name(from_file, specifier);
+        let kind = if self.workspace_packages.contains_key(&package) {
+            DependencyKind::Workspace
+        } else {
+            DependencyKind::External
+        };
+
+        ClassifiedDependency {
+            specifier: specifier.to_string(),
+            kind,
+            package: Some(package),
+            resolved_file: None,
+        }
+    }
+
+    /// Registra la configuración de aliases de un tsconfig
+    pub fn add_ts_config(&mut self, config: TsPathConfig) {
+        self.ts_configs.push(config);
+        // Los tsconfig más profundos tienen prioridad
+        self.ts_configs
+            .sort_by_key(|c| std::cmp::Reverse(c.config_dir.len()));
+    }
+
+    /// Nombre del ejecutable que un manifiesto del proyecto asocia al archivo
+    pub fn bin_target(&self, file_path: &str) -> Option<&str> {
+        self.bin_targets.get(file_path).map(String::as_str)
+    }
+
+    /// Indica si el archivo existe en el proyecto
+    pub fn contains(&self, file_path: &str) -> bool {
+        self.files.contains(file_path)
+    }
+
+    /// Resuelve el import `specifier` hecho desde `from_file` a un archivo del proyecto
+    pub fn resolve(&self, from_file: &str, specifier: &str) -> Option<String> {
+        match extension_of(from_file).as_str() {
+            "rs" => self.resolve_rust(from_file, specifier),
+            "py" => self.resolve_python(from_file, specifier),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" | "vue" | "svelte" => {
+                self.resolve_js(from_file, specifier)
+            }
+            _ => None,
+        }
+    }
+
+    fn resolve_js(&self, from_file: &str, specifier: &str) -> Option<String> {
+        if specifier.starts_with('.') {
+            let target = normalize_path(&parent_dir(from_file), specifier);
+            return self.probe_js(&target);
+        }
+
+        // Aliases de tsconfig del config más cercano que aplique
+        for config in self
+            .ts_configs
+            .iter()
+            .filter(|c| is_within(from_file, &c.config_dir))
+        {
+            for (pattern, targets) in &config.paths {
+                let Some(captured) = match_path_pattern(pattern, specifier) else {
+                    continue;
+                };
+                for target in targets {
+                    let candidate = target.replacen('*', captured, 1);
+                    if let Some(found) = self.probe_js(&candidate) {
+                        return Some(found);
+                    }
+                }
+            }
+
+            if let Some(base_url) = &config.base_url {
+                if let Some(found) = self.probe_js(&join_path(base_url, specifier)) {
+                    return Some(found);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Prueba el path tal cual, con extensiones y como directorio con index
+    fn probe_js(&self, target: &str) -> Option<String> {
+        if self.files.contains(target) {
+            return Some(target.to_string());
+        }
+
+        // Convención ESM de TypeScript: import "./x.js" apunta a "./x.ts"
+        let stem = Path::new(target)
+            .extension()
+            .and_then(|e| e.to_str())
+            .filter(|e| matches!(*e, "js" | "jsx" | "mjs" | "cjs"))
+            .map(|e| &target[..target.len() - e.len() - 1]);
+
+        for base in [Some(target), stem].into_iter().flatten() {
+            for ext in JS_EXTENSIONS {
+                let candidate = format!("{}.{}", base, ext);
+                if self.files.contains(&candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+
+        for ext in JS_EXTENSIONS {
+            let candidate = join_path(target, &format!("index.{}", ext));
+            if self.files.contains(&candidate) {
+                return Some(candidate);
+            }
+        }
+
+        None
+    }
+
+    fn resolve_rust(&self, from_file: &str, specifier: &str) -> Option<String> {
+        let segments: Vec<&str> = specifier.split("::").filter(|s| !s.is_empty()).collect();
+        let first = *segments.first()?;
+
+        let (start_dir, rest) = match first {
+            "crate" => (self.rust_crate_root(from_file)?, &segments[1..]),
+            "self" => (rust_module_dir(from_file), &segments[1..]),
+            "super" => {
+                let mut dir = rust_module_dir(from_file);
+                let mut idx = 0;
+                while segments.get(idx) == Some(&"super") {
+                    dir = parent_dir(&dir);
+                    idx += 1;
+                }
+                (dir, &segments[idx..])
+            }
+            // Rutas uniformes (2018+): `use foo::bar` puede referirse a un módulo hermano
+            _ => (rust_module_dir(from_file), &segments[..]),
+        };
+
+        let mut dir = start_dir;
+        let mut resolved: Option<String> = None;
+
+        for segment in rest {
+            let file = join_path(&dir, &format!("{}.rs", segment));
+            let mod_file = join_path(&dir, &format!("{}/mod.rs", segment));
+
+            if self.files.contains(&file) {
+                resolved = Some(file);
+            } else if self.files.contains(&mod_file) {
+                resolved = Some(mod_file);
+            } else {
+                break;
+            }
+            dir = join_path(&dir, segment);
+        }
+
+        if resolved.is_none() && first == "crate" {
+            // `use crate::Tipo` apunta a la raíz del crate
+            let root = self.rust_crate_root(from_file)?;
+            return ["lib.rs", "main.rs"]
+                .iter()
+                .map(|f| join_path(&root, f))
+                .find(|f| self.files.contains(f));
+        }
+
+        resolved
+    }
+
+    /// Directorio raíz del crate: el ancestro más cercano con lib.rs o main.rs
+    fn rust_crate_root(&self, from_file: &str) -> Option<String> {
+        let mut dir = parent_dir(from_file);
+        loop {
+            if self.files.contains(&join_path(&dir, "lib.rs"))
+                || self.files.contains(&join_path(&dir, "main.rs"))
+            {
+                return Some(dir);
+            }
+            if dir == "." {
+                return None;
+            }
+            dir = parent_dir(&dir);
+        }
+    }
+
+    fn resolve_python(&self, from_file: &str, specifier: &str) -> Option<String> {
+        if specifier.starts_with('.') {
+            let dots = specifier.chars().take_while(|c| *c == '.').count();
+            let mut base = parent_dir(from_file);
+            for _ in 1..dots {
+                base = parent_dir(&base);
+            }
+            let rest = specifier[dots..].replace('.', "/");
+            return self.probe_python(&join_path(&base, &rest));
+        }
+
+        let as_path = specifier.replace('.', "/");
+
+        // Raíces de búsqueda: raíz del proyecto, src/ y el directorio superior del archivo
+        let mut roots = vec![".".to_string(), "src".to_string()];
+        if let Some(top) = from_file.split('/').next().filter(|t| *t != from_file) {
+            roots.push(top.to_string());
+        }
+
+        for root in roots {
+            if let Some(found) = self.probe_python(&join_path(&root, &as_path)) {
+                return Some(found);
+            }
+        }
+
+        None
+    }
+
+    fn probe_python(&self, target: &str) -> Option<String> {
+        let candidates = [format!("{}.py", target), join_path(target, "__init__.py")];
+        candidates.into_iter().find(|c| self.files.contains(c))
+    }
+}
+
+/// Indica si el import es relativo al propio proyecto aunque no se haya podido resolver
+pub fn is_relative_specifier(from_file: &str, specifier: &str) -> bool {
+    match extension_of(from_file).as_str() {
+        "rs" => {
+            specifier.starts_with("crate::")
+                || specifier.starts_with("self::")
+                || specifier.starts_with("super::")
+        }
+        _ => specifier.starts_with('.') || specifier.starts_with('/'),
+    }
+}
+
+/// Nombre del paquete importado (`@scope/pkg/sub` -> `@scope/pkg`, `serde::de` -> `serde`)
+pub fn package_name(from_file: &str, specifier: &str) -> String {
+    match extension_of(from_file).as_str() {
+        "rs" => specifier.split("::").next().unwrap_or(specifier).to_string(),
+        "py" => specifier.split('.').next().unwrap_or(specifier).to_string(),
+        _ => {
+            let mut parts = specifier.split('/');
+            match parts.next() {
+                Some(scope) if scope.starts_with('@') => {
+                    format!("{}/{}", scope, parts.next().unwrap_or(""))
+                }
+                Some(name) => name.to_string(),
+                None => specifier.to_string(),
+            }
+        }
+    }
+}
+
+/// Extrae el nombre del paquete de un manifiesto (package.json, Cargo.toml, pyproject.toml)
+pub fn parse_manifest_package_name(file_name: &str, content: &str) -> Option<String> {
+    match file_name {
+        "package.json" => serde_json::from_str::<Value>(content)
+            .ok()?
+            .get("name")?
+            .as_str()
+            .map(|s| s.to_string()),
+        "Cargo.toml" | "pyproject.toml" => {
+            let section = if file_name == "Cargo.toml" {
+                "[package]"
+            } else {
+                "[project]"
+            };
+            let name_re = Regex::new(r#"^\s*name\s*=\s*["']([^"']+)["']"#).unwrap();
+            let mut in_section = false;
+            for line in content.lines() {
+                let trimmed = line.trim();
+                if trimmed.starts_with('[') {
+                    in_section = trimmed == section;
+                    continue;
+                }
+                if in_section {
+                    if let Some(cap) = name_re.captures(line) {
+                        return Some(cap[1].to_string());
+                    }
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Parsea un tsconfig (JSON con comentarios y comas finales)
+pub fn parse_tsconfig(config_dir: &str, content: &str) -> Option<TsPathConfig> {
+    let cleaned = strip_json_comments(content);
+    let trailing_commas = Regex::new(r",(\s*[}\]])").unwrap();
+    let cleaned = trailing_commas.replace_all(&cleaned, "$1");
+
+    let json: Value = serde_json::from_str(&cleaned).ok()?;
+    let options = json.get("compilerOptions");
+
+    let base_url = options
+        .and_then(|o| o.get("baseUrl"))
+        .and_then(|v| v.as_str())
+        .map(|b| normalize_path(config_dir, b));
+
+    // Los destinos de `paths` son relativos a baseUrl (o al tsconfig si no hay baseUrl)
+    let paths_base = base_url.clone().unwrap_or_else(|| config_dir.to_string());
+    let mut paths: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    if let Some(map) = options
+        .and_then(|o| o.get("paths"))
+        .and_then(|v| v.as_object())
+    {
+        for (pattern, targets) in map {
+            let targets: Vec<String> = targets
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|t| t.as_str())
+                        .map(|t| normalize_path(&paths_base, t))
+                        .collect()
+                })
+                .unwrap_or_default();
+            paths.insert(pattern.clone(), targets);
+        }
+    }
+
+    Some(TsPathConfig {
+        config_dir: config_dir.to_string(),
+        base_url,
+        paths: paths.into_iter().collect(),
+    })
+}
+
+/// Elimina comentarios `//` y `/* */` fuera de strings
+fn strip_json_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Compara un especificador con un patrón de `paths` ("@/*"); retorna lo capturado por `*`
+fn match_path_pattern<'a>(pattern: &str, specifier: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => specifier
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(suffix)),
+        None => (pattern == specifier).then_some(""),
+    }
+}
+
+/// Directorio del módulo Rust que define un archivo
+/// (`src/foo/mod.rs` -> `src/foo`, `src/foo/bar.rs` -> `src/foo/bar`)
+fn rust_module_dir(file_path: &str) -> String {
+    let dir = parent_dir(file_path);
+    let name = file_path.rsplit('/').next().unwrap_or(file_path);
+    match name {
+        "mod.rs" | "lib.rs" | "main.rs" => dir,
+        _ => join_path(&dir, name.trim_end_matches(".rs")),
+    }
+}
+
+fn is_within(file_path: &str, dir: &str) -> bool {
+    dir == "." || file_path.starts_with(&format!("{}/", dir))
+}
+
+fn extension_of(file_path: &str) -> String {
+    Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Directorio padre de una ruta relativa ("." para la raíz)
+pub fn parent_dir(path: &str) -> String {
+    match path.rfind('/') {
+        Some(idx) => path[..idx].to_string(),
+        None => ".".to_string(),
+    }
+}
+
+fn join_path(base: &str, rest: &str) -> String {
+    if rest.is_empty() {
+        base.to_string()
+    } else if base == "." || base.is_empty() {
+        rest.to_string()
+    } else {
+        format!("{}/{}", base, rest)
+    }
+}
+
+/// Resuelve `spec` relativo al directorio `base`, eliminando `.` y `..`
+pub fn normalize_path(base: &str, spec: &str) -> String {
+    let mut parts: Vec<&str> = if base == "." || spec.starts_with('/') {
+        Vec::new()
+    } else {
+        base.split('/').collect()
+    };
+
+    for segment in spec.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            other => parts.push(other),
+        }
+    }
+
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver(files: &[&str]) -> ImportResolver {
+        ImportResolver::new(files.iter().map(|f| f.to_string()).collect())
+    }
+
+    #[test]
+    fn test_resolve_js_relative() {
+        let r = resolver(&[
+            "src/components/App.tsx",
+            "src/lib/api.ts",
+            "src/components/ui/index.ts",
+            "src/utils.js",
+        ]);
+        assert_eq!(
+            r.resolve("src/components/App.tsx", "../lib/api"),
+            Some("src/lib/api.ts".into())
+        );
+        assert_eq!(
+            r.resolve("src/components/App.tsx", "./ui"),
+            Some("src/components/ui/index.ts".into())
+        );
+        assert_eq!(
+            r.resolve("src/components/App.tsx", "../lib/api.js"),
+            Some("src/lib/api.ts".into())
+        );
+        assert_eq!(
+            r.resolve("src/components/App.tsx", "../utils"),
+            Some("src/utils.js".into())
+        );
+        assert_eq!(r.resolve("src/components/App.tsx", "react"), None);
+    }
+
+    #[test]
+    fn test_resolve_tsconfig_paths() {
+        let mut r = resolver(&["src/lib/api.ts", "src/main.tsx", "tsconfig.json"]);
+        let config = parse_tsconfig(
+            ".",
+            r#"{
+                // comentario
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": { "@/*": ["./src/*"], }, /* alias */
+                },
+            }"#,
+        )
+        .unwrap();
+        r.add_ts_config(config);
+
+        assert_eq!(
+            r.resolve("src/main.tsx", "@/lib/api"),
+            Some("src/lib/api.ts".into())
+        );
+        assert_eq!(
+            r.resolve("src/main.tsx", "src/lib/api"),
+            Some("src/lib/api.ts".into())
+        );
+        assert_eq!(r.resolve("src/main.tsx", "@tauri-apps/api"), None);
+    }
+
+    #[test]
+    fn test_resolve_rust_module_tree() {
+        let r = resolver(&[
+            "src-tauri/src/main.rs",
+            "src-tauri/src/chunking/mod.rs",
+            "src-tauri/src/chunking/types.rs",
+            "src-tauri/src/commands/mod.rs",
+            "src-tauri/src/commands/chunking.rs",
+        ]);
+        assert_eq!(
+            r.resolve(
+                "src-tauri/src/commands/chunking.rs",
+                "crate::chunking::types::Chunk"
+            ),
+            Some("src-tauri/src/chunking/types.rs".into())
+        );
+        assert_eq!(
+            r.resolve("src-tauri/src/chunking/mod.rs", "self::types"),
+            Some("src-tauri/src/chunking/types.rs".into())
+        );
+        assert_eq!(
+            r.resolve(
+                "src-tauri/src/chunking/types.rs",
+                "super::super::commands::chunking"
+            ),
+            Some("src-tauri/src/commands/chunking.rs".into())
+        );
+        assert_eq!(r.resolve("src-tauri/src/main.rs", "std::path::Path"), None);
+    }
+
+    #[test]
+    fn test_classify_dependencies() {
+        let mut r = resolver(&["packages/ui/src/index.ts", "apps/web/src/main.ts"]);
+        r.add_workspace_package("@acme/ui", "packages/ui");
+
+        let internal = r.classify("apps/web/src/main.ts", "../../../packages/ui/src");
+        assert_eq!(internal.kind, DependencyKind::Internal);
+        assert_eq!(internal.resolved_file.as_deref(), Some("packages/ui/src/index.ts"));
+
+        let workspace = r.classify("apps/web/src/main.ts", "@acme/ui/button");
+        assert_eq!(workspace.kind, DependencyKind::Workspace);
+        assert_eq!(workspace.package.as_deref(), Some("@acme/ui"));
+
+        let external = r.classify("apps/web/src/main.ts", "react-dom/client");
+        assert_eq!(external.kind, DependencyKind::External);
+        assert_eq!(external.package.as_deref(), Some("react-dom"));
+
+        let rust = r.classify("src/lib.rs", "reqwest::Client");
+        assert_eq!(rust.kind, DependencyKind::External);
+        assert_eq!(rust.package.as_deref(), Some("reqwest"));
+    }
+
+    #[test]
+    fn test_parse_manifest_package_name() {
+        let cargo = "[workspace]\nmembers = []\n\n[package]\nname = \"opcode-chunking\"\n";
+        assert_eq!(
+            parse_manifest_package_name("Cargo.toml", cargo),
+            Some("opcode-chunking".to_string())
+        );
+        assert_eq!(
+            parse_manifest_package_name("package.json", r#"{"name": "@acme/ui"}"#),
+            Some("@acme/ui".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_python_packages() {
+        let r = resolver(&[
+            "app/__init__.py",
+            "app/models/__init__.py",
+            "app/models/user.py",
+            "app/views/home.py",
+        ]);
+        assert_eq!(
+            r.resolve("app/views/home.py", "..models.user"),
+            Some("app/models/user.py".into())
+        );
+        assert_eq!(
+            r.resolve("app/views/home.py", "app.models"),
+            Some("app/models/__init__.py".into())
+        );
+        assert_eq!(r.resolve("app/views/home.py", "flask"), None);
+    }
+}
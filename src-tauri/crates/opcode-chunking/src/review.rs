@@ -0,0 +1,588 @@
+use super::lint::{enclosing_entity, file_content};
+use super::snapshots::get_snapshot_diff;
+use super::storage::{get_business_rules, get_snapshot, query_chunks};
+use super::types::{
+    Chunk, ChunkQuery, ChunkType, RelatedChunk, RelationshipType, ReviewContext, ReviewFile,
+    SnapshotFileDiff,
+};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Máximo de caracteres de patch por archivo en el Markdown del contexto
+const MAX_MARKDOWN_PATCH_CHARS: usize = 16 * 1024;
+
+/// Reúne el contexto para revisar un cambio: chunks de los archivos modificados,
+/// archivos que los importan, tests que los cubren y reglas de negocio aplicables.
+/// El cambio viene de un snapshot o de un unified diff (`git diff`)
+pub fn build_review_context(
+    conn: &Connection,
+    project_path: &str,
+    diff: Option<&str>,
+    snapshot_id: Option<i64>,
+) -> Result<ReviewContext> {
+    let file_diffs = match (diff, snapshot_id) {
+        (Some(diff), _) => parse_unified_diff(diff),
+        (None, Some(snapshot_id)) => {
+            let snapshot = get_snapshot(conn, snapshot_id)?.context("Snapshot not found")?;
+            if snapshot.project_path != project_path {
+                bail!("Snapshot {} belongs to another project", snapshot_id);
+            }
+            get_snapshot_diff(conn, snapshot_id)?.files
+        }
+        (None, None) => bail!("Either a diff or a snapshot_id is required"),
+    };
+
+    let mut files = Vec::new();
+    let mut changed_chunks = Vec::new();
+    for file_diff in &file_diffs {
+        let changed_entities = match (&file_diff.patch, file_diff.status.as_str()) {
+            (Some(patch), status) if status != "deleted" => {
+                let content = file_content(conn, project_path, &file_diff.file_path);
+                changed_lines(patch)
+                    .into_iter()
+                    .filter_map(|line| content.as_deref().and_then(|c| enclosing_entity(c, line)))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        if let Some(chunk) = latest_file_chunk(conn, project_path, &file_diff.file_path)? {
+            changed_chunks.push(chunk);
+        }
+        files.push(ReviewFile {
+            file_path: file_diff.file_path.clone(),
+            status: file_diff.status.clone(),
+            additions: file_diff.additions,
+            deletions: file_diff.deletions,
+            changed_entities,
+            patch: file_diff.patch.clone(),
+        });
+    }
+
+    let changed: BTreeSet<String> = files.iter().map(|f| f.file_path.clone()).collect();
+    let entities: BTreeSet<String> = files
+        .iter()
+        .flat_map(|f| f.changed_entities.iter().cloned())
+        .collect();
+
+    // Archivos que importan los modificados (relaciones depends_on resueltas)
+    let mut importers: BTreeMap<String, String> = BTreeMap::new();
+    for file in &changed {
+        for importer in importers_of(conn, project_path, file)? {
+            if !changed.contains(&importer) {
+                importers.entry(importer).or_insert_with(|| file.clone());
+            }
+        }
+    }
+
+    let mut tests = Vec::new();
+    let mut test_files = BTreeSet::new();
+    let test_chunks = query_chunks(
+        conn,
+        &ChunkQuery {
+            project_path: Some(project_path.to_string()),
+            chunk_types: Some(vec![ChunkType::Tests]),
+            ..Default::default()
+        },
+    )?;
+    let tested_by = tested_by_files(conn, project_path, &changed)?;
+    for chunk in test_chunks {
+        // Un resultado por archivo de tests: el chunk del archivo, no los de cada función
+        if chunk.entity_name.is_some() {
+            continue;
+        }
+        let Some(test_file) = chunk.file_path.clone() else {
+            continue;
+        };
+        if !test_files.insert(test_file.clone()) {
+            continue;
+        }
+        let reason = if let Some(target) = tested_by.get(&test_file) {
+            Some(format!("tests {}", target))
+        } else if let Some(target) = importers.get(&test_file) {
+            Some(format!("imports {}", target))
+        } else if let Some(target) = changed.iter().find(|f| same_stem(&test_file, f)) {
+            Some(format!("named after {}", target))
+        } else {
+            entities
+                .iter()
+                .find(|e| mentions_word(&chunk.content, e))
+                .map(|e| format!("references {}", e))
+        };
+        if let Some(reason) = reason {
+            tests.push(RelatedChunk { reason, chunk });
+        }
+    }
+
+    let mut callers = Vec::new();
+    for (importer, target) in &importers {
+        // Los tests que importan el archivo ya aparecen en `tests`
+        if tests
+            .iter()
+            .any(|t| t.chunk.file_path.as_deref() == Some(importer.as_str()))
+        {
+            continue;
+        }
+        if let Some(chunk) = latest_file_chunk(conn, project_path, importer)? {
+            callers.push(RelatedChunk {
+                reason: format!("depends on {}", target),
+                chunk,
+            });
+        }
+    }
+
+    let business_rules = get_business_rules(conn, project_path)?
+        .into_iter()
+        .filter(|r| changed.contains(&r.file_path) || entities.contains(&r.entity_name))
+        .collect();
+
+    let mut context = ReviewContext {
+        project_path: project_path.to_string(),
+        snapshot_id: if diff.is_some() { None } else { snapshot_id },
+        files,
+        changed_chunks,
+        callers,
+        tests,
+        business_rules,
+        markdown: String::new(),
+    };
+    context.markdown = render_review_context(&context);
+    Ok(context)
+}
+
+/// Convierte un unified diff (`git diff`, `git show`) en diffs por archivo
+pub fn parse_unified_diff(diff: &str) -> Vec<SnapshotFileDiff> {
+    let mut files: Vec<SnapshotFileDiff> = Vec::new();
+    let mut current: Option<SnapshotFileDiff> = None;
+    let mut patch = String::new();
+    let mut in_hunk = false;
+
+    let finish = |current: &mut Option<SnapshotFileDiff>,
+                  patch: &mut String,
+                  files: &mut Vec<SnapshotFileDiff>| {
+        if let Some(mut file) = current.take() {
+            if !file.file_path.is_empty() {
+                file.patch = Some(std::mem::take(patch));
+                files.push(file);
+            }
+        }
+        patch.clear();
+    };
+    let new_file = || SnapshotFileDiff {
+        file_path: String::new(),
+        old_path: None,
+        status: "modified".to_string(),
+        additions: 0,
+        deletions: 0,
+        is_binary: false,
+        patch: None,
+        truncated: false,
+    };
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            finish(&mut current, &mut patch, &mut files);
+            let mut file = new_file();
+            if let Some((_, b)) = rest.split_once(" b/") {
+                file.file_path = b.to_string();
+            }
+            current = Some(file);
+            in_hunk = false;
+        } else if line.starts_with("--- ") && !in_hunk {
+            if current.is_none() {
+                current = Some(new_file());
+            }
+            if let (Some(file), Some(old)) = (current.as_mut(), line.strip_prefix("--- a/")) {
+                if file.file_path.is_empty() {
+                    file.file_path = old.to_string();
+                }
+            }
+        } else if let Some(new_path) = line.strip_prefix("+++ ").filter(|_| !in_hunk) {
+            if let Some(file) = current.as_mut() {
+                match new_path.strip_prefix("b/") {
+                    Some(path) => file.file_path = path.to_string(),
+                    None if new_path == "/dev/null" => file.status = "deleted".to_string(),
+                    None => file.file_path = new_path.to_string(),
+                }
+            }
+        } else if line.starts_with("new file mode") {
+            if let Some(file) = current.as_mut() {
+                file.status = "added".to_string();
+            }
+        } else if line.starts_with("deleted file mode") {
+            if let Some(file) = current.as_mut() {
+                file.status = "deleted".to_string();
+            }
+        } else if let Some(old) = line.strip_prefix("rename from ") {
+            if let Some(file) = current.as_mut() {
+                file.status = "renamed".to_string();
+                file.old_path = Some(old.to_string());
+            }
+        } else if line.starts_with("Binary files ") {
+            if let Some(file) = current.as_mut() {
+                file.is_binary = true;
+            }
+        } else if line.starts_with("@@") {
+            in_hunk = true;
+            patch.push_str(line);
+            patch.push('\n');
+            continue;
+        } else if in_hunk {
+            if let Some(file) = current.as_mut() {
+                if line.starts_with('+') {
+                    file.additions += 1;
+                } else if line.starts_with('-') {
+                    file.deletions += 1;
+                }
+            }
+            patch.push_str(line);
+            patch.push('\n');
+            continue;
+        }
+        if !in_hunk {
+            patch.push_str(line);
+            patch.push('\n');
+        }
+    }
+    finish(&mut current, &mut patch, &mut files);
+
+    files
+}
+
+/// Líneas (1-based, en el archivo nuevo) agregadas o junto a las eliminadas
+fn changed_lines(patch: &str) -> Vec<usize> {
+    let hunk_re = Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,\d+)? @@").unwrap();
+    let mut lines = Vec::new();
+    let mut new_line = 0usize;
+    let mut in_hunk = false;
+
+    for line in patch.lines() {
+        if let Some(cap) = hunk_re.captures(line) {
+            new_line = cap[1].parse().unwrap_or(1);
+            in_hunk = true;
+        } else if !in_hunk {
+            continue;
+        } else if line.starts_with('+') {
+            lines.push(new_line);
+            new_line += 1;
+        } else if line.starts_with('-') {
+            lines.push(new_line.max(1));
+        } else if !line.starts_with('\\') {
+            new_line += 1;
+        }
+    }
+
+    lines
+}
+
+/// Chunk AST del archivo (más compacto), o el código completo si no hay AST
+fn latest_file_chunk(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+) -> Result<Option<Chunk>> {
+    for chunk_type in [ChunkType::Ast, ChunkType::RawSource] {
+        let chunk = query_chunks(
+            conn,
+            &ChunkQuery {
+                project_path: Some(project_path.to_string()),
+                chunk_types: Some(vec![chunk_type]),
+                file_path: Some(file_path.to_string()),
+                limit: Some(1),
+                ..Default::default()
+            },
+        )?
+        .into_iter()
+        .next();
+        if chunk.is_some() {
+            return Ok(chunk);
+        }
+    }
+    Ok(None)
+}
+
+/// Archivos que importan `file_path` según las relaciones depends_on entre archivos
+fn importers_of(conn: &Connection, project_path: &str, file_path: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT json_extract(r.metadata, '$.from_file') FROM chunk_relationships r
+         JOIN chunks c ON c.id = r.from_chunk_id
+         WHERE c.project_path = ?1 AND r.relationship_type = ?2
+           AND json_extract(r.metadata, '$.to_file') = ?3
+           AND json_extract(r.metadata, '$.from_file') IS NOT NULL",
+    )?;
+    let files = stmt
+        .query_map(
+            params![
+                project_path,
+                RelationshipType::DependsOn.as_str(),
+                file_path
+            ],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(files)
+}
+
+/// Archivo de tests -> archivo modificado, según relaciones tested_by
+fn tested_by_files(
+    conn: &Connection,
+    project_path: &str,
+    changed: &BTreeSet<String>,
+) -> Result<BTreeMap<String, String>> {
+    let mut stmt = conn.prepare(
+        "SELECT src.file_path, test.file_path FROM chunk_relationships r
+         JOIN chunks src ON src.id = r.from_chunk_id
+         JOIN chunks test ON test.id = r.to_chunk_id
+         WHERE src.project_path = ?1 AND r.relationship_type = ?2
+           AND src.file_path IS NOT NULL AND test.file_path IS NOT NULL",
+    )?;
+    let pairs = stmt
+        .query_map(
+            params![project_path, RelationshipType::TestedBy.as_str()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(pairs
+        .into_iter()
+        .filter(|(source, _)| changed.contains(source))
+        .map(|(source, test)| (test, source))
+        .collect())
+}
+
+/// `login.test.ts`, `test_login.py` o `login_test.go` para `login.ts` / `login.py` / `login.go`
+fn same_stem(test_file: &str, source_file: &str) -> bool {
+    let stem = |path: &str| {
+        Path::new(path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.split('.').next().unwrap_or(n).to_lowercase())
+            .unwrap_or_default()
+    };
+    let source = stem(source_file);
+    let test = stem(test_file);
+    if source.is_empty() || source == test {
+        return false;
+    }
+    test.strip_prefix("test_")
+        .or_else(|| test.strip_suffix("_test"))
+        .or_else(|| test.strip_suffix("_spec"))
+        .map(|t| t == source)
+        .unwrap_or(false)
+        || test_file
+            .to_lowercase()
+            .contains(&format!("/{}.test.", source))
+        || test_file
+            .to_lowercase()
+            .contains(&format!("/{}.spec.", source))
+        || test_file
+            .to_lowercase()
+            .starts_with(&format!("{}.test.", source))
+        || test_file
+            .to_lowercase()
+            .starts_with(&format!("{}.spec.", source))
+}
+
+fn mentions_word(content: &str, word: &str) -> bool {
+    Regex::new(&format!(r"\b{}\b", regex::escape(word)))
+        .map(|re| re.is_match(content))
+        .unwrap_or(false)
+}
+
+/// Renderiza el contexto de revisión como Markdown para el prompt del agente
+pub fn render_review_context(context: &ReviewContext) -> String {
+    let mut md = String::from("# Review Context\n\n## Changed Files\n\n");
+    for file in &context.files {
+        md.push_str(&format!(
+            "- `{}` ({}, +{} -{})",
+            file.file_path, file.status, file.additions, file.deletions
+        ));
+        if !file.changed_entities.is_empty() {
+            md.push_str(&format!(" — {}", file.changed_entities.join(", ")));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("\n## Diff\n");
+    for file in &context.files {
+        let Some(patch) = file.patch.as_deref().filter(|p| !p.trim().is_empty()) else {
+            continue;
+        };
+        let mut end = patch.len().min(MAX_MARKDOWN_PATCH_CHARS);
+        while !patch.is_char_boundary(end) {
+            end -= 1;
+        }
+        md.push_str(&format!(
+            "\n### {}\n\n```diff\n{}",
+            file.file_path,
+            &patch[..end]
+        ));
+        if !patch[..end].ends_with('\n') {
+            md.push('\n');
+        }
+        if end < patch.len() {
+            md.push_str("… (truncated)\n");
+        }
+        md.push_str("```\n");
+    }
+
+    if !context.callers.is_empty() {
+        md.push_str("\n## Callers\n\n");
+        for caller in &context.callers {
+            md.push_str(&format!(
+                "- `{}` {}\n",
+                caller.chunk.file_path.as_deref().unwrap_or(""),
+                caller.reason
+            ));
+        }
+    }
+
+    if !context.tests.is_empty() {
+        md.push_str("\n## Tests\n\n");
+        for test in &context.tests {
+            md.push_str(&format!(
+                "- `{}` ({})\n",
+                test.chunk.file_path.as_deref().unwrap_or(""),
+                test.reason
+            ));
+        }
+    }
+
+    if !context.business_rules.is_empty() {
+        md.push_str("\n## Business Rules\n\n");
+        for rule in &context.business_rules {
+            let description = rule
+                .user_correction
+                .as_deref()
+                .unwrap_or(&rule.rule_description);
+            md.push_str(&format!(
+                "- **{}** (`{}`){}: {}\n",
+                rule.entity_name,
+                rule.file_path,
+                if rule.is_validated {
+                    ""
+                } else {
+                    " [pending validation]"
+                },
+                description.trim()
+            ));
+        }
+    }
+
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{init_chunk_database, insert_relationship, upsert_business_rule};
+    use crate::test_support;
+    use crate::types::{BusinessRule, ChunkRelationship};
+    use chrono::Utc;
+
+    fn chunk(conn: &Connection, chunk_type: ChunkType, file_path: &str, content: &str) -> i64 {
+        test_support::chunk()
+            .chunk_type(chunk_type)
+            .file(file_path)
+            .content(content)
+            .insert(conn)
+    }
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let diff = "diff --git a/src/auth.rs b/src/auth.rs\nindex 1..2 100644\n--- a/src/auth.rs\n+++ b/src/auth.rs\n@@ -1,3 +1,3 @@\n fn login() {\n-    old();\n+    new();\n }\ndiff --git a/README.md b/README.md\nnew file mode 100644\n--- /dev/null\n+++ b/README.md\n@@ -0,0 +1 @@\n+# App\n";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].file_path, "src/auth.rs");
+        assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+        assert_eq!(files[1].status, "added");
+        assert_eq!(
+            changed_lines(files[0].patch.as_deref().unwrap()),
+            vec![2, 2]
+        );
+    }
+
+    #[test]
+    fn test_build_review_context() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let auth = chunk(
+            &conn,
+            ChunkType::RawSource,
+            "src/auth.rs",
+            "pub fn login() {\n    old();\n}\n",
+        );
+        let api = chunk(
+            &conn,
+            ChunkType::RawSource,
+            "src/api.rs",
+            "use crate::auth;\n",
+        );
+        chunk(
+            &conn,
+            ChunkType::Tests,
+            "tests/auth_test.rs",
+            "1. test_login_ok\n",
+        );
+        chunk(
+            &conn,
+            ChunkType::Tests,
+            "tests/other_test.rs",
+            "1. test_unrelated\n",
+        );
+        insert_relationship(
+            &conn,
+            &ChunkRelationship {
+                id: None,
+                from_chunk_id: api,
+                to_chunk_id: auth,
+                relationship_type: RelationshipType::DependsOn,
+                metadata: Some(
+                    r#"{"source":"import_resolution","from_file":"src/api.rs","to_file":"src/auth.rs"}"#
+                        .to_string(),
+                ),
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+        upsert_business_rule(
+            &conn,
+            &BusinessRule {
+                id: None,
+                project_path: "/p".to_string(),
+                entity_name: "login".to_string(),
+                file_path: "src/auth.rs".to_string(),
+                rule_description: "Login locks after 3 failures".to_string(),
+                ai_interpretation: String::new(),
+                user_correction: None,
+                is_validated: true,
+                validation_date: None,
+                tags: Vec::new(),
+                check: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+        )
+        .unwrap();
+
+        let diff = "diff --git a/src/auth.rs b/src/auth.rs\n--- a/src/auth.rs\n+++ b/src/auth.rs\n@@ -1,3 +1,3 @@\n pub fn login() {\n-    old();\n+    new();\n }\n";
+        let context = build_review_context(&conn, "/p", Some(diff), None).unwrap();
+        assert_eq!(context.files[0].changed_entities, vec!["login"]);
+        assert_eq!(context.changed_chunks.len(), 1);
+        assert_eq!(context.callers.len(), 1);
+        assert_eq!(context.callers[0].reason, "depends on src/auth.rs");
+        assert_eq!(context.tests.len(), 1);
+        assert_eq!(
+            context.tests[0].chunk.file_path.as_deref(),
+            Some("tests/auth_test.rs")
+        );
+        assert_eq!(context.business_rules.len(), 1);
+        assert!(context.markdown.contains("```diff\n"));
+        assert!(context.markdown.contains("Login locks after 3 failures"));
+    }
+}
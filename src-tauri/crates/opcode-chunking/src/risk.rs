@@ -0,0 +1,330 @@
+use super::graph_metrics::GRAPH_METRICS_KEY;
+use super::health::MIN_HOTSPOT_COMMITS;
+use super::storage::get_latest_file_contents;
+use super::types::{
+    ChunkType, CommitMetadata, FileRisk, RelationshipType, RiskAssessment, RiskSignal,
+};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Puntaje desde el que un archivo es de riesgo alto y pide revisión humana
+pub const HIGH_RISK_SCORE: u32 = 60;
+
+/// Puntaje desde el que un archivo es de riesgo medio y pide tests adicionales
+pub const MEDIUM_RISK_SCORE: u32 = 30;
+
+/// Señales del puntaje: (nombre, puntos máximos, valor que aporta la mitad de los
+/// puntos). Cada señal satura en sus puntos máximos; los máximos suman 100
+const SIGNALS: [(&str, f64, f64); 4] = [
+    ("errors", 35.0, 3.0),
+    ("churn", 30.0, 5.0),
+    ("complexity", 20.0, 40.0),
+    ("fan_in", 15.0, 5.0),
+];
+
+/// Peso de un error ya resuelto frente a uno abierto: el historial también predice
+const RESOLVED_ERROR_WEIGHT: f64 = 0.5;
+
+/// Evalúa el riesgo de modificar cada archivo combinando su historial de errores
+/// (error_logs), su churn en los commits indexados (los fixes cuentan doble), los
+/// puntos de decisión de su código y cuántos chunks dependen de él. Pensado para que
+/// el agente pida más tests o revisión humana antes de tocar archivos de riesgo.
+/// Los archivos se devuelven de mayor a menor puntaje
+pub fn assess_risk(
+    conn: &Connection,
+    project_path: &str,
+    files: &[String],
+) -> Result<RiskAssessment> {
+    let root = format!("{}/", project_path.trim_end_matches('/'));
+    let files: BTreeSet<String> = files
+        .iter()
+        .map(|f| {
+            f.strip_prefix(&root)
+                .unwrap_or(f)
+                .trim_start_matches("./")
+                .to_string()
+        })
+        .collect();
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+    let churn = churn_by_file(conn, project_path, &files)?;
+    let tested = tested_files(conn, project_path)?;
+
+    let mut risks = Vec::new();
+    for file_path in files {
+        let (open_errors, errors) = error_history(conn, project_path, &file_path)?;
+        let source = sources.get(&file_path);
+        let fan_in = match source {
+            Some((chunk_id, _)) => fan_in(conn, *chunk_id)?,
+            None => 0,
+        };
+        let values = [
+            errors,
+            churn.get(&file_path).copied().unwrap_or(0.0),
+            source.map_or(0, |(_, content)| decision_points(content)) as f64,
+            fan_in as f64,
+        ];
+        let signals: Vec<RiskSignal> = SIGNALS
+            .iter()
+            .zip(values)
+            .map(|((name, max_points, half), value)| RiskSignal {
+                name: name.to_string(),
+                value,
+                points: max_points * value / (value + half),
+                max_points: *max_points,
+            })
+            .collect();
+        let score = signals
+            .iter()
+            .map(|s| s.points)
+            .sum::<f64>()
+            .clamp(0.0, 100.0)
+            .round() as u32;
+        let is_tested = tested.contains(&file_path);
+        let commits = values[1];
+
+        risks.push(FileRisk {
+            level: if score >= HIGH_RISK_SCORE {
+                "high"
+            } else if score >= MEDIUM_RISK_SCORE {
+                "medium"
+            } else {
+                "low"
+            }
+            .to_string(),
+            // Un hotspot sin tests pide tests aunque su puntaje sea bajo
+            requires_extra_tests: score >= MEDIUM_RISK_SCORE
+                || (!is_tested && commits >= MIN_HOTSPOT_COMMITS as f64),
+            requires_review: score >= HIGH_RISK_SCORE,
+            indexed: source.is_some(),
+            tested: is_tested,
+            open_errors,
+            file_path,
+            score,
+            signals,
+        });
+    }
+    risks.sort_by(|a, b| b.score.cmp(&a.score).then(a.file_path.cmp(&b.file_path)));
+
+    Ok(RiskAssessment {
+        project_path: project_path.to_string(),
+        max_score: risks.first().map_or(0, |r| r.score),
+        requires_extra_tests: risks.iter().any(|r| r.requires_extra_tests),
+        requires_review: risks.iter().any(|r| r.requires_review),
+        files: risks,
+        generated_at: Utc::now(),
+    })
+}
+
+/// (errores abiertos, ocurrencias ponderadas) del archivo en error_logs: las abiertas
+/// cuentan completas y las resueltas con `RESOLVED_ERROR_WEIGHT`
+fn error_history(conn: &Connection, project_path: &str, file_path: &str) -> Result<(usize, f64)> {
+    let mut stmt = conn.prepare(
+        "SELECT is_resolved, occurrence_count FROM error_logs
+         WHERE project_path = ?1 AND file_path = ?2",
+    )?;
+    let rows = stmt
+        .query_map(params![project_path, file_path], |row| {
+            Ok((row.get::<_, bool>(0)?, row.get::<_, i64>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let open = rows.iter().filter(|(resolved, _)| !resolved).count();
+    let weighted = rows
+        .iter()
+        .map(|(resolved, occurrences)| {
+            let weight = if *resolved {
+                RESOLVED_ERROR_WEIGHT
+            } else {
+                1.0
+            };
+            weight * (*occurrences).max(1) as f64
+        })
+        .sum();
+    Ok((open, weighted))
+}
+
+/// Commits indexados que tocaron cada archivo pedido; los de tipo `fix` valen doble
+fn churn_by_file(
+    conn: &Connection,
+    project_path: &str,
+    files: &BTreeSet<String>,
+) -> Result<BTreeMap<String, f64>> {
+    let mut stmt = conn.prepare(
+        "SELECT metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND metadata IS NOT NULL
+           AND deleted_at IS NULL",
+    )?;
+    let commits = stmt
+        .query_map(
+            params![project_path, ChunkType::CommitHistory.as_str()],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut churn: BTreeMap<String, f64> = BTreeMap::new();
+    for metadata in commits {
+        let Ok(commit) = serde_json::from_str::<CommitMetadata>(&metadata) else {
+            continue;
+        };
+        let weight = if commit.commit_type.as_deref() == Some("fix") {
+            2.0
+        } else {
+            1.0
+        };
+        let touched: BTreeSet<&String> = commit
+            .files_modified
+            .iter()
+            .filter(|f| files.contains(*f))
+            .collect();
+        for file in touched {
+            *churn.entry(file.clone()).or_default() += weight;
+        }
+    }
+    Ok(churn)
+}
+
+/// Archivos con alguna relación `tested_by` vigente
+fn tested_files(conn: &Connection, project_path: &str) -> Result<BTreeSet<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT c.file_path FROM chunk_relationships r
+         JOIN chunks c ON c.id = r.from_chunk_id
+         WHERE c.project_path = ?1 AND r.relationship_type = ?2
+           AND c.file_path IS NOT NULL AND c.deleted_at IS NULL",
+    )?;
+    let tested = stmt
+        .query_map(
+            params![project_path, RelationshipType::TestedBy.as_str()],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<BTreeSet<_>>>()?;
+    Ok(tested)
+}
+
+/// Fan-in guardado por `compute_graph_metrics` en el chunk del archivo
+fn fan_in(conn: &Connection, chunk_id: i64) -> Result<usize> {
+    let fan_in: Option<i64> = conn.query_row(
+        "SELECT CASE WHEN json_valid(metadata) THEN json_extract(metadata, ?2) END
+         FROM chunks WHERE id = ?1",
+        params![chunk_id, format!("$.{}.fan_in", GRAPH_METRICS_KEY)],
+        |row| row.get(0),
+    )?;
+    Ok(fan_in.unwrap_or(0).max(0) as usize)
+}
+
+/// Estimación de complejidad ciclomática del archivo: ramas (`if`, `match`, `case`,
+/// bucles, `catch`/`except`) y operadores lógicos de corto circuito
+fn decision_points(content: &str) -> usize {
+    let branch_re =
+        Regex::new(r"\b(?:if|elif|for|while|case|match|when|catch|except)\b|&&|\|\|").unwrap();
+    branch_re.find_iter(content).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+
+    fn insert_chunk(
+        conn: &Connection,
+        chunk_type: ChunkType,
+        file_path: Option<&str>,
+        content: &str,
+        metadata: Option<String>,
+    ) {
+        let mut builder = chunk().chunk_type(chunk_type).content(content);
+        if let Some(file_path) = file_path {
+            builder = builder.file(file_path);
+        }
+        if let Some(metadata) = metadata {
+            builder = builder.metadata(metadata);
+        }
+        builder.insert(conn);
+    }
+
+    #[test]
+    fn test_decision_points() {
+        let code = "fn f(x: i32) { if x > 0 && x < 9 { for i in 0..x {} } match x { _ => {} } }";
+        assert_eq!(decision_points(code), 4);
+        assert_eq!(decision_points("// modified\nlet ifx = whiles;"), 0);
+    }
+
+    #[test]
+    fn test_assess_risk() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let branchy = "if a { } else if b { } while c && d || e { match x {} }\n".repeat(10);
+        insert_chunk(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/core.rs"),
+            &branchy,
+            Some(r#"{"graph_metrics":{"fan_in":20,"fan_out":1,"centrality":0.5}}"#.to_string()),
+        );
+        insert_chunk(
+            &conn,
+            ChunkType::RawSource,
+            Some("src/util.rs"),
+            "fn util() {}\n",
+            None,
+        );
+        for i in 0..4 {
+            let metadata = CommitMetadata {
+                commit_hash: format!("c{}", i),
+                author: "dev".to_string(),
+                author_email: "dev@example.com".to_string(),
+                commit_date: Utc::now(),
+                files_modified: vec!["src/core.rs".to_string()],
+                insertions: 0,
+                deletions: 0,
+                commit_type: Some(if i % 2 == 0 { "fix" } else { "feat" }.to_string()),
+                scope: None,
+                breaking_change: false,
+                conventional: true,
+                branches: Vec::new(),
+            };
+            insert_chunk(
+                &conn,
+                ChunkType::CommitHistory,
+                None,
+                &format!("commit {}", i),
+                Some(serde_json::to_string(&metadata).unwrap()),
+            );
+        }
+        let now = Utc::now().to_rfc3339();
+        for (resolved, occurrences) in [(0, 4), (1, 2)] {
+            conn.execute(
+                "INSERT INTO error_logs (project_path, file_path, error_type, message,
+                     occurrence_count, first_seen, last_seen, is_resolved)
+                 VALUES ('/p', 'src/core.rs', 'panic', 'boom', ?1, ?2, ?2, ?3)",
+                params![occurrences, now, resolved],
+            )
+            .unwrap();
+        }
+
+        let report = assess_risk(
+            &conn,
+            "/p",
+            &["/p/src/util.rs".to_string(), "./src/core.rs".to_string()],
+        )
+        .unwrap();
+        let core = &report.files[0];
+        assert_eq!(core.file_path, "src/core.rs");
+        // errores 4 + 0.5 * 2 = 5, churn 2 fixes * 2 + 2 = 6, 60 puntos de decisión
+        let values: Vec<f64> = core.signals.iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![5.0, 6.0, 60.0, 20.0]);
+        assert_eq!(core.score, 62);
+        assert_eq!(core.open_errors, 1);
+        assert_eq!(core.level, "high");
+        assert!(core.requires_review && core.requires_extra_tests);
+
+        let util = &report.files[1];
+        assert_eq!((util.file_path.as_str(), util.score), ("src/util.rs", 0));
+        assert!(util.indexed && !util.requires_extra_tests);
+        assert_eq!(report.max_score, core.score);
+        assert!(report.requires_review);
+    }
+}
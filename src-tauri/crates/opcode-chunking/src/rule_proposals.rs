@@ -0,0 +1,225 @@
+use super::api_surface::diff_public_api;
+use super::ast::detect_language;
+use super::business_rules::propose_business_rule;
+use super::project_config::resolve_options;
+use super::storage::{get_snapshot, set_business_rule_tags};
+use super::types::{ChunkingOptions, SnapshotType};
+use super::version_bump::{blob_content, is_test_path};
+use anyhow::{bail, Context, Result};
+use git2::{Oid, Repository};
+use rusqlite::{params, Connection};
+
+/// Etiqueta de las reglas propuestas automáticamente tras un snapshot agent
+pub const AUTO_PROPOSED_RULE_TAG: &str = "auto-proposed";
+
+/// Tipos de símbolo público que reciben una propuesta de regla
+const PROPOSED_KINDS: [&str; 5] = ["function", "method", "class", "struct", "trait"];
+
+/// Propone una regla de negocio (pendiente de validación) por cada función o clase
+/// pública que el snapshot agent agregó respecto de su commit master. Los símbolos
+/// que ya tienen una regla en el mismo archivo se omiten. Retorna los ids creados
+pub fn propose_rules_for_agent_snapshot(conn: &Connection, snapshot_id: i64) -> Result<Vec<i64>> {
+    let snapshot = get_snapshot(conn, snapshot_id)?
+        .with_context(|| format!("Snapshot {} not found", snapshot_id))?;
+    if snapshot.snapshot_type != SnapshotType::Agent {
+        bail!("Snapshot {} is not an agent snapshot", snapshot_id);
+    }
+    let hash = snapshot
+        .git_commit_hash
+        .as_deref()
+        .with_context(|| format!("Snapshot {} has no git commit", snapshot_id))?;
+
+    let repo = Repository::open(&snapshot.project_path).context("Failed to open git repository")?;
+    let commit = repo.find_commit(Oid::from_str(hash)?)?;
+    let new_tree = commit.tree()?;
+    let old_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+    let paths: Vec<String> = diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path()?.to_str().map(str::to_string))
+        .collect();
+
+    let version = format!(
+        "V{}.{}",
+        snapshot.version_major,
+        snapshot.version_minor.unwrap_or(0)
+    );
+    let mut proposed = Vec::new();
+    for path in paths {
+        if detect_language(&path).is_err() || is_test_path(&path) {
+            continue;
+        }
+        let Some(new_content) = blob_content(&repo, &new_tree, &path) else {
+            continue;
+        };
+        let old_content = old_tree
+            .as_ref()
+            .and_then(|tree| blob_content(&repo, tree, &path));
+        let api_diff = match diff_public_api(&path, old_content.as_deref(), Some(&new_content)) {
+            Ok(api_diff) => api_diff,
+            Err(e) => {
+                log::warn!("Public API of {} not analyzed: {}", path, e);
+                continue;
+            }
+        };
+
+        for symbol in api_diff.added {
+            if !PROPOSED_KINDS.contains(&symbol.kind.as_str())
+                || rule_exists(conn, &snapshot.project_path, &symbol.name, &path)?
+            {
+                continue;
+            }
+            let interpretation = format!(
+                "New public {} added by agent snapshot {}: `{}`. Describe the business rule it implements.",
+                symbol.kind,
+                version,
+                symbol.new_signature.as_deref().unwrap_or(&symbol.name)
+            );
+            let rule_id = propose_business_rule(
+                conn,
+                &snapshot.project_path,
+                &symbol.name,
+                &path,
+                &interpretation,
+            )?;
+            set_business_rule_tags(conn, rule_id, &[AUTO_PROPOSED_RULE_TAG.to_string()])?;
+            proposed.push(rule_id);
+        }
+    }
+
+    if !proposed.is_empty() {
+        log::info!(
+            "Proposed {} business rules for agent snapshot {}",
+            proposed.len(),
+            version
+        );
+    }
+    Ok(proposed)
+}
+
+/// Propone las reglas de un snapshot agent recién creado si la política de snapshots
+/// del proyecto lo permite (`propose_rules`). Una falla solo se registra en el log
+pub fn propose_rules_after_agent_snapshot(conn: &Connection, project_path: &str, snapshot_id: i64) {
+    let (options, _) = resolve_options(project_path, &ChunkingOptions::default());
+    if !options.snapshot_policy.propose_rules {
+        return;
+    }
+    if let Err(e) = propose_rules_for_agent_snapshot(conn, snapshot_id) {
+        log::warn!(
+            "Failed to propose business rules for snapshot {}: {}",
+            snapshot_id,
+            e
+        );
+    }
+}
+
+fn rule_exists(
+    conn: &Connection,
+    project_path: &str,
+    entity_name: &str,
+    file_path: &str,
+) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM business_rules
+         WHERE project_path = ?1 AND entity_name = ?2 AND file_path = ?3",
+        params![project_path, entity_name, file_path],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::business_rules::get_pending_rules;
+    use crate::storage::{create_snapshot, init_chunk_database};
+    use crate::types::Snapshot;
+    use chrono::Utc;
+    use git2::{IndexAddOption, Signature};
+
+    fn commit_all(repo: &Repository, message: &str) -> Oid {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => Vec::new(),
+        };
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    fn snapshot(project: &str, snapshot_type: SnapshotType, oid: Oid) -> Snapshot {
+        Snapshot {
+            id: None,
+            project_path: project.to_string(),
+            snapshot_type,
+            parent_snapshot_id: None,
+            message: "snapshot".to_string(),
+            user_message: None,
+            changed_files: "[]".to_string(),
+            diff_summary: None,
+            metadata: None,
+            git_commit_hash: Some(oid.to_string()),
+            git_tag: None,
+            git_branch: None,
+            version_major: 1,
+            version_minor: Some(1),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_proposes_rules_for_new_public_symbols() {
+        let dir = std::env::temp_dir().join(format!("opcode-proposals-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("tests")).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        std::fs::write(dir.join("lib.rs"), "pub fn old() {}\n").unwrap();
+        let master = commit_all(&repo, "master");
+        std::fs::write(
+            dir.join("lib.rs"),
+            "pub fn old() {}\npub fn charge(amount: u64) {}\nfn helper() {}\npub const LIMIT: u32 = 1;\npub struct Invoice;\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("tests/it.rs"), "pub fn fixture() {}\n").unwrap();
+        let agent = commit_all(&repo, "agent");
+        let project = dir.to_str().unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let master_id =
+            create_snapshot(&conn, &snapshot(project, SnapshotType::Master, master)).unwrap();
+        let agent_id =
+            create_snapshot(&conn, &snapshot(project, SnapshotType::Agent, agent)).unwrap();
+
+        assert_eq!(
+            propose_rules_for_agent_snapshot(&conn, agent_id)
+                .unwrap()
+                .len(),
+            2
+        );
+        let rules = get_pending_rules(&conn, project).unwrap();
+        let mut names: Vec<&str> = rules.iter().map(|r| r.entity_name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["Invoice", "charge"]);
+        assert!(rules.iter().all(|r| r.file_path == "lib.rs"
+            && r.tags == vec![AUTO_PROPOSED_RULE_TAG.to_string()]
+            && r.ai_interpretation.contains("agent snapshot V1.1")));
+
+        // Sin duplicados al repetir, y solo para snapshots agent
+        assert!(propose_rules_for_agent_snapshot(&conn, agent_id)
+            .unwrap()
+            .is_empty());
+        assert!(propose_rules_for_agent_snapshot(&conn, master_id).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,226 @@
+use super::storage::{
+    end_agent_session, get_active_snapshot_id, get_agent_session, get_context_deliveries,
+    get_error_logs, get_snapshot, get_snapshots, insert_agent_session,
+};
+use super::types::{AgentSession, AgentSessionReport, AgentSessionStatus, SnapshotType};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use rusqlite::Connection;
+use std::collections::BTreeSet;
+
+/// Abre una sesión de agente. Sin `session_id` se genera uno; sin snapshot master
+/// se usa el snapshot activo del proyecto (el creado antes de enviar el mensaje)
+pub fn start_agent_session(
+    conn: &Connection,
+    project_path: &str,
+    session_id: Option<&str>,
+    master_snapshot_id: Option<i64>,
+    task: Option<&str>,
+) -> Result<AgentSession> {
+    let session_id = session_id
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    if get_agent_session(conn, &session_id)?.is_some() {
+        bail!("Agent session {} already exists", session_id);
+    }
+
+    let master_snapshot_id = match master_snapshot_id {
+        Some(id) => {
+            let snapshot = get_snapshot(conn, id)?.context("Snapshot not found")?;
+            if snapshot.project_path != project_path {
+                bail!("Snapshot {} belongs to another project", id);
+            }
+            if snapshot.snapshot_type != SnapshotType::Master {
+                bail!("Snapshot {} is not a master snapshot", id);
+            }
+            Some(id)
+        }
+        None => get_active_snapshot_id(conn, project_path)?
+            .and_then(|id| get_snapshot(conn, id).ok().flatten())
+            .filter(|s| s.snapshot_type == SnapshotType::Master)
+            .and_then(|s| s.id),
+    };
+
+    let session = AgentSession {
+        id: session_id,
+        project_path: project_path.to_string(),
+        master_snapshot_id,
+        task: task.map(str::to_string),
+        status: AgentSessionStatus::Active,
+        started_at: Utc::now(),
+        ended_at: None,
+    };
+    insert_agent_session(conn, &session)?;
+    Ok(session)
+}
+
+/// Cierra una sesión de agente con su estado final
+pub fn close_agent_session(
+    conn: &Connection,
+    session_id: &str,
+    status: AgentSessionStatus,
+) -> Result<AgentSession> {
+    if status == AgentSessionStatus::Active {
+        bail!("A session can't be closed as active");
+    }
+    let session = get_agent_session(conn, session_id)?.context("Agent session not found")?;
+    if session.status != AgentSessionStatus::Active {
+        bail!(
+            "Agent session {} is already {}",
+            session_id,
+            session.status.as_str()
+        );
+    }
+    end_agent_session(conn, session_id, &status)?;
+    get_agent_session(conn, session_id)?.context("Agent session not found")
+}
+
+/// Reúne lo ocurrido en una sesión: su snapshot master, los snapshots agent de ese
+/// master creados durante la sesión, los errores vistos en ellos mientras estaba
+/// abierta y los chunks que se le entregaron
+pub fn get_agent_session_report(conn: &Connection, session_id: &str) -> Result<AgentSessionReport> {
+    let session = get_agent_session(conn, session_id)?.context("Agent session not found")?;
+    let ended_at = session.ended_at.unwrap_or_else(Utc::now);
+    let in_session = |t: &chrono::DateTime<Utc>| *t >= session.started_at && *t <= ended_at;
+
+    let master_snapshot = match session.master_snapshot_id {
+        Some(id) => get_snapshot(conn, id)?,
+        None => None,
+    };
+
+    let mut agent_snapshots: Vec<_> =
+        get_snapshots(conn, &session.project_path, Some(SnapshotType::Agent))?
+            .into_iter()
+            .filter(|s| {
+                s.parent_snapshot_id.is_some() && s.parent_snapshot_id == session.master_snapshot_id
+            })
+            .filter(|s| in_session(&s.created_at))
+            .collect();
+    agent_snapshots.sort_by_key(|s| s.created_at);
+
+    let snapshot_ids: BTreeSet<i64> = session
+        .master_snapshot_id
+        .into_iter()
+        .chain(agent_snapshots.iter().filter_map(|s| s.id))
+        .collect();
+    let errors = get_error_logs(conn, &session.project_path, true)?
+        .into_iter()
+        .filter(|e| e.snapshot_id.is_some_and(|id| snapshot_ids.contains(&id)))
+        .filter(|e| e.last_seen >= session.started_at && e.first_seen <= ended_at)
+        .collect();
+
+    let mut deliveries =
+        get_context_deliveries(conn, &session.project_path, Some(session_id), None)?;
+    deliveries.reverse();
+    let mut seen = BTreeSet::new();
+    let delivered_chunk_ids = deliveries
+        .iter()
+        .flat_map(|d| d.chunk_ids.iter().copied())
+        .filter(|id| seen.insert(*id))
+        .collect();
+    let delivered_tokens = deliveries.iter().map(|d| d.total_tokens).sum();
+
+    Ok(AgentSessionReport {
+        session,
+        master_snapshot,
+        agent_snapshots,
+        errors,
+        deliveries,
+        delivered_chunk_ids,
+        delivered_tokens,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        create_snapshot, init_chunk_database, insert_context_delivery, upsert_error_log,
+    };
+    use crate::types::{ContextDelivery, ErrorLog, Snapshot};
+
+    fn snapshot(conn: &Connection, snapshot_type: SnapshotType, parent: Option<i64>) -> i64 {
+        create_snapshot(
+            conn,
+            &Snapshot {
+                id: None,
+                project_path: "/p".to_string(),
+                snapshot_type,
+                parent_snapshot_id: parent,
+                message: "snapshot".to_string(),
+                user_message: None,
+                changed_files: "[]".to_string(),
+                diff_summary: None,
+                metadata: None,
+                git_commit_hash: None,
+                git_tag: None,
+                git_branch: None,
+                version_major: 1,
+                version_minor: parent.map(|_| 1),
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_agent_session_report() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let master = snapshot(&conn, SnapshotType::Master, None);
+
+        let session =
+            start_agent_session(&conn, "/p", Some("s1"), None, Some("fix login")).unwrap();
+        assert_eq!(session.master_snapshot_id, Some(master));
+        assert!(start_agent_session(&conn, "/p", Some("s1"), None, None).is_err());
+
+        insert_context_delivery(
+            &conn,
+            &ContextDelivery {
+                id: None,
+                project_path: "/p".to_string(),
+                session_id: Some("s1".to_string()),
+                snapshot_id: Some(master),
+                task_text: "fix login".to_string(),
+                chunk_ids: vec![1, 2],
+                chunk_tokens: vec![10, 20],
+                total_tokens: 30,
+                token_budget: 100,
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+        let agent = snapshot(&conn, SnapshotType::Agent, Some(master));
+        upsert_error_log(
+            &conn,
+            &ErrorLog {
+                id: None,
+                project_path: "/p".to_string(),
+                snapshot_id: Some(agent),
+                file_path: Some("src/login.rs".to_string()),
+                entity_name: None,
+                error_type: "TypeError".to_string(),
+                message: "boom".to_string(),
+                stacktrace: None,
+                severity: None,
+                occurrence_count: 1,
+                first_seen: Utc::now(),
+                last_seen: Utc::now(),
+                is_resolved: false,
+            },
+        )
+        .unwrap();
+
+        let closed = close_agent_session(&conn, "s1", AgentSessionStatus::Completed).unwrap();
+        assert_eq!(closed.status, AgentSessionStatus::Completed);
+        assert!(closed.ended_at.is_some());
+        assert!(close_agent_session(&conn, "s1", AgentSessionStatus::Failed).is_err());
+
+        let report = get_agent_session_report(&conn, "s1").unwrap();
+        assert_eq!(report.master_snapshot.and_then(|s| s.id), Some(master));
+        assert_eq!(report.agent_snapshots.len(), 1);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.delivered_chunk_ids, vec![1, 2]);
+        assert_eq!(report.delivered_tokens, 30);
+    }
+}
@@ -0,0 +1,1144 @@
+use super::index_runs::get_index_state;
+use super::read_only::ensure_index_writable;
+use super::remote_repos::ensure_writable_project;
+use super::storage::{
+    create_snapshot, get_active_snapshot_id, get_snapshot, get_snapshot_file_diffs, get_snapshots,
+    row_timestamp, set_active_snapshot, store_snapshot_diffs,
+};
+use super::types::{
+    DiffSummary, Snapshot, SnapshotDiff, SnapshotEdge, SnapshotFileDiff, SnapshotGraph,
+    SnapshotNode, SnapshotRepairReport, SnapshotType,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use git2::{BranchType, Repository, Signature, IndexAddOption, Oid};
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Tamaño máximo del patch guardado por archivo (se trunca al excederlo)
+pub(crate) const MAX_FILE_PATCH_BYTES: usize = 64 * 1024;
+
+/// Tamaño máximo acumulado de patches por snapshot; el resto guarda solo estadísticas
+const MAX_SNAPSHOT_PATCH_BYTES: usize = 1024 * 1024;
+
+/// Refs que marcan commits de snapshots que no se pudieron deshacer ni registrar
+pub const ORPHANED_SNAPSHOT_REFS: &str = "refs/opcode/orphaned";
+
+/// Objetos Git creados por un snapshot en curso, para deshacerlos si falla
+/// el registro en la base (transacción compensatoria)
+#[derive(Default)]
+struct SnapshotGitChanges {
+    commit: Option<Oid>,
+    tag: Option<String>,
+    branch: Option<String>,
+    /// Rama y commit de HEAD antes del snapshot
+    previous_head: Option<(String, Oid)>,
+}
+
+impl SnapshotGitChanges {
+    /// Deshace tag, rama y commit creados. Si HEAD ya avanzó sobre el commit
+    /// (u otro paso falla) el commit se marca en `refs/opcode/orphaned`
+    fn rollback(&self, repo: &Repository) -> Result<()> {
+        if let (Some(tag), Some(commit)) = (&self.tag, self.commit) {
+            if let Ok(mut reference) = repo.find_reference(&format!("refs/tags/{}", tag)) {
+                if reference.target() == Some(commit) {
+                    reference.delete()?;
+                }
+            }
+        }
+
+        self.restore_head(repo)?;
+
+        // Snapshot agent: basta con borrar su rama
+        if let Some(branch) = &self.branch {
+            if let Ok(mut branch) = repo.find_branch(branch, BranchType::Local) {
+                branch.delete()?;
+            }
+            return Ok(());
+        }
+
+        // Snapshot master: el commit quedó sobre la rama actual
+        let (Some(commit), Some((head_ref, previous))) = (self.commit, &self.previous_head) else {
+            return Ok(());
+        };
+        let head = repo.find_reference(head_ref)?;
+        if head.target() == Some(commit) {
+            let previous = repo.find_commit(*previous)?;
+            repo.reset(previous.as_object(), git2::ResetType::Mixed, None)?;
+        } else {
+            mark_orphaned_commit(repo, commit)?;
+        }
+        Ok(())
+    }
+
+    /// Vuelve HEAD a la rama previa (los snapshots agent hacen checkout de su rama)
+    fn restore_head(&self, repo: &Repository) -> Result<()> {
+        if let (Some(_), Some((head_ref, _))) = (&self.branch, &self.previous_head) {
+            let current = repo.head().ok().and_then(|h| h.name().map(str::to_string));
+            if current.as_deref() != Some(head_ref.as_str()) {
+                repo.set_head(head_ref)?;
+                repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Marca un commit de snapshot sin registro para que `repair_snapshot_divergence` lo reporte
+fn mark_orphaned_commit(repo: &Repository, commit: Oid) -> Result<()> {
+    repo.reference(
+        &format!("{}/{}", ORPHANED_SNAPSHOT_REFS, commit),
+        commit,
+        true,
+        "opcode: orphaned snapshot commit",
+    )?;
+    Ok(())
+}
+
+/// Ejecuta los pasos de un snapshot y, si fallan, compensa los cambios en Git
+fn with_git_compensation(
+    repo: &Repository,
+    steps: impl FnOnce(&mut SnapshotGitChanges) -> Result<i64>,
+) -> Result<i64> {
+    let mut changes = SnapshotGitChanges::default();
+    let result = steps(&mut changes);
+
+    if let Err(e) = &result {
+        match changes.rollback(repo) {
+            Ok(()) => log::warn!("Snapshot failed, git changes rolled back: {}", e),
+            Err(rollback_error) => {
+                log::error!(
+                    "Snapshot failed ({}) and git rollback failed: {}",
+                    e,
+                    rollback_error
+                );
+                if let Some(commit) = changes.commit {
+                    if let Err(mark_error) = mark_orphaned_commit(repo, commit) {
+                        log::error!("Failed to mark orphaned commit {}: {}", commit, mark_error);
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Registra el snapshot y sus diffs en una sola transacción
+fn record_snapshot(
+    conn: &Connection,
+    snapshot: &Snapshot,
+    file_diffs: &[SnapshotFileDiff],
+) -> Result<i64> {
+    let tx = conn.unchecked_transaction()?;
+    let snapshot_id = create_snapshot(&tx, snapshot)?;
+    store_snapshot_diffs(&tx, snapshot_id, file_diffs)?;
+    tx.commit()?;
+    Ok(snapshot_id)
+}
+
+/// Asegura que el proyecto tenga Git inicializado
+/// Si no existe .git, lo inicializa y hace un commit inicial
+pub fn ensure_git_initialized(project_path: &str) -> Result<Repository> {
+    let path = Path::new(project_path);
+    let git_path = path.join(".git");
+
+    if git_path.exists() {
+        // Ya tiene Git, simplemente abrirlo
+        Repository::open(path).context("Failed to open existing Git repository")
+    } else {
+        // Inicializar nuevo repositorio
+        let repo = Repository::init(path).context("Failed to initialize Git repository")?;
+
+        // Crear commit inicial vacío
+        let sig = Signature::now("Opcode Agent", "agent@opcode.local")?;
+        let tree_id = {
+            let mut index = repo.index()?;
+            index.write_tree()?
+        };
+
+        {
+            let tree = repo.find_tree(tree_id)?;
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "chore: initialize opcode chunking system",
+                &tree,
+                &[],
+            )?;
+        }
+
+        println!("[Chunking] Initialized Git repository at: {}", project_path);
+        Ok(repo)
+    }
+}
+
+/// Obtiene el siguiente número de versión master para un proyecto
+fn get_next_master_version(conn: &Connection, project_path: &str) -> Result<i32> {
+    let max_version: Option<i32> = conn
+        .query_row(
+            "SELECT MAX(version_major) FROM snapshots WHERE project_path = ?1 AND snapshot_type = 'master'",
+            rusqlite::params![project_path],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+
+    Ok(max_version.unwrap_or(0) + 1)
+}
+
+/// Obtiene el siguiente número de versión agent para un snapshot master dado
+fn get_next_agent_version(conn: &Connection, project_path: &str, master_version: i32) -> Result<i32> {
+    let max_minor: Option<i32> = conn
+        .query_row(
+            "SELECT MAX(version_minor) FROM snapshots WHERE project_path = ?1 AND snapshot_type = 'agent' AND version_major = ?2",
+            rusqlite::params![project_path, master_version],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+
+    Ok(max_minor.unwrap_or(0) + 1)
+}
+
+/// Obtiene la lista de archivos modificados desde Git
+fn get_changed_files_from_repo(repo: &Repository) -> Result<Vec<String>> {
+    let mut changed_files = Vec::new();
+
+    // Obtener HEAD
+    let head = repo.head()?;
+    let head_tree = head.peel_to_tree()?;
+
+    // Comparar con working directory
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&head_tree), None)?;
+
+    diff.foreach(
+        &mut |delta, _progress| {
+            if let Some(path) = delta.new_file().path() {
+                if let Some(path_str) = path.to_str() {
+                    changed_files.push(path_str.to_string());
+                }
+            }
+            true
+        },
+        None,
+        None,
+        None,
+    )?;
+
+    Ok(changed_files)
+}
+
+/// Calcula el diff por archivo de un commit respecto a su primer padre
+/// (o al árbol vacío si es el commit inicial), con patches de tamaño limitado
+fn commit_file_diffs(repo: &Repository, commit_oid: Oid) -> Result<Vec<SnapshotFileDiff>> {
+    let commit = repo.find_commit(commit_oid)?;
+    let new_tree = commit.tree()?;
+    let old_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+
+    let mut diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+    diff.find_similar(None)?;
+
+    let mut files = Vec::new();
+    let mut patch_budget = MAX_SNAPSHOT_PATCH_BYTES;
+    for idx in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(idx) else {
+            continue;
+        };
+        let new_path = delta.new_file().path().map(|p| p.to_string_lossy().to_string());
+        let old_path = delta.old_file().path().map(|p| p.to_string_lossy().to_string());
+        let file_path = new_path.clone().or_else(|| old_path.clone()).unwrap_or_default();
+        let status = delta_status(delta.status()).to_string();
+
+        let mut file = SnapshotFileDiff {
+            old_path: old_path.filter(|old| Some(old) != new_path.as_ref()),
+            file_path,
+            status,
+            additions: 0,
+            deletions: 0,
+            is_binary: delta.flags().is_binary(),
+            patch: None,
+            truncated: false,
+        };
+
+        if let Some(mut patch) = git2::Patch::from_diff(&diff, idx)? {
+            let (_, additions, deletions) = patch.line_stats()?;
+            file.additions = additions;
+            file.deletions = deletions;
+            file.is_binary |= patch.delta().flags().is_binary();
+
+            if !file.is_binary {
+                let buf = patch.to_buf()?;
+                let text = String::from_utf8_lossy(&buf).to_string();
+                if patch_budget == 0 {
+                    file.truncated = true;
+                } else {
+                    let limit = MAX_FILE_PATCH_BYTES.min(patch_budget);
+                    let (text, truncated) = truncate_patch(text, limit);
+                    patch_budget = patch_budget.saturating_sub(text.len());
+                    file.patch = Some(text);
+                    file.truncated = truncated;
+                }
+            }
+        }
+
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+/// Corta el patch en el último salto de línea antes del límite
+pub(crate) fn truncate_patch(text: String, limit: usize) -> (String, bool) {
+    if text.len() <= limit {
+        return (text, false);
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = text[..end].rfind('\n').map(|i| i + 1).unwrap_or(end);
+    (text[..end].to_string(), true)
+}
+
+/// Resumen estructurado (JSON) para `Snapshot.diff_summary`
+fn summarize_file_diffs(files: &[SnapshotFileDiff]) -> Result<String> {
+    Ok(serde_json::to_string(&DiffSummary::from_file_diffs(files))?)
+}
+
+/// Estado del archivo en el vocabulario de `FileChangeStat.status`
+fn delta_status(status: git2::Delta) -> &'static str {
+    match status {
+        git2::Delta::Added | git2::Delta::Untracked => "added",
+        git2::Delta::Deleted => "deleted",
+        git2::Delta::Renamed => "renamed",
+        git2::Delta::Copied => "copied",
+        git2::Delta::Typechange => "typechange",
+        _ => "modified",
+    }
+}
+
+/// Obtiene el diff de un snapshot. Los snapshots creados antes de guardar diffs
+/// (o restaurados desde notas) lo calculan desde su commit y lo guardan
+pub fn get_snapshot_diff(conn: &Connection, snapshot_id: i64) -> Result<SnapshotDiff> {
+    let mut files = get_snapshot_file_diffs(conn, snapshot_id)?;
+
+    if files.is_empty() {
+        let snapshot = get_snapshot(conn, snapshot_id)?.context("Snapshot not found")?;
+        if let Some(hash) = &snapshot.git_commit_hash {
+            if let Ok(repo) = Repository::open(&snapshot.project_path) {
+                if let Ok(oid) = Oid::from_str(hash) {
+                    if repo.find_commit(oid).is_ok() {
+                        files = commit_file_diffs(&repo, oid)?;
+                        store_snapshot_diffs(conn, snapshot_id, &files)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(SnapshotDiff {
+        snapshot_id,
+        total_additions: files.iter().map(|f| f.additions).sum(),
+        total_deletions: files.iter().map(|f| f.deletions).sum(),
+        files,
+    })
+}
+
+/// Crea un snapshot MASTER con commit y tag de Git
+/// Versión: V1, V2, V3, etc.
+/// Se ejecuta ANTES de enviar un mensaje al agente
+/// La metadata guarda el `IndexState` del proyecto en `index_state` (antes de reindexar)
+pub fn create_master_snapshot_with_git(
+    conn: &Connection,
+    project_path: &str,
+    user_message: &str,
+) -> Result<i64> {
+    ensure_writable_project(conn, project_path)?;
+
+    // Asegurar que Git esté inicializado
+    let repo = ensure_git_initialized(project_path)?;
+
+    with_git_compensation(&repo, |changes| {
+        master_snapshot_steps(conn, &repo, project_path, user_message, changes)
+    })
+}
+
+fn master_snapshot_steps(
+    conn: &Connection,
+    repo: &Repository,
+    project_path: &str,
+    user_message: &str,
+    changes: &mut SnapshotGitChanges,
+) -> Result<i64> {
+    // Obtener la versión siguiente
+    let version = get_next_master_version(conn, project_path)?;
+    let tag_name = format!("v{}", version);
+
+    // Hacer commit de todos los cambios actuales
+    let sig = Signature::now("Opcode User", "user@opcode.local")?;
+
+    // Stage todos los archivos (git add -A)
+    let mut index = repo.index()?;
+    index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+
+    let tree_id = index.write_tree()?;
+    let tree = repo.find_tree(tree_id)?;
+
+    // Obtener archivos modificados antes del commit
+    let changed_files = get_changed_files_from_repo(repo).unwrap_or_default();
+
+    let commit_message = format!("Master snapshot V{}: {}", version, user_message);
+
+    // Crear commit
+    let head = repo.head()?;
+    let parent_commit = head.peel_to_commit()?;
+    if let Some(head_ref) = head.name() {
+        changes.previous_head = Some((head_ref.to_string(), parent_commit.id()));
+    }
+    let commit_oid = repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        &commit_message,
+        &tree,
+        &[&parent_commit],
+    )?;
+    changes.commit = Some(commit_oid);
+
+    // Crear tag
+    let commit = repo.find_commit(commit_oid)?;
+    repo.tag_lightweight(&tag_name, commit.as_object(), false)?;
+    changes.tag = Some(tag_name.clone());
+
+    println!(
+        "[Chunking] Created master snapshot V{} with commit {} and tag {}",
+        version,
+        commit_oid,
+        tag_name
+    );
+
+    let file_diffs = commit_file_diffs(repo, commit_oid).unwrap_or_default();
+
+    // Guardar en la base de datos
+    let snapshot = Snapshot {
+        id: None,
+        project_path: project_path.to_string(),
+        snapshot_type: SnapshotType::Master,
+        parent_snapshot_id: None,
+        message: commit_message.clone(),
+        user_message: Some(user_message.to_string()),
+        changed_files: serde_json::to_string(&changed_files)?,
+        diff_summary: Some(summarize_file_diffs(&file_diffs)?),
+        metadata: Some(serde_json::json!({
+            "index_state": get_index_state(conn, project_path)?,
+        }).to_string()),
+        git_commit_hash: Some(commit_oid.to_string()),
+        git_tag: Some(tag_name.clone()),
+        git_branch: Some("main".to_string()),
+        version_major: version,
+        version_minor: None,
+        created_at: Utc::now(),
+    };
+
+    record_snapshot(conn, &snapshot, &file_diffs)
+}
+
+/// Crea un snapshot AGENT en rama paralela con commit y tag
+/// Versión: V{master_version}.{minor} (ej: V1.1, V1.2, V2.1)
+/// Se ejecuta DESPUÉS de que el agente completa una ejecución
+/// La metadata guarda el `IndexState` del proyecto en `index_state`, como en master
+pub fn create_agent_snapshot_with_git(
+    conn: &Connection,
+    project_path: &str,
+    master_snapshot_id: i64,
+    message: &str,
+    changed_files_override: Option<Vec<String>>,
+) -> Result<i64> {
+    ensure_writable_project(conn, project_path)?;
+
+    // Asegurar que Git esté inicializado
+    let repo = ensure_git_initialized(project_path)?;
+
+    with_git_compensation(&repo, |changes| {
+        agent_snapshot_steps(
+            conn,
+            &repo,
+            project_path,
+            master_snapshot_id,
+            message,
+            changed_files_override,
+            changes,
+        )
+    })
+}
+
+fn agent_snapshot_steps(
+    conn: &Connection,
+    repo: &Repository,
+    project_path: &str,
+    master_snapshot_id: i64,
+    message: &str,
+    changed_files_override: Option<Vec<String>>,
+    changes: &mut SnapshotGitChanges,
+) -> Result<i64> {
+    // Obtener el snapshot master padre
+    let master_snapshot: Snapshot = conn.query_row(
+        "SELECT id, project_path, snapshot_type, parent_snapshot_id, message, user_message, changed_files, diff_summary, metadata, git_commit_hash, git_tag, git_branch, version_major, version_minor, created_at
+         FROM snapshots WHERE id = ?1",
+        rusqlite::params![master_snapshot_id],
+        |row| {
+            let snapshot_type_str: String = row.get(2)?;
+            Ok(Snapshot {
+                id: Some(row.get(0)?),
+                project_path: row.get(1)?,
+                snapshot_type: if snapshot_type_str == "master" {
+                    SnapshotType::Master
+                } else {
+                    SnapshotType::Agent
+                },
+                parent_snapshot_id: row.get(3)?,
+                message: row.get(4)?,
+                user_message: row.get(5)?,
+                changed_files: row.get(6)?,
+                diff_summary: row.get(7)?,
+                metadata: row.get(8)?,
+                git_commit_hash: row.get(9)?,
+                git_tag: row.get(10)?,
+                git_branch: row.get(11)?,
+                version_major: row.get(12)?,
+                version_minor: row.get(13)?,
+                created_at: row_timestamp(row, 14)?,
+            })
+        },
+    )?;
+
+    let master_version = master_snapshot.version_major;
+    let agent_version = get_next_agent_version(conn, project_path, master_version)?;
+    let tag_name = format!("v{}.{}", master_version, agent_version);
+    let branch_name = format!("agent/v{}.{}", master_version, agent_version);
+
+    // Obtener el commit hash del master snapshot
+    let master_commit_hash = master_snapshot.git_commit_hash
+        .context("Master snapshot does not have git_commit_hash")?;
+    let master_oid = Oid::from_str(&master_commit_hash)?;
+    let master_commit = repo.find_commit(master_oid)?;
+
+    // Crear rama desde el commit master
+    let head = repo.head()?;
+    if let Some(head_ref) = head.name() {
+        changes.previous_head = Some((head_ref.to_string(), head.peel_to_commit()?.id()));
+    }
+    repo.branch(&branch_name, &master_commit, false)?;
+    changes.branch = Some(branch_name.clone());
+
+    // Cambiar a la nueva rama
+    let branch_ref = format!("refs/heads/{}", branch_name);
+    repo.set_head(&branch_ref)?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+
+    // Stage todos los archivos modificados por el agente
+    let sig = Signature::now("Opcode Agent", "agent@opcode.local")?;
+    let mut index = repo.index()?;
+    index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+
+    let tree_id = index.write_tree()?;
+    let tree = repo.find_tree(tree_id)?;
+
+    // Obtener archivos modificados
+    let changed_files = changed_files_override.unwrap_or_else(|| {
+        get_changed_files_from_repo(repo).unwrap_or_default()
+    });
+
+    let commit_message = format!("Agent snapshot V{}.{}: {}", master_version, agent_version, message);
+
+    // Crear commit en la rama agent
+    let commit_oid = repo.commit(
+        Some(&branch_ref),
+        &sig,
+        &sig,
+        &commit_message,
+        &tree,
+        &[&master_commit],
+    )?;
+    changes.commit = Some(commit_oid);
+
+    // Crear tag
+    let commit = repo.find_commit(commit_oid)?;
+    repo.tag_lightweight(&tag_name, commit.as_object(), false)?;
+    changes.tag = Some(tag_name.clone());
+
+    println!(
+        "[Chunking] Created agent snapshot V{}.{} on branch {} with commit {} and tag {}",
+        master_version,
+        agent_version,
+        branch_name,
+        commit_oid,
+        tag_name
+    );
+
+    // Volver a la rama main
+    repo.set_head("refs/heads/main")?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+
+    let file_diffs = commit_file_diffs(repo, commit_oid).unwrap_or_default();
+
+    // Guardar en la base de datos
+    let snapshot = Snapshot {
+        id: None,
+        project_path: project_path.to_string(),
+        snapshot_type: SnapshotType::Agent,
+        parent_snapshot_id: Some(master_snapshot_id),
+        message: commit_message.clone(),
+        user_message: None,
+        changed_files: serde_json::to_string(&changed_files)?,
+        diff_summary: Some(summarize_file_diffs(&file_diffs)?),
+        metadata: Some(serde_json::json!({
+            "master_version": master_version,
+            "agent_version": agent_version,
+            "index_state": get_index_state(conn, project_path)?,
+        }).to_string()),
+        git_commit_hash: Some(commit_oid.to_string()),
+        git_tag: Some(tag_name.clone()),
+        git_branch: Some(branch_name.clone()),
+        version_major: master_version,
+        version_minor: Some(agent_version),
+        created_at: Utc::now(),
+    };
+
+    record_snapshot(conn, &snapshot, &file_diffs)
+}
+
+/// Retrocede la rama master a un snapshot anterior
+/// Usa push force para reescribir el historial
+/// Elimina snapshots master posteriores de la DB
+/// Preserva las ramas agent paralelas
+pub fn rewind_master_to_snapshot_with_git(
+    conn: &Connection,
+    snapshot_id: i64,
+) -> Result<()> {
+    // Obtener el snapshot
+    let snapshot: Snapshot = conn.query_row(
+        "SELECT id, project_path, snapshot_type, parent_snapshot_id, message, user_message, changed_files, diff_summary, metadata, git_commit_hash, git_tag, git_branch, version_major, version_minor, created_at
+         FROM snapshots WHERE id = ?1",
+        rusqlite::params![snapshot_id],
+        |row| {
+            let snapshot_type_str: String = row.get(2)?;
+            Ok(Snapshot {
+                id: Some(row.get(0)?),
+                project_path: row.get(1)?,
+                snapshot_type: if snapshot_type_str == "master" {
+                    SnapshotType::Master
+                } else {
+                    SnapshotType::Agent
+                },
+                parent_snapshot_id: row.get(3)?,
+                message: row.get(4)?,
+                user_message: row.get(5)?,
+                changed_files: row.get(6)?,
+                diff_summary: row.get(7)?,
+                metadata: row.get(8)?,
+                git_commit_hash: row.get(9)?,
+                git_tag: row.get(10)?,
+                git_branch: row.get(11)?,
+                version_major: row.get(12)?,
+                version_minor: row.get(13)?,
+                created_at: row_timestamp(row, 14)?,
+            })
+        },
+    )?;
+
+    if snapshot.snapshot_type != SnapshotType::Master {
+        anyhow::bail!("Can only rewind to master snapshots");
+    }
+    ensure_writable_project(conn, &snapshot.project_path)?;
+
+    let commit_hash = snapshot.git_commit_hash
+        .context("Snapshot does not have git_commit_hash")?;
+
+    // Abrir repositorio
+    let repo = Repository::open(&snapshot.project_path)?;
+
+    // Reset hard al commit del snapshot
+    let oid = Oid::from_str(&commit_hash)?;
+    let commit = repo.find_commit(oid)?;
+    repo.reset(commit.as_object(), git2::ResetType::Hard, None)?;
+
+    println!(
+        "[Chunking] Rewinded main branch to snapshot V{} (commit: {})",
+        snapshot.version_major,
+        commit_hash
+    );
+
+    // Eliminar snapshots master posteriores de la DB (version_major > snapshot.version_major)
+    conn.execute(
+        "DELETE FROM snapshot_diffs WHERE snapshot_id IN (
+            SELECT id FROM snapshots WHERE project_path = ?1 AND snapshot_type = 'master' AND version_major > ?2
+        )",
+        rusqlite::params![&snapshot.project_path, snapshot.version_major],
+    )?;
+    conn.execute(
+        "UPDATE agent_sessions SET master_snapshot_id = NULL WHERE master_snapshot_id IN (
+            SELECT id FROM snapshots WHERE project_path = ?1 AND snapshot_type = 'master' AND version_major > ?2
+        )",
+        rusqlite::params![&snapshot.project_path, snapshot.version_major],
+    )?;
+    conn.execute(
+        "UPDATE context_deliveries SET snapshot_id = NULL WHERE snapshot_id IN (
+            SELECT id FROM snapshots WHERE project_path = ?1 AND snapshot_type = 'master' AND version_major > ?2
+        )",
+        rusqlite::params![&snapshot.project_path, snapshot.version_major],
+    )?;
+    conn.execute(
+        "DELETE FROM snapshots WHERE project_path = ?1 AND snapshot_type = 'master' AND version_major > ?2",
+        rusqlite::params![&snapshot.project_path, snapshot.version_major],
+    )?;
+
+    println!(
+        "[Chunking] Deleted master snapshots with version > V{}",
+        snapshot.version_major
+    );
+
+    // Las ramas agent paralelas se preservan automáticamente en Git
+    // No se eliminan de la DB ni de Git para mantener historial de lo que se intentó
+
+    // Los nuevos errores se atribuyen al snapshot al que se retrocedió
+    set_active_snapshot(conn, &snapshot.project_path, snapshot_id)?;
+
+    Ok(())
+}
+
+/// Commits creados por `create_*_snapshot_with_git`
+fn is_snapshot_commit(commit: &git2::Commit) -> bool {
+    commit.message().is_some_and(|message| {
+        message.starts_with("Master snapshot V") || message.starts_with("Agent snapshot V")
+    })
+}
+
+/// Tags con el formato de los snapshots (`v3`, `v3.2`)
+fn is_snapshot_tag(tag: &str) -> bool {
+    tag.strip_prefix('v').is_some_and(|version| {
+        let parts: Vec<&str> = version.split('.').collect();
+        parts.len() <= 2
+            && parts
+                .iter()
+                .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Reconcilia los snapshots de la base con Git: restaura los tags faltantes de
+/// snapshots registrados, elimina tags y ramas agent de commits de snapshot sin
+/// registro y reporta los commits perdidos o marcados como huérfanos
+pub fn repair_snapshot_divergence(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<SnapshotRepairReport> {
+    ensure_index_writable(conn, project_path)?;
+    let repo = Repository::open(project_path).context("Failed to open Git repository")?;
+    let snapshots = get_snapshots(conn, project_path, None)?;
+    let registered: HashSet<String> = snapshots
+        .iter()
+        .filter_map(|s| s.git_commit_hash.clone())
+        .collect();
+    let mut report = SnapshotRepairReport {
+        project_path: project_path.to_string(),
+        ..Default::default()
+    };
+
+    for snapshot in &snapshots {
+        let Some(hash) = &snapshot.git_commit_hash else {
+            continue;
+        };
+        let commit = Oid::from_str(hash)
+            .ok()
+            .and_then(|oid| repo.find_commit(oid).ok());
+        let Some(commit) = commit else {
+            report.missing_commits.extend(snapshot.id);
+            continue;
+        };
+        if let Some(tag) = &snapshot.git_tag {
+            if repo.find_reference(&format!("refs/tags/{}", tag)).is_err() {
+                repo.tag_lightweight(tag, commit.as_object(), false)?;
+                report.tags_restored.push(tag.clone());
+            }
+        }
+    }
+
+    let tags: Vec<String> = repo
+        .tag_names(None)?
+        .iter()
+        .flatten()
+        .filter(|tag| is_snapshot_tag(tag))
+        .map(String::from)
+        .collect();
+    for tag in tags {
+        let mut reference = repo.find_reference(&format!("refs/tags/{}", tag))?;
+        let Ok(commit) = reference.peel_to_commit() else {
+            continue;
+        };
+        if is_snapshot_commit(&commit) && !registered.contains(&commit.id().to_string()) {
+            reference.delete()?;
+            report.tags_removed.push(tag);
+        }
+    }
+
+    let current_branch = repo.head().ok().and_then(|h| h.shorthand().map(String::from));
+    let mut orphan_branches = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let Some(name) = branch.name()?.map(String::from) else {
+            continue;
+        };
+        if !name.starts_with("agent/") || current_branch.as_deref() == Some(name.as_str()) {
+            continue;
+        }
+        let commit = branch.get().peel_to_commit()?;
+        if is_snapshot_commit(&commit) && !registered.contains(&commit.id().to_string()) {
+            orphan_branches.push(name);
+        }
+    }
+    for name in orphan_branches {
+        repo.find_branch(&name, BranchType::Local)?.delete()?;
+        report.branches_removed.push(name);
+    }
+
+    // Los marcadores de commits que luego se registraron ya no hacen falta
+    let markers: Vec<(String, Option<Oid>)> = repo
+        .references_glob(&format!("{}/*", ORPHANED_SNAPSHOT_REFS))?
+        .filter_map(|r| r.ok())
+        .filter_map(|r| r.name().map(|name| (name.to_string(), r.target())))
+        .collect();
+    for (name, target) in markers {
+        let Some(commit) = target.map(|oid| oid.to_string()) else {
+            continue;
+        };
+        if registered.contains(&commit) {
+            repo.find_reference(&name)?.delete()?;
+        } else {
+            report.orphaned_commits.push(commit);
+        }
+    }
+
+    log::info!(
+        "Snapshot repair for {}: {} tags restored, {} tags removed, {} branches removed, {} missing commits, {} orphaned commits",
+        project_path,
+        report.tags_restored.len(),
+        report.tags_removed.len(),
+        report.branches_removed.len(),
+        report.missing_commits.len(),
+        report.orphaned_commits.len()
+    );
+
+    Ok(report)
+}
+
+/// Grafo completo de snapshots del proyecto para la vista de timeline: los
+/// master forman la línea principal en orden de versión y cada agent cuelga de
+/// su master padre. Si el repositorio existe, indica qué refs Git siguen vivas
+pub fn get_snapshot_graph(conn: &Connection, project_path: &str) -> Result<SnapshotGraph> {
+    let mut snapshots = get_snapshots(conn, project_path, None)?;
+    snapshots.sort_by_key(|s| (s.version_major, s.version_minor.unwrap_or(0), s.created_at));
+    let active_snapshot_id = get_active_snapshot_id(conn, project_path)?;
+    let repo = Repository::open(project_path).ok();
+
+    let mut nodes = Vec::with_capacity(snapshots.len());
+    for snapshot in &snapshots {
+        let Some(id) = snapshot.id else {
+            continue;
+        };
+        let (commit_exists, tag_exists, branch_exists) = match &repo {
+            Some(repo) => (
+                snapshot.git_commit_hash.as_deref().is_some_and(|hash| {
+                    Oid::from_str(hash).is_ok_and(|oid| repo.find_commit(oid).is_ok())
+                }),
+                snapshot
+                    .git_tag
+                    .as_deref()
+                    .is_some_and(|tag| repo.find_reference(&format!("refs/tags/{}", tag)).is_ok()),
+                snapshot
+                    .git_branch
+                    .as_deref()
+                    .is_some_and(|branch| repo.find_branch(branch, BranchType::Local).is_ok()),
+            ),
+            None => (false, false, false),
+        };
+        let version = match snapshot.version_minor {
+            Some(minor) => format!("V{}.{}", snapshot.version_major, minor),
+            None => format!("V{}", snapshot.version_major),
+        };
+        nodes.push(SnapshotNode {
+            id,
+            snapshot_type: snapshot.snapshot_type.as_str().to_string(),
+            version,
+            version_major: snapshot.version_major,
+            version_minor: snapshot.version_minor,
+            message: snapshot.message.clone(),
+            user_message: snapshot.user_message.clone(),
+            git_commit_hash: snapshot.git_commit_hash.clone(),
+            git_tag: snapshot.git_tag.clone(),
+            git_branch: snapshot.git_branch.clone(),
+            commit_exists,
+            tag_exists,
+            branch_exists,
+            is_active: active_snapshot_id == Some(id),
+            created_at: snapshot.created_at,
+        });
+    }
+
+    let ids: HashSet<i64> = nodes.iter().map(|n| n.id).collect();
+    let mut edges = Vec::new();
+    let mut previous_master: Option<i64> = None;
+    for snapshot in &snapshots {
+        let Some(id) = snapshot.id else {
+            continue;
+        };
+        match snapshot.snapshot_type {
+            // Los master no guardan padre: la línea principal sigue el orden de versión
+            SnapshotType::Master => {
+                if let Some(parent_id) = previous_master {
+                    edges.push(SnapshotEdge {
+                        parent_id,
+                        child_id: id,
+                        kind: "master".to_string(),
+                    });
+                }
+                previous_master = Some(id);
+            }
+            // Un agent cuyo master se eliminó al retroceder queda como raíz
+            SnapshotType::Agent => {
+                if let Some(parent_id) = snapshot.parent_snapshot_id.filter(|p| ids.contains(p)) {
+                    edges.push(SnapshotEdge {
+                        parent_id,
+                        child_id: id,
+                        kind: "branch".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    let current_branch = repo
+        .as_ref()
+        .and_then(|r| r.head().ok())
+        .and_then(|h| h.shorthand().map(String::from));
+
+    Ok(SnapshotGraph {
+        project_path: project_path.to_string(),
+        nodes,
+        edges,
+        active_snapshot_id,
+        current_branch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+
+    fn commit_all(repo: &Repository, message: &str) -> Oid {
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => Vec::new(),
+        };
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_diff_from_commit() {
+        let dir = std::env::temp_dir().join(format!("opcode-diff-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "one\ntwo\n").unwrap();
+        commit_all(&repo, "initial");
+        std::fs::write(dir.join("a.txt"), "one\nthree\n").unwrap();
+        std::fs::write(dir.join("b.txt"), "new\n").unwrap();
+        let oid = commit_all(&repo, "change");
+        let project = dir.to_str().unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let snapshot_id = create_snapshot(
+            &conn,
+            &Snapshot {
+                id: None,
+                project_path: project.to_string(),
+                snapshot_type: SnapshotType::Master,
+                parent_snapshot_id: None,
+                message: "Master snapshot V1: change".to_string(),
+                user_message: None,
+                changed_files: "[]".to_string(),
+                diff_summary: None,
+                metadata: None,
+                git_commit_hash: Some(oid.to_string()),
+                git_tag: None,
+                git_branch: None,
+                version_major: 1,
+                version_minor: None,
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap();
+
+        // Se calcula desde el commit y queda guardado
+        let diff = get_snapshot_diff(&conn, snapshot_id).unwrap();
+        assert_eq!(diff.files.len(), 2);
+        assert_eq!(diff.total_additions, 2);
+        assert_eq!(diff.total_deletions, 1);
+        let a = diff.files.iter().find(|f| f.file_path == "a.txt").unwrap();
+        assert_eq!(a.status, "modified");
+        assert!(a.patch.as_deref().unwrap().contains("+three"));
+        assert_eq!(get_snapshot_file_diffs(&conn, snapshot_id).unwrap().len(), 2);
+        let summary = DiffSummary::parse(&summarize_file_diffs(&diff.files).unwrap()).unwrap();
+        assert_eq!(summary.files_changed, 2);
+        assert_eq!(summary.headline(), "2 files changed, 2 insertions(+), 1 deletions(-)");
+        let b = summary.files.iter().find(|f| f.path == "b.txt").unwrap();
+        assert_eq!((b.status.as_str(), b.additions), ("added", 1));
+        assert!(DiffSummary::parse("3 files changed").is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    fn snapshot_project() -> (std::path::PathBuf, Connection) {
+        let dir = std::env::temp_dir().join(format!("opcode-atomic-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init_opts(
+            &dir,
+            git2::RepositoryInitOptions::new().initial_head("main"),
+        )
+        .unwrap();
+        std::fs::write(dir.join("a.txt"), "one\n").unwrap();
+        commit_all(&repo, "initial");
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        (dir, conn)
+    }
+
+    #[test]
+    fn test_failed_db_write_rolls_back_git_snapshot() {
+        let (dir, conn) = snapshot_project();
+        let project = dir.to_str().unwrap();
+        let repo = Repository::open(&dir).unwrap();
+        let head_before = repo.head().unwrap().peel_to_commit().unwrap().id();
+
+        let v1 = create_master_snapshot_with_git(&conn, project, "first").unwrap();
+        let head_v1 = repo.head().unwrap().peel_to_commit().unwrap().id();
+        assert_ne!(head_v1, head_before);
+
+        // Sin tabla de diffs la transacción falla después de crear commit y tag
+        conn.execute("DROP TABLE snapshot_diffs", []).unwrap();
+        std::fs::write(dir.join("a.txt"), "two\n").unwrap();
+        assert!(create_master_snapshot_with_git(&conn, project, "second").is_err());
+        assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), head_v1);
+        assert!(repo.find_reference("refs/tags/v2").is_err());
+        assert_eq!(get_snapshots(&conn, project, None).unwrap().len(), 1);
+
+        assert!(create_agent_snapshot_with_git(&conn, project, v1, "agent", None).is_err());
+        assert!(repo.find_branch("agent/v1.1", BranchType::Local).is_err());
+        assert!(repo.find_reference("refs/tags/v1.1").is_err());
+        assert_eq!(repo.head().unwrap().shorthand(), Some("main"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_repair_reconciles_tags_and_branches() {
+        let (dir, conn) = snapshot_project();
+        let project = dir.to_str().unwrap();
+        let repo = Repository::open(&dir).unwrap();
+
+        create_master_snapshot_with_git(&conn, project, "first").unwrap();
+        repo.find_reference("refs/tags/v1").unwrap().delete().unwrap();
+
+        // Commit de snapshot con tag y rama pero sin fila en la base
+        std::fs::write(dir.join("a.txt"), "two\n").unwrap();
+        let unregistered = commit_all(&repo, "Agent snapshot V1.1: lost");
+        let commit = repo.find_commit(unregistered).unwrap();
+        repo.tag_lightweight("v1.1", commit.as_object(), false).unwrap();
+        repo.branch("agent/v1.1", &commit, false).unwrap();
+        // Tags propios del usuario: no se tocan
+        repo.tag_lightweight("release-1", commit.as_object(), false).unwrap();
+        std::fs::write(dir.join("a.txt"), "three\n").unwrap();
+        let user_commit = commit_all(&repo, "feat: user work");
+        repo.tag_lightweight("release-2", repo.find_commit(user_commit).unwrap().as_object(), false)
+            .unwrap();
+
+        let report = repair_snapshot_divergence(&conn, project).unwrap();
+        assert_eq!(report.tags_restored, vec!["v1"]);
+        assert_eq!(report.tags_removed, vec!["v1.1"]);
+        assert_eq!(report.branches_removed, vec!["agent/v1.1"]);
+        assert!(report.missing_commits.is_empty());
+        assert!(repo.find_reference("refs/tags/v1").is_ok());
+        assert!(repo.find_reference("refs/tags/release-1").is_ok());
+        assert!(repo.find_reference("refs/tags/release-2").is_ok());
+
+        let again = repair_snapshot_divergence(&conn, project).unwrap();
+        assert!(again.tags_restored.is_empty() && again.tags_removed.is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_snapshot_graph_links_masters_and_agents() {
+        let (dir, conn) = snapshot_project();
+        let project = dir.to_str().unwrap();
+
+        let v1 = create_master_snapshot_with_git(&conn, project, "first").unwrap();
+        std::fs::write(dir.join("a.txt"), "agent\n").unwrap();
+        let v1_1 = create_agent_snapshot_with_git(&conn, project, v1, "agent", None).unwrap();
+        std::fs::write(dir.join("a.txt"), "two\n").unwrap();
+        let v2 = create_master_snapshot_with_git(&conn, project, "second").unwrap();
+
+        let graph = get_snapshot_graph(&conn, project).unwrap();
+        let versions: Vec<&str> = graph.nodes.iter().map(|n| n.version.as_str()).collect();
+        assert_eq!(versions, vec!["V1", "V1.1", "V2"]);
+        let edges: Vec<(i64, i64, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.parent_id, e.child_id, e.kind.as_str()))
+            .collect();
+        assert!(edges.contains(&(v1, v1_1, "branch")));
+        assert!(edges.contains(&(v1, v2, "master")));
+        assert_eq!(edges.len(), 2);
+
+        let agent = graph.nodes.iter().find(|n| n.id == v1_1).unwrap();
+        assert_eq!(agent.git_branch.as_deref(), Some("agent/v1.1"));
+        assert!(agent.commit_exists && agent.tag_exists && agent.branch_exists);
+        Repository::open(&dir)
+            .unwrap()
+            .find_reference("refs/tags/v1.1")
+            .unwrap()
+            .delete()
+            .unwrap();
+        let graph = get_snapshot_graph(&conn, project).unwrap();
+        let agent = graph.nodes.iter().find(|n| n.id == v1_1).unwrap();
+        assert!(agent.commit_exists && !agent.tag_exists);
+        assert_eq!(graph.current_branch.as_deref(), Some("main"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_truncate_patch_at_line_boundary() {
+        let (text, truncated) = truncate_patch("+aaa\n+bbb\n+ccc\n".to_string(), 12);
+        assert!(truncated);
+        assert_eq!(text, "+aaa\n+bbb\n");
+        let (text, truncated) = truncate_patch("+a\n".to_string(), 12);
+        assert!(!truncated);
+        assert_eq!(text, "+a\n");
+    }
+}
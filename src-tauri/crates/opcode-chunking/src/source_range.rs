@@ -0,0 +1,182 @@
+use super::storage::{
+    get_chunk_by_id, get_latest_file_chunk_id, get_snapshot, normalize_file_path,
+};
+use super::types::{ChunkType, SourceRange};
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Líneas `start_line..=end_line` (1-based) de un archivo, servidas desde su chunk raw
+/// source sin leer el filesystem. Sin `snapshot_id` se usa la versión vigente; con él,
+/// la versión indexada hasta el snapshot siguiente del proyecto (la reindexación de un
+/// snapshot ocurre después de crearlo). `None` si el archivo no está indexado
+pub fn get_source_range(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    start_line: usize,
+    end_line: usize,
+    snapshot_id: Option<i64>,
+) -> Result<Option<SourceRange>> {
+    if start_line == 0 || end_line < start_line {
+        bail!("Invalid line range {}-{}", start_line, end_line);
+    }
+
+    let chunk_id = match snapshot_id {
+        Some(snapshot_id) => historical_chunk_id(conn, project_path, file_path, snapshot_id)?,
+        None => get_latest_file_chunk_id(conn, project_path, file_path, &ChunkType::RawSource)?,
+    };
+    let Some(chunk_id) = chunk_id else {
+        return Ok(None);
+    };
+    let chunk = get_chunk_by_id(conn, chunk_id)?.context("Raw source chunk disappeared")?;
+
+    let total_lines = chunk.content.lines().count();
+    if start_line > total_lines {
+        bail!(
+            "Line {} is past the end of {} ({} lines)",
+            start_line,
+            file_path,
+            total_lines
+        );
+    }
+    let end_line = end_line.min(total_lines);
+    // Conserva los saltos de línea originales (incluido \r\n)
+    let content: String = chunk
+        .content
+        .split_inclusive('\n')
+        .skip(start_line - 1)
+        .take(end_line - start_line + 1)
+        .collect();
+
+    Ok(Some(SourceRange {
+        project_path: chunk.project_path,
+        file_path: chunk
+            .file_path
+            .unwrap_or_else(|| normalize_file_path(file_path)),
+        chunk_id,
+        snapshot_id,
+        start_line,
+        end_line,
+        total_lines,
+        content,
+        content_hash: chunk.content_hash,
+        indexed_at: chunk.created_at,
+    }))
+}
+
+/// Chunk raw source del archivo vigente en un snapshot: el último creado antes del
+/// snapshot siguiente y que no estaba en la papelera a esa altura
+fn historical_chunk_id(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    snapshot_id: i64,
+) -> Result<Option<i64>> {
+    let snapshot = get_snapshot(conn, snapshot_id)?
+        .with_context(|| format!("Snapshot {} not found", snapshot_id))?;
+    if snapshot.project_path != project_path {
+        bail!(
+            "Snapshot {} does not belong to {}",
+            snapshot_id,
+            project_path
+        );
+    }
+
+    let next_snapshot_at: Option<String> = conn.query_row(
+        "SELECT MIN(created_at) FROM snapshots
+         WHERE project_path = ?1 AND created_at > ?2",
+        params![project_path, snapshot.created_at.to_rfc3339()],
+        |row| row.get(0),
+    )?;
+    let id = conn
+        .query_row(
+            "SELECT id FROM chunks
+             WHERE project_path = ?1 AND file_path = ?2 AND chunk_type = ?3
+               AND entity_name IS NULL
+               AND (?4 IS NULL OR created_at < ?4)
+               AND (deleted_at IS NULL OR (?4 IS NOT NULL AND deleted_at >= ?4))
+             ORDER BY created_at DESC, id DESC LIMIT 1",
+            params![
+                project_path,
+                normalize_file_path(file_path),
+                ChunkType::RawSource.as_str(),
+                next_snapshot_at
+            ],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{create_snapshot, init_chunk_database};
+    use crate::test_support::chunk;
+    use crate::types::{Snapshot, SnapshotType};
+    use chrono::Utc;
+
+    fn index_file(conn: &Connection, content: &str) {
+        chunk().file("src/lib.rs").content(content).insert(conn);
+    }
+
+    fn snapshot(conn: &Connection) -> i64 {
+        create_snapshot(
+            conn,
+            &Snapshot {
+                id: None,
+                project_path: "/p".to_string(),
+                snapshot_type: SnapshotType::Master,
+                parent_snapshot_id: None,
+                message: "snapshot".to_string(),
+                user_message: None,
+                changed_files: "[]".to_string(),
+                diff_summary: None,
+                metadata: None,
+                git_commit_hash: None,
+                git_tag: None,
+                git_branch: None,
+                version_major: 1,
+                version_minor: None,
+                created_at: Utc::now(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_source_range_current_and_historical() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let first = snapshot(&conn);
+        index_file(&conn, "fn a() {}\r\nfn b() {}\r\nfn c() {}\r\n");
+        let second = snapshot(&conn);
+        index_file(&conn, "fn a() {}\nfn b() { todo!() }\n");
+
+        let range = get_source_range(&conn, "/p", "src\\lib.rs", 2, 10, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(range.content, "fn b() { todo!() }\n");
+        assert_eq!(
+            (range.start_line, range.end_line, range.total_lines),
+            (2, 2, 2)
+        );
+
+        let old = get_source_range(&conn, "/p", "src/lib.rs", 2, 3, Some(first))
+            .unwrap()
+            .unwrap();
+        assert_eq!(old.content, "fn b() {}\r\nfn c() {}\r\n");
+        assert_eq!(old.snapshot_id, Some(first));
+        let latest = get_source_range(&conn, "/p", "src/lib.rs", 1, 1, Some(second))
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.chunk_id, range.chunk_id);
+
+        assert!(get_source_range(&conn, "/p", "src/lib.rs", 3, 4, None).is_err());
+        assert!(get_source_range(&conn, "/p", "src/lib.rs", 0, 1, None).is_err());
+        assert!(get_source_range(&conn, "/other", "src/lib.rs", 1, 1, Some(first)).is_err());
+        assert!(get_source_range(&conn, "/p", "src/main.rs", 1, 1, None)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,753 @@
+use super::storage::{
+    calculate_content_hash, delete_relationships_by_source, get_chunk_id_by_hash,
+    get_latest_file_contents, insert_relationship, upsert_chunk, CHUNK_CONTENT,
+};
+use super::types::{
+    Chunk, ChunkRelationship, ChunkType, RelationshipType, SqlQueryMetadata, SqlTableMetadata,
+    TableQuery,
+};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Valor de `source` en la metadata de las relaciones consulta → tabla
+const SQL_ACCESS_SOURCE: &str = "sql_access";
+
+/// Primeras palabras de un literal que se considera SQL
+const SQL_KEYWORDS: &[&str] = &[
+    "SELECT", "INSERT", "UPDATE", "DELETE", "WITH", "REPLACE", "CREATE", "ALTER", "DROP",
+    "TRUNCATE",
+];
+
+/// Tabla definida con `CREATE TABLE` o con el macro `table!` de diesel
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlTableDef {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub line: usize,
+    pub source: String,
+    pub sql: String,
+}
+
+/// Consulta encontrada en un archivo, con las tablas que lee y escribe
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlQueryDef {
+    pub operation: String,
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+    pub line: usize,
+    pub source: String,
+    pub sql: String,
+}
+
+/// Regenera los chunks de esquema (uno por tabla definida) y de consulta (uno por
+/// sentencia SQL embebida) del proyecto, y las relaciones DependsOn de cada consulta
+/// hacia las tablas que toca, a partir de los raw source vigentes.
+/// Retorna (chunks generados, relaciones creadas)
+pub fn generate_sql_chunks(conn: &Connection, project_path: &str) -> Result<(usize, usize)> {
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+
+    delete_relationships_by_source(conn, project_path, SQL_ACCESS_SOURCE)?;
+    conn.execute(
+        "DELETE FROM chunks WHERE project_path = ?1 AND chunk_type = 'callgraph'
+         AND json_extract(metadata, '$.kind') IN ('sql_table', 'sql_query')",
+        params![project_path],
+    )?;
+
+    let mut chunks_created = 0;
+    // Tabla (en minúsculas) → chunks de esquema que la definen
+    let mut table_chunks: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    let mut queries: Vec<(String, SqlQueryDef)> = Vec::new();
+    for (file_path, (_, content)) in &sources {
+        let (tables, file_queries) = extract_sql(file_path, content);
+        for table in tables {
+            let metadata = SqlTableMetadata {
+                kind: "sql_table".to_string(),
+                table: table.table.clone(),
+                columns: table.columns.clone(),
+                line: table.line,
+                source: table.source.clone(),
+            };
+            let chunk = build_chunk(
+                project_path,
+                file_path,
+                &table.table,
+                format!("-- {}:{}\n{}", file_path, table.line, table.sql),
+                serde_json::to_string(&metadata)?,
+            );
+            upsert_chunk(conn, &chunk, None)?;
+            chunks_created += 1;
+            if let Some(id) = get_chunk_id_by_hash(conn, &chunk.project_path, &chunk.content_hash)? {
+                table_chunks
+                    .entry(table.table.to_lowercase())
+                    .or_default()
+                    .push(id);
+            }
+        }
+        queries.extend(file_queries.into_iter().map(|q| (file_path.clone(), q)));
+    }
+
+    let mut relationships_created = 0;
+    for (file_path, query) in queries {
+        let tables: BTreeSet<&String> = query.reads.iter().chain(&query.writes).collect();
+        let entity_name = format!(
+            "{} {}",
+            query.operation,
+            tables
+                .iter()
+                .map(|t| t.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let metadata = SqlQueryMetadata {
+            kind: "sql_query".to_string(),
+            operation: query.operation.clone(),
+            reads: query.reads.clone(),
+            writes: query.writes.clone(),
+            line: query.line,
+            source: query.source.clone(),
+        };
+        let chunk = build_chunk(
+            project_path,
+            &file_path,
+            &entity_name,
+            format!("-- {}:{}\n{}", file_path, query.line, query.sql),
+            serde_json::to_string(&metadata)?,
+        );
+        upsert_chunk(conn, &chunk, None)?;
+        chunks_created += 1;
+        let Some(query_id) = get_chunk_id_by_hash(conn, &chunk.project_path, &chunk.content_hash)? else {
+            continue;
+        };
+
+        for table in tables {
+            let access = if query.writes.contains(table) {
+                "write"
+            } else {
+                "read"
+            };
+            for table_id in table_chunks
+                .get(&table.to_lowercase())
+                .into_iter()
+                .flatten()
+            {
+                insert_relationship(
+                    conn,
+                    &ChunkRelationship {
+                        id: None,
+                        from_chunk_id: query_id,
+                        to_chunk_id: *table_id,
+                        relationship_type: RelationshipType::DependsOn,
+                        metadata: Some(
+                            serde_json::json!({
+                                "source": SQL_ACCESS_SOURCE,
+                                "table": table,
+                                "access": access,
+                                "from_file": file_path,
+                            })
+                            .to_string(),
+                        ),
+                        created_at: Utc::now(),
+                    },
+                )?;
+                relationships_created += 1;
+            }
+        }
+    }
+
+    Ok((chunks_created, relationships_created))
+}
+
+/// Consultas del proyecto que leen o escriben `table` (sin distinguir mayúsculas ni
+/// esquema), ordenadas por archivo y línea
+pub fn find_table_queries(
+    conn: &Connection,
+    project_path: &str,
+    table: &str,
+) -> Result<Vec<TableQuery>> {
+    let table = normalize_table(table);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT file_path, {}, metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = 'callgraph' AND deleted_at IS NULL
+           AND json_extract(metadata, '$.kind') = 'sql_query'",
+        CHUNK_CONTENT
+    ))?;
+    let rows = stmt
+        .query_map(params![project_path], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut usages = Vec::new();
+    for (file_path, content, metadata) in rows {
+        let Ok(metadata) = serde_json::from_str::<SqlQueryMetadata>(&metadata) else {
+            continue;
+        };
+        let access = if metadata
+            .writes
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(&table))
+        {
+            "write"
+        } else if metadata
+            .reads
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(&table))
+        {
+            "read"
+        } else {
+            continue;
+        };
+        usages.push(TableQuery {
+            file_path,
+            line: metadata.line,
+            operation: metadata.operation,
+            access: access.to_string(),
+            sql: content
+                .split_once('\n')
+                .map(|(_, sql)| sql.to_string())
+                .unwrap_or(content),
+            source: metadata.source,
+        });
+    }
+    usages.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.line.cmp(&b.line)));
+    Ok(usages)
+}
+
+/// Tablas definidas y consultas de un archivo: sentencias de un `.sql`, literales de
+/// string que son SQL (sqlx `query!`, rusqlite, `knex.raw`, cursores de Python...),
+/// macros `table!` y referencias `users::table` de diesel, y query builders de knex
+pub fn extract_sql(file_path: &str, content: &str) -> (Vec<SqlTableDef>, Vec<SqlQueryDef>) {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let statements: Vec<(usize, &str, &str)> = if extension == "sql" {
+        split_statements(content, 0)
+            .into_iter()
+            .map(|(offset, sql)| (offset, sql, "sql"))
+            .collect()
+    } else {
+        string_literals(content, &extension)
+            .into_iter()
+            .filter(|(_, text)| looks_like_sql(text))
+            .flat_map(|(offset, text)| split_statements(text, offset))
+            .map(|(offset, sql)| (offset, sql, "literal"))
+            .collect()
+    };
+
+    let mut tables = Vec::new();
+    let mut queries = Vec::new();
+    for (offset, sql, source) in statements {
+        let line = line_of(content, offset);
+        let normalized = collapse_whitespace(sql);
+        if let Some(table) = parse_create_table(&normalized) {
+            tables.push(SqlTableDef {
+                line,
+                source: source.to_string(),
+                sql: normalized,
+                ..table
+            });
+            continue;
+        }
+        if let Some((operation, reads, writes)) = analyze_statement(&normalized) {
+            queries.push(SqlQueryDef {
+                operation,
+                reads,
+                writes,
+                line,
+                source: source.to_string(),
+                sql: normalized,
+            });
+        }
+    }
+
+    match extension.as_str() {
+        "rs" if content.contains("diesel") => extract_diesel(content, &mut tables, &mut queries),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => extract_knex(content, &mut queries),
+        _ => {}
+    }
+    (tables, queries)
+}
+
+/// Literales de string (offset del contenido, texto) según el lenguaje: raw strings de
+/// Rust, template literals de JS, triple comillas de Python y comillas simples donde
+/// no son literales de carácter
+fn string_literals<'a>(content: &'a str, extension: &str) -> Vec<(usize, &'a str)> {
+    let single_quotes = matches!(
+        extension,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "py" | "rb" | "php" | "vue" | "svelte"
+    );
+    let mut pattern = String::from(
+        r##"(?s)\br#"(.*?)"#|"""(.*?)"""|'''(.*?)'''|\br"([^"]*)"|"((?:[^"\\]|\\.)*)"|`((?:[^`\\]|\\.)*)`"##,
+    );
+    if single_quotes {
+        pattern.push_str(r#"|'((?:[^'\\\n]|\\.)*)'"#);
+    }
+    let literal_re = Regex::new(&pattern).unwrap();
+    literal_re
+        .captures_iter(content)
+        .filter_map(|cap| {
+            let text = cap.iter().skip(1).flatten().next()?;
+            Some((text.start(), text.as_str()))
+        })
+        .collect()
+}
+
+/// Un literal es SQL si empieza con una palabra clave y, escrita en minúsculas,
+/// lleva algo de sintaxis SQL (`*`, `?`, `=`, `(`, `;`, `$1`), para no confundir
+/// frases como "Delete from cache failed"
+fn looks_like_sql(text: &str) -> bool {
+    let trimmed = text.trim_start();
+    let first = trimmed
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or("");
+    if !SQL_KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(first)) {
+        return false;
+    }
+    first.chars().all(|c| c.is_ascii_uppercase())
+        || trimmed.contains(['*', '?', '=', '(', ';', '$'])
+}
+
+/// Sentencias separadas por `;` (offset absoluto, texto), sin comentarios `--` ni vacías
+fn split_statements(text: &str, base_offset: usize) -> Vec<(usize, &str)> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    for part in text.split(';') {
+        // El offset apunta al primer carácter que no es espacio ni comentario
+        let mut skip = 0;
+        for line in part.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with("--") {
+                skip += line.len();
+            } else {
+                skip += line.len() - line.trim_start().len();
+                break;
+            }
+        }
+        let statement = part[skip.min(part.len())..].trim_end();
+        if !statement.is_empty() {
+            statements.push((base_offset + start + skip, statement));
+        }
+        start += part.len() + 1;
+    }
+    statements
+}
+
+/// `CREATE [TEMP|VIRTUAL] TABLE [IF NOT EXISTS] nombre (...)` con sus columnas
+fn parse_create_table(sql: &str) -> Option<SqlTableDef> {
+    let create_re = Regex::new(
+        r#"(?i)^CREATE\s+(?:(?:TEMP|TEMPORARY|VIRTUAL|UNLOGGED)\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?([\w."`\[\]]+)\s*(?:USING\s+\w+\s*)?\("#,
+    )
+    .unwrap();
+    let cap = create_re.captures(sql)?;
+    let body_start = cap.get(0).unwrap().end();
+
+    let mut depth = 1;
+    let mut parts = Vec::new();
+    let mut part_start = body_start;
+    for (i, c) in sql[body_start..].char_indices() {
+        let i = body_start + i;
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    parts.push(&sql[part_start..i]);
+                    break;
+                }
+            }
+            ',' if depth == 1 => {
+                parts.push(&sql[part_start..i]);
+                part_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    let columns = parts
+        .into_iter()
+        .filter_map(|part| {
+            let name: String = part
+                .trim()
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '"' | '`' | '[' | ']'))
+                .collect();
+            let upper = name.to_uppercase();
+            if name.is_empty()
+                || matches!(
+                    upper.as_str(),
+                    "PRIMARY" | "FOREIGN" | "UNIQUE" | "CONSTRAINT" | "CHECK" | "KEY" | "INDEX"
+                )
+            {
+                return None;
+            }
+            Some(
+                name.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+                    .to_string(),
+            )
+        })
+        .collect();
+
+    Some(SqlTableDef {
+        table: normalize_table(&cap[1]),
+        columns,
+        line: 0,
+        source: String::new(),
+        sql: String::new(),
+    })
+}
+
+/// Operación y tablas leídas y escritas por una sentencia; None si no toca tablas
+fn analyze_statement(sql: &str) -> Option<(String, Vec<String>, Vec<String>)> {
+    const NAME: &str = r#"((?:["`\[]?[A-Za-z_][\w$]*["`\]]?\.)?["`\[]?[A-Za-z_][\w$]*["`\]]?)"#;
+    let write_re = Regex::new(&format!(
+        r"(?i)\b(?:INSERT\s+(?:OR\s+\w+\s+)?INTO|REPLACE\s+INTO|UPDATE\s+(?:OR\s+\w+\s+)?{name}\s+SET\b|DELETE\s+FROM|ALTER\s+TABLE|DROP\s+TABLE\s+(?:IF\s+EXISTS\s+)?|TRUNCATE\s+(?:TABLE\s+)?|CREATE\s+(?:UNIQUE\s+)?INDEX\s+(?:IF\s+NOT\s+EXISTS\s+)?\w+\s+ON)\s*{name}?",
+        name = NAME
+    ))
+    .unwrap();
+    let read_re = Regex::new(&format!(r"(?i)\b(?:FROM|JOIN|USING)\s+{}", NAME)).unwrap();
+    let cte_re = Regex::new(r"(?i)(?:\bWITH(?:\s+RECURSIVE)?|,)\s+(\w+)\s+AS\s*\(").unwrap();
+
+    let operation = sql
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or("")
+        .to_uppercase();
+    let ctes: BTreeSet<String> = cte_re
+        .captures_iter(sql)
+        .map(|cap| cap[1].to_lowercase())
+        .collect();
+
+    let mut writes: Vec<String> = Vec::new();
+    let mut write_spans = Vec::new();
+    for cap in write_re.captures_iter(sql) {
+        let name = cap.get(1).or(cap.get(2));
+        if let Some(name) = name {
+            push_table(&mut writes, name.as_str(), &ctes);
+        }
+        write_spans.push(cap.get(0).unwrap().range());
+    }
+    let mut reads: Vec<String> = Vec::new();
+    for cap in read_re.captures_iter(sql) {
+        let whole = cap.get(0).unwrap();
+        // `DELETE FROM x` es escritura, no lectura
+        if write_spans.iter().any(|span| span.contains(&whole.start())) {
+            continue;
+        }
+        push_table(&mut reads, &cap[1], &ctes);
+    }
+
+    if reads.is_empty() && writes.is_empty() {
+        return None;
+    }
+    // En un WITH la operación es la de la sentencia principal
+    let operation = if operation == "WITH" {
+        Regex::new(r"(?i)\b(INSERT|UPDATE|DELETE)\b")
+            .unwrap()
+            .captures(sql)
+            .map(|cap| cap[1].to_uppercase())
+            .unwrap_or_else(|| "SELECT".to_string())
+    } else {
+        operation
+    };
+    Some((operation, reads, writes))
+}
+
+fn push_table(tables: &mut Vec<String>, name: &str, ctes: &BTreeSet<String>) {
+    let table = normalize_table(name);
+    let upper = table.to_uppercase();
+    let is_keyword = matches!(
+        upper.as_str(),
+        "SELECT" | "LATERAL" | "ONLY" | "UNNEST" | "VALUES" | "WHERE"
+    );
+    if !table.is_empty() && !is_keyword && !ctes.contains(&table) && !tables.contains(&table) {
+        tables.push(table);
+    }
+}
+
+/// Sin comillas ni esquema y en minúsculas: `"public"."Users"` → `users`
+fn normalize_table(name: &str) -> String {
+    name.rsplit('.')
+        .next()
+        .unwrap_or(name)
+        .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+        .to_lowercase()
+}
+
+/// Diesel: tablas del macro `table!` (con columnas) y cada línea que usa
+/// `tabla::table` o `tabla::dsl` de una tabla conocida
+fn extract_diesel(content: &str, tables: &mut Vec<SqlTableDef>, queries: &mut Vec<SqlQueryDef>) {
+    let table_re =
+        Regex::new(r"(?s)\btable!\s*\{\s*(?:[\w:]+\.)?(\w+)\s*(?:\([^)]*\))?\s*\{([^}]*)\}")
+            .unwrap();
+    let column_re = Regex::new(r"(\w+)\s*->").unwrap();
+    for cap in table_re.captures_iter(content) {
+        tables.push(SqlTableDef {
+            table: normalize_table(&cap[1]),
+            columns: column_re
+                .captures_iter(&cap[2])
+                .map(|c| c[1].to_string())
+                .collect(),
+            line: line_of(content, cap.get(0).unwrap().start()),
+            source: "diesel".to_string(),
+            sql: collapse_whitespace(&cap[0]),
+        });
+    }
+
+    let usage_re = Regex::new(r"\b(\w+)::(?:table|dsl)\b").unwrap();
+    for (index, text) in content.lines().enumerate() {
+        if text.contains("table!") {
+            continue;
+        }
+        let mut used = Vec::new();
+        for cap in usage_re.captures_iter(text) {
+            push_table(&mut used, &cap[1], &BTreeSet::new());
+        }
+        if used.is_empty() {
+            continue;
+        }
+        let write = ["insert_into(", "update(", "delete("]
+            .iter()
+            .find(|call| text.contains(*call));
+        let (operation, reads, writes) = match write {
+            Some(call) => (
+                call.trim_end_matches('(')
+                    .replace("_into", "")
+                    .to_uppercase(),
+                Vec::new(),
+                used,
+            ),
+            None => ("SELECT".to_string(), used, Vec::new()),
+        };
+        queries.push(SqlQueryDef {
+            operation,
+            reads,
+            writes,
+            line: index + 1,
+            source: "diesel".to_string(),
+            sql: text.trim().to_string(),
+        });
+    }
+}
+
+/// knex: `knex('users')`, `db('users')`, `trx('users')` y `.from('users')` /
+/// `.into('users')`; la cadena hasta el `;` decide si escribe
+fn extract_knex(content: &str, queries: &mut Vec<SqlQueryDef>) {
+    if !content.contains("knex") {
+        return;
+    }
+    let builder_re = Regex::new(
+        r#"\b(?:knex|db|trx)\s*\(\s*['"`](\w+)['"`]\s*\)|\.(?:from|into|table)\(\s*['"`](\w+)['"`]\s*\)"#,
+    )
+    .unwrap();
+    for cap in builder_re.captures_iter(content) {
+        let whole = cap.get(0).unwrap();
+        let table = cap.get(1).or(cap.get(2)).unwrap().as_str();
+        let end = content[whole.start()..]
+            .find(';')
+            .map(|i| whole.start() + i)
+            .unwrap_or(content.len());
+        let chain = &content[whole.start()..end];
+        let operation = [
+            (".insert(", "INSERT"),
+            (".update(", "UPDATE"),
+            (".del(", "DELETE"),
+            (".delete(", "DELETE"),
+        ]
+        .iter()
+        .find(|(call, _)| chain.contains(*call))
+        .map(|(_, op)| *op)
+        .unwrap_or("SELECT");
+        let tables = vec![normalize_table(table)];
+        let (reads, writes) = if operation == "SELECT" {
+            (tables, Vec::new())
+        } else {
+            (Vec::new(), tables)
+        };
+        queries.push(SqlQueryDef {
+            operation: operation.to_string(),
+            reads,
+            writes,
+            line: line_of(content, whole.start()),
+            source: "knex".to_string(),
+            sql: collapse_whitespace(chain),
+        });
+    }
+}
+
+fn build_chunk(
+    project_path: &str,
+    file_path: &str,
+    entity_name: &str,
+    content: String,
+    metadata: String,
+) -> Chunk {
+    let content_hash = calculate_content_hash(&format!("{}\n{}", project_path, content));
+
+    Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::Callgraph,
+        file_path: Some(file_path.to_string()),
+        entity_name: Some(entity_name.to_string()),
+        content,
+        content_hash,
+        metadata: Some(metadata),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{get_relationships, init_chunk_database};
+    use crate::test_support::chunk;
+
+    #[test]
+    fn test_analyze_statement() {
+        assert_eq!(
+            analyze_statement("SELECT u.id FROM users u JOIN orders o ON o.user_id = u.id"),
+            Some((
+                "SELECT".to_string(),
+                vec!["users".to_string(), "orders".to_string()],
+                vec![]
+            ))
+        );
+        assert_eq!(
+            analyze_statement("DELETE FROM sessions WHERE user_id IN (SELECT id FROM users)"),
+            Some((
+                "DELETE".to_string(),
+                vec!["users".to_string()],
+                vec!["sessions".to_string()]
+            ))
+        );
+        assert_eq!(
+            analyze_statement("UPDATE \"public\".\"Users\" SET name = ?1"),
+            Some(("UPDATE".to_string(), vec![], vec!["users".to_string()]))
+        );
+        assert_eq!(
+            analyze_statement(
+                "WITH recent AS (SELECT * FROM orders) INSERT INTO archive SELECT * FROM recent"
+            ),
+            Some((
+                "INSERT".to_string(),
+                vec!["orders".to_string()],
+                vec!["archive".to_string()]
+            ))
+        );
+        assert_eq!(analyze_statement("SELECT 1"), None);
+    }
+
+    #[test]
+    fn test_extract_sql_from_sources() {
+        let rust = r##"
+fn load(conn: &Connection) {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY,
+            email TEXT NOT NULL,
+            UNIQUE(email)
+        );
+        CREATE INDEX idx_users_email ON users(email);",
+    )?;
+    let user = sqlx::query!(r#"SELECT id, email FROM users WHERE id = $1"#, id);
+    log::warn!("Delete from cache failed");
+}
+"##;
+        let (tables, queries) = extract_sql("src/db.rs", rust);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].table, "users");
+        assert_eq!(tables[0].columns, vec!["id", "email"]);
+        assert_eq!(tables[0].line, 4);
+        let ops: Vec<(&str, usize)> = queries
+            .iter()
+            .map(|q| (q.operation.as_str(), q.line))
+            .collect();
+        assert_eq!(ops, vec![("CREATE", 9), ("SELECT", 11)]);
+        assert_eq!(queries[0].writes, vec!["users"]);
+
+        let js = "const knex = require('knex')(config);\nawait knex('orders').where({ id }).update({ paid: true });\nconst rows = await db.query('select * from orders where id = ?', [id]);\n";
+        let (_, queries) = extract_sql("src/orders.js", js);
+        let ops: Vec<(&str, &str, usize)> = queries
+            .iter()
+            .map(|q| (q.operation.as_str(), q.source.as_str(), q.line))
+            .collect();
+        assert_eq!(ops, vec![("SELECT", "literal", 3), ("UPDATE", "knex", 2)]);
+
+        let diesel = "use diesel::prelude::*;\ntable! {\n    posts (id) {\n        id -> Int4,\n        title -> Varchar,\n    }\n}\nfn f() { diesel::insert_into(posts::table).values(&p).execute(conn); }\n";
+        let (tables, queries) = extract_sql("src/schema.rs", diesel);
+        assert_eq!(tables[0].table, "posts");
+        assert_eq!(tables[0].columns, vec!["id", "title"]);
+        assert_eq!(queries[0].operation, "INSERT");
+        assert_eq!(queries[0].writes, vec!["posts"]);
+
+        let migration =
+            "-- users\nCREATE TABLE users (id INT);\n\nINSERT INTO users (id) VALUES (1);\n";
+        let (tables, queries) = extract_sql("migrations/001.sql", migration);
+        assert_eq!((tables[0].table.as_str(), tables[0].line), ("users", 2));
+        assert_eq!(
+            (queries[0].operation.as_str(), queries[0].line),
+            ("INSERT", 4)
+        );
+    }
+
+    #[test]
+    fn test_generate_sql_chunks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let files = [
+            (
+                "migrations/001.sql",
+                "CREATE TABLE users (id INT, email TEXT);\nCREATE TABLE orders (id INT, user_id INT);\n",
+            ),
+            (
+                "src/repo.py",
+                "cur.execute(\"SELECT o.id FROM orders o JOIN users u ON u.id = o.user_id\")\ncur.execute('DELETE FROM orders WHERE id = %s', (id,))\n",
+            ),
+        ];
+        for (file, content) in files {
+            chunk().file(file).content(content).insert(&conn);
+        }
+
+        // 2 tablas + 2 consultas; SELECT → orders y users, DELETE → orders
+        assert_eq!(generate_sql_chunks(&conn, "/p").unwrap(), (4, 3));
+        assert_eq!(generate_sql_chunks(&conn, "/p").unwrap(), (4, 3));
+
+        let usages = find_table_queries(&conn, "/p", "\"Orders\"").unwrap();
+        let found: Vec<(&str, usize, &str)> = usages
+            .iter()
+            .map(|u| (u.operation.as_str(), u.line, u.access.as_str()))
+            .collect();
+        assert_eq!(found, vec![("SELECT", 1, "read"), ("DELETE", 2, "write")]);
+        assert_eq!(usages[1].sql, "DELETE FROM orders WHERE id = %s");
+
+        let orders_id: i64 = conn
+            .query_row(
+                "SELECT id FROM chunks WHERE entity_name = 'orders'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(get_relationships(&conn, orders_id, false).unwrap().len(), 2);
+    }
+}
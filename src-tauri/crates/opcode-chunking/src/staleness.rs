@@ -0,0 +1,182 @@
+use super::hashing::hash_matches;
+use super::storage::{
+    calculate_hash, delete_file_fingerprint, get_file_fingerprints, normalize_file_path,
+    set_file_chunks_stale, upsert_file_fingerprint,
+};
+use super::types::{FileFingerprint, HashAlgorithm, StalenessReport};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Tamaño y mtime (ms desde epoch) del archivo; None si no existe o no se puede leer
+fn disk_stat(path: &Path) -> Option<(u64, i64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0);
+    Some((metadata.len(), modified))
+}
+
+/// Guarda la huella del contenido recién indexado y quita la marca de desactualizado
+/// a los chunks del archivo
+pub fn record_fingerprint(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+    hash_algorithm: HashAlgorithm,
+) -> Result<()> {
+    let (size, modified_at) =
+        disk_stat(&Path::new(project_path).join(file_path)).unwrap_or((content.len() as u64, 0));
+    upsert_file_fingerprint(
+        conn,
+        &FileFingerprint {
+            project_path: project_path.to_string(),
+            file_path: normalize_file_path(file_path),
+            content_hash: calculate_hash(content, hash_algorithm),
+            size,
+            modified_at,
+            indexed_at: Utc::now(),
+        },
+    )?;
+    set_file_chunks_stale(conn, project_path, file_path, false)?;
+    Ok(())
+}
+
+/// Olvida la huella de un archivo eliminado (sus chunks ya van a la papelera)
+pub fn forget_file(conn: &Connection, project_path: &str, file_path: &str) -> Result<()> {
+    delete_file_fingerprint(conn, project_path, file_path)?;
+    Ok(())
+}
+
+/// Compara las huellas con el disco y marca como desactualizados los chunks de los
+/// archivos cambiados o eliminados. Solo re-hashea si cambió el tamaño o el mtime;
+/// un archivo tocado sin cambios de contenido actualiza su huella y sigue vigente.
+/// Cada huella se compara con el algoritmo con que se calculó
+pub fn mark_stale_chunks(conn: &Connection, project_path: &str) -> Result<StalenessReport> {
+    let fingerprints = get_file_fingerprints(conn, project_path)?;
+    let root = Path::new(project_path);
+    let mut report = StalenessReport {
+        project_path: project_path.to_string(),
+        files_checked: fingerprints.len(),
+        ..Default::default()
+    };
+
+    for fingerprint in fingerprints {
+        let full_path = root.join(&fingerprint.file_path);
+        let stale = match disk_stat(&full_path) {
+            None => true,
+            Some((size, modified_at))
+                if size == fingerprint.size && modified_at == fingerprint.modified_at =>
+            {
+                false
+            }
+            Some((size, modified_at)) => match std::fs::read_to_string(&full_path) {
+                Ok(content) if hash_matches(&fingerprint.content_hash, &content) => {
+                    upsert_file_fingerprint(
+                        conn,
+                        &FileFingerprint {
+                            size,
+                            modified_at,
+                            ..fingerprint.clone()
+                        },
+                    )?;
+                    false
+                }
+                _ => true,
+            },
+        };
+
+        set_file_chunks_stale(conn, project_path, &fingerprint.file_path, stale)?;
+        if stale {
+            report.stale_files.push(fingerprint.file_path);
+        }
+    }
+
+    report.stale_chunks = conn.query_row(
+        "SELECT COUNT(*) FROM chunks WHERE project_path = ?1 AND stale = 1 AND deleted_at IS NULL",
+        params![project_path],
+        |row| row.get::<_, i64>(0),
+    )? as usize;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{init_chunk_database, query_chunks};
+    use crate::test_support::chunk;
+    use crate::types::ChunkQuery;
+
+    fn insert_chunk(conn: &Connection, project_path: &str, file_path: &str, content: &str) {
+        chunk()
+            .project(project_path)
+            .file(file_path)
+            .content(content)
+            .insert(conn);
+    }
+
+    #[test]
+    fn test_mark_stale_chunks() {
+        let dir = std::env::temp_dir().join(format!("opcode-staleness-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let project = dir.to_str().unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        for (file, content) in [
+            ("a.rs", "fn a() {}"),
+            ("b.rs", "fn b() {}"),
+            ("c.rs", "fn c() {}"),
+        ] {
+            std::fs::write(dir.join(file), content).unwrap();
+            insert_chunk(&conn, project, file, content);
+            record_fingerprint(&conn, project, file, content, HashAlgorithm::Sha256).unwrap();
+        }
+
+        let report = mark_stale_chunks(&conn, project).unwrap();
+        assert_eq!(report.files_checked, 3);
+        assert!(report.stale_files.is_empty());
+
+        std::fs::write(dir.join("a.rs"), "fn a() { changed(); }").unwrap();
+        std::fs::remove_file(dir.join("c.rs")).unwrap();
+        let report = mark_stale_chunks(&conn, project).unwrap();
+        assert_eq!(report.stale_files, vec!["a.rs", "c.rs"]);
+        assert_eq!(report.stale_chunks, 2);
+
+        let stale = query_chunks(
+            &conn,
+            &ChunkQuery {
+                project_path: Some(project.to_string()),
+                stale: Some(true),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut files: Vec<_> = stale
+            .iter()
+            .filter_map(|c| c.file_path.as_deref())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["a.rs", "c.rs"]);
+
+        // Reindexar el archivo limpia la marca, aunque el proyecto haya cambiado de algoritmo
+        record_fingerprint(
+            &conn,
+            project,
+            "a.rs",
+            "fn a() { changed(); }",
+            HashAlgorithm::Blake3,
+        )
+        .unwrap();
+        let report = mark_stale_chunks(&conn, project).unwrap();
+        assert_eq!(report.stale_files, vec!["c.rs"]);
+        assert_eq!(report.stale_chunks, 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,3163 @@
+use super::logical_projects::LOGICAL_PROJECT_SCHEME;
+use super::types::*;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+
+/// Database connection wrapper para chunks
+pub struct ChunkDb(pub Mutex<Connection>);
+
+/// Operaciones básicas sobre el índice de chunks. Implementado para
+/// `rusqlite::Connection`, para que otras herramientas puedan usar el índice
+/// sin depender de las funciones sueltas de este módulo
+pub trait Storage {
+    fn upsert_chunk(&self, chunk: &Chunk, snapshot_id: Option<i64>) -> Result<bool>;
+    fn query_chunks(&self, query: &ChunkQuery) -> Result<Vec<Chunk>>;
+    fn delete_project_chunks(&self, project_path: &str) -> Result<usize>;
+    fn insert_relationship(&self, rel: &ChunkRelationship) -> Result<i64>;
+    fn get_relationships(&self, chunk_id: i64, outgoing: bool) -> Result<Vec<ChunkRelationship>>;
+    fn create_snapshot(&self, snapshot: &Snapshot) -> Result<i64>;
+    fn get_snapshot(&self, snapshot_id: i64) -> Result<Option<Snapshot>>;
+    fn get_snapshots(
+        &self,
+        project_path: &str,
+        snapshot_type: Option<SnapshotType>,
+    ) -> Result<Vec<Snapshot>>;
+    fn upsert_error_log(&self, error: &ErrorLog) -> Result<i64>;
+    fn get_error_logs(&self, project_path: &str, include_resolved: bool) -> Result<Vec<ErrorLog>>;
+    fn get_business_rules(&self, project_path: &str) -> Result<Vec<BusinessRule>>;
+}
+
+impl Storage for Connection {
+    fn upsert_chunk(&self, chunk: &Chunk, snapshot_id: Option<i64>) -> Result<bool> {
+        upsert_chunk(self, chunk, snapshot_id)
+    }
+
+    fn query_chunks(&self, query: &ChunkQuery) -> Result<Vec<Chunk>> {
+        query_chunks(self, query)
+    }
+
+    fn delete_project_chunks(&self, project_path: &str) -> Result<usize> {
+        delete_project_chunks(self, project_path)
+    }
+
+    fn insert_relationship(&self, rel: &ChunkRelationship) -> Result<i64> {
+        insert_relationship(self, rel)
+    }
+
+    fn get_relationships(&self, chunk_id: i64, outgoing: bool) -> Result<Vec<ChunkRelationship>> {
+        get_relationships(self, chunk_id, outgoing)
+    }
+
+    fn create_snapshot(&self, snapshot: &Snapshot) -> Result<i64> {
+        create_snapshot(self, snapshot)
+    }
+
+    fn get_snapshot(&self, snapshot_id: i64) -> Result<Option<Snapshot>> {
+        get_snapshot(self, snapshot_id)
+    }
+
+    fn get_snapshots(
+        &self,
+        project_path: &str,
+        snapshot_type: Option<SnapshotType>,
+    ) -> Result<Vec<Snapshot>> {
+        get_snapshots(self, project_path, snapshot_type)
+    }
+
+    fn upsert_error_log(&self, error: &ErrorLog) -> Result<i64> {
+        upsert_error_log(self, error)
+    }
+
+    fn get_error_logs(&self, project_path: &str, include_resolved: bool) -> Result<Vec<ErrorLog>> {
+        get_error_logs(self, project_path, include_resolved)
+    }
+
+    fn get_business_rules(&self, project_path: &str) -> Result<Vec<BusinessRule>> {
+        get_business_rules(self, project_path)
+    }
+}
+
+/// Inicializa la base de datos de chunks
+pub fn init_chunk_database(conn: &Connection) -> SqliteResult<()> {
+    // Los triggers de solo lectura se recrean al final, tras las migraciones
+    for table in READ_ONLY_TABLES.into_iter().chain(["chunk_relationships"]) {
+        for operation in ["insert", "update", "delete"] {
+            conn.execute(
+                &format!("DROP TRIGGER IF EXISTS read_only_{}_{}", table, operation),
+                [],
+            )?;
+        }
+    }
+
+    // Migration: el hash era único en toda la base; ahora lo es por proyecto
+    migrate_chunk_hash_per_project(conn)?;
+
+    // Tabla principal de chunks. Dos proyectos con un archivo idéntico tienen cada
+    // uno su chunk: el hash es único por proyecto (`idx_chunks_project_hash`)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS chunks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            chunk_type TEXT NOT NULL,
+            file_path TEXT,
+            entity_name TEXT,
+            content TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            metadata TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_chunks_project_hash ON chunks(project_path, content_hash)",
+        [],
+    )?;
+
+    // Índices para búsqueda eficiente
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_project ON chunks(project_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_type ON chunks(chunk_type)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_file ON chunks(file_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_entity ON chunks(entity_name)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_hash ON chunks(content_hash)",
+        [],
+    )?;
+
+    // Tabla de relaciones entre chunks
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS chunk_relationships (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            from_chunk_id INTEGER NOT NULL,
+            to_chunk_id INTEGER NOT NULL,
+            relationship_type TEXT NOT NULL,
+            metadata TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (from_chunk_id) REFERENCES chunks(id) ON DELETE CASCADE,
+            FOREIGN KEY (to_chunk_id) REFERENCES chunks(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_relationships_from ON chunk_relationships(from_chunk_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_relationships_to ON chunk_relationships(to_chunk_id)",
+        [],
+    )?;
+
+    // Tabla de reglas de negocio
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS business_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            entity_name TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            rule_description TEXT NOT NULL,
+            ai_interpretation TEXT NOT NULL,
+            user_correction TEXT,
+            is_validated BOOLEAN NOT NULL DEFAULT 0,
+            validation_date TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_business_rules_project ON business_rules(project_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_business_rules_entity ON business_rules(entity_name)",
+        [],
+    )?;
+
+    // Tabla de snapshots (git real con versionado)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            snapshot_type TEXT NOT NULL,
+            parent_snapshot_id INTEGER,
+            message TEXT NOT NULL,
+            user_message TEXT,
+            changed_files TEXT NOT NULL,
+            diff_summary TEXT,
+            metadata TEXT,
+            git_commit_hash TEXT,
+            git_tag TEXT,
+            git_branch TEXT,
+            version_major INTEGER NOT NULL DEFAULT 1,
+            version_minor INTEGER,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (parent_snapshot_id) REFERENCES snapshots(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Migrations: Add Git fields if they don't exist (for existing databases)
+    let _ = conn.execute("ALTER TABLE snapshots ADD COLUMN git_commit_hash TEXT", []);
+    let _ = conn.execute("ALTER TABLE snapshots ADD COLUMN git_tag TEXT", []);
+    let _ = conn.execute("ALTER TABLE snapshots ADD COLUMN git_branch TEXT", []);
+    let _ = conn.execute("ALTER TABLE snapshots ADD COLUMN version_major INTEGER NOT NULL DEFAULT 1", []);
+    let _ = conn.execute("ALTER TABLE snapshots ADD COLUMN version_minor INTEGER", []);
+
+    // Migration: Add snapshot_id to chunks table for linking chunks with snapshots
+    let _ = conn.execute("ALTER TABLE chunks ADD COLUMN snapshot_id INTEGER", []);
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_snapshot ON chunks(snapshot_id)",
+        [],
+    )?;
+
+    // Snapshot activo por proyecto (último creado o destino del último rewind)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS active_snapshots (
+            project_path TEXT PRIMARY KEY,
+            snapshot_id INTEGER NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Diffs por archivo de cada snapshot (patch con tamaño limitado)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS snapshot_diffs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            snapshot_id INTEGER NOT NULL,
+            file_path TEXT NOT NULL,
+            old_path TEXT,
+            status TEXT NOT NULL,
+            additions INTEGER NOT NULL DEFAULT 0,
+            deletions INTEGER NOT NULL DEFAULT 0,
+            is_binary BOOLEAN NOT NULL DEFAULT 0,
+            patch TEXT,
+            truncated BOOLEAN NOT NULL DEFAULT 0,
+            FOREIGN KEY (snapshot_id) REFERENCES snapshots(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_snapshot_diffs_snapshot ON snapshot_diffs(snapshot_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_snapshots_project ON snapshots(project_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_snapshots_type ON snapshots(snapshot_type)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_snapshots_parent ON snapshots(parent_snapshot_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_snapshots_version ON snapshots(version_major, version_minor)",
+        [],
+    )?;
+
+    // Reparto del presupuesto de tokens por tipo de chunk (JSON), por proyecto
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS context_budget_weights (
+            project_path TEXT PRIMARY KEY,
+            weights TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Paquetes de contexto entregados a sesiones de agente (auditoría)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS context_deliveries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            session_id TEXT,
+            snapshot_id INTEGER,
+            task_text TEXT NOT NULL,
+            chunk_ids TEXT NOT NULL,
+            chunk_tokens TEXT NOT NULL,
+            total_tokens INTEGER NOT NULL,
+            token_budget INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (snapshot_id) REFERENCES snapshots(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_context_deliveries_project ON context_deliveries(project_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_context_deliveries_session ON context_deliveries(session_id)",
+        [],
+    )?;
+
+    // Sesiones de agente (enlazan snapshots, errores y paquetes de contexto)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_sessions (
+            id TEXT PRIMARY KEY,
+            project_path TEXT NOT NULL,
+            master_snapshot_id INTEGER,
+            task TEXT,
+            status TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT,
+            FOREIGN KEY (master_snapshot_id) REFERENCES snapshots(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_agent_sessions_project ON agent_sessions(project_path)",
+        [],
+    )?;
+
+    // Proyectos lógicos con varias raíces (JSON con la configuración de cada raíz)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS logical_projects (
+            name TEXT PRIMARY KEY,
+            roots TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Repositorios remotos indexados en modo solo lectura
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS remote_repositories (
+            project_path TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            depth INTEGER,
+            head_commit TEXT,
+            cloned_at TEXT NOT NULL,
+            last_indexed_at TEXT
+        )",
+        [],
+    )?;
+
+    // Proyectos cuyo índice es de solo lectura (repos de referencia, índices compartidos)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS read_only_projects (
+            project_path TEXT PRIMARY KEY,
+            reason TEXT,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Tabla de errores/logs
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS error_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            snapshot_id INTEGER,
+            file_path TEXT,
+            entity_name TEXT,
+            error_type TEXT NOT NULL,
+            message TEXT NOT NULL,
+            stacktrace TEXT,
+            occurrence_count INTEGER NOT NULL DEFAULT 1,
+            first_seen TEXT NOT NULL,
+            last_seen TEXT NOT NULL,
+            is_resolved BOOLEAN NOT NULL DEFAULT 0,
+            FOREIGN KEY (snapshot_id) REFERENCES snapshots(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // Migration: severidad para diagnósticos importados (lint, build)
+    let _ = conn.execute("ALTER TABLE error_logs ADD COLUMN severity TEXT", []);
+
+    // Migration: fecha de resolución (tasas de apertura/cierre de errores)
+    let _ = conn.execute("ALTER TABLE error_logs ADD COLUMN resolved_at TEXT", []);
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_error_logs_project ON error_logs(project_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_error_logs_snapshot ON error_logs(snapshot_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_error_logs_file ON error_logs(file_path)",
+        [],
+    )?;
+
+    // Tabla de vulnerabilidades de dependencias
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS security_findings (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            metadata_chunk_id INTEGER,
+            manifest_path TEXT NOT NULL,
+            ecosystem TEXT NOT NULL,
+            package_name TEXT NOT NULL,
+            installed_version TEXT NOT NULL,
+            advisory_id TEXT NOT NULL,
+            aliases TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            fixed_version TEXT,
+            affected_files TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (metadata_chunk_id) REFERENCES chunks(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_security_findings_project ON security_findings(project_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_security_findings_package ON security_findings(package_name)",
+        [],
+    )?;
+
+    // Tabla de secretos detectados en el código indexado
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS secret_findings (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            chunk_id INTEGER,
+            file_path TEXT NOT NULL,
+            line_number INTEGER NOT NULL,
+            rule_id TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            masked_preview TEXT NOT NULL,
+            fingerprint TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (chunk_id) REFERENCES chunks(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_secret_findings_project ON secret_findings(project_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_secret_findings_file ON secret_findings(file_path)",
+        [],
+    )?;
+
+    // Tabla de resultados de tests importados (JUnit XML)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS test_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            run_id TEXT NOT NULL,
+            suite_name TEXT,
+            class_name TEXT,
+            test_name TEXT NOT NULL,
+            file_path TEXT,
+            status TEXT NOT NULL,
+            duration_ms REAL,
+            failure_message TEXT,
+            failure_details TEXT,
+            test_chunk_id INTEGER,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (test_chunk_id) REFERENCES chunks(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_test_results_project ON test_results(project_path)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_test_results_run ON test_results(run_id)",
+        [],
+    )?;
+
+    // Archivos que tree-sitter no pudo parsear (skip list del AST)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS parse_failures (
+            project_path TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 1,
+            first_failed_at TEXT NOT NULL,
+            last_failed_at TEXT NOT NULL,
+            PRIMARY KEY (project_path, file_path)
+        )",
+        [],
+    )?;
+
+    // Migration: tombstone de soft delete (NULL = chunk vigente)
+    let _ = conn.execute("ALTER TABLE chunks ADD COLUMN deleted_at TEXT", []);
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_deleted ON chunks(deleted_at)",
+        [],
+    )?;
+
+    // Operaciones destructivas deshacibles; cada chunk borrado apunta a la suya
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS index_operations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            chunks_affected INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            undone_at TEXT
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_index_operations_project ON index_operations(project_path)",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE chunks ADD COLUMN deleted_by_operation INTEGER", []);
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_deleted_by_operation ON chunks(deleted_by_operation)",
+        [],
+    )?;
+
+    // Migration: etiquetas de reglas de negocio (JSON) y reglas fijadas en el contexto
+    let _ = conn.execute(
+        "ALTER TABLE business_rules ADD COLUMN tags TEXT NOT NULL DEFAULT '[]'",
+        [],
+    );
+    // Migration: comprobación automática de la regla (JSON de RuleCheck)
+    let _ = conn.execute("ALTER TABLE business_rules ADD COLUMN check_spec TEXT", []);
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS context_rule_pinning (
+            project_path TEXT PRIMARY KEY,
+            mode TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Índices para las consultas ordenadas o filtradas por fecha
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_project_updated ON chunks(project_path, updated_at)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_snapshots_project_created ON snapshots(project_path, created_at)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_error_logs_project_last_seen ON error_logs(project_path, last_seen)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_agent_sessions_project_started ON agent_sessions(project_path, started_at)",
+        [],
+    )?;
+
+    // Contenido deduplicado por hash: los chunks con el mismo contenido (entre snapshots,
+    // versiones o proyectos) apuntan al mismo blob con `blob_hash` y dejan `content` vacío
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS content_blobs (
+            hash TEXT PRIMARY KEY,
+            content TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE chunks ADD COLUMN blob_hash TEXT", []);
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunks_blob_hash ON chunks(blob_hash)",
+        [],
+    )?;
+
+    // Vista: cada relación con los dos chunks que une (identidad, si está en la
+    // papelera y el comienzo de su contenido), para leerlas en una sola consulta.
+    // Se recrea para leer el contenido desde `content_blobs`
+    conn.execute("DROP VIEW IF EXISTS chunk_relationship_details", [])?;
+    conn.execute(
+        "CREATE VIEW chunk_relationship_details AS
+         SELECT r.id, r.from_chunk_id, r.to_chunk_id, r.relationship_type, r.metadata,
+                r.created_at, src.project_path,
+                src.chunk_type AS from_chunk_type, src.file_path AS from_file_path,
+                src.entity_name AS from_entity_name, src.deleted_at IS NOT NULL AS from_deleted,
+                substr(COALESCE(src_blob.content, src.content), 1, 500) AS from_preview,
+                dst.chunk_type AS to_chunk_type, dst.file_path AS to_file_path,
+                dst.entity_name AS to_entity_name, dst.deleted_at IS NOT NULL AS to_deleted,
+                substr(COALESCE(dst_blob.content, dst.content), 1, 500) AS to_preview
+         FROM chunk_relationships r
+         JOIN chunks src ON src.id = r.from_chunk_id
+         JOIN chunks dst ON dst.id = r.to_chunk_id
+         LEFT JOIN content_blobs src_blob ON src_blob.hash = src.blob_hash
+         LEFT JOIN content_blobs dst_blob ON dst_blob.hash = dst.blob_hash",
+        [],
+    )?;
+
+    // Ejecuciones de indexación (completa o incremental) que escribieron en el índice
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS indexing_runs (
+            run_id TEXT PRIMARY KEY,
+            project_path TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            status TEXT NOT NULL,
+            chunks_created INTEGER NOT NULL DEFAULT 0,
+            chunks_updated INTEGER NOT NULL DEFAULT 0,
+            chunks_deleted INTEGER NOT NULL DEFAULT 0,
+            errors INTEGER NOT NULL DEFAULT 0,
+            started_at TEXT NOT NULL,
+            completed_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_indexing_runs_project
+         ON indexing_runs(project_path, completed_at)",
+        [],
+    )?;
+
+    // Migration: tiempos por fase de cada indexación (JSON de `PhaseDuration`)
+    let _ = conn.execute(
+        "ALTER TABLE indexing_runs ADD COLUMN phase_durations TEXT",
+        [],
+    );
+
+    // Ejecuciones del benchmark de consultas e indexación (métricas en JSON)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS benchmark_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            crate_version TEXT NOT NULL,
+            iterations INTEGER NOT NULL,
+            total_chunks INTEGER NOT NULL,
+            indexing TEXT,
+            queries TEXT NOT NULL,
+            regressions TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_benchmark_runs_project
+         ON benchmark_runs(project_path, created_at)",
+        [],
+    )?;
+
+    // Diagnósticos transitorios de language servers (se reemplazan por archivo)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS lsp_diagnostics (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_path TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            chunk_id INTEGER,
+            entity_name TEXT,
+            line INTEGER NOT NULL,
+            column INTEGER NOT NULL,
+            end_line INTEGER NOT NULL,
+            end_column INTEGER NOT NULL,
+            severity TEXT NOT NULL,
+            code TEXT,
+            source TEXT,
+            message TEXT NOT NULL,
+            document_version INTEGER,
+            received_at TEXT NOT NULL,
+            FOREIGN KEY (chunk_id) REFERENCES chunks(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_lsp_diagnostics_file
+         ON lsp_diagnostics(project_path, file_path)",
+        [],
+    )?;
+
+    // Vista: errores abiertos de error_logs y diagnósticos LSP con columnas comunes
+    conn.execute(
+        "CREATE VIEW IF NOT EXISTS open_diagnostics AS
+         SELECT 'error_log' AS origin, id, project_path, file_path, entity_name,
+                NULL AS line, severity, error_type AS kind, message, last_seen AS seen_at
+         FROM error_logs WHERE is_resolved = 0
+         UNION ALL
+         SELECT 'lsp' AS origin, id, project_path, file_path, entity_name,
+                line, severity, COALESCE(source, 'lsp') AS kind, message, received_at AS seen_at
+         FROM lsp_diagnostics",
+        [],
+    )?;
+
+    // Extensiones de código agregadas (enabled = 1) o quitadas (enabled = 0) por proyecto
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS code_extension_overrides (
+            project_path TEXT NOT NULL,
+            extension TEXT NOT NULL,
+            enabled INTEGER NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (project_path, extension)
+        )",
+        [],
+    )?;
+
+    // Huella de cada archivo indexado: tamaño y mtime (ms) evitan re-hashear los que no cambiaron
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS file_fingerprints (
+            project_path TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            modified_at INTEGER NOT NULL,
+            indexed_at TEXT NOT NULL,
+            PRIMARY KEY (project_path, file_path)
+        )",
+        [],
+    )?;
+    // Algoritmo de hash del contenido de cada proyecto y la sal de su identificador anónimo
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_hashing (
+            project_path TEXT PRIMARY KEY,
+            algorithm TEXT NOT NULL,
+            salt TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    // Embedding de cada chunk: vector f32 little-endian, con el hash del contenido
+    // embebido y el modelo/versión que lo calculó
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS chunk_embeddings (
+            chunk_id INTEGER PRIMARY KEY,
+            project_path TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            model TEXT NOT NULL,
+            model_version TEXT NOT NULL,
+            dimensions INTEGER NOT NULL,
+            vector BLOB NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (chunk_id) REFERENCES chunks(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_chunk_embeddings_project ON chunk_embeddings(project_path)",
+        [],
+    )?;
+    // Backend de embeddings de cada proyecto (JSON de `EmbeddingBackend`)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS embedding_settings (
+            project_path TEXT PRIMARY KEY,
+            backend TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Migration: chunk cuyo archivo cambió desde que se generó (1 = desactualizado)
+    let _ = conn.execute(
+        "ALTER TABLE chunks ADD COLUMN stale INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
+
+    // Migration: rutas guardadas con `\` (índices creados en Windows) pasan a `/`
+    for (table, column) in FILE_PATH_COLUMNS {
+        conn.execute(
+            &format!(
+                "UPDATE OR IGNORE {0} SET {1} = replace({1}, char(92), '/')
+                 WHERE instr({1}, char(92)) > 0",
+                table, column
+            ),
+            [],
+        )?;
+    }
+    // Fallas de parseo y huellas que ya existían con la ruta normalizada
+    conn.execute(
+        "DELETE FROM parse_failures WHERE instr(file_path, char(92)) > 0",
+        [],
+    )?;
+    conn.execute(
+        "DELETE FROM file_fingerprints WHERE instr(file_path, char(92)) > 0",
+        [],
+    )?;
+
+    // Escrituras rechazadas en los proyectos marcados en `read_only_projects`
+    for table in READ_ONLY_TABLES {
+        for (operation, row) in [("insert", "NEW"), ("update", "OLD"), ("delete", "OLD")] {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER read_only_{0}_{1} BEFORE {2} ON {0}
+                     WHEN EXISTS (SELECT 1 FROM read_only_projects WHERE project_path = {3}.project_path)
+                     BEGIN SELECT RAISE(ABORT, '{4}'); END",
+                    table,
+                    operation,
+                    operation.to_uppercase(),
+                    row,
+                    READ_ONLY_ERROR
+                ),
+                [],
+            )?;
+        }
+    }
+    // Las relaciones no guardan el proyecto: se toma el del chunk de origen
+    for (operation, row) in [("insert", "NEW"), ("update", "OLD"), ("delete", "OLD")] {
+        conn.execute(
+            &format!(
+                "CREATE TRIGGER read_only_chunk_relationships_{0} BEFORE {1} ON chunk_relationships
+                 WHEN EXISTS (SELECT 1 FROM chunks c JOIN read_only_projects r ON r.project_path = c.project_path
+                              WHERE c.id = {2}.from_chunk_id)
+                 BEGIN SELECT RAISE(ABORT, '{3}'); END",
+                operation,
+                operation.to_uppercase(),
+                row,
+                READ_ONLY_ERROR
+            ),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reconstruye `chunks` sin el `UNIQUE` de `content_hash` de las bases anteriores.
+/// SQLite no permite quitar una restricción con ALTER TABLE: se copia la tabla con su
+/// definición actual (incluidas las columnas agregadas por otras migraciones)
+fn migrate_chunk_hash_per_project(conn: &Connection) -> SqliteResult<()> {
+    let definition: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'chunks'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(definition) = definition else {
+        return Ok(());
+    };
+    if !definition.contains("content_hash TEXT NOT NULL UNIQUE") {
+        return Ok(());
+    }
+    let rebuilt = definition
+        .replacen("content_hash TEXT NOT NULL UNIQUE", "content_hash TEXT NOT NULL", 1)
+        .replacen("chunks", "chunks_rebuild", 1);
+    // Conserva el contador de ids: no se reutilizan los de chunks ya purgados
+    let sequence: Option<i64> = conn
+        .query_row(
+            "SELECT seq FROM sqlite_sequence WHERE name = 'chunks'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    // Las vistas que leen `chunks` se recrean más adelante en init_chunk_database
+    conn.execute_batch("SAVEPOINT chunk_hash_migration")?;
+    let result = conn.execute_batch(&format!(
+        "DROP VIEW IF EXISTS chunk_relationship_details;
+         DROP VIEW IF EXISTS open_diagnostics;
+         {};
+         INSERT INTO chunks_rebuild SELECT * FROM chunks;
+         DROP TABLE chunks;
+         ALTER TABLE chunks_rebuild RENAME TO chunks;
+         UPDATE sqlite_sequence SET seq = MAX(seq, {}) WHERE name = 'chunks';",
+        rebuilt,
+        sequence.unwrap_or(0)
+    ));
+    if result.is_err() {
+        conn.execute_batch("ROLLBACK TO chunk_hash_migration")?;
+    }
+    conn.execute_batch("RELEASE chunk_hash_migration")?;
+    result
+}
+
+/// Mensaje de los triggers que rechazan escrituras en un índice de solo lectura
+pub const READ_ONLY_ERROR: &str = "Project index is read-only";
+
+/// Tablas del índice (con columna `project_path`) protegidas en modo solo lectura
+const READ_ONLY_TABLES: [&str; 8] = [
+    "chunks",
+    "snapshots",
+    "business_rules",
+    "secret_findings",
+    "parse_failures",
+    "index_operations",
+    "file_fingerprints",
+    "chunk_embeddings",
+];
+
+/// Columnas con rutas de archivo relativas al proyecto, normalizadas con [`normalize_file_path`]
+const FILE_PATH_COLUMNS: [(&str, &str); 11] = [
+    ("chunks", "file_path"),
+    ("business_rules", "file_path"),
+    ("snapshot_diffs", "file_path"),
+    ("snapshot_diffs", "old_path"),
+    ("error_logs", "file_path"),
+    ("secret_findings", "file_path"),
+    ("security_findings", "manifest_path"),
+    ("test_results", "file_path"),
+    ("parse_failures", "file_path"),
+    ("lsp_diagnostics", "file_path"),
+    ("file_fingerprints", "file_path"),
+];
+
+/// Parsea un timestamp guardado como texto RFC3339. Un valor inválido es un error
+/// de conversión de la columna en vez de reemplazarse por la hora actual
+pub fn parse_timestamp(value: &str, column: usize) -> SqliteResult<DateTime<Utc>> {
+    value.parse::<DateTime<Utc>>().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(column, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// Lee una columna de timestamp obligatoria
+pub fn row_timestamp(row: &rusqlite::Row, idx: usize) -> SqliteResult<DateTime<Utc>> {
+    parse_timestamp(&row.get::<_, String>(idx)?, idx)
+}
+
+/// Lee una columna de timestamp opcional (NULL = None)
+pub fn row_optional_timestamp(
+    row: &rusqlite::Row,
+    idx: usize,
+) -> SqliteResult<Option<DateTime<Utc>>> {
+    row.get::<_, Option<String>>(idx)?
+        .map(|value| parse_timestamp(&value, idx))
+        .transpose()
+}
+
+/// Ruta relativa con `/` como separador. Todas las rutas de archivo del índice se
+/// guardan y comparan así, para que un índice creado en Windows (`src\main.rs`) sirva
+/// en otras plataformas
+pub fn normalize_file_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Calcula el hash SHA256 del contenido
+pub fn calculate_content_hash(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Hash del contenido con el algoritmo del proyecto. SHA256 da el mismo hash que
+/// [`calculate_content_hash`]; BLAKE3 lleva el prefijo `blake3:`
+pub fn calculate_hash(content: &str, algorithm: HashAlgorithm) -> String {
+    match algorithm {
+        HashAlgorithm::Sha256 => calculate_content_hash(content),
+        HashAlgorithm::Blake3 => format!("blake3:{}", blake3::hash(content.as_bytes()).to_hex()),
+    }
+}
+
+/// Inserta o actualiza un chunk
+/// Retorna (created: bool) - true si se creó nuevo, false si se actualizó existente
+pub fn upsert_chunk(conn: &Connection, chunk: &Chunk, snapshot_id: Option<i64>) -> Result<bool> {
+    let chunk_type_str = chunk.chunk_type.as_str();
+    let now = Utc::now().to_rfc3339();
+
+    // Check if chunk already exists (solo en su proyecto: otro proyecto con el mismo
+    // contenido tiene su propia fila, que puede ser de solo lectura)
+    let existing = get_chunk_id_by_hash(conn, &chunk.project_path, &chunk.content_hash)?;
+
+    if let Some(id) = existing {
+        // Update existing chunk (si estaba en la papelera, vuelve a estar vigente)
+        conn.execute(
+            "UPDATE chunks SET updated_at = ?1, metadata = ?2, snapshot_id = ?3, deleted_at = NULL, deleted_by_operation = NULL
+             WHERE id = ?4",
+            params![&now, &chunk.metadata, snapshot_id, id],
+        )?;
+        Ok(false) // Updated, not created
+    } else {
+        // Insert new chunk (el contenido va al blob compartido)
+        let blob_hash = store_content_blob(conn, &chunk.content)?;
+        conn.execute(
+            "INSERT INTO chunks (project_path, chunk_type, file_path, entity_name, content, content_hash, metadata, snapshot_id, created_at, updated_at, blob_hash)
+             VALUES (?1, ?2, ?3, ?4, '', ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                &chunk.project_path,
+                chunk_type_str,
+                chunk.file_path.as_deref().map(normalize_file_path),
+                &chunk.entity_name,
+                &chunk.content_hash,
+                &chunk.metadata,
+                snapshot_id,
+                &now,
+                &now,
+                &blob_hash,
+            ],
+        )?;
+        Ok(true) // Created new
+    }
+}
+
+/// Guarda el contenido en `content_blobs` (si no estaba) y retorna su hash
+pub fn store_content_blob(conn: &Connection, content: &str) -> Result<String> {
+    let hash = calculate_content_hash(content);
+    conn.execute(
+        "INSERT OR IGNORE INTO content_blobs (hash, content, size, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![&hash, content, content.len() as i64, Utc::now().to_rfc3339()],
+    )?;
+    Ok(hash)
+}
+
+/// Elimina los blobs que ya no referencia ningún chunk. Retorna los eliminados
+pub fn delete_orphan_blobs(conn: &Connection) -> Result<usize> {
+    let count = conn.execute(
+        "DELETE FROM content_blobs
+         WHERE NOT EXISTS (SELECT 1 FROM chunks WHERE chunks.blob_hash = content_blobs.hash)",
+        [],
+    )?;
+    Ok(count)
+}
+
+/// Expresión SQL con el contenido de un chunk: el de su blob o, en filas anteriores a
+/// la deduplicación, el de la columna `content`. Solo sirve con `FROM chunks` sin alias
+pub const CHUNK_CONTENT: &str = "COALESCE((SELECT content_blobs.content FROM content_blobs WHERE content_blobs.hash = chunks.blob_hash), chunks.content)";
+
+/// Columnas de `chunks` en el orden que espera [`parse_chunk_row`]
+const CHUNK_COLUMNS: &str = "id, project_path, chunk_type, file_path, entity_name, COALESCE((SELECT content_blobs.content FROM content_blobs WHERE content_blobs.hash = chunks.blob_hash), chunks.content), content_hash, metadata, created_at, updated_at";
+
+/// Condiciones WHERE (precedidas de AND) y parámetros para los filtros de un ChunkQuery
+fn chunk_query_filters(query: &ChunkQuery) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut sql = String::new();
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if !query.include_deleted {
+        sql.push_str(" AND deleted_at IS NULL");
+    }
+
+    if let Some(project_path) = &query.project_path {
+        sql.push_str(" AND project_path = ?");
+        params_vec.push(Box::new(project_path.clone()));
+    }
+
+    if let Some(chunk_types) = &query.chunk_types {
+        let placeholders: Vec<String> = chunk_types.iter().map(|_| "?".to_string()).collect();
+        sql.push_str(&format!(" AND chunk_type IN ({})", placeholders.join(",")));
+        for ct in chunk_types {
+            params_vec.push(Box::new(ct.as_str().to_string()));
+        }
+    }
+
+    if let Some(file_path) = &query.file_path {
+        sql.push_str(" AND file_path = ?");
+        params_vec.push(Box::new(normalize_file_path(file_path)));
+    }
+
+    if let Some(entity_name) = &query.entity_name {
+        sql.push_str(" AND entity_name = ?");
+        params_vec.push(Box::new(entity_name.clone()));
+    }
+
+    if query.dependency_kind.is_some() || query.dependency_package.is_some() {
+        sql.push_str(" AND EXISTS (SELECT 1 FROM json_each(chunks.metadata, '$.dependencies') d WHERE 1=1");
+        if let Some(kind) = &query.dependency_kind {
+            sql.push_str(" AND json_extract(d.value, '$.kind') = ?");
+            params_vec.push(Box::new(kind.as_str().to_string()));
+        }
+        if let Some(package) = &query.dependency_package {
+            sql.push_str(" AND json_extract(d.value, '$.package') = ?");
+            params_vec.push(Box::new(package.clone()));
+        }
+        sql.push(')');
+    }
+
+    if let Some(commit_type) = &query.commit_type {
+        sql.push_str(" AND json_extract(metadata, '$.commit_type') = ?");
+        params_vec.push(Box::new(commit_type.clone()));
+    }
+
+    if let Some(breaking) = query.breaking_change {
+        sql.push_str(" AND COALESCE(json_extract(metadata, '$.breaking_change'), 0) = ?");
+        params_vec.push(Box::new(breaking));
+    }
+
+    if let Some(prefix) = &query.touches_path {
+        sql.push_str(
+            " AND EXISTS (SELECT 1 FROM json_each(chunks.metadata, '$.files_modified') f WHERE substr(f.value, 1, length(?)) = ?)",
+        );
+        let prefix = normalize_file_path(prefix);
+        params_vec.push(Box::new(prefix.clone()));
+        params_vec.push(Box::new(prefix));
+    }
+
+    if let Some(branch) = &query.branch {
+        sql.push_str(" AND EXISTS (SELECT 1 FROM json_each(chunks.metadata, '$.branches') b WHERE b.value = ?)");
+        params_vec.push(Box::new(branch.clone()));
+    }
+
+    if let Some(stale) = query.stale {
+        sql.push_str(" AND COALESCE(stale, 0) = ?");
+        params_vec.push(Box::new(stale));
+    }
+
+    (sql, params_vec)
+}
+
+fn parse_chunk_row(row: &rusqlite::Row) -> SqliteResult<Chunk> {
+    let chunk_type_str: String = row.get(2)?;
+    let chunk_type = ChunkType::from_str(&chunk_type_str)
+        .ok_or_else(|| rusqlite::Error::InvalidQuery)?;
+
+    Ok(Chunk {
+        id: Some(row.get(0)?),
+        project_path: row.get(1)?,
+        chunk_type,
+        file_path: row.get(3)?,
+        entity_name: row.get(4)?,
+        content: row.get(5)?,
+        content_hash: row.get(6)?,
+        metadata: row.get(7)?,
+        created_at: row_timestamp(row, 8)?,
+        updated_at: row_timestamp(row, 9)?,
+    })
+}
+
+/// Obtiene un chunk por id (incluidos los que están en la papelera)
+pub fn get_chunk_by_id(conn: &Connection, chunk_id: i64) -> Result<Option<Chunk>> {
+    let chunk = conn
+        .query_row(
+            &format!("SELECT {} FROM chunks WHERE id = ?1", CHUNK_COLUMNS),
+            params![chunk_id],
+            parse_chunk_row,
+        )
+        .optional()?;
+    Ok(chunk)
+}
+
+/// Chunks vigentes con los ids dados, en una sola consulta y en el orden pedido.
+/// Los ids inexistentes o en la papelera se omiten y los repetidos se devuelven una vez
+pub fn get_chunks_by_ids(conn: &Connection, chunk_ids: &[i64]) -> Result<Vec<Chunk>> {
+    if chunk_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM chunks
+         WHERE id IN (SELECT value FROM json_each(?1)) AND deleted_at IS NULL",
+        CHUNK_COLUMNS
+    ))?;
+    let mut by_id: HashMap<i64, Chunk> = stmt
+        .query_map(params![serde_json::to_string(chunk_ids)?], parse_chunk_row)?
+        .map(|chunk| chunk.map(|c| (c.id.unwrap_or_default(), c)))
+        .collect::<SqliteResult<_>>()?;
+    Ok(chunk_ids.iter().filter_map(|id| by_id.remove(id)).collect())
+}
+
+/// Versiones de un chunk (mismo proyecto, tipo, archivo y entidad), de la más antigua
+/// a la más reciente
+pub fn get_chunk_versions(conn: &Connection, chunk_id: i64) -> Result<Vec<ChunkVersion>> {
+    let mut stmt = conn.prepare(
+        "SELECT v.id, v.content_hash, v.created_at, v.updated_at, v.deleted_at IS NOT NULL
+         FROM chunks c
+         JOIN chunks v ON v.project_path = c.project_path AND v.chunk_type = c.chunk_type
+                      AND v.file_path IS c.file_path AND v.entity_name IS c.entity_name
+         WHERE c.id = ?1
+         ORDER BY v.id",
+    )?;
+    let versions = stmt
+        .query_map(params![chunk_id], |row| {
+            Ok(ChunkVersion {
+                version: 0,
+                chunk_id: row.get(0)?,
+                content_hash: row.get(1)?,
+                created_at: row_timestamp(row, 2)?,
+                updated_at: row_timestamp(row, 3)?,
+                deleted: row.get(4)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(versions
+        .into_iter()
+        .enumerate()
+        .map(|(idx, version)| ChunkVersion {
+            version: idx + 1,
+            ..version
+        })
+        .collect())
+}
+
+/// Obtiene chunks según criterios de búsqueda
+pub fn query_chunks(conn: &Connection, query: &ChunkQuery) -> Result<Vec<Chunk>> {
+    let (filters, params_vec) = chunk_query_filters(query);
+    let mut sql = format!("SELECT {} FROM chunks WHERE 1=1{}", CHUNK_COLUMNS, filters);
+
+    sql.push_str(" ORDER BY updated_at DESC");
+
+    if let Some(limit) = query.limit {
+        sql.push_str(&format!(" LIMIT {}", limit));
+    }
+
+    if let Some(offset) = query.offset {
+        sql.push_str(&format!(" OFFSET {}", offset));
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+    let chunks = stmt
+        .query_map(param_refs.as_slice(), parse_chunk_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(chunks)
+}
+
+/// Página de chunks con paginación por cursor (id ascendente): `limit`/`offset` del
+/// query se ignoran. Retorna como cursor siguiente el id del último chunk si puede
+/// haber más
+pub fn fetch_chunks_page(
+    conn: &Connection,
+    query: &ChunkQuery,
+    after_id: Option<i64>,
+    page_size: usize,
+) -> Result<ChunkPage> {
+    let (filters, mut params_vec) = chunk_query_filters(query);
+    let sql = format!(
+        "SELECT {} FROM chunks WHERE id > ?{} ORDER BY id ASC LIMIT {}",
+        CHUNK_COLUMNS,
+        filters,
+        page_size.max(1)
+    );
+    params_vec.insert(0, Box::new(after_id.unwrap_or(0)));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+    let chunks = stmt
+        .query_map(param_refs.as_slice(), parse_chunk_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    let next_cursor = if chunks.len() >= page_size.max(1) {
+        chunks.last().and_then(|c| c.id)
+    } else {
+        None
+    };
+    Ok(ChunkPage { chunks, next_cursor })
+}
+
+/// Iterador que recorre los chunks de un query página a página sin materializarlos todos
+pub struct ChunkIter<'a> {
+    conn: &'a Connection,
+    query: ChunkQuery,
+    page_size: usize,
+    cursor: Option<i64>,
+    buffer: std::vec::IntoIter<Chunk>,
+    exhausted: bool,
+}
+
+impl<'a> ChunkIter<'a> {
+    pub fn new(conn: &'a Connection, query: ChunkQuery, page_size: usize) -> Self {
+        Self {
+            conn,
+            query,
+            page_size,
+            cursor: None,
+            buffer: Vec::new().into_iter(),
+            exhausted: false,
+        }
+    }
+}
+
+impl Iterator for ChunkIter<'_> {
+    type Item = Result<Chunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(chunk) = self.buffer.next() {
+            return Some(Ok(chunk));
+        }
+        if self.exhausted {
+            return None;
+        }
+
+        match fetch_chunks_page(self.conn, &self.query, self.cursor, self.page_size) {
+            Ok(page) => {
+                self.exhausted = page.next_cursor.is_none();
+                self.cursor = page.next_cursor;
+                self.buffer = page.chunks.into_iter();
+                self.buffer.next().map(Ok)
+            }
+            Err(e) => {
+                self.exhausted = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Inserta una relación entre chunks
+pub fn insert_relationship(conn: &Connection, rel: &ChunkRelationship) -> Result<i64> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO chunk_relationships (from_chunk_id, to_chunk_id, relationship_type, metadata, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            rel.from_chunk_id,
+            rel.to_chunk_id,
+            rel.relationship_type.as_str(),
+            &rel.metadata,
+            &now,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Obtiene el id de un chunk del proyecto a partir de su hash de contenido
+pub fn get_chunk_id_by_hash(
+    conn: &Connection,
+    project_path: &str,
+    content_hash: &str,
+) -> Result<Option<i64>> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM chunks WHERE project_path = ?1 AND content_hash = ?2",
+            params![project_path, content_hash],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+/// Obtiene el id del chunk más reciente de un tipo para un archivo del proyecto
+/// (el chunk del archivo completo, no los de sus entidades)
+pub fn get_latest_file_chunk_id(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    chunk_type: &ChunkType,
+) -> Result<Option<i64>> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM chunks WHERE project_path = ?1 AND file_path = ?2 AND chunk_type = ?3
+               AND entity_name IS NULL AND deleted_at IS NULL
+             ORDER BY updated_at DESC, id DESC LIMIT 1",
+            params![
+                project_path,
+                normalize_file_path(file_path),
+                chunk_type.as_str()
+            ],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+/// Elimina las relaciones de un proyecto generadas por una fuente concreta
+/// (campo `source` del JSON de metadata), para poder regenerarlas sin duplicados
+pub fn delete_relationships_by_source(
+    conn: &Connection,
+    project_path: &str,
+    source: &str,
+) -> Result<usize> {
+    let count = conn.execute(
+        "DELETE FROM chunk_relationships
+         WHERE json_extract(metadata, '$.source') = ?2
+           AND from_chunk_id IN (SELECT id FROM chunks WHERE project_path = ?1)",
+        params![project_path, source],
+    )?;
+    Ok(count)
+}
+
+/// Reemplaza las relaciones de un origen que salen de un archivo (`from_file` en la
+/// metadata) por `relationships`, todo o nada. Usa un savepoint para poder ir dentro
+/// de otra transacción (dry run)
+pub fn replace_file_relationships(
+    conn: &Connection,
+    project_path: &str,
+    source: &str,
+    from_file: &str,
+    relationships: &[ChunkRelationship],
+) -> Result<usize> {
+    conn.execute_batch("SAVEPOINT replace_file_relationships")?;
+    let replaced = (|| -> Result<usize> {
+        conn.execute(
+            "DELETE FROM chunk_relationships
+             WHERE json_extract(metadata, '$.source') = ?2
+               AND json_extract(metadata, '$.from_file') = ?3
+               AND from_chunk_id IN (SELECT id FROM chunks WHERE project_path = ?1)",
+            params![project_path, source, from_file],
+        )?;
+        for rel in relationships {
+            insert_relationship(conn, rel)?;
+        }
+        Ok(relationships.len())
+    })();
+
+    match replaced {
+        Ok(count) => {
+            conn.execute_batch("RELEASE replace_file_relationships")?;
+            Ok(count)
+        }
+        Err(e) => {
+            conn.execute_batch(
+                "ROLLBACK TO replace_file_relationships; RELEASE replace_file_relationships",
+            )?;
+            Err(e)
+        }
+    }
+}
+
+/// Archivos con relaciones de un origen que apuntan (`to_file`) a alguno de `to_files`
+pub fn get_files_related_to(
+    conn: &Connection,
+    project_path: &str,
+    source: &str,
+    to_files: &BTreeSet<String>,
+) -> Result<BTreeSet<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT json_extract(r.metadata, '$.from_file'),
+                json_extract(r.metadata, '$.to_file')
+         FROM chunk_relationships r
+         JOIN chunks c ON c.id = r.from_chunk_id
+         WHERE c.project_path = ?1 AND json_extract(r.metadata, '$.source') = ?2
+           AND json_extract(r.metadata, '$.from_file') IS NOT NULL",
+    )?;
+    let pairs = stmt
+        .query_map(params![project_path, source], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(pairs
+        .into_iter()
+        .filter(|(_, to_file)| to_file.as_ref().is_some_and(|f| to_files.contains(f)))
+        .map(|(from_file, _)| from_file)
+        .collect())
+}
+
+/// Versión vigente más reciente de cada archivo para un tipo de chunk, sin contar
+/// los chunks de entidad: file_path → (chunk_id, contenido)
+pub fn get_latest_file_contents(
+    conn: &Connection,
+    project_path: &str,
+    chunk_type: &ChunkType,
+) -> Result<BTreeMap<String, (i64, String)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, file_path, {} FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND file_path IS NOT NULL
+           AND entity_name IS NULL AND deleted_at IS NULL
+         ORDER BY updated_at DESC, id DESC",
+        CHUNK_CONTENT
+    ))?;
+    let rows = stmt
+        .query_map(params![project_path, chunk_type.as_str()], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    let mut latest = BTreeMap::new();
+    for (id, file_path, content) in rows {
+        latest.entry(file_path).or_insert((id, content));
+    }
+    Ok(latest)
+}
+
+/// Obtiene relaciones de un chunk
+pub fn get_relationships(
+    conn: &Connection,
+    chunk_id: i64,
+    outgoing: bool,
+) -> Result<Vec<ChunkRelationship>> {
+    let sql = if outgoing {
+        "SELECT id, from_chunk_id, to_chunk_id, relationship_type, metadata, created_at
+         FROM chunk_relationships WHERE from_chunk_id = ?1"
+    } else {
+        "SELECT id, from_chunk_id, to_chunk_id, relationship_type, metadata, created_at
+         FROM chunk_relationships WHERE to_chunk_id = ?1"
+    };
+
+    let mut stmt = conn.prepare(sql)?;
+    let rels = stmt
+        .query_map(params![chunk_id], |row| {
+            let rel_type_str: String = row.get(3)?;
+
+            Ok(ChunkRelationship {
+                id: Some(row.get(0)?),
+                from_chunk_id: row.get(1)?,
+                to_chunk_id: row.get(2)?,
+                relationship_type: RelationshipType::from_str(&rel_type_str)
+                    .unwrap_or(RelationshipType::DependsOn),
+                metadata: row.get(4)?,
+                created_at: row_timestamp(row, 5)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(rels)
+}
+
+/// Líneas no vacías del comienzo de un chunk que forman su resumen
+const SUMMARY_LINES: usize = 3;
+
+/// Un chunk con sus relaciones en ambas direcciones y el resumen de cada chunk vigente
+/// relacionado, leídos de `chunk_relationship_details` en una sola consulta
+pub fn get_chunk_with_context(
+    conn: &Connection,
+    chunk_id: i64,
+) -> Result<Option<ChunkWithContext>> {
+    let Some(chunk) = get_chunk_by_id(conn, chunk_id)? else {
+        return Ok(None);
+    };
+
+    // Columnas del otro extremo según la dirección de la relación
+    let mut stmt = conn.prepare(
+        "SELECT id, relationship_type, metadata, from_chunk_id = ?1 AS outgoing,
+                CASE WHEN from_chunk_id = ?1 THEN to_chunk_id ELSE from_chunk_id END,
+                CASE WHEN from_chunk_id = ?1 THEN to_chunk_type ELSE from_chunk_type END,
+                CASE WHEN from_chunk_id = ?1 THEN to_file_path ELSE from_file_path END,
+                CASE WHEN from_chunk_id = ?1 THEN to_entity_name ELSE from_entity_name END,
+                CASE WHEN from_chunk_id = ?1 THEN to_preview ELSE from_preview END
+         FROM chunk_relationship_details
+         WHERE (from_chunk_id = ?1 AND NOT to_deleted) OR (to_chunk_id = ?1 AND NOT from_deleted)
+         ORDER BY id",
+    )?;
+    let rows = stmt
+        .query_map(params![chunk_id], |row| {
+            let relationship_type = RelationshipType::from_str(&row.get::<_, String>(1)?);
+            let chunk_type = ChunkType::from_str(&row.get::<_, String>(5)?);
+            let preview: String = row.get(8)?;
+            let (Some(relationship_type), Some(chunk_type)) = (relationship_type, chunk_type)
+            else {
+                return Ok(None);
+            };
+            Ok(Some((
+                row.get::<_, bool>(3)?,
+                LinkedChunk {
+                    relationship_id: row.get(0)?,
+                    relationship_type,
+                    relationship_metadata: row.get(2)?,
+                    chunk_id: row.get(4)?,
+                    chunk_type,
+                    file_path: row.get(6)?,
+                    entity_name: row.get(7)?,
+                    summary: preview
+                        .lines()
+                        .map(str::trim_end)
+                        .filter(|line| !line.trim().is_empty())
+                        .take(SUMMARY_LINES)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                },
+            )))
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    let (outgoing, incoming): (Vec<_>, Vec<_>) = rows
+        .into_iter()
+        .flatten()
+        .partition(|(outgoing, _)| *outgoing);
+    let outgoing = outgoing.into_iter().map(|(_, related)| related).collect();
+    let incoming = incoming.into_iter().map(|(_, related)| related).collect();
+
+    Ok(Some(ChunkWithContext {
+        chunk,
+        outgoing,
+        incoming,
+    }))
+}
+
+/// Inserta o actualiza una regla de negocio
+pub fn upsert_business_rule(conn: &Connection, rule: &BusinessRule) -> Result<i64> {
+    let now = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT INTO business_rules (project_path, entity_name, file_path, rule_description, ai_interpretation, user_correction, is_validated, validation_date, created_at, updated_at, tags, check_spec)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+         ON CONFLICT(rowid) DO UPDATE SET
+            rule_description = ?4,
+            ai_interpretation = ?5,
+            user_correction = ?6,
+            is_validated = ?7,
+            validation_date = ?8,
+            updated_at = ?10,
+            tags = ?11,
+            check_spec = ?12",
+        params![
+            &rule.project_path,
+            &rule.entity_name,
+            normalize_file_path(&rule.file_path),
+            &rule.rule_description,
+            &rule.ai_interpretation,
+            &rule.user_correction,
+            rule.is_validated,
+            rule.validation_date.as_ref().map(|d| d.to_rfc3339()),
+            &now,
+            &now,
+            serde_json::to_string(&rule.tags)?,
+            rule.check.as_ref().map(serde_json::to_string).transpose()?,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Columnas de business_rules en el orden que espera [`parse_business_rule_row`]
+pub(crate) const BUSINESS_RULE_COLUMNS: &str = "id, project_path, entity_name, file_path, rule_description, ai_interpretation, user_correction, is_validated, validation_date, created_at, updated_at, tags, check_spec";
+
+pub(crate) fn parse_business_rule_row(row: &rusqlite::Row) -> SqliteResult<BusinessRule> {
+    let tags: String = row.get(11)?;
+    let check: Option<String> = row.get(12)?;
+    Ok(BusinessRule {
+        id: Some(row.get(0)?),
+        project_path: row.get(1)?,
+        entity_name: row.get(2)?,
+        file_path: row.get(3)?,
+        rule_description: row.get(4)?,
+        ai_interpretation: row.get(5)?,
+        user_correction: row.get(6)?,
+        is_validated: row.get(7)?,
+        validation_date: row_optional_timestamp(row, 8)?,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        check: check.and_then(|c| serde_json::from_str(&c).ok()),
+        created_at: row_timestamp(row, 9)?,
+        updated_at: row_timestamp(row, 10)?,
+    })
+}
+
+/// Obtiene reglas de negocio para un proyecto
+pub fn get_business_rules(conn: &Connection, project_path: &str) -> Result<Vec<BusinessRule>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM business_rules WHERE project_path = ?1 ORDER BY entity_name",
+        BUSINESS_RULE_COLUMNS
+    ))?;
+
+    let rules = stmt
+        .query_map(params![project_path], parse_business_rule_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(rules)
+}
+
+/// Reemplaza las etiquetas de una regla de negocio
+pub fn set_business_rule_tags(conn: &Connection, rule_id: i64, tags: &[String]) -> Result<()> {
+    let updated = conn.execute(
+        "UPDATE business_rules SET tags = ?1, updated_at = ?2 WHERE id = ?3",
+        params![
+            serde_json::to_string(tags)?,
+            Utc::now().to_rfc3339(),
+            rule_id
+        ],
+    )?;
+    if updated == 0 {
+        anyhow::bail!("Business rule not found: {}", rule_id);
+    }
+    Ok(())
+}
+
+/// Asigna (o quita, con None) la comprobación automática de una regla de negocio
+pub fn set_business_rule_check(
+    conn: &Connection,
+    rule_id: i64,
+    check: Option<&RuleCheck>,
+) -> Result<()> {
+    let updated = conn.execute(
+        "UPDATE business_rules SET check_spec = ?1, updated_at = ?2 WHERE id = ?3",
+        params![
+            check.map(serde_json::to_string).transpose()?,
+            Utc::now().to_rfc3339(),
+            rule_id
+        ],
+    )?;
+    if updated == 0 {
+        anyhow::bail!("Business rule not found: {}", rule_id);
+    }
+    Ok(())
+}
+
+/// Guarda qué reglas de negocio se fijan en los paquetes de contexto del proyecto
+pub fn set_context_rule_pinning(
+    conn: &Connection,
+    project_path: &str,
+    mode: RulePinning,
+) -> Result<()> {
+    if mode == RulePinning::None {
+        conn.execute(
+            "DELETE FROM context_rule_pinning WHERE project_path = ?1",
+            params![project_path],
+        )?;
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO context_rule_pinning (project_path, mode, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(project_path) DO UPDATE SET mode = excluded.mode, updated_at = excluded.updated_at",
+        params![project_path, mode.as_str(), Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Obtiene qué reglas de negocio se fijan en los paquetes de contexto del proyecto
+pub fn get_context_rule_pinning(conn: &Connection, project_path: &str) -> Result<RulePinning> {
+    let mode: Option<String> = conn
+        .query_row(
+            "SELECT mode FROM context_rule_pinning WHERE project_path = ?1",
+            params![project_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(mode
+        .as_deref()
+        .and_then(RulePinning::from_str)
+        .unwrap_or_default())
+}
+
+/// Crea un snapshot con información Git
+pub fn create_snapshot(conn: &Connection, snapshot: &Snapshot) -> Result<i64> {
+    let now = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT INTO snapshots (project_path, snapshot_type, parent_snapshot_id, message, user_message, changed_files, diff_summary, metadata, git_commit_hash, git_tag, git_branch, version_major, version_minor, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            &snapshot.project_path,
+            snapshot.snapshot_type.as_str(),
+            snapshot.parent_snapshot_id,
+            &snapshot.message,
+            &snapshot.user_message,
+            &snapshot.changed_files,
+            &snapshot.diff_summary,
+            &snapshot.metadata,
+            &snapshot.git_commit_hash,
+            &snapshot.git_tag,
+            &snapshot.git_branch,
+            snapshot.version_major,
+            snapshot.version_minor,
+            &now,
+        ],
+    )?;
+    let snapshot_id = conn.last_insert_rowid();
+    set_active_snapshot(conn, &snapshot.project_path, snapshot_id)?;
+    Ok(snapshot_id)
+}
+
+/// Marca el snapshot activo de un proyecto (al que se atribuyen los nuevos errores)
+pub fn set_active_snapshot(conn: &Connection, project_path: &str, snapshot_id: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO active_snapshots (project_path, snapshot_id, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(project_path) DO UPDATE SET snapshot_id = excluded.snapshot_id, updated_at = excluded.updated_at",
+        params![project_path, snapshot_id, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Obtiene el snapshot activo de un proyecto.
+/// Si el registrado ya no existe, usa el snapshot más reciente
+pub fn get_active_snapshot_id(conn: &Connection, project_path: &str) -> Result<Option<i64>> {
+    let tracked: Option<i64> = conn
+        .query_row(
+            "SELECT a.snapshot_id FROM active_snapshots a
+             JOIN snapshots s ON s.id = a.snapshot_id
+             WHERE a.project_path = ?1",
+            params![project_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if tracked.is_some() {
+        return Ok(tracked);
+    }
+
+    let latest = conn
+        .query_row(
+            "SELECT id FROM snapshots WHERE project_path = ?1 ORDER BY created_at DESC, id DESC LIMIT 1",
+            params![project_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(latest)
+}
+
+/// Obtiene snapshots de un proyecto
+pub fn get_snapshots(
+    conn: &Connection,
+    project_path: &str,
+    snapshot_type: Option<SnapshotType>,
+) -> Result<Vec<Snapshot>> {
+    let (sql, has_type_filter) = if let Some(st) = snapshot_type {
+        (
+            "SELECT id, project_path, snapshot_type, parent_snapshot_id, message, user_message, changed_files, diff_summary, metadata, git_commit_hash, git_tag, git_branch, version_major, version_minor, created_at
+             FROM snapshots WHERE project_path = ?1 AND snapshot_type = ?2 ORDER BY version_major DESC, version_minor DESC, created_at DESC",
+            Some(st),
+        )
+    } else {
+        (
+            "SELECT id, project_path, snapshot_type, parent_snapshot_id, message, user_message, changed_files, diff_summary, metadata, git_commit_hash, git_tag, git_branch, version_major, version_minor, created_at
+             FROM snapshots WHERE project_path = ?1 ORDER BY version_major DESC, version_minor DESC, created_at DESC",
+            None,
+        )
+    };
+
+    let mut stmt = conn.prepare(sql)?;
+    let snapshots = if let Some(st) = has_type_filter {
+        stmt.query_map(params![project_path, st.as_str()], |row| {
+            parse_snapshot_row(row)
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?
+    } else {
+        stmt.query_map(params![project_path], parse_snapshot_row)?
+            .collect::<SqliteResult<Vec<_>>>()?
+    };
+
+    Ok(snapshots)
+}
+
+/// Obtiene un snapshot por id
+pub fn get_snapshot(conn: &Connection, snapshot_id: i64) -> Result<Option<Snapshot>> {
+    let snapshot = conn
+        .query_row(
+            "SELECT id, project_path, snapshot_type, parent_snapshot_id, message, user_message, changed_files, diff_summary, metadata, git_commit_hash, git_tag, git_branch, version_major, version_minor, created_at
+             FROM snapshots WHERE id = ?1",
+            params![snapshot_id],
+            parse_snapshot_row,
+        )
+        .optional()?;
+    Ok(snapshot)
+}
+
+fn parse_snapshot_row(row: &rusqlite::Row) -> SqliteResult<Snapshot> {
+    let snapshot_type_str: String = row.get(2)?;
+
+    Ok(Snapshot {
+        id: Some(row.get(0)?),
+        project_path: row.get(1)?,
+        snapshot_type: if snapshot_type_str == "master" {
+            SnapshotType::Master
+        } else {
+            SnapshotType::Agent
+        },
+        parent_snapshot_id: row.get(3)?,
+        message: row.get(4)?,
+        user_message: row.get(5)?,
+        changed_files: row.get(6)?,
+        diff_summary: row.get(7)?,
+        metadata: row.get(8)?,
+        git_commit_hash: row.get(9)?,
+        git_tag: row.get(10)?,
+        git_branch: row.get(11)?,
+        version_major: row.get(12)?,
+        version_minor: row.get(13)?,
+        created_at: row_timestamp(row, 14)?,
+    })
+}
+
+/// Guarda los diffs por archivo de un snapshot (reemplaza los existentes)
+pub fn store_snapshot_diffs(conn: &Connection, snapshot_id: i64, files: &[SnapshotFileDiff]) -> Result<()> {
+    conn.execute("DELETE FROM snapshot_diffs WHERE snapshot_id = ?1", params![snapshot_id])?;
+    for file in files {
+        conn.execute(
+            "INSERT INTO snapshot_diffs (snapshot_id, file_path, old_path, status, additions, deletions, is_binary, patch, truncated)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                snapshot_id,
+                normalize_file_path(&file.file_path),
+                file.old_path.as_deref().map(normalize_file_path),
+                &file.status,
+                file.additions as i64,
+                file.deletions as i64,
+                file.is_binary,
+                &file.patch,
+                file.truncated,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Obtiene los diffs por archivo guardados para un snapshot
+pub fn get_snapshot_file_diffs(conn: &Connection, snapshot_id: i64) -> Result<Vec<SnapshotFileDiff>> {
+    let mut stmt = conn.prepare(
+        "SELECT file_path, old_path, status, additions, deletions, is_binary, patch, truncated
+         FROM snapshot_diffs WHERE snapshot_id = ?1 ORDER BY file_path",
+    )?;
+
+    let files = stmt
+        .query_map(params![snapshot_id], |row| {
+            Ok(SnapshotFileDiff {
+                file_path: row.get(0)?,
+                old_path: row.get(1)?,
+                status: row.get(2)?,
+                additions: row.get::<_, i64>(3)? as usize,
+                deletions: row.get::<_, i64>(4)? as usize,
+                is_binary: row.get(5)?,
+                patch: row.get(6)?,
+                truncated: row.get(7)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(files)
+}
+
+/// Guarda el reparto del presupuesto de contexto de un proyecto (vacío = sin reparto)
+pub fn set_context_budget_weights(
+    conn: &Connection,
+    project_path: &str,
+    weights: &[ChunkTypeWeight],
+) -> Result<()> {
+    if weights.is_empty() {
+        conn.execute(
+            "DELETE FROM context_budget_weights WHERE project_path = ?1",
+            params![project_path],
+        )?;
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO context_budget_weights (project_path, weights, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(project_path) DO UPDATE SET weights = excluded.weights, updated_at = excluded.updated_at",
+        params![project_path, serde_json::to_string(weights)?, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Obtiene el reparto del presupuesto de contexto de un proyecto, si se configuró
+pub fn get_context_budget_weights(conn: &Connection, project_path: &str) -> Result<Option<Vec<ChunkTypeWeight>>> {
+    let weights: Option<String> = conn
+        .query_row(
+            "SELECT weights FROM context_budget_weights WHERE project_path = ?1",
+            params![project_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(match weights {
+        Some(json) => Some(serde_json::from_str(&json)?),
+        None => None,
+    })
+}
+
+/// Registra un paquete de contexto entregado
+pub fn insert_context_delivery(conn: &Connection, delivery: &ContextDelivery) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO context_deliveries (project_path, session_id, snapshot_id, task_text, chunk_ids, chunk_tokens, total_tokens, token_budget, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            &delivery.project_path,
+            &delivery.session_id,
+            delivery.snapshot_id,
+            &delivery.task_text,
+            serde_json::to_string(&delivery.chunk_ids)?,
+            serde_json::to_string(&delivery.chunk_tokens)?,
+            delivery.total_tokens as i64,
+            delivery.token_budget as i64,
+            delivery.created_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Obtiene los paquetes entregados de un proyecto (más recientes primero),
+/// opcionalmente de una sesión o un snapshot
+pub fn get_context_deliveries(
+    conn: &Connection,
+    project_path: &str,
+    session_id: Option<&str>,
+    snapshot_id: Option<i64>,
+) -> Result<Vec<ContextDelivery>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, session_id, snapshot_id, task_text, chunk_ids, chunk_tokens, total_tokens, token_budget, created_at
+         FROM context_deliveries
+         WHERE project_path = ?1 AND (?2 IS NULL OR session_id = ?2) AND (?3 IS NULL OR snapshot_id = ?3)
+         ORDER BY created_at DESC, id DESC",
+    )?;
+
+    let deliveries = stmt
+        .query_map(params![project_path, session_id, snapshot_id], |row| {
+            let chunk_ids: String = row.get(5)?;
+            let chunk_tokens: String = row.get(6)?;
+
+            Ok(ContextDelivery {
+                id: Some(row.get(0)?),
+                project_path: row.get(1)?,
+                session_id: row.get(2)?,
+                snapshot_id: row.get(3)?,
+                task_text: row.get(4)?,
+                chunk_ids: serde_json::from_str(&chunk_ids).unwrap_or_default(),
+                chunk_tokens: serde_json::from_str(&chunk_tokens).unwrap_or_default(),
+                total_tokens: row.get::<_, i64>(7)? as usize,
+                token_budget: row.get::<_, i64>(8)? as usize,
+                created_at: row_timestamp(row, 9)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(deliveries)
+}
+
+/// Registra una sesión de agente
+pub fn insert_agent_session(conn: &Connection, session: &AgentSession) -> Result<()> {
+    conn.execute(
+        "INSERT INTO agent_sessions (id, project_path, master_snapshot_id, task, status, started_at, ended_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            &session.id,
+            &session.project_path,
+            session.master_snapshot_id,
+            &session.task,
+            session.status.as_str(),
+            session.started_at.to_rfc3339(),
+            session.ended_at.map(|t| t.to_rfc3339()),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Cierra una sesión de agente con su estado final
+pub fn end_agent_session(conn: &Connection, session_id: &str, status: &AgentSessionStatus) -> Result<bool> {
+    let updated = conn.execute(
+        "UPDATE agent_sessions SET status = ?2, ended_at = ?3 WHERE id = ?1",
+        params![session_id, status.as_str(), Utc::now().to_rfc3339()],
+    )?;
+    Ok(updated > 0)
+}
+
+/// Obtiene una sesión de agente por id
+pub fn get_agent_session(conn: &Connection, session_id: &str) -> Result<Option<AgentSession>> {
+    let session = conn
+        .query_row(
+            "SELECT id, project_path, master_snapshot_id, task, status, started_at, ended_at
+             FROM agent_sessions WHERE id = ?1",
+            params![session_id],
+            parse_agent_session_row,
+        )
+        .optional()?;
+    Ok(session)
+}
+
+/// Obtiene las sesiones de agente de un proyecto (más recientes primero)
+pub fn get_agent_sessions(conn: &Connection, project_path: &str) -> Result<Vec<AgentSession>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, master_snapshot_id, task, status, started_at, ended_at
+         FROM agent_sessions WHERE project_path = ?1 ORDER BY started_at DESC",
+    )?;
+    let sessions = stmt
+        .query_map(params![project_path], parse_agent_session_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(sessions)
+}
+
+fn parse_agent_session_row(row: &rusqlite::Row) -> SqliteResult<AgentSession> {
+    let status_str: String = row.get(4)?;
+
+    Ok(AgentSession {
+        id: row.get(0)?,
+        project_path: row.get(1)?,
+        master_snapshot_id: row.get(2)?,
+        task: row.get(3)?,
+        status: AgentSessionStatus::parse(&status_str).unwrap_or(AgentSessionStatus::Active),
+        started_at: row_timestamp(row, 5)?,
+        ended_at: row_optional_timestamp(row, 6)?,
+    })
+}
+
+/// Crea o reemplaza las raíces de un proyecto lógico
+pub fn upsert_logical_project(conn: &Connection, name: &str, roots: &[ProjectRoot]) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO logical_projects (name, roots, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)
+         ON CONFLICT(name) DO UPDATE SET roots = excluded.roots, updated_at = excluded.updated_at",
+        params![name, serde_json::to_string(roots)?, now],
+    )?;
+    Ok(())
+}
+
+/// Obtiene un proyecto lógico por nombre
+pub fn get_logical_project(conn: &Connection, name: &str) -> Result<Option<LogicalProject>> {
+    let project = conn
+        .query_row(
+            "SELECT name, roots, created_at, updated_at FROM logical_projects WHERE name = ?1",
+            params![name],
+            parse_logical_project_row,
+        )
+        .optional()?;
+    Ok(project)
+}
+
+/// Lista los proyectos lógicos registrados
+pub fn get_logical_projects(conn: &Connection) -> Result<Vec<LogicalProject>> {
+    let mut stmt = conn.prepare(
+        "SELECT name, roots, created_at, updated_at FROM logical_projects ORDER BY name",
+    )?;
+    let projects = stmt
+        .query_map([], parse_logical_project_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(projects)
+}
+
+/// Elimina un proyecto lógico (los chunks de sus raíces se conservan)
+pub fn delete_logical_project(conn: &Connection, name: &str) -> Result<bool> {
+    let deleted = conn.execute("DELETE FROM logical_projects WHERE name = ?1", params![name])?;
+    Ok(deleted > 0)
+}
+
+fn parse_logical_project_row(row: &rusqlite::Row) -> SqliteResult<LogicalProject> {
+    let name: String = row.get(0)?;
+    let roots: String = row.get(1)?;
+
+    Ok(LogicalProject {
+        project_path: format!("{}{}", LOGICAL_PROJECT_SCHEME, name),
+        name,
+        roots: serde_json::from_str(&roots).unwrap_or_default(),
+        created_at: row_timestamp(row, 2)?,
+        updated_at: row_timestamp(row, 3)?,
+    })
+}
+
+/// Registra (o actualiza) un repositorio remoto clonado en la caché
+pub fn upsert_remote_repository(conn: &Connection, repository: &RemoteRepository) -> Result<()> {
+    conn.execute(
+        "INSERT INTO remote_repositories (project_path, url, depth, head_commit, cloned_at, last_indexed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(project_path) DO UPDATE SET url = excluded.url, depth = excluded.depth,
+             head_commit = excluded.head_commit, cloned_at = excluded.cloned_at,
+             last_indexed_at = excluded.last_indexed_at",
+        params![
+            &repository.project_path,
+            &repository.url,
+            repository.depth,
+            &repository.head_commit,
+            repository.cloned_at.to_rfc3339(),
+            repository.last_indexed_at.map(|t| t.to_rfc3339()),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Obtiene el repositorio remoto clonado en `project_path`, si lo es
+pub fn get_remote_repository(conn: &Connection, project_path: &str) -> Result<Option<RemoteRepository>> {
+    let repository = conn
+        .query_row(
+            "SELECT project_path, url, depth, head_commit, cloned_at, last_indexed_at
+             FROM remote_repositories WHERE project_path = ?1",
+            params![project_path],
+            parse_remote_repository_row,
+        )
+        .optional()?;
+    Ok(repository)
+}
+
+/// Lista los repositorios remotos indexados
+pub fn get_remote_repositories(conn: &Connection) -> Result<Vec<RemoteRepository>> {
+    let mut stmt = conn.prepare(
+        "SELECT project_path, url, depth, head_commit, cloned_at, last_indexed_at
+         FROM remote_repositories ORDER BY url",
+    )?;
+    let repositories = stmt
+        .query_map([], parse_remote_repository_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(repositories)
+}
+
+fn parse_remote_repository_row(row: &rusqlite::Row) -> SqliteResult<RemoteRepository> {
+    Ok(RemoteRepository {
+        project_path: row.get(0)?,
+        url: row.get(1)?,
+        depth: row.get(2)?,
+        head_commit: row.get(3)?,
+        cloned_at: row_timestamp(row, 4)?,
+        last_indexed_at: row_optional_timestamp(row, 5)?,
+    })
+}
+
+/// Marca (o desmarca) el índice del proyecto como de solo lectura
+pub fn set_read_only_project(
+    conn: &Connection,
+    project_path: &str,
+    read_only: bool,
+    reason: Option<&str>,
+) -> Result<()> {
+    if read_only {
+        conn.execute(
+            "INSERT INTO read_only_projects (project_path, reason, created_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(project_path) DO UPDATE SET reason = excluded.reason",
+            params![project_path, reason, Utc::now().to_rfc3339()],
+        )?;
+    } else {
+        conn.execute(
+            "DELETE FROM read_only_projects WHERE project_path = ?1",
+            params![project_path],
+        )?;
+    }
+    Ok(())
+}
+
+/// Obtiene la marca de solo lectura del proyecto, si la tiene
+pub fn get_read_only_project(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Option<ReadOnlyProject>> {
+    let project = conn
+        .query_row(
+            "SELECT project_path, reason, created_at FROM read_only_projects WHERE project_path = ?1",
+            params![project_path],
+            parse_read_only_project_row,
+        )
+        .optional()?;
+    Ok(project)
+}
+
+/// Lista los proyectos con índice de solo lectura
+pub fn get_read_only_projects(conn: &Connection) -> Result<Vec<ReadOnlyProject>> {
+    let mut stmt = conn.prepare(
+        "SELECT project_path, reason, created_at FROM read_only_projects ORDER BY project_path",
+    )?;
+    let projects = stmt
+        .query_map([], parse_read_only_project_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(projects)
+}
+
+fn parse_read_only_project_row(row: &rusqlite::Row) -> SqliteResult<ReadOnlyProject> {
+    Ok(ReadOnlyProject {
+        project_path: row.get(0)?,
+        reason: row.get(1)?,
+        created_at: row_timestamp(row, 2)?,
+    })
+}
+
+/// Guarda una ejecución del benchmark y retorna su id
+pub fn insert_benchmark_run(conn: &Connection, run: &BenchmarkRun) -> Result<i64> {
+    let indexing = run
+        .indexing
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()?;
+    conn.execute(
+        "INSERT INTO benchmark_runs (project_path, crate_version, iterations, total_chunks,
+             indexing, queries, regressions, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            &run.project_path,
+            &run.crate_version,
+            run.iterations as i64,
+            run.total_chunks as i64,
+            indexing,
+            serde_json::to_string(&run.queries)?,
+            serde_json::to_string(&run.regressions)?,
+            run.created_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Ejecuciones del benchmark del proyecto, la más reciente primero
+pub fn get_benchmark_runs(
+    conn: &Connection,
+    project_path: &str,
+    limit: usize,
+) -> Result<Vec<BenchmarkRun>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, crate_version, iterations, total_chunks, indexing, queries,
+                regressions, created_at
+         FROM benchmark_runs WHERE project_path = ?1
+         ORDER BY created_at DESC, id DESC LIMIT ?2",
+    )?;
+    let runs = stmt
+        .query_map(params![project_path, limit as i64], parse_benchmark_run_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(runs)
+}
+
+fn parse_benchmark_run_row(row: &rusqlite::Row) -> SqliteResult<BenchmarkRun> {
+    let indexing: Option<String> = row.get(5)?;
+    let queries: String = row.get(6)?;
+    let regressions: String = row.get(7)?;
+    Ok(BenchmarkRun {
+        id: Some(row.get(0)?),
+        project_path: row.get(1)?,
+        crate_version: row.get(2)?,
+        iterations: row.get::<_, i64>(3)? as usize,
+        total_chunks: row.get::<_, i64>(4)? as usize,
+        indexing: indexing.and_then(|json| serde_json::from_str(&json).ok()),
+        queries: serde_json::from_str(&queries).unwrap_or_default(),
+        regressions: serde_json::from_str(&regressions).unwrap_or_default(),
+        created_at: row_timestamp(row, 8)?,
+    })
+}
+
+/// Inserta o actualiza un error log
+pub fn upsert_error_log(conn: &Connection, error: &ErrorLog) -> Result<i64> {
+    let now = Utc::now().to_rfc3339();
+
+    // Intentar encontrar error similar existente
+    let existing_id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM error_logs WHERE project_path = ?1 AND error_type = ?2 AND message = ?3 AND is_resolved = 0",
+            params![&error.project_path, &error.error_type, &error.message],
+            |row| row.get(0),
+        )
+        .ok();
+
+    if let Some(id) = existing_id {
+        // Actualizar contador y last_seen
+        conn.execute(
+            "UPDATE error_logs SET occurrence_count = occurrence_count + 1, last_seen = ?1 WHERE id = ?2",
+            params![&now, id],
+        )?;
+        Ok(id)
+    } else {
+        // Insertar nuevo error
+        conn.execute(
+            "INSERT INTO error_logs (project_path, snapshot_id, file_path, entity_name, error_type, message, stacktrace, occurrence_count, first_seen, last_seen, is_resolved, severity)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                &error.project_path,
+                error.snapshot_id,
+                error.file_path.as_deref().map(normalize_file_path),
+                &error.entity_name,
+                &error.error_type,
+                &error.message,
+                &error.stacktrace,
+                1,
+                &now,
+                &now,
+                false,
+                &error.severity,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+}
+
+/// Obtiene error logs de un proyecto
+pub fn get_error_logs(conn: &Connection, project_path: &str, include_resolved: bool) -> Result<Vec<ErrorLog>> {
+    let sql = if include_resolved {
+        "SELECT id, project_path, snapshot_id, file_path, entity_name, error_type, message, stacktrace, occurrence_count, first_seen, last_seen, is_resolved, severity
+         FROM error_logs WHERE project_path = ?1 ORDER BY last_seen DESC"
+    } else {
+        "SELECT id, project_path, snapshot_id, file_path, entity_name, error_type, message, stacktrace, occurrence_count, first_seen, last_seen, is_resolved, severity
+         FROM error_logs WHERE project_path = ?1 AND is_resolved = 0 ORDER BY last_seen DESC"
+    };
+
+    let mut stmt = conn.prepare(sql)?;
+    let errors = stmt
+        .query_map(params![project_path], |row| {
+            Ok(ErrorLog {
+                id: Some(row.get(0)?),
+                project_path: row.get(1)?,
+                snapshot_id: row.get(2)?,
+                file_path: row.get(3)?,
+                entity_name: row.get(4)?,
+                error_type: row.get(5)?,
+                message: row.get(6)?,
+                stacktrace: row.get(7)?,
+                severity: row.get(12)?,
+                occurrence_count: row.get(8)?,
+                first_seen: row_timestamp(row, 9)?,
+                last_seen: row_timestamp(row, 10)?,
+                is_resolved: row.get(11)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(errors)
+}
+
+/// Manda a la papelera todos los chunks de un proyecto. Se puede deshacer con
+/// [`undo_index_operation`] hasta que se purguen
+pub fn delete_project_chunks(conn: &Connection, project_path: &str) -> Result<usize> {
+    let operation_id =
+        create_index_operation(conn, project_path, IndexOperationKind::DeleteProjectChunks)?;
+    let count = soft_delete_project_chunks(conn, project_path, operation_id)?;
+    set_index_operation_count(conn, operation_id, count)?;
+    Ok(count)
+}
+
+/// Manda a la papelera (soft delete) los chunks vigentes de un archivo de los tipos
+/// dados, opcionalmente como parte de una operación deshacible
+pub fn soft_delete_file_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    chunk_types: &[ChunkType],
+    operation_id: Option<i64>,
+) -> Result<usize> {
+    let types: Vec<&str> = chunk_types.iter().map(|t| t.as_str()).collect();
+    let count = conn.execute(
+        "UPDATE chunks SET deleted_at = ?1, deleted_by_operation = ?2
+         WHERE project_path = ?3 AND file_path = ?4 AND deleted_at IS NULL
+           AND chunk_type IN (SELECT value FROM json_each(?5))",
+        params![
+            Utc::now().to_rfc3339(),
+            operation_id,
+            project_path,
+            normalize_file_path(file_path),
+            serde_json::to_string(&types)?,
+        ],
+    )?;
+    Ok(count)
+}
+
+/// Manda a la papelera todos los chunks vigentes de un proyecto dentro de una operación
+pub fn soft_delete_project_chunks(
+    conn: &Connection,
+    project_path: &str,
+    operation_id: i64,
+) -> Result<usize> {
+    let count = conn.execute(
+        "UPDATE chunks SET deleted_at = ?1, deleted_by_operation = ?2
+         WHERE project_path = ?3 AND deleted_at IS NULL",
+        params![Utc::now().to_rfc3339(), operation_id, project_path],
+    )?;
+    Ok(count)
+}
+
+/// Registra una operación destructiva; `chunks_affected` se completa al terminarla
+pub fn create_index_operation(
+    conn: &Connection,
+    project_path: &str,
+    kind: IndexOperationKind,
+) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO index_operations (project_path, kind, created_at) VALUES (?1, ?2, ?3)",
+        params![project_path, kind.as_str(), Utc::now().to_rfc3339()],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn set_index_operation_count(conn: &Connection, operation_id: i64, count: usize) -> Result<()> {
+    conn.execute(
+        "UPDATE index_operations SET chunks_affected = ?1 WHERE id = ?2",
+        params![count as i64, operation_id],
+    )?;
+    Ok(())
+}
+
+/// Última operación del proyecto que todavía no se deshizo
+pub fn get_last_index_operation(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Option<IndexOperation>> {
+    let operation = conn
+        .query_row(
+            "SELECT id, project_path, kind, chunks_affected, created_at, undone_at
+             FROM index_operations WHERE project_path = ?1 AND undone_at IS NULL
+             ORDER BY id DESC LIMIT 1",
+            params![project_path],
+            parse_index_operation_row,
+        )
+        .optional()?;
+    Ok(operation)
+}
+
+/// Historial de operaciones del proyecto, la más reciente primero
+pub fn get_index_operations(conn: &Connection, project_path: &str) -> Result<Vec<IndexOperation>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, kind, chunks_affected, created_at, undone_at
+         FROM index_operations WHERE project_path = ?1 ORDER BY id DESC",
+    )?;
+    let operations = stmt
+        .query_map(params![project_path], parse_index_operation_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(operations)
+}
+
+/// Restaura los chunks que siguen en la papelera por una operación y la marca como
+/// deshecha. Retorna los chunks restaurados
+pub fn undo_index_operation(conn: &Connection, operation_id: i64) -> Result<usize> {
+    let count = conn.execute(
+        "UPDATE chunks SET deleted_at = NULL, deleted_by_operation = NULL
+         WHERE deleted_by_operation = ?1 AND deleted_at IS NOT NULL",
+        params![operation_id],
+    )?;
+    conn.execute(
+        "UPDATE index_operations SET undone_at = ?1 WHERE id = ?2",
+        params![Utc::now().to_rfc3339(), operation_id],
+    )?;
+    Ok(count)
+}
+
+/// Borra lo indexado en el proyecto desde `since`: los chunks vigentes creados desde
+/// entonces (con sus relaciones y embeddings) y las relaciones nuevas entre chunks
+/// anteriores. Los chunks que la reindexación sacó de la papelera se conservan
+pub fn delete_chunks_indexed_since(
+    conn: &Connection,
+    project_path: &str,
+    since: DateTime<Utc>,
+) -> Result<usize> {
+    let since = since.to_rfc3339();
+    let indexed = "SELECT id FROM chunks
+         WHERE project_path = ?1 AND deleted_at IS NULL AND created_at >= ?2";
+
+    conn.execute(
+        &format!(
+            "DELETE FROM chunk_relationships
+             WHERE from_chunk_id IN ({0}) OR to_chunk_id IN ({0})
+                OR (created_at >= ?2
+                    AND from_chunk_id IN (SELECT id FROM chunks WHERE project_path = ?1))",
+            indexed
+        ),
+        params![project_path, &since],
+    )?;
+    conn.execute(
+        &format!(
+            "DELETE FROM chunk_embeddings WHERE chunk_id IN ({})",
+            indexed
+        ),
+        params![project_path, &since],
+    )?;
+    let count = conn.execute(
+        "DELETE FROM chunks
+         WHERE project_path = ?1 AND deleted_at IS NULL AND created_at >= ?2",
+        params![project_path, &since],
+    )?;
+    if count > 0 {
+        delete_orphan_blobs(conn)?;
+    }
+    Ok(count)
+}
+
+fn parse_index_operation_row(row: &rusqlite::Row) -> SqliteResult<IndexOperation> {
+    let kind: String = row.get(2)?;
+    Ok(IndexOperation {
+        id: row.get(0)?,
+        project_path: row.get(1)?,
+        kind: IndexOperationKind::from_str(&kind).ok_or(rusqlite::Error::InvalidQuery)?,
+        chunks_affected: row.get::<_, i64>(3)? as usize,
+        created_at: row_timestamp(row, 4)?,
+        undone_at: row_optional_timestamp(row, 5)?,
+    })
+}
+
+/// Archivos con chunks vigentes de los tipos dados
+pub fn get_indexed_file_paths(
+    conn: &Connection,
+    project_path: &str,
+    chunk_types: &[ChunkType],
+) -> Result<Vec<String>> {
+    let types: Vec<&str> = chunk_types.iter().map(|t| t.as_str()).collect();
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT file_path FROM chunks
+         WHERE project_path = ?1 AND file_path IS NOT NULL AND deleted_at IS NULL
+           AND chunk_type IN (SELECT value FROM json_each(?2))
+         ORDER BY file_path",
+    )?;
+    let paths = stmt
+        .query_map(params![project_path, serde_json::to_string(&types)?], |row| {
+            row.get(0)
+        })?
+        .collect::<SqliteResult<Vec<String>>>()?;
+    Ok(paths)
+}
+
+/// Restaura los chunks en la papelera de un proyecto (o de un solo archivo)
+pub fn restore_deleted_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_path: Option<&str>,
+) -> Result<usize> {
+    let count = conn.execute(
+        "UPDATE chunks SET deleted_at = NULL, deleted_by_operation = NULL
+         WHERE project_path = ?1 AND deleted_at IS NOT NULL AND (?2 IS NULL OR file_path = ?2)",
+        params![project_path, file_path.map(normalize_file_path)],
+    )?;
+    Ok(count)
+}
+
+/// Elimina definitivamente los chunks borrados antes de `deleted_before` (todos los
+/// proyectos con `project_path = None`) junto con sus relaciones
+pub fn purge_deleted_chunks(
+    conn: &Connection,
+    project_path: Option<&str>,
+    deleted_before: DateTime<Utc>,
+) -> Result<usize> {
+    let cutoff = deleted_before.to_rfc3339();
+    // Los índices de solo lectura conservan su papelera
+    let expired = "SELECT id FROM chunks
+         WHERE deleted_at IS NOT NULL AND deleted_at < ?1 AND (?2 IS NULL OR project_path = ?2)
+           AND project_path NOT IN (SELECT project_path FROM read_only_projects)";
+
+    conn.execute(
+        &format!(
+            "DELETE FROM chunk_relationships WHERE from_chunk_id IN ({0}) OR to_chunk_id IN ({0})",
+            expired
+        ),
+        params![&cutoff, project_path],
+    )?;
+    let count = conn.execute(
+        "DELETE FROM chunks
+         WHERE deleted_at IS NOT NULL AND deleted_at < ?1 AND (?2 IS NULL OR project_path = ?2)
+           AND project_path NOT IN (SELECT project_path FROM read_only_projects)",
+        params![&cutoff, project_path],
+    )?;
+    if count > 0 {
+        delete_orphan_blobs(conn)?;
+    }
+    Ok(count)
+}
+
+/// Inserta un hallazgo de seguridad
+pub fn insert_security_finding(conn: &Connection, finding: &SecurityFinding) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO security_findings (project_path, metadata_chunk_id, manifest_path, ecosystem, package_name, installed_version, advisory_id, aliases, severity, summary, fixed_version, affected_files, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            &finding.project_path,
+            finding.metadata_chunk_id,
+            normalize_file_path(&finding.manifest_path),
+            &finding.ecosystem,
+            &finding.package_name,
+            &finding.installed_version,
+            &finding.advisory_id,
+            serde_json::to_string(&finding.aliases)?,
+            &finding.severity,
+            &finding.summary,
+            &finding.fixed_version,
+            serde_json::to_string(&finding.affected_files)?,
+            finding.created_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Elimina los hallazgos de seguridad de un proyecto (antes de un nuevo escaneo)
+pub fn delete_security_findings(conn: &Connection, project_path: &str) -> Result<usize> {
+    let count = conn.execute(
+        "DELETE FROM security_findings WHERE project_path = ?1",
+        params![project_path],
+    )?;
+    Ok(count)
+}
+
+/// Obtiene los hallazgos de seguridad de un proyecto, opcionalmente filtrados por paquete
+pub fn get_security_findings(
+    conn: &Connection,
+    project_path: &str,
+    package_name: Option<&str>,
+) -> Result<Vec<SecurityFinding>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, metadata_chunk_id, manifest_path, ecosystem, package_name, installed_version, advisory_id, aliases, severity, summary, fixed_version, affected_files, created_at
+         FROM security_findings
+         WHERE project_path = ?1 AND (?2 IS NULL OR package_name = ?2)
+         ORDER BY package_name, advisory_id",
+    )?;
+
+    let findings = stmt
+        .query_map(params![project_path, package_name], |row| {
+            let aliases_str: String = row.get(8)?;
+            let files_str: String = row.get(12)?;
+
+            Ok(SecurityFinding {
+                id: Some(row.get(0)?),
+                project_path: row.get(1)?,
+                metadata_chunk_id: row.get(2)?,
+                manifest_path: row.get(3)?,
+                ecosystem: row.get(4)?,
+                package_name: row.get(5)?,
+                installed_version: row.get(6)?,
+                advisory_id: row.get(7)?,
+                aliases: serde_json::from_str(&aliases_str).unwrap_or_default(),
+                severity: row.get(9)?,
+                summary: row.get(10)?,
+                fixed_version: row.get(11)?,
+                affected_files: serde_json::from_str(&files_str).unwrap_or_default(),
+                created_at: row_timestamp(row, 13)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(findings)
+}
+
+/// Inserta un secreto detectado
+pub fn insert_secret_finding(conn: &Connection, finding: &SecretFinding) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO secret_findings (project_path, chunk_id, file_path, line_number, rule_id, severity, masked_preview, fingerprint, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            &finding.project_path,
+            finding.chunk_id,
+            normalize_file_path(&finding.file_path),
+            finding.line_number as i64,
+            &finding.rule_id,
+            &finding.severity,
+            &finding.masked_preview,
+            &finding.fingerprint,
+            finding.created_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Elimina los secretos detectados de un proyecto (o de un solo archivo)
+pub fn delete_secret_findings(
+    conn: &Connection,
+    project_path: &str,
+    file_path: Option<&str>,
+) -> Result<usize> {
+    let count = conn.execute(
+        "DELETE FROM secret_findings WHERE project_path = ?1 AND (?2 IS NULL OR file_path = ?2)",
+        params![project_path, file_path.map(normalize_file_path)],
+    )?;
+    Ok(count)
+}
+
+/// Obtiene los secretos detectados, con filtros opcionales por severidad y archivo
+pub fn get_secret_findings(
+    conn: &Connection,
+    project_path: &str,
+    severity: Option<&str>,
+    file_path: Option<&str>,
+) -> Result<Vec<SecretFinding>> {
+    let file_path = file_path.map(normalize_file_path);
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, chunk_id, file_path, line_number, rule_id, severity, masked_preview, fingerprint, created_at
+         FROM secret_findings
+         WHERE project_path = ?1 AND (?2 IS NULL OR severity = ?2) AND (?3 IS NULL OR file_path = ?3)
+         ORDER BY file_path, line_number",
+    )?;
+
+    let findings = stmt
+        .query_map(params![project_path, severity, file_path], |row| {
+            let line_number: i64 = row.get(4)?;
+
+            Ok(SecretFinding {
+                id: Some(row.get(0)?),
+                project_path: row.get(1)?,
+                chunk_id: row.get(2)?,
+                file_path: row.get(3)?,
+                line_number: line_number as usize,
+                rule_id: row.get(5)?,
+                severity: row.get(6)?,
+                masked_preview: row.get(7)?,
+                fingerprint: row.get(8)?,
+                created_at: row_timestamp(row, 9)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(findings)
+}
+
+/// Inserta el resultado de un caso de test
+pub fn insert_test_result(conn: &Connection, result: &TestResult) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO test_results (project_path, run_id, suite_name, class_name, test_name, file_path, status, duration_ms, failure_message, failure_details, test_chunk_id, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            &result.project_path,
+            &result.run_id,
+            &result.suite_name,
+            &result.class_name,
+            &result.test_name,
+            result.file_path.as_deref().map(normalize_file_path),
+            result.status.as_str(),
+            result.duration_ms,
+            &result.failure_message,
+            &result.failure_details,
+            result.test_chunk_id,
+            result.created_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Obtiene los resultados de una ejecución de tests
+pub fn get_test_results(conn: &Connection, project_path: &str, run_id: &str) -> Result<Vec<TestResult>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, run_id, suite_name, class_name, test_name, file_path, status, duration_ms, failure_message, failure_details, test_chunk_id, created_at
+         FROM test_results WHERE project_path = ?1 AND run_id = ?2 ORDER BY id",
+    )?;
+
+    let results = stmt
+        .query_map(params![project_path, run_id], |row| {
+            let status_str: String = row.get(7)?;
+
+            Ok(TestResult {
+                id: Some(row.get(0)?),
+                project_path: row.get(1)?,
+                run_id: row.get(2)?,
+                suite_name: row.get(3)?,
+                class_name: row.get(4)?,
+                test_name: row.get(5)?,
+                file_path: row.get(6)?,
+                status: match status_str.as_str() {
+                    "passed" => TestStatus::Passed,
+                    "failed" => TestStatus::Failed,
+                    "skipped" => TestStatus::Skipped,
+                    _ => TestStatus::Error,
+                },
+                duration_ms: row.get(8)?,
+                failure_message: row.get(9)?,
+                failure_details: row.get(10)?,
+                test_chunk_id: row.get(11)?,
+                code_under_test: Vec::new(),
+                created_at: row_timestamp(row, 12)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(results)
+}
+
+/// Registra una falla de parseo. Si el contenido no cambió suma un intento;
+/// si cambió, reinicia la cuenta
+pub fn record_parse_failure(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content_hash: &str,
+    reason: &str,
+) -> Result<ParseFailure> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO parse_failures (project_path, file_path, content_hash, reason, attempts, first_failed_at, last_failed_at)
+         VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5)
+         ON CONFLICT(project_path, file_path) DO UPDATE SET
+             attempts = CASE WHEN content_hash = excluded.content_hash THEN attempts + 1 ELSE 1 END,
+             first_failed_at = CASE WHEN content_hash = excluded.content_hash THEN first_failed_at ELSE excluded.first_failed_at END,
+             content_hash = excluded.content_hash,
+             reason = excluded.reason,
+             last_failed_at = excluded.last_failed_at",
+        params![project_path, normalize_file_path(file_path), content_hash, reason, &now],
+    )?;
+    get_parse_failure(conn, project_path, file_path)?.context("Parse failure not recorded")
+}
+
+pub fn get_parse_failure(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+) -> Result<Option<ParseFailure>> {
+    let failure = conn
+        .query_row(
+            "SELECT project_path, file_path, content_hash, reason, attempts, first_failed_at, last_failed_at
+             FROM parse_failures WHERE project_path = ?1 AND file_path = ?2",
+            params![project_path, normalize_file_path(file_path)],
+            parse_parse_failure_row,
+        )
+        .optional()?;
+    Ok(failure)
+}
+
+/// Lista las fallas de parseo del proyecto, las más intentadas primero
+pub fn get_parse_failures(conn: &Connection, project_path: &str) -> Result<Vec<ParseFailure>> {
+    let mut stmt = conn.prepare(
+        "SELECT project_path, file_path, content_hash, reason, attempts, first_failed_at, last_failed_at
+         FROM parse_failures WHERE project_path = ?1 ORDER BY attempts DESC, file_path",
+    )?;
+    let failures = stmt
+        .query_map(params![project_path], parse_parse_failure_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(failures)
+}
+
+/// Elimina fallas de parseo; `file_path = None` limpia todas las del proyecto
+pub fn delete_parse_failures(
+    conn: &Connection,
+    project_path: &str,
+    file_path: Option<&str>,
+) -> Result<usize> {
+    let count = conn.execute(
+        "DELETE FROM parse_failures WHERE project_path = ?1 AND (?2 IS NULL OR file_path = ?2)",
+        params![project_path, file_path.map(normalize_file_path)],
+    )?;
+    Ok(count)
+}
+
+pub fn upsert_file_fingerprint(conn: &Connection, fingerprint: &FileFingerprint) -> Result<()> {
+    conn.execute(
+        "INSERT INTO file_fingerprints (project_path, file_path, content_hash, size, modified_at, indexed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(project_path, file_path) DO UPDATE SET
+             content_hash = excluded.content_hash,
+             size = excluded.size,
+             modified_at = excluded.modified_at,
+             indexed_at = excluded.indexed_at",
+        params![
+            fingerprint.project_path,
+            normalize_file_path(&fingerprint.file_path),
+            fingerprint.content_hash,
+            fingerprint.size as i64,
+            fingerprint.modified_at,
+            fingerprint.indexed_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Huellas de los archivos indexados del proyecto, ordenadas por ruta
+pub fn get_file_fingerprints(
+    conn: &Connection,
+    project_path: &str,
+) -> Result<Vec<FileFingerprint>> {
+    let mut stmt = conn.prepare(
+        "SELECT project_path, file_path, content_hash, size, modified_at, indexed_at
+         FROM file_fingerprints WHERE project_path = ?1 ORDER BY file_path",
+    )?;
+    let fingerprints = stmt
+        .query_map(params![project_path], |row| {
+            Ok(FileFingerprint {
+                project_path: row.get(0)?,
+                file_path: row.get(1)?,
+                content_hash: row.get(2)?,
+                size: row.get::<_, i64>(3)? as u64,
+                modified_at: row.get(4)?,
+                indexed_at: row_timestamp(row, 5)?,
+            })
+        })?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(fingerprints)
+}
+
+pub fn delete_file_fingerprint(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+) -> Result<usize> {
+    let count = conn.execute(
+        "DELETE FROM file_fingerprints WHERE project_path = ?1 AND file_path = ?2",
+        params![project_path, normalize_file_path(file_path)],
+    )?;
+    Ok(count)
+}
+
+/// Marca (o desmarca) como desactualizados los chunks vigentes de un archivo.
+/// Devuelve cuántos chunks cambiaron de estado
+pub fn set_file_chunks_stale(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    stale: bool,
+) -> Result<usize> {
+    let count = conn.execute(
+        "UPDATE chunks SET stale = ?3
+         WHERE project_path = ?1 AND file_path = ?2 AND deleted_at IS NULL AND stale != ?3",
+        params![project_path, normalize_file_path(file_path), stale],
+    )?;
+    Ok(count)
+}
+
+fn parse_parse_failure_row(row: &rusqlite::Row) -> SqliteResult<ParseFailure> {
+    Ok(ParseFailure {
+        project_path: row.get(0)?,
+        file_path: row.get(1)?,
+        content_hash: row.get(2)?,
+        reason: row.get(3)?,
+        attempts: row.get::<_, i64>(4)? as usize,
+        first_failed_at: row_timestamp(row, 5)?,
+        last_failed_at: row_timestamp(row, 6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::chunk;
+
+    fn insert_chunks(conn: &Connection, project_path: &str, count: usize) {
+        for i in 0..count {
+            chunk()
+                .project(project_path)
+                .file(&format!("src/file_{}.rs", i))
+                .content(&format!("{} chunk {}", project_path, i))
+                .insert(conn);
+        }
+    }
+
+    #[test]
+    fn test_cursor_pagination_visits_every_chunk_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_chunks(&conn, "/a", 5);
+        insert_chunks(&conn, "/b", 3);
+
+        let query = ChunkQuery {
+            project_path: Some("/a".to_string()),
+            ..Default::default()
+        };
+        let first = fetch_chunks_page(&conn, &query, None, 2).unwrap();
+        assert_eq!(first.chunks.len(), 2);
+        let second = fetch_chunks_page(&conn, &query, first.next_cursor, 2).unwrap();
+        assert!(second.chunks[0].id > first.chunks[1].id);
+
+        let all: Vec<Chunk> = ChunkIter::new(&conn, query, 2)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(all.iter().all(|c| c.project_path == "/a"));
+        assert!(all.windows(2).all(|w| w[0].id < w[1].id));
+
+        let last = fetch_chunks_page(&conn, &ChunkQuery::default(), all[4].id, 10).unwrap();
+        assert_eq!(last.chunks.len(), 3);
+        assert_eq!(last.next_cursor, None);
+    }
+
+    #[test]
+    fn test_invalid_timestamps_are_errors() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_chunks(&conn, "/a", 1);
+
+        let chunks = query_chunks(&conn, &ChunkQuery::default()).unwrap();
+        assert!(chunks[0].updated_at <= Utc::now());
+
+        conn.execute("UPDATE chunks SET updated_at = 'yesterday'", [])
+            .unwrap();
+        assert!(query_chunks(&conn, &ChunkQuery::default()).is_err());
+        assert!(parse_timestamp("2024-05-01T10:00:00+02:00", 0).is_ok());
+    }
+
+    #[test]
+    fn test_content_hash_becomes_unique_per_project() {
+        let conn = Connection::open_in_memory().unwrap();
+        // Esquema anterior: hash único en toda la base
+        conn.execute_batch(
+            "CREATE TABLE chunks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_path TEXT NOT NULL,
+                chunk_type TEXT NOT NULL,
+                file_path TEXT,
+                entity_name TEXT,
+                content TEXT NOT NULL,
+                content_hash TEXT NOT NULL UNIQUE,
+                metadata TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            INSERT INTO chunks (id, project_path, chunk_type, file_path, content, content_hash, created_at, updated_at)
+            VALUES (7, '/a', 'raw_source', 'src/lib.rs', 'pub fn a() {}', 'h', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00'),
+                   (9, '/a', 'raw_source', 'src/old.rs', 'purged', 'old', '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');
+            DELETE FROM chunks WHERE id = 9;",
+        )
+        .unwrap();
+
+        init_chunk_database(&conn).unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        let query = ChunkQuery {
+            project_path: Some("/a".to_string()),
+            ..Default::default()
+        };
+        let chunks = query_chunks(&conn, &query).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].id, Some(7));
+        assert_eq!(chunks[0].content, "pub fn a() {}");
+
+        // El mismo hash en otro proyecto es otra fila; en el mismo proyecto, la misma
+        let copy = Chunk {
+            id: None,
+            project_path: "/b".to_string(),
+            ..chunks[0].clone()
+        };
+        assert!(upsert_chunk(&conn, &copy, None).unwrap());
+        assert!(!upsert_chunk(&conn, &chunks[0], None).unwrap());
+        // Los ids de chunks purgados no se reutilizan
+        assert_eq!(get_chunk_id_by_hash(&conn, "/b", "h").unwrap(), Some(10));
+    }
+
+    #[test]
+    fn test_file_paths_use_forward_slashes() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        chunk()
+            .project("/a")
+            .file("src\\win\\main.rs")
+            .content("fn main() {}")
+            .insert(&conn);
+
+        // Se guarda con `/` y se encuentra con cualquiera de los dos separadores
+        for filter in ["src/win/main.rs", "src\\win\\main.rs"] {
+            let query = ChunkQuery {
+                file_path: Some(filter.to_string()),
+                ..Default::default()
+            };
+            let chunks = query_chunks(&conn, &query).unwrap();
+            assert_eq!(chunks.len(), 1);
+            assert_eq!(chunks[0].file_path.as_deref(), Some("src/win/main.rs"));
+        }
+
+        // Las filas existentes se migran al abrir la base
+        conn.execute("UPDATE chunks SET file_path = 'src\\old.rs'", [])
+            .unwrap();
+        init_chunk_database(&conn).unwrap();
+        let chunks = query_chunks(&conn, &ChunkQuery::default()).unwrap();
+        assert_eq!(chunks[0].file_path.as_deref(), Some("src/old.rs"));
+    }
+
+    #[test]
+    fn test_get_chunks_by_ids_keeps_requested_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_chunks(&conn, "/a", 3);
+        // query_chunks devuelve primero los más recientes: ids en orden de inserción
+        let mut ids: Vec<i64> = query_chunks(&conn, &ChunkQuery::default())
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.id)
+            .collect();
+        ids.sort();
+        conn.execute(
+            "UPDATE chunks SET deleted_at = ?2 WHERE id = ?1",
+            params![ids[1], Utc::now().to_rfc3339()],
+        )
+        .unwrap();
+
+        let chunks = get_chunks_by_ids(&conn, &[ids[2], -1, ids[1], ids[0], ids[2]]).unwrap();
+        let found: Vec<Option<i64>> = chunks.iter().map(|c| c.id).collect();
+        assert_eq!(found, vec![Some(ids[2]), Some(ids[0])]);
+        assert_eq!(chunks[1].content, "/a chunk 0");
+        assert!(get_chunks_by_ids(&conn, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_chunk_with_context_reads_both_directions() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_chunks(&conn, "/a", 3);
+        // query_chunks devuelve primero los más recientes: ids en orden de inserción
+        let mut ids: Vec<i64> = query_chunks(&conn, &ChunkQuery::default())
+            .unwrap()
+            .iter()
+            .filter_map(|c| c.id)
+            .collect();
+        ids.sort();
+        let link = |from: i64, to: i64, relationship_type: RelationshipType| {
+            insert_relationship(
+                &conn,
+                &ChunkRelationship {
+                    id: None,
+                    from_chunk_id: from,
+                    to_chunk_id: to,
+                    relationship_type,
+                    metadata: None,
+                    created_at: Utc::now(),
+                },
+            )
+            .unwrap();
+        };
+        link(ids[0], ids[1], RelationshipType::Calls);
+        link(ids[2], ids[0], RelationshipType::DependsOn);
+
+        let context = get_chunk_with_context(&conn, ids[0]).unwrap().unwrap();
+        assert_eq!(context.chunk.id, Some(ids[0]));
+        assert_eq!(context.outgoing.len(), 1);
+        assert_eq!(context.outgoing[0].chunk_id, ids[1]);
+        assert_eq!(
+            context.outgoing[0].relationship_type,
+            RelationshipType::Calls
+        );
+        assert_eq!(context.outgoing[0].summary, "/a chunk 1");
+        assert_eq!(context.incoming[0].chunk_id, ids[2]);
+        assert_eq!(
+            context.incoming[0].file_path.as_deref(),
+            Some("src/file_2.rs")
+        );
+
+        // Los chunks relacionados en la papelera no se incluyen
+        conn.execute(
+            "UPDATE chunks SET deleted_at = ?2 WHERE id = ?1",
+            params![ids[2], Utc::now().to_rfc3339()],
+        )
+        .unwrap();
+        let context = get_chunk_with_context(&conn, ids[0]).unwrap().unwrap();
+        assert!(context.incoming.is_empty());
+        assert!(get_chunk_with_context(&conn, -1).unwrap().is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use super::errors::{log_diagnostic, resolve_error};
+use super::storage::{get_error_logs, normalize_file_path};
+use super::types::{AstMetadata, Chunk, FileSyntaxErrors};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, HashSet};
+
+/// `error_type` de los errores de sintaxis en error_logs
+pub const SYNTAX_ERROR_TYPE: &str = "syntax";
+
+/// Registra en error_logs los errores de sintaxis de los chunks AST recién generados
+/// de un archivo ("archivo:línea:columna: mensaje", atribuidos a la entidad que los
+/// contiene) y resuelve los del archivo que ya no aparecen. Retorna los registrados
+pub fn record_syntax_errors(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    chunks: &[Chunk],
+) -> Result<usize> {
+    let parsed: Vec<(Option<&str>, AstMetadata)> = chunks
+        .iter()
+        .filter_map(|chunk| {
+            let metadata = serde_json::from_str(chunk.metadata.as_deref()?).ok()?;
+            Some((chunk.entity_name.as_deref(), metadata))
+        })
+        .collect();
+    let syntax_errors = parsed
+        .iter()
+        .find(|(entity, _)| entity.is_none())
+        .map(|(_, metadata)| metadata.syntax_errors.as_slice())
+        .unwrap_or_default();
+
+    let file_path = normalize_file_path(file_path);
+    let mut current = HashSet::new();
+    for error in syntax_errors {
+        let entity = parsed.iter().find_map(|(entity, metadata)| {
+            let lines = metadata.start_line?..=metadata.end_line?;
+            if lines.contains(&error.line) {
+                *entity
+            } else {
+                None
+            }
+        });
+        let message = format!(
+            "{}:{}:{}: {}",
+            file_path, error.line, error.column, error.message
+        );
+        log_diagnostic(
+            conn,
+            project_path,
+            SYNTAX_ERROR_TYPE,
+            "error",
+            &message,
+            Some(&file_path),
+            entity,
+        )?;
+        current.insert(message);
+    }
+
+    for (id, message) in open_syntax_errors(conn, project_path, &file_path)? {
+        if !current.contains(&message) {
+            resolve_error(conn, id)?;
+        }
+    }
+    Ok(current.len())
+}
+
+/// Resuelve los errores de sintaxis abiertos de un archivo (borrado del proyecto)
+pub fn resolve_file_syntax_errors(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+) -> Result<usize> {
+    let open = open_syntax_errors(conn, project_path, &normalize_file_path(file_path))?;
+    for (id, _) in &open {
+        resolve_error(conn, *id)?;
+    }
+    Ok(open.len())
+}
+
+fn open_syntax_errors(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, message FROM error_logs
+         WHERE project_path = ?1 AND file_path = ?2 AND error_type = ?3 AND is_resolved = 0",
+    )?;
+    let errors = stmt
+        .query_map(params![project_path, file_path, SYNTAX_ERROR_TYPE], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(errors)
+}
+
+/// Archivos que el indexador no pudo parsear por completo, con sus errores de
+/// sintaxis abiertos ordenados por posición
+pub fn get_syntax_errors(conn: &Connection, project_path: &str) -> Result<Vec<FileSyntaxErrors>> {
+    let mut files: BTreeMap<String, Vec<_>> = BTreeMap::new();
+    for error in get_error_logs(conn, project_path, false)? {
+        if error.error_type != SYNTAX_ERROR_TYPE {
+            continue;
+        }
+        let Some(file_path) = error.file_path.clone() else {
+            continue;
+        };
+        files.entry(file_path).or_default().push(error);
+    }
+
+    Ok(files
+        .into_iter()
+        .map(|(file_path, mut errors)| {
+            errors.sort_by_key(|error| position(&file_path, &error.message));
+            FileSyntaxErrors { file_path, errors }
+        })
+        .collect())
+}
+
+/// Línea y columna de un mensaje "archivo:línea:columna: ..."
+fn position(file_path: &str, message: &str) -> (usize, usize) {
+    let mut parts = message
+        .strip_prefix(file_path)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or_default()
+        .splitn(3, ':')
+        .map(|part| part.parse().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::generate_ast_chunks;
+    use crate::storage::init_chunk_database;
+    use crate::types::ChunkingOptions;
+
+    #[test]
+    fn test_syntax_errors_are_logged_and_resolved() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let options = ChunkingOptions::default();
+        let broken = "fn ok() {}\n\nfn broken( {\n    let x = 1\n}\n";
+
+        generate_ast_chunks(&conn, "/p", "src/lib.rs", broken, &options).unwrap();
+        generate_ast_chunks(&conn, "/p", "src/ok.rs", "fn ok() {}\n", &options).unwrap();
+        let files = get_syntax_errors(&conn, "/p").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_path, "src/lib.rs");
+        let first = &files[0].errors[0];
+        assert_eq!(first.error_type, SYNTAX_ERROR_TYPE);
+        assert!((3..=5).contains(&position("src/lib.rs", &first.message).0));
+        let count = files[0].errors.len();
+
+        // Reindexar sin cambios cuenta ocurrencias en lugar de duplicar
+        generate_ast_chunks(&conn, "/p", "src/lib.rs", broken, &options).unwrap();
+        let files = get_syntax_errors(&conn, "/p").unwrap();
+        assert_eq!(files[0].errors.len(), count);
+        assert!(files[0].errors.iter().all(|e| e.occurrence_count == 2));
+
+        // Corregido el archivo, sus errores quedan resueltos
+        let fixed = "fn ok() {}\n\nfn broken() {\n    let x = 1;\n}\n";
+        generate_ast_chunks(&conn, "/p", "src/lib.rs", fixed, &options).unwrap();
+        assert!(get_syntax_errors(&conn, "/p").unwrap().is_empty());
+    }
+}
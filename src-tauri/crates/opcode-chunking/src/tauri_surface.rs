@@ -0,0 +1,452 @@
+use super::dependency_graph::is_supported_source;
+use super::storage::{calculate_content_hash, get_latest_file_contents, upsert_chunk};
+use super::types::{
+    Chunk, ChunkType, TauriCallSite, TauriCommand, TauriCommandParam, TauriSurface,
+};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// entity_name del chunk de proyecto con la superficie de comandos Tauri
+pub const TAURI_SURFACE_ENTITY: &str = "<tauri_commands>";
+
+/// Tipos que Tauri inyecta en un comando; no los envía el frontend
+const INJECTED_TYPES: &[&str] = &[
+    "State",
+    "AppHandle",
+    "Window",
+    "Webview",
+    "WebviewWindow",
+    "Request",
+    "CommandScope",
+    "GlobalScope",
+];
+
+/// Genera (o reemplaza) el chunk de API-surface Tauri del proyecto a partir de los raw
+/// source vigentes: cada `#[tauri::command]` con sus parámetros, su registro en
+/// `generate_handler!` y los call sites del frontend. Sin comandos el chunk se elimina.
+/// Retorna el número de comandos encontrados
+pub fn generate_tauri_surface_chunk(conn: &Connection, project_path: &str) -> Result<usize> {
+    let sources: BTreeMap<String, String> =
+        get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?
+            .into_iter()
+            .map(|(file_path, (_, content))| (file_path, content))
+            .collect();
+    let surface = extract_tauri_surface(&sources);
+
+    conn.execute(
+        "DELETE FROM chunks WHERE project_path = ?1 AND chunk_type = ?2 AND entity_name = ?3",
+        params![
+            project_path,
+            ChunkType::Callgraph.as_str(),
+            TAURI_SURFACE_ENTITY
+        ],
+    )?;
+    if surface.commands.is_empty() {
+        return Ok(0);
+    }
+
+    let content = render_surface(&surface);
+    let chunk = Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::Callgraph,
+        file_path: None,
+        entity_name: Some(TAURI_SURFACE_ENTITY.to_string()),
+        content_hash: calculate_content_hash(&format!("{}\n{}", project_path, content)),
+        content,
+        metadata: Some(serde_json::to_string(&surface)?),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+    upsert_chunk(conn, &chunk, None)?;
+    Ok(surface.commands.len())
+}
+
+/// Superficie de comandos Tauri guardada en el último indexado, si el proyecto tiene
+pub fn get_tauri_surface(conn: &Connection, project_path: &str) -> Result<Option<TauriSurface>> {
+    let metadata: Option<String> = conn
+        .query_row(
+            "SELECT metadata FROM chunks
+             WHERE project_path = ?1 AND chunk_type = ?2 AND entity_name = ?3
+               AND deleted_at IS NULL
+             ORDER BY updated_at DESC LIMIT 1",
+            params![
+                project_path,
+                ChunkType::Callgraph.as_str(),
+                TAURI_SURFACE_ENTITY
+            ],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    Ok(match metadata {
+        Some(json) => Some(serde_json::from_str(&json)?),
+        None => None,
+    })
+}
+
+/// Extrae los comandos Tauri de los archivos Rust y sus llamadas desde JS/TS
+/// (archivo -> contenido)
+pub fn extract_tauri_surface(sources: &BTreeMap<String, String>) -> TauriSurface {
+    let command_re = Regex::new(
+        r#"#\[tauri::command(?:\(([^\]]*)\))?\]\s*(?:#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?(async\s+)?fn\s+(\w+)"#,
+    )
+    .unwrap();
+    let handler_re = Regex::new(r"generate_handler!\s*\[([^\]]*)\]").unwrap();
+
+    let mut commands = Vec::new();
+    let mut registered = BTreeSet::new();
+    for (file_path, content) in sources.iter().filter(|(f, _)| f.ends_with(".rs")) {
+        for cap in handler_re.captures_iter(content) {
+            for item in cap[1].split(',') {
+                let name = item.trim().rsplit("::").next().unwrap_or("").trim();
+                if !name.is_empty() {
+                    registered.insert(name.to_string());
+                }
+            }
+        }
+        for cap in command_re.captures_iter(content) {
+            let whole = cap.get(0).unwrap();
+            let camel_case = !cap
+                .get(1)
+                .is_some_and(|args| args.as_str().contains("snake_case"));
+            let (params, return_type) = parse_signature(&content[whole.end()..]);
+            commands.push(TauriCommand {
+                name: cap[3].to_string(),
+                file_path: file_path.clone(),
+                line: line_of(content, whole.start()),
+                is_async: cap.get(2).is_some(),
+                params: params
+                    .into_iter()
+                    .map(|(name, rust_type)| TauriCommandParam {
+                        js_name: if camel_case {
+                            snake_to_camel(&name)
+                        } else {
+                            name.clone()
+                        },
+                        injected: is_injected(&rust_type),
+                        name,
+                        rust_type,
+                    })
+                    .collect(),
+                return_type,
+                registered: false,
+                callers: Vec::new(),
+            });
+        }
+    }
+
+    let names: BTreeSet<String> = commands.iter().map(|c| c.name.clone()).collect();
+    let call_re = Regex::new(r#"\(\s*['"`]([A-Za-z_]\w*)['"`]"#).unwrap();
+    let invoke_re = Regex::new(r#"\binvoke\s*(?:<[^(]*?>)?\(\s*['"`]([A-Za-z_]\w*)['"`]"#).unwrap();
+    let mut callers: BTreeMap<String, Vec<TauriCallSite>> = BTreeMap::new();
+    let mut unknown_invokes = Vec::new();
+    for (file_path, content) in sources
+        .iter()
+        .filter(|(f, _)| !f.ends_with(".rs") && is_supported_source(f))
+    {
+        for cap in call_re.captures_iter(content) {
+            if names.contains(&cap[1]) {
+                callers
+                    .entry(cap[1].to_string())
+                    .or_default()
+                    .push(TauriCallSite {
+                        command: cap[1].to_string(),
+                        file_path: file_path.clone(),
+                        line: line_of(content, cap.get(0).unwrap().start()),
+                    });
+            }
+        }
+        for cap in invoke_re.captures_iter(content) {
+            if !names.contains(&cap[1]) {
+                unknown_invokes.push(TauriCallSite {
+                    command: cap[1].to_string(),
+                    file_path: file_path.clone(),
+                    line: line_of(content, cap.get(0).unwrap().start()),
+                });
+            }
+        }
+    }
+
+    for command in &mut commands {
+        command.registered = registered.contains(&command.name);
+        command.callers = callers.get(&command.name).cloned().unwrap_or_default();
+    }
+    commands.sort_by(|a, b| a.name.cmp(&b.name).then(a.file_path.cmp(&b.file_path)));
+
+    TauriSurface {
+        kind: "tauri_surface".to_string(),
+        commands,
+        unknown_invokes,
+    }
+}
+
+/// Parámetros (nombre, tipo) y tipo de retorno de una firma que empieza tras el
+/// nombre de la función (genéricos incluidos)
+fn parse_signature(rest: &str) -> (Vec<(String, String)>, Option<String>) {
+    let Some(open) = rest.find('(') else {
+        return (Vec::new(), None);
+    };
+    let mut depth = 0usize;
+    let mut close = None;
+    let mut params = Vec::new();
+    let mut start = open + 1;
+    for (i, c) in rest.char_indices().skip_while(|(i, _)| *i < open) {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' | '>' | ']' if depth > 0 => {
+                // `->` dentro de un tipo de función no cierra nada
+                if c == '>' && rest[..i].ends_with('-') {
+                    continue;
+                }
+                depth -= 1;
+                if depth == 0 {
+                    params.push(&rest[start..i]);
+                    close = Some(i);
+                    break;
+                }
+            }
+            ',' if depth == 1 => {
+                params.push(&rest[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let Some(close) = close else {
+        return (Vec::new(), None);
+    };
+
+    let params = params
+        .into_iter()
+        .filter_map(|param| {
+            let (name, rust_type) = param.split_once(':')?;
+            let name = name.trim().trim_start_matches("mut ").trim();
+            Some((name.to_string(), collapse_whitespace(rust_type)))
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect();
+
+    let after = rest[close + 1..].trim_start();
+    let return_type = after.strip_prefix("->").map(|ret| {
+        let end = [ret.find('{'), ret.find(" where ")]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(ret.len());
+        collapse_whitespace(&ret[..end])
+    });
+    (params, return_type)
+}
+
+fn is_injected(rust_type: &str) -> bool {
+    let base = rust_type
+        .split('<')
+        .next()
+        .unwrap_or(rust_type)
+        .trim()
+        .trim_start_matches('&')
+        .rsplit("::")
+        .next()
+        .unwrap_or("");
+    INJECTED_TYPES.contains(&base)
+}
+
+fn render_surface(surface: &TauriSurface) -> String {
+    let mut repr = format!("# Tauri Commands ({})\n", surface.commands.len());
+    for command in &surface.commands {
+        repr.push_str(&format!(
+            "\n{} ({}:{}{})",
+            command.name,
+            command.file_path,
+            command.line,
+            if command.is_async { ", async" } else { "" }
+        ));
+        if let Some(ret) = &command.return_type {
+            repr.push_str(&format!(" -> {}", ret));
+        }
+        repr.push('\n');
+        let args: Vec<String> = command
+            .params
+            .iter()
+            .filter(|p| !p.injected)
+            .map(|p| format!("{}: {}", p.js_name, p.rust_type))
+            .collect();
+        repr.push_str(&format!("  args: {}\n", args.join(", ")));
+        let injected: Vec<&str> = command
+            .params
+            .iter()
+            .filter(|p| p.injected)
+            .map(|p| p.rust_type.as_str())
+            .collect();
+        if !injected.is_empty() {
+            repr.push_str(&format!("  injected: {}\n", injected.join(", ")));
+        }
+        if !command.registered {
+            repr.push_str("  not registered in generate_handler!\n");
+        }
+        let callers: Vec<String> = command
+            .callers
+            .iter()
+            .map(|c| format!("{}:{}", c.file_path, c.line))
+            .collect();
+        repr.push_str(&format!(
+            "  callers ({}): {}\n",
+            callers.len(),
+            callers.join(", ")
+        ));
+    }
+    if !surface.unknown_invokes.is_empty() {
+        repr.push_str(&format!(
+            "\n# Unknown Invokes ({})\n",
+            surface.unknown_invokes.len()
+        ));
+        for call in &surface.unknown_invokes {
+            repr.push_str(&format!(
+                "{}:{} invoke(\"{}\")\n",
+                call.file_path, call.line, call.command
+            ));
+        }
+    }
+    repr
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn snake_to_camel(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' && !camel.is_empty() {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+
+    const COMMANDS: &str = r#"use tauri::State;
+
+#[tauri::command]
+pub async fn get_user(
+    state: State<'_, AppState>,
+    user_id: u64,
+    include_posts: Option<bool>,
+) -> Result<User, String> {
+    todo!()
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn save_map<R: Runtime>(app: tauri::AppHandle<R>, entries: HashMap<String, Vec<u8>>) {}
+
+fn main() {
+    tauri::Builder::default()
+        .invoke_handler(tauri::generate_handler![commands::get_user])
+        .run(tauri::generate_context!());
+}
+"#;
+
+    #[test]
+    fn test_extract_tauri_surface() {
+        let sources: BTreeMap<String, String> = [
+            ("src-tauri/src/main.rs", COMMANDS),
+            (
+                "src/api.ts",
+                "export const getUser = (id: number) =>\n  invoke<User>(\"get_user\", { userId: id });\ninvoke(\"gone_command\");\n",
+            ),
+            ("src/util.ts", "apiCall(\"get_user\");\n"),
+        ]
+        .into_iter()
+        .map(|(f, c)| (f.to_string(), c.to_string()))
+        .collect();
+
+        let surface = extract_tauri_surface(&sources);
+        assert_eq!(surface.commands.len(), 2);
+
+        let get_user = &surface.commands[0];
+        assert_eq!(get_user.name, "get_user");
+        assert_eq!(get_user.line, 3);
+        assert!(get_user.is_async && get_user.registered);
+        assert_eq!(
+            get_user.return_type.as_deref(),
+            Some("Result<User, String>")
+        );
+        let params: Vec<(&str, &str, bool)> = get_user
+            .params
+            .iter()
+            .map(|p| (p.js_name.as_str(), p.rust_type.as_str(), p.injected))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("state", "State<'_, AppState>", true),
+                ("userId", "u64", false),
+                ("includePosts", "Option<bool>", false),
+            ]
+        );
+        let callers: Vec<(&str, usize)> = get_user
+            .callers
+            .iter()
+            .map(|c| (c.file_path.as_str(), c.line))
+            .collect();
+        assert_eq!(callers, vec![("src/api.ts", 2), ("src/util.ts", 1)]);
+
+        let save_map = &surface.commands[1];
+        assert!(!save_map.registered && save_map.callers.is_empty());
+        assert_eq!(save_map.return_type, None);
+        assert_eq!(save_map.params[0].rust_type, "tauri::AppHandle<R>");
+        assert!(save_map.params[0].injected);
+        assert_eq!(save_map.params[1].js_name, "entries");
+        assert_eq!(save_map.params[1].rust_type, "HashMap<String, Vec<u8>>");
+
+        assert_eq!(surface.unknown_invokes.len(), 1);
+        assert_eq!(surface.unknown_invokes[0].command, "gone_command");
+    }
+
+    #[test]
+    fn test_surface_chunk_is_replaced() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let insert = |file: &str, content: &str| {
+            chunk().file(file).content(content).insert(&conn);
+        };
+
+        assert_eq!(generate_tauri_surface_chunk(&conn, "/p").unwrap(), 0);
+        assert!(get_tauri_surface(&conn, "/p").unwrap().is_none());
+
+        insert("src-tauri/src/main.rs", COMMANDS);
+        assert_eq!(generate_tauri_surface_chunk(&conn, "/p").unwrap(), 2);
+        insert("src/lib.rs", "#[tauri::command]\nfn ping() {}\n");
+        assert_eq!(generate_tauri_surface_chunk(&conn, "/p").unwrap(), 3);
+
+        let surface = get_tauri_surface(&conn, "/p").unwrap().unwrap();
+        assert_eq!(surface.commands.len(), 3);
+        let surface_chunks: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM chunks WHERE entity_name = ?1",
+                [TAURI_SURFACE_ENTITY],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(surface_chunks, 1);
+    }
+}
@@ -0,0 +1,383 @@
+use super::storage::{get_test_results, insert_test_result, CHUNK_CONTENT};
+use super::types::{ChunkType, RelationshipType, TestImportResult, TestResult, TestStatus};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Caso de test leído de un reporte JUnit
+#[derive(Debug, Clone, PartialEq)]
+pub struct JUnitTestCase {
+    pub suite_name: Option<String>,
+    pub class_name: Option<String>,
+    pub test_name: String,
+    pub file_path: Option<String>, // Atributo `file` (pytest, jest-junit)
+    pub status: TestStatus,
+    pub duration_ms: Option<f64>,
+    pub failure_message: Option<String>,
+    pub failure_details: Option<String>,
+}
+
+/// Parsea un reporte JUnit XML (`<testsuites>`/`<testsuite>`/`<testcase>`)
+pub fn parse_junit_xml(xml: &str) -> Result<Vec<JUnitTestCase>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut cases = Vec::new();
+    let mut suites: Vec<Option<String>> = Vec::new();
+    let mut current: Option<JUnitTestCase> = None;
+    let mut in_failure = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| anyhow!("Invalid JUnit XML at {}: {}", reader.buffer_position(), e))?;
+
+        match event {
+            Event::Start(e) if e.name().as_ref() == b"testsuite" => {
+                suites.push(attribute(&e, "name"));
+            }
+            Event::Start(e) if e.name().as_ref() == b"testcase" => {
+                current = Some(test_case(&e, suites.last().cloned().flatten()));
+            }
+            Event::Empty(e) if e.name().as_ref() == b"testcase" => {
+                cases.push(test_case(&e, suites.last().cloned().flatten()));
+            }
+            Event::Start(e) | Event::Empty(e) if current.is_some() => {
+                let status = match e.name().as_ref() {
+                    b"failure" => Some(TestStatus::Failed),
+                    b"error" => Some(TestStatus::Error),
+                    b"skipped" => Some(TestStatus::Skipped),
+                    _ => None,
+                };
+                if let (Some(status), Some(case)) = (status, current.as_mut()) {
+                    if status != TestStatus::Skipped {
+                        case.failure_message = attribute(&e, "message");
+                        in_failure = true;
+                    }
+                    case.status = status;
+                }
+            }
+            Event::Text(t) if in_failure => {
+                if let Some(case) = current.as_mut() {
+                    let text = t.unescape().map(|s| s.to_string()).unwrap_or_default();
+                    append_details(case, &text);
+                }
+            }
+            Event::CData(c) if in_failure => {
+                if let Some(case) = current.as_mut() {
+                    append_details(case, &String::from_utf8_lossy(&c.into_inner()));
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"failure" | b"error" => in_failure = false,
+                b"testcase" => {
+                    if let Some(case) = current.take() {
+                        cases.push(case);
+                    }
+                    in_failure = false;
+                }
+                b"testsuite" => {
+                    suites.pop();
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(cases)
+}
+
+/// Importa un reporte JUnit: registra cada caso en test_results, enlazado al chunk
+/// de tests de su archivo. Retorna el resumen de la ejecución
+pub fn import_junit_report(
+    conn: &Connection,
+    project_path: &str,
+    xml: &str,
+) -> Result<TestImportResult> {
+    let cases = parse_junit_xml(xml)?;
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let created_at = Utc::now();
+
+    let mut result = TestImportResult {
+        run_id: run_id.clone(),
+        total: cases.len(),
+        passed: 0,
+        failed: 0,
+        errors: 0,
+        skipped: 0,
+        linked: 0,
+    };
+
+    for case in cases {
+        match case.status {
+            TestStatus::Passed => result.passed += 1,
+            TestStatus::Failed => result.failed += 1,
+            TestStatus::Error => result.errors += 1,
+            TestStatus::Skipped => result.skipped += 1,
+        }
+
+        let test_chunk = find_test_chunk(conn, project_path, &case)?;
+        if test_chunk.is_some() {
+            result.linked += 1;
+        }
+
+        insert_test_result(
+            conn,
+            &TestResult {
+                id: None,
+                project_path: project_path.to_string(),
+                run_id: run_id.clone(),
+                suite_name: case.suite_name,
+                class_name: case.class_name,
+                test_name: case.test_name,
+                file_path: test_chunk
+                    .as_ref()
+                    .map(|(_, file)| file.clone())
+                    .or(case.file_path),
+                status: case.status,
+                duration_ms: case.duration_ms,
+                failure_message: case.failure_message,
+                failure_details: case.failure_details,
+                test_chunk_id: test_chunk.map(|(id, _)| id),
+                code_under_test: Vec::new(),
+                created_at,
+            },
+        )?;
+    }
+
+    Ok(result)
+}
+
+/// Tests fallidos (failed/error) de una ejecución (por defecto la última importada),
+/// con los archivos de código bajo test según las relaciones `tested_by`
+pub fn get_failing_tests(
+    conn: &Connection,
+    project_path: &str,
+    run_id: Option<&str>,
+) -> Result<Vec<TestResult>> {
+    let run_id = match run_id {
+        Some(id) => id.to_string(),
+        None => match conn
+            .query_row(
+                "SELECT run_id FROM test_results WHERE project_path = ?1
+                 ORDER BY created_at DESC, id DESC LIMIT 1",
+                params![project_path],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+        {
+            Some(id) => id,
+            None => return Ok(Vec::new()),
+        },
+    };
+
+    let mut failing: Vec<TestResult> = get_test_results(conn, project_path, &run_id)?
+        .into_iter()
+        .filter(|r| matches!(r.status, TestStatus::Failed | TestStatus::Error))
+        .collect();
+
+    for result in &mut failing {
+        if let Some(chunk_id) = result.test_chunk_id {
+            result.code_under_test = code_under_test(conn, chunk_id)?;
+        }
+    }
+
+    Ok(failing)
+}
+
+/// Archivos de código enlazados al chunk de tests con `tested_by`
+fn code_under_test(conn: &Connection, test_chunk_id: i64) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT c.file_path FROM chunk_relationships r
+         JOIN chunks c ON c.id = r.from_chunk_id
+         WHERE r.to_chunk_id = ?1 AND r.relationship_type = ?2 AND c.file_path IS NOT NULL
+         ORDER BY c.file_path",
+    )?;
+    let files = stmt
+        .query_map(
+            params![test_chunk_id, RelationshipType::TestedBy.as_str()],
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(files)
+}
+
+/// Busca el chunk de tests del caso: por el atributo `file`, por el classname
+/// convertido en ruta o por el nombre del test dentro del contenido del chunk
+fn find_test_chunk(
+    conn: &Connection,
+    project_path: &str,
+    case: &JUnitTestCase,
+) -> Result<Option<(i64, String)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, file_path, {} FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND file_path IS NOT NULL
+           AND entity_name IS NULL AND deleted_at IS NULL
+         ORDER BY updated_at DESC, id DESC",
+        CHUNK_CONTENT
+    ))?;
+    let chunks = stmt
+        .query_map(params![project_path, ChunkType::Tests.as_str()], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // 1. Atributo `file`
+    if let Some(file) = &case.file_path {
+        let file = file.trim_start_matches("./").replace('\\', "/");
+        if let Some((id, path, _)) = chunks
+            .iter()
+            .find(|(_, path, _)| *path == file || file.ends_with(&format!("/{}", path)))
+        {
+            return Ok(Some((*id, path.clone())));
+        }
+    }
+
+    // 2. Classname como ruta: `tests.test_api.TestUsers` -> tests/test_api
+    if let Some(class_name) = &case.class_name {
+        let segments: Vec<&str> = class_name
+            .split(['.', ':'])
+            .filter(|s| !s.is_empty())
+            .collect();
+        for len in (1..=segments.len()).rev() {
+            let candidate = segments[..len].join("/");
+            if let Some((id, path, _)) = chunks.iter().find(|(_, path, _)| {
+                let stem = path.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(path);
+                stem == candidate || stem.ends_with(&format!("/{}", candidate))
+            }) {
+                return Ok(Some((*id, path.clone())));
+            }
+        }
+    }
+
+    // 3. Nombre del test listado en el chunk (sin los parámetros de pytest: `test_x[1-2]`)
+    let test_name = case
+        .test_name
+        .split_once('[')
+        .map(|(name, _)| name)
+        .unwrap_or(&case.test_name);
+    Ok(chunks
+        .iter()
+        .find(|(_, _, content)| {
+            content
+                .lines()
+                .any(|l| l.ends_with(&format!(" {}", test_name)))
+        })
+        .map(|(id, path, _)| (*id, path.clone())))
+}
+
+fn test_case(e: &BytesStart, suite_name: Option<String>) -> JUnitTestCase {
+    JUnitTestCase {
+        suite_name,
+        class_name: attribute(e, "classname"),
+        test_name: attribute(e, "name").unwrap_or_default(),
+        file_path: attribute(e, "file"),
+        status: TestStatus::Passed,
+        duration_ms: attribute(e, "time")
+            .and_then(|t| t.parse::<f64>().ok())
+            .map(|secs| secs * 1000.0),
+        failure_message: None,
+        failure_details: None,
+    }
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Option<String> {
+    e.attributes()
+        .filter_map(|a| a.ok())
+        .find(|a| a.key.as_ref() == name.as_bytes())
+        .and_then(|a| a.unescape_value().ok().map(|v| v.to_string()))
+}
+
+fn append_details(case: &mut JUnitTestCase, text: &str) {
+    let details = case.failure_details.get_or_insert_with(String::new);
+    if !details.is_empty() {
+        details.push('\n');
+    }
+    details.push_str(text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+
+    const REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="api" tests="3">
+    <testcase classname="tests.test_api" name="test_list_users" time="0.012"/>
+    <testcase classname="tests.test_api" name="test_create_user" time="0.5">
+      <failure message="assert 500 == 201">Traceback &lt;most recent call last&gt;
+AssertionError</failure>
+    </testcase>
+    <testcase classname="tests.test_api" name="test_delete_user">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+
+    #[test]
+    fn test_parse_junit_xml() {
+        let cases = parse_junit_xml(REPORT).unwrap();
+        assert_eq!(cases.len(), 3);
+        assert_eq!(cases[0].status, TestStatus::Passed);
+        assert_eq!(cases[0].suite_name.as_deref(), Some("api"));
+        assert_eq!(cases[1].status, TestStatus::Failed);
+        assert_eq!(
+            cases[1].failure_message.as_deref(),
+            Some("assert 500 == 201")
+        );
+        assert!(cases[1]
+            .failure_details
+            .as_deref()
+            .unwrap()
+            .contains("<most recent call last>"));
+        assert_eq!(cases[1].duration_ms, Some(500.0));
+        assert_eq!(cases[2].status, TestStatus::Skipped);
+    }
+
+    #[test]
+    fn test_import_links_test_chunk_and_code_under_test() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let project = "/work/svc";
+        conn.execute(
+            "INSERT INTO chunks (id, project_path, chunk_type, file_path, content, content_hash, created_at, updated_at)
+             VALUES (1, ?1, 'tests', 'tests/test_api.py', '1. test_create_user', 'h1', '', ''),
+                    (2, ?1, 'raw_source', 'svc/api.py', 'def create_user(): ...', 'h2', '', '')",
+            params![project],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO chunk_relationships (from_chunk_id, to_chunk_id, relationship_type, created_at)
+             VALUES (2, 1, 'tested_by', '')",
+            [],
+        )
+        .unwrap();
+
+        let summary = import_junit_report(&conn, project, REPORT).unwrap();
+        assert_eq!(
+            (
+                summary.total,
+                summary.passed,
+                summary.failed,
+                summary.skipped
+            ),
+            (3, 1, 1, 1)
+        );
+        assert_eq!(summary.linked, 3);
+
+        let failing = get_failing_tests(&conn, project, None).unwrap();
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].test_name, "test_create_user");
+        assert_eq!(failing[0].file_path.as_deref(), Some("tests/test_api.py"));
+        assert_eq!(failing[0].code_under_test, vec!["svc/api.py".to_string()]);
+    }
+}
@@ -0,0 +1,257 @@
+use super::lint::{definitions, file_content};
+use super::snapshots::get_snapshot_diff;
+use super::storage::{get_latest_file_chunk_id, get_snapshot};
+use super::types::{ChunkType, RelationshipType, SuggestedTest, TestSuggestion, TestsMetadata};
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Saltos máximos por relaciones `calls` desde un archivo modificado hasta sus llamadores
+pub const MAX_CALL_DEPTH: usize = 3;
+
+/// Tests que conviene ejecutar para un cambio (lista de archivos o snapshot): los que
+/// prueban los archivos modificados y los que prueban a quienes los llaman, siguiendo
+/// las relaciones `tested_by` y `calls`. Ordenados del más cercano al más lejano
+pub fn suggest_tests_for_changes(
+    conn: &Connection,
+    project_path: &str,
+    files: Option<&[String]>,
+    snapshot_id: Option<i64>,
+) -> Result<TestSuggestion> {
+    let changed_files: BTreeSet<String> = match (files, snapshot_id) {
+        (Some(files), _) => files
+            .iter()
+            .map(|f| f.trim_start_matches("./").to_string())
+            .collect(),
+        (None, Some(snapshot_id)) => {
+            let snapshot = get_snapshot(conn, snapshot_id)?.context("Snapshot not found")?;
+            if snapshot.project_path != project_path {
+                bail!("Snapshot {} belongs to another project", snapshot_id);
+            }
+            get_snapshot_diff(conn, snapshot_id)?
+                .files
+                .into_iter()
+                .map(|f| f.file_path)
+                .collect()
+        }
+        (None, None) => bail!("Either files or a snapshot_id is required"),
+    };
+
+    // Archivo alcanzado -> (distancia, archivo modificado de origen), en orden BFS
+    let callers = file_edges(conn, project_path, RelationshipType::Calls)?;
+    let mut reached: BTreeMap<String, (usize, String)> = BTreeMap::new();
+    let mut queue: VecDeque<String> = VecDeque::new();
+    for file in &changed_files {
+        reached.insert(file.clone(), (0, file.clone()));
+        queue.push_back(file.clone());
+    }
+    let mut order = Vec::new();
+    while let Some(file) = queue.pop_front() {
+        let (distance, origin) = reached[&file].clone();
+        order.push(file.clone());
+        if distance == MAX_CALL_DEPTH {
+            continue;
+        }
+        // Las relaciones `calls` van del llamador al llamado
+        for (caller, callee) in &callers {
+            if callee == &file && !reached.contains_key(caller) {
+                reached.insert(caller.clone(), (distance + 1, origin.clone()));
+                queue.push_back(caller.clone());
+            }
+        }
+    }
+
+    let tested_by = file_edges(conn, project_path, RelationshipType::TestedBy)?;
+    let mut tests: Vec<SuggestedTest> = Vec::new();
+    let mut seen = BTreeSet::new();
+    for file in &order {
+        let (distance, origin) = &reached[file];
+        // Un archivo de tests modificado se ejecuta entero
+        let mut candidates: Vec<(&String, &String)> = Vec::new();
+        if *distance == 0 {
+            candidates.push((file, file));
+        }
+        candidates.extend(
+            tested_by
+                .iter()
+                .filter(|(source, _)| source == file)
+                .map(|(source, test)| (test, source)),
+        );
+
+        for (test_file, tested_file) in candidates {
+            if seen.contains(test_file) {
+                continue;
+            }
+            let Some(chunk_id) =
+                get_latest_file_chunk_id(conn, project_path, test_file, &ChunkType::Tests)?
+            else {
+                continue;
+            };
+            seen.insert(test_file.clone());
+            let test_functions = if test_file == tested_file {
+                Vec::new()
+            } else {
+                test_functions_calling(conn, project_path, chunk_id, tested_file)?
+            };
+            tests.push(SuggestedTest {
+                test_file: test_file.clone(),
+                chunk_id,
+                tested_file: tested_file.clone(),
+                changed_file: origin.clone(),
+                distance: *distance,
+                test_functions,
+            });
+        }
+    }
+
+    Ok(TestSuggestion {
+        project_path: project_path.to_string(),
+        changed_files: changed_files.into_iter().collect(),
+        tests,
+    })
+}
+
+/// Pares (archivo origen, archivo destino) de las relaciones vigentes de un tipo
+fn file_edges(
+    conn: &Connection,
+    project_path: &str,
+    relationship_type: RelationshipType,
+) -> Result<BTreeSet<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT src.file_path, dst.file_path FROM chunk_relationships r
+         JOIN chunks src ON src.id = r.from_chunk_id
+         JOIN chunks dst ON dst.id = r.to_chunk_id
+         WHERE src.project_path = ?1 AND r.relationship_type = ?2
+           AND src.file_path IS NOT NULL AND dst.file_path IS NOT NULL
+           AND src.deleted_at IS NULL AND dst.deleted_at IS NULL",
+    )?;
+    let edges = stmt
+        .query_map(params![project_path, relationship_type.as_str()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<BTreeSet<_>>>()?;
+    Ok(edges)
+}
+
+/// Funciones de test del archivo (chunks hijos del chunk de tests vigente) con alguna
+/// assertion que llama a una función definida en `tested_file`
+fn test_functions_calling(
+    conn: &Connection,
+    project_path: &str,
+    test_chunk_id: i64,
+    tested_file: &str,
+) -> Result<Vec<String>> {
+    let Some(content) = file_content(conn, project_path, tested_file) else {
+        return Ok(Vec::new());
+    };
+    let defined: BTreeSet<String> = definitions(&content)
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect();
+
+    let mut stmt = conn.prepare(
+        "SELECT entity_name, metadata FROM chunks
+         WHERE project_path = ?1 AND chunk_type = ?2 AND entity_name IS NOT NULL
+           AND deleted_at IS NULL
+           AND json_extract(metadata, '$.parent_chunk_id') = ?3
+         ORDER BY json_extract(metadata, '$.start_line')",
+    )?;
+    let functions = stmt
+        .query_map(
+            params![project_path, ChunkType::Tests.as_str(), test_chunk_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(functions
+        .into_iter()
+        .filter(|(_, metadata)| {
+            serde_json::from_str::<TestsMetadata>(metadata).is_ok_and(|m| {
+                m.expectations
+                    .iter()
+                    .flat_map(|e| &e.subjects)
+                    .any(|subject| defined.contains(&subject.name))
+            })
+        })
+        .map(|(name, _)| name)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::relationships::infer_relationships;
+    use crate::storage::init_chunk_database;
+    use crate::test_support::chunk;
+    use crate::tests::generate_test_chunks;
+
+    fn insert_source(conn: &Connection, file_path: &str, content: &str) {
+        chunk().file(file_path).content(content).insert(conn);
+    }
+
+    fn index_test(conn: &Connection, file_path: &str, content: &str) {
+        insert_source(conn, file_path, content);
+        generate_test_chunks(conn, "/p", file_path, content).unwrap();
+    }
+
+    #[test]
+    fn test_suggests_tests_of_changed_files_and_their_callers() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_source(&conn, "app/db.py", "def query_users():\n    pass\n");
+        insert_source(
+            &conn,
+            "app/users.py",
+            "from app.db import query_users\n\ndef list_users():\n    return query_users()\n",
+        );
+        insert_source(&conn, "app/billing.py", "def total():\n    pass\n");
+        index_test(
+            &conn,
+            "tests/test_db.py",
+            "from app.db import query_users\n\ndef test_query():\n    assert query_users() == []\n",
+        );
+        index_test(
+            &conn,
+            "tests/test_users.py",
+            "from app.users import list_users\n\ndef test_list():\n    assert list_users() == []\n\ndef test_other():\n    assert 1 == 1\n",
+        );
+        index_test(
+            &conn,
+            "tests/test_billing.py",
+            "from app.billing import total\n\ndef test_total():\n    assert total() is None\n",
+        );
+        infer_relationships(&conn, "/p").unwrap();
+
+        let suggestion =
+            suggest_tests_for_changes(&conn, "/p", Some(&["./app/db.py".to_string()][..]), None)
+                .unwrap();
+        assert_eq!(suggestion.changed_files, vec!["app/db.py"]);
+        let tests: Vec<(&str, usize, &str)> = suggestion
+            .tests
+            .iter()
+            .map(|t| (t.test_file.as_str(), t.distance, t.tested_file.as_str()))
+            .collect();
+        assert_eq!(
+            tests,
+            vec![
+                ("tests/test_db.py", 0, "app/db.py"),
+                ("tests/test_users.py", 1, "app/users.py"),
+            ]
+        );
+        assert_eq!(suggestion.tests[1].changed_file, "app/db.py");
+        assert_eq!(suggestion.tests[1].test_functions, vec!["test_list"]);
+
+        // Un archivo de tests modificado se sugiere a sí mismo
+        let suggestion = suggest_tests_for_changes(
+            &conn,
+            "/p",
+            Some(&["tests/test_billing.py".to_string()][..]),
+            None,
+        )
+        .unwrap();
+        assert_eq!(suggestion.tests.len(), 1);
+        assert!(suggestion.tests[0].test_functions.is_empty());
+
+        assert!(suggest_tests_for_changes(&conn, "/p", None, None).is_err());
+    }
+}
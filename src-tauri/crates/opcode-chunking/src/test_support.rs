@@ -0,0 +1,85 @@
+//! Utilidades compartidas por los tests del crate
+
+use super::storage::{calculate_content_hash, get_chunk_id_by_hash, upsert_chunk};
+use super::types::{Chunk, ChunkType};
+use chrono::Utc;
+use rusqlite::Connection;
+
+/// Chunk de prueba: `raw_source` del proyecto `/p`, sin archivo ni contenido
+/// salvo que se indiquen. El hash cubre proyecto, tipo, archivo, entidad y
+/// contenido, así dos chunks que difieren en cualquiera de ellos no se pisan
+pub(crate) fn chunk() -> ChunkBuilder {
+    ChunkBuilder {
+        chunk: Chunk {
+            id: None,
+            project_path: "/p".to_string(),
+            chunk_type: ChunkType::RawSource,
+            file_path: None,
+            entity_name: None,
+            content: String::new(),
+            content_hash: String::new(),
+            metadata: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        },
+    }
+}
+
+pub(crate) struct ChunkBuilder {
+    chunk: Chunk,
+}
+
+impl ChunkBuilder {
+    pub(crate) fn project(mut self, project_path: &str) -> Self {
+        self.chunk.project_path = project_path.to_string();
+        self
+    }
+
+    pub(crate) fn chunk_type(mut self, chunk_type: ChunkType) -> Self {
+        self.chunk.chunk_type = chunk_type;
+        self
+    }
+
+    pub(crate) fn file(mut self, file_path: &str) -> Self {
+        self.chunk.file_path = Some(file_path.to_string());
+        self
+    }
+
+    pub(crate) fn entity(mut self, entity_name: &str) -> Self {
+        self.chunk.entity_name = Some(entity_name.to_string());
+        self
+    }
+
+    pub(crate) fn content(mut self, content: &str) -> Self {
+        self.chunk.content = content.to_string();
+        self
+    }
+
+    /// Acepta JSON ya serializado o un `serde_json::Value`
+    pub(crate) fn metadata(mut self, metadata: impl ToString) -> Self {
+        self.chunk.metadata = Some(metadata.to_string());
+        self
+    }
+
+    pub(crate) fn build(mut self) -> Chunk {
+        let chunk = &self.chunk;
+        self.chunk.content_hash = calculate_content_hash(&format!(
+            "{}\0{}\0{:?}\0{:?}\0{}",
+            chunk.project_path,
+            chunk.chunk_type.as_str(),
+            chunk.file_path,
+            chunk.entity_name,
+            chunk.content
+        ));
+        self.chunk
+    }
+
+    /// Guarda el chunk (o lo actualiza si ya existía) y devuelve su id
+    pub(crate) fn insert(self, conn: &Connection) -> i64 {
+        let chunk = self.build();
+        upsert_chunk(conn, &chunk, None).unwrap();
+        get_chunk_id_by_hash(conn, &chunk.project_path, &chunk.content_hash)
+            .unwrap()
+            .unwrap()
+    }
+}
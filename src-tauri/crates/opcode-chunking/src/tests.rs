@@ -0,0 +1,598 @@
+use super::storage::{calculate_content_hash, get_chunk_id_by_hash, upsert_chunk};
+use super::types::{Chunk, ChunkType, TestExpectation, TestSubject, TestsMetadata};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Función de test de un archivo con su rango de líneas (1-based, inclusivo)
+#[derive(Debug, Clone, PartialEq)]
+struct TestFunction {
+    name: String,
+    start_line: usize,
+    end_line: usize,
+}
+
+/// Genera chunks de tests por archivo: un chunk del archivo con el resumen y, colgando
+/// de él, un chunk por función de test (`entity_name` = nombre del test)
+pub fn generate_test_chunks(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    content: &str,
+) -> Result<usize> {
+    // Detectar si es un archivo de tests
+    if !is_test_file(file_path, content) {
+        return Ok(0);
+    }
+
+    // Extraer información de tests
+    let test_functions = extract_test_functions(content, file_path);
+    let expectations = extract_expectations(content);
+
+    // Crear representación del chunk de tests
+    let mut test_repr = String::new();
+    test_repr.push_str(&format!("# Test File: {}\n", file_path));
+    test_repr.push_str(&format!("# Test Functions: {}\n\n", test_functions.len()));
+
+    for (idx, test_func) in test_functions.iter().enumerate() {
+        test_repr.push_str(&format!("{}. {}\n", idx + 1, test_func.name));
+    }
+
+    test_repr.push_str(&format!("\n# Expectations: {}\n", expectations.len()));
+    for exp in &expectations {
+        test_repr.push_str(&format!("- {}", exp.assertion));
+        if !exp.subjects.is_empty() {
+            let subjects: Vec<String> = exp.subjects.iter().map(render_subject).collect();
+            test_repr.push_str(&format!(" → {}", subjects.join(", ")));
+        }
+        test_repr.push('\n');
+    }
+
+    let content_hash = calculate_content_hash(&test_repr);
+
+    let chunk = Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::Tests,
+        file_path: Some(file_path.to_string()),
+        entity_name: None,
+        content: test_repr,
+        content_hash: content_hash.clone(),
+        metadata: Some(serde_json::to_string(&TestsMetadata {
+            expectations: expectations.clone(),
+            ..Default::default()
+        })?),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    upsert_chunk(conn, &chunk, None)?;
+    let parent_chunk_id = get_chunk_id_by_hash(conn, project_path, &content_hash)?;
+
+    // Un chunk por función para poder recuperar un test fallido por separado
+    let lines: Vec<&str> = content.lines().collect();
+    for test_func in &test_functions {
+        let body = lines[test_func.start_line - 1..test_func.end_line.min(lines.len())].join("\n");
+        let metadata = TestsMetadata {
+            parent_chunk_id,
+            start_line: Some(test_func.start_line),
+            end_line: Some(test_func.end_line),
+            expectations: expectations
+                .iter()
+                .filter(|e| (test_func.start_line..=test_func.end_line).contains(&e.line))
+                .cloned()
+                .collect(),
+        };
+        let function_chunk = Chunk {
+            id: None,
+            project_path: project_path.to_string(),
+            chunk_type: ChunkType::Tests,
+            file_path: Some(file_path.to_string()),
+            entity_name: Some(test_func.name.clone()),
+            // El hash incluye archivo y nombre: dos tests con el mismo cuerpo no se mezclan
+            content_hash: calculate_content_hash(&format!(
+                "{}:{}\n{}",
+                file_path, test_func.name, body
+            )),
+            content: body,
+            metadata: Some(serde_json::to_string(&metadata)?),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        upsert_chunk(conn, &function_chunk, None)?;
+    }
+
+    Ok(1 + test_functions.len())
+}
+
+/// Detecta si un archivo es un archivo de tests
+fn is_test_file(file_path: &str, content: &str) -> bool {
+    // Por nombre de archivo
+    if file_path.contains("test") || file_path.contains("spec") || file_path.ends_with("_test.rs") {
+        return true;
+    }
+
+    // Por contenido (buscar keywords de testing)
+    content.contains("#[test]")
+        || content.contains("func Test")
+        || content.contains("@Test")
+        || content.contains("describe(")
+        || content.contains("it(")
+        || content.contains("test(")
+        || content.contains("def test_")
+        || content.contains("class Test")
+}
+
+/// Extrae las funciones de test con su rango de líneas
+fn extract_test_functions(content: &str, file_path: &str) -> Vec<TestFunction> {
+    let extension = file_path.rsplit('.').next().unwrap_or_default();
+    match extension {
+        // Rust: #[test] fn test_name() { ... }
+        "rs" => braced_tests(
+            content,
+            r"#\[(?:tokio::)?test\]\s*(?:#\[[^\]]*\]\s*)*(?:async\s+)?fn\s+([a-zA-Z0-9_]+)",
+        ),
+        // Go: func TestXxx(t *testing.T) { ... }
+        "go" => braced_tests(content, r"(?m)^func\s+(Test[A-Z0-9_][a-zA-Z0-9_]*)\s*\("),
+        // Java/JUnit: @Test (y otras anotaciones) void name() { ... }
+        "java" => braced_tests(
+            content,
+            r"@Test\b(?:\([^)]*\))?\s+(?:@\w+(?:\([^)]*\))?\s+)*(?:(?:public|protected|private|static|final)\s+)*void\s+(\w+)\s*\(",
+        ),
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" => jest_tests(content),
+        "py" => python_tests(content),
+        _ => Vec::new(),
+    }
+}
+
+/// Tests cuyo cuerpo es el bloque `{ ... }` que sigue a la cabecera (grupo 1 = nombre)
+fn braced_tests(content: &str, header: &str) -> Vec<TestFunction> {
+    let re = Regex::new(header).unwrap();
+    re.captures_iter(content)
+        .filter_map(|cap| {
+            let (whole, name) = (cap.get(0)?, cap.get(1)?);
+            let end = content[whole.end()..]
+                .find('{')
+                .map(|open| block_end(content, whole.end() + open, '{', '}'))
+                .unwrap_or(whole.end());
+            Some(TestFunction {
+                name: name.as_str().to_string(),
+                start_line: line_of(content, whole.start()),
+                end_line: line_of(content, end),
+            })
+        })
+        .collect()
+}
+
+/// Jest/Vitest: `it`/`test` (con `.only`, `.skip`...) dentro de sus `describe`; el nombre
+/// lleva la ruta completa de describes (`api > users > lists users`)
+fn jest_tests(content: &str) -> Vec<TestFunction> {
+    let re = Regex::new(
+        r#"\b(describe|it|test)(?:\.(?:only|skip|concurrent|todo))?\s*(\()\s*['"`]([^'"`]+)['"`]"#,
+    )
+    .unwrap();
+
+    // (inicio, fin, nombre) de cada describe
+    let mut describes: Vec<(usize, usize, &str)> = Vec::new();
+    let mut tests = Vec::new();
+    for cap in re.captures_iter(content) {
+        let (Some(whole), Some(open), Some(name)) = (cap.get(0), cap.get(2), cap.get(3)) else {
+            continue;
+        };
+        let end = block_end(content, open.start(), '(', ')');
+        if &cap[1] == "describe" {
+            describes.push((whole.start(), end, name.as_str()));
+            continue;
+        }
+        let mut path: Vec<&str> = describes
+            .iter()
+            .filter(|(start, describe_end, _)| {
+                *start < whole.start() && whole.start() < *describe_end
+            })
+            .map(|(_, _, describe)| *describe)
+            .collect();
+        path.push(name.as_str());
+        tests.push(TestFunction {
+            name: path.join(" > "),
+            start_line: line_of(content, whole.start()),
+            end_line: line_of(content, end),
+        });
+    }
+    tests
+}
+
+/// Python: `def test_*` hasta la siguiente línea con la misma indentación o menos. Los
+/// decoradores (`@pytest.mark.parametrize`...) forman parte del test
+fn python_tests(content: &str) -> Vec<TestFunction> {
+    let re = Regex::new(r"^(\s*)(?:async\s+)?def\s+(test_[a-zA-Z0-9_]+)").unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut tests = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        let Some(cap) = re.captures(line) else {
+            continue;
+        };
+        let indent = cap[1].len();
+        let start = decorators_start(&lines, idx, indent);
+        let mut end_line = idx + 1;
+        for (offset, next) in lines[idx + 1..].iter().enumerate() {
+            if next.trim().is_empty() {
+                continue;
+            }
+            if next.len() - next.trim_start().len() <= indent {
+                break;
+            }
+            end_line = idx + offset + 2;
+        }
+        tests.push(TestFunction {
+            name: cap[2].to_string(),
+            start_line: start + 1,
+            end_line,
+        });
+    }
+    tests
+}
+
+/// Índice de la primera línea de los decoradores de un `def` (el propio `def` si no
+/// tiene), incluyendo los que ocupan varias líneas
+fn decorators_start(lines: &[&str], def_idx: usize, def_indent: usize) -> usize {
+    let mut start = def_idx;
+    for idx in (0..def_idx).rev() {
+        let trimmed = lines[idx].trim_start();
+        let indent = lines[idx].len() - trimmed.len();
+        if trimmed.is_empty() {
+            break;
+        }
+        if indent == def_indent && trimmed.starts_with('@') {
+            start = idx;
+        } else if indent < def_indent || (indent == def_indent && !trimmed.starts_with([')', ']']))
+        {
+            break;
+        }
+    }
+    start
+}
+
+/// Línea (1-based) de un offset del contenido
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Offset del delimitador que cierra el que está en `open_at` (o el final del contenido
+/// si no se cierra). Cuenta delimitadores sin entender strings ni comentarios
+fn block_end(content: &str, open_at: usize, open: char, close: char) -> usize {
+    let mut depth = 0usize;
+    for (offset, c) in content[open_at..].char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return open_at + offset;
+            }
+        }
+    }
+    content.len().saturating_sub(1)
+}
+
+/// Extrae las assertions de los tests (una por línea) con las funciones que llaman,
+/// resueltas contra los imports del archivo
+fn extract_expectations(content: &str) -> Vec<TestExpectation> {
+    // Patrones comunes de assertions
+    let assertion_re = Regex::new(
+        r"assert(?:_eq|_ne)?!?\s*\(|assert[A-Z][a-zA-Z]*\s*\(|^\s*assert\s|\bexpect\s*\(|\.to[A-Z][a-zA-Z]*\(|should\.|\bt\.(?:Error|Errorf|Fatal|Fatalf)\(",
+    )
+    .unwrap();
+    // Llamadas con su cualificador: `login(`, `auth.login(`, `auth::login(`
+    let call_re =
+        Regex::new(r"((?:[A-Za-z_][A-Za-z0-9_]*(?:\.|::))*)([A-Za-z_][A-Za-z0-9_]*)\s*\(").unwrap();
+    let imports = imported_names(content);
+
+    let mut expectations = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        if !assertion_re.is_match(line) {
+            continue;
+        }
+        let mut subjects: Vec<TestSubject> = Vec::new();
+        for cap in call_re.captures_iter(line) {
+            let name = &cap[2];
+            if is_assertion_call(name) {
+                continue;
+            }
+            let qualifier = cap[1].trim_end_matches(['.', ':']);
+            let module = if qualifier.is_empty() {
+                imports.get(name).cloned()
+            } else {
+                // `auth.login(`: el primer segmento tiene que ser un import
+                let head = qualifier.split(['.', ':']).next().unwrap_or_default();
+                match imports.get(head) {
+                    Some(module) => Some(module.clone()),
+                    None => continue, // Método de una variable local
+                }
+            };
+            let subject = TestSubject {
+                name: name.to_string(),
+                module,
+            };
+            if !subjects.contains(&subject) {
+                subjects.push(subject);
+            }
+        }
+        expectations.push(TestExpectation {
+            line: idx + 1,
+            assertion: line.trim().to_string(),
+            subjects,
+        });
+    }
+
+    expectations
+}
+
+/// Funciones del framework de tests y builtins que no son el sujeto de la assertion
+fn is_assertion_call(name: &str) -> bool {
+    let is_matcher = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(|c: char| c.is_uppercase()))
+    };
+    is_matcher("assert")
+        || is_matcher("to")
+        || matches!(
+            name,
+            "assert_eq"
+                | "assert_ne"
+                | "expect"
+                | "Error"
+                | "Errorf"
+                | "Fatal"
+                | "Fatalf"
+                | "len"
+                | "str"
+                | "int"
+                | "list"
+                | "dict"
+                | "set"
+                | "isinstance"
+                | "Some"
+                | "Ok"
+                | "Err"
+                | "String"
+        )
+}
+
+/// Nombres importados en el archivo → módulo del que vienen (JS/TS, Python, Rust, Go, Java)
+fn imported_names(content: &str) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+
+    // JS/TS: import x, { a, b as c } from 'm' / import * as x from 'm'
+    let js_re = Regex::new(
+        r#"import\s+(?:type\s+)?(?:([A-Za-z_$][\w$]*)\s*,?\s*)?(?:\{([^}]*)\}|\*\s+as\s+([A-Za-z_$][\w$]*))?\s*from\s*['"]([^'"]+)['"]"#,
+    )
+    .unwrap();
+    for cap in js_re.captures_iter(content) {
+        let module = &cap[4];
+        for group in [1, 2, 3] {
+            if let Some(list) = cap.get(group) {
+                add_imports(&mut names, list.as_str(), module);
+            }
+        }
+    }
+
+    // Python: from m import a, b / import m.n as x
+    let py_from_re = Regex::new(r"(?m)^\s*from\s+([\w.]+)\s+import\s+\(?([^)\n]+)").unwrap();
+    for cap in py_from_re.captures_iter(content) {
+        add_imports(&mut names, &cap[2], &cap[1]);
+    }
+    let py_import_re = Regex::new(r"(?m)^\s*import\s+([\w.]+)(?:\s+as\s+(\w+))?\s*$").unwrap();
+    for cap in py_import_re.captures_iter(content) {
+        let alias = cap.get(2).map_or(&cap[1], |a| a.as_str());
+        names.insert(alias.to_string(), cap[1].to_string());
+    }
+
+    // Rust: use a::b::{c, d}; / use a::b::c;
+    let rust_re =
+        Regex::new(r"(?m)^\s*(?:pub\s+)?use\s+([\w:]+?)::(?:\{([^}]*)\}|(\w+(?:\s+as\s+\w+)?));")
+            .unwrap();
+    for cap in rust_re.captures_iter(content) {
+        if let Some(list) = cap.get(2).or_else(|| cap.get(3)) {
+            add_imports(&mut names, list.as_str(), &cap[1]);
+        }
+    }
+
+    // Go: import "a/b" / import x "a/b", también dentro de `import ( ... )`
+    let go_re =
+        Regex::new(r#"(?m)^\s*(?:import\s+)?(?:([A-Za-z_]\w*)\s+)?"([\w./-]+)"\s*$"#).unwrap();
+    for cap in go_re.captures_iter(content) {
+        let path = &cap[2];
+        let alias = cap
+            .get(1)
+            .map_or_else(|| path.rsplit('/').next().unwrap_or(path), |a| a.as_str());
+        names.insert(alias.to_string(), path.to_string());
+    }
+
+    // Java: import a.b.C; / import static a.b.C.method;
+    let java_re = Regex::new(r"(?m)^\s*import\s+(?:static\s+)?([\w.]+)\.(\w+)\s*;").unwrap();
+    for cap in java_re.captures_iter(content) {
+        names.insert(cap[2].to_string(), cap[1].to_string());
+    }
+
+    names
+}
+
+/// Registra una lista de imports (`a, b as c`) de un módulo
+fn add_imports(names: &mut HashMap<String, String>, list: &str, module: &str) {
+    for item in list.split(',') {
+        // `a as b` importa `b`; `self` en Rust es el propio módulo
+        let item = item.trim().trim_start_matches("type ");
+        let name = item.rsplit(" as ").next().unwrap_or(item).trim();
+        if name.is_empty() || name == "*" {
+            continue;
+        }
+        let name = if name == "self" {
+            module.rsplit("::").next().unwrap_or(module)
+        } else {
+            name
+        };
+        names.insert(name.to_string(), module.to_string());
+    }
+}
+
+fn render_subject(subject: &TestSubject) -> String {
+    match &subject.module {
+        Some(module) => format!("{} ({})", subject.name, module),
+        None => subject.name.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test_chunk_tests {
+    use super::*;
+    use crate::storage::{init_chunk_database, query_chunks};
+    use crate::types::ChunkQuery;
+
+    #[test]
+    fn test_extract_test_function_ranges() {
+        let rust = "use super::*;\n\n#[test]\nfn adds() {\n    assert_eq!(add(1, 1), 2);\n}\n\n#[test]\nfn subs() { assert!(true); }\n";
+        let ranges: Vec<(String, usize, usize)> = extract_test_functions(rust, "src/math_test.rs")
+            .into_iter()
+            .map(|t| (t.name, t.start_line, t.end_line))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![("adds".to_string(), 3, 6), ("subs".to_string(), 8, 9)]
+        );
+
+        let js = "describe('api', () => {\n  it('lists users', () => {\n    expect(list()).toEqual([]);\n  });\n});\n";
+        let tests = extract_test_functions(js, "api.test.ts");
+        assert_eq!(tests[0].name, "api > lists users");
+        assert_eq!((tests[0].start_line, tests[0].end_line), (2, 4));
+
+        let python = "class TestApi:\n    def test_one(self):\n        x = 1\n\n        assert x\n\n    def test_two(self):\n        pass\n";
+        let tests = extract_test_functions(python, "tests/test_api.py");
+        assert_eq!((tests[0].start_line, tests[0].end_line), (2, 5));
+        assert_eq!((tests[1].start_line, tests[1].end_line), (7, 8));
+    }
+
+    #[test]
+    fn test_go_java_pytest_and_nested_describe_conventions() {
+        let go = "package auth\n\nfunc TestLogin(t *testing.T) {\n\tif !login() {\n\t\tt.Fatal(\"no\")\n\t}\n}\n\nfunc helper() {}\n";
+        let tests = extract_test_functions(go, "auth/login_test.go");
+        assert_eq!(tests.len(), 1);
+        assert_eq!(
+            (
+                tests[0].name.as_str(),
+                tests[0].start_line,
+                tests[0].end_line
+            ),
+            ("TestLogin", 3, 7)
+        );
+
+        let java = "class LoginTest {\n    @Test\n    @DisplayName(\"logs in\")\n    public void logsIn() {\n        assertTrue(login());\n    }\n\n    void helper() {}\n}\n";
+        let tests = extract_test_functions(java, "src/test/java/LoginTest.java");
+        assert_eq!(tests.len(), 1);
+        assert_eq!(
+            (
+                tests[0].name.as_str(),
+                tests[0].start_line,
+                tests[0].end_line
+            ),
+            ("logsIn", 2, 6)
+        );
+
+        let python = "import pytest\n\n@pytest.mark.parametrize(\n    \"a,b\",\n    [(1, 2)],\n)\ndef test_add(a, b):\n    assert a < b\n";
+        let tests = extract_test_functions(python, "tests/test_math.py");
+        assert_eq!((tests[0].start_line, tests[0].end_line), (3, 8));
+
+        let jest = "describe('api', () => {\n  describe.each([1])('users', () => {});\n  describe('users', () => {\n    it.only('lists', () => {});\n  });\n  test('health', () => {});\n});\n";
+        let names: Vec<String> = extract_test_functions(jest, "api.spec.tsx")
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["api > users > lists", "api > health"]);
+    }
+
+    #[test]
+    fn test_expectations_map_to_subjects() {
+        let js = "import { login, logout as signOut } from '../src/auth';\nimport * as api from './api';\n\nit('logs in', () => {\n  const user = makeUser();\n  expect(login(user)).toBe(true);\n  expect(api.fetchUser(user.id)).toEqual(user);\n  expect(user.name.trim()).toBe('x');\n  expect(signOut()).toBeUndefined();\n});\n";
+        let expectations = extract_expectations(js);
+        assert_eq!(expectations.len(), 4);
+        assert_eq!(expectations[0].line, 6);
+        assert_eq!(expectations[0].assertion, "expect(login(user)).toBe(true);");
+        let subject = |name: &str, module: &str| TestSubject {
+            name: name.to_string(),
+            module: Some(module.to_string()),
+        };
+        assert_eq!(
+            expectations[0].subjects,
+            vec![subject("login", "../src/auth")]
+        );
+        assert_eq!(
+            expectations[1].subjects,
+            vec![subject("fetchUser", "./api")]
+        );
+        // Métodos de variables locales no son sujetos
+        assert!(expectations[2].subjects.is_empty());
+        assert_eq!(
+            expectations[3].subjects,
+            vec![subject("signOut", "../src/auth")]
+        );
+
+        let python = "from app.billing import total\nimport app.tax as tax\n\ndef test_total():\n    assert total([1, 2]) == 3\n    assert tax.rate() > 0\n";
+        let expectations = extract_expectations(python);
+        assert_eq!(
+            expectations[0].subjects,
+            vec![subject("total", "app.billing")]
+        );
+        assert_eq!(expectations[1].subjects, vec![subject("rate", "app.tax")]);
+
+        let rust = "use crate::math::{add, sub};\n\n#[test]\nfn adds() {\n    assert_eq!(add(1, 1), helper(2));\n}\n";
+        let expectations = extract_expectations(rust);
+        assert_eq!(
+            expectations[0].subjects,
+            vec![
+                subject("add", "crate::math"),
+                TestSubject {
+                    name: "helper".to_string(),
+                    module: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_one_chunk_per_test_function() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let content =
+            "def test_login():\n    assert login()\n\ndef test_logout():\n    assert logout()\n";
+        assert_eq!(
+            generate_test_chunks(&conn, "/p", "tests/test_auth.py", content).unwrap(),
+            3
+        );
+
+        let chunks = query_chunks(
+            &conn,
+            &ChunkQuery {
+                project_path: Some("/p".to_string()),
+                entity_name: Some("test_logout".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content, "def test_logout():\n    assert logout()");
+        let metadata: serde_json::Value =
+            serde_json::from_str(chunks[0].metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata["start_line"], 4);
+        assert_eq!(metadata["end_line"], 5);
+        assert_eq!(metadata["expectations"][0]["line"], 5);
+        assert_eq!(metadata["expectations"][0]["subjects"][0]["name"], "logout");
+
+        let parent_id = metadata["parent_chunk_id"].as_i64().unwrap();
+        let parent = crate::storage::get_chunk_by_id(&conn, parent_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(parent.entity_name, None);
+        assert!(parent.content.contains("2. test_logout"));
+    }
+}
@@ -0,0 +1,152 @@
+use super::storage::{
+    create_index_operation, get_indexed_file_paths, purge_deleted_chunks,
+    set_index_operation_count, soft_delete_file_chunks,
+};
+use super::types::{ChunkType, IndexOperationKind};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use rusqlite::Connection;
+use std::path::Path;
+
+/// Tipos de chunk que salen de un archivo del proyecto: van a la papelera cuando
+/// el archivo desaparece
+pub const FILE_CHUNK_TYPES: [ChunkType; 6] = [
+    ChunkType::RawSource,
+    ChunkType::Ast,
+    ChunkType::Callgraph,
+    ChunkType::Tests,
+    ChunkType::StateConfig,
+    ChunkType::ProjectMetadata,
+];
+
+/// Manda a la papelera los chunks de un archivo borrado
+pub fn delete_file(conn: &Connection, project_path: &str, file_path: &str) -> Result<usize> {
+    delete_file_in_operation(conn, project_path, file_path, None)
+}
+
+fn delete_file_in_operation(
+    conn: &Connection,
+    project_path: &str,
+    file_path: &str,
+    operation_id: Option<i64>,
+) -> Result<usize> {
+    let count = soft_delete_file_chunks(
+        conn,
+        project_path,
+        file_path,
+        &FILE_CHUNK_TYPES,
+        operation_id,
+    )?;
+    if count > 0 {
+        log::debug!("Soft-deleted {} chunks of {}", count, file_path);
+    }
+    Ok(count)
+}
+
+/// Manda a la papelera los chunks de los archivos indexados que ya no existen en disco,
+/// como una operación `Prune` deshacible
+pub fn prune_missing_files(conn: &Connection, project_path: &str) -> Result<usize> {
+    let missing: Vec<String> = get_indexed_file_paths(conn, project_path, &FILE_CHUNK_TYPES)?
+        .into_iter()
+        .filter(|file_path| !Path::new(project_path).join(file_path).exists())
+        .collect();
+    if missing.is_empty() {
+        return Ok(0);
+    }
+
+    let operation_id = create_index_operation(conn, project_path, IndexOperationKind::Prune)?;
+    let mut count = 0;
+    for file_path in &missing {
+        count += delete_file_in_operation(conn, project_path, file_path, Some(operation_id))?;
+    }
+    set_index_operation_count(conn, operation_id, count)?;
+    Ok(count)
+}
+
+/// Elimina definitivamente los chunks que llevan más de `grace_days` en la papelera
+pub fn purge_expired(
+    conn: &Connection,
+    project_path: Option<&str>,
+    grace_days: u32,
+) -> Result<usize> {
+    let cutoff = Utc::now() - Duration::days(i64::from(grace_days));
+    let count = purge_deleted_chunks(conn, project_path, cutoff)?;
+    if count > 0 {
+        log::info!("Purged {} expired chunk tombstones", count);
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{init_chunk_database, query_chunks, restore_deleted_chunks};
+    use crate::test_support::chunk;
+    use crate::types::ChunkQuery;
+
+    fn insert_file_chunk(conn: &Connection, project_path: &str, file_path: &str) {
+        chunk()
+            .project(project_path)
+            .file(file_path)
+            .content(&format!("// {}", file_path))
+            .insert(conn);
+    }
+
+    fn live_files(conn: &Connection, include_deleted: bool) -> Vec<String> {
+        let mut files: Vec<String> = query_chunks(
+            conn,
+            &ChunkQuery {
+                project_path: Some("/p".to_string()),
+                include_deleted,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .into_iter()
+        .filter_map(|chunk| chunk.file_path)
+        .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_deleted_chunks_are_hidden_until_restored() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_file_chunk(&conn, "/p", "a.rs");
+        insert_file_chunk(&conn, "/p", "b.rs");
+
+        assert_eq!(delete_file(&conn, "/p", "a.rs").unwrap(), 1);
+        assert_eq!(live_files(&conn, false), vec!["b.rs"]);
+        assert_eq!(live_files(&conn, true), vec!["a.rs", "b.rs"]);
+
+        assert_eq!(restore_deleted_chunks(&conn, "/p", None).unwrap(), 1);
+        assert_eq!(live_files(&conn, false), vec!["a.rs", "b.rs"]);
+
+        // Reindexar el mismo contenido también lo saca de la papelera
+        delete_file(&conn, "/p", "b.rs").unwrap();
+        insert_file_chunk(&conn, "/p", "b.rs");
+        assert_eq!(live_files(&conn, false), vec!["a.rs", "b.rs"]);
+    }
+
+    #[test]
+    fn test_purge_only_removes_expired_tombstones() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_file_chunk(&conn, "/p", "old.rs");
+        insert_file_chunk(&conn, "/p", "recent.rs");
+        delete_file(&conn, "/p", "old.rs").unwrap();
+        delete_file(&conn, "/p", "recent.rs").unwrap();
+        let long_ago = (Utc::now() - Duration::days(45)).to_rfc3339();
+        conn.execute(
+            "UPDATE chunks SET deleted_at = ?1 WHERE file_path = 'old.rs'",
+            [&long_ago],
+        )
+        .unwrap();
+
+        assert_eq!(purge_expired(&conn, Some("/p"), 30).unwrap(), 1);
+        assert_eq!(live_files(&conn, true), vec!["recent.rs"]);
+        assert_eq!(purge_expired(&conn, None, 0).unwrap(), 1);
+        assert!(live_files(&conn, true).is_empty());
+    }
+}
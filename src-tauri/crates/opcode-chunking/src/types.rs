@@ -0,0 +1,2431 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Representa el tipo de chunk
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkType {
+    /// Chunk 1: Raw source code - archivo completo
+    RawSource,
+    /// Chunk 2: AST comprimido por archivo
+    Ast,
+    /// Chunk 3: Callgraph y dependencias (estático + dinámico)
+    Callgraph,
+    /// Chunk 4: Tests - pruebas unitarias/integrales
+    Tests,
+    /// Chunk 5: Commit history - documentación técnica real
+    CommitHistory,
+    /// Chunk 6: Estado/configuración - ENV, flags, settings
+    StateConfig,
+    /// Chunk 7: Metadata del proyecto - paquetes, versiones, deps
+    ProjectMetadata,
+    /// Chunk 8: Reglas de negocio validadas por humanos
+    BusinessRules,
+    /// Chunk 9: History/Snapshots - Git virtual interno
+    Snapshot,
+    /// Chunk 10: Errores/logs - stacktraces, crashes
+    ErrorLog,
+    /// Issues importados de GitHub/GitLab - requerimiento original
+    Issue,
+    /// Contenido guardado por el usuario o un agente: notas de reunión, decisiones de diseño
+    UserContent,
+}
+
+impl ChunkType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChunkType::RawSource => "raw_source",
+            ChunkType::Ast => "ast",
+            ChunkType::Callgraph => "callgraph",
+            ChunkType::Tests => "tests",
+            ChunkType::CommitHistory => "commit_history",
+            ChunkType::StateConfig => "state_config",
+            ChunkType::ProjectMetadata => "project_metadata",
+            ChunkType::BusinessRules => "business_rules",
+            ChunkType::Snapshot => "snapshot",
+            ChunkType::ErrorLog => "error_log",
+            ChunkType::Issue => "issue",
+            ChunkType::UserContent => "user_content",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "raw_source" => Some(ChunkType::RawSource),
+            "ast" => Some(ChunkType::Ast),
+            "callgraph" => Some(ChunkType::Callgraph),
+            "tests" => Some(ChunkType::Tests),
+            "commit_history" => Some(ChunkType::CommitHistory),
+            "state_config" => Some(ChunkType::StateConfig),
+            "project_metadata" => Some(ChunkType::ProjectMetadata),
+            "business_rules" => Some(ChunkType::BusinessRules),
+            "snapshot" => Some(ChunkType::Snapshot),
+            "error_log" => Some(ChunkType::ErrorLog),
+            "issue" => Some(ChunkType::Issue),
+            "user_content" => Some(ChunkType::UserContent),
+            _ => None,
+        }
+    }
+}
+
+/// Representa un chunk de código/información
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chunk {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub chunk_type: ChunkType,
+    pub file_path: Option<String>, // Path relativo al proyecto
+    pub entity_name: Option<String>, // Nombre de clase/función/módulo si aplica
+    pub content: String,
+    pub content_hash: String, // SHA256 del contenido
+    pub metadata: Option<String>, // JSON con metadata adicional
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Representa una relación entre chunks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkRelationship {
+    pub id: Option<i64>,
+    pub from_chunk_id: i64,
+    pub to_chunk_id: i64,
+    pub relationship_type: RelationshipType,
+    pub metadata: Option<String>, // JSON con metadata adicional
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tipos de relaciones entre chunks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationshipType {
+    /// Importa/depende de
+    DependsOn,
+    /// Llama a
+    Calls,
+    /// Es testeado por
+    TestedBy,
+    /// Implementa regla de negocio
+    ImplementsRule,
+    /// Modificado en el mismo commit
+    ModifiedWith,
+    /// Asociado con error
+    AssociatedWithError,
+    /// Contiene configuración para
+    ConfiguresFor,
+    /// Menciona (issue → archivo)
+    Mentions,
+    /// Renderiza (componente padre → hijo, tabla de rutas → componente)
+    Renders,
+}
+
+impl RelationshipType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationshipType::DependsOn => "depends_on",
+            RelationshipType::Calls => "calls",
+            RelationshipType::TestedBy => "tested_by",
+            RelationshipType::ImplementsRule => "implements_rule",
+            RelationshipType::ModifiedWith => "modified_with",
+            RelationshipType::AssociatedWithError => "associated_with_error",
+            RelationshipType::ConfiguresFor => "configures_for",
+            RelationshipType::Mentions => "mentions",
+            RelationshipType::Renders => "renders",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "depends_on" => Some(RelationshipType::DependsOn),
+            "calls" => Some(RelationshipType::Calls),
+            "tested_by" => Some(RelationshipType::TestedBy),
+            "implements_rule" => Some(RelationshipType::ImplementsRule),
+            "modified_with" => Some(RelationshipType::ModifiedWith),
+            "associated_with_error" => Some(RelationshipType::AssociatedWithError),
+            "configures_for" => Some(RelationshipType::ConfiguresFor),
+            "mentions" => Some(RelationshipType::Mentions),
+            "renders" => Some(RelationshipType::Renders),
+            _ => None,
+        }
+    }
+}
+
+/// Chunk vigente en el otro extremo de una relación, resumido
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedChunk {
+    pub relationship_id: i64,
+    pub relationship_type: RelationshipType,
+    pub relationship_metadata: Option<String>,
+    pub chunk_id: i64,
+    pub chunk_type: ChunkType,
+    pub file_path: Option<String>,
+    pub entity_name: Option<String>,
+    pub summary: String, // Primeras líneas no vacías del contenido
+}
+
+/// Chunk con sus relaciones salientes y entrantes y el resumen de cada chunk relacionado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkWithContext {
+    pub chunk: Chunk,
+    pub outgoing: Vec<LinkedChunk>,
+    pub incoming: Vec<LinkedChunk>,
+}
+
+/// Relaciones creadas por el job de inferencia, por origen
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelationshipRebuildReport {
+    pub project_path: String,
+    pub relationships_created: usize,
+    pub imports: usize,      // depends_on entre archivos y módulos por imports resueltos
+    pub calls: usize,        // calls hacia el único archivo que define la función llamada
+    pub tests: usize,        // tested_by por import, nombre del archivo o tests en el mismo archivo
+    pub config_usage: usize, // configures_for hacia el código que usa sus claves
+    pub co_changes: usize,   // modified_with entre archivos que cambian juntos en commits
+    pub boundaries: usize,   // calls de JS/TS al Rust que implementa el comando o binding
+    pub i18n_usage: usize,   // configures_for de cada clave de traducción al código que la usa
+}
+
+/// Regla de negocio validada por humanos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessRule {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub entity_name: String, // Clase/función/módulo
+    pub file_path: String,
+    pub rule_description: String,
+    pub ai_interpretation: String,
+    pub user_correction: Option<String>,
+    pub is_validated: bool,
+    pub validation_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>, // Etiquetas libres ("critical" marca restricciones no negociables)
+    #[serde(default)]
+    pub check: Option<RuleCheck>, // Comprobación automática de la regla, si se puede expresar
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Comprobación automática de una regla de negocio sobre el código indexado.
+/// `files` limita los archivos revisados con un glob (como en .gitignore)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleCheck {
+    /// Los nombres de funciones/clases deben coincidir con la regex `pattern`
+    Naming {
+        pattern: String,
+        #[serde(default)]
+        files: Option<String>,
+    },
+    /// Ningún archivo puede importar `module` (ni sus submódulos)
+    ForbiddenImport {
+        module: String,
+        #[serde(default)]
+        files: Option<String>,
+    },
+    /// Cada archivo debe tener al menos una coincidencia de la query de tree-sitter
+    /// (ej. una llamada obligatoria). Los lenguajes donde la query no compila no se revisan
+    RequiredPattern {
+        query: String,
+        #[serde(default)]
+        files: Option<String>,
+    },
+}
+
+impl RuleCheck {
+    pub fn files(&self) -> Option<&str> {
+        match self {
+            RuleCheck::Naming { files, .. }
+            | RuleCheck::ForbiddenImport { files, .. }
+            | RuleCheck::RequiredPattern { files, .. } => files.as_deref(),
+        }
+    }
+}
+
+/// Violación de la comprobación de una regla, enlazada a la entidad que la comete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleViolation {
+    pub file_path: String,
+    pub chunk_id: i64, // Chunk de raw source revisado
+    pub line: Option<usize>,
+    pub entity_name: Option<String>, // Función/clase que contiene la línea (None = el archivo)
+    pub message: String,
+}
+
+/// Resultado de comprobar una regla de negocio contra los chunks actuales
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleConformance {
+    pub rule: BusinessRule,
+    pub files_checked: usize,
+    pub violations: Vec<RuleViolation>,
+    pub error: Option<String>, // La comprobación no se pudo evaluar (regex o glob inválidos)
+}
+
+/// Qué reglas de negocio se fijan al inicio de cada paquete de contexto,
+/// sin pasar por el ranking
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RulePinning {
+    /// Las reglas compiten por relevancia como cualquier chunk
+    #[default]
+    None,
+    /// Todas las reglas validadas
+    Validated,
+    /// Solo las reglas validadas con la etiqueta "critical"
+    Critical,
+}
+
+impl RulePinning {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RulePinning::None => "none",
+            RulePinning::Validated => "validated",
+            RulePinning::Critical => "critical",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(RulePinning::None),
+            "validated" => Some(RulePinning::Validated),
+            "critical" => Some(RulePinning::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Snapshot del proyecto (Git real con versionado)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub snapshot_type: SnapshotType,
+    pub parent_snapshot_id: Option<i64>,
+    pub message: String,
+    pub user_message: Option<String>, // Mensaje original del usuario (solo para master)
+    pub changed_files: String,        // JSON array de archivos modificados
+    pub diff_summary: Option<String>, // JSON con DiffSummary (texto libre en snapshots antiguos)
+    pub metadata: Option<String>,     // JSON con metadata adicional
+
+    // Git integration fields
+    pub git_commit_hash: Option<String>, // Hash del commit real de Git
+    pub git_tag: Option<String>,         // Tag de versión (V1, V2, V3 o V1.1, V1.2)
+    pub git_branch: Option<String>,      // Rama (main para master, agent/v1.1 para agent)
+    pub version_major: i32,              // Número de versión principal (1, 2, 3...)
+    pub version_minor: Option<i32>,      // Número de versión secundaria (solo para agent: 1, 2, 3...)
+
+    pub created_at: DateTime<Utc>,
+}
+
+/// Tipo de snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotType {
+    /// Snapshot en rama MASTER (user intent timeline)
+    Master,
+    /// Snapshot en rama AGENT (agent execution timeline)
+    Agent,
+}
+
+impl SnapshotType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotType::Master => "master",
+            SnapshotType::Agent => "agent",
+        }
+    }
+}
+
+/// Error/log entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorLog {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub snapshot_id: Option<i64>,
+    pub file_path: Option<String>,
+    pub entity_name: Option<String>, // Función/método donde ocurrió
+    pub error_type: String,
+    pub message: String,
+    pub stacktrace: Option<String>,
+    #[serde(default)]
+    pub severity: Option<String>, // "error", "warning"... (diagnósticos de lint/build)
+    pub occurrence_count: i32,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub is_resolved: bool,
+}
+
+/// Errores de sintaxis abiertos de un archivo (error_logs con `error_type = "syntax"`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSyntaxErrors {
+    pub file_path: String,
+    pub errors: Vec<ErrorLog>, // Ordenados por posición; el mensaje empieza por "línea:columna"
+}
+
+/// Grupo de errores con mensajes/stacktraces similares
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorCluster {
+    pub error_type: String,
+    pub representative: ErrorLog,   // Error con más ocurrencias del grupo
+    pub examples: Vec<ErrorLog>,    // Variantes distintas del representante (máx. 3)
+    pub error_ids: Vec<i64>,
+    pub total_occurrences: i64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Nivel de detalle del AST serializado, de mayor a menor tamaño
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AstDetail {
+    /// Todos los nodos, incluidos los tokens de puntuación y palabras clave
+    #[default]
+    Full,
+    /// Solo nodos con nombre; de las funciones se omite el cuerpo
+    SignaturesOnly,
+    /// Solo las declaraciones (funciones, tipos, módulos) y su anidamiento
+    Skeleton,
+}
+
+impl AstDetail {
+    /// Siguiente nivel más compacto, None si ya es el mínimo
+    pub fn coarser(self) -> Option<Self> {
+        match self {
+            AstDetail::Full => Some(AstDetail::SignaturesOnly),
+            AstDetail::SignaturesOnly => Some(AstDetail::Skeleton),
+            AstDetail::Skeleton => None,
+        }
+    }
+}
+
+/// Metadata del chunk de AST
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AstMetadata {
+    pub language: String,
+    pub node_count: usize,
+    pub max_depth: usize,
+    /// Estimación de la complejidad ciclomática: 1 + puntos de decisión (condicionales,
+    /// bucles, casos, ternarios, `catch` y operadores `&&`/`||`)
+    #[serde(default)]
+    pub cyclomatic_complexity: usize,
+    /// Funciones, métodos y lambdas (incluidas las anidadas)
+    #[serde(default)]
+    pub function_count: usize,
+    /// Líneas de la función más larga
+    #[serde(default)]
+    pub longest_function_lines: usize,
+    pub has_syntax_errors: bool,
+    /// Nivel de detalle con el que se serializó (puede ser menor al pedido si el AST
+    /// no entraba en `ChunkingOptions.ast_max_tokens`)
+    #[serde(default)]
+    pub detail: AstDetail,
+    /// La representación superó el límite de tamaño y se cortó con un marcador
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(default)]
+    pub omitted_bytes: usize,
+    /// Tipo de nodo tree-sitter de un chunk de entidad (`function_item`, `class_declaration`...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    /// Outline del archivo (solo en el chunk del archivo completo)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<SymbolsMetadata>,
+    /// Posiciones de los nodos ERROR/MISSING (solo en el chunk del archivo completo)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub syntax_errors: Vec<SyntaxErrorLocation>,
+}
+
+/// Error de sintaxis que tree-sitter recuperó con un nodo ERROR o MISSING.
+/// Líneas y columnas empiezan en 1
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyntaxErrorLocation {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub message: String, // "unexpected `...`" o "missing `;`"
+}
+
+/// Símbolos de un archivo extraídos con queries tree-sitter, para mostrar el outline
+/// sin volver a parsear
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SymbolsMetadata {
+    pub exports: Vec<String>,       // Funciones y tipos de primer nivel exportados/públicos
+    pub functions: Vec<SymbolInfo>, // Funciones de primer nivel
+    pub classes: Vec<ClassSymbol>,  // Clases, structs, interfaces, enums... con sus miembros
+}
+
+/// Función o miembro de una clase
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SymbolInfo {
+    pub name: String,
+    pub kind: String,      // "function", "method" o "field"
+    pub signature: String, // Declaración hasta el cuerpo, en una línea
+    pub line: usize,       // 1-based
+}
+
+/// Tipo con miembros; los bloques `impl` de Rust y los métodos de Go se agregan al tipo
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClassSymbol {
+    pub name: String,
+    pub kind: String, // Tipo de nodo tree-sitter (`struct_item`, `class_declaration`...)
+    pub line: usize,
+    pub members: Vec<SymbolInfo>,
+}
+
+/// Metadata del chunk de callgraph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallgraphMetadata {
+    pub is_static: bool,    // true = análisis estático, false = runtime tracking
+    pub entry_points: Vec<String>, // `tipo:nombre`, ej: "main:main", "route:GET /users"
+    pub external_calls: Vec<String>,
+    pub call_count: usize,
+    #[serde(default)]
+    pub dependencies: Vec<ClassifiedDependency>,
+}
+
+/// Metadata de un chunk de tests: el del archivo (sin rango) o el de una función de
+/// test, con su rango de líneas y el chunk del archivo como padre
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestsMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_chunk_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(default)]
+    pub expectations: Vec<TestExpectation>,
+}
+
+/// Assertion de un test y lo que comprueba
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TestExpectation {
+    pub line: usize,       // 1-based dentro del archivo
+    pub assertion: String, // Línea de la assertion sin indentación
+    pub subjects: Vec<TestSubject>,
+}
+
+/// Función o módulo bajo test al que llama una assertion
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TestSubject {
+    pub name: String,
+    pub module: Option<String>, // Módulo del import que lo trae (None = del propio archivo)
+}
+
+/// Origen de una dependencia importada
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    /// Archivo del propio proyecto
+    Internal,
+    /// Paquete hermano del mismo workspace/monorepo
+    Workspace,
+    /// Paquete de terceros
+    External,
+}
+
+impl DependencyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DependencyKind::Internal => "internal",
+            DependencyKind::Workspace => "workspace",
+            DependencyKind::External => "external",
+        }
+    }
+}
+
+/// Import clasificado según su origen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassifiedDependency {
+    pub specifier: String,
+    pub kind: DependencyKind,
+    pub package: Option<String>,       // Nombre del paquete (workspace/external)
+    pub resolved_file: Option<String>, // Archivo del proyecto (internal)
+}
+
+/// Uso de una dependencia en un archivo del proyecto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyUsage {
+    pub file_path: String,
+    pub specifier: String,
+    pub kind: DependencyKind,
+    pub package: Option<String>,
+}
+
+/// Punto de entrada detectado en un archivo (main, bin, ruta HTTP, handler, comando)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EntryPoint {
+    pub file_path: String,
+    pub kind: String, // bin | main | route | handler | command
+    pub name: String, // Nombre del bin o función, o "GET /users" para rutas
+}
+
+/// Parámetro de un comando Tauri
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TauriCommandParam {
+    pub name: String,
+    pub js_name: String, // Clave del objeto de argumentos en `invoke` (camelCase por defecto)
+    pub rust_type: String,
+    pub injected: bool, // State, AppHandle, Window...: lo inyecta Tauri, no el frontend
+}
+
+/// Llamada `invoke("comando")` desde el frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TauriCallSite {
+    pub command: String,
+    pub file_path: String,
+    pub line: usize,
+}
+
+/// Función `#[tauri::command]` con sus parámetros y llamadas desde el frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TauriCommand {
+    pub name: String,
+    pub file_path: String,
+    pub line: usize,
+    pub is_async: bool,
+    pub params: Vec<TauriCommandParam>,
+    pub return_type: Option<String>,
+    pub registered: bool, // Aparece en algún `generate_handler!`
+    pub callers: Vec<TauriCallSite>,
+}
+
+/// Metadata del chunk de API-surface Tauri de un proyecto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TauriSurface {
+    pub kind: String, // "tauri_surface"
+    pub commands: Vec<TauriCommand>,
+    pub unknown_invokes: Vec<TauriCallSite>, // invoke de comandos que no existen en el backend
+}
+
+/// Metadata de un chunk de módulo dentro del grafo de dependencias
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleDependencyMetadata {
+    pub kind: String, // Siempre "module"
+    pub module: String,
+    pub files: Vec<String>,
+    pub depends_on: Vec<String>,   // Módulos internos de los que depende
+    pub dependents: Vec<String>,   // Módulos internos que dependen de este
+    pub external: Vec<String>,     // Paquetes externos importados
+}
+
+/// Metadata del chunk de proyecto con el grafo de módulos completo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleGraphMetadata {
+    pub kind: String, // Siempre "module_graph"
+    pub module_count: usize,
+    pub edge_count: usize,
+    pub edges: Vec<(String, String)>,
+    pub external_packages: Vec<String>,
+}
+
+/// Metadata del chunk de entidad de un componente de UI (React, Vue, Svelte)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UiComponentMetadata {
+    pub kind: String,      // Siempre "ui_component"
+    pub framework: String, // react | vue | svelte
+    pub name: String,
+    pub line: usize,
+    pub renders: Vec<String>,     // Componentes usados como etiqueta en su JSX/template
+    pub rendered_by: Vec<String>, // Componentes del proyecto que lo renderizan
+    pub routes: Vec<String>,      // Rutas que lo muestran
+}
+
+/// Ruta del frontend: de la tabla del router o del sistema de archivos (Next, Nuxt, SvelteKit)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UiRoute {
+    pub path: String,
+    pub component: Option<String>,
+    pub component_file: Option<String>, // Archivo que define el componente, si se resolvió
+    pub file_path: String,              // Archivo donde se declara la ruta
+    pub line: usize,
+    pub source: String, // router | file
+}
+
+/// Metadata del chunk de proyecto con la tabla de rutas del frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UiRouteTableMetadata {
+    pub kind: String, // Siempre "ui_routes"
+    pub routes: Vec<UiRoute>,
+}
+
+/// Metadata del chunk de una clave de traducción en un archivo de locale
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct I18nKeyMetadata {
+    pub kind: String, // Siempre "i18n_key"
+    pub locale: String,
+    pub namespace: Option<String>, // `common` en locales/en/common.json
+    pub key: String,               // Ruta con puntos (`settings.title`) o msgid de gettext
+    pub line: usize,
+}
+
+/// Uso de una clave de traducción en el código
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct I18nUsage {
+    pub key: String,
+    pub file_path: String,
+    pub line: usize,
+}
+
+/// Clave definida en algunos locales pero no en todos
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct I18nMissingKey {
+    pub key: String, // `namespace:clave` si el archivo tiene namespace
+    pub missing_locales: Vec<String>,
+}
+
+/// Claves de traducción sin traducir, sin usar o usadas sin definir
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct I18nReport {
+    pub project_path: String,
+    pub locales: Vec<String>,
+    pub key_count: usize,
+    pub missing: Vec<I18nMissingKey>,
+    pub unused: Vec<String>,
+    pub undefined: Vec<I18nUsage>,
+}
+
+/// Metadata del chunk de esquema de una tabla (`CREATE TABLE` o `table!` de diesel)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SqlTableMetadata {
+    pub kind: String, // Siempre "sql_table"
+    pub table: String,
+    pub columns: Vec<String>,
+    pub line: usize,
+    pub source: String, // "sql" | "literal" | "diesel"
+}
+
+/// Metadata del chunk de una consulta SQL embebida en el código
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SqlQueryMetadata {
+    pub kind: String,      // Siempre "sql_query"
+    pub operation: String, // SELECT, INSERT, UPDATE, DELETE, CREATE, ALTER...
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+    pub line: usize,
+    pub source: String, // "sql" | "literal" | "diesel" | "knex"
+}
+
+/// Consulta que lee o escribe una tabla, para análisis de impacto sobre datos
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableQuery {
+    pub file_path: String,
+    pub line: usize,
+    pub operation: String,
+    pub access: String, // "read" | "write"
+    pub sql: String,
+    pub source: String,
+}
+
+/// Sección del manifiesto donde se declara una dependencia
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyScope {
+    /// Dependencia de runtime (`dependencies`)
+    Normal,
+    /// Solo desarrollo/tests (`devDependencies`, `dev-dependencies`)
+    Dev,
+    /// Scripts de build (`build-dependencies`)
+    Build,
+    /// Opcional o peer (`optionalDependencies`, `peerDependencies`, extras)
+    Optional,
+}
+
+/// Dependencia declarada en un manifiesto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclaredDependency {
+    pub name: String,
+    pub scope: DependencyScope,
+    #[serde(default)]
+    pub version: Option<String>, // Requisito declarado ("^1.2") o versión exacta del lockfile
+}
+
+/// Metadata del chunk de un manifiesto (package.json, Cargo.toml, pyproject.toml...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestMetadata {
+    pub manifest_type: String, // Nombre del archivo: "package.json", "Cargo.toml"...
+    pub package_name: Option<String>,
+    pub dependencies: Vec<DeclaredDependency>,
+}
+
+/// Paquete importado que no está declarado en el manifiesto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndeclaredDependency {
+    pub package: String,
+    pub files: Vec<String>, // Archivos que lo importan
+}
+
+/// Resultado de cruzar un manifiesto con los imports de sus archivos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyAuditReport {
+    pub manifest_path: String,
+    pub package_name: Option<String>,
+    pub unused: Vec<String>,                   // Declaradas pero nunca importadas
+    pub undeclared: Vec<UndeclaredDependency>, // Importadas pero no declaradas
+}
+
+/// Vulnerabilidad conocida que afecta a una dependencia del proyecto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityFinding {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub metadata_chunk_id: Option<i64>, // Chunk del manifiesto/lockfile que declara la versión
+    pub manifest_path: String,
+    pub ecosystem: String, // Ecosistema OSV: "crates.io", "npm", "PyPI"
+    pub package_name: String,
+    pub installed_version: String,
+    pub advisory_id: String, // RUSTSEC-..., GHSA-..., PYSEC-...
+    pub aliases: Vec<String>,
+    pub severity: String,
+    pub summary: String,
+    pub fixed_version: Option<String>,
+    pub affected_files: Vec<String>, // Archivos que importan el paquete
+    pub created_at: DateTime<Utc>,
+}
+
+/// Posible secreto (credencial, token, clave privada) encontrado en el código indexado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretFinding {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub chunk_id: Option<i64>, // Chunk de raw source o config escaneado
+    pub file_path: String,
+    pub line_number: usize,
+    pub rule_id: String,        // "aws_access_key_id", "private_key", "generic_secret"...
+    pub severity: String,       // "critical", "high", "medium"
+    pub masked_preview: String, // Línea con el secreto enmascarado
+    pub fingerprint: String,    // Hash estable de archivo + regla + secreto
+    pub created_at: DateTime<Utc>,
+}
+
+/// Resultado de importar la salida de un linter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintImportResult {
+    pub tool: String,
+    pub imported: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub unmatched_files: Vec<String>, // Rutas reportadas que no corresponden a archivos del proyecto
+}
+
+/// Resultado de importar la salida de un build (cargo, tsc)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildImportResult {
+    pub tool: String,
+    pub errors_imported: usize,
+    pub relationships_created: usize, // Enlaces associated_with_error creados
+    pub unmatched_files: Vec<String>,
+}
+
+/// Diagnóstico transitorio publicado por un language server (`textDocument/publishDiagnostics`).
+/// Cada publicación reemplaza los diagnósticos anteriores del archivo
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LspDiagnostic {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub file_path: String,
+    pub chunk_id: Option<i64>, // Raw source vigente del archivo
+    pub entity_name: Option<String>,
+    pub line: usize, // 1-based
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub severity: String, // "error" | "warning" | "information" | "hint"
+    pub code: Option<String>,
+    pub source: Option<String>, // Language server que lo emitió (ej: "rust-analyzer")
+    pub message: String,
+    pub document_version: Option<i64>,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Resultado de guardar una publicación de diagnósticos LSP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LspPublishResult {
+    pub file_path: Option<String>, // None si la URI no corresponde a un archivo del proyecto
+    pub uri: String,
+    pub stored: usize,
+    pub replaced: usize, // Diagnósticos anteriores del archivo que se descartaron
+}
+
+/// Diagnóstico abierto del proyecto: un error de error_logs o un diagnóstico LSP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDiagnostic {
+    pub origin: String, // "error_log" | "lsp"
+    pub id: i64,
+    pub file_path: Option<String>,
+    pub entity_name: Option<String>,
+    pub line: Option<usize>,
+    pub severity: Option<String>,
+    pub kind: String, // error_type o source del language server
+    pub message: String,
+    pub seen_at: DateTime<Utc>,
+}
+
+/// Resultado de un caso de test
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Error,
+    Skipped,
+}
+
+impl TestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TestStatus::Passed => "passed",
+            TestStatus::Failed => "failed",
+            TestStatus::Error => "error",
+            TestStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// Caso de test importado de un reporte (JUnit XML)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestResult {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub run_id: String, // Identifica la ejecución/importación
+    pub suite_name: Option<String>,
+    pub class_name: Option<String>,
+    pub test_name: String,
+    pub file_path: Option<String>,
+    pub status: TestStatus,
+    pub duration_ms: Option<f64>,
+    pub failure_message: Option<String>,
+    pub failure_details: Option<String>, // Stacktrace/salida del fallo
+    pub test_chunk_id: Option<i64>,      // Chunk de tests del archivo
+    #[serde(default)]
+    pub code_under_test: Vec<String>, // Archivos enlazados con tested_by (se calcula al consultar)
+    pub created_at: DateTime<Utc>,
+}
+
+/// Resumen de la importación de un reporte de tests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestImportResult {
+    pub run_id: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub skipped: usize,
+    pub linked: usize, // Casos enlazados a un chunk de tests
+}
+
+/// Diff de un archivo dentro de un snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotFileDiff {
+    pub file_path: String,
+    pub old_path: Option<String>, // Ruta anterior si el archivo fue renombrado
+    pub status: String,           // added, deleted, modified, renamed, ...
+    pub additions: usize,
+    pub deletions: usize,
+    pub is_binary: bool,
+    pub patch: Option<String>, // Unified diff (None si es binario o excede el límite total)
+    pub truncated: bool,
+}
+
+/// Estadísticas de un archivo dentro del resumen de un snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileChangeStat {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub status: String, // added, modified, deleted, renamed
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Resumen estructurado de cambios de un snapshot (se guarda como JSON en `diff_summary`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DiffSummary {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files: Vec<FileChangeStat>,
+}
+
+impl DiffSummary {
+    pub fn from_file_diffs(files: &[SnapshotFileDiff]) -> Self {
+        DiffSummary {
+            files_changed: files.len(),
+            insertions: files.iter().map(|f| f.additions).sum(),
+            deletions: files.iter().map(|f| f.deletions).sum(),
+            files: files
+                .iter()
+                .map(|f| FileChangeStat {
+                    path: f.file_path.clone(),
+                    old_path: f.old_path.clone(),
+                    status: f.status.clone(),
+                    additions: f.additions,
+                    deletions: f.deletions,
+                })
+                .collect(),
+        }
+    }
+
+    /// Interpreta `Snapshot.diff_summary`; None si es texto libre de un snapshot antiguo
+    pub fn parse(diff_summary: &str) -> Option<Self> {
+        serde_json::from_str(diff_summary).ok()
+    }
+
+    /// Línea estilo `git diff --stat`
+    pub fn headline(&self) -> String {
+        format!(
+            "{} files changed, {} insertions(+), {} deletions(-)",
+            self.files_changed, self.insertions, self.deletions
+        )
+    }
+}
+
+/// Diff completo de un snapshot respecto a su commit padre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub snapshot_id: i64,
+    pub files: Vec<SnapshotFileDiff>,
+    pub total_additions: usize,
+    pub total_deletions: usize,
+}
+
+/// Resultado de reconciliar los snapshots de la base con los objetos Git del proyecto
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotRepairReport {
+    pub project_path: String,
+    pub tags_restored: Vec<String>,    // Tags de snapshots registrados que faltaban en Git
+    pub tags_removed: Vec<String>,     // Tags de commits de snapshot sin registro
+    pub branches_removed: Vec<String>, // Ramas agent de commits sin registro
+    pub missing_commits: Vec<i64>,     // Snapshots cuyo commit ya no existe
+    pub orphaned_commits: Vec<String>, // Commits marcados en refs/opcode/orphaned
+}
+
+/// Snapshot como nodo del grafo de versiones (timeline)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotNode {
+    pub id: i64,
+    pub snapshot_type: String, // "master" o "agent"
+    pub version: String,       // "V2" o "V2.1"
+    pub version_major: i32,
+    pub version_minor: Option<i32>,
+    pub message: String,
+    pub user_message: Option<String>,
+    pub git_commit_hash: Option<String>,
+    pub git_tag: Option<String>,
+    pub git_branch: Option<String>,
+    pub commit_exists: bool, // El commit sigue en el repositorio
+    pub tag_exists: bool,
+    pub branch_exists: bool,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Arista padre → hijo del grafo de snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEdge {
+    pub parent_id: i64,
+    pub child_id: i64,
+    pub kind: String, // "master" (línea principal) o "branch" (rama agent)
+}
+
+/// DAG completo de snapshots master/agent de un proyecto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotGraph {
+    pub project_path: String,
+    pub nodes: Vec<SnapshotNode>, // Ordenados por versión ascendente
+    pub edges: Vec<SnapshotEdge>,
+    pub active_snapshot_id: Option<i64>,
+    pub current_branch: Option<String>, // Rama de HEAD, si el repositorio existe
+}
+
+/// Proveedor Git donde se publican las ramas agent
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitProvider {
+    GitHub,
+    GitLab,
+}
+
+impl GitProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GitProvider::GitHub => "github",
+            GitProvider::GitLab => "gitlab",
+        }
+    }
+}
+
+/// Pull/merge request abierto para un snapshot agent (se guarda en su metadata)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PullRequestInfo {
+    pub provider: GitProvider,
+    pub url: String,
+    pub number: u64,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub draft: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Issue de GitHub/GitLab normalizado para importarlo como chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueItem {
+    pub provider: GitProvider,
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    pub state: String, // open, closed
+    pub labels: Vec<String>,
+    pub url: String,
+    pub author: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// Resultado de importar issues
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueImportResult {
+    pub issues_imported: usize,
+    pub issues_unchanged: usize,
+    pub relationships_created: usize,
+}
+
+/// Archivo modificado dentro del contexto de revisión
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewFile {
+    pub file_path: String,
+    pub status: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub changed_entities: Vec<String>, // Funciones/clases que contienen las líneas modificadas
+    pub patch: Option<String>,
+}
+
+/// Chunk relacionado con el cambio y el motivo por el que se incluye
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedChunk {
+    pub reason: String,
+    pub chunk: Chunk,
+}
+
+/// Paquete de contexto para revisar un cambio ("review this change")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewContext {
+    pub project_path: String,
+    pub snapshot_id: Option<i64>,
+    pub files: Vec<ReviewFile>,
+    pub changed_chunks: Vec<Chunk>,
+    pub callers: Vec<RelatedChunk>,
+    pub tests: Vec<RelatedChunk>,
+    pub business_rules: Vec<BusinessRule>,
+    pub markdown: String, // Todo lo anterior renderizado para el prompt del agente
+}
+
+/// Posición de un chunk en el grafo de dependencias y llamadas
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GraphMetrics {
+    pub fan_in: usize,   // Chunks distintos que dependen de él o lo llaman
+    pub fan_out: usize,  // Chunks distintos de los que depende o a los que llama
+    pub centrality: f64, // Centralidad de grado normalizada (0..=1)
+}
+
+/// Métricas de grafo de un chunk, para análisis de arquitectura
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityGraphMetrics {
+    pub chunk_id: i64,
+    pub chunk_type: ChunkType,
+    pub file_path: Option<String>,
+    pub entity_name: Option<String>,
+    pub metrics: GraphMetrics,
+}
+
+/// Test afectado por un cambio, con el camino que lleva hasta él
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestedTest {
+    pub test_file: String,
+    pub chunk_id: i64,        // Chunk de tests del archivo
+    pub tested_file: String,  // Archivo de código que prueba (el propio test si cambió)
+    pub changed_file: String, // Archivo modificado del que parte el camino
+    pub distance: usize,      // 0 = prueba un archivo modificado; n = a n saltos de `calls`
+    /// Funciones de test cuyas assertions llaman a código de `tested_file`
+    /// (vacío = ejecutar el archivo completo)
+    pub test_functions: Vec<String>,
+}
+
+/// Conjunto mínimo de tests a ejecutar para un cambio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestSuggestion {
+    pub project_path: String,
+    pub changed_files: Vec<String>,
+    pub tests: Vec<SuggestedTest>,
+}
+
+/// Chunk puntuado según su relevancia para una tarea
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoredChunk {
+    pub chunk: Chunk,
+    pub score: f64,
+    pub lexical_score: f64,   // BM25 normalizado (0-1)
+    pub proximity_score: f64, // Cercanía en el grafo de imports a archivos modificados recientemente (0-1)
+    pub matched_terms: Vec<String>,
+}
+
+/// Región de código fuente (líneas 1-based, inclusivas)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceRegion {
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: Option<usize>, // None = hasta el final del archivo
+}
+
+/// Fracción del presupuesto de contexto reservada para un tipo de chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkTypeWeight {
+    pub chunk_type: ChunkType,
+    pub weight: f64, // Relativo: se normaliza contra la suma de pesos
+}
+
+/// Chunk incluido en un paquete de contexto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackedChunk {
+    pub chunk: Chunk,
+    pub score: f64,
+    pub tokens: usize,
+    pub region: Option<SourceRegion>, // None si el chunk no representa código fuente
+}
+
+/// Paquete de contexto para una tarea, dentro de un presupuesto de tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextPack {
+    pub project_path: String,
+    pub task_text: String,
+    pub token_budget: usize,
+    pub total_tokens: usize,
+    #[serde(default)]
+    pub pinned_rules: Vec<BusinessRule>, // Reglas fijadas al inicio; sus tokens se descuentan primero
+    pub chunks: Vec<PackedChunk>,
+    pub budget_weights: Option<Vec<ChunkTypeWeight>>, // Reparto por tipo aplicado, si el proyecto lo configuró
+    pub overlaps_skipped: usize,    // Chunks descartados por repetir una región ya incluida
+    pub over_budget_skipped: usize, // Chunks que no cabían en el presupuesto restante
+    pub delivery_id: Option<i64>,   // Registro en context_deliveries
+    pub markdown: String,
+}
+
+/// Registro de un paquete de contexto entregado a una sesión de agente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextDelivery {
+    pub id: Option<i64>,
+    pub project_path: String,
+    pub session_id: Option<String>,
+    pub snapshot_id: Option<i64>, // Snapshot activo al armar el paquete
+    pub task_text: String,
+    pub chunk_ids: Vec<i64>,
+    pub chunk_tokens: Vec<usize>, // Tokens estimados de cada chunk (mismo orden que chunk_ids)
+    pub total_tokens: usize,
+    pub token_budget: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Estado de una sesión de agente
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentSessionStatus {
+    Active,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl AgentSessionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgentSessionStatus::Active => "active",
+            AgentSessionStatus::Completed => "completed",
+            AgentSessionStatus::Failed => "failed",
+            AgentSessionStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "active" => Some(AgentSessionStatus::Active),
+            "completed" => Some(AgentSessionStatus::Completed),
+            "failed" => Some(AgentSessionStatus::Failed),
+            "cancelled" => Some(AgentSessionStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Sesión de agente: una ejecución que parte de un snapshot master
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSession {
+    pub id: String,
+    pub project_path: String,
+    pub master_snapshot_id: Option<i64>,
+    pub task: Option<String>, // Mensaje/tarea que recibió el agente
+    pub status: AgentSessionStatus,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+/// Todo lo que ocurrió en una sesión de agente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSessionReport {
+    pub session: AgentSession,
+    pub master_snapshot: Option<Snapshot>,
+    pub agent_snapshots: Vec<Snapshot>, // Snapshots agent del master creados durante la sesión
+    pub errors: Vec<ErrorLog>,          // Errores vistos durante la sesión en esos snapshots
+    pub deliveries: Vec<ContextDelivery>,
+    pub delivered_chunk_ids: Vec<i64>, // Chunks distintos entregados al agente
+    pub delivered_tokens: usize,
+}
+
+/// Cantidad de chunks de un tipo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkTypeCount {
+    pub chunk_type: String,
+    pub count: usize,
+}
+
+/// Chunks creados en un día (YYYY-MM-DD, UTC)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunksPerDay {
+    pub day: String,
+    pub created: usize,
+    pub cumulative: usize, // Total de chunks del proyecto creados hasta ese día
+    pub by_type: Vec<ChunkTypeCount>,
+}
+
+/// Snapshots creados en un día (YYYY-MM-DD, UTC)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotsPerDay {
+    pub day: String,
+    pub master: usize,
+    pub agent: usize,
+}
+
+/// Ritmo de snapshots de un proyecto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotCadence {
+    pub per_day: Vec<SnapshotsPerDay>,
+    pub avg_hours_between_masters: Option<f64>, // None con menos de dos snapshots master
+    pub avg_agents_per_master: Option<f64>,
+}
+
+/// Carpeta raíz de un proyecto lógico (ej: repo frontend + repo backend)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectRoot {
+    pub path: String,  // Ruta absoluta; es el project_path con el que se indexa la raíz
+    pub label: String, // Prefijo de sus archivos en el namespace lógico (ej: "frontend")
+    #[serde(default)]
+    pub chunk_types: Option<Vec<ChunkType>>, // None = los de las opciones de indexación
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>, // Se suman a los de las opciones
+    #[serde(default = "default_true")]
+    pub snapshots_enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Proyecto lógico formado por varias raíces con búsqueda y snapshots unificados
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogicalProject {
+    pub name: String,
+    pub project_path: String, // Namespace lógico: `logical://{name}`
+    pub roots: Vec<ProjectRoot>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Snapshot creado en una raíz al hacer un snapshot del proyecto lógico
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogicalSnapshot {
+    pub root_label: String,
+    pub root_path: String,
+    pub snapshot_id: i64,
+}
+
+/// Repositorio remoto clonado en la caché e indexado en modo solo lectura (sin snapshots)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteRepository {
+    pub url: String,
+    pub project_path: String, // Clon local en la caché; es el project_path de sus chunks
+    pub depth: Option<i32>,   // None = historial completo
+    pub head_commit: Option<String>,
+    pub cloned_at: DateTime<Utc>,
+    pub last_indexed_at: Option<DateTime<Utc>>,
+}
+
+/// Proyecto con índice de solo lectura: el almacenamiento rechaza sus escrituras y no
+/// admite reindexación ni snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadOnlyProject {
+    pub project_path: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Archivo que tree-sitter no pudo parsear. Tras `max_parse_attempts` fallas con el
+/// mismo contenido se omite del AST hasta que cambie o se limpie la entrada
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParseFailure {
+    pub project_path: String,
+    pub file_path: String,
+    pub content_hash: String, // Hash del contenido del archivo que falló
+    pub reason: String,
+    pub attempts: usize,
+    pub first_failed_at: DateTime<Utc>,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+/// Huella de un archivo al indexarlo. `size` y `modified_at` (ms desde epoch) permiten
+/// descartar cambios sin re-hashear el contenido
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileFingerprint {
+    pub project_path: String,
+    pub file_path: String,
+    pub content_hash: String,
+    pub size: u64,
+    pub modified_at: i64,
+    pub indexed_at: DateTime<Utc>,
+}
+
+/// Resultado de comparar las huellas guardadas con el disco
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StalenessReport {
+    pub project_path: String,
+    pub files_checked: usize,
+    pub stale_files: Vec<String>, // Cambiados o eliminados desde que se indexaron
+    pub stale_chunks: usize,      // Chunks marcados como desactualizados
+}
+
+/// Tamaño del almacén de contenido deduplicado (`content_blobs`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentStoreStats {
+    pub blob_count: usize,
+    pub blob_bytes: u64,       // Bytes guardados en blobs (una copia por contenido)
+    pub referenced_bytes: u64, // Bytes que ocuparían los chunks con una copia cada uno
+    pub inline_chunks: usize,  // Chunks anteriores a la deduplicación, sin blob
+    pub inline_bytes: u64,
+    pub saved_bytes: u64,
+}
+
+/// Resultado de mover el contenido en línea a blobs y limpiar los huérfanos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentCompactionReport {
+    pub chunks_migrated: usize,
+    pub blobs_removed: usize,
+    pub stats: ContentStoreStats,
+}
+
+/// Versión de un chunk: los chunks con el mismo proyecto, tipo, archivo y entidad,
+/// numerados desde 1 en orden de creación
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChunkVersion {
+    pub version: usize,
+    pub chunk_id: i64,
+    pub content_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted: bool, // En la papelera
+}
+
+/// Rango de líneas de un archivo servido desde su chunk raw source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceRange {
+    pub project_path: String,
+    pub file_path: String,
+    pub chunk_id: i64,
+    pub snapshot_id: Option<i64>, // None = versión vigente
+    pub start_line: usize,        // 1-based, inclusiva
+    pub end_line: usize,          // Inclusiva, recortada al final del archivo
+    pub total_lines: usize,
+    pub content: String,
+    pub content_hash: String, // Hash del archivo completo
+    pub indexed_at: DateTime<Utc>,
+}
+
+/// Diff unificado del contenido entre dos versiones de un chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkVersionDiff {
+    pub chunk_type: ChunkType,
+    pub file_path: Option<String>,
+    pub entity_name: Option<String>,
+    pub from: ChunkVersion,
+    pub to: ChunkVersion,
+    pub additions: usize,
+    pub deletions: usize,
+    pub patch: String, // Vacío si el contenido es idéntico
+    pub truncated: bool,
+}
+
+/// Algoritmo con el que se hashea el contenido de los archivos de un proyecto.
+/// Los hashes BLAKE3 llevan el prefijo `blake3:`, así cada hash dice con qué
+/// algoritmo se calculó aunque la base mezcle proyectos con distintos algoritmos
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    /// Bastante más rápido en repositorios grandes
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "sha256" => Some(HashAlgorithm::Sha256),
+            "blake3" => Some(HashAlgorithm::Blake3),
+            _ => None,
+        }
+    }
+
+    /// Algoritmo con el que se calculó un hash guardado
+    pub fn of_hash(hash: &str) -> Self {
+        if hash.starts_with("blake3:") {
+            HashAlgorithm::Blake3
+        } else {
+            HashAlgorithm::Sha256
+        }
+    }
+}
+
+/// Formato de exportación del grafo de relaciones
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphFormat {
+    /// Graphviz
+    Dot,
+    /// Gephi, yEd, Cytoscape
+    Graphml,
+}
+
+impl GraphFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Graphml => "graphml",
+        }
+    }
+}
+
+/// Configuración de hashing de un proyecto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectHashing {
+    pub project_path: String,
+    pub algorithm: HashAlgorithm,
+    /// Identificador anónimo y estable del proyecto: hash de la ruta con una sal
+    /// aleatoria propia de esta base, así no se puede recuperar la ruta probando
+    pub project_id: String,
+}
+
+/// Operación destructiva sobre el índice. Sus chunks quedan en la papelera, así que
+/// se puede deshacer mientras no se purguen
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexOperationKind {
+    /// Borrado de todos los chunks de un proyecto
+    DeleteProjectChunks,
+    /// Chunks de archivos que ya no existen, en la indexación completa
+    Prune,
+    /// Reindexación desde cero: quedan borrados los chunks que no se regeneran
+    Reset,
+}
+
+impl IndexOperationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexOperationKind::DeleteProjectChunks => "delete_project_chunks",
+            IndexOperationKind::Prune => "prune",
+            IndexOperationKind::Reset => "reset",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "delete_project_chunks" => Some(IndexOperationKind::DeleteProjectChunks),
+            "prune" => Some(IndexOperationKind::Prune),
+            "reset" => Some(IndexOperationKind::Reset),
+            _ => None,
+        }
+    }
+}
+
+/// Registro de una operación destructiva sobre el índice de un proyecto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexOperation {
+    pub id: i64,
+    pub project_path: String,
+    pub kind: IndexOperationKind,
+    pub chunks_affected: usize, // Chunks enviados a la papelera
+    pub created_at: DateTime<Utc>,
+    pub undone_at: Option<DateTime<Utc>>,
+}
+
+/// Resultado de deshacer una operación
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoResult {
+    pub operation: IndexOperation,
+    /// Chunks restaurados; menos que `chunks_affected` si alguno ya se purgó o se regeneró
+    pub chunks_restored: usize,
+}
+
+/// Resultado de clonar e indexar un repositorio remoto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteIndexResult {
+    pub repository: RemoteRepository,
+    pub result: ChunkingResult,
+}
+
+/// Errores abiertos y resueltos en un día (YYYY-MM-DD, UTC)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorRatePerDay {
+    pub day: String,
+    pub opened: usize,
+    pub resolved: usize,
+    pub open_at_end: usize, // Errores abiertos al terminar el día
+}
+
+/// Frescura del índice para una extensión de archivo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTypeFreshness {
+    pub extension: String, // Vacía para archivos sin extensión
+    pub files: usize,
+    pub oldest_indexed_at: DateTime<Utc>,
+    pub newest_indexed_at: DateTime<Utc>,
+    pub avg_age_hours: f64,
+}
+
+/// Resultado de escribir/importar notas Git de opcode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitNotesResult {
+    pub notes_written: usize,
+    pub notes_imported: usize,
+    pub snapshots_restored: usize,
+}
+
+/// Incremento de versión semántica sugerido
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionBump {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+/// Cambio en un símbolo de la API pública
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiChange {
+    pub file_path: String,
+    pub name: String,
+    pub kind: String, // function, struct, class, method, type...
+    pub old_signature: Option<String>,
+    pub new_signature: Option<String>,
+}
+
+/// Sugerencia de versión entre dos snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionBumpSuggestion {
+    pub bump: VersionBump,
+    pub breaking_changes: Vec<ApiChange>, // Símbolos eliminados o con firma distinta
+    pub added_exports: Vec<ApiChange>,
+    pub internal_files: Vec<String>, // Archivos modificados sin cambios de API
+    pub reasons: Vec<String>,
+}
+
+/// Archivo con más diagnósticos de lint sin resolver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintOffender {
+    pub file_path: String,
+    pub errors: usize,
+    pub warnings: usize,
+    pub top_rules: Vec<String>, // Reglas más frecuentes en el archivo
+}
+
+/// Archivo con muchos commits en el historial indexado y sin tests enlazados
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UncoveredHotspot {
+    pub file_path: String,
+    pub commits: usize,
+}
+
+/// Parte del puntaje de salud: hallazgos de un tipo y los puntos que restan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthComponent {
+    pub name: String, // open_errors, uncovered_hotspots, stale_files...
+    pub count: usize,
+    pub penalty: f64,
+    pub max_penalty: f64,
+}
+
+/// Estado del proyecto para el dashboard y los agentes. `score` va de 0 a 100
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectHealthReport {
+    pub project_path: String,
+    pub score: u32,
+    pub components: Vec<HealthComponent>,
+    pub open_errors: Vec<ErrorLog>,
+    pub uncovered_hotspots: Vec<UncoveredHotspot>,
+    pub stale_files: Vec<String>, // Cambiados o eliminados en disco desde que se indexaron
+    pub unvalidated_rules: Vec<BusinessRule>,
+    pub failing_tests: Vec<TestResult>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Una señal del puntaje de riesgo de un archivo y los puntos que aporta
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskSignal {
+    pub name: String, // errors, churn, complexity, fan_in
+    pub value: f64,
+    pub points: f64,
+    pub max_points: f64,
+}
+
+/// Riesgo de modificar un archivo. `score` va de 0 a 100
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRisk {
+    pub file_path: String,
+    pub score: u32,
+    pub level: String, // "low" | "medium" | "high"
+    pub signals: Vec<RiskSignal>,
+    pub open_errors: usize,
+    pub tested: bool,  // Tiene alguna relación tested_by
+    pub indexed: bool, // false si el archivo no tiene raw source indexado (archivo nuevo)
+    pub requires_extra_tests: bool,
+    pub requires_review: bool,
+}
+
+/// Métricas de complejidad de un archivo (de su chunk AST más reciente)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileComplexity {
+    pub file_path: String,
+    pub language: String,
+    pub cyclomatic_complexity: usize,
+    pub function_count: usize,
+    pub longest_function_lines: usize,
+    pub node_count: usize,
+}
+
+/// Archivos de un proyecto de mayor a menor complejidad
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplexityReport {
+    pub project_path: String,
+    pub total_files: usize, // Archivos con AST, antes de aplicar el límite
+    pub files: Vec<FileComplexity>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Índice de un proyecto exportado para compartirlo con otro usuario. Las relaciones
+/// se identifican por el hash de sus chunks porque los ids no viajan entre bases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexExport {
+    pub format_version: u32,
+    pub source_project_path: String,
+    pub exported_at: DateTime<Utc>,
+    pub chunks: Vec<Chunk>,
+    pub business_rules: Vec<BusinessRule>,
+    pub relationships: Vec<ExportedRelationship>,
+}
+
+/// Relación exportada entre dos chunks, por hash de contenido
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedRelationship {
+    pub from_hash: String,
+    pub to_hash: String,
+    pub relationship_type: RelationshipType,
+    pub metadata: Option<String>,
+}
+
+/// Resultado de escribir un índice exportado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexExportSummary {
+    pub path: String,
+    pub chunks: usize,
+    pub business_rules: usize,
+    pub relationships: usize,
+}
+
+/// Qué divergía entre el índice local y el importado
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeConflictKind {
+    /// La misma regla (entidad y archivo) con otra descripción, corrección o validación
+    BusinessRule,
+    /// El mismo chunk (hash) con otra metadata (notas o anotaciones del usuario)
+    Annotation,
+}
+
+/// Versión que quedó tras un conflicto
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeResolution {
+    KeptLocal,
+    TookImported,
+}
+
+/// Conflicto resuelto al fusionar un índice importado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexMergeConflict {
+    pub kind: MergeConflictKind,
+    pub key: String, // `archivo#entidad` de la regla o hash del chunk
+    pub local: String,
+    pub imported: String,
+    pub local_updated_at: DateTime<Utc>,
+    pub imported_updated_at: DateTime<Utc>,
+    pub resolution: MergeResolution,
+}
+
+/// Resultado de fusionar el índice exportado de otro usuario en un proyecto
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexMergeReport {
+    pub project_path: String,
+    pub source_project_path: String,
+    pub chunks_imported: usize,
+    pub chunks_updated: usize,
+    pub chunks_unchanged: usize,
+    pub rules_imported: usize,
+    pub rules_updated: usize,
+    pub rules_unchanged: usize,
+    pub relationships_imported: usize,
+    pub conflicts: Vec<IndexMergeConflict>,
+}
+
+/// Riesgo de los archivos que un agente va a modificar, de mayor a menor puntaje
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskAssessment {
+    pub project_path: String,
+    pub files: Vec<FileRisk>,
+    pub max_score: u32,
+    pub requires_extra_tests: bool,
+    pub requires_review: bool,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Metadata del chunk de commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitMetadata {
+    pub commit_hash: String,
+    pub author: String,
+    pub author_email: String,
+    pub commit_date: DateTime<Utc>,
+    pub files_modified: Vec<String>,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Tipo conventional commit (feat, fix, refactor...) o inferido del mensaje libre
+    #[serde(default)]
+    pub commit_type: Option<String>,
+    #[serde(default)]
+    pub scope: Option<String>,
+    #[serde(default)]
+    pub breaking_change: bool,
+    /// true si el mensaje sigue el formato `tipo(scope)!: descripción`
+    #[serde(default)]
+    pub conventional: bool,
+    /// Ramas locales que contienen el commit
+    #[serde(default)]
+    pub branches: Vec<String>,
+}
+
+/// Resultado de procesamiento de chunking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkingResult {
+    pub project_path: String,
+    pub chunks_created: usize,
+    pub chunks_updated: usize,
+    /// Chunks enviados a la papelera porque su archivo ya no existe
+    #[serde(default)]
+    pub chunks_deleted: usize,
+    pub relationships_created: usize,
+    #[serde(default)]
+    pub secrets_found: usize,
+    /// El historial de commits se cortó en el límite de un clon superficial
+    #[serde(default)]
+    pub shallow_history: bool,
+    pub errors: Vec<IndexingError>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Cambios que se habrían aplicado (solo con `dry_run`; nada se escribió)
+    #[serde(default)]
+    pub dry_run: Option<DryRunReport>,
+    /// Conteos por tipo de chunk (clave: `ChunkType::as_str`)
+    #[serde(default)]
+    pub by_type: BTreeMap<String, ChunkTypeStats>,
+    /// Tiempo de cada fase de la indexación, en orden de ejecución
+    #[serde(default)]
+    pub phase_durations: Vec<PhaseDuration>,
+    /// `Aborted` si se agotó el `ErrorBudget`: el resultado es parcial
+    #[serde(default)]
+    pub status: IndexingStatus,
+    /// Motivo del aborto (solo con `IndexingStatus::Aborted`)
+    #[serde(default)]
+    pub abort_reason: Option<String>,
+    /// Id de la ejecución registrada en `indexing_runs` (None en dry run y en el
+    /// resultado combinado de un proyecto lógico, donde cada raíz registra la suya)
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// Embeddings recalculados tras indexar (solo si el proyecto ya tenía embeddings
+    /// y el orquestador tiene un proveedor configurado)
+    #[serde(default)]
+    pub embeddings: Option<EmbeddingRefreshReport>,
+}
+
+/// Embedding guardado de un chunk, con el modelo y la versión que lo calcularon
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChunkEmbedding {
+    pub chunk_id: i64,
+    /// Hash del contenido embebido: si el chunk cambia de hash hay que recalcularlo
+    pub content_hash: String,
+    pub model: String,
+    pub model_version: String,
+    pub vector: Vec<f32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Resultado de refrescar los embeddings de un proyecto
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct EmbeddingRefreshReport {
+    pub project_path: String,
+    pub model: String,
+    pub model_version: String,
+    /// Chunks vigentes considerados (la última versión de cada uno)
+    pub chunks_checked: usize,
+    /// Recalculados: sin embedding, con otro hash de contenido o de otro modelo/versión
+    pub embedded: usize,
+    pub unchanged: usize,
+    /// Embeddings de chunks borrados o reemplazados por una versión más nueva
+    pub removed: usize,
+    /// Llamadas al proveedor
+    pub batches: usize,
+}
+
+/// Backend de embeddings elegido para un proyecto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum EmbeddingBackend {
+    /// API compatible con OpenAI (`POST {endpoint}/embeddings`). La clave se lee de la
+    /// variable de entorno `api_key_env`, nunca se guarda en la base
+    Http {
+        endpoint: String,
+        model: String,
+        #[serde(default)]
+        api_key_env: Option<String>,
+    },
+    /// Modelo ONNX local, sin red: `model.onnx` y los archivos del tokenizer
+    /// (`tokenizer.json`, `config.json`, `special_tokens_map.json`,
+    /// `tokenizer_config.json`) en `model_dir`. Requiere la feature `local-embeddings`
+    Local { model_dir: String },
+}
+
+/// Configuración de embeddings de un proyecto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmbeddingSettings {
+    pub project_path: String,
+    /// `None` si el proyecto no tiene backend configurado
+    pub backend: Option<EmbeddingBackend>,
+    /// El binario se compiló con el backend local
+    pub local_available: bool,
+}
+
+/// Chunk encontrado por búsqueda semántica
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SemanticMatch {
+    pub chunk_id: i64,
+    pub chunk_type: ChunkType,
+    pub file_path: Option<String>,
+    pub entity_name: Option<String>,
+    /// Similitud coseno con la consulta (-1 a 1)
+    pub score: f32,
+}
+
+/// Estado del índice de un proyecto en un instante: chunks vigentes por tipo y la
+/// última indexación registrada. Se guarda en la metadata de cada snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexState {
+    /// Conteos por tipo de chunk (clave: `ChunkType::as_str`)
+    pub chunk_counts: BTreeMap<String, usize>,
+    pub total_chunks: usize,
+    pub last_run_id: Option<String>,
+    pub last_run_kind: Option<String>, // "full", "incremental" o "partial"
+    pub last_run_status: Option<IndexingStatus>,
+    pub last_indexed_at: Option<DateTime<Utc>>,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Error de una indexación; `file_path` es None para las fallas de proyecto
+/// (commits, grafo de dependencias, escaneo de secretos)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexingError {
+    pub file_path: Option<String>,
+    pub chunk_type: Option<ChunkType>,
+    pub kind: IndexingErrorKind,
+    pub message: String,
+}
+
+impl IndexingError {
+    /// Traduce el error de una raíz al namespace de un proyecto lógico
+    pub fn in_root(mut self, label: &str) -> Self {
+        match &self.file_path {
+            Some(path) => self.file_path = Some(format!("{}/{}", label, path)),
+            None => self.message = format!("[{}] {}", label, self.message),
+        }
+        self
+    }
+}
+
+/// Origen de un error de indexación
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexingErrorKind {
+    /// `opcode.toml` inválido
+    Config,
+    /// Lectura del archivo
+    Io,
+    /// SQLite
+    Storage,
+    /// libgit2
+    Git,
+    Other,
+}
+
+/// Estado final de una indexación
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexingStatus {
+    #[default]
+    Completed,
+    Aborted,
+}
+
+/// Resultado de una indexación para un tipo de chunk. `skipped` cuenta archivos
+/// que el generador descartó (lenguaje no soportado, parse fallido) y `errored`
+/// fallas que se reportaron en `errors`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChunkTypeStats {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub errored: usize,
+}
+
+impl ChunkTypeStats {
+    pub fn merge(&mut self, other: &ChunkTypeStats) {
+        self.created += other.created;
+        self.updated += other.updated;
+        self.skipped += other.skipped;
+        self.errored += other.errored;
+    }
+}
+
+/// Duración acumulada de una fase de la indexación
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhaseDuration {
+    pub phase: String,
+    pub duration_ms: u64,
+}
+
+/// Perfil anónimo del índice de un proyecto para adjuntar a reportes de rendimiento:
+/// solo conteos, tamaños y tiempos agregados, sin rutas, nombres ni contenido
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexProfile {
+    pub format_version: u32,
+    pub generated_at: DateTime<Utc>,
+    pub crate_version: String,
+    pub os: String,
+    /// Identificador anónimo del proyecto (ver [`ProjectHashing`]): permite agrupar
+    /// perfiles del mismo proyecto sin revelar su ruta. `None` si nunca se indexó
+    #[serde(default)]
+    pub project_id: Option<String>,
+    pub hash_algorithm: HashAlgorithm,
+    pub files: usize,
+    /// Archivos indexados por extensión ("(none)" sin extensión, "other" si es rara)
+    pub files_by_extension: BTreeMap<String, usize>,
+    /// Chunks vigentes por tipo (clave: `ChunkType::as_str`)
+    pub chunks_by_type: BTreeMap<String, ChunkTypeProfile>,
+    pub deleted_chunks: usize,
+    pub stale_chunks: usize,
+    /// Archivos con AST por lenguaje
+    pub ast_languages: BTreeMap<String, usize>,
+    /// Tamaño en bytes del raw source de los archivos
+    pub file_sizes: SizeDistribution,
+    pub relationships_by_type: BTreeMap<String, usize>,
+    pub snapshots: usize,
+    pub business_rules: usize,
+    pub open_errors: usize,
+    pub parse_failures: usize,
+    /// Tamaño de la base completa (todos los proyectos)
+    pub database_bytes: u64,
+    /// Últimas indexaciones, la más reciente primero
+    pub recent_runs: Vec<IndexRunProfile>,
+}
+
+/// Cantidad y tamaño del contenido de los chunks de un tipo
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChunkTypeProfile {
+    pub count: usize,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+}
+
+/// Percentiles de una distribución de tamaños en bytes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SizeDistribution {
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+/// Ejecución del benchmark del índice de un proyecto (`bench::bench_index`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    pub id: Option<i64>,
+    pub project_path: String,
+    /// Versión del crate que corrió el benchmark, para comparar entre releases
+    pub crate_version: String,
+    pub iterations: usize,
+    pub total_chunks: usize,
+    /// None si el índice es de solo lectura (no se puede simular la indexación)
+    pub indexing: Option<IndexingBenchmark>,
+    pub queries: Vec<QueryBenchmark>,
+    /// Métricas que empeoraron respecto a la ejecución anterior del proyecto
+    pub regressions: Vec<BenchmarkRegression>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Indexación completa en dry run (se revierte) medida por el benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexingBenchmark {
+    pub duration_ms: u64,
+    pub chunks: usize,
+    pub chunks_per_second: f64,
+}
+
+/// Latencia de un patrón de consulta, en microsegundos
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueryBenchmark {
+    pub pattern: String,
+    pub iterations: usize,
+    pub rows: usize, // Filas que devolvió la consulta
+    pub mean_us: u64,
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub max_us: u64,
+}
+
+/// Métrica del benchmark que empeoró. `ratio` es cuántas veces más lenta es la
+/// ejecución actual (latencia p50 de una consulta o chunks/s de la indexación)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BenchmarkRegression {
+    pub metric: String, // Patrón de consulta o "indexing"
+    pub previous_version: String,
+    pub previous: f64,
+    pub current: f64,
+    pub ratio: f64,
+}
+
+/// Una indexación registrada, sin su id ni el proyecto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexRunProfile {
+    pub kind: String, // "full", "incremental" o "partial"
+    pub status: IndexingStatus,
+    pub chunks_created: usize,
+    pub chunks_updated: usize,
+    pub chunks_deleted: usize,
+    pub errors: usize,
+    pub duration_ms: i64,
+    /// Vacío en las ejecuciones registradas antes de guardar los tiempos por fase
+    pub phase_durations: Vec<PhaseDuration>,
+}
+
+/// Chunks que una indexación crearía, actualizaría o eliminaría
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DryRunReport {
+    pub chunks_created: usize,
+    pub chunks_updated: usize,
+    pub chunks_deleted: usize,
+    pub by_type: Vec<DryRunChanges>,
+    pub files: Vec<DryRunFileChanges>,
+}
+
+impl DryRunReport {
+    /// Suma el reporte de una raíz de un proyecto lógico (archivos como `label/ruta`)
+    pub fn merge_root(&mut self, label: &str, report: DryRunReport) {
+        self.chunks_created += report.chunks_created;
+        self.chunks_updated += report.chunks_updated;
+        self.chunks_deleted += report.chunks_deleted;
+        for changes in report.by_type {
+            match self.by_type.iter_mut().find(|c| c.chunk_type == changes.chunk_type) {
+                Some(existing) => {
+                    existing.created += changes.created;
+                    existing.updated += changes.updated;
+                    existing.deleted += changes.deleted;
+                }
+                None => self.by_type.push(changes),
+            }
+        }
+        self.files.extend(report.files.into_iter().map(|mut file| {
+            file.file_path = file.file_path.map(|path| format!("{}/{}", label, path));
+            file
+        }));
+    }
+}
+
+/// Cambios de un tipo de chunk en un dry run
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DryRunChanges {
+    pub chunk_type: String,
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+}
+
+/// Cambios de un archivo (y tipo de chunk) en un dry run. `file_path` es None
+/// para los chunks de proyecto (commits, grafo de módulos)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DryRunFileChanges {
+    pub file_path: Option<String>,
+    pub chunk_type: String,
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+}
+
+/// Opciones de configuración para el chunking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkingOptions {
+    /// Tipos de chunks a generar
+    pub chunk_types: Vec<ChunkType>,
+    /// Máxima profundidad del AST
+    pub max_ast_depth: Option<usize>,
+    /// Incluir análisis de callgraph dinámico (requiere runtime tracking)
+    pub include_dynamic_callgraph: bool,
+    /// Número máximo de commits a analizar
+    pub max_commits: Option<usize>,
+    /// Recorrer todas las ramas locales (incluidas las agent) en lugar de solo HEAD
+    #[serde(default)]
+    pub all_branches: bool,
+    /// Escribir resúmenes de commits y snapshots en `refs/notes/opcode`
+    #[serde(default)]
+    pub write_git_notes: bool,
+    /// Patrones de archivos a ignorar
+    pub ignore_patterns: Vec<String>,
+    /// Nivel de detalle de los chunks AST
+    #[serde(default)]
+    pub ast_detail: AstDetail,
+    /// Tokens máximos (estimados) por chunk AST: si se pasan, el chunk se serializa
+    /// con el siguiente nivel de detalle más compacto hasta entrar
+    #[serde(default)]
+    pub ast_max_tokens: Option<usize>,
+    /// Lenguaje forzado por extensión (ej: "mjs" -> "javascript")
+    #[serde(default)]
+    pub language_overrides: HashMap<String, String>,
+    /// Reglas adicionales y exclusiones del escaneo de secretos
+    #[serde(default)]
+    pub secret_redaction: SecretRedactionRules,
+    /// Qué hacer tras crear snapshots
+    #[serde(default)]
+    pub snapshot_policy: SnapshotPolicy,
+    /// Reportar los cambios sin escribir en la base ni en Git
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Cuándo abortar la indexación en lugar de seguir acumulando errores
+    #[serde(default)]
+    pub error_budget: ErrorBudget,
+    /// Intentos de parseo fallidos tras los que un archivo se omite del AST
+    #[serde(default = "default_max_parse_attempts")]
+    pub max_parse_attempts: usize,
+    /// Días que un chunk borrado queda en la papelera antes de eliminarse
+    #[serde(default = "default_tombstone_grace_days")]
+    pub tombstone_grace_days: u32,
+    /// Extensiones (sin punto, en minúsculas) que se indexan como código fuente
+    #[serde(default = "default_code_extensions")]
+    pub code_extensions: Vec<String>,
+}
+
+fn default_max_parse_attempts() -> usize {
+    3
+}
+
+fn default_tombstone_grace_days() -> u32 {
+    30
+}
+
+/// Extensiones de código fuente que se indexan por defecto
+pub fn default_code_extensions() -> Vec<String> {
+    [
+        "rs", "js", "jsx", "ts", "tsx", "py", "java", "cpp", "c", "h", "hpp", "cs", "go", "rb",
+        "php", "swift", "kt", "scala", "r", "m", "mm", "vue", "svelte", "dart", "lua", "sh",
+        "bash", "zsh", "fish", "sql", "graphql", "proto", "toml", "yaml", "yml", "json", "xml",
+        "html", "css", "scss", "sass", "less",
+    ]
+    .iter()
+    .map(|ext| ext.to_string())
+    .collect()
+}
+
+impl Default for ChunkingOptions {
+    fn default() -> Self {
+        Self {
+            chunk_types: vec![
+                ChunkType::RawSource,
+                ChunkType::Ast,
+                ChunkType::Callgraph,
+                ChunkType::Tests,
+                ChunkType::CommitHistory,
+                ChunkType::StateConfig,
+                ChunkType::ProjectMetadata,
+            ],
+            max_ast_depth: None,
+            include_dynamic_callgraph: false,
+            max_commits: Some(100),
+            all_branches: false,
+            write_git_notes: false,
+            ignore_patterns: vec![
+                "node_modules/**".to_string(),
+                "target/**".to_string(),
+                "dist/**".to_string(),
+                "build/**".to_string(),
+                ".git/**".to_string(),
+            ],
+            ast_detail: AstDetail::default(),
+            ast_max_tokens: None,
+            language_overrides: HashMap::new(),
+            secret_redaction: SecretRedactionRules::default(),
+            snapshot_policy: SnapshotPolicy::default(),
+            dry_run: false,
+            error_budget: ErrorBudget::default(),
+            max_parse_attempts: default_max_parse_attempts(),
+            tombstone_grace_days: default_tombstone_grace_days(),
+            code_extensions: default_code_extensions(),
+        }
+    }
+}
+
+/// Regla de detección de secretos definida por el proyecto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomSecretRule {
+    pub id: String,
+    pub pattern: String, // Regex; si tiene grupos de captura, el primero es el secreto
+    #[serde(default = "default_secret_severity")]
+    pub severity: String,
+}
+
+fn default_secret_severity() -> String {
+    "high".to_string()
+}
+
+/// Ajustes del escaneo de secretos
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SecretRedactionRules {
+    pub rules: Vec<CustomSecretRule>,
+    pub disabled_rules: Vec<String>, // IDs de reglas integradas a omitir (ej: "jwt")
+    pub allow_paths: Vec<String>,    // Globs de archivos que no se escanean (fixtures, ejemplos)
+}
+
+/// Política aplicada al crear snapshots
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SnapshotPolicy {
+    /// Reindexar los archivos modificados tras cada snapshot
+    pub auto_reindex: bool,
+    /// Escribir el resumen del snapshot master en `refs/notes/opcode`
+    pub write_git_notes: bool,
+    /// Proponer reglas de negocio para las funciones y clases públicas nuevas de cada
+    /// snapshot agent
+    pub propose_rules: bool,
+}
+
+impl Default for SnapshotPolicy {
+    fn default() -> Self {
+        Self {
+            auto_reindex: true,
+            write_git_notes: false,
+            propose_rules: true,
+        }
+    }
+}
+
+/// Límite de errores de una indexación
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ErrorBudget {
+    /// Abortar al llegar a este número de errores por archivo (None = sin límite)
+    pub max_file_errors: Option<usize>,
+    /// Abortar ante el primer error de la base de datos
+    pub fail_on_storage_error: bool,
+}
+
+impl Default for ErrorBudget {
+    fn default() -> Self {
+        Self {
+            max_file_errors: Some(200),
+            fail_on_storage_error: true,
+        }
+    }
+}
+
+/// Página de chunks recorridos por cursor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkPage {
+    pub chunks: Vec<Chunk>,
+    pub next_cursor: Option<i64>, // Pasar como `after_id` para la siguiente página; None = fin
+}
+
+/// Chunk de contenido de usuario a crear (nota de reunión, decisión de diseño...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomChunkInput {
+    pub project_path: String,
+    pub title: String, // entity_name del chunk
+    pub content: String,
+    #[serde(default)]
+    pub file_path: Option<String>, // Archivo del proyecto al que se refiere (relativo)
+    #[serde(default)]
+    pub kind: Option<String>, // meeting_note, design_decision...
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>, // Objeto JSON con datos adicionales
+}
+
+/// Query para búsqueda de chunks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChunkQuery {
+    pub project_path: Option<String>,
+    pub chunk_types: Option<Vec<ChunkType>>,
+    pub file_path: Option<String>,
+    pub entity_name: Option<String>,
+    /// Solo chunks de callgraph con dependencias de este tipo
+    pub dependency_kind: Option<DependencyKind>,
+    /// Solo chunks de callgraph que importan este paquete
+    pub dependency_package: Option<String>,
+    /// Solo commits de este tipo (feat, fix...)
+    pub commit_type: Option<String>,
+    /// Solo commits con (o sin) breaking change
+    pub breaking_change: Option<bool>,
+    /// Solo commits que modifican archivos bajo este prefijo (ej: "src/auth/")
+    pub touches_path: Option<String>,
+    /// Solo commits contenidos en esta rama
+    pub branch: Option<String>,
+    /// Incluir los chunks en la papelera (soft delete)
+    #[serde(default)]
+    pub include_deleted: bool,
+    /// Solo chunks desactualizados (o vigentes) respecto a su archivo
+    pub stale: Option<bool>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
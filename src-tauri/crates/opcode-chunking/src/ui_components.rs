@@ -0,0 +1,731 @@
+use super::storage::{
+    calculate_content_hash, delete_relationships_by_source, get_chunk_id_by_hash,
+    get_latest_file_contents, insert_relationship, upsert_chunk,
+};
+use super::types::{
+    Chunk, ChunkRelationship, ChunkType, RelationshipType, UiComponentMetadata, UiRoute,
+    UiRouteTableMetadata,
+};
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// entity_name del chunk de proyecto con la tabla de rutas del frontend
+pub const UI_ROUTES_ENTITY: &str = "<ui_routes>";
+
+/// Valor de `source` en la metadata de las relaciones Renders
+const RENDER_SOURCE: &str = "ui_render";
+const ROUTE_SOURCE: &str = "ui_route";
+
+/// Componente encontrado en un archivo, antes de resolver quién lo renderiza
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentDef {
+    pub name: String,
+    pub framework: String,
+    pub line: usize,
+    pub renders: Vec<String>,
+}
+
+/// Ruta encontrada en un archivo; `component_hint` es el nombre del componente o
+/// el specifier de un import perezoso (`() => import('./Settings.vue')`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteDef {
+    pub path: String,
+    pub component_hint: Option<String>,
+    pub line: usize,
+    pub source: String,
+}
+
+/// Regenera los chunks de componentes de UI (uno por componente), el chunk de
+/// proyecto con la tabla de rutas y las relaciones Renders entre ellos a partir de
+/// los raw source vigentes.
+/// Retorna (chunks generados, relaciones creadas)
+pub fn generate_ui_chunks(conn: &Connection, project_path: &str) -> Result<(usize, usize)> {
+    let sources = get_latest_file_contents(conn, project_path, &ChunkType::RawSource)?;
+    let file_routing = sources.keys().any(|file| {
+        matches!(
+            file_stem(file).as_str(),
+            "next.config" | "nuxt.config" | "svelte.config"
+        )
+    });
+
+    delete_relationships_by_source(conn, project_path, RENDER_SOURCE)?;
+    delete_relationships_by_source(conn, project_path, ROUTE_SOURCE)?;
+    conn.execute(
+        "DELETE FROM chunks WHERE project_path = ?1 AND chunk_type = 'callgraph'
+         AND json_extract(metadata, '$.kind') IN ('ui_component', 'ui_routes')",
+        params![project_path],
+    )?;
+
+    let mut components: Vec<(String, ComponentDef)> = Vec::new();
+    let mut routes: Vec<(String, RouteDef)> = Vec::new();
+    for (file_path, (_, content)) in &sources {
+        if !is_ui_source(file_path) {
+            continue;
+        }
+        for component in extract_components(file_path, content) {
+            components.push((file_path.clone(), component));
+        }
+        for route in extract_routes(file_path, content, file_routing) {
+            routes.push((file_path.clone(), route));
+        }
+    }
+    if components.is_empty() && routes.is_empty() {
+        return Ok((0, 0));
+    }
+
+    // Rutas con su componente resuelto a un archivo del proyecto
+    let routes: Vec<UiRoute> = routes
+        .into_iter()
+        .map(|(file_path, route)| {
+            let resolved = route
+                .component_hint
+                .as_deref()
+                .and_then(|hint| resolve_route_component(&components, &file_path, hint));
+            UiRoute {
+                path: route.path,
+                component: resolved
+                    .map(|i| components[i].1.name.clone())
+                    .or(route.component_hint),
+                component_file: resolved.map(|i| components[i].0.clone()),
+                file_path,
+                line: route.line,
+                source: route.source,
+            }
+        })
+        .collect();
+
+    // Aristas padre → hijo entre índices de `components`
+    let mut edges: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for (parent, (file_path, component)) in components.iter().enumerate() {
+        for child in &component.renders {
+            if let Some(child) = resolve_component(&components, file_path, child) {
+                if child != parent {
+                    edges.insert((parent, child));
+                }
+            }
+        }
+    }
+
+    let mut chunks_created = 0;
+    let mut chunk_ids: Vec<Option<i64>> = Vec::with_capacity(components.len());
+    for (i, (file_path, component)) in components.iter().enumerate() {
+        let rendered_by: Vec<String> = edges
+            .iter()
+            .filter(|(_, child)| *child == i)
+            .map(|(parent, _)| components[*parent].1.name.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let component_routes: Vec<String> = routes
+            .iter()
+            .filter(|r| {
+                r.component_file.as_deref() == Some(file_path.as_str())
+                    && r.component.as_deref() == Some(component.name.as_str())
+            })
+            .map(|r| r.path.clone())
+            .collect();
+
+        let mut repr = format!(
+            "# Component: {} ({})\nFile: {}:{}\n",
+            component.name, component.framework, file_path, component.line
+        );
+        if !component_routes.is_empty() {
+            repr.push_str(&format!("Routes: {}\n", component_routes.join(", ")));
+        }
+        repr.push_str(&format!("\n# Renders ({})\n", component.renders.len()));
+        for child in &component.renders {
+            repr.push_str(&format!("  - {}\n", child));
+        }
+        repr.push_str(&format!("\n# Rendered By ({})\n", rendered_by.len()));
+        for parent in &rendered_by {
+            repr.push_str(&format!("  - {}\n", parent));
+        }
+
+        let metadata = UiComponentMetadata {
+            kind: "ui_component".to_string(),
+            framework: component.framework.clone(),
+            name: component.name.clone(),
+            line: component.line,
+            renders: component.renders.clone(),
+            rendered_by,
+            routes: component_routes,
+        };
+        let chunk = build_chunk(
+            project_path,
+            Some(file_path),
+            &component.name,
+            repr,
+            serde_json::to_string(&metadata)?,
+        );
+        upsert_chunk(conn, &chunk, None)?;
+        chunks_created += 1;
+        chunk_ids.push(get_chunk_id_by_hash(conn, &chunk.project_path, &chunk.content_hash)?);
+    }
+
+    let mut relationships_created = 0;
+    for (parent, child) in &edges {
+        let (Some(from_id), Some(to_id)) = (chunk_ids[*parent], chunk_ids[*child]) else {
+            continue;
+        };
+        insert_relationship(
+            conn,
+            &renders(
+                from_id,
+                to_id,
+                serde_json::json!({
+                    "source": RENDER_SOURCE,
+                    "from_file": components[*parent].0,
+                    "to_file": components[*child].0,
+                }),
+            ),
+        )?;
+        relationships_created += 1;
+    }
+
+    if !routes.is_empty() {
+        let mut repr = format!("# UI Routes ({})\n", routes.len());
+        for route in &routes {
+            repr.push_str(&format!(
+                "{} -> {}",
+                route.path,
+                route.component.as_deref().unwrap_or("?")
+            ));
+            if let Some(component_file) = &route.component_file {
+                repr.push_str(&format!(" ({})", component_file));
+            }
+            repr.push_str(&format!(" [{}:{}]\n", route.file_path, route.line));
+        }
+
+        let metadata = UiRouteTableMetadata {
+            kind: "ui_routes".to_string(),
+            routes: routes.clone(),
+        };
+        let chunk = build_chunk(
+            project_path,
+            None,
+            UI_ROUTES_ENTITY,
+            repr,
+            serde_json::to_string(&metadata)?,
+        );
+        upsert_chunk(conn, &chunk, None)?;
+        chunks_created += 1;
+
+        if let Some(table_id) = get_chunk_id_by_hash(conn, &chunk.project_path, &chunk.content_hash)? {
+            for route in &routes {
+                let Some(i) = components.iter().position(|(file, c)| {
+                    route.component_file.as_deref() == Some(file.as_str())
+                        && route.component.as_deref() == Some(c.name.as_str())
+                }) else {
+                    continue;
+                };
+                let Some(to_id) = chunk_ids[i] else {
+                    continue;
+                };
+                insert_relationship(
+                    conn,
+                    &renders(
+                        table_id,
+                        to_id,
+                        serde_json::json!({
+                            "source": ROUTE_SOURCE,
+                            "path": route.path,
+                            "from_file": route.file_path,
+                        }),
+                    ),
+                )?;
+                relationships_created += 1;
+            }
+        }
+    }
+
+    Ok((chunks_created, relationships_created))
+}
+
+/// Rutas del frontend guardadas en el último indexado. Con `path`, solo las que
+/// coinciden exactamente o ignorando la barra final
+pub fn get_ui_routes(
+    conn: &Connection,
+    project_path: &str,
+    path: Option<&str>,
+) -> Result<Vec<UiRoute>> {
+    let metadata: Option<String> = conn
+        .query_row(
+            "SELECT metadata FROM chunks
+             WHERE project_path = ?1 AND chunk_type = 'callgraph' AND entity_name = ?2
+               AND deleted_at IS NULL
+             ORDER BY updated_at DESC LIMIT 1",
+            params![project_path, UI_ROUTES_ENTITY],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    let Some(metadata) = metadata else {
+        return Ok(Vec::new());
+    };
+    let table: UiRouteTableMetadata = serde_json::from_str(&metadata)?;
+    let path = path.map(|p| p.trim_end_matches('/'));
+    Ok(table
+        .routes
+        .into_iter()
+        .filter(|route| path.is_none() || path == Some(route.path.trim_end_matches('/')))
+        .collect())
+}
+
+/// Componentes definidos en un archivo: funciones, arrow functions y clases React en
+/// PascalCase que devuelven JSX, o el propio archivo `.vue` / `.svelte`
+pub fn extract_components(file_path: &str, content: &str) -> Vec<ComponentDef> {
+    let extension = extension_of(file_path);
+    if extension == "vue" || extension == "svelte" {
+        return vec![ComponentDef {
+            name: pascal_case(&file_stem(file_path)),
+            framework: extension,
+            line: 1,
+            renders: rendered_tags(content, true),
+        }];
+    }
+    if !matches!(extension.as_str(), "jsx" | "tsx" | "js" | "mjs") {
+        return Vec::new();
+    }
+
+    let def_re = Regex::new(
+        r"(?m)^[ \t]*(?:export\s+(?:default\s+)?)?(?:function\s+([A-Z]\w*)\s*[<(]|const\s+([A-Z]\w*)\b[^=\n]*=\s*(?:(?:React\.)?(?:memo|forwardRef)\s*\(|async\s*\(|\(|function\b|[a-z_]\w*\s*=>)|class\s+([A-Z]\w*)\s+extends\s+(?:React\.)?(?:Pure)?Component\b)",
+    )
+    .unwrap();
+    let defs: Vec<(usize, String)> = def_re
+        .captures_iter(content)
+        .map(|cap| {
+            let name = cap.get(1).or(cap.get(2)).or(cap.get(3)).unwrap();
+            (cap.get(0).unwrap().start(), name.as_str().to_string())
+        })
+        .collect();
+
+    let mut components = Vec::new();
+    for (i, (start, name)) in defs.iter().enumerate() {
+        let end = defs.get(i + 1).map(|(s, _)| *s).unwrap_or(content.len());
+        let body = &content[*start..end];
+        if !body.contains("/>") && !body.contains("</") {
+            continue;
+        }
+        let mut renders = rendered_tags(body, false);
+        renders.retain(|tag| tag != name);
+        components.push(ComponentDef {
+            name: name.clone(),
+            framework: "react".to_string(),
+            line: line_of(content, *start),
+            renders,
+        });
+    }
+    components
+}
+
+/// Rutas declaradas en un archivo: `<Route path=... element=...>` y objetos
+/// `{ path, component }` de react-router/vue-router. Con `file_routing` (proyecto
+/// Next, Nuxt o SvelteKit) también la ruta implícita del propio archivo
+pub fn extract_routes(file_path: &str, content: &str, file_routing: bool) -> Vec<RouteDef> {
+    let mut routes = Vec::new();
+
+    if let Some(path) = file_route(file_path).filter(|_| file_routing) {
+        routes.push(RouteDef {
+            path,
+            component_hint: extract_components(file_path, content)
+                .into_iter()
+                .next()
+                .map(|c| c.name),
+            line: 1,
+            source: "file".to_string(),
+        });
+    }
+
+    let route_tag_re = Regex::new(r"<Route\b").unwrap();
+    let tag_path_re = Regex::new(r#"^[^>]*?\bpath\s*=\s*\{?\s*['"`]([^'"`]*)['"`]"#).unwrap();
+    let tag_component_re =
+        Regex::new(r"\b(?:element\s*=\s*\{\s*<\s*|component\s*=\s*\{\s*)([A-Z]\w*)").unwrap();
+    let starts: Vec<usize> = route_tag_re.find_iter(content).map(|m| m.start()).collect();
+    for (i, start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(content.len());
+        let tag = &content[*start + "<Route".len()..end];
+        let Some(path) = tag_path_re.captures(tag) else {
+            continue;
+        };
+        routes.push(RouteDef {
+            path: path[1].to_string(),
+            component_hint: tag_component_re.captures(tag).map(|c| c[1].to_string()),
+            line: line_of(content, *start),
+            source: "router".to_string(),
+        });
+    }
+
+    // Tablas de rutas como objetos: solo en archivos que hablan de un router
+    let lower = content.to_lowercase();
+    if lower.contains("router") || lower.contains("routes") {
+        let path_re = Regex::new(r#"\bpath\s*:\s*['"`]([^'"`]*)['"`]"#).unwrap();
+        let component_re = Regex::new(
+            r#"\b(?:component|element)\s*:\s*<?\s*([A-Z]\w*)|\bimport\(\s*['"`]([^'"`]+)['"`]\s*\)"#,
+        )
+        .unwrap();
+        let matches: Vec<(usize, usize, String)> = path_re
+            .captures_iter(content)
+            .map(|cap| {
+                let whole = cap.get(0).unwrap();
+                (whole.start(), whole.end(), cap[1].to_string())
+            })
+            .collect();
+        for (i, (start, end, path)) in matches.iter().enumerate() {
+            let next = matches.get(i + 1).map(|m| m.0).unwrap_or(content.len());
+            let entry = &content[*end..next];
+            let component_hint = component_re
+                .captures(entry)
+                .and_then(|cap| cap.get(1).or(cap.get(2)).map(|m| m.as_str().to_string()));
+            routes.push(RouteDef {
+                path: path.clone(),
+                component_hint,
+                line: line_of(content, *start),
+                source: "router".to_string(),
+            });
+        }
+    }
+
+    routes
+}
+
+/// Ruta implícita de un archivo en los routers por sistema de archivos: `app/**/page.tsx`
+/// (Next), `pages/**` (Next, Nuxt) y `routes/**/+page.svelte` (SvelteKit)
+fn file_route(file_path: &str) -> Option<String> {
+    let segments: Vec<&str> = file_path.split('/').collect();
+    let (file_name, dirs) = segments.split_last()?;
+    let extension = extension_of(file_path);
+    let stem = file_stem(file_path);
+
+    let after = |root: &str| -> Option<Vec<&str>> {
+        let index = dirs.iter().position(|d| *d == root)?;
+        Some(
+            dirs[index + 1..]
+                .iter()
+                .copied()
+                .filter(|d| !(d.starts_with('(') && d.ends_with(')')))
+                .collect(),
+        )
+    };
+
+    let route = if *file_name == "+page.svelte" {
+        after("routes")?
+    } else if stem == "page" && matches!(extension.as_str(), "tsx" | "jsx" | "ts" | "js") {
+        after("app")?
+    } else if matches!(extension.as_str(), "tsx" | "jsx" | "ts" | "js" | "vue") {
+        let mut route = after("pages")?;
+        if stem.starts_with('_') || route.first() == Some(&"api") {
+            return None;
+        }
+        if stem != "index" {
+            route.push(stem.as_str());
+        }
+        return Some(format!("/{}", route.join("/")));
+    } else {
+        return None;
+    };
+    Some(format!("/{}", route.join("/")))
+}
+
+/// Nombres de componente usados como etiqueta: `<UserCard`, y en templates Vue/Svelte
+/// también `<user-card` (convertido a PascalCase). Sin repetidos, en orden de aparición
+fn rendered_tags(content: &str, kebab_case: bool) -> Vec<String> {
+    // El carácter previo descarta genéricos de TypeScript como `useState<User>`
+    let tag_re = Regex::new(r"(?:^|[^\w.$])<([A-Z]\w*)").unwrap();
+    let kebab_re = Regex::new(r"<([a-z][a-z0-9]*(?:-[a-z0-9]+)+)\b").unwrap();
+
+    let mut seen = BTreeSet::new();
+    let mut tags: Vec<(usize, String)> = tag_re
+        .captures_iter(content)
+        .map(|cap| (cap.get(1).unwrap().start(), cap[1].to_string()))
+        .collect();
+    if kebab_case {
+        tags.extend(
+            kebab_re
+                .captures_iter(content)
+                .map(|cap| (cap.get(1).unwrap().start(), pascal_case(&cap[1]))),
+        );
+        tags.sort();
+    }
+    tags.into_iter()
+        .map(|(_, tag)| tag)
+        .filter(|tag| seen.insert(tag.clone()))
+        .collect()
+}
+
+/// Índice del componente `name` visto desde `file_path`: el del mismo archivo, o el
+/// único del proyecto con ese nombre
+fn resolve_component(
+    components: &[(String, ComponentDef)],
+    file_path: &str,
+    name: &str,
+) -> Option<usize> {
+    let candidates: Vec<usize> = components
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, c))| c.name == name)
+        .map(|(i, _)| i)
+        .collect();
+    candidates
+        .iter()
+        .copied()
+        .find(|i| components[*i].0 == file_path)
+        .or(if candidates.len() == 1 {
+            Some(candidates[0])
+        } else {
+            None
+        })
+}
+
+/// Componente de una ruta: por nombre, o por el archivo de un import perezoso
+fn resolve_route_component(
+    components: &[(String, ComponentDef)],
+    file_path: &str,
+    hint: &str,
+) -> Option<usize> {
+    if !hint.contains('/') && !hint.contains('.') {
+        return resolve_component(components, file_path, hint);
+    }
+    // `./views/Settings.vue`, `@/pages/Settings`: se compara por nombre de archivo
+    let stem = file_stem(hint);
+    let candidates: Vec<usize> = components
+        .iter()
+        .enumerate()
+        .filter(|(_, (file, _))| file_stem(file) == stem)
+        .map(|(i, _)| i)
+        .collect();
+    if candidates.len() == 1 {
+        Some(candidates[0])
+    } else {
+        None
+    }
+}
+
+fn renders(from_chunk_id: i64, to_chunk_id: i64, metadata: serde_json::Value) -> ChunkRelationship {
+    ChunkRelationship {
+        id: None,
+        from_chunk_id,
+        to_chunk_id,
+        relationship_type: RelationshipType::Renders,
+        metadata: Some(metadata.to_string()),
+        created_at: Utc::now(),
+    }
+}
+
+fn build_chunk(
+    project_path: &str,
+    file_path: Option<&str>,
+    entity_name: &str,
+    content: String,
+    metadata: String,
+) -> Chunk {
+    let content_hash = calculate_content_hash(&format!(
+        "{}\n{}\n{}",
+        project_path,
+        file_path.unwrap_or(""),
+        content
+    ));
+
+    Chunk {
+        id: None,
+        project_path: project_path.to_string(),
+        chunk_type: ChunkType::Callgraph,
+        file_path: file_path.map(|f| f.to_string()),
+        entity_name: Some(entity_name.to_string()),
+        content,
+        content_hash,
+        metadata: Some(metadata),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn is_ui_source(file_path: &str) -> bool {
+    matches!(
+        extension_of(file_path).as_str(),
+        "js" | "jsx" | "mjs" | "ts" | "tsx" | "vue" | "svelte"
+    )
+}
+
+fn extension_of(file_path: &str) -> String {
+    Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+fn file_stem(file_path: &str) -> String {
+    Path::new(file_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// `user-card` / `user_card` / `+page` → `UserCard` / `UserCard` / `Page`
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{get_relationships, init_chunk_database};
+    use crate::test_support::chunk;
+
+    const APP: &str = r#"import { Routes, Route } from "react-router-dom";
+import { useState } from "react";
+
+export default function App() {
+  const [user] = useState<User>(null);
+  return (
+    <Layout>
+      <Routes>
+        <Route path="/" element={<Home />} />
+        <Route path="/settings" element={<Settings user={user} />} />
+      </Routes>
+    </Layout>
+  );
+}
+
+const Layout = ({ children }) => <main><Header />{children}</main>;
+
+const MAX_ITEMS = 10;
+"#;
+
+    #[test]
+    fn test_extract_react_components_and_routes() {
+        let components = extract_components("src/App.tsx", APP);
+        let names: Vec<(&str, usize)> = components
+            .iter()
+            .map(|c| (c.name.as_str(), c.line))
+            .collect();
+        assert_eq!(names, vec![("App", 4), ("Layout", 16)]);
+        assert_eq!(
+            components[0].renders,
+            vec!["Layout", "Routes", "Route", "Home", "Settings"]
+        );
+        assert_eq!(components[1].renders, vec!["Header"]);
+
+        let routes: Vec<(String, Option<String>, usize)> = extract_routes("src/App.tsx", APP, true)
+            .into_iter()
+            .map(|r| (r.path, r.component_hint, r.line))
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("/".to_string(), Some("Home".to_string()), 9),
+                ("/settings".to_string(), Some("Settings".to_string()), 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_vue_routes_and_file_routes() {
+        let router = "import { createRouter } from 'vue-router'\nconst routes = [\n  { path: '/', component: HomeView },\n  { path: '/settings', component: () => import('../views/SettingsView.vue') },\n]\n";
+        let routes: Vec<(String, Option<String>)> =
+            extract_routes("src/router/index.ts", router, false)
+                .into_iter()
+                .map(|r| (r.path, r.component_hint))
+                .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("/".to_string(), Some("HomeView".to_string())),
+                (
+                    "/settings".to_string(),
+                    Some("../views/SettingsView.vue".to_string())
+                ),
+            ]
+        );
+
+        let view = extract_components(
+            "src/views/SettingsView.vue",
+            "<template>\n  <user-card :user=\"user\" />\n  <SaveButton />\n</template>\n",
+        );
+        assert_eq!(view[0].name, "SettingsView");
+        assert_eq!(view[0].renders, vec!["UserCard", "SaveButton"]);
+
+        assert_eq!(
+            file_route("app/(admin)/settings/page.tsx").as_deref(),
+            Some("/settings")
+        );
+        assert_eq!(
+            file_route("pages/blog/[slug].vue").as_deref(),
+            Some("/blog/[slug]")
+        );
+        assert_eq!(file_route("pages/index.tsx").as_deref(), Some("/"));
+        assert_eq!(file_route("pages/api/users.ts"), None);
+        assert_eq!(
+            file_route("src/routes/about/+page.svelte").as_deref(),
+            Some("/about")
+        );
+        assert_eq!(file_route("src/components/Button.tsx"), None);
+
+        let page = "<script setup>\n</script>\n<template><SettingsForm /></template>\n";
+        let routes = extract_routes("pages/settings.vue", page, true);
+        assert_eq!(routes[0].path, "/settings");
+        assert_eq!(routes[0].component_hint.as_deref(), Some("Settings"));
+        assert!(extract_routes("pages/settings.vue", page, false).is_empty());
+    }
+
+    #[test]
+    fn test_generate_ui_chunks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let files = [
+            ("src/App.tsx", APP),
+            (
+                "src/pages/Settings.tsx",
+                "export function Settings({ user }) {\n  return <Form user={user} />;\n}\n",
+            ),
+            ("src/Form.jsx", "export const Form = (props) => <form />;\n"),
+        ];
+        for (file, content) in files {
+            chunk().file(file).content(content).insert(&conn);
+        }
+
+        // App, Layout, Settings, Form + tabla de rutas
+        assert_eq!(generate_ui_chunks(&conn, "/p").unwrap(), (5, 4));
+        assert_eq!(generate_ui_chunks(&conn, "/p").unwrap(), (5, 4));
+
+        let settings = get_ui_routes(&conn, "/p", Some("/settings/")).unwrap();
+        assert_eq!(settings.len(), 1);
+        assert_eq!(settings[0].component.as_deref(), Some("Settings"));
+        assert_eq!(
+            settings[0].component_file.as_deref(),
+            Some("src/pages/Settings.tsx")
+        );
+        assert_eq!(settings[0].file_path, "src/App.tsx");
+
+        let settings_id: i64 = conn
+            .query_row(
+                "SELECT id FROM chunks WHERE entity_name = 'Settings'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let incoming = get_relationships(&conn, settings_id, false).unwrap();
+        assert_eq!(incoming.len(), 2);
+        assert!(incoming
+            .iter()
+            .all(|r| r.relationship_type == RelationshipType::Renders));
+    }
+}
@@ -0,0 +1,199 @@
+use super::storage::{
+    create_index_operation, delete_chunks_indexed_since, get_last_index_operation,
+    set_index_operation_count, soft_delete_project_chunks, undo_index_operation,
+};
+use super::types::{IndexOperationKind, UndoResult};
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::Connection;
+
+/// Deshace la última operación destructiva del proyecto (borrado, prune o reset)
+/// restaurando sus chunks desde la papelera. Retorna None si no hay nada que deshacer.
+/// Un borrado o reset de todo el proyecto descarta antes lo indexado después de él,
+/// para no mezclar el índice restaurado con el nuevo
+pub fn undo_last_operation(conn: &Connection, project_path: &str) -> Result<Option<UndoResult>> {
+    let Some(mut operation) = get_last_index_operation(conn, project_path)? else {
+        return Ok(None);
+    };
+
+    if matches!(
+        operation.kind,
+        IndexOperationKind::Reset | IndexOperationKind::DeleteProjectChunks
+    ) {
+        let discarded = delete_chunks_indexed_since(conn, project_path, operation.created_at)?;
+        if discarded > 0 {
+            log::info!(
+                "Discarded {} chunks indexed after {} on {}",
+                discarded,
+                operation.kind.as_str(),
+                project_path
+            );
+        }
+    }
+    let chunks_restored = undo_index_operation(conn, operation.id)?;
+    operation.undone_at = Some(Utc::now());
+    log::info!(
+        "Undid {} on {}: restored {} of {} chunks",
+        operation.kind.as_str(),
+        project_path,
+        chunks_restored,
+        operation.chunks_affected
+    );
+
+    Ok(Some(UndoResult {
+        operation,
+        chunks_restored,
+    }))
+}
+
+/// Manda todos los chunks del proyecto a la papelera como una operación `Reset`, antes
+/// de reindexar desde cero. Los chunks que se regeneran salen de la papelera al indexar
+pub fn begin_reset(conn: &Connection, project_path: &str) -> Result<usize> {
+    let operation_id = create_index_operation(conn, project_path, IndexOperationKind::Reset)?;
+    let count = soft_delete_project_chunks(conn, project_path, operation_id)?;
+    set_index_operation_count(conn, operation_id, count)?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        delete_project_chunks, get_index_operations, init_chunk_database, insert_relationship,
+        query_chunks,
+    };
+    use crate::test_support::chunk;
+    use crate::tombstones::purge_expired;
+    use crate::types::{ChunkQuery, ChunkRelationship, RelationshipType};
+
+    fn insert_chunk(conn: &Connection, file_path: &str) {
+        chunk()
+            .file(file_path)
+            .content(&format!("// {}", file_path))
+            .insert(conn);
+    }
+
+    fn live_count(conn: &Connection) -> usize {
+        query_chunks(
+            conn,
+            &ChunkQuery {
+                project_path: Some("/p".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .len()
+    }
+
+    #[test]
+    fn test_undo_walks_back_operations_in_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_chunk(&conn, "a.rs");
+        insert_chunk(&conn, "b.rs");
+
+        assert_eq!(begin_reset(&conn, "/p").unwrap(), 2);
+        insert_chunk(&conn, "a.rs"); // Regenerado por la reindexación
+        assert_eq!(delete_project_chunks(&conn, "/p").unwrap(), 1);
+        assert_eq!(live_count(&conn), 0);
+
+        let undone = undo_last_operation(&conn, "/p").unwrap().unwrap();
+        assert_eq!(
+            undone.operation.kind,
+            IndexOperationKind::DeleteProjectChunks
+        );
+        assert_eq!(undone.chunks_restored, 1);
+        assert_eq!(live_count(&conn), 1);
+
+        let undone = undo_last_operation(&conn, "/p").unwrap().unwrap();
+        assert_eq!(undone.operation.kind, IndexOperationKind::Reset);
+        assert_eq!(undone.operation.chunks_affected, 2);
+        assert_eq!(undone.chunks_restored, 1);
+        assert_eq!(live_count(&conn), 2);
+
+        assert!(undo_last_operation(&conn, "/p").unwrap().is_none());
+        assert!(get_index_operations(&conn, "/p")
+            .unwrap()
+            .iter()
+            .all(|op| op.undone_at.is_some()));
+    }
+
+    #[test]
+    fn test_undo_reset_discards_chunks_indexed_after_it() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        let a = chunk().file("a.rs").content("// a.rs").insert(&conn);
+        let b = chunk().file("b.rs").content("// b.rs").insert(&conn);
+        let link = |from: i64, to: i64| {
+            insert_relationship(
+                &conn,
+                &ChunkRelationship {
+                    id: None,
+                    from_chunk_id: from,
+                    to_chunk_id: to,
+                    relationship_type: RelationshipType::Calls,
+                    metadata: None,
+                    created_at: Utc::now(),
+                },
+            )
+            .unwrap();
+        };
+        link(a, b);
+
+        begin_reset(&conn, "/p").unwrap();
+        // La reindexación regenera a.rs, indexa un archivo nuevo y lo relaciona y embebe
+        insert_chunk(&conn, "a.rs");
+        let c = chunk().file("c.rs").content("// c.rs").insert(&conn);
+        link(a, c);
+        conn.execute(
+            "INSERT INTO chunk_embeddings (chunk_id, project_path, content_hash, model, model_version, dimensions, vector, updated_at)
+             VALUES (?1, '/p', 'h', 'm', 'v', 1, x'00000000', ?2)",
+            rusqlite::params![c, Utc::now().to_rfc3339()],
+        )
+        .unwrap();
+
+        let undone = undo_last_operation(&conn, "/p").unwrap().unwrap();
+        assert_eq!(undone.operation.kind, IndexOperationKind::Reset);
+        assert_eq!(undone.chunks_restored, 1);
+        let mut files: Vec<String> = query_chunks(
+            &conn,
+            &ChunkQuery {
+                project_path: Some("/p".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .into_iter()
+        .filter_map(|chunk| chunk.file_path)
+        .collect();
+        files.sort();
+        assert_eq!(files, vec!["a.rs", "b.rs"]);
+
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM chunks"), 2);
+        assert_eq!(count("SELECT COUNT(*) FROM chunk_embeddings"), 0);
+        let links: (i64, i64) = conn
+            .query_row(
+                "SELECT from_chunk_id, to_chunk_id FROM chunk_relationships",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(links, (a, b));
+        assert_eq!(count("SELECT COUNT(*) FROM chunk_relationships"), 1);
+    }
+
+    #[test]
+    fn test_purged_chunks_cannot_be_restored() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+        insert_chunk(&conn, "a.rs");
+
+        delete_project_chunks(&conn, "/p").unwrap();
+        purge_expired(&conn, Some("/p"), 0).unwrap();
+
+        let undone = undo_last_operation(&conn, "/p").unwrap().unwrap();
+        assert_eq!(undone.operation.chunks_affected, 1);
+        assert_eq!(undone.chunks_restored, 0);
+    }
+}
@@ -0,0 +1,163 @@
+use super::api_surface::diff_public_api;
+use super::ast::detect_language;
+use super::commits::classify_commit_message;
+use super::storage::get_snapshot;
+use super::types::{Snapshot, VersionBump, VersionBumpSuggestion};
+use anyhow::{Context, Result};
+use git2::{Oid, Repository, Tree};
+use rusqlite::Connection;
+use std::path::Path;
+
+/// Sugiere major/minor/patch comparando la API pública entre los commits de dos snapshots:
+/// símbolos eliminados o con firma distinta (o commits `!`/`BREAKING CHANGE`) → major,
+/// nuevos exports (o commits `feat`) → minor, cambios internos → patch
+pub fn suggest_version_bump(
+    conn: &Connection,
+    from_snapshot_id: i64,
+    to_snapshot_id: i64,
+) -> Result<VersionBumpSuggestion> {
+    let from = get_snapshot(conn, from_snapshot_id)?
+        .with_context(|| format!("Snapshot {} not found", from_snapshot_id))?;
+    let to = get_snapshot(conn, to_snapshot_id)?
+        .with_context(|| format!("Snapshot {} not found", to_snapshot_id))?;
+    if from.project_path != to.project_path {
+        anyhow::bail!("Snapshots belong to different projects");
+    }
+
+    let repo = Repository::open(&to.project_path).context("Failed to open git repository")?;
+    let from_oid = snapshot_oid(&from)?;
+    let to_oid = snapshot_oid(&to)?;
+    let from_tree = repo.find_commit(from_oid)?.tree()?;
+    let to_tree = repo.find_commit(to_oid)?.tree()?;
+
+    let mut suggestion = VersionBumpSuggestion {
+        bump: VersionBump::None,
+        breaking_changes: Vec::new(),
+        added_exports: Vec::new(),
+        internal_files: Vec::new(),
+        reasons: Vec::new(),
+    };
+
+    let diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?;
+    let mut paths: Vec<String> = Vec::new();
+    for delta in diff.deltas() {
+        for file in [delta.old_file(), delta.new_file()] {
+            if let Some(path) = file.path().and_then(|p| p.to_str()) {
+                if !paths.iter().any(|p| p == path) {
+                    paths.push(path.to_string());
+                }
+            }
+        }
+    }
+
+    for path in &paths {
+        if detect_language(path).is_err() || is_test_path(path) {
+            suggestion.internal_files.push(path.clone());
+            continue;
+        }
+        let old = blob_content(&repo, &from_tree, path);
+        let new = blob_content(&repo, &to_tree, path);
+        match diff_public_api(path, old.as_deref(), new.as_deref()) {
+            Ok(api_diff) => {
+                if api_diff.removed.is_empty()
+                    && api_diff.changed.is_empty()
+                    && api_diff.added.is_empty()
+                {
+                    suggestion.internal_files.push(path.clone());
+                }
+                suggestion.breaking_changes.extend(api_diff.removed);
+                suggestion.breaking_changes.extend(api_diff.changed);
+                suggestion.added_exports.extend(api_diff.added);
+            }
+            Err(e) => {
+                suggestion
+                    .reasons
+                    .push(format!("{}: API not analyzed ({})", path, e));
+                suggestion.internal_files.push(path.clone());
+            }
+        }
+    }
+
+    let mut bump = if paths.is_empty() {
+        VersionBump::None
+    } else {
+        VersionBump::Patch
+    };
+    if !suggestion.added_exports.is_empty() {
+        bump = bump.max(VersionBump::Minor);
+        suggestion.reasons.push(format!(
+            "{} new public symbol(s)",
+            suggestion.added_exports.len()
+        ));
+    }
+    if !suggestion.breaking_changes.is_empty() {
+        bump = VersionBump::Major;
+        suggestion.reasons.push(format!(
+            "{} public symbol(s) removed or with a changed signature",
+            suggestion.breaking_changes.len()
+        ));
+    }
+
+    // Los mensajes de commit también pueden declarar breaking changes o features
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(to_oid)?;
+    revwalk.hide(from_oid)?;
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let message = commit.message().unwrap_or("");
+        let classification = classify_commit_message(message);
+        let short_hash: String = commit.id().to_string().chars().take(7).collect();
+        if classification.breaking_change {
+            bump = VersionBump::Major;
+            suggestion.reasons.push(format!(
+                "Commit {} declares a breaking change: {}",
+                short_hash,
+                message.lines().next().unwrap_or("").trim()
+            ));
+        } else if classification.commit_type == "feat" && bump < VersionBump::Minor {
+            bump = VersionBump::Minor;
+            suggestion.reasons.push(format!(
+                "Commit {} adds a feature: {}",
+                short_hash,
+                message.lines().next().unwrap_or("").trim()
+            ));
+        }
+    }
+
+    if bump == VersionBump::Patch && suggestion.reasons.is_empty() {
+        suggestion
+            .reasons
+            .push("Only internal changes (public API unchanged)".to_string());
+    }
+    suggestion.bump = bump;
+    Ok(suggestion)
+}
+
+fn snapshot_oid(snapshot: &Snapshot) -> Result<Oid> {
+    let hash = snapshot
+        .git_commit_hash
+        .as_deref()
+        .with_context(|| format!("Snapshot V{} has no git commit", snapshot.version_major))?;
+    Ok(Oid::from_str(hash)?)
+}
+
+/// Contenido de un archivo en un árbol de Git (None si no existe o no es texto)
+pub(crate) fn blob_content(repo: &Repository, tree: &Tree, path: &str) -> Option<String> {
+    let entry = tree.get_path(Path::new(path)).ok()?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    String::from_utf8(blob.content().to_vec()).ok()
+}
+
+/// Archivos de tests (no forman parte de la API publicada)
+pub(crate) fn is_test_path(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or("");
+    let in_test_dir = path
+        .split('/')
+        .any(|c| matches!(c, "tests" | "test" | "__tests__" | "spec"));
+    let stem = file_name.split('.').next().unwrap_or("");
+    in_test_dir
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || file_name.contains(".test.")
+        || file_name.contains(".spec.")
+}
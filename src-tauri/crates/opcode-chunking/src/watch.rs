@@ -0,0 +1,277 @@
+use super::git_hooks;
+use super::storage;
+use super::types::IndexingError;
+use anyhow::Result;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::{RecursiveMode, Watcher};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Espera por defecto tras el último cambio antes de reindexar
+pub const DEFAULT_DEBOUNCE_MS: u64 = 500;
+
+/// Directorios que nunca se reindexan aunque no estén en .gitignore
+const ALWAYS_IGNORED_DIRS: &[&str] = &[".git", "node_modules", "target", ".opcode"];
+
+/// Evento emitido por el modo watch (una línea JSON por evento)
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WatchEvent {
+    Started {
+        project_path: String,
+        debounce_ms: u64,
+    },
+    Changed {
+        files: Vec<String>,
+    },
+    Reindexed {
+        files: Vec<String>,
+        chunks_created: usize,
+        chunks_updated: usize,
+        chunks_deleted: usize,
+        errors: Vec<IndexingError>,
+        duration_ms: u64,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Filtro de rutas del proyecto: respeta .gitignore y los directorios siempre ignorados
+pub struct WatchFilter {
+    root: PathBuf,
+    gitignore: Gitignore,
+}
+
+impl WatchFilter {
+    pub fn new(project_path: &Path) -> Self {
+        let mut builder = GitignoreBuilder::new(project_path);
+        builder.add(project_path.join(".gitignore"));
+        let gitignore = builder.build().unwrap_or_else(|_| Gitignore::empty());
+        Self {
+            root: project_path.to_path_buf(),
+            gitignore,
+        }
+    }
+
+    /// Ruta relativa al proyecto si el archivo debe reindexarse
+    pub fn relevant_path(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        if relative.as_os_str().is_empty() {
+            return None;
+        }
+        let ignored_dir = relative
+            .components()
+            .any(|c| ALWAYS_IGNORED_DIRS.iter().any(|dir| c.as_os_str() == *dir));
+        if ignored_dir {
+            return None;
+        }
+        if self
+            .gitignore
+            .matched_path_or_any_parents(relative, path.is_dir())
+            .is_ignore()
+        {
+            return None;
+        }
+        if path.is_dir() {
+            return None;
+        }
+        Some(storage::normalize_file_path(&relative.to_string_lossy()))
+    }
+}
+
+/// Reindexa un lote de archivos cambiados sobre el snapshot activo
+pub fn reindex_batch(conn: &Connection, project_path: &str, files: &[String]) -> WatchEvent {
+    let started = Instant::now();
+    let snapshot_id = match storage::get_active_snapshot_id(conn, project_path) {
+        Ok(id) => id,
+        Err(e) => {
+            return WatchEvent::Error {
+                message: e.to_string(),
+            }
+        }
+    };
+
+    match super::reindex_files(conn, project_path, files, snapshot_id) {
+        Ok(result) => WatchEvent::Reindexed {
+            files: files.to_vec(),
+            chunks_created: result.chunks_created,
+            chunks_updated: result.chunks_updated,
+            chunks_deleted: result.chunks_deleted,
+            errors: result.errors,
+            duration_ms: started.elapsed().as_millis() as u64,
+        },
+        Err(e) => WatchEvent::Error {
+            message: e.to_string(),
+        },
+    }
+}
+
+/// Observa el proyecto y reindexa los archivos modificados hasta que el watcher se cierre.
+/// Los cambios se agrupan: se espera `debounce` sin eventos nuevos antes de reindexar.
+/// También consume `.git/opcode-reindex`, así los commits y merges registrados por los
+/// hooks se reindexan aunque se hayan hecho antes de arrancar o desde otra worktree.
+pub fn watch_project(
+    conn: &Connection,
+    project_path: &str,
+    debounce: Duration,
+    mut emit: impl FnMut(&WatchEvent),
+) -> Result<()> {
+    // Ruta absoluta: es la clave con la que la app guarda el proyecto
+    let root = std::fs::canonicalize(project_path)?;
+    let project_path = root.to_string_lossy().to_string();
+    let filter = WatchFilter::new(&root);
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&root, RecursiveMode::Recursive)?;
+
+    let trigger = git_hooks::reindex_trigger_path(&project_path);
+    if let Some(git_dir) = trigger.as_ref().and_then(|t| t.parent()) {
+        if !git_dir.starts_with(&root) {
+            watcher.watch(git_dir, RecursiveMode::NonRecursive)?;
+        }
+    }
+
+    emit(&WatchEvent::Started {
+        project_path: project_path.clone(),
+        debounce_ms: debounce.as_millis() as u64,
+    });
+
+    let mut pending: BTreeSet<String> = BTreeSet::new();
+    if trigger.is_some() {
+        take_trigger(&project_path, &mut pending, &mut emit);
+    }
+    loop {
+        let received = if pending.is_empty() {
+            rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected)
+        } else {
+            rx.recv_timeout(debounce)
+        };
+
+        match received {
+            Ok(Ok(event)) => {
+                if event.paths.iter().any(|p| Some(p) == trigger.as_ref()) {
+                    take_trigger(&project_path, &mut pending, &mut emit);
+                }
+                pending.extend(event.paths.iter().filter_map(|p| filter.relevant_path(p)));
+            }
+            Ok(Err(e)) => emit(&WatchEvent::Error {
+                message: e.to_string(),
+            }),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                let files: Vec<String> = std::mem::take(&mut pending).into_iter().collect();
+                emit(&WatchEvent::Changed {
+                    files: files.clone(),
+                });
+                emit(&reindex_batch(conn, &project_path, &files));
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// Agrega a `pending` los archivos registrados por los hooks de git
+fn take_trigger(
+    project_path: &str,
+    pending: &mut BTreeSet<String>,
+    emit: &mut impl FnMut(&WatchEvent),
+) {
+    match git_hooks::take_reindex_trigger(project_path) {
+        Ok(files) => pending.extend(files),
+        Err(e) => emit(&WatchEvent::Error {
+            message: e.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::init_chunk_database;
+
+    fn temp_project() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("opcode-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+        std::fs::create_dir_all(dir.join("dist")).unwrap();
+        std::fs::write(dir.join(".gitignore"), "dist/\n*.log\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_filter_skips_ignored_paths() {
+        let dir = temp_project();
+        std::fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.join("dist/out.js"), "x").unwrap();
+        std::fs::write(dir.join("debug.log"), "x").unwrap();
+        std::fs::write(dir.join("node_modules/pkg/index.js"), "x").unwrap();
+        let filter = WatchFilter::new(&dir);
+
+        assert_eq!(
+            filter.relevant_path(&dir.join("src/main.rs")),
+            Some("src/main.rs".to_string())
+        );
+        assert_eq!(filter.relevant_path(&dir.join("dist/out.js")), None);
+        assert_eq!(filter.relevant_path(&dir.join("debug.log")), None);
+        assert_eq!(
+            filter.relevant_path(&dir.join("node_modules/pkg/index.js")),
+            None
+        );
+        assert_eq!(filter.relevant_path(&dir.join("src")), None);
+        assert_eq!(filter.relevant_path(Path::new("/elsewhere/file.rs")), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reindex_batch_event_serializes() {
+        let dir = temp_project();
+        std::fs::write(dir.join("src/lib.rs"), "pub fn answer() -> u32 { 42 }\n").unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        init_chunk_database(&conn).unwrap();
+
+        let project = dir.to_string_lossy().to_string();
+        let event = reindex_batch(&conn, &project, &["src/lib.rs".to_string()]);
+        match &event {
+            WatchEvent::Reindexed { chunks_created, .. } => assert!(*chunks_created > 0),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "reindexed");
+        assert_eq!(json["files"][0], "src/lib.rs");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_take_trigger_adds_hook_files_to_pending() {
+        let dir = temp_project();
+        let repo = git2::Repository::init(&dir).unwrap();
+        std::fs::write(
+            repo.path().join(git_hooks::REINDEX_TRIGGER_FILE),
+            "src/a.rs\nsrc/b.rs\n",
+        )
+        .unwrap();
+        let project = dir.to_string_lossy().to_string();
+        assert_eq!(
+            git_hooks::reindex_trigger_path(&project),
+            Some(repo.path().join(git_hooks::REINDEX_TRIGGER_FILE))
+        );
+
+        let mut pending = BTreeSet::from(["src/main.rs".to_string()]);
+        take_trigger(&project, &mut pending, &mut |e| panic!("{:?}", e));
+        assert_eq!(
+            pending.into_iter().collect::<Vec<_>>(),
+            vec!["src/a.rs", "src/b.rs", "src/main.rs"]
+        );
+        assert!(!repo.path().join(git_hooks::REINDEX_TRIGGER_FILE).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
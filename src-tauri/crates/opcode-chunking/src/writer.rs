@@ -0,0 +1,248 @@
+use super::ChunkingOrchestrator;
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use std::ops::Deref;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Conexiones de lectura que el pool mantiene abiertas
+pub const DEFAULT_READER_POOL_SIZE: usize = 4;
+
+/// Espera máxima de una conexión ante un lock de SQLite
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+type WriteOp = Box<dyn FnOnce(&ChunkingOrchestrator) + Send>;
+
+/// Actor escritor: un hilo dueño de la única conexión de escritura (dentro de un
+/// orquestador) que ejecuta las operaciones en el orden en que se encolan
+pub struct StorageWriter {
+    sender: Option<mpsc::Sender<WriteOp>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl StorageWriter {
+    /// Abre la base en modo WAL, inicializa el esquema y arranca el hilo escritor
+    pub fn open(db_path: &Path) -> Result<Self> {
+        let conn = Connection::open(db_path)
+            .with_context(|| format!("Failed to open {}", db_path.display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let orchestrator = ChunkingOrchestrator::new(conn)?;
+
+        let (sender, receiver) = mpsc::channel::<WriteOp>();
+        let handle = std::thread::Builder::new()
+            .name("chunk-storage-writer".to_string())
+            .spawn(move || {
+                for op in receiver {
+                    // Una operación que entra en pánico no debe detener al escritor
+                    if catch_unwind(AssertUnwindSafe(|| op(&orchestrator))).is_err() {
+                        log::error!("Storage write operation panicked");
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            sender: Some(sender),
+            handle: Some(handle),
+        })
+    }
+
+    /// Encola una operación y espera su resultado. Las operaciones se ejecutan
+    /// de a una, en orden FIFO
+    pub fn execute<T, F>(&self, op: F) -> Result<T>
+    where
+        F: FnOnce(&ChunkingOrchestrator) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (reply, response) = mpsc::sync_channel(1);
+        let op: WriteOp = Box::new(move |orchestrator| {
+            let _ = reply.send(op(orchestrator));
+        });
+
+        self.sender
+            .as_ref()
+            .ok_or_else(|| anyhow!("Storage writer is closed"))?
+            .send(op)
+            .map_err(|_| anyhow!("Storage writer stopped"))?;
+        response
+            .recv()
+            .map_err(|_| anyhow!("Storage write operation aborted"))?
+    }
+}
+
+impl Drop for StorageWriter {
+    /// Cierra el canal y espera a que se drenen las operaciones pendientes
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Pool de conexiones de solo lectura sobre la misma base
+pub struct ReaderPool {
+    db_path: PathBuf,
+    idle: Mutex<Vec<Connection>>,
+    max_idle: usize,
+}
+
+impl ReaderPool {
+    pub fn new(db_path: &Path, max_idle: usize) -> Self {
+        Self {
+            db_path: db_path.to_path_buf(),
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+        }
+    }
+
+    /// Toma una conexión libre o abre una nueva si todas están en uso
+    pub fn get(&self) -> Result<PooledConnection<'_>> {
+        let idle = self
+            .idle
+            .lock()
+            .map_err(|_| anyhow!("Reader pool poisoned"))?
+            .pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => {
+                let conn = Connection::open_with_flags(
+                    &self.db_path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )
+                .with_context(|| format!("Failed to open {}", self.db_path.display()))?;
+                conn.busy_timeout(BUSY_TIMEOUT)?;
+                conn
+            }
+        };
+
+        Ok(PooledConnection {
+            conn: Some(conn),
+            pool: self,
+        })
+    }
+
+    fn release(&self, conn: Connection) {
+        if let Ok(mut idle) = self.idle.lock() {
+            if idle.len() < self.max_idle {
+                idle.push(conn);
+            }
+        }
+    }
+}
+
+/// Conexión de lectura prestada; vuelve al pool al salir de scope
+pub struct PooledConnection<'a> {
+    conn: Option<Connection>,
+    pool: &'a ReaderPool,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection already released")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.release(conn);
+        }
+    }
+}
+
+/// Acceso compartido a la base de chunks: escrituras serializadas por el actor
+/// escritor y lecturas concurrentes desde el pool (WAL no bloquea lectores)
+pub struct ChunkStore {
+    writer: StorageWriter,
+    readers: ReaderPool,
+}
+
+impl ChunkStore {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        // El escritor crea la base y el esquema antes de abrir lectores
+        let writer = StorageWriter::open(db_path)?;
+        Ok(Self {
+            writer,
+            readers: ReaderPool::new(db_path, DEFAULT_READER_POOL_SIZE),
+        })
+    }
+
+    /// Ejecuta una operación de escritura en el actor escritor
+    pub fn write<T, F>(&self, op: F) -> Result<T>
+    where
+        F: FnOnce(&ChunkingOrchestrator) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.writer.execute(op)
+    }
+
+    /// Conexión de solo lectura del pool
+    pub fn read(&self) -> Result<PooledConnection<'_>> {
+        self.readers.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::get_active_errors;
+
+    fn temp_db() -> PathBuf {
+        std::env::temp_dir().join(format!("opcode-writer-{}.db", uuid::Uuid::new_v4()))
+    }
+
+    fn remove_db(path: &Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            std::fs::remove_file(format!("{}{}", path.display(), suffix)).ok();
+        }
+    }
+
+    #[test]
+    fn test_writes_are_applied_in_submission_order() {
+        let path = temp_db();
+        let store = ChunkStore::open(&path).unwrap();
+
+        let ids: Vec<i64> = (0..20)
+            .map(|i| {
+                store
+                    .write(move |orchestrator| {
+                        orchestrator.log_error("/p", "runtime", &format!("error {}", i), None, None)
+                    })
+                    .unwrap()
+            })
+            .collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+
+        let reader = store.read().unwrap();
+        assert_eq!(get_active_errors(&reader, "/p").unwrap().len(), 20);
+        assert!(reader.execute("DELETE FROM error_logs", []).is_err());
+        drop(reader);
+        drop(store);
+
+        remove_db(&path);
+    }
+
+    #[test]
+    fn test_writer_survives_failed_and_panicking_ops() {
+        let path = temp_db();
+        let store = ChunkStore::open(&path).unwrap();
+
+        assert!(store
+            .write(|_| -> Result<()> { Err(anyhow!("boom")) })
+            .is_err());
+        assert!(store.write(|_| -> Result<()> { panic!("boom") }).is_err());
+        let id = store
+            .write(|orchestrator| orchestrator.log_error("/p", "runtime", "ok", None, None))
+            .unwrap();
+        assert!(id > 0);
+        drop(store);
+
+        remove_db(&path);
+    }
+}
@@ -0,0 +1,10 @@
+from dataclasses import dataclass
+
+
+@dataclass
+class Discount:
+    percent: int
+
+
+def apply_discount(cents, discount):
+    return cents - cents * discount.percent // 100
@@ -0,0 +1,25 @@
+pub struct Invoice {
+    amounts: Vec<u32>,
+}
+
+impl Invoice {
+    pub fn new(amounts: &[u32]) -> Self {
+        Invoice {
+            amounts: amounts.to_vec(),
+        }
+    }
+
+    pub fn sum(&self) -> u32 {
+        self.amounts.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_amounts() {
+        assert_eq!(Invoice::new(&[1, 2]).sum(), 3);
+    }
+}
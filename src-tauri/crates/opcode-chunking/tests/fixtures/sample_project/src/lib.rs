@@ -0,0 +1,8 @@
+pub mod billing;
+
+use billing::Invoice;
+
+pub fn total(amounts: &[u32]) -> u32 {
+    let invoice = Invoice::new(amounts);
+    invoice.sum()
+}
@@ -0,0 +1,9 @@
+from app.pricing import Discount, apply_discount
+
+
+def test_apply_discount():
+    assert apply_discount(1000, Discount(percent=10)) == 900
+
+
+def test_no_discount():
+    assert apply_discount(1000, Discount(percent=0)) == 1000
@@ -0,0 +1,14 @@
+import { describe, expect, it } from "vitest";
+import { Cart, cartTotal } from "./cart";
+
+describe("cart", () => {
+  it("sums line prices", () => {
+    expect(cartTotal([{ name: "tea", cents: 250 }])).toBe(250);
+  });
+
+  it("formats the label", () => {
+    const cart = new Cart();
+    cart.add({ name: "tea", cents: 250 });
+    expect(cart.label()).toBe("$2.50");
+  });
+});
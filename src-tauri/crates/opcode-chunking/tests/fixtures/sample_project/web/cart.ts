@@ -0,0 +1,21 @@
+import { formatPrice } from "./format";
+
+export interface CartLine {
+  name: string;
+  cents: number;
+}
+
+export const cartTotal = (lines: CartLine[]) =>
+  lines.reduce((acc, line) => acc + line.cents, 0);
+
+export class Cart {
+  private lines: CartLine[] = [];
+
+  add(line: CartLine): void {
+    this.lines.push(line);
+  }
+
+  label(): string {
+    return formatPrice(cartTotal(this.lines));
+  }
+}
@@ -0,0 +1,188 @@
+//! Golden tests: indexa el proyecto de ejemplo de `tests/fixtures/sample_project`
+//! (Rust, TypeScript y Python) con el orquestador completo y compara conteos de
+//! chunks, entidades y relaciones con `tests/fixtures/sample_project.golden.json`.
+//!
+//! Si un cambio en los extractores es intencional, regenerar el golden con
+//! `UPDATE_GOLDEN=1 cargo test -p opcode-chunking --test golden` y revisar el diff
+
+use opcode_chunking::types::{ChunkType, ChunkingOptions, ChunkingResult};
+use opcode_chunking::ChunkingOrchestrator;
+use rusqlite::{params, Connection};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const FIXTURE: &str = "tests/fixtures/sample_project";
+const GOLDEN: &str = "tests/fixtures/sample_project.golden.json";
+
+/// Copia el fixture a un directorio temporal: el orquestador no debe ver el repo
+/// de opcode (ni su .gitignore) alrededor del proyecto
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+/// `tipo:archivo`, `tipo:entidad` (chunks de módulo) o `tipo:archivo#entidad`
+fn chunk_label(chunk_type: &str, file_path: Option<String>, entity: Option<String>) -> String {
+    match (file_path, entity) {
+        (Some(file), Some(entity)) => format!("{}:{}#{}", chunk_type, file, entity),
+        (Some(file), None) => format!("{}:{}", chunk_type, file),
+        (None, Some(entity)) => format!("{}:{}", chunk_type, entity),
+        (None, None) => chunk_type.to_string(),
+    }
+}
+
+/// Resumen estable del índice: sin ids, hashes, fechas ni rutas absolutas
+fn summarize(conn: &Connection, project: &str) -> Value {
+    let mut stmt = conn
+        .prepare(
+            "SELECT chunk_type, COUNT(*) FROM chunks
+             WHERE project_path = ?1 AND deleted_at IS NULL GROUP BY chunk_type",
+        )
+        .unwrap();
+    let chunk_counts: BTreeMap<String, i64> = stmt
+        .query_map(params![project], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap();
+
+    let mut entities: BTreeMap<&str, BTreeMap<String, BTreeSet<String>>> = BTreeMap::new();
+    for chunk_type in [ChunkType::Ast, ChunkType::Tests] {
+        let mut stmt = conn
+            .prepare(
+                "SELECT file_path, entity_name FROM chunks
+                 WHERE project_path = ?1 AND chunk_type = ?2 AND deleted_at IS NULL
+                   AND file_path IS NOT NULL AND entity_name IS NOT NULL",
+            )
+            .unwrap();
+        let rows = stmt
+            .query_map(params![project, chunk_type.as_str()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .unwrap();
+        let by_file = entities.entry(chunk_type.as_str()).or_default();
+        for row in rows {
+            let (file_path, entity) = row.unwrap();
+            by_file.entry(file_path).or_default().insert(entity);
+        }
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.relationship_type, f.chunk_type, f.file_path, f.entity_name,
+                    t.chunk_type, t.file_path, t.entity_name
+             FROM chunk_relationships r
+             JOIN chunks f ON f.id = r.from_chunk_id
+             JOIN chunks t ON t.id = r.to_chunk_id
+             WHERE f.project_path = ?1 AND f.deleted_at IS NULL AND t.deleted_at IS NULL",
+        )
+        .unwrap();
+    let relationships: BTreeSet<String> = stmt
+        .query_map(params![project], |row| {
+            Ok(format!(
+                "{} {} -> {}",
+                row.get::<_, String>(0)?,
+                chunk_label(&row.get::<_, String>(1)?, row.get(2)?, row.get(3)?),
+                chunk_label(&row.get::<_, String>(4)?, row.get(5)?, row.get(6)?),
+            ))
+        })
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap();
+
+    json!({
+        "chunk_counts": chunk_counts,
+        "entities": entities,
+        "relationships": relationships,
+    })
+}
+
+/// Indexa una copia del fixture; devuelve el orquestador, la ruta del proyecto
+/// (ya borrada del disco) y el resultado
+fn index_fixture() -> (ChunkingOrchestrator, String, ChunkingResult) {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let dir = std::env::temp_dir().join(format!("opcode-golden-{}", uuid::Uuid::new_v4()));
+    copy_dir(&manifest_dir.join(FIXTURE), &dir);
+    let project = dir.to_string_lossy().to_string();
+
+    let orchestrator = ChunkingOrchestrator::new(Connection::open_in_memory().unwrap()).unwrap();
+    // Sin historial: el fixture copiado no es un repositorio git
+    let options = ChunkingOptions {
+        chunk_types: vec![
+            ChunkType::RawSource,
+            ChunkType::Ast,
+            ChunkType::Callgraph,
+            ChunkType::Tests,
+            ChunkType::StateConfig,
+            ChunkType::ProjectMetadata,
+        ],
+        ..Default::default()
+    };
+    let result = orchestrator.process_project(&project, &options).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(
+        result.errors.is_empty(),
+        "indexing errors: {:?}",
+        result.errors
+    );
+    (orchestrator, project, result)
+}
+
+#[test]
+fn test_sample_project_matches_golden() {
+    let (orchestrator, project, _) = index_fixture();
+    let summary = summarize(orchestrator.connection(), &project);
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let golden_path = manifest_dir.join(GOLDEN);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let pretty = serde_json::to_string_pretty(&summary).unwrap();
+        std::fs::write(&golden_path, pretty + "\n").unwrap();
+        return;
+    }
+    let golden: Value =
+        serde_json::from_str(&std::fs::read_to_string(&golden_path).unwrap()).unwrap();
+    assert!(
+        summary == golden,
+        "index of {} differs from {} (UPDATE_GOLDEN=1 to regenerate):\n{}",
+        FIXTURE,
+        GOLDEN,
+        serde_json::to_string_pretty(&summary).unwrap()
+    );
+}
+
+#[test]
+fn test_internal_import_becomes_depends_on() {
+    let (orchestrator, project, result) = index_fixture();
+
+    // `web/cart.ts` importa `./format`: el raw source de un archivo depende del otro
+    let edges: Vec<(String, String)> = orchestrator
+        .connection()
+        .prepare(
+            "SELECT f.file_path, t.file_path
+             FROM chunk_relationships r
+             JOIN chunks f ON f.id = r.from_chunk_id
+             JOIN chunks t ON t.id = r.to_chunk_id
+             WHERE r.relationship_type = 'depends_on' AND f.project_path = ?1
+               AND f.chunk_type = 'raw_source' AND t.chunk_type = 'raw_source'
+               AND f.file_path = 'web/cart.ts'",
+        )
+        .unwrap()
+        .query_map(params![project], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap();
+    assert_eq!(
+        edges,
+        vec![("web/cart.ts".to_string(), "web/format.ts".to_string())]
+    );
+    assert!(result.relationships_created >= edges.len());
+}
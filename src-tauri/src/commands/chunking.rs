@@ -1,33 +1,225 @@
-use crate::chunking::business_rules::{get_pending_rules, validate_business_rule};
-use crate::chunking::errors::{get_active_errors, resolve_error};
-use crate::chunking::storage::{get_snapshots, query_chunks};
+use super::claude::get_claude_dir;
+use crate::chunking::advisories::{load_advisory_db, scan_vulnerabilities};
+use crate::chunking::analytics::{
+    chunks_over_time, error_rates, index_freshness, snapshot_cadence, DEFAULT_ANALYTICS_DAYS,
+};
+use crate::chunking::bench::DEFAULT_BENCH_ITERATIONS;
+use crate::chunking::build_errors::import_build_output;
+use crate::chunking::business_rules::{
+    get_pending_rules, tag_business_rule, validate_business_rule,
+};
+use crate::chunking::callgraph::find_dependency_usages as find_usages;
+use crate::chunking::changelog::generate_changelog;
+use crate::chunking::chunk_versions::diff_chunk_versions;
+use crate::chunking::code_extensions::{
+    add_code_extension, remove_code_extension, resolve_code_extensions,
+};
+use crate::chunking::conformance::{check_rule_conformance, save_rule_check};
+use crate::chunking::complexity::get_complexity_report as build_complexity_report;
+use crate::chunking::content_store::{compact_chunk_contents, get_content_store_stats};
+use crate::chunking::context_pack::{build_context_pack, save_budget_weights, DEFAULT_TOKEN_BUDGET};
+use crate::chunking::custom_chunks::{create_custom_chunk, update_chunk_metadata};
+use crate::chunking::dependency_audit::audit_dependencies;
+use crate::chunking::embedding_providers::{
+    create_provider, get_embedding_settings, set_embedding_backend,
+};
+use crate::chunking::embeddings::{embed_query, search_embeddings};
+use crate::chunking::entry_points::get_entry_points;
+use crate::chunking::errors::{
+    get_active_errors, get_error_clusters, resolve_error, DEFAULT_CLUSTER_SIMILARITY,
+};
+use crate::chunking::git_hooks::{install_git_hooks, process_reindex_trigger, uninstall_git_hooks};
+use crate::chunking::git_notes::{import_commit_notes, write_commit_notes};
+use crate::chunking::graph_export::{export_callgraph as export_graph, export_cypher};
+use crate::chunking::graph_metrics::{compute_graph_metrics, get_graph_metrics};
+use crate::chunking::hashing::{get_project_hashing, set_hash_algorithm};
+use crate::chunking::health::get_project_health;
+use crate::chunking::i18n::get_i18n_report;
+use crate::chunking::index_merge::{
+    export_index, load_index_export, merge_index, save_index_export,
+};
+use crate::chunking::index_profile::build_index_profile;
+use crate::chunking::issues::{fetch_issues, import_issues};
+use crate::chunking::lint::{get_lint_offenders, import_lint_output};
+use crate::chunking::logical_projects::{
+    create_logical_snapshot, query_logical_chunks, register_logical_project, LOGICAL_PROJECT_SCHEME,
+};
+use crate::chunking::lsp_diagnostics::{
+    clear_lsp_diagnostics, get_lsp_diagnostics, get_open_diagnostics, publish_lsp_diagnostics,
+};
+use crate::chunking::parse_failures::clear_parse_failures;
+use crate::chunking::path_guard::{
+    validate_export_path, validate_import_path, validate_project_path,
+};
+use crate::chunking::project_config::resolve_options;
+use crate::chunking::publish::{
+    open_pull_request, prepare_publish, push_snapshot_branch, read_provider_token,
+    record_pull_request, resolve_remote, store_provider_token,
+};
+use crate::chunking::ranking::rank_chunks_for_task;
+use crate::chunking::read_only::ensure_index_writable;
+use crate::chunking::relationships::rebuild_relationships;
+use crate::chunking::release_notes::{build_release_notes, release_notes_prompt};
+use crate::chunking::remote_repos::DEFAULT_CLONE_DEPTH;
+use crate::chunking::review::build_review_context;
+use crate::chunking::risk::assess_risk;
+use crate::chunking::rule_proposals::propose_rules_after_agent_snapshot;
+use crate::chunking::source_range::get_source_range;
+use crate::chunking::sessions::{
+    close_agent_session, get_agent_session_report, start_agent_session,
+};
+use crate::chunking::sql_queries::find_table_queries;
+use crate::chunking::staleness::mark_stale_chunks;
+use crate::chunking::storage::{
+    delete_logical_project, fetch_chunks_page, get_agent_sessions, get_benchmark_runs,
+    get_chunk_by_id, get_chunk_versions, get_chunk_with_context, get_chunks_by_ids,
+    get_context_budget_weights, get_context_deliveries, get_context_rule_pinning,
+    get_index_operations, get_logical_projects, get_parse_failures, get_read_only_project,
+    get_read_only_projects, get_remote_repositories, get_secret_findings, get_security_findings,
+    get_snapshot, get_snapshots, query_chunks, restore_deleted_chunks, set_context_rule_pinning,
+    set_read_only_project,
+};
+use crate::chunking::syntax_errors::get_syntax_errors as list_syntax_errors;
+use crate::chunking::test_results::{get_failing_tests, import_junit_report};
+use crate::chunking::test_selection::suggest_tests_for_changes;
+use crate::chunking::tauri_surface::get_tauri_surface;
+use crate::chunking::tombstones::purge_expired;
 use crate::chunking::types::*;
-use crate::chunking::ChunkingOrchestrator;
-use anyhow::Result;
+use crate::chunking::ui_components::get_ui_routes;
+use crate::chunking::undo::undo_last_operation;
+use crate::chunking::version_bump::suggest_version_bump;
+use crate::chunking::writer::PooledConnection;
+use crate::chunking::{ChunkStore, ChunkingOrchestrator};
+use anyhow::{Context, Result};
 use rusqlite::Connection;
-use std::sync::Mutex;
+use std::collections::BTreeSet;
 use tauri::{AppHandle, Manager, State};
 
-/// Estado global del sistema de chunking
-pub struct ChunkingState(pub Mutex<Connection>);
+/// Estado global del sistema de chunking: escrituras por el actor escritor,
+/// lecturas desde el pool de conexiones de solo lectura
+pub struct ChunkingState(pub ChunkStore);
+
+impl ChunkingState {
+    /// Conexión de solo lectura (no espera a las escrituras en curso)
+    fn reader(&self) -> Result<PooledConnection<'_>, String> {
+        self.0.read().map_err(|e| e.to_string())
+    }
+
+    /// Encola una escritura sobre la conexión del actor escritor y espera su resultado
+    fn write<T, F>(&self, op: F) -> Result<T, String>
+    where
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.0
+            .write(move |orchestrator| op(orchestrator.connection()))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Encola una operación del orquestador (indexación, snapshots con reindexación)
+    fn orchestrate<T, F>(&self, op: F) -> Result<T, String>
+    where
+        F: FnOnce(&ChunkingOrchestrator) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.0.write(op).map_err(|e| e.to_string())
+    }
+
+    /// Rechaza los project_path que no son proyectos registrados (`~/.claude/projects`,
+    /// proyectos lógicos o repositorios remotos); lo usan todos los comandos de chunking
+    fn check_project(&self, project_path: &str) -> Result<(), String> {
+        let conn = self.reader()?;
+        let projects_dir = get_claude_dir().ok().map(|dir| dir.join("projects"));
+        validate_project_path(&conn, project_path, projects_dir.as_deref())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Como `check_project`, pero rechaza además los proyectos con índice de solo
+    /// lectura; lo usan los comandos que reindexan o crean snapshots
+    fn check_writable(&self, project_path: &str) -> Result<(), String> {
+        self.check_project(project_path)?;
+        let conn = self.reader()?;
+        ensure_index_writable(&conn, project_path).map_err(|e| e.to_string())
+    }
+
+    /// `check_project` sobre el proyecto de cada chunk devuelto (las consultas sin
+    /// `project_path` y las listas de ids pueden cruzar proyectos)
+    fn check_chunks(&self, chunks: &[Chunk]) -> Result<(), String> {
+        let projects: BTreeSet<&str> = chunks
+            .iter()
+            .map(|chunk| chunk.project_path.as_str())
+            .collect();
+        for project_path in projects {
+            self.check_project(project_path)?;
+        }
+        Ok(())
+    }
+
+    /// Proyecto dueño del chunk, validado con `check_project`
+    fn check_chunk(&self, chunk_id: i64) -> Result<String, String> {
+        let project_path = {
+            let conn = self.reader()?;
+            get_chunk_by_id(&conn, chunk_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Chunk not found: {}", chunk_id))?
+                .project_path
+        };
+        self.check_project(&project_path)?;
+        Ok(project_path)
+    }
+
+    /// Proyecto dueño del snapshot, validado con `check_project`
+    fn check_snapshot(&self, snapshot_id: i64) -> Result<String, String> {
+        let project_path = {
+            let conn = self.reader()?;
+            get_snapshot(&conn, snapshot_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Snapshot not found: {}", snapshot_id))?
+                .project_path
+        };
+        self.check_project(&project_path)?;
+        Ok(project_path)
+    }
+
+    /// `check_project` sobre un proyecto lógico (`logical://nombre`)
+    fn check_logical_project(&self, name: &str) -> Result<(), String> {
+        self.check_project(&format!("{}{}", LOGICAL_PROJECT_SCHEME, name))
+    }
+}
 
 /// Inicializa el sistema de chunking para la aplicación
-pub fn init_chunking_system(app: &AppHandle) -> Result<Connection> {
+pub fn init_chunking_system(app: &AppHandle) -> Result<ChunkStore> {
     let app_dir = app
         .path()
         .app_data_dir()
         .expect("Failed to get app data dir");
     std::fs::create_dir_all(&app_dir).expect("Failed to create app data dir");
 
-    let db_path = app_dir.join("chunks.db");
-    let conn = Connection::open(db_path)?;
+    // El actor escritor crea la base (WAL) e inicializa el esquema
+    ChunkStore::open(&app_dir.join("chunks.db"))
+}
+
+/// Directorio de la caché de clones de repositorios remotos
+const REMOTE_REPOS_DIR: &str = "remote-repos";
 
-    // Inicializar esquema
-    crate::chunking::storage::init_chunk_database(&conn)?;
+/// Directorio de la app para los archivos exportados (índices, perfiles, grafos)
+const EXPORTS_DIR: &str = "exports";
 
-    Ok(conn)
+/// `<app data>/exports`; se crea si no existe. Las exportaciones escriben ahí o dentro
+/// del proyecto (`path_guard::validate_export_path`)
+fn exports_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(EXPORTS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
 }
 
+/// Tamaño de página por defecto al recorrer chunks por cursor
+const DEFAULT_CHUNK_PAGE_SIZE: usize = 500;
+
 /// Procesa un proyecto completo y genera todos los chunks
 #[tauri::command]
 pub async fn process_project_chunks(
@@ -35,34 +227,374 @@ pub async fn process_project_chunks(
     project_path: String,
     options: Option<ChunkingOptions>,
 ) -> Result<ChunkingResult, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    let orchestrator = ChunkingOrchestrator::new(Connection::open_in_memory().map_err(|e| e.to_string())?)
-        .map_err(|e| e.to_string())?;
+    chunking_state.check_writable(&project_path)?;
+    let opts = options.unwrap_or_default();
+    chunking_state
+        .orchestrate(move |orchestrator| orchestrator.process_project(&project_path, &opts))
+}
+
+/// Indexa solo un subdirectorio del proyecto (relativo a su raíz) con las mismas opciones
+#[tauri::command]
+pub async fn process_project_path(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    subpath: String,
+    options: Option<ChunkingOptions>,
+) -> Result<ChunkingResult, String> {
+    chunking_state.check_writable(&project_path)?;
+    let opts = options.unwrap_or_default();
+    chunking_state.orchestrate(move |orchestrator| {
+        orchestrator.process_project_path(&project_path, &subpath, &opts)
+    })
+}
 
-    // Usar la conexión del state en lugar de crear una nueva
+/// Reindexa un proyecto desde cero; los chunks que no se regeneran quedan en la
+/// papelera y se recuperan con `undo_last_operation_command`
+#[tauri::command]
+pub async fn reset_project_index_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    options: Option<ChunkingOptions>,
+) -> Result<ChunkingResult, String> {
+    chunking_state.check_writable(&project_path)?;
     let opts = options.unwrap_or_default();
+    chunking_state.orchestrate(move |orchestrator| orchestrator.reset_project(&project_path, &opts))
+}
+
+/// Tamaño del almacén de contenido deduplicado y bytes ahorrados
+#[tauri::command]
+pub async fn get_content_store_stats_command(
+    chunking_state: State<'_, ChunkingState>,
+) -> Result<ContentStoreStats, String> {
+    let conn = chunking_state.reader()?;
+    get_content_store_stats(&conn).map_err(|e| e.to_string())
+}
+
+/// Mueve a blobs el contenido de los chunks guardados antes de la deduplicación y
+/// elimina los blobs huérfanos
+#[tauri::command]
+pub async fn compact_chunk_contents_command(
+    chunking_state: State<'_, ChunkingState>,
+) -> Result<ContentCompactionReport, String> {
+    chunking_state.write(compact_chunk_contents)
+}
+
+/// Marca los chunks cuyos archivos cambiaron desde la última indexación, sin reindexar
+#[tauri::command]
+pub async fn check_chunk_staleness(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<StalenessReport, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| mark_stale_chunks(conn, &project_path))
+}
+
+/// Reindexa solo los archivos con chunks desactualizados
+#[tauri::command]
+pub async fn refresh_stale_chunks(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<ChunkingResult, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.orchestrate(move |orchestrator| orchestrator.refresh_stale_chunks(&project_path))
+}
+
+/// Reconstruye en bloque las relaciones inferidas de los chunks existentes
+#[tauri::command]
+pub async fn rebuild_relationships_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<RelationshipRebuildReport, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| rebuild_relationships(conn, &project_path))
+}
+
+/// Deshace la última operación destructiva del proyecto (borrado, prune o reset)
+#[tauri::command]
+pub async fn undo_last_operation_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Option<UndoResult>, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| undo_last_operation(conn, &project_path))
+}
+
+/// Historial de operaciones destructivas del proyecto, la más reciente primero
+#[tauri::command]
+pub async fn get_index_operations_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<IndexOperation>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_index_operations(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Profundiza el historial de un clon superficial (depth = None trae el historial completo).
+/// Retorna si el repositorio sigue siendo superficial
+#[tauri::command]
+pub async fn deepen_commit_history(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    depth: Option<i32>,
+) -> Result<bool, String> {
+    chunking_state.check_project(&project_path)?;
+    tokio::task::spawn_blocking(move || {
+        crate::chunking::commits::deepen_commit_history(&project_path, depth)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
 
-    // Nota: Aquí necesitamos refactorizar para pasar la conexión existente
-    // Por ahora, retornaremos un resultado de ejemplo
-    Ok(ChunkingResult {
-        project_path: project_path.clone(),
-        chunks_created: 0,
-        chunks_updated: 0,
-        relationships_created: 0,
-        errors: vec!["Chunking system initialized. Full processing coming soon.".to_string()],
-        started_at: chrono::Utc::now(),
-        completed_at: chrono::Utc::now(),
+/// Escribe los resúmenes de commits y snapshots del proyecto en `refs/notes/opcode`
+#[tauri::command]
+pub async fn write_git_notes(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<GitNotesResult, String> {
+    chunking_state.check_writable(&project_path)?;
+    let notes_written =
+        chunking_state.write(move |conn| write_commit_notes(conn, &project_path))?;
+    Ok(GitNotesResult {
+        notes_written,
+        notes_imported: 0,
+        snapshots_restored: 0,
     })
 }
 
+/// Importa `refs/notes/opcode` y restaura los snapshots que falten en la base de datos
+#[tauri::command]
+pub async fn import_git_notes(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<GitNotesResult, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| import_commit_notes(conn, &project_path))
+}
+
+/// Instala los hooks post-commit/post-merge que registran archivos para reindexar
+#[tauri::command]
+pub async fn install_git_hooks_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<String>, String> {
+    chunking_state.check_writable(&project_path)?;
+    install_git_hooks(&project_path).map_err(|e| e.to_string())
+}
+
+/// Quita el bloque de opcode de los hooks del repositorio
+#[tauri::command]
+pub async fn uninstall_git_hooks_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<String>, String> {
+    chunking_state.check_project(&project_path)?;
+    uninstall_git_hooks(&project_path).map_err(|e| e.to_string())
+}
+
+/// Reindexa los archivos registrados por los hooks (commits hechos fuera de la app)
+#[tauri::command]
+pub async fn process_git_hook_trigger(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Option<ChunkingResult>, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| process_reindex_trigger(conn, &project_path))
+}
+
 /// Busca chunks según criterios
 #[tauri::command]
 pub async fn search_chunks(
     chunking_state: State<'_, ChunkingState>,
     query: ChunkQuery,
 ) -> Result<Vec<Chunk>, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    query_chunks(&conn, &query).map_err(|e| e.to_string())
+    if let Some(project_path) = &query.project_path {
+        chunking_state.check_project(project_path)?;
+    }
+    let chunks = {
+        let conn = chunking_state.reader()?;
+        query_chunks(&conn, &query).map_err(|e| e.to_string())?
+    };
+    chunking_state.check_chunks(&chunks)?;
+    Ok(chunks)
+}
+
+/// Chunks completos por lista de ids en una sola consulta, en el orden pedido
+#[tauri::command]
+pub async fn get_chunks_by_ids_command(
+    chunking_state: State<'_, ChunkingState>,
+    chunk_ids: Vec<i64>,
+) -> Result<Vec<Chunk>, String> {
+    let chunks = {
+        let conn = chunking_state.reader()?;
+        get_chunks_by_ids(&conn, &chunk_ids).map_err(|e| e.to_string())?
+    };
+    chunking_state.check_chunks(&chunks)?;
+    Ok(chunks)
+}
+
+/// Página de chunks por cursor (id ascendente) para recorrer resultados grandes
+/// sin cargarlos todos en un solo payload
+#[tauri::command]
+pub async fn fetch_chunks_page_command(
+    chunking_state: State<'_, ChunkingState>,
+    query: ChunkQuery,
+    after_id: Option<i64>,
+    page_size: Option<usize>,
+) -> Result<ChunkPage, String> {
+    if let Some(project_path) = &query.project_path {
+        chunking_state.check_project(project_path)?;
+    }
+    let page = {
+        let conn = chunking_state.reader()?;
+        fetch_chunks_page(
+            &conn,
+            &query,
+            after_id,
+            page_size.unwrap_or(DEFAULT_CHUNK_PAGE_SIZE),
+        )
+        .map_err(|e| e.to_string())?
+    };
+    chunking_state.check_chunks(&page.chunks)?;
+    Ok(page)
+}
+
+/// Lista los archivos que importan un paquete y/o tipo de dependencia
+#[tauri::command]
+pub async fn find_dependency_usages(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    package: Option<String>,
+    kind: Option<DependencyKind>,
+) -> Result<Vec<DependencyUsage>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    find_usages(&conn, &project_path, package.as_deref(), kind.as_ref())
+        .map_err(|e| e.to_string())
+}
+
+/// Lista los puntos de entrada detectados en el proyecto (mains, bins, rutas,
+/// handlers exportados y comandos)
+#[tauri::command]
+pub async fn get_entry_points_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<EntryPoint>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_entry_points(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Superficie de comandos Tauri del proyecto: cada `#[tauri::command]`, sus
+/// parámetros y los `invoke` del frontend que lo llaman
+#[tauri::command]
+pub async fn get_tauri_surface_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Option<TauriSurface>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_tauri_surface(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Rutas del frontend del proyecto con el componente que renderiza cada una;
+/// con `path`, solo esa ruta
+#[tauri::command]
+pub async fn get_ui_routes_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    path: Option<String>,
+) -> Result<Vec<UiRoute>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_ui_routes(&conn, &project_path, path.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Claves de traducción sin traducir en algún locale, sin usar o usadas sin definir
+#[tauri::command]
+pub async fn get_i18n_report_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<I18nReport, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_i18n_report(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Consultas SQL del proyecto que leen o escriben una tabla
+#[tauri::command]
+pub async fn find_table_queries_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    table: String,
+) -> Result<Vec<TableQuery>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    find_table_queries(&conn, &project_path, &table).map_err(|e| e.to_string())
+}
+
+/// Reporta dependencias declaradas sin uso y usadas sin declarar, por manifiesto
+#[tauri::command]
+pub async fn audit_project_dependencies(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<DependencyAuditReport>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    audit_dependencies(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Escanea las dependencias del proyecto contra una base de advisories OSV local.
+/// Por defecto usa `<app_data_dir>/advisories` (funciona sin conexión)
+#[tauri::command]
+pub async fn scan_dependency_vulnerabilities(
+    app: AppHandle,
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    advisory_db_path: Option<String>,
+) -> Result<Vec<SecurityFinding>, String> {
+    chunking_state.check_project(&project_path)?;
+    let db_path = match advisory_db_path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("advisories"),
+    };
+    if !db_path.exists() {
+        return Err(format!("Advisory database not found: {}", db_path.display()));
+    }
+
+    let advisories = load_advisory_db(&db_path).map_err(|e| e.to_string())?;
+    chunking_state.write(move |conn| scan_vulnerabilities(conn, &project_path, &advisories))
+}
+
+/// Obtiene los hallazgos de seguridad del último escaneo
+#[tauri::command]
+pub async fn get_security_findings_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    package_name: Option<String>,
+) -> Result<Vec<SecurityFinding>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_security_findings(&conn, &project_path, package_name.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Obtiene los secretos detectados en el código indexado
+#[tauri::command]
+pub async fn get_secret_findings_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    severity: Option<String>,
+    file_path: Option<String>,
+) -> Result<Vec<SecretFinding>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_secret_findings(&conn, &project_path, severity.as_deref(), file_path.as_deref())
+        .map_err(|e| e.to_string())
 }
 
 /// Obtiene reglas de negocio pendientes de validación
@@ -71,7 +603,8 @@ pub async fn get_pending_business_rules(
     chunking_state: State<'_, ChunkingState>,
     project_path: String,
 ) -> Result<Vec<BusinessRule>, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
     get_pending_rules(&conn, &project_path).map_err(|e| e.to_string())
 }
 
@@ -83,14 +616,45 @@ pub async fn validate_business_rule_command(
     rule_description: String,
     user_correction: Option<String>,
 ) -> Result<(), String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    validate_business_rule(
-        &conn,
-        rule_id,
-        &rule_description,
-        user_correction.as_deref(),
-    )
-    .map_err(|e| e.to_string())
+    chunking_state.write(move |conn| {
+        validate_business_rule(
+            conn,
+            rule_id,
+            &rule_description,
+            user_correction.as_deref(),
+        )
+    })
+}
+
+/// Reemplaza las etiquetas de una regla de negocio ("critical" la fija en el contexto)
+#[tauri::command]
+pub async fn tag_business_rule_command(
+    chunking_state: State<'_, ChunkingState>,
+    rule_id: i64,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    chunking_state.write(move |conn| tag_business_rule(conn, rule_id, &tags))
+}
+
+/// Asigna (o quita) la comprobación automática de una regla de negocio
+#[tauri::command]
+pub async fn set_business_rule_check_command(
+    chunking_state: State<'_, ChunkingState>,
+    rule_id: i64,
+    check: Option<RuleCheck>,
+) -> Result<(), String> {
+    chunking_state.write(move |conn| save_rule_check(conn, rule_id, check.as_ref()))
+}
+
+/// Comprueba las reglas de negocio con comprobación automática contra el código actual
+#[tauri::command]
+pub async fn check_rule_conformance_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<RuleConformance>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    check_rule_conformance(&conn, &project_path).map_err(|e| e.to_string())
 }
 
 /// Obtiene snapshots de un proyecto
@@ -100,7 +664,8 @@ pub async fn get_project_snapshots(
     project_path: String,
     snapshot_type: Option<String>,
 ) -> Result<Vec<Snapshot>, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
 
     let st = snapshot_type.and_then(|s| {
         if s == "master" {
@@ -115,116 +680,1279 @@ pub async fn get_project_snapshots(
     get_snapshots(&conn, &project_path, st).map_err(|e| e.to_string())
 }
 
-/// Obtiene errores activos de un proyecto
+/// Obtiene el diff por archivo de un snapshot (para la vista de revisión). Va por el
+/// escritor: los snapshots sin diff guardado lo calculan desde su commit y lo guardan
 #[tauri::command]
-pub async fn get_project_errors(
+pub async fn get_snapshot_diff(
     chunking_state: State<'_, ChunkingState>,
-    project_path: String,
-) -> Result<Vec<ErrorLog>, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    get_active_errors(&conn, &project_path).map_err(|e| e.to_string())
+    snapshot_id: i64,
+) -> Result<SnapshotDiff, String> {
+    chunking_state.check_snapshot(snapshot_id)?;
+    chunking_state
+        .write(move |conn| crate::chunking::snapshots::get_snapshot_diff(conn, snapshot_id))
 }
 
-/// Marca un error como resuelto
+/// Sube la rama de un snapshot agent al remote y abre un PR (GitHub) o MR (GitLab).
+/// La URL queda en `metadata.pull_request` del snapshot
 #[tauri::command]
-pub async fn resolve_error_command(
+pub async fn publish_agent_snapshot(
     chunking_state: State<'_, ChunkingState>,
-    error_id: i64,
-) -> Result<(), String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    resolve_error(&conn, error_id).map_err(|e| e.to_string())
+    snapshot_id: i64,
+    remote: Option<String>,
+    target_branch: Option<String>,
+    title: Option<String>,
+    draft: Option<bool>,
+) -> Result<PullRequestInfo, String> {
+    chunking_state.check_snapshot(snapshot_id)?;
+    let plan = {
+        let conn = chunking_state.reader()?;
+        prepare_publish(
+            &conn,
+            snapshot_id,
+            remote.as_deref(),
+            target_branch.as_deref(),
+            title,
+            draft.unwrap_or(false),
+        )
+        .map_err(|e| e.to_string())?
+    };
+    let token = read_provider_token(plan.remote.provider).map_err(|e| e.to_string())?;
+
+    let push_plan = plan.clone();
+    let push_token = token.clone();
+    tokio::task::spawn_blocking(move || push_snapshot_branch(&push_plan, &push_token))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let pr = open_pull_request(&plan, &token)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let recorded = pr.clone();
+    chunking_state.write(move |conn| record_pull_request(conn, snapshot_id, &recorded))?;
+    Ok(pr)
 }
 
-/// Crea un snapshot master (user intent) con Git real
-/// Se ejecuta automáticamente ANTES de enviar un mensaje al agente
+/// Guarda en el keychain del sistema el token de GitHub/GitLab usado para publicar
 #[tauri::command]
-pub async fn create_master_snapshot(
+pub async fn save_git_provider_token(provider: GitProvider, token: String) -> Result<(), String> {
+    store_provider_token(provider, &token).map_err(|e| e.to_string())
+}
+
+/// Importa issues de GitHub/GitLab (según el remote) como chunks `issue`
+/// enlazados con los archivos que mencionan
+#[tauri::command]
+pub async fn import_project_issues(
     chunking_state: State<'_, ChunkingState>,
     project_path: String,
-    user_message: String,
-) -> Result<i64, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    crate::chunking::snapshots::create_master_snapshot_with_git(
-        &conn,
-        &project_path,
-        &user_message,
+    remote: Option<String>,
+    state: Option<String>,
+    limit: Option<usize>,
+) -> Result<IssueImportResult, String> {
+    chunking_state.check_project(&project_path)?;
+    let (_, remote) = resolve_remote(&project_path, remote.as_deref()).map_err(|e| e.to_string())?;
+    // Sin token solo se pueden leer repositorios públicos
+    let token = read_provider_token(remote.provider).ok();
+    let issues = fetch_issues(
+        &remote,
+        token.as_deref(),
+        state.as_deref().unwrap_or("open"),
+        limit.unwrap_or(500),
     )
-    .map_err(|e| e.to_string())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    chunking_state.write(move |conn| import_issues(conn, &project_path, &issues))
 }
 
-/// Crea un snapshot agent (agent execution) con Git real en rama paralela
-/// Se ejecuta automáticamente DESPUÉS de que el agente completa una ejecución
+/// Reúne el contexto para revisar un cambio (unified diff o snapshot): chunks
+/// modificados, archivos que los importan, tests que los cubren y reglas de negocio.
+/// Va por el escritor porque el diff de un snapshot puede guardarse al leerlo
 #[tauri::command]
-pub async fn create_agent_snapshot(
+pub async fn build_review_context_command(
     chunking_state: State<'_, ChunkingState>,
     project_path: String,
-    master_snapshot_id: i64,
-    message: String,
-    changed_files: Option<Vec<String>>,
-) -> Result<i64, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    crate::chunking::snapshots::create_agent_snapshot_with_git(
-        &conn,
-        &project_path,
-        master_snapshot_id,
-        &message,
-        changed_files,
-    )
-    .map_err(|e| e.to_string())
+    diff: Option<String>,
+    snapshot_id: Option<i64>,
+) -> Result<ReviewContext, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state
+        .write(move |conn| build_review_context(conn, &project_path, diff.as_deref(), snapshot_id))
 }
 
-/// Retrocede la rama master a un snapshot anterior (time travel)
-/// Usa git reset --hard y elimina snapshots master posteriores
+/// Ordena los chunks del proyecto por relevancia para una tarea (similitud léxica
+/// y cercanía a los archivos modificados recientemente)
 #[tauri::command]
-pub async fn rewind_master_snapshot(
+pub async fn rank_chunks_for_task_command(
     chunking_state: State<'_, ChunkingState>,
-    snapshot_id: i64,
-) -> Result<(), String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    crate::chunking::snapshots::rewind_master_to_snapshot_with_git(&conn, snapshot_id)
+    project_path: String,
+    task_text: String,
+    limit: Option<usize>,
+) -> Result<Vec<ScoredChunk>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    rank_chunks_for_task(&conn, &project_path, &task_text, limit.unwrap_or(20))
         .map_err(|e| e.to_string())
 }
 
-/// Propone una regla de negocio para validación
+/// Arma un paquete de contexto para una tarea dentro de un presupuesto de tokens,
+/// sin repetir regiones de código entre raw source, AST y entidades. La entrega
+/// queda registrada en context_deliveries (con la sesión del agente si se indica)
 #[tauri::command]
-pub async fn propose_business_rule_command(
+pub async fn build_context_pack_command(
     chunking_state: State<'_, ChunkingState>,
     project_path: String,
-    entity_name: String,
-    file_path: String,
-    ai_interpretation: String,
-) -> Result<i64, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    crate::chunking::business_rules::propose_business_rule(
-        &conn,
-        &project_path,
-        &entity_name,
-        &file_path,
-        &ai_interpretation,
-    )
-    .map_err(|e| e.to_string())
+    task_text: String,
+    token_budget: Option<usize>,
+    session_id: Option<String>,
+) -> Result<ContextPack, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| {
+        build_context_pack(
+            conn,
+            &project_path,
+            &task_text,
+            token_budget.unwrap_or(DEFAULT_TOKEN_BUDGET),
+            session_id.as_deref(),
+        )
+    })
 }
 
-/// Registra un error en el sistema
+/// Lista los paquetes de contexto entregados (qué chunks vio el agente),
+/// filtrando opcionalmente por sesión o snapshot
 #[tauri::command]
-pub async fn log_error_command(
+pub async fn get_context_deliveries_command(
     chunking_state: State<'_, ChunkingState>,
     project_path: String,
-    error_type: String,
-    message: String,
-    file_path: Option<String>,
-    stacktrace: Option<String>,
-) -> Result<i64, String> {
-    let conn = chunking_state.0.lock().map_err(|e| e.to_string())?;
-    crate::chunking::errors::log_error(
-        &conn,
-        &project_path,
-        &error_type,
-        &message,
-        file_path.as_deref(),
-        None,
-        stacktrace.as_deref(),
-        None,
-    )
-    .map_err(|e| e.to_string())
+    session_id: Option<String>,
+    snapshot_id: Option<i64>,
+) -> Result<Vec<ContextDelivery>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_context_deliveries(&conn, &project_path, session_id.as_deref(), snapshot_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Obtiene el reparto del presupuesto de contexto por tipo de chunk (None = sin reparto)
+#[tauri::command]
+pub async fn get_context_budget_weights_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Option<Vec<ChunkTypeWeight>>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_context_budget_weights(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Guarda el reparto del presupuesto de contexto por tipo de chunk (vacío = sin reparto)
+#[tauri::command]
+pub async fn set_context_budget_weights_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    weights: Vec<ChunkTypeWeight>,
+) -> Result<(), String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| save_budget_weights(conn, &project_path, &weights))
+}
+
+/// Obtiene qué reglas de negocio se fijan al inicio de cada paquete de contexto
+#[tauri::command]
+pub async fn get_context_rule_pinning_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<RulePinning, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_context_rule_pinning(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Configura qué reglas de negocio se fijan al inicio de cada paquete de contexto
+#[tauri::command]
+pub async fn set_context_rule_pinning_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    mode: RulePinning,
+) -> Result<(), String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| set_context_rule_pinning(conn, &project_path, mode))
+}
+
+/// Abre una sesión de agente enlazada al snapshot master (por defecto el activo)
+#[tauri::command]
+pub async fn start_agent_session_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    session_id: Option<String>,
+    master_snapshot_id: Option<i64>,
+    task: Option<String>,
+) -> Result<AgentSession, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| {
+        start_agent_session(
+            conn,
+            &project_path,
+            session_id.as_deref(),
+            master_snapshot_id,
+            task.as_deref(),
+        )
+    })
+}
+
+/// Cierra una sesión de agente (completed, failed o cancelled)
+#[tauri::command]
+pub async fn close_agent_session_command(
+    chunking_state: State<'_, ChunkingState>,
+    session_id: String,
+    status: AgentSessionStatus,
+) -> Result<AgentSession, String> {
+    chunking_state.write(move |conn| close_agent_session(conn, &session_id, status))
+}
+
+/// Lista las sesiones de agente de un proyecto
+#[tauri::command]
+pub async fn get_project_agent_sessions(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<AgentSession>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_agent_sessions(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Reporte completo de una sesión: snapshots, errores y chunks entregados
+#[tauri::command]
+pub async fn get_agent_session_report_command(
+    chunking_state: State<'_, ChunkingState>,
+    session_id: String,
+) -> Result<AgentSessionReport, String> {
+    let conn = chunking_state.reader()?;
+    get_agent_session_report(&conn, &session_id).map_err(|e| e.to_string())
+}
+
+/// Chunks creados por día y tipo (últimos `days` días, 30 por defecto)
+#[tauri::command]
+pub async fn get_chunks_over_time(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    days: Option<u32>,
+) -> Result<Vec<ChunksPerDay>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    chunks_over_time(&conn, &project_path, days.unwrap_or(DEFAULT_ANALYTICS_DAYS))
+        .map_err(|e| e.to_string())
+}
+
+/// Snapshots por día y ritmo entre snapshots master
+#[tauri::command]
+pub async fn get_snapshot_cadence(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    days: Option<u32>,
+) -> Result<SnapshotCadence, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    snapshot_cadence(&conn, &project_path, days.unwrap_or(DEFAULT_ANALYTICS_DAYS))
+        .map_err(|e| e.to_string())
+}
+
+/// Errores abiertos y resueltos por día
+#[tauri::command]
+pub async fn get_error_rates(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    days: Option<u32>,
+) -> Result<Vec<ErrorRatePerDay>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    error_rates(&conn, &project_path, days.unwrap_or(DEFAULT_ANALYTICS_DAYS))
+        .map_err(|e| e.to_string())
+}
+
+/// Antigüedad del índice por extensión de archivo
+#[tauri::command]
+pub async fn get_index_freshness(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<FileTypeFreshness>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    index_freshness(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Clona una URL Git en la caché de la app (shallow por defecto) y la indexa en modo
+/// solo lectura. Las escrituras quedan encoladas detrás de la indexación; las
+/// lecturas siguen disponibles desde el pool
+#[tauri::command]
+pub async fn index_remote_repository(
+    app: AppHandle,
+    chunking_state: State<'_, ChunkingState>,
+    url: String,
+    depth: Option<i32>,
+    options: Option<ChunkingOptions>,
+) -> Result<RemoteIndexResult, String> {
+    let cache_root = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(REMOTE_REPOS_DIR);
+    chunking_state.orchestrate(move |orchestrator| {
+        orchestrator.index_remote_repository(
+            &url,
+            &cache_root,
+            Some(depth.unwrap_or(DEFAULT_CLONE_DEPTH)),
+            &options.unwrap_or_default(),
+        )
+    })
+}
+
+/// Lista los repositorios remotos indexados
+#[tauri::command]
+pub async fn get_remote_repositories_command(
+    chunking_state: State<'_, ChunkingState>,
+) -> Result<Vec<RemoteRepository>, String> {
+    let conn = chunking_state.reader()?;
+    get_remote_repositories(&conn).map_err(|e| e.to_string())
+}
+
+/// Marca (o desmarca) el índice del proyecto como de solo lectura
+#[tauri::command]
+pub async fn set_project_read_only_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    read_only: bool,
+    reason: Option<String>,
+) -> Result<(), String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state
+        .write(move |conn| set_read_only_project(conn, &project_path, read_only, reason.as_deref()))
+}
+
+/// Marca de solo lectura del proyecto (None si es escribible)
+#[tauri::command]
+pub async fn get_project_read_only_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Option<ReadOnlyProject>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_read_only_project(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Lista los proyectos con índice de solo lectura
+#[tauri::command]
+pub async fn get_read_only_projects_command(
+    chunking_state: State<'_, ChunkingState>,
+) -> Result<Vec<ReadOnlyProject>, String> {
+    let conn = chunking_state.reader()?;
+    get_read_only_projects(&conn).map_err(|e| e.to_string())
+}
+
+/// Exporta el índice del proyecto (chunks, reglas y relaciones) a un archivo JSON para
+/// compartirlo con otro usuario
+#[tauri::command]
+pub async fn export_project_index(
+    app: AppHandle,
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    output_path: String,
+) -> Result<IndexExportSummary, String> {
+    chunking_state.check_project(&project_path)?;
+    let target = validate_export_path(&output_path, &exports_dir(&app)?, &project_path)
+        .map_err(|e| e.to_string())?;
+    let conn = chunking_state.reader()?;
+    let export = export_index(&conn, &project_path).map_err(|e| e.to_string())?;
+    save_index_export(&export, &target).map_err(|e| e.to_string())?;
+    Ok(IndexExportSummary {
+        path: target.to_string_lossy().to_string(),
+        chunks: export.chunks.len(),
+        business_rules: export.business_rules.len(),
+        relationships: export.relationships.len(),
+    })
+}
+
+/// Fusiona en el proyecto un índice exportado por otro usuario, por hash de contenido y
+/// fecha, y devuelve los conflictos de reglas y anotaciones
+#[tauri::command]
+pub async fn import_project_index(
+    app: AppHandle,
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    input_path: String,
+) -> Result<IndexMergeReport, String> {
+    chunking_state.check_writable(&project_path)?;
+    let source = validate_import_path(&input_path, &exports_dir(&app)?, &project_path)
+        .map_err(|e| e.to_string())?;
+    let export = load_index_export(&source).map_err(|e| e.to_string())?;
+    chunking_state.write(move |conn| merge_index(conn, &project_path, &export))
+}
+
+/// Perfil anónimo del índice (conteos, tamaños y tiempos, sin rutas ni contenido) para
+/// adjuntar a reportes de rendimiento; con `output_path` también se escribe como JSON
+#[tauri::command]
+pub async fn export_index_profile(
+    app: AppHandle,
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    output_path: Option<String>,
+) -> Result<IndexProfile, String> {
+    chunking_state.check_project(&project_path)?;
+    let target = match output_path {
+        Some(path) => Some(
+            validate_export_path(&path, &exports_dir(&app)?, &project_path)
+                .map_err(|e| e.to_string())?,
+        ),
+        None => None,
+    };
+    let conn = chunking_state.reader()?;
+    let profile = build_index_profile(&conn, &project_path).map_err(|e| e.to_string())?;
+    if let Some(target) = target {
+        let json = serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())?;
+        std::fs::write(&target, json).map_err(|e| e.to_string())?;
+    }
+    Ok(profile)
+}
+
+/// Benchmark del índice: throughput de una indexación en dry run y latencia de las
+/// consultas representativas. Se guarda con las regresiones respecto a la anterior
+#[tauri::command]
+pub async fn bench_index(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    iterations: Option<usize>,
+    options: Option<ChunkingOptions>,
+) -> Result<BenchmarkRun, String> {
+    chunking_state.check_project(&project_path)?;
+    let opts = options.unwrap_or_default();
+    chunking_state.orchestrate(move |orchestrator| {
+        orchestrator.bench_index(
+            &project_path,
+            &opts,
+            iterations.unwrap_or(DEFAULT_BENCH_ITERATIONS),
+        )
+    })
+}
+
+/// Ejecuciones del benchmark del proyecto, la más reciente primero
+#[tauri::command]
+pub async fn get_benchmark_runs_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<BenchmarkRun>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_benchmark_runs(&conn, &project_path, limit.unwrap_or(20)).map_err(|e| e.to_string())
+}
+
+/// Lista los archivos que tree-sitter no pudo parsear (skip list del AST)
+#[tauri::command]
+pub async fn get_parse_failures_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<ParseFailure>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_parse_failures(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Quita archivos de la skip list (todos si `file_paths` está vacío) para que la
+/// próxima indexación vuelva a parsearlos
+#[tauri::command]
+pub async fn clear_parse_failures_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    file_paths: Option<Vec<String>>,
+) -> Result<usize, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| {
+        clear_parse_failures(conn, &project_path, &file_paths.unwrap_or_default())
+    })
+}
+
+/// Extensiones que el proyecto indexa como código fuente (defaults, `opcode.toml`
+/// y las agregadas o quitadas desde la app)
+#[tauri::command]
+pub async fn get_code_extensions_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<String>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    effective_code_extensions(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Agrega una extensión de código al proyecto y retorna las extensiones efectivas.
+/// Los archivos nuevos se indexan en la próxima indexación
+#[tauri::command]
+pub async fn add_code_extension_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    extension: String,
+) -> Result<Vec<String>, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| {
+        add_code_extension(conn, &project_path, &extension)?;
+        effective_code_extensions(conn, &project_path)
+    })
+}
+
+/// Quita una extensión de código del proyecto (también una de las por defecto) y
+/// retorna las extensiones efectivas
+#[tauri::command]
+pub async fn remove_code_extension_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    extension: String,
+) -> Result<Vec<String>, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| {
+        remove_code_extension(conn, &project_path, &extension)?;
+        effective_code_extensions(conn, &project_path)
+    })
+}
+
+/// Algoritmo de hash del contenido e identificador anónimo del proyecto. Escribe:
+/// la sal del identificador se genera la primera vez
+#[tauri::command]
+pub async fn get_project_hashing_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<ProjectHashing, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| get_project_hashing(conn, &project_path))
+}
+
+/// Cambia el algoritmo de hash del contenido del proyecto desde la próxima indexación
+#[tauri::command]
+pub async fn set_hash_algorithm_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    algorithm: HashAlgorithm,
+) -> Result<ProjectHashing, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| set_hash_algorithm(conn, &project_path, algorithm))
+}
+
+/// Backend de embeddings del proyecto y si el binario incluye el backend local
+#[tauri::command]
+pub async fn get_embedding_settings_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<EmbeddingSettings, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_embedding_settings(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Elige el backend de embeddings del proyecto (HTTP o modelo local); `None` lo quita
+#[tauri::command]
+pub async fn set_embedding_backend_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    backend: Option<EmbeddingBackend>,
+) -> Result<EmbeddingSettings, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| set_embedding_backend(conn, &project_path, backend))
+}
+
+/// Calcula los embeddings pendientes del proyecto. La primera llamada embebe todos los
+/// chunks; desde entonces cada indexación refresca solo los que cambiaron
+#[tauri::command]
+pub async fn refresh_embeddings_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<EmbeddingRefreshReport, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.orchestrate(move |orchestrator| {
+        orchestrator
+            .refresh_embeddings(&project_path)?
+            .context("Project has no embedding backend")
+    })
+}
+
+/// Chunks más parecidos a la consulta según sus embeddings
+#[tauri::command]
+pub async fn semantic_search_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SemanticMatch>, String> {
+    chunking_state.check_project(&project_path)?;
+    let backend = {
+        let conn = chunking_state.reader()?;
+        get_embedding_settings(&conn, &project_path)
+            .map_err(|e| e.to_string())?
+            .backend
+            .ok_or("Project has no embedding backend")?
+    };
+
+    // La consulta se embebe fuera del escritor: un proveedor lento no bloquea escrituras
+    let query = tokio::task::spawn_blocking(move || {
+        let provider = create_provider(&backend)?;
+        embed_query(provider.as_ref(), &query)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    let conn = chunking_state.reader()?;
+    search_embeddings(&conn, &project_path, &query, limit.unwrap_or(20)).map_err(|e| e.to_string())
+}
+
+fn effective_code_extensions(conn: &Connection, project_path: &str) -> Result<Vec<String>> {
+    let (options, _) = resolve_options(project_path, &ChunkingOptions::default());
+    resolve_code_extensions(conn, project_path, &options.code_extensions)
+}
+
+/// Lista las versiones de un chunk (mismo archivo, tipo y entidad), la más antigua primero
+#[tauri::command]
+pub async fn get_chunk_versions_command(
+    chunking_state: State<'_, ChunkingState>,
+    chunk_id: i64,
+) -> Result<Vec<ChunkVersion>, String> {
+    chunking_state.check_chunk(chunk_id)?;
+    let conn = chunking_state.reader()?;
+    get_chunk_versions(&conn, chunk_id).map_err(|e| e.to_string())
+}
+
+/// Líneas de un archivo (1-based, inclusivas) desde su chunk raw source, vigente o
+/// de un snapshot, sin leer el filesystem. None si el archivo no está indexado
+#[tauri::command]
+pub async fn get_source_range_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    file_path: String,
+    start_line: usize,
+    end_line: usize,
+    snapshot_id: Option<i64>,
+) -> Result<Option<SourceRange>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_source_range(
+        &conn,
+        &project_path,
+        &file_path,
+        start_line,
+        end_line,
+        snapshot_id,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Un chunk con sus relaciones salientes y entrantes y el resumen de cada chunk
+/// relacionado, en una sola llamada
+#[tauri::command]
+pub async fn get_chunk_with_context_command(
+    chunking_state: State<'_, ChunkingState>,
+    chunk_id: i64,
+) -> Result<Option<ChunkWithContext>, String> {
+    chunking_state.check_chunk(chunk_id)?;
+    let conn = chunking_state.reader()?;
+    get_chunk_with_context(&conn, chunk_id).map_err(|e| e.to_string())
+}
+
+/// Guarda un chunk de contenido de usuario (nota de reunión, decisión de diseño...)
+#[tauri::command]
+pub async fn create_custom_chunk_command(
+    chunking_state: State<'_, ChunkingState>,
+    input: CustomChunkInput,
+) -> Result<Chunk, String> {
+    chunking_state.check_project(&input.project_path)?;
+    chunking_state.write(move |conn| create_custom_chunk(conn, &input))
+}
+
+/// Reemplaza la metadata de un chunk, o la combina con la actual si `merge`
+#[tauri::command]
+pub async fn update_chunk_metadata_command(
+    chunking_state: State<'_, ChunkingState>,
+    chunk_id: i64,
+    metadata: serde_json::Value,
+    merge: Option<bool>,
+) -> Result<Chunk, String> {
+    chunking_state.check_chunk(chunk_id)?;
+    chunking_state
+        .write(move |conn| update_chunk_metadata(conn, chunk_id, &metadata, merge.unwrap_or(false)))
+}
+
+/// Diff unificado del contenido entre dos versiones (numeradas desde 1) de un chunk
+#[tauri::command]
+pub async fn diff_chunk_versions_command(
+    chunking_state: State<'_, ChunkingState>,
+    chunk_id: i64,
+    from_version: usize,
+    to_version: usize,
+) -> Result<ChunkVersionDiff, String> {
+    chunking_state.check_chunk(chunk_id)?;
+    let conn = chunking_state.reader()?;
+    diff_chunk_versions(&conn, chunk_id, from_version, to_version).map_err(|e| e.to_string())
+}
+
+/// Saca de la papelera los chunks de un proyecto (o de un solo archivo)
+#[tauri::command]
+pub async fn restore_deleted_chunks_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    file_path: Option<String>,
+) -> Result<usize, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state
+        .write(move |conn| restore_deleted_chunks(conn, &project_path, file_path.as_deref()))
+}
+
+/// Elimina definitivamente los chunks con más de `grace_days` en la papelera
+/// (todos los proyectos si no se indica `project_path`)
+#[tauri::command]
+pub async fn purge_deleted_chunks_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: Option<String>,
+    grace_days: Option<u32>,
+) -> Result<usize, String> {
+    if let Some(project_path) = &project_path {
+        chunking_state.check_project(project_path)?;
+    }
+    let grace_days = grace_days.unwrap_or(ChunkingOptions::default().tombstone_grace_days);
+    chunking_state.write(move |conn| purge_expired(conn, project_path.as_deref(), grace_days))
+}
+
+/// Registra (o reemplaza) un proyecto lógico formado por varias raíces
+#[tauri::command]
+pub async fn register_logical_project_command(
+    chunking_state: State<'_, ChunkingState>,
+    name: String,
+    roots: Vec<ProjectRoot>,
+) -> Result<LogicalProject, String> {
+    for root in &roots {
+        chunking_state.check_project(&root.path)?;
+    }
+    chunking_state.write(move |conn| register_logical_project(conn, &name, &roots))
+}
+
+/// Lista los proyectos lógicos registrados
+#[tauri::command]
+pub async fn get_logical_projects_command(
+    chunking_state: State<'_, ChunkingState>,
+) -> Result<Vec<LogicalProject>, String> {
+    let conn = chunking_state.reader()?;
+    get_logical_projects(&conn).map_err(|e| e.to_string())
+}
+
+/// Elimina un proyecto lógico (los índices de sus raíces se conservan)
+#[tauri::command]
+pub async fn delete_logical_project_command(
+    chunking_state: State<'_, ChunkingState>,
+    name: String,
+) -> Result<bool, String> {
+    chunking_state.write(move |conn| delete_logical_project(conn, &name))
+}
+
+/// Busca chunks en todas las raíces de un proyecto lógico (rutas `raíz/archivo`)
+#[tauri::command]
+pub async fn search_logical_chunks(
+    chunking_state: State<'_, ChunkingState>,
+    name: String,
+    query: ChunkQuery,
+) -> Result<Vec<Chunk>, String> {
+    chunking_state.check_logical_project(&name)?;
+    let conn = chunking_state.reader()?;
+    query_logical_chunks(&conn, &name, &query).map_err(|e| e.to_string())
+}
+
+/// Crea un snapshot master en cada raíz con snapshots habilitados
+#[tauri::command]
+pub async fn create_logical_snapshot_command(
+    chunking_state: State<'_, ChunkingState>,
+    name: String,
+    user_message: String,
+) -> Result<Vec<LogicalSnapshot>, String> {
+    chunking_state.check_logical_project(&name)?;
+    chunking_state.write(move |conn| {
+        create_logical_snapshot(conn, &name, |root| {
+            crate::chunking::snapshots::create_master_snapshot_with_git(
+                conn,
+                &root.path,
+                &user_message,
+            )
+        })
+    })
+}
+
+/// Obtiene errores activos de un proyecto
+#[tauri::command]
+pub async fn get_project_errors(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<ErrorLog>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_active_errors(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Agrupa los errores de un proyecto por similitud, con ejemplos representativos
+#[tauri::command]
+pub async fn get_project_error_clusters(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    include_resolved: Option<bool>,
+    similarity: Option<f64>,
+) -> Result<Vec<ErrorCluster>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_error_clusters(
+        &conn,
+        &project_path,
+        include_resolved.unwrap_or(false),
+        similarity.unwrap_or(DEFAULT_CLUSTER_SIMILARITY),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Archivos que el indexador no pudo parsear por completo, con sus errores de sintaxis
+#[tauri::command]
+pub async fn get_syntax_errors(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<FileSyntaxErrors>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    list_syntax_errors(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Importa la salida JSON de un linter ("clippy" o "eslint") como diagnósticos del proyecto
+#[tauri::command]
+pub async fn import_lint_diagnostics(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    tool: String,
+    output: String,
+) -> Result<LintImportResult, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| import_lint_output(conn, &project_path, &tool, &output))
+}
+
+/// Obtiene los archivos con más diagnósticos de lint abiertos
+#[tauri::command]
+pub async fn get_lint_offenders_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<LintOffender>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_lint_offenders(&conn, &project_path, limit.unwrap_or(10)).map_err(|e| e.to_string())
+}
+
+/// Guarda un `textDocument/publishDiagnostics` de un editor o language server; reemplaza
+/// los diagnósticos anteriores del archivo
+#[tauri::command]
+pub async fn publish_lsp_diagnostics_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    payload: serde_json::Value,
+) -> Result<LspPublishResult, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| publish_lsp_diagnostics(conn, &project_path, &payload))
+}
+
+/// Diagnósticos LSP del proyecto o de un archivo
+#[tauri::command]
+pub async fn get_lsp_diagnostics_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    file_path: Option<String>,
+) -> Result<Vec<LspDiagnostic>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_lsp_diagnostics(&conn, &project_path, file_path.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Descarta los diagnósticos LSP del proyecto o de un archivo
+#[tauri::command]
+pub async fn clear_lsp_diagnostics_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    file_path: Option<String>,
+) -> Result<usize, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state
+        .write(move |conn| clear_lsp_diagnostics(conn, &project_path, file_path.as_deref()))
+}
+
+/// Errores abiertos y diagnósticos LSP del proyecto en una sola lista
+#[tauri::command]
+pub async fn get_open_diagnostics_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<Vec<ProjectDiagnostic>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_open_diagnostics(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Importa errores de compilación ("cargo" JSON o "tsc --pretty false") y los enlaza a sus chunks
+#[tauri::command]
+pub async fn import_build_errors(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    tool: String,
+    output: String,
+) -> Result<BuildImportResult, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| import_build_output(conn, &project_path, &tool, &output))
+}
+
+/// Importa un reporte JUnit XML y enlaza cada caso con su chunk de tests
+#[tauri::command]
+pub async fn import_junit_report_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    xml: String,
+) -> Result<TestImportResult, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| import_junit_report(conn, &project_path, &xml))
+}
+
+/// Obtiene los tests fallidos de una ejecución (por defecto la última importada)
+#[tauri::command]
+pub async fn get_failing_tests_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    run_id: Option<String>,
+) -> Result<Vec<TestResult>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_failing_tests(&conn, &project_path, run_id.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Sugiere los tests a ejecutar para un cambio (lista de archivos o snapshot)
+/// siguiendo las relaciones tested_by y calls. Va por el escritor porque el diff de
+/// un snapshot puede guardarse al leerlo
+#[tauri::command]
+pub async fn suggest_tests_for_changes_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    files: Option<Vec<String>>,
+    snapshot_id: Option<i64>,
+) -> Result<TestSuggestion, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| {
+        suggest_tests_for_changes(conn, &project_path, files.as_deref(), snapshot_id)
+    })
+}
+
+/// Recalcula fan-in, fan-out y centralidad de los chunks del grafo de relaciones
+#[tauri::command]
+pub async fn compute_graph_metrics_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<usize, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| compute_graph_metrics(conn, &project_path))
+}
+
+/// Archivos del proyecto de mayor a menor complejidad ciclomática
+#[tauri::command]
+pub async fn get_complexity_report(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    limit: Option<usize>,
+) -> Result<ComplexityReport, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    build_complexity_report(&conn, &project_path, limit).map_err(|e| e.to_string())
+}
+
+/// Métricas de grafo de los chunks del proyecto, de mayor a menor centralidad
+#[tauri::command]
+pub async fn get_graph_metrics_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<EntityGraphMetrics>, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_graph_metrics(&conn, &project_path, limit).map_err(|e| e.to_string())
+}
+
+/// Reporte de salud del proyecto con puntaje: errores abiertos, hotspots sin tests,
+/// archivos desactualizados, reglas sin validar y tests fallidos
+#[tauri::command]
+pub async fn get_project_health_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<ProjectHealthReport, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    get_project_health(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Riesgo de modificar los archivos indicados (historial de errores, churn,
+/// complejidad y dependientes), para pedir más tests o revisión humana
+#[tauri::command]
+pub async fn risk_assessment(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    files: Vec<String>,
+) -> Result<RiskAssessment, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    assess_risk(&conn, &project_path, &files).map_err(|e| e.to_string())
+}
+
+/// Script Cypher con los chunks como nodos y las relaciones como aristas, para cargar
+/// el grafo del proyecto en Neo4j
+#[tauri::command]
+pub async fn export_graph_cypher_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    include_content: Option<bool>,
+) -> Result<String, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    export_cypher(&conn, &project_path, include_content.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Grafo de relaciones del proyecto en DOT (Graphviz) o GraphML (Gephi); con
+/// `output_path` también se escribe en ese archivo
+#[tauri::command]
+pub async fn export_callgraph(
+    app: AppHandle,
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    format: GraphFormat,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    chunking_state.check_project(&project_path)?;
+    let target = match output_path {
+        Some(path) => Some(
+            validate_export_path(&path, &exports_dir(&app)?, &project_path)
+                .map_err(|e| e.to_string())?,
+        ),
+        None => None,
+    };
+    let conn = chunking_state.reader()?;
+    let graph = export_graph(&conn, &project_path, format).map_err(|e| e.to_string())?;
+    if let Some(target) = target {
+        std::fs::write(&target, &graph).map_err(|e| e.to_string())?;
+    }
+    Ok(graph)
+}
+
+/// Marca un error como resuelto
+#[tauri::command]
+pub async fn resolve_error_command(
+    chunking_state: State<'_, ChunkingState>,
+    error_id: i64,
+) -> Result<(), String> {
+    chunking_state.write(move |conn| resolve_error(conn, error_id))
+}
+
+/// Crea un snapshot master (user intent) con Git real
+/// Se ejecuta automáticamente ANTES de enviar un mensaje al agente
+#[tauri::command]
+pub async fn create_master_snapshot(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    user_message: String,
+) -> Result<i64, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| {
+        crate::chunking::snapshots::create_master_snapshot_with_git(
+            conn,
+            &project_path,
+            &user_message,
+        )
+    })
+}
+
+/// Crea un snapshot agent (agent execution) con Git real en rama paralela
+/// Se ejecuta automáticamente DESPUÉS de que el agente completa una ejecución y
+/// propone reglas de negocio para las funciones y clases públicas nuevas
+#[tauri::command]
+pub async fn create_agent_snapshot(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    master_snapshot_id: i64,
+    message: String,
+    changed_files: Option<Vec<String>>,
+) -> Result<i64, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| {
+        let snapshot_id = crate::chunking::snapshots::create_agent_snapshot_with_git(
+            conn,
+            &project_path,
+            master_snapshot_id,
+            &message,
+            changed_files,
+        )?;
+        propose_rules_after_agent_snapshot(conn, &project_path, snapshot_id);
+        Ok(snapshot_id)
+    })
+}
+
+/// Reconcilia los snapshots de la base con Git (tags faltantes, tags y ramas de
+/// commits sin registro, commits huérfanos)
+#[tauri::command]
+pub async fn repair_snapshots_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<SnapshotRepairReport, String> {
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| {
+        crate::chunking::snapshots::repair_snapshot_divergence(conn, &project_path)
+    })
+}
+
+/// Grafo master/agent completo del proyecto (nodos, aristas padre → hijo y refs Git)
+#[tauri::command]
+pub async fn get_snapshot_graph(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+) -> Result<SnapshotGraph, String> {
+    chunking_state.check_project(&project_path)?;
+    let conn = chunking_state.reader()?;
+    crate::chunking::snapshots::get_snapshot_graph(&conn, &project_path).map_err(|e| e.to_string())
+}
+
+/// Genera un changelog Markdown con los commits y snapshots entre dos snapshots
+#[tauri::command]
+pub async fn generate_changelog_command(
+    chunking_state: State<'_, ChunkingState>,
+    from_snapshot_id: i64,
+    to_snapshot_id: i64,
+) -> Result<String, String> {
+    chunking_state.check_snapshot(from_snapshot_id)?;
+    chunking_state.check_snapshot(to_snapshot_id)?;
+    let conn = chunking_state.reader()?;
+    generate_changelog(&conn, from_snapshot_id, to_snapshot_id).map_err(|e| e.to_string())
+}
+
+/// Genera release notes para un rango de snapshots master.
+/// Con `polish` el borrador se reescribe con Claude; si falla se retorna el borrador
+#[tauri::command]
+pub async fn generate_release_notes(
+    app: AppHandle,
+    chunking_state: State<'_, ChunkingState>,
+    from_snapshot_id: i64,
+    to_snapshot_id: i64,
+    polish: Option<bool>,
+    model: Option<String>,
+) -> Result<String, String> {
+    chunking_state.check_snapshot(from_snapshot_id)?;
+    chunking_state.check_snapshot(to_snapshot_id)?;
+    let draft = {
+        let conn = chunking_state.reader()?;
+        build_release_notes(&conn, from_snapshot_id, to_snapshot_id).map_err(|e| e.to_string())?
+    };
+
+    if !polish.unwrap_or(false) {
+        return Ok(draft);
+    }
+
+    let claude_path = crate::claude_binary::find_claude_binary(&app)?;
+    let prompt = release_notes_prompt(&draft);
+    let output = tokio::task::spawn_blocking(move || {
+        let mut cmd = crate::claude_binary::create_command_with_env(&claude_path);
+        cmd.arg("-p").arg(prompt).arg("--output-format").arg("text");
+        if let Some(model) = model {
+            cmd.arg("--model").arg(model);
+        }
+        cmd.output()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let polished = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            Ok(if polished.is_empty() { draft } else { polished })
+        }
+        Ok(output) => {
+            log::warn!(
+                "Release notes polishing failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Ok(draft)
+        }
+        Err(e) => {
+            log::warn!("Failed to run claude for release notes: {}", e);
+            Ok(draft)
+        }
+    }
+}
+
+/// Sugiere el incremento de versión semántica entre dos snapshots
+#[tauri::command]
+pub async fn suggest_version_bump_command(
+    chunking_state: State<'_, ChunkingState>,
+    from_snapshot_id: i64,
+    to_snapshot_id: i64,
+) -> Result<VersionBumpSuggestion, String> {
+    chunking_state.check_snapshot(from_snapshot_id)?;
+    chunking_state.check_snapshot(to_snapshot_id)?;
+    let conn = chunking_state.reader()?;
+    suggest_version_bump(&conn, from_snapshot_id, to_snapshot_id).map_err(|e| e.to_string())
+}
+
+/// Retrocede la rama master a un snapshot anterior (time travel)
+/// Usa git reset --hard y elimina snapshots master posteriores
+#[tauri::command]
+pub async fn rewind_master_snapshot(
+    chunking_state: State<'_, ChunkingState>,
+    snapshot_id: i64,
+) -> Result<(), String> {
+    let project_path = chunking_state.check_snapshot(snapshot_id)?;
+    chunking_state.check_writable(&project_path)?;
+    chunking_state.write(move |conn| {
+        crate::chunking::snapshots::rewind_master_to_snapshot_with_git(conn, snapshot_id)
+    })
+}
+
+/// Propone una regla de negocio para validación
+#[tauri::command]
+pub async fn propose_business_rule_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    entity_name: String,
+    file_path: String,
+    ai_interpretation: String,
+) -> Result<i64, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| {
+        crate::chunking::business_rules::propose_business_rule(
+            conn,
+            &project_path,
+            &entity_name,
+            &file_path,
+            &ai_interpretation,
+        )
+    })
+}
+
+/// Registra un error en el sistema (por defecto atribuido al snapshot activo)
+#[tauri::command]
+pub async fn log_error_command(
+    chunking_state: State<'_, ChunkingState>,
+    project_path: String,
+    error_type: String,
+    message: String,
+    file_path: Option<String>,
+    stacktrace: Option<String>,
+    snapshot_id: Option<i64>,
+) -> Result<i64, String> {
+    chunking_state.check_project(&project_path)?;
+    chunking_state.write(move |conn| {
+        crate::chunking::errors::log_error(
+            conn,
+            &project_path,
+            &error_type,
+            &message,
+            file_path.as_deref(),
+            None,
+            stacktrace.as_deref(),
+            snapshot_id,
+        )
+    })
 }
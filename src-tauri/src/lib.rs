@@ -2,7 +2,7 @@
 
 // Declare modules
 pub mod checkpoint;
-pub mod chunking;
+pub use opcode_chunking as chunking;
 pub mod claude_binary;
 pub mod commands;
 pub mod process;
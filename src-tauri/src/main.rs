@@ -2,7 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod checkpoint;
-mod chunking;
+use opcode_chunking as chunking;
 mod claude_binary;
 mod commands;
 mod process;
@@ -43,10 +43,45 @@ use commands::storage::{
     storage_read_table, storage_reset_database, storage_update_row,
 };
 use commands::chunking::{
-    create_agent_snapshot, create_master_snapshot, get_pending_business_rules,
-    get_project_errors, get_project_snapshots, init_chunking_system, log_error_command,
-    process_project_chunks, propose_business_rule_command, resolve_error_command,
-    rewind_master_snapshot, search_chunks, validate_business_rule_command, ChunkingState,
+    add_code_extension_command, audit_project_dependencies, bench_index,
+    build_context_pack_command, build_review_context_command, check_chunk_staleness,
+    check_rule_conformance_command, clear_lsp_diagnostics_command, clear_parse_failures_command,
+    close_agent_session_command, compact_chunk_contents_command, compute_graph_metrics_command,
+    create_agent_snapshot, create_custom_chunk_command, create_logical_snapshot_command,
+    create_master_snapshot, deepen_commit_history, delete_logical_project_command,
+    diff_chunk_versions_command, export_callgraph, export_graph_cypher_command,
+    export_index_profile, export_project_index, fetch_chunks_page_command, find_dependency_usages,
+    find_table_queries_command, generate_changelog_command, generate_release_notes,
+    get_agent_session_report_command, get_benchmark_runs_command, get_chunk_versions_command,
+    get_chunk_with_context_command, get_chunks_by_ids_command, get_chunks_over_time,
+    get_code_extensions_command, get_complexity_report, get_content_store_stats_command,
+    get_context_budget_weights_command, get_context_deliveries_command,
+    get_context_rule_pinning_command, get_embedding_settings_command, get_entry_points_command,
+    get_error_rates, get_failing_tests_command, get_graph_metrics_command, get_i18n_report_command,
+    get_index_freshness, get_index_operations_command, get_lint_offenders_command,
+    get_logical_projects_command, get_lsp_diagnostics_command, get_open_diagnostics_command,
+    get_parse_failures_command, get_pending_business_rules, get_project_agent_sessions,
+    get_project_error_clusters, get_project_errors, get_project_hashing_command,
+    get_project_health_command, get_project_read_only_command, get_project_snapshots,
+    get_read_only_projects_command, get_remote_repositories_command, get_secret_findings_command,
+    get_security_findings_command, get_snapshot_cadence, get_snapshot_diff, get_snapshot_graph,
+    get_source_range_command, get_syntax_errors, get_tauri_surface_command, get_ui_routes_command,
+    import_build_errors, import_git_notes, import_junit_report_command, import_lint_diagnostics,
+    import_project_index, import_project_issues, index_remote_repository, init_chunking_system,
+    install_git_hooks_command, log_error_command, process_git_hook_trigger, process_project_chunks,
+    process_project_path, propose_business_rule_command, publish_agent_snapshot,
+    publish_lsp_diagnostics_command, purge_deleted_chunks_command, rank_chunks_for_task_command,
+    rebuild_relationships_command, refresh_embeddings_command, refresh_stale_chunks,
+    register_logical_project_command, remove_code_extension_command, repair_snapshots_command,
+    reset_project_index_command, resolve_error_command, restore_deleted_chunks_command,
+    rewind_master_snapshot, risk_assessment, save_git_provider_token,
+    scan_dependency_vulnerabilities, search_chunks, search_logical_chunks, semantic_search_command,
+    set_business_rule_check_command, set_context_budget_weights_command,
+    set_context_rule_pinning_command, set_embedding_backend_command, set_hash_algorithm_command,
+    set_project_read_only_command, start_agent_session_command, suggest_tests_for_changes_command,
+    suggest_version_bump_command, tag_business_rule_command, undo_last_operation_command,
+    uninstall_git_hooks_command, update_chunk_metadata_command, validate_business_rule_command,
+    write_git_notes, ChunkingState,
 };
 use commands::usage::{
     get_session_stats, get_usage_by_date_range, get_usage_details, get_usage_stats,
@@ -128,9 +163,9 @@ fn main() {
             app.manage(AgentDb(Mutex::new(conn)));
 
             // Initialize chunking system
-            let chunking_conn = init_chunking_system(&app.handle())
+            let chunk_store = init_chunking_system(&app.handle())
                 .expect("Failed to initialize chunking database");
-            app.manage(ChunkingState(Mutex::new(chunking_conn)));
+            app.manage(ChunkingState(chunk_store));
 
             // Initialize checkpoint state
             let checkpoint_state = CheckpointState::new();
@@ -303,17 +338,125 @@ fn main() {
             save_proxy_settings,
             // Chunking System
             process_project_chunks,
+            process_project_path,
             search_chunks,
+            fetch_chunks_page_command,
+            find_dependency_usages,
+            get_entry_points_command,
+            get_tauri_surface_command,
+            get_ui_routes_command,
+            get_i18n_report_command,
+            find_table_queries_command,
+            audit_project_dependencies,
             get_pending_business_rules,
             validate_business_rule_command,
             get_project_snapshots,
             get_project_errors,
+            get_syntax_errors,
             resolve_error_command,
             create_master_snapshot,
             create_agent_snapshot,
             rewind_master_snapshot,
+            repair_snapshots_command,
+            get_snapshot_graph,
             propose_business_rule_command,
             log_error_command,
+            scan_dependency_vulnerabilities,
+            get_security_findings_command,
+            get_secret_findings_command,
+            import_lint_diagnostics,
+            get_lint_offenders_command,
+            publish_lsp_diagnostics_command,
+            get_lsp_diagnostics_command,
+            clear_lsp_diagnostics_command,
+            get_open_diagnostics_command,
+            import_build_errors,
+            import_junit_report_command,
+            get_failing_tests_command,
+            get_project_error_clusters,
+            generate_changelog_command,
+            generate_release_notes,
+            suggest_version_bump_command,
+            deepen_commit_history,
+            write_git_notes,
+            import_git_notes,
+            install_git_hooks_command,
+            uninstall_git_hooks_command,
+            process_git_hook_trigger,
+            get_snapshot_diff,
+            publish_agent_snapshot,
+            save_git_provider_token,
+            import_project_issues,
+            build_review_context_command,
+            rank_chunks_for_task_command,
+            build_context_pack_command,
+            get_context_budget_weights_command,
+            set_context_budget_weights_command,
+            get_context_rule_pinning_command,
+            set_context_rule_pinning_command,
+            tag_business_rule_command,
+            set_business_rule_check_command,
+            check_rule_conformance_command,
+            rebuild_relationships_command,
+            suggest_tests_for_changes_command,
+            compute_graph_metrics_command,
+            get_complexity_report,
+            get_graph_metrics_command,
+            get_project_health_command,
+            risk_assessment,
+            export_graph_cypher_command,
+            export_callgraph,
+            get_chunk_with_context_command,
+            create_custom_chunk_command,
+            update_chunk_metadata_command,
+            get_chunks_by_ids_command,
+            get_context_deliveries_command,
+            start_agent_session_command,
+            close_agent_session_command,
+            get_project_agent_sessions,
+            get_agent_session_report_command,
+            get_chunks_over_time,
+            get_snapshot_cadence,
+            get_error_rates,
+            get_index_freshness,
+            register_logical_project_command,
+            get_logical_projects_command,
+            delete_logical_project_command,
+            search_logical_chunks,
+            create_logical_snapshot_command,
+            index_remote_repository,
+            get_remote_repositories_command,
+            set_project_read_only_command,
+            get_project_read_only_command,
+            get_read_only_projects_command,
+            export_project_index,
+            import_project_index,
+            export_index_profile,
+            bench_index,
+            get_benchmark_runs_command,
+            get_parse_failures_command,
+            clear_parse_failures_command,
+            get_code_extensions_command,
+            add_code_extension_command,
+            remove_code_extension_command,
+            get_project_hashing_command,
+            set_hash_algorithm_command,
+            get_embedding_settings_command,
+            set_embedding_backend_command,
+            refresh_embeddings_command,
+            semantic_search_command,
+            restore_deleted_chunks_command,
+            purge_deleted_chunks_command,
+            reset_project_index_command,
+            check_chunk_staleness,
+            refresh_stale_chunks,
+            get_content_store_stats_command,
+            compact_chunk_contents_command,
+            undo_last_operation_command,
+            get_index_operations_command,
+            get_chunk_versions_command,
+            diff_chunk_versions_command,
+            get_source_range_command,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -19,6 +19,9 @@ import {
   Box,
   AlertCircle,
   Clock,
+  CircleDot,
+  StickyNote,
+  Lock,
 } from 'lucide-react';
 import { ChunkGrid } from './ChunkGrid';
 import { ChunkDetail } from './ChunkDetail';
@@ -40,6 +43,7 @@ export const ChunkExplorer: React.FC<ChunkExplorerProps> = ({
   const {
     chunks,
     currentProjectPath,
+    readOnly,
     isProcessing,
     isLoadingChunks,
     error,
@@ -119,6 +123,8 @@ export const ChunkExplorer: React.FC<ChunkExplorerProps> = ({
     business_rules: <AlertCircle className="h-4 w-4" />,
     snapshot: <Database className="h-4 w-4" />,
     error_log: <AlertCircle className="h-4 w-4" />,
+    issue: <CircleDot className="h-4 w-4" />,
+    user_content: <StickyNote className="h-4 w-4" />,
   };
 
   return (
@@ -137,6 +143,12 @@ export const ChunkExplorer: React.FC<ChunkExplorerProps> = ({
                 {currentProjectPath && (
                   <span className="ml-2 text-xs">({currentProjectPath})</span>
                 )}
+                {readOnly && (
+                  <Badge variant="secondary" className="ml-2" title={readOnly.reason}>
+                    <Lock className="h-3 w-3 mr-1" />
+                    Read-only
+                  </Badge>
+                )}
               </CardDescription>
             </div>
             <div className="flex gap-2">
@@ -144,10 +156,12 @@ export const ChunkExplorer: React.FC<ChunkExplorerProps> = ({
                 <RefreshCw className={`h-4 w-4 mr-1 ${isLoadingChunks ? 'animate-spin' : ''}`} />
                 Refresh
               </Button>
-              <Button onClick={handleProcess} size="sm" disabled={isProcessing || !projectPath}>
-                <Play className="h-4 w-4 mr-1" />
-                Process Project
-              </Button>
+              {!readOnly && (
+                <Button onClick={handleProcess} size="sm" disabled={isProcessing || !projectPath}>
+                  <Play className="h-4 w-4 mr-1" />
+                  Process Project
+                </Button>
+              )}
             </div>
           </div>
         </CardHeader>
@@ -7,14 +7,24 @@ import { ScrollArea } from '@/components/ui/scroll-area';
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
 import { User, Bot, Clock, FileText, RotateCcw, GitBranch, Tag } from 'lucide-react';
 import { api } from '@/lib/api';
-import type { Snapshot } from '@/types/chunking';
+import type { DiffSummary, Snapshot } from '@/types/chunking';
+
+const parseDiffSummary = (diffSummary?: string): DiffSummary | null => {
+  if (!diffSummary) return null;
+  try {
+    const parsed = JSON.parse(diffSummary);
+    return parsed && Array.isArray(parsed.files) ? (parsed as DiffSummary) : null;
+  } catch {
+    return null;
+  }
+};
 
 interface SnapshotTimelineProps {
   projectPath?: string;
 }
 
 export const SnapshotTimeline: React.FC<SnapshotTimelineProps> = ({ projectPath }) => {
-  const { snapshots, isLoadingSnapshots, fetchSnapshots } = useChunkingStore();
+  const { snapshots, readOnly, isLoadingSnapshots, fetchSnapshots } = useChunkingStore();
   const [rewindingId, setRewindingId] = useState<number | null>(null);
   const [activeTab, setActiveTab] = useState('master');
 
@@ -51,6 +61,7 @@ export const SnapshotTimeline: React.FC<SnapshotTimelineProps> = ({ projectPath
 
   const renderSnapshot = (snapshot: Snapshot) => {
     const changedFiles = JSON.parse(snapshot.changed_files) as string[];
+    const diffSummary = parseDiffSummary(snapshot.diff_summary);
     const isMaster = snapshot.snapshot_type === 'master';
     const versionTag = snapshot.version_minor
       ? `V${snapshot.version_major}.${snapshot.version_minor}`
@@ -114,11 +125,20 @@ export const SnapshotTimeline: React.FC<SnapshotTimelineProps> = ({ projectPath
                   Changed Files ({changedFiles.length}):
                 </div>
                 <div className="space-y-1">
-                  {changedFiles.slice(0, 5).map((file, idx) => (
-                    <div key={idx} className="text-xs text-muted-foreground truncate">
-                      • {file}
-                    </div>
-                  ))}
+                  {diffSummary
+                    ? diffSummary.files.slice(0, 5).map((file, idx) => (
+                        <div key={idx} className="text-xs text-muted-foreground truncate">
+                          • {file.old_path ? `${file.old_path} → ${file.path}` : file.path}{' '}
+                          <span className="text-green-600">+{file.additions}</span>{' '}
+                          <span className="text-red-600">-{file.deletions}</span>{' '}
+                          <span className="italic">({file.status})</span>
+                        </div>
+                      ))
+                    : changedFiles.slice(0, 5).map((file, idx) => (
+                        <div key={idx} className="text-xs text-muted-foreground truncate">
+                          • {file}
+                        </div>
+                      ))}
                   {changedFiles.length > 5 && (
                     <div className="text-xs text-muted-foreground">
                       ... and {changedFiles.length - 5} more
@@ -129,10 +149,12 @@ export const SnapshotTimeline: React.FC<SnapshotTimelineProps> = ({ projectPath
             )}
             {snapshot.diff_summary && (
               <div className="text-sm bg-muted p-2 rounded-sm mt-2">
-                {snapshot.diff_summary}
+                {diffSummary
+                  ? `${diffSummary.files_changed} files changed, ${diffSummary.insertions} insertions(+), ${diffSummary.deletions} deletions(-)`
+                  : snapshot.diff_summary}
               </div>
             )}
-            {isMaster && snapshot.id && (
+            {isMaster && snapshot.id && !readOnly && (
               <div className="pt-2 border-t">
                 <Button
                   variant="outline"
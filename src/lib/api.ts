@@ -3,12 +3,90 @@ import type { HooksConfiguration } from '@/types/hooks';
 import type {
   Chunk,
   ChunkQuery,
+  ChunkPage,
   ChunkingOptions,
   ChunkingResult,
   BusinessRule,
   Snapshot,
   SnapshotType,
+  SnapshotDiff,
+  SnapshotRepairReport,
+  SnapshotGraph,
+  ErrorCluster,
   ErrorLog,
+  FileSyntaxErrors,
+  GitNotesResult,
+  GitProvider,
+  PullRequestInfo,
+  IssueImportResult,
+  ReviewContext,
+  ScoredChunk,
+  ContextPack,
+  ChunkTypeWeight,
+  RulePinning,
+  RuleCheck,
+  RuleConformance,
+  ContextDelivery,
+  AgentSession,
+  AgentSessionStatus,
+  AgentSessionReport,
+  ChunksPerDay,
+  SnapshotCadence,
+  ErrorRatePerDay,
+  FileTypeFreshness,
+  ProjectRoot,
+  LogicalProject,
+  LogicalSnapshot,
+  RemoteRepository,
+  RemoteIndexResult,
+  ReadOnlyProject,
+  IndexExportSummary,
+  IndexMergeReport,
+  IndexProfile,
+  HashAlgorithm,
+  ProjectHashing,
+  GraphFormat,
+  EmbeddingBackend,
+  EmbeddingSettings,
+  EmbeddingRefreshReport,
+  SemanticMatch,
+  BenchmarkRun,
+  ParseFailure,
+  StalenessReport,
+  ContentStoreStats,
+  ContentCompactionReport,
+  IndexOperation,
+  RelationshipRebuildReport,
+  UndoResult,
+  ChunkVersion,
+  ChunkVersionDiff,
+  VersionBumpSuggestion,
+  DependencyKind,
+  DependencyUsage,
+  EntryPoint,
+  DependencyAuditReport,
+  SecurityFinding,
+  SecretFinding,
+  LintImportResult,
+  LintOffender,
+  BuildImportResult,
+  TestImportResult,
+  TestResult,
+  TestSuggestion,
+  EntityGraphMetrics,
+  ComplexityReport,
+  ProjectHealthReport,
+  RiskAssessment,
+  ChunkWithContext,
+  CustomChunkInput,
+  LspDiagnostic,
+  LspPublishResult,
+  ProjectDiagnostic,
+  SourceRange,
+  TauriSurface,
+  UiRoute,
+  I18nReport,
+  TableQuery,
 } from '@/types/chunking';
 
 /** Process type for tracking in ProcessRegistry */
@@ -1975,6 +2053,30 @@ export const api = {
     }
   },
 
+  /**
+   * Indexes only a subdirectory of a project, with the same options as a full run
+   * @param projectPath - Absolute path to the project
+   * @param subpath - Directory relative to the project root (e.g. "services/api")
+   * @param options - Optional chunking configuration
+   * @returns Promise resolving to chunking result with statistics
+   */
+  async processProjectPath(
+    projectPath: string,
+    subpath: string,
+    options?: ChunkingOptions
+  ): Promise<ChunkingResult> {
+    try {
+      return await apiCall<ChunkingResult>("process_project_path", {
+        projectPath,
+        subpath,
+        options
+      });
+    } catch (error) {
+      console.error("Failed to process project path:", error);
+      throw error;
+    }
+  },
+
   /**
    * Searches for chunks matching the specified criteria
    * @param query - Search query with filters
@@ -1989,6 +2091,50 @@ export const api = {
     }
   },
 
+  /**
+   * Gets full chunks by ID in a single call, in the requested order
+   * @param chunkIds - IDs of the chunks; missing or deleted ones are skipped
+   * @returns Promise resolving to the chunks found
+   */
+  async getChunksByIds(chunkIds: number[]): Promise<Chunk[]> {
+    try {
+      return await apiCall<Chunk[]>("get_chunks_by_ids_command", { chunkIds });
+    } catch (error) {
+      console.error("Failed to get chunks by IDs:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Fetches one page of chunks using a cursor (ascending chunk id)
+   * @param query - Search filters (limit/offset are ignored)
+   * @param afterId - Cursor returned by the previous page (omit for the first page)
+   * @param pageSize - Chunks per page (default 500)
+   * @returns Promise resolving to the page and the next cursor (null at the end)
+   */
+  async fetchChunksPage(query: ChunkQuery, afterId?: number, pageSize?: number): Promise<ChunkPage> {
+    try {
+      return await apiCall<ChunkPage>("fetch_chunks_page_command", { query, afterId, pageSize });
+    } catch (error) {
+      console.error("Failed to fetch chunks page:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Iterates over every chunk matching a query, one page at a time
+   * @param query - Search filters (limit/offset are ignored)
+   * @param pageSize - Chunks per page (default 500)
+   */
+  async *iterateChunks(query: ChunkQuery, pageSize?: number): AsyncGenerator<Chunk> {
+    let afterId: number | undefined;
+    do {
+      const page = await this.fetchChunksPage(query, afterId, pageSize);
+      yield* page.chunks;
+      afterId = page.next_cursor ?? undefined;
+    } while (afterId !== undefined);
+  },
+
   /**
    * Gets business rules pending validation for a project
    * @param projectPath - Absolute path to the project
@@ -2056,6 +2202,50 @@ export const api = {
     }
   },
 
+  /**
+   * Replaces the tags of a business rule
+   * @param ruleId - ID of the business rule
+   * @param tags - New tags; "critical" marks a non-negotiable constraint
+   * @returns Promise resolving when the tags are saved
+   */
+  async tagBusinessRule(ruleId: number, tags: string[]): Promise<void> {
+    try {
+      await apiCall("tag_business_rule_command", { ruleId, tags });
+    } catch (error) {
+      console.error("Failed to tag business rule:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Attaches a machine-checkable check to a business rule
+   * @param ruleId - ID of the business rule
+   * @param check - Naming regex, forbidden import or required tree-sitter query; null to remove it
+   * @returns Promise resolving when the check is validated and saved
+   */
+  async setBusinessRuleCheck(ruleId: number, check: RuleCheck | null): Promise<void> {
+    try {
+      await apiCall("set_business_rule_check_command", { ruleId, check });
+    } catch (error) {
+      console.error("Failed to set business rule check:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Evaluates every business rule that has a check against the current code
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the violations of each checked rule
+   */
+  async checkRuleConformance(projectPath: string): Promise<RuleConformance[]> {
+    try {
+      return await apiCall<RuleConformance[]>("check_rule_conformance_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to check rule conformance:", error);
+      throw error;
+    }
+  },
+
   /**
    * Gets snapshots for a project
    * @param projectPath - Absolute path to the project
@@ -2077,6 +2267,20 @@ export const api = {
     }
   },
 
+  /**
+   * Gets the per-file diff stored for a snapshot
+   * @param snapshotId - ID of the snapshot
+   * @returns Promise resolving to the snapshot diff with patches and line stats
+   */
+  async getSnapshotDiff(snapshotId: number): Promise<SnapshotDiff> {
+    try {
+      return await apiCall<SnapshotDiff>("get_snapshot_diff", { snapshotId });
+    } catch (error) {
+      console.error("Failed to get snapshot diff:", error);
+      throw error;
+    }
+  },
+
   /**
    * Creates a master snapshot (user intent timeline) with Git commit
    * Automatically called BEFORE sending a message to the agent
@@ -2148,47 +2352,1746 @@ export const api = {
   },
 
   /**
-   * Gets active errors for a project
+   * Reconciles the project's snapshots with Git: restores missing snapshot tags,
+   * removes tags/agent branches of unregistered snapshot commits and reports
+   * missing or orphaned commits
+   * @param projectPath - Path to the project
+   * @returns Promise resolving to the repair report
+   */
+  async repairSnapshots(projectPath: string): Promise<SnapshotRepairReport> {
+    try {
+      return await apiCall<SnapshotRepairReport>("repair_snapshots_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to repair snapshots:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the full master/agent snapshot DAG (nodes, parent→child edges, git refs)
+   * @param projectPath - Path to the project
+   * @returns Promise resolving to the snapshot graph
+   */
+  async getSnapshotGraph(projectPath: string): Promise<SnapshotGraph> {
+    try {
+      return await apiCall<SnapshotGraph>("get_snapshot_graph", { projectPath });
+    } catch (error) {
+      console.error("Failed to get snapshot graph:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Generates a Markdown changelog (features/fixes/refactors) between two snapshots
+   * @param fromSnapshotId - Starting snapshot (excluded)
+   * @param toSnapshotId - Ending snapshot (included)
+   * @returns Promise resolving to the Markdown changelog
+   */
+  async generateChangelog(fromSnapshotId: number, toSnapshotId: number): Promise<string> {
+    try {
+      return await apiCall<string>("generate_changelog_command", {
+        fromSnapshotId,
+        toSnapshotId
+      });
+    } catch (error) {
+      console.error("Failed to generate changelog:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Builds release notes for a range of master snapshots
+   * @param fromSnapshotId - Starting master snapshot (excluded)
+   * @param toSnapshotId - Ending master snapshot (included)
+   * @param polish - Rewrite the draft with Claude (falls back to the draft on failure)
+   * @param model - Optional model used for polishing
+   * @returns Promise resolving to the Markdown release notes
+   */
+  async generateReleaseNotes(
+    fromSnapshotId: number,
+    toSnapshotId: number,
+    polish?: boolean,
+    model?: string
+  ): Promise<string> {
+    try {
+      return await apiCall<string>("generate_release_notes", {
+        fromSnapshotId,
+        toSnapshotId,
+        polish,
+        model
+      });
+    } catch (error) {
+      console.error("Failed to generate release notes:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Suggests a major/minor/patch bump by diffing the public API between two snapshots
+   * @param fromSnapshotId - Baseline snapshot
+   * @param toSnapshotId - Snapshot being released
+   * @returns Promise resolving to the suggested bump with breaking/added symbols
+   */
+  async suggestVersionBump(
+    fromSnapshotId: number,
+    toSnapshotId: number
+  ): Promise<VersionBumpSuggestion> {
+    try {
+      return await apiCall<VersionBumpSuggestion>("suggest_version_bump_command", {
+        fromSnapshotId,
+        toSnapshotId
+      });
+    } catch (error) {
+      console.error("Failed to suggest version bump:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Deepens a shallow clone so commit chunking can index more history
    * @param projectPath - Absolute path to the project
-   * @returns Promise resolving to array of error logs
+   * @param depth - Commits to fetch from origin (omit to fetch the full history)
+   * @returns Promise resolving to whether the repository is still shallow
    */
-  async getProjectErrors(projectPath: string): Promise<ErrorLog[]> {
+  async deepenCommitHistory(projectPath: string, depth?: number): Promise<boolean> {
     try {
-      return await apiCall<ErrorLog[]>("get_project_errors", {
+      return await apiCall<boolean>("deepen_commit_history", {
+        projectPath,
+        depth
+      });
+    } catch (error) {
+      console.error("Failed to deepen commit history:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Writes commit summaries and snapshot ids to refs/notes/opcode
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the number of notes written
+   */
+  async writeGitNotes(projectPath: string): Promise<GitNotesResult> {
+    try {
+      return await apiCall<GitNotesResult>("write_git_notes", {
         projectPath
       });
     } catch (error) {
-      console.error("Failed to get project errors:", error);
+      console.error("Failed to write git notes:", error);
       throw error;
     }
   },
 
   /**
-   * Logs an error in the system
+   * Imports refs/notes/opcode and restores snapshots missing from the local database
    * @param projectPath - Absolute path to the project
-   * @param errorType - Type/category of the error
-   * @param message - Error message
-   * @param filePath - Optional path to file where error occurred
-   * @param stacktrace - Optional error stacktrace
-   * @returns Promise resolving to the new error log ID
+   * @returns Promise resolving to imported notes and restored snapshots
    */
-  async logError(
+  async importGitNotes(projectPath: string): Promise<GitNotesResult> {
+    try {
+      return await apiCall<GitNotesResult>("import_git_notes", {
+        projectPath
+      });
+    } catch (error) {
+      console.error("Failed to import git notes:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Installs post-commit/post-merge hooks that record changed files for reindexing
+   * @param projectPath - Absolute path to the git repository
+   * @returns Promise resolving to the installed hook names
+   */
+  async installGitHooks(projectPath: string): Promise<string[]> {
+    try {
+      return await apiCall<string[]>("install_git_hooks_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to install git hooks:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Removes the opcode block from the repository git hooks
+   * @param projectPath - Absolute path to the git repository
+   * @returns Promise resolving to the hook names that were cleaned up
+   */
+  async uninstallGitHooks(projectPath: string): Promise<string[]> {
+    try {
+      return await apiCall<string[]>("uninstall_git_hooks_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to uninstall git hooks:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Reindexes the files recorded by the git hooks since the last call
+   * @param projectPath - Absolute path to the git repository
+   * @returns Promise resolving to the reindex result, or null when nothing is pending
+   */
+  async processGitHookTrigger(projectPath: string): Promise<ChunkingResult | null> {
+    try {
+      return await apiCall<ChunkingResult | null>("process_git_hook_trigger", { projectPath });
+    } catch (error) {
+      console.error("Failed to process git hook trigger:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Pushes an agent snapshot branch and opens a GitHub PR or GitLab MR for it
+   * @param snapshotId - ID of the agent snapshot
+   * @param options - Optional remote (default "origin"), target branch (default "main"), title and draft flag
+   * @returns Promise resolving to the created pull request
+   */
+  async publishAgentSnapshot(
+    snapshotId: number,
+    options: { remote?: string; targetBranch?: string; title?: string; draft?: boolean } = {}
+  ): Promise<PullRequestInfo> {
+    try {
+      return await apiCall<PullRequestInfo>("publish_agent_snapshot", {
+        snapshotId,
+        remote: options.remote,
+        targetBranch: options.targetBranch,
+        title: options.title,
+        draft: options.draft
+      });
+    } catch (error) {
+      console.error("Failed to publish agent snapshot:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Stores a GitHub/GitLab token in the system keychain for publishing snapshots
+   * @param provider - Git hosting provider
+   * @param token - Personal access token
+   */
+  async saveGitProviderToken(provider: GitProvider, token: string): Promise<void> {
+    try {
+      await apiCall<void>("save_git_provider_token", { provider, token });
+    } catch (error) {
+      console.error("Failed to save git provider token:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Imports GitHub/GitLab issues as chunks linked to the files they mention
+   * @param projectPath - Absolute path to the git repository
+   * @param options - Optional remote (default "origin"), state ("open", "closed" or "all") and limit
+   * @returns Promise resolving to the import result
+   */
+  async importProjectIssues(
     projectPath: string,
-    errorType: string,
-    message: string,
-    filePath?: string,
-    stacktrace?: string
-  ): Promise<number> {
+    options: { remote?: string; state?: 'open' | 'closed' | 'all'; limit?: number } = {}
+  ): Promise<IssueImportResult> {
     try {
-      return await apiCall<number>("log_error_command", {
+      return await apiCall<IssueImportResult>("import_project_issues", {
         projectPath,
-        errorType,
-        message,
-        filePath,
-        stacktrace
+        remote: options.remote,
+        state: options.state,
+        limit: options.limit
       });
     } catch (error) {
-      console.error("Failed to log error:", error);
+      console.error("Failed to import project issues:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Builds a review package for a change: changed chunks, callers, covering tests and business rules
+   * @param projectPath - Absolute path to the project
+   * @param source - Either a unified diff (`git diff` output) or an agent/master snapshot ID
+   * @returns Promise resolving to the review context, including a Markdown rendering for agents
+   */
+  async buildReviewContext(
+    projectPath: string,
+    source: { diff?: string; snapshotId?: number }
+  ): Promise<ReviewContext> {
+    try {
+      return await apiCall<ReviewContext>("build_review_context_command", {
+        projectPath,
+        diff: source.diff,
+        snapshotId: source.snapshotId
+      });
+    } catch (error) {
+      console.error("Failed to build review context:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Ranks the project's chunks by relevance to a task description
+   * @param projectPath - Absolute path to the project
+   * @param taskText - Free-form task or issue text
+   * @param limit - Maximum number of chunks to return (default 20)
+   * @returns Promise resolving to chunks ordered by score (lexical similarity + proximity to recent changes)
+   */
+  async rankChunksForTask(projectPath: string, taskText: string, limit?: number): Promise<ScoredChunk[]> {
+    try {
+      return await apiCall<ScoredChunk[]>("rank_chunks_for_task_command", { projectPath, taskText, limit });
+    } catch (error) {
+      console.error("Failed to rank chunks for task:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Builds a token-budgeted context pack for a task, including each source region only once
+   * @param projectPath - Absolute path to the project
+   * @param taskText - Free-form task or issue text
+   * @param tokenBudget - Estimated token budget (default 8000)
+   * @param sessionId - Agent session receiving the pack, recorded for auditing
+   * @returns Promise resolving to the selected chunks and a Markdown rendering for agents
+   */
+  async buildContextPack(
+    projectPath: string,
+    taskText: string,
+    tokenBudget?: number,
+    sessionId?: string
+  ): Promise<ContextPack> {
+    try {
+      return await apiCall<ContextPack>("build_context_pack_command", {
+        projectPath,
+        taskText,
+        tokenBudget,
+        sessionId
+      });
+    } catch (error) {
+      console.error("Failed to build context pack:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets how the context pack token budget is split across chunk types
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the configured weights, or null when packs are not split by type
+   */
+  async getContextBudgetWeights(projectPath: string): Promise<ChunkTypeWeight[] | null> {
+    try {
+      return await apiCall<ChunkTypeWeight[] | null>("get_context_budget_weights_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get context budget weights:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sets how the context pack token budget is split across chunk types
+   * @param projectPath - Absolute path to the project
+   * @param weights - Relative weight per chunk type (types not listed are excluded); empty to reset
+   * @returns Promise resolving when the weights are saved
+   */
+  async setContextBudgetWeights(projectPath: string, weights: ChunkTypeWeight[]): Promise<void> {
+    try {
+      await apiCall("set_context_budget_weights_command", { projectPath, weights });
+    } catch (error) {
+      console.error("Failed to set context budget weights:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets which business rules are pinned at the top of every context pack
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the pinning mode ('none' when not configured)
+   */
+  async getContextRulePinning(projectPath: string): Promise<RulePinning> {
+    try {
+      return await apiCall<RulePinning>("get_context_rule_pinning_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get context rule pinning:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sets which business rules are pinned at the top of every context pack, regardless of relevance
+   * @param projectPath - Absolute path to the project
+   * @param mode - 'validated' pins every validated rule, 'critical' only validated rules tagged "critical"
+   * @returns Promise resolving when the setting is saved
+   */
+  async setContextRulePinning(projectPath: string, mode: RulePinning): Promise<void> {
+    try {
+      await apiCall("set_context_rule_pinning_command", { projectPath, mode });
+    } catch (error) {
+      console.error("Failed to set context rule pinning:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the context packs delivered to agents, i.e. exactly which chunks they saw
+   * @param projectPath - Absolute path to the project
+   * @param filter - Optional agent session ID and/or snapshot ID
+   * @returns Promise resolving to the deliveries, newest first
+   */
+  async getContextDeliveries(
+    projectPath: string,
+    filter: { sessionId?: string; snapshotId?: number } = {}
+  ): Promise<ContextDelivery[]> {
+    try {
+      return await apiCall<ContextDelivery[]>("get_context_deliveries_command", {
+        projectPath,
+        sessionId: filter.sessionId,
+        snapshotId: filter.snapshotId
+      });
+    } catch (error) {
+      console.error("Failed to get context deliveries:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Starts an agent session linked to a master snapshot
+   * @param projectPath - Absolute path to the project
+   * @param options - Session ID (generated when omitted), master snapshot (defaults to the active one) and task
+   * @returns Promise resolving to the created session
+   */
+  async startAgentSession(
+    projectPath: string,
+    options: { sessionId?: string; masterSnapshotId?: number; task?: string } = {}
+  ): Promise<AgentSession> {
+    try {
+      return await apiCall<AgentSession>("start_agent_session_command", {
+        projectPath,
+        sessionId: options.sessionId,
+        masterSnapshotId: options.masterSnapshotId,
+        task: options.task
+      });
+    } catch (error) {
+      console.error("Failed to start agent session:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Closes an agent session with its final status
+   * @param sessionId - The session ID
+   * @param status - Final status: completed, failed or cancelled
+   * @returns Promise resolving to the closed session
+   */
+  async closeAgentSession(sessionId: string, status: AgentSessionStatus): Promise<AgentSession> {
+    try {
+      return await apiCall<AgentSession>("close_agent_session_command", { sessionId, status });
+    } catch (error) {
+      console.error("Failed to close agent session:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the agent sessions of a project
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the sessions, newest first
+   */
+  async getProjectAgentSessions(projectPath: string): Promise<AgentSession[]> {
+    try {
+      return await apiCall<AgentSession[]>("get_project_agent_sessions", { projectPath });
+    } catch (error) {
+      console.error("Failed to get agent sessions:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the full report of an agent session
+   * @param sessionId - The session ID
+   * @returns Promise resolving to the session's snapshots, errors and delivered chunks
+   */
+  async getAgentSessionReport(sessionId: string): Promise<AgentSessionReport> {
+    try {
+      return await apiCall<AgentSessionReport>("get_agent_session_report_command", { sessionId });
+    } catch (error) {
+      console.error("Failed to get agent session report:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets chunks created per day and type, with the running total
+   * @param projectPath - Absolute path to the project
+   * @param days - Window in days (default 30)
+   * @returns Promise resolving to one entry per day with chunks
+   */
+  async getChunksOverTime(projectPath: string, days?: number): Promise<ChunksPerDay[]> {
+    try {
+      return await apiCall<ChunksPerDay[]>("get_chunks_over_time", { projectPath, days });
+    } catch (error) {
+      console.error("Failed to get chunks over time:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets master/agent snapshots per day and the average time between master snapshots
+   * @param projectPath - Absolute path to the project
+   * @param days - Window in days (default 30)
+   * @returns Promise resolving to the snapshot cadence
+   */
+  async getSnapshotCadence(projectPath: string, days?: number): Promise<SnapshotCadence> {
+    try {
+      return await apiCall<SnapshotCadence>("get_snapshot_cadence", { projectPath, days });
+    } catch (error) {
+      console.error("Failed to get snapshot cadence:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets errors opened and resolved per day, and how many stayed open
+   * @param projectPath - Absolute path to the project
+   * @param days - Window in days (default 30)
+   * @returns Promise resolving to one entry per day with error activity
+   */
+  async getErrorRates(projectPath: string, days?: number): Promise<ErrorRatePerDay[]> {
+    try {
+      return await apiCall<ErrorRatePerDay[]>("get_error_rates", { projectPath, days });
+    } catch (error) {
+      console.error("Failed to get error rates:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets how fresh the index is for each file extension
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to indexed file counts and index ages per extension
+   */
+  async getIndexFreshness(projectPath: string): Promise<FileTypeFreshness[]> {
+    try {
+      return await apiCall<FileTypeFreshness[]>("get_index_freshness", { projectPath });
+    } catch (error) {
+      console.error("Failed to get index freshness:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Registers (or replaces) a logical project made of several root folders
+   * @param name - Logical project name (namespace `logical://{name}`)
+   * @param roots - Root folders with their label and per-root settings
+   * @returns Promise resolving to the registered project
+   */
+  async registerLogicalProject(name: string, roots: ProjectRoot[]): Promise<LogicalProject> {
+    try {
+      return await apiCall<LogicalProject>("register_logical_project_command", { name, roots });
+    } catch (error) {
+      console.error("Failed to register logical project:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the registered logical projects
+   * @returns Promise resolving to the logical projects, sorted by name
+   */
+  async getLogicalProjects(): Promise<LogicalProject[]> {
+    try {
+      return await apiCall<LogicalProject[]>("get_logical_projects_command");
+    } catch (error) {
+      console.error("Failed to get logical projects:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Deletes a logical project (the indexes of its roots are kept)
+   * @param name - Logical project name
+   * @returns Promise resolving to whether the project existed
+   */
+  async deleteLogicalProject(name: string): Promise<boolean> {
+    try {
+      return await apiCall<boolean>("delete_logical_project_command", { name });
+    } catch (error) {
+      console.error("Failed to delete logical project:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Searches chunks across every root of a logical project
+   * @param name - Logical project name
+   * @param query - Search filters; file paths are `label/relative/path`
+   * @returns Promise resolving to the matching chunks, newest first
+   */
+  async searchLogicalChunks(name: string, query: ChunkQuery): Promise<Chunk[]> {
+    try {
+      return await apiCall<Chunk[]>("search_logical_chunks", { name, query });
+    } catch (error) {
+      console.error("Failed to search logical project chunks:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Creates a master snapshot in every root of a logical project with snapshots enabled
+   * @param name - Logical project name
+   * @param userMessage - The user's message
+   * @returns Promise resolving to the snapshot created in each root
+   */
+  async createLogicalSnapshot(name: string, userMessage: string): Promise<LogicalSnapshot[]> {
+    try {
+      return await apiCall<LogicalSnapshot[]>("create_logical_snapshot_command", {
+        name,
+        userMessage
+      });
+    } catch (error) {
+      console.error("Failed to create logical snapshot:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Clones a remote git repository into the app cache and indexes it read-only (no snapshots)
+   * @param url - Repository URL (https, ssh or file)
+   * @param depth - Clone depth (default 1; 0 fetches the full history)
+   * @param options - Optional chunking options
+   * @returns Promise resolving to the cached repository and the indexing result
+   */
+  async indexRemoteRepository(
+    url: string,
+    depth?: number,
+    options?: ChunkingOptions
+  ): Promise<RemoteIndexResult> {
+    try {
+      return await apiCall<RemoteIndexResult>("index_remote_repository", { url, depth, options });
+    } catch (error) {
+      console.error("Failed to index remote repository:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the remote repositories indexed as read-only references
+   * @returns Promise resolving to the cached repositories
+   */
+  async getRemoteRepositories(): Promise<RemoteRepository[]> {
+    try {
+      return await apiCall<RemoteRepository[]>("get_remote_repositories_command");
+    } catch (error) {
+      console.error("Failed to get remote repositories:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Marks or unmarks a project index as read-only (rejects reindexing, snapshots and any write)
+   * @param projectPath - Path to the project
+   * @param readOnly - Whether the index becomes read-only
+   * @param reason - Optional note shown when a write is rejected
+   */
+  async setProjectReadOnly(projectPath: string, readOnly: boolean, reason?: string): Promise<void> {
+    try {
+      return await apiCall<void>("set_project_read_only_command", { projectPath, readOnly, reason });
+    } catch (error) {
+      console.error("Failed to set project read-only flag:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the read-only flag of a project index
+   * @param projectPath - Path to the project
+   * @returns Promise resolving to the flag, or null when the index is writable
+   */
+  async getProjectReadOnly(projectPath: string): Promise<ReadOnlyProject | null> {
+    try {
+      return await apiCall<ReadOnlyProject | null>("get_project_read_only_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get project read-only flag:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the projects whose index is read-only
+   * @returns Promise resolving to the read-only projects
+   */
+  async getReadOnlyProjects(): Promise<ReadOnlyProject[]> {
+    try {
+      return await apiCall<ReadOnlyProject[]>("get_read_only_projects_command");
+    } catch (error) {
+      console.error("Failed to get read-only projects:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Exports the project index (chunks, business rules and relationships) to a JSON file
+   * @param projectPath - Path to the project
+   * @param outputPath - File to write the export to, inside `<app data>/exports` or the project
+   *   (existing files are only replaced inside `<app data>/exports`)
+   * @returns Promise resolving to what was exported
+   */
+  async exportProjectIndex(projectPath: string, outputPath: string): Promise<IndexExportSummary> {
+    try {
+      return await apiCall<IndexExportSummary>("export_project_index", { projectPath, outputPath });
+    } catch (error) {
+      console.error("Failed to export project index:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Merges an index exported by a teammate into the project, by content hash and timestamps
+   * @param projectPath - Path to the project
+   * @param inputPath - Exported index file, inside `<app data>/exports` or the project
+   * @returns Promise resolving to the merge report with the rule and annotation conflicts
+   */
+  async importProjectIndex(projectPath: string, inputPath: string): Promise<IndexMergeReport> {
+    try {
+      return await apiCall<IndexMergeReport>("import_project_index", { projectPath, inputPath });
+    } catch (error) {
+      console.error("Failed to import project index:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Builds an anonymized index profile (counts, sizes and timings, no paths or content)
+   * to attach to indexing performance bug reports
+   * @param projectPath - Path to the project
+   * @param outputPath - Optional file to also write the profile to as JSON, inside
+   *   `<app data>/exports` or the project
+   * @returns Promise resolving to the profile
+   */
+  async exportIndexProfile(projectPath: string, outputPath?: string): Promise<IndexProfile> {
+    try {
+      return await apiCall<IndexProfile>("export_index_profile", { projectPath, outputPath });
+    } catch (error) {
+      console.error("Failed to export index profile:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Benchmarks the index: dry-run indexing throughput and latency of representative queries.
+   * The run is stored and compared with the previous one
+   * @param projectPath - Path to the project
+   * @param iterations - Repetitions of each query (default 20)
+   * @param options - Chunking options for the dry-run indexing
+   * @returns Promise resolving to the stored run with its regressions
+   */
+  async benchIndex(
+    projectPath: string,
+    iterations?: number,
+    options?: ChunkingOptions
+  ): Promise<BenchmarkRun> {
+    try {
+      return await apiCall<BenchmarkRun>("bench_index", { projectPath, iterations, options });
+    } catch (error) {
+      console.error("Failed to benchmark index:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the stored benchmark runs of a project
+   * @param projectPath - Path to the project
+   * @param limit - Maximum runs to return (default 20)
+   * @returns Promise resolving to the runs, most recent first
+   */
+  async getBenchmarkRuns(projectPath: string, limit?: number): Promise<BenchmarkRun[]> {
+    try {
+      return await apiCall<BenchmarkRun[]>("get_benchmark_runs_command", { projectPath, limit });
+    } catch (error) {
+      console.error("Failed to get benchmark runs:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists files that tree-sitter failed to parse (the AST skip list)
+   * @param projectPath - Path to the project
+   * @returns Promise resolving to the parse failures, most attempted first
+   */
+  async getParseFailures(projectPath: string): Promise<ParseFailure[]> {
+    try {
+      return await apiCall<ParseFailure[]>("get_parse_failures_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get parse failures:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Removes files from the parse-failure skip list so the next indexing retries them
+   * @param projectPath - Path to the project
+   * @param filePaths - Files to clear (all files when omitted)
+   * @returns Promise resolving to the number of entries cleared
+   */
+  async clearParseFailures(projectPath: string, filePaths?: string[]): Promise<number> {
+    try {
+      return await apiCall<number>("clear_parse_failures_command", { projectPath, filePaths });
+    } catch (error) {
+      console.error("Failed to clear parse failures:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the file extensions a project indexes as source code
+   * @param projectPath - The project path
+   * @returns Promise resolving to the effective extensions (defaults, opcode.toml and app overrides)
+   */
+  async getCodeExtensions(projectPath: string): Promise<string[]> {
+    try {
+      return await apiCall<string[]>("get_code_extensions_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get code extensions:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Adds a file extension to the ones a project indexes as source code
+   * @param projectPath - The project path
+   * @param extension - Extension with or without the leading dot (e.g. "zig")
+   * @returns Promise resolving to the effective extensions
+   */
+  async addCodeExtension(projectPath: string, extension: string): Promise<string[]> {
+    try {
+      return await apiCall<string[]>("add_code_extension_command", { projectPath, extension });
+    } catch (error) {
+      console.error("Failed to add code extension:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Removes a file extension (including a default one) from the ones a project indexes
+   * @param projectPath - The project path
+   * @param extension - Extension with or without the leading dot
+   * @returns Promise resolving to the effective extensions
+   */
+  async removeCodeExtension(projectPath: string, extension: string): Promise<string[]> {
+    try {
+      return await apiCall<string[]>("remove_code_extension_command", { projectPath, extension });
+    } catch (error) {
+      console.error("Failed to remove code extension:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the content hashing algorithm and the anonymous id of a project
+   * @param projectPath - The project path
+   * @returns Promise resolving to the hashing settings ('sha256' when never set)
+   */
+  async getProjectHashing(projectPath: string): Promise<ProjectHashing> {
+    try {
+      return await apiCall<ProjectHashing>("get_project_hashing_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get project hashing:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Sets the algorithm used to hash a project's file contents from the next indexing on.
+   * Stored hashes keep the algorithm they were computed with
+   * @param projectPath - The project path
+   * @param algorithm - 'sha256' or 'blake3' (faster on large repositories)
+   * @returns Promise resolving to the updated hashing settings
+   */
+  async setHashAlgorithm(projectPath: string, algorithm: HashAlgorithm): Promise<ProjectHashing> {
+    try {
+      return await apiCall<ProjectHashing>("set_hash_algorithm_command", { projectPath, algorithm });
+    } catch (error) {
+      console.error("Failed to set hash algorithm:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the embedding backend of a project
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the settings, including whether local models are available
+   */
+  async getEmbeddingSettings(projectPath: string): Promise<EmbeddingSettings> {
+    try {
+      return await apiCall<EmbeddingSettings>("get_embedding_settings_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get embedding settings:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Selects the embedding backend of a project
+   * @param projectPath - Absolute path to the project
+   * @param backend - HTTP or local backend, or null to remove it
+   * @returns Promise resolving to the updated settings
+   */
+  async setEmbeddingBackend(projectPath: string, backend: EmbeddingBackend | null): Promise<EmbeddingSettings> {
+    try {
+      return await apiCall<EmbeddingSettings>("set_embedding_backend_command", { projectPath, backend });
+    } catch (error) {
+      console.error("Failed to set embedding backend:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Embeds the project's new or changed chunks with its backend
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the refresh report
+   */
+  async refreshEmbeddings(projectPath: string): Promise<EmbeddingRefreshReport> {
+    try {
+      return await apiCall<EmbeddingRefreshReport>("refresh_embeddings_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to refresh embeddings:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Finds the chunks most similar to a natural-language query
+   * @param projectPath - Absolute path to the project
+   * @param query - Search text
+   * @param limit - Maximum number of results (default 20)
+   * @returns Promise resolving to the matches, best first
+   */
+  async semanticSearch(projectPath: string, query: string, limit?: number): Promise<SemanticMatch[]> {
+    try {
+      return await apiCall<SemanticMatch[]>("semantic_search_command", { projectPath, query, limit });
+    } catch (error) {
+      console.error("Failed to run semantic search:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the versions of a chunk (same file, type and entity), oldest first
+   * @param chunkId - Any version of the chunk
+   * @returns Promise resolving to the versions, numbered from 1
+   */
+  async getChunkVersions(chunkId: number): Promise<ChunkVersion[]> {
+    try {
+      return await apiCall<ChunkVersion[]>("get_chunk_versions_command", { chunkId });
+    } catch (error) {
+      console.error("Failed to get chunk versions:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets a chunk together with its outgoing and incoming relationships and a summary
+   * of each related chunk, in a single call
+   * @param chunkId - ID of the chunk
+   * @returns Promise resolving to the chunk with its context, or null if it doesn't exist
+   */
+  async getChunkWithContext(chunkId: number): Promise<ChunkWithContext | null> {
+    try {
+      return await apiCall<ChunkWithContext | null>("get_chunk_with_context_command", { chunkId });
+    } catch (error) {
+      console.error("Failed to get chunk with context:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Stores a user_content chunk (meeting note, design decision...) written by the user or an agent
+   * @param input - Project, title, content and optional file, kind, tags and metadata
+   * @returns Promise resolving to the stored chunk
+   */
+  async createCustomChunk(input: CustomChunkInput): Promise<Chunk> {
+    try {
+      return await apiCall<Chunk>("create_custom_chunk_command", { input });
+    } catch (error) {
+      console.error("Failed to create custom chunk:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Replaces the metadata of a chunk, or merges it into the current one (JSON merge patch)
+   * @param chunkId - ID of the chunk
+   * @param metadata - JSON object with the new metadata
+   * @param merge - Merge instead of replacing; keys set to null are removed
+   * @returns Promise resolving to the updated chunk
+   */
+  async updateChunkMetadata(
+    chunkId: number,
+    metadata: Record<string, unknown>,
+    merge?: boolean
+  ): Promise<Chunk> {
+    try {
+      return await apiCall<Chunk>("update_chunk_metadata_command", { chunkId, metadata, merge });
+    } catch (error) {
+      console.error("Failed to update chunk metadata:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets a unified diff of the content between two versions of a chunk
+   * @param chunkId - Any version of the chunk
+   * @param fromVersion - Base version (1-based)
+   * @param toVersion - Target version (1-based)
+   * @returns Promise resolving to the diff with line stats
+   */
+  async diffChunkVersions(
+    chunkId: number,
+    fromVersion: number,
+    toVersion: number
+  ): Promise<ChunkVersionDiff> {
+    try {
+      return await apiCall<ChunkVersionDiff>("diff_chunk_versions_command", {
+        chunkId,
+        fromVersion,
+        toVersion
+      });
+    } catch (error) {
+      console.error("Failed to diff chunk versions:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets a line range of a file from its stored raw source chunk, without reading the filesystem
+   * @param projectPath - Absolute path to the project
+   * @param filePath - Path relative to the project
+   * @param startLine - First line (1-based)
+   * @param endLine - Last line (inclusive, clamped to the end of the file)
+   * @param snapshotId - Serve the version indexed at this snapshot instead of the current one
+   * @returns Promise resolving to the range, or null when the file is not indexed
+   */
+  async getSourceRange(
+    projectPath: string,
+    filePath: string,
+    startLine: number,
+    endLine: number,
+    snapshotId?: number
+  ): Promise<SourceRange | null> {
+    try {
+      return await apiCall<SourceRange | null>("get_source_range_command", {
+        projectPath,
+        filePath,
+        startLine,
+        endLine,
+        snapshotId
+      });
+    } catch (error) {
+      console.error("Failed to get source range:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Restores soft-deleted chunks of a project
+   * @param projectPath - Absolute path to the project
+   * @param filePath - Only restore the chunks of this file
+   * @returns Promise resolving to the number of chunks restored
+   */
+  async restoreDeletedChunks(projectPath: string, filePath?: string): Promise<number> {
+    try {
+      return await apiCall<number>("restore_deleted_chunks_command", { projectPath, filePath });
+    } catch (error) {
+      console.error("Failed to restore deleted chunks:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Permanently removes chunks that have been in the trash longer than the grace period
+   * @param projectPath - Only purge this project (all projects when omitted)
+   * @param graceDays - Days a deleted chunk is kept (defaults to 30)
+   * @returns Promise resolving to the number of chunks purged
+   */
+  async purgeDeletedChunks(projectPath?: string, graceDays?: number): Promise<number> {
+    try {
+      return await apiCall<number>("purge_deleted_chunks_command", { projectPath, graceDays });
+    } catch (error) {
+      console.error("Failed to purge deleted chunks:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Rebuilds a project index from scratch; chunks that are not regenerated stay in the trash
+   * @param projectPath - Absolute path to the project
+   * @param options - Optional chunking configuration
+   * @returns Promise resolving to chunking result with statistics
+   */
+  async resetProjectIndex(projectPath: string, options?: ChunkingOptions): Promise<ChunkingResult> {
+    try {
+      return await apiCall<ChunkingResult>("reset_project_index_command", { projectPath, options });
+    } catch (error) {
+      console.error("Failed to reset project index:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the size of the deduplicated content store and the bytes it saves
+   * @returns Promise resolving to content store statistics
+   */
+  async getContentStoreStats(): Promise<ContentStoreStats> {
+    try {
+      return await apiCall<ContentStoreStats>("get_content_store_stats_command");
+    } catch (error) {
+      console.error("Failed to get content store stats:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Moves the content of chunks stored before deduplication into shared blobs and removes orphan blobs
+   * @returns Promise resolving to the compaction report
+   */
+  async compactChunkContents(): Promise<ContentCompactionReport> {
+    try {
+      return await apiCall<ContentCompactionReport>("compact_chunk_contents_command");
+    } catch (error) {
+      console.error("Failed to compact chunk contents:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Compares file fingerprints with the disk and flags the chunks of changed or deleted files as stale
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the stale files and chunk count
+   */
+  async checkChunkStaleness(projectPath: string): Promise<StalenessReport> {
+    try {
+      return await apiCall<StalenessReport>("check_chunk_staleness", { projectPath });
+    } catch (error) {
+      console.error("Failed to check chunk staleness:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Reindexes only the files whose chunks are stale
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to chunking result with statistics
+   */
+  async refreshStaleChunks(projectPath: string): Promise<ChunkingResult> {
+    try {
+      return await apiCall<ChunkingResult>("refresh_stale_chunks", { projectPath });
+    } catch (error) {
+      console.error("Failed to refresh stale chunks:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Rebuilds every inferred relationship (imports, calls, tests, config usage, co-change) from the existing chunks
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the number of relationships created per source
+   */
+  async rebuildRelationships(projectPath: string): Promise<RelationshipRebuildReport> {
+    try {
+      return await apiCall<RelationshipRebuildReport>("rebuild_relationships_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to rebuild relationships:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Undoes the last destructive index operation (delete, prune or reset) of a project
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the undo result, or null when there is nothing to undo
+   */
+  async undoLastOperation(projectPath: string): Promise<UndoResult | null> {
+    try {
+      return await apiCall<UndoResult | null>("undo_last_operation_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to undo last operation:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the destructive index operations of a project, most recent first
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the operation history
+   */
+  async getIndexOperations(projectPath: string): Promise<IndexOperation[]> {
+    try {
+      return await apiCall<IndexOperation[]>("get_index_operations_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get index operations:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets active errors for a project
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to array of error logs
+   */
+  async getProjectErrors(projectPath: string): Promise<ErrorLog[]> {
+    try {
+      return await apiCall<ErrorLog[]>("get_project_errors", {
+        projectPath
+      });
+    } catch (error) {
+      console.error("Failed to get project errors:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the files the indexer couldn't fully parse, with their syntax errors
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the files with open syntax errors
+   */
+  async getSyntaxErrors(projectPath: string): Promise<FileSyntaxErrors[]> {
+    try {
+      return await apiCall<FileSyntaxErrors[]>("get_syntax_errors", { projectPath });
+    } catch (error) {
+      console.error("Failed to get syntax errors:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Groups project errors with similar messages/stacktraces
+   * @param projectPath - Absolute path to the project
+   * @param includeResolved - Whether resolved errors are clustered too (default false)
+   * @param similarity - Minimum shingle similarity between 0 and 1 (default 0.6)
+   * @returns Promise resolving to clusters ordered by total occurrences
+   */
+  async getProjectErrorClusters(
+    projectPath: string,
+    includeResolved?: boolean,
+    similarity?: number
+  ): Promise<ErrorCluster[]> {
+    try {
+      return await apiCall<ErrorCluster[]>("get_project_error_clusters", {
+        projectPath,
+        includeResolved,
+        similarity
+      });
+    } catch (error) {
+      console.error("Failed to get project error clusters:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the entry points detected in a project (mains, bin targets, routes, exported handlers, commands)
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the entry points, ordered by file
+   */
+  async getEntryPoints(projectPath: string): Promise<EntryPoint[]> {
+    try {
+      return await apiCall<EntryPoint[]>("get_entry_points_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get entry points:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the Tauri command surface of a project: every `#[tauri::command]`, its parameters and its frontend callers
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the surface, or null if the project has no Tauri commands
+   */
+  async getTauriSurface(projectPath: string): Promise<TauriSurface | null> {
+    try {
+      return await apiCall<TauriSurface | null>("get_tauri_surface_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get Tauri surface:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the frontend routes of a project and the component that renders each one
+   * @param projectPath - Absolute path to the project
+   * @param path - Optional route path (e.g. "/settings") to look up a single route
+   * @returns Promise resolving to the routes, in declaration order
+   */
+  async getUiRoutes(projectPath: string, path?: string): Promise<UiRoute[]> {
+    try {
+      return await apiCall<UiRoute[]>("get_ui_routes_command", { projectPath, path });
+    } catch (error) {
+      console.error("Failed to get UI routes:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Reports translation keys that are missing in some locale, unused in code, or used without being defined
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the i18n report
+   */
+  async getI18nReport(projectPath: string): Promise<I18nReport> {
+    try {
+      return await apiCall<I18nReport>("get_i18n_report_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get i18n report:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the SQL queries that read or write a table, for data-access impact analysis
+   * @param projectPath - Absolute path to the project
+   * @param table - Table name (case-insensitive, schema prefix ignored)
+   * @returns Promise resolving to the queries ordered by file and line
+   */
+  async findTableQueries(projectPath: string, table: string): Promise<TableQuery[]> {
+    try {
+      return await apiCall<TableQuery[]>("find_table_queries_command", { projectPath, table });
+    } catch (error) {
+      console.error("Failed to find table queries:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Lists the files that import a given package and/or dependency kind
+   * @param projectPath - Absolute path to the project
+   * @param packageName - Optional package name (e.g. "reqwest")
+   * @param kind - Optional dependency kind filter
+   * @returns Promise resolving to array of dependency usages
+   */
+  async findDependencyUsages(
+    projectPath: string,
+    packageName?: string,
+    kind?: DependencyKind
+  ): Promise<DependencyUsage[]> {
+    try {
+      return await apiCall<DependencyUsage[]>("find_dependency_usages", {
+        projectPath,
+        package: packageName,
+        kind
+      });
+    } catch (error) {
+      console.error("Failed to find dependency usages:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Cross-references manifest dependencies with resolved imports
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to one report per manifest
+   */
+  async auditProjectDependencies(projectPath: string): Promise<DependencyAuditReport[]> {
+    try {
+      return await apiCall<DependencyAuditReport[]>("audit_project_dependencies", {
+        projectPath
+      });
+    } catch (error) {
+      console.error("Failed to audit project dependencies:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Scans project dependencies against a local OSV advisory database
+   * @param projectPath - Absolute path to the project
+   * @param advisoryDbPath - Optional path to an OSV file or directory (defaults to app data dir)
+   * @returns Promise resolving to the security findings of this scan
+   */
+  async scanDependencyVulnerabilities(
+    projectPath: string,
+    advisoryDbPath?: string
+  ): Promise<SecurityFinding[]> {
+    try {
+      return await apiCall<SecurityFinding[]>("scan_dependency_vulnerabilities", {
+        projectPath,
+        advisoryDbPath
+      });
+    } catch (error) {
+      console.error("Failed to scan dependency vulnerabilities:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the security findings from the latest scan
+   * @param projectPath - Absolute path to the project
+   * @param packageName - Optional package name filter
+   * @returns Promise resolving to array of security findings
+   */
+  async getSecurityFindings(projectPath: string, packageName?: string): Promise<SecurityFinding[]> {
+    try {
+      return await apiCall<SecurityFinding[]>("get_security_findings_command", {
+        projectPath,
+        packageName
+      });
+    } catch (error) {
+      console.error("Failed to get security findings:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets potential secrets detected in indexed source and config files
+   * @param projectPath - Absolute path to the project
+   * @param severity - Optional severity filter ("critical", "high", "medium")
+   * @param filePath - Optional file filter
+   * @returns Promise resolving to array of secret findings with masked previews
+   */
+  async getSecretFindings(
+    projectPath: string,
+    severity?: string,
+    filePath?: string
+  ): Promise<SecretFinding[]> {
+    try {
+      return await apiCall<SecretFinding[]>("get_secret_findings_command", {
+        projectPath,
+        severity,
+        filePath
+      });
+    } catch (error) {
+      console.error("Failed to get secret findings:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Logs an error in the system
+   * @param projectPath - Absolute path to the project
+   * @param errorType - Type/category of the error
+   * @param message - Error message
+   * @param filePath - Optional path to file where error occurred
+   * @param stacktrace - Optional error stacktrace
+   * @param snapshotId - Snapshot to attribute the error to (defaults to the project's active snapshot)
+   * @returns Promise resolving to the new error log ID
+   */
+  async logError(
+    projectPath: string,
+    errorType: string,
+    message: string,
+    filePath?: string,
+    stacktrace?: string,
+    snapshotId?: number
+  ): Promise<number> {
+    try {
+      return await apiCall<number>("log_error_command", {
+        projectPath,
+        errorType,
+        message,
+        filePath,
+        stacktrace,
+        snapshotId
+      });
+    } catch (error) {
+      console.error("Failed to log error:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Imports linter JSON output as severity-tagged project diagnostics
+   * @param projectPath - Absolute path to the project
+   * @param tool - Linter that produced the output ("clippy" or "eslint")
+   * @param output - Raw output of `cargo clippy --message-format=json` or `eslint -f json`
+   * @returns Promise resolving to the import summary
+   */
+  async importLintDiagnostics(
+    projectPath: string,
+    tool: 'clippy' | 'eslint',
+    output: string
+  ): Promise<LintImportResult> {
+    try {
+      return await apiCall<LintImportResult>("import_lint_diagnostics", {
+        projectPath,
+        tool,
+        output
+      });
+    } catch (error) {
+      console.error("Failed to import lint diagnostics:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the files with the most open lint diagnostics
+   * @param projectPath - Absolute path to the project
+   * @param limit - Maximum number of files (default 10)
+   * @returns Promise resolving to array of lint offenders
+   */
+  async getLintOffenders(projectPath: string, limit?: number): Promise<LintOffender[]> {
+    try {
+      return await apiCall<LintOffender[]>("get_lint_offenders_command", {
+        projectPath,
+        limit
+      });
+    } catch (error) {
+      console.error("Failed to get lint offenders:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Stores a `textDocument/publishDiagnostics` payload from an editor or language server,
+   * replacing the previous diagnostics of the file
+   * @param projectPath - Absolute path to the project
+   * @param payload - The notification params (`uri`, `diagnostics`, `version`) or the full JSON-RPC notification
+   * @returns Promise resolving to the publish summary
+   */
+  async publishLspDiagnostics(
+    projectPath: string,
+    payload: Record<string, unknown>
+  ): Promise<LspPublishResult> {
+    try {
+      return await apiCall<LspPublishResult>("publish_lsp_diagnostics_command", {
+        projectPath,
+        payload
+      });
+    } catch (error) {
+      console.error("Failed to publish LSP diagnostics:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the LSP diagnostics of a project, optionally for a single file
+   * @param projectPath - Absolute path to the project
+   * @param filePath - Optional project-relative file path
+   * @returns Promise resolving to array of LSP diagnostics
+   */
+  async getLspDiagnostics(projectPath: string, filePath?: string): Promise<LspDiagnostic[]> {
+    try {
+      return await apiCall<LspDiagnostic[]>("get_lsp_diagnostics_command", {
+        projectPath,
+        filePath
+      });
+    } catch (error) {
+      console.error("Failed to get LSP diagnostics:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Discards the LSP diagnostics of a project, optionally for a single file
+   * @param projectPath - Absolute path to the project
+   * @param filePath - Optional project-relative file path
+   * @returns Promise resolving to the number of diagnostics removed
+   */
+  async clearLspDiagnostics(projectPath: string, filePath?: string): Promise<number> {
+    try {
+      return await apiCall<number>("clear_lsp_diagnostics_command", {
+        projectPath,
+        filePath
+      });
+    } catch (error) {
+      console.error("Failed to clear LSP diagnostics:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets open error logs and LSP diagnostics of a project in a single list
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to array of diagnostics, most recent first
+   */
+  async getOpenDiagnostics(projectPath: string): Promise<ProjectDiagnostic[]> {
+    try {
+      return await apiCall<ProjectDiagnostic[]>("get_open_diagnostics_command", {
+        projectPath
+      });
+    } catch (error) {
+      console.error("Failed to get open diagnostics:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Imports compiler/build errors and links them to the offending chunks
+   * @param projectPath - Absolute path to the project
+   * @param tool - Build tool that produced the output ("cargo" or "tsc")
+   * @param output - Raw output of `cargo build --message-format=json` or `tsc --pretty false`
+   * @returns Promise resolving to the import summary
+   */
+  async importBuildErrors(
+    projectPath: string,
+    tool: 'cargo' | 'tsc',
+    output: string
+  ): Promise<BuildImportResult> {
+    try {
+      return await apiCall<BuildImportResult>("import_build_errors", {
+        projectPath,
+        tool,
+        output
+      });
+    } catch (error) {
+      console.error("Failed to import build errors:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Imports a JUnit XML report and links each test case to its test chunk
+   * @param projectPath - Absolute path to the project
+   * @param xml - JUnit XML report content
+   * @returns Promise resolving to the run summary
+   */
+  async importJUnitReport(projectPath: string, xml: string): Promise<TestImportResult> {
+    try {
+      return await apiCall<TestImportResult>("import_junit_report_command", {
+        projectPath,
+        xml
+      });
+    } catch (error) {
+      console.error("Failed to import JUnit report:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets failing tests of a run, with the code under test of each one
+   * @param projectPath - Absolute path to the project
+   * @param runId - Optional run ID (defaults to the latest import)
+   * @returns Promise resolving to array of failing test results
+   */
+  async getFailingTests(projectPath: string, runId?: string): Promise<TestResult[]> {
+    try {
+      return await apiCall<TestResult[]>("get_failing_tests_command", {
+        projectPath,
+        runId
+      });
+    } catch (error) {
+      console.error("Failed to get failing tests:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Suggests the tests to run for a change, following tested_by and calls relationships
+   * @param projectPath - Absolute path to the project
+   * @param files - Changed files, relative to the project root
+   * @param snapshotId - Snapshot whose diff is the change (used when no files are given)
+   * @returns Promise resolving to the affected tests, closest first
+   */
+  async suggestTestsForChanges(
+    projectPath: string,
+    files?: string[],
+    snapshotId?: number
+  ): Promise<TestSuggestion> {
+    try {
+      return await apiCall<TestSuggestion>("suggest_tests_for_changes_command", {
+        projectPath,
+        files,
+        snapshotId
+      });
+    } catch (error) {
+      console.error("Failed to suggest tests for changes:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Recomputes fan-in, fan-out and centrality of the chunks in the relationship graph
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the number of chunks measured
+   */
+  async computeGraphMetrics(projectPath: string): Promise<number> {
+    try {
+      return await apiCall<number>("compute_graph_metrics_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to compute graph metrics:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Ranks the project's files by cyclomatic complexity, most complex first
+   * @param projectPath - Absolute path to the project
+   * @param limit - Maximum number of files to return
+   * @returns Promise resolving to the complexity report
+   */
+  async getComplexityReport(projectPath: string, limit?: number): Promise<ComplexityReport> {
+    try {
+      return await apiCall<ComplexityReport>("get_complexity_report", { projectPath, limit });
+    } catch (error) {
+      console.error("Failed to get complexity report:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the stored graph metrics of the project's chunks, most central first
+   * @param projectPath - Absolute path to the project
+   * @param limit - Maximum number of chunks to return
+   * @returns Promise resolving to the chunks with their metrics
+   */
+  async getGraphMetrics(projectPath: string, limit?: number): Promise<EntityGraphMetrics[]> {
+    try {
+      return await apiCall<EntityGraphMetrics[]>("get_graph_metrics_command", {
+        projectPath,
+        limit
+      });
+    } catch (error) {
+      console.error("Failed to get graph metrics:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Gets the scored health report of a project: open errors, uncovered hotspots,
+   * stale files, unvalidated rules and failing tests
+   * @param projectPath - Absolute path to the project
+   * @returns Promise resolving to the health report
+   */
+  async getProjectHealth(projectPath: string): Promise<ProjectHealthReport> {
+    try {
+      return await apiCall<ProjectHealthReport>("get_project_health_command", { projectPath });
+    } catch (error) {
+      console.error("Failed to get project health:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Scores the risk of modifying the given files from their error history, churn,
+   * complexity and dependents, so an agent can demand extra tests or human review
+   * @param projectPath - Absolute path to the project
+   * @param files - Files about to be modified, relative to the project root
+   * @returns Promise resolving to the risk assessment, riskiest file first
+   */
+  async riskAssessment(projectPath: string, files: string[]): Promise<RiskAssessment> {
+    try {
+      return await apiCall<RiskAssessment>("risk_assessment", { projectPath, files });
+    } catch (error) {
+      console.error("Failed to assess risk:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Exports the project's chunks and relationships as a Cypher script for Neo4j
+   * @param projectPath - Absolute path to the project
+   * @param includeContent - Whether to add each chunk's content as a node property
+   * @returns Promise resolving to the Cypher script
+   */
+  async exportGraphCypher(projectPath: string, includeContent?: boolean): Promise<string> {
+    try {
+      return await apiCall<string>("export_graph_cypher_command", { projectPath, includeContent });
+    } catch (error) {
+      console.error("Failed to export graph to Cypher:", error);
+      throw error;
+    }
+  },
+
+  /**
+   * Exports the project's relationship graph as DOT (Graphviz) or GraphML (Gephi)
+   * @param projectPath - Absolute path to the project
+   * @param format - Output format
+   * @param outputPath - Optional file to also write the graph to, inside `<app data>/exports`
+   *   or the project
+   * @returns Promise resolving to the graph document
+   */
+  async exportCallgraph(projectPath: string, format: GraphFormat, outputPath?: string): Promise<string> {
+    try {
+      return await apiCall<string>("export_callgraph", { projectPath, format, outputPath });
+    } catch (error) {
+      console.error("Failed to export callgraph:", error);
       throw error;
     }
   },
@@ -110,8 +110,13 @@ const agentStore: StateCreator<
         console.log('[Snapshots] Creating master snapshot before agent execution...');
         let masterSnapshotId: number | undefined;
         try {
-          masterSnapshotId = await api.createMasterSnapshot(data.projectPath, data.task);
-          console.log('[Snapshots] Master snapshot created with ID:', masterSnapshotId);
+          // Read-only indexes (reference repos, shared exports) never get snapshots
+          if (await api.getProjectReadOnly(data.projectPath)) {
+            console.log('[Snapshots] Project index is read-only, skipping snapshots');
+          } else {
+            masterSnapshotId = await api.createMasterSnapshot(data.projectPath, data.task);
+            console.log('[Snapshots] Master snapshot created with ID:', masterSnapshotId);
+          }
         } catch (snapshotError) {
           console.warn('[Snapshots] Failed to create master snapshot:', snapshotError);
           // Continue with agent execution even if snapshot fails
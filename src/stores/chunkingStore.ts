@@ -14,6 +14,7 @@ import type {
   ErrorLog,
   ChunkFilterOptions,
   ChunkStats,
+  ReadOnlyProject,
 } from '@/types/chunking';
 
 interface ChunkingState {
@@ -28,6 +29,7 @@ interface ChunkingState {
     agent: Snapshot[];
   };
   errors: ErrorLog[];
+  readOnly: ReadOnlyProject | null;
 
   // Processing state
   isProcessing: boolean;
@@ -56,6 +58,7 @@ interface ChunkingState {
   // Actions - Processing
   processProject: (projectPath: string, options?: ChunkingOptions) => Promise<void>;
   refreshChunks: (projectPath: string) => Promise<void>;
+  setProjectReadOnly: (projectPath: string, readOnly: boolean, reason?: string) => Promise<void>;
 
   // Actions - Search & Filter
   searchChunks: (query: ChunkQuery) => Promise<void>;
@@ -130,6 +133,7 @@ const chunkingStore: StateCreator<
     agent: [],
   },
   errors: [],
+  readOnly: null,
 
   isProcessing: false,
   processingResult: null,
@@ -153,6 +157,14 @@ const chunkingStore: StateCreator<
   processProject: async (projectPath: string, options?: ChunkingOptions) => {
     set({ isProcessing: true, error: null, processingProgress: 0 });
     try {
+      // Read-only indexes are only browsed, never reprocessed
+      const readOnly = await api.getProjectReadOnly(projectPath);
+      if (readOnly) {
+        set({ readOnly, isProcessing: false });
+        await get().refreshChunks(projectPath);
+        return;
+      }
+
       const result = await api.processProjectChunks(projectPath, options);
       set({
         processingResult: result,
@@ -176,7 +188,15 @@ const chunkingStore: StateCreator<
   refreshChunks: async (projectPath: string) => {
     set({ isLoadingChunks: true, error: null });
     try {
-      const chunks = await api.searchChunks({ project_path: projectPath });
+      // On project open, reindex files committed outside the app (recorded by the git hooks)
+      if (get().currentProjectPath !== projectPath) {
+        await api.processGitHookTrigger(projectPath).catch(() => null);
+      }
+
+      const [chunks, readOnly] = await Promise.all([
+        api.searchChunks({ project_path: projectPath }),
+        api.getProjectReadOnly(projectPath),
+      ]);
 
       // Calculate stats
       const chunksByType: Record<ChunkType, number> = {} as any;
@@ -193,6 +213,7 @@ const chunkingStore: StateCreator<
       set({
         chunks,
         currentProjectPath: projectPath,
+        readOnly,
         stats,
         isLoadingChunks: false,
       });
@@ -204,6 +225,18 @@ const chunkingStore: StateCreator<
     }
   },
 
+  // Mark or unmark the project index as read-only
+  setProjectReadOnly: async (projectPath: string, readOnly: boolean, reason?: string) => {
+    try {
+      await api.setProjectReadOnly(projectPath, readOnly, reason);
+      set({ readOnly: await api.getProjectReadOnly(projectPath) });
+    } catch (error) {
+      set({
+        error: error instanceof Error ? error.message : 'Failed to update read-only flag',
+      });
+    }
+  },
+
   // Search chunks with filters
   searchChunks: async (query: ChunkQuery) => {
     set({ isLoadingChunks: true, error: null });
@@ -464,6 +497,7 @@ const chunkingStore: StateCreator<
       pendingBusinessRules: [],
       snapshots: { master: [], agent: [] },
       errors: [],
+      readOnly: null,
       isProcessing: false,
       processingResult: null,
       processingProgress: 0,
@@ -13,7 +13,9 @@ export type ChunkType =
   | 'project_metadata'
   | 'business_rules'
   | 'snapshot'
-  | 'error_log';
+  | 'error_log'
+  | 'issue'
+  | 'user_content';
 
 export interface Chunk {
   id?: number;
@@ -35,7 +37,36 @@ export type RelationshipType =
   | 'implements_rule'
   | 'modified_with'
   | 'associated_with_error'
-  | 'configures_for';
+  | 'configures_for'
+  | 'mentions'
+  | 'renders';
+
+export interface RelationshipRebuildReport {
+  project_path: string;
+  relationships_created: number;
+  imports: number;
+  calls: number;
+  tests: number;
+  config_usage: number;
+  co_changes: number;
+  /** Calls from JS/TS to the Rust that implements a Tauri command, N-API, wasm or FFI binding */
+  boundaries: number;
+  i18n_usage: number;
+}
+
+export interface CustomChunkInput {
+  project_path: string;
+  /** Stored as the chunk's entity_name */
+  title: string;
+  content: string;
+  /** Project file the content refers to, relative to the project root */
+  file_path?: string;
+  /** meeting_note, design_decision... */
+  kind?: string;
+  tags?: string[];
+  /** Extra data; must be a JSON object */
+  metadata?: Record<string, unknown>;
+}
 
 export interface ChunkRelationship {
   id?: number;
@@ -46,6 +77,24 @@ export interface ChunkRelationship {
   created_at: string;
 }
 
+export interface LinkedChunk {
+  relationship_id: number;
+  relationship_type: RelationshipType;
+  relationship_metadata?: string;
+  chunk_id: number;
+  chunk_type: ChunkType;
+  file_path?: string;
+  entity_name?: string;
+  /** First non-empty lines of the related chunk's content */
+  summary: string;
+}
+
+export interface ChunkWithContext {
+  chunk: Chunk;
+  outgoing: LinkedChunk[];
+  incoming: LinkedChunk[];
+}
+
 export interface BusinessRule {
   id?: number;
   project_path: string;
@@ -56,10 +105,36 @@ export interface BusinessRule {
   user_correction?: string;
   is_validated: boolean;
   validation_date?: string;
+  tags: string[];
+  check?: RuleCheck;
   created_at: string;
   updated_at: string;
 }
 
+/** Machine-checkable form of a business rule; `files` is an optional glob limiting the files checked */
+export type RuleCheck =
+  | { kind: 'naming'; pattern: string; files?: string }
+  | { kind: 'forbidden_import'; module: string; files?: string }
+  | { kind: 'required_pattern'; query: string; files?: string };
+
+export interface RuleViolation {
+  file_path: string;
+  chunk_id: number;
+  line?: number;
+  entity_name?: string;
+  message: string;
+}
+
+export interface RuleConformance {
+  rule: BusinessRule;
+  files_checked: number;
+  violations: RuleViolation[];
+  error?: string;
+}
+
+/** Which business rules are pinned at the top of every context pack */
+export type RulePinning = 'none' | 'validated' | 'critical';
+
 export type SnapshotType = 'master' | 'agent';
 
 export interface Snapshot {
@@ -70,7 +145,7 @@ export interface Snapshot {
   message: string;
   user_message?: string;
   changed_files: string; // JSON array
-  diff_summary?: string;
+  diff_summary?: string; // JSON DiffSummary (free text on older snapshots)
   metadata?: string;
 
   // Git integration fields
@@ -92,24 +167,503 @@ export interface ErrorLog {
   error_type: string;
   message: string;
   stacktrace?: string;
+  severity?: string;
   occurrence_count: number;
   first_seen: string;
   last_seen: string;
   is_resolved: boolean;
 }
 
+export interface FileSyntaxErrors {
+  file_path: string;
+  /** Open `syntax` error logs ordered by position */
+  errors: ErrorLog[];
+}
+
+export interface ErrorCluster {
+  error_type: string;
+  representative: ErrorLog;
+  examples: ErrorLog[];
+  error_ids: number[];
+  total_occurrences: number;
+  first_seen: string;
+  last_seen: string;
+}
+
+export interface LintImportResult {
+  tool: string;
+  imported: number;
+  errors: number;
+  warnings: number;
+  unmatched_files: string[];
+}
+
+export type TestStatus = 'passed' | 'failed' | 'error' | 'skipped';
+
+export interface TestResult {
+  id?: number;
+  project_path: string;
+  run_id: string;
+  suite_name?: string;
+  class_name?: string;
+  test_name: string;
+  file_path?: string;
+  status: TestStatus;
+  duration_ms?: number;
+  failure_message?: string;
+  failure_details?: string;
+  test_chunk_id?: number;
+  code_under_test: string[];
+  created_at: string;
+}
+
+export interface GraphMetrics {
+  /** Distinct chunks with a depends_on/calls relationship into this one */
+  fan_in: number;
+  /** Distinct chunks this one depends on or calls */
+  fan_out: number;
+  /** Degree centrality: (fan_in + fan_out) / 2(n - 1), between 0 and 1 */
+  centrality: number;
+}
+
+export interface EntityGraphMetrics {
+  chunk_id: number;
+  chunk_type: ChunkType;
+  file_path?: string;
+  entity_name?: string;
+  metrics: GraphMetrics;
+}
+
+export interface UncoveredHotspot {
+  file_path: string;
+  commits: number;
+}
+
+export interface HealthComponent {
+  /** open_errors, failing_tests, uncovered_hotspots, stale_files or unvalidated_rules */
+  name: string;
+  count: number;
+  penalty: number;
+  max_penalty: number;
+}
+
+export interface ProjectHealthReport {
+  project_path: string;
+  /** 0 (worst) to 100 */
+  score: number;
+  components: HealthComponent[];
+  open_errors: ErrorLog[];
+  uncovered_hotspots: UncoveredHotspot[];
+  /** Files changed or deleted on disk since they were indexed */
+  stale_files: string[];
+  unvalidated_rules: BusinessRule[];
+  failing_tests: TestResult[];
+  generated_at: string;
+}
+
+export interface RiskSignal {
+  /** errors, churn, complexity or fan_in */
+  name: string;
+  value: number;
+  points: number;
+  max_points: number;
+}
+
+export interface FileRisk {
+  file_path: string;
+  /** 0 (safe) to 100 */
+  score: number;
+  level: 'low' | 'medium' | 'high';
+  signals: RiskSignal[];
+  open_errors: number;
+  /** Has at least one tested_by relationship */
+  tested: boolean;
+  /** False for files without indexed source (e.g. new files) */
+  indexed: boolean;
+  requires_extra_tests: boolean;
+  requires_review: boolean;
+}
+
+export interface RiskAssessment {
+  project_path: string;
+  /** Highest score first */
+  files: FileRisk[];
+  max_score: number;
+  requires_extra_tests: boolean;
+  requires_review: boolean;
+  generated_at: string;
+}
+
+export interface SuggestedTest {
+  test_file: string;
+  chunk_id: number;
+  tested_file: string;
+  changed_file: string;
+  /** 0 = tests a changed file; n = reached through n `calls` hops */
+  distance: number;
+  /** Test functions whose assertions call code of `tested_file` (empty = run the whole file) */
+  test_functions: string[];
+}
+
+export interface TestSuggestion {
+  project_path: string;
+  changed_files: string[];
+  tests: SuggestedTest[];
+}
+
+export interface TestImportResult {
+  run_id: string;
+  total: number;
+  passed: number;
+  failed: number;
+  errors: number;
+  skipped: number;
+  linked: number;
+}
+
+export interface BuildImportResult {
+  tool: string;
+  errors_imported: number;
+  relationships_created: number;
+  unmatched_files: string[];
+}
+
+/**
+ * Transient diagnostic published by a language server (`textDocument/publishDiagnostics`)
+ */
+export interface LspDiagnostic {
+  id?: number;
+  project_path: string;
+  file_path: string;
+  /** Current raw source chunk of the file */
+  chunk_id?: number | null;
+  entity_name?: string | null;
+  /** 1-based positions */
+  line: number;
+  column: number;
+  end_line: number;
+  end_column: number;
+  severity: 'error' | 'warning' | 'information' | 'hint';
+  code?: string | null;
+  /** Language server that emitted it (e.g. "rust-analyzer") */
+  source?: string | null;
+  message: string;
+  document_version?: number | null;
+  received_at: string;
+}
+
+export interface LspPublishResult {
+  /** Null when the URI is not a file of the project */
+  file_path?: string | null;
+  uri: string;
+  stored: number;
+  /** Previous diagnostics of the file that were discarded */
+  replaced: number;
+}
+
+/**
+ * Open diagnostic of a project: an unresolved error log or an LSP diagnostic
+ */
+export interface ProjectDiagnostic {
+  origin: 'error_log' | 'lsp';
+  id: number;
+  file_path?: string | null;
+  entity_name?: string | null;
+  line?: number | null;
+  severity?: string | null;
+  /** Error type, or the language server for LSP diagnostics */
+  kind: string;
+  message: string;
+  seen_at: string;
+}
+
+export interface LintOffender {
+  file_path: string;
+  errors: number;
+  warnings: number;
+  top_rules: string[];
+}
+
+/** How much of the syntax tree an AST chunk keeps, from largest to smallest */
+export type AstDetail = 'full' | 'signatures_only' | 'skeleton';
+
 export interface AstMetadata {
   language: string;
   node_count: number;
   max_depth: number;
+  /** 1 + decision points (branches, loops, cases, ternaries, catches, `&&`/`||`) */
+  cyclomatic_complexity?: number;
+  /** Functions, methods and lambdas, nested ones included */
+  function_count?: number;
+  longest_function_lines?: number;
   has_syntax_errors: boolean;
+  /** Detail level actually used (lower than requested when over `ast_max_tokens`) */
+  detail?: AstDetail;
+  truncated?: boolean;
+  omitted_bytes?: number;
+  /** Tree-sitter node kind, only on per-entity chunks (e.g. `function_item`) */
+  entity_kind?: string;
+  start_line?: number;
+  end_line?: number;
+  /** File outline, only on the whole-file chunk */
+  symbols?: SymbolsMetadata;
+  /** ERROR/MISSING node positions, only on the whole-file chunk */
+  syntax_errors?: SyntaxErrorLocation[];
+}
+
+/** Syntax error tree-sitter recovered from; lines and columns are 1-based */
+export interface SyntaxErrorLocation {
+  line: number;
+  column: number;
+  end_line: number;
+  end_column: number;
+  message: string;
+}
+
+export interface FileComplexity {
+  file_path: string;
+  language: string;
+  cyclomatic_complexity: number;
+  function_count: number;
+  longest_function_lines: number;
+  node_count: number;
+}
+
+export interface ComplexityReport {
+  project_path: string;
+  /** Files with an AST, before applying the limit */
+  total_files: number;
+  files: FileComplexity[];
+  generated_at: string;
+}
+
+export interface SymbolInfo {
+  name: string;
+  kind: 'function' | 'method' | 'field';
+  signature: string;
+  line: number;
+}
+
+export interface ClassSymbol {
+  name: string;
+  /** Tree-sitter node kind (`struct_item`, `class_declaration`...) */
+  kind: string;
+  line: number;
+  members: SymbolInfo[];
+}
+
+export interface SymbolsMetadata {
+  exports: string[];
+  functions: SymbolInfo[];
+  classes: ClassSymbol[];
 }
 
 export interface CallgraphMetadata {
   is_static: boolean;
+  /** "kind:name", e.g. "main:main" or "route:GET /users" */
   entry_points: string[];
   external_calls: string[];
   call_count: number;
+  dependencies: ClassifiedDependency[];
+}
+
+export interface TestSubject {
+  name: string;
+  /** Module the subject is imported from; undefined when defined in the test file itself */
+  module?: string;
+}
+
+export interface TestExpectation {
+  line: number;
+  assertion: string;
+  subjects: TestSubject[];
+}
+
+export interface TestsMetadata {
+  /** Set on per-test-function chunks: the test file chunk they belong to */
+  parent_chunk_id?: number;
+  start_line?: number;
+  end_line?: number;
+  expectations: TestExpectation[];
+}
+
+export type DependencyKind = 'internal' | 'workspace' | 'external';
+
+export interface ClassifiedDependency {
+  specifier: string;
+  kind: DependencyKind;
+  package?: string;
+  resolved_file?: string;
+}
+
+export interface DependencyUsage {
+  file_path: string;
+  specifier: string;
+  kind: DependencyKind;
+  package?: string;
+}
+
+export type EntryPointKind = 'bin' | 'main' | 'route' | 'handler' | 'command';
+
+export interface EntryPoint {
+  file_path: string;
+  kind: EntryPointKind;
+  /** Bin or function name; "GET /users" for routes */
+  name: string;
+}
+
+export interface TauriCommandParam {
+  name: string;
+  /** Key expected in the `invoke` arguments object (camelCase unless `rename_all`) */
+  js_name: string;
+  rust_type: string;
+  /** State, AppHandle, Window...: supplied by Tauri, not by the frontend */
+  injected: boolean;
+}
+
+export interface TauriCallSite {
+  command: string;
+  file_path: string;
+  line: number;
+}
+
+export interface TauriCommand {
+  name: string;
+  file_path: string;
+  line: number;
+  is_async: boolean;
+  params: TauriCommandParam[];
+  return_type: string | null;
+  /** Whether the command appears in a `generate_handler!` list */
+  registered: boolean;
+  callers: TauriCallSite[];
+}
+
+export interface TauriSurface {
+  kind: 'tauri_surface';
+  commands: TauriCommand[];
+  /** `invoke` calls naming commands that the backend does not define */
+  unknown_invokes: TauriCallSite[];
+}
+
+export interface UiRoute {
+  path: string;
+  /** Component name, or the lazy import specifier when it could not be resolved */
+  component: string | null;
+  /** File that defines the component, when resolved */
+  component_file: string | null;
+  /** File that declares the route */
+  file_path: string;
+  line: number;
+  source: 'router' | 'file';
+}
+
+export interface I18nUsage {
+  key: string;
+  file_path: string;
+  line: number;
+}
+
+export interface I18nMissingKey {
+  /** `namespace:key` when the locale file has a namespace */
+  key: string;
+  missing_locales: string[];
+}
+
+export interface I18nReport {
+  project_path: string;
+  locales: string[];
+  key_count: number;
+  /** Keys defined in some locales but not all */
+  missing: I18nMissingKey[];
+  /** Keys never referenced from code (dynamic keys are not detected) */
+  unused: string[];
+  /** Translation calls whose key is not defined in any locale */
+  undefined: I18nUsage[];
+}
+
+export interface TableQuery {
+  file_path: string;
+  line: number;
+  /** SELECT, INSERT, UPDATE, DELETE, CREATE, ALTER... */
+  operation: string;
+  access: 'read' | 'write';
+  /** Statement with collapsed whitespace (for diesel/knex, the builder chain) */
+  sql: string;
+  source: 'sql' | 'literal' | 'diesel' | 'knex';
+}
+
+export type DependencyScope = 'normal' | 'dev' | 'build' | 'optional';
+
+export interface DeclaredDependency {
+  name: string;
+  scope: DependencyScope;
+  version?: string;
+}
+
+export interface ManifestMetadata {
+  manifest_type: string;
+  package_name?: string;
+  dependencies: DeclaredDependency[];
+}
+
+export interface UndeclaredDependency {
+  package: string;
+  files: string[];
+}
+
+export interface DependencyAuditReport {
+  manifest_path: string;
+  package_name?: string;
+  unused: string[];
+  undeclared: UndeclaredDependency[];
+}
+
+export interface SecurityFinding {
+  id?: number;
+  project_path: string;
+  metadata_chunk_id?: number;
+  manifest_path: string;
+  ecosystem: string;
+  package_name: string;
+  installed_version: string;
+  advisory_id: string;
+  aliases: string[];
+  severity: string;
+  summary: string;
+  fixed_version?: string;
+  affected_files: string[];
+  created_at: string;
+}
+
+export interface SecretFinding {
+  id?: number;
+  project_path: string;
+  chunk_id?: number;
+  file_path: string;
+  line_number: number;
+  rule_id: string;
+  severity: string;
+  masked_preview: string;
+  fingerprint: string;
+  created_at: string;
+}
+
+export interface ModuleDependencyMetadata {
+  kind: 'module';
+  module: string;
+  files: string[];
+  depends_on: string[];
+  dependents: string[];
+  external: string[];
+}
+
+export interface ModuleGraphMetadata {
+  kind: 'module_graph';
+  module_count: number;
+  edge_count: number;
+  edges: [string, string][];
+  external_packages: string[];
 }
 
 export interface CommitMetadata {
@@ -120,16 +674,692 @@ export interface CommitMetadata {
   files_modified: string[];
   insertions: number;
   deletions: number;
+  commit_type?: string;
+  scope?: string;
+  breaking_change: boolean;
+  conventional: boolean;
+  branches: string[];
+}
+
+export interface FileChangeStat {
+  path: string;
+  old_path?: string;
+  status: 'added' | 'modified' | 'deleted' | 'renamed' | 'copied' | 'typechange';
+  additions: number;
+  deletions: number;
+}
+
+/** Structured content of Snapshot.diff_summary (older snapshots hold free text) */
+export interface DiffSummary {
+  files_changed: number;
+  insertions: number;
+  deletions: number;
+  files: FileChangeStat[];
+}
+
+export interface SnapshotFileDiff {
+  file_path: string;
+  old_path?: string;
+  status: string;
+  additions: number;
+  deletions: number;
+  is_binary: boolean;
+  patch?: string;
+  truncated: boolean;
+}
+
+export interface SnapshotDiff {
+  snapshot_id: number;
+  files: SnapshotFileDiff[];
+  total_additions: number;
+  total_deletions: number;
+}
+
+export interface SnapshotRepairReport {
+  project_path: string;
+  tags_restored: string[];
+  tags_removed: string[];
+  branches_removed: string[];
+  missing_commits: number[];
+  orphaned_commits: string[];
+}
+
+export interface SnapshotNode {
+  id: number;
+  snapshot_type: 'master' | 'agent';
+  version: string;
+  version_major: number;
+  version_minor?: number;
+  message: string;
+  user_message?: string;
+  git_commit_hash?: string;
+  git_tag?: string;
+  git_branch?: string;
+  commit_exists: boolean;
+  tag_exists: boolean;
+  branch_exists: boolean;
+  is_active: boolean;
+  created_at: string;
+}
+
+export interface SnapshotEdge {
+  parent_id: number;
+  child_id: number;
+  kind: 'master' | 'branch';
+}
+
+export interface SnapshotGraph {
+  project_path: string;
+  nodes: SnapshotNode[];
+  edges: SnapshotEdge[];
+  active_snapshot_id?: number;
+  current_branch?: string;
+}
+
+export type GitProvider = 'github' | 'gitlab';
+
+export interface PullRequestInfo {
+  provider: GitProvider;
+  url: string;
+  number: number;
+  source_branch: string;
+  target_branch: string;
+  draft: boolean;
+  created_at: string;
+}
+
+export interface IssueImportResult {
+  issues_imported: number;
+  issues_unchanged: number;
+  relationships_created: number;
+}
+
+export interface ReviewFile {
+  file_path: string;
+  status: string;
+  additions: number;
+  deletions: number;
+  changed_entities: string[];
+  patch?: string;
+}
+
+export interface RelatedChunk {
+  reason: string;
+  chunk: Chunk;
+}
+
+export interface ReviewContext {
+  project_path: string;
+  snapshot_id?: number;
+  files: ReviewFile[];
+  changed_chunks: Chunk[];
+  callers: RelatedChunk[];
+  tests: RelatedChunk[];
+  business_rules: BusinessRule[];
+  markdown: string;
+}
+
+export interface ScoredChunk {
+  chunk: Chunk;
+  score: number;
+  lexical_score: number;
+  proximity_score: number;
+  matched_terms: string[];
+}
+
+export interface SourceRegion {
+  file_path: string;
+  start_line: number;
+  end_line?: number;
+}
+
+export interface ChunkTypeWeight {
+  chunk_type: ChunkType;
+  weight: number;
+}
+
+export interface PackedChunk {
+  chunk: Chunk;
+  score: number;
+  tokens: number;
+  region?: SourceRegion;
+}
+
+export interface ContextPack {
+  project_path: string;
+  task_text: string;
+  token_budget: number;
+  total_tokens: number;
+  pinned_rules: BusinessRule[];
+  chunks: PackedChunk[];
+  budget_weights?: ChunkTypeWeight[];
+  overlaps_skipped: number;
+  over_budget_skipped: number;
+  delivery_id?: number;
+  markdown: string;
+}
+
+export interface ContextDelivery {
+  id?: number;
+  project_path: string;
+  session_id?: string;
+  snapshot_id?: number;
+  task_text: string;
+  chunk_ids: number[];
+  chunk_tokens: number[];
+  total_tokens: number;
+  token_budget: number;
+  created_at: string;
+}
+
+export type AgentSessionStatus = 'active' | 'completed' | 'failed' | 'cancelled';
+
+export interface AgentSession {
+  id: string;
+  project_path: string;
+  master_snapshot_id?: number;
+  task?: string;
+  status: AgentSessionStatus;
+  started_at: string;
+  ended_at?: string;
+}
+
+export interface AgentSessionReport {
+  session: AgentSession;
+  master_snapshot?: Snapshot;
+  agent_snapshots: Snapshot[];
+  errors: ErrorLog[];
+  deliveries: ContextDelivery[];
+  delivered_chunk_ids: number[];
+  delivered_tokens: number;
+}
+
+export interface ChunkTypeCount {
+  chunk_type: string;
+  count: number;
+}
+
+export interface ChunksPerDay {
+  day: string;
+  created: number;
+  cumulative: number;
+  by_type: ChunkTypeCount[];
+}
+
+export interface SnapshotsPerDay {
+  day: string;
+  master: number;
+  agent: number;
+}
+
+export interface SnapshotCadence {
+  per_day: SnapshotsPerDay[];
+  avg_hours_between_masters?: number;
+  avg_agents_per_master?: number;
+}
+
+export interface ErrorRatePerDay {
+  day: string;
+  opened: number;
+  resolved: number;
+  open_at_end: number;
+}
+
+export interface FileTypeFreshness {
+  extension: string;
+  files: number;
+  oldest_indexed_at: string;
+  newest_indexed_at: string;
+  avg_age_hours: number;
+}
+
+export interface ProjectRoot {
+  path: string;
+  label: string;
+  chunk_types?: ChunkType[];
+  ignore_patterns?: string[];
+  snapshots_enabled?: boolean;
+}
+
+export interface LogicalProject {
+  name: string;
+  project_path: string;
+  roots: ProjectRoot[];
+  created_at: string;
+  updated_at: string;
+}
+
+export interface LogicalSnapshot {
+  root_label: string;
+  root_path: string;
+  snapshot_id: number;
+}
+
+export interface RemoteRepository {
+  url: string;
+  project_path: string;
+  depth?: number;
+  head_commit?: string;
+  cloned_at: string;
+  last_indexed_at?: string;
+}
+
+export interface ReadOnlyProject {
+  project_path: string;
+  reason?: string;
+  created_at: string;
+}
+
+export interface IndexExportSummary {
+  path: string;
+  chunks: number;
+  business_rules: number;
+  relationships: number;
+}
+
+export type MergeConflictKind = 'business_rule' | 'annotation';
+
+export type MergeResolution = 'kept_local' | 'took_imported';
+
+export interface IndexMergeConflict {
+  kind: MergeConflictKind;
+  key: string;
+  local: string;
+  imported: string;
+  local_updated_at: string;
+  imported_updated_at: string;
+  resolution: MergeResolution;
+}
+
+export interface IndexMergeReport {
+  project_path: string;
+  source_project_path: string;
+  chunks_imported: number;
+  chunks_updated: number;
+  chunks_unchanged: number;
+  rules_imported: number;
+  rules_updated: number;
+  rules_unchanged: number;
+  relationships_imported: number;
+  conflicts: IndexMergeConflict[];
+}
+
+export interface ParseFailure {
+  project_path: string;
+  file_path: string;
+  content_hash: string;
+  reason: string;
+  attempts: number;
+  first_failed_at: string;
+  last_failed_at: string;
+}
+
+export interface FileFingerprint {
+  project_path: string;
+  file_path: string;
+  content_hash: string;
+  size: number;
+  /** Modification time in milliseconds since epoch */
+  modified_at: number;
+  indexed_at: string;
+}
+
+export interface StalenessReport {
+  project_path: string;
+  files_checked: number;
+  /** Files changed or deleted since they were indexed */
+  stale_files: string[];
+  stale_chunks: number;
+}
+
+export interface ContentStoreStats {
+  blob_count: number;
+  /** Bytes stored in blobs (one copy per distinct content) */
+  blob_bytes: number;
+  /** Bytes the chunks would take with one copy each */
+  referenced_bytes: number;
+  /** Chunks stored before deduplication, still without a blob */
+  inline_chunks: number;
+  inline_bytes: number;
+  saved_bytes: number;
+}
+
+export interface ContentCompactionReport {
+  chunks_migrated: number;
+  blobs_removed: number;
+  stats: ContentStoreStats;
+}
+
+export interface ChunkVersion {
+  /** 1-based, oldest first */
+  version: number;
+  chunk_id: number;
+  content_hash: string;
+  created_at: string;
+  updated_at: string;
+  /** The version is in the trash */
+  deleted: boolean;
+}
+
+/** Line range of a file served from its raw source chunk */
+export interface SourceRange {
+  project_path: string;
+  file_path: string;
+  chunk_id: number;
+  /** Null for the current version */
+  snapshot_id?: number | null;
+  /** 1-based, inclusive */
+  start_line: number;
+  /** Inclusive, clamped to the end of the file */
+  end_line: number;
+  total_lines: number;
+  content: string;
+  /** Hash of the whole file */
+  content_hash: string;
+  indexed_at: string;
+}
+
+export interface ChunkVersionDiff {
+  chunk_type: ChunkType;
+  file_path?: string;
+  entity_name?: string;
+  from: ChunkVersion;
+  to: ChunkVersion;
+  additions: number;
+  deletions: number;
+  /** Unified diff; empty when both versions have the same content */
+  patch: string;
+  truncated: boolean;
+}
+
+export type IndexOperationKind = 'delete_project_chunks' | 'prune' | 'reset';
+
+export interface IndexOperation {
+  id: number;
+  project_path: string;
+  kind: IndexOperationKind;
+  /** Chunks moved to the trash by the operation */
+  chunks_affected: number;
+  created_at: string;
+  undone_at?: string;
+}
+
+export interface UndoResult {
+  operation: IndexOperation;
+  /** Fewer than `chunks_affected` when some chunks were purged or regenerated */
+  chunks_restored: number;
+}
+
+export interface RemoteIndexResult {
+  repository: RemoteRepository;
+  result: ChunkingResult;
+}
+
+export interface GitNotesResult {
+  notes_written: number;
+  notes_imported: number;
+  snapshots_restored: number;
+}
+
+export type VersionBump = 'none' | 'patch' | 'minor' | 'major';
+
+export interface ApiChange {
+  file_path: string;
+  name: string;
+  kind: string;
+  old_signature?: string;
+  new_signature?: string;
+}
+
+export interface VersionBumpSuggestion {
+  bump: VersionBump;
+  breaking_changes: ApiChange[];
+  added_exports: ApiChange[];
+  internal_files: string[];
+  reasons: string[];
 }
 
 export interface ChunkingResult {
   project_path: string;
   chunks_created: number;
   chunks_updated: number;
+  /** Chunks moved to the trash because their file no longer exists */
+  chunks_deleted?: number;
   relationships_created: number;
-  errors: string[];
+  secrets_found: number;
+  shallow_history: boolean;
+  errors: IndexingError[];
   started_at: string;
   completed_at: string;
+  dry_run?: DryRunReport;
+  /** Counts per chunk type, keyed by chunk type */
+  by_type?: Record<string, ChunkTypeStats>;
+  /** Time spent in each indexing phase, in execution order */
+  phase_durations?: PhaseDuration[];
+  /** `aborted` when the error budget ran out; counts are partial */
+  status?: IndexingStatus;
+  abort_reason?: string;
+  /** Id of the recorded indexing run; absent on dry runs and combined logical-project results */
+  run_id?: string | null;
+  /** Embeddings refreshed after indexing; absent when the project has none or no provider is set */
+  embeddings?: EmbeddingRefreshReport | null;
+}
+
+/**
+ * Result of an incremental embedding refresh
+ */
+export interface EmbeddingRefreshReport {
+  project_path: string;
+  model: string;
+  model_version: string;
+  /** Current chunks compared against the stored embeddings */
+  chunks_checked: number;
+  /** Chunks (re)embedded because they were new, changed or embedded with another model */
+  embedded: number;
+  unchanged: number;
+  /** Embeddings of deleted or superseded chunks */
+  removed: number;
+  /** Calls made to the embedding provider */
+  batches: number;
+}
+
+/**
+ * Index state at a point in time, stored under `index_state` in snapshot metadata
+ */
+export interface IndexState {
+  /** Live chunk counts, keyed by chunk type */
+  chunk_counts: Record<string, number>;
+  total_chunks: number;
+  last_run_id?: string | null;
+  last_run_kind?: 'full' | 'incremental' | 'partial' | null;
+  last_run_status?: IndexingStatus | null;
+  last_indexed_at?: string | null;
+  captured_at: string;
+}
+
+export type IndexingStatus = 'completed' | 'aborted';
+
+export type IndexingErrorKind = 'config' | 'io' | 'storage' | 'git' | 'other';
+
+export interface IndexingError {
+  /** Relative file path; null for project-level failures (commits, dependency graph, secret scan) */
+  file_path?: string | null;
+  chunk_type?: ChunkType | null;
+  kind: IndexingErrorKind;
+  message: string;
+}
+
+export interface ChunkTypeStats {
+  created: number;
+  updated: number;
+  skipped: number;
+  errored: number;
+}
+
+export interface PhaseDuration {
+  phase: string;
+  duration_ms: number;
+}
+
+/** Export format of the relationship graph: Graphviz DOT or GraphML (Gephi, yEd) */
+export type GraphFormat = 'dot' | 'graphml';
+
+/** Content hashing algorithm of a project; BLAKE3 hashes are prefixed with "blake3:" */
+export type HashAlgorithm = 'sha256' | 'blake3';
+
+export interface ProjectHashing {
+  project_path: string;
+  algorithm: HashAlgorithm;
+  /** Anonymous stable id: the path hashed with a random salt local to this database */
+  project_id: string;
+}
+
+/**
+ * Embedding backend of a project: an OpenAI-compatible HTTP API or a local ONNX model
+ * (requires a build with the `local-embeddings` feature)
+ */
+export type EmbeddingBackend =
+  | {
+      backend: 'http';
+      endpoint: string;
+      model: string;
+      /** Environment variable holding the API key; the key itself is never stored */
+      api_key_env?: string | null;
+    }
+  | {
+      backend: 'local';
+      /** Directory with model.onnx and the tokenizer files */
+      model_dir: string;
+    };
+
+export interface EmbeddingSettings {
+  project_path: string;
+  backend: EmbeddingBackend | null;
+  /** Whether this build includes the local embedding backend */
+  local_available: boolean;
+}
+
+export interface SemanticMatch {
+  chunk_id: number;
+  chunk_type: ChunkType;
+  file_path?: string | null;
+  entity_name?: string | null;
+  /** Cosine similarity with the query, from -1 to 1 */
+  score: number;
+}
+
+/** Anonymized aggregate index stats for bug reports: no paths, names or content */
+export interface IndexProfile {
+  format_version: number;
+  generated_at: string;
+  crate_version: string;
+  os: string;
+  /** Salted anonymous project id, stable across profiles of the same project; null if never indexed */
+  project_id: string | null;
+  hash_algorithm: HashAlgorithm;
+  files: number;
+  /** "(none)" for files without extension, "other" for unusual ones */
+  files_by_extension: Record<string, number>;
+  chunks_by_type: Record<string, ChunkTypeProfile>;
+  deleted_chunks: number;
+  stale_chunks: number;
+  ast_languages: Record<string, number>;
+  /** Raw source bytes per file */
+  file_sizes: SizeDistribution;
+  relationships_by_type: Record<string, number>;
+  snapshots: number;
+  business_rules: number;
+  open_errors: number;
+  parse_failures: number;
+  /** Size of the whole database (all projects) */
+  database_bytes: number;
+  /** Most recent first */
+  recent_runs: IndexRunProfile[];
+}
+
+/** Stored run of the index benchmark */
+export interface BenchmarkRun {
+  id?: number;
+  project_path: string;
+  crate_version: string;
+  iterations: number;
+  total_chunks: number;
+  /** Missing when the index is read-only */
+  indexing?: IndexingBenchmark;
+  queries: QueryBenchmark[];
+  /** Metrics at least 1.5x worse than in the previous run */
+  regressions: BenchmarkRegression[];
+  created_at: string;
+}
+
+export interface IndexingBenchmark {
+  duration_ms: number;
+  chunks: number;
+  chunks_per_second: number;
+}
+
+/** Latencies in microseconds */
+export interface QueryBenchmark {
+  pattern: string;
+  iterations: number;
+  rows: number;
+  mean_us: number;
+  p50_us: number;
+  p95_us: number;
+  max_us: number;
+}
+
+export interface BenchmarkRegression {
+  /** Query pattern or "indexing" */
+  metric: string;
+  previous_version: string;
+  previous: number;
+  current: number;
+  /** How many times slower the current run is */
+  ratio: number;
+}
+
+export interface ChunkTypeProfile {
+  count: number;
+  total_bytes: number;
+  max_bytes: number;
+}
+
+export interface SizeDistribution {
+  p50: number;
+  p90: number;
+  p99: number;
+  max: number;
+}
+
+export interface IndexRunProfile {
+  kind: string;
+  status: IndexingStatus;
+  chunks_created: number;
+  chunks_updated: number;
+  chunks_deleted: number;
+  errors: number;
+  duration_ms: number;
+  phase_durations: PhaseDuration[];
+}
+
+export interface DryRunChanges {
+  chunk_type: string;
+  created: number;
+  updated: number;
+  deleted: number;
+}
+
+export interface DryRunFileChanges extends DryRunChanges {
+  file_path?: string;
+}
+
+export interface DryRunReport {
+  chunks_created: number;
+  chunks_updated: number;
+  chunks_deleted: number;
+  by_type: DryRunChanges[];
+  files: DryRunFileChanges[];
 }
 
 export interface ChunkingOptions {
@@ -137,7 +1367,54 @@ export interface ChunkingOptions {
   max_ast_depth?: number;
   include_dynamic_callgraph: boolean;
   max_commits?: number;
+  all_branches?: boolean;
+  write_git_notes?: boolean;
   ignore_patterns: string[];
+  /** Detail level of AST chunks (default `full`) */
+  ast_detail?: AstDetail;
+  /** Estimated token budget per AST chunk; larger chunks fall back to a coarser detail */
+  ast_max_tokens?: number | null;
+  language_overrides?: Record<string, string>;
+  secret_redaction?: SecretRedactionRules;
+  snapshot_policy?: SnapshotPolicy;
+  dry_run?: boolean;
+  error_budget?: ErrorBudget;
+  max_parse_attempts?: number;
+  /** Days a deleted chunk stays in the trash before it is purged */
+  tombstone_grace_days?: number;
+  /** Extensions (no dot, lowercase) indexed as source code */
+  code_extensions?: string[];
+}
+
+export interface ErrorBudget {
+  /** Abort after this many per-file errors (null = no limit) */
+  max_file_errors?: number | null;
+  /** Abort on the first database error */
+  fail_on_storage_error: boolean;
+}
+
+export interface CustomSecretRule {
+  id: string;
+  pattern: string;
+  severity?: string;
+}
+
+export interface SecretRedactionRules {
+  rules: CustomSecretRule[];
+  disabled_rules: string[];
+  allow_paths: string[];
+}
+
+export interface SnapshotPolicy {
+  auto_reindex: boolean;
+  write_git_notes: boolean;
+  /** Propose business rules for new public functions/classes in agent snapshots */
+  propose_rules: boolean;
+}
+
+export interface ChunkPage {
+  chunks: Chunk[];
+  next_cursor?: number | null;
 }
 
 export interface ChunkQuery {
@@ -145,13 +1422,23 @@ export interface ChunkQuery {
   chunk_types?: ChunkType[];
   file_path?: string;
   entity_name?: string;
+  dependency_kind?: DependencyKind;
+  dependency_package?: string;
+  commit_type?: string;
+  breaking_change?: boolean;
+  touches_path?: string;
+  branch?: string;
+  /** Include soft-deleted chunks */
+  include_deleted?: boolean;
+  /** Only chunks whose file changed (true) or did not change (false) since indexing */
+  stale?: boolean;
   limit?: number;
   offset?: number;
 }
 
 // UI-specific types
 export interface ChunkWithMetadata extends Chunk {
-  parsedMetadata?: AstMetadata | CallgraphMetadata | CommitMetadata;
+  parsedMetadata?: AstMetadata | CallgraphMetadata | TestsMetadata | CommitMetadata;
   relationships?: ChunkRelationship[];
 }
 